@@ -0,0 +1,264 @@
+//! Regression suite for the hot paths: put, get, query, history, vector
+//! search and replica sync.
+//!
+//! Datasets are built once per benchmark outside the measured loop, so
+//! numbers compare across commits. Run with:
+//!
+//! ```bash
+//! cargo bench --bench regression
+//! cargo bench --bench regression -- --save-baseline main   # on main
+//! cargo bench --bench regression -- --baseline main        # on a branch
+//! ```
+
+use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use koru_delta::query::{Filter, Query};
+use koru_delta::{Durability, KoruDelta, PutOptions, Vector, VectorSearchOptions};
+use serde_json::json;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+const DIMENSIONS: usize = 64;
+
+/// A database with `keys` user records spread over `namespaces` namespaces.
+fn populated(rt: &Runtime, keys: usize, namespaces: usize) -> KoruDelta {
+    rt.block_on(async {
+        let db = KoruDelta::start().await.unwrap();
+        for i in 0..keys {
+            db.put(
+                format!("users{}", i % namespaces),
+                format!("user{}", i),
+                json!({"id": i, "age": i % 90, "city": format!("city{}", i % 20)}),
+            )
+            .await
+            .unwrap();
+        }
+        db
+    })
+}
+
+/// A deterministic pseudo-random vector.
+fn vector(seed: usize) -> Vector {
+    let data = (0..DIMENSIONS)
+        .map(|d| (((seed * 31 + d * 17) % 97) as f32) / 97.0)
+        .collect();
+    Vector::new(data, "bench")
+}
+
+/// Benchmark: puts, single key and spread over namespaces from many tasks
+fn bench_put(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("regression_put");
+
+    let db = rt.block_on(async { KoruDelta::start().await.unwrap() });
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("single", |b| {
+        b.to_async(&rt)
+            .iter(|| async { black_box(db.put("bench", "key", json!({"n": 1})).await.unwrap()) })
+    });
+
+    // Concurrent writers to distinct namespaces should not contend
+    for writers in [1, 4, 16] {
+        group.throughput(Throughput::Elements(writers as u64 * 100));
+        group.bench_with_input(
+            BenchmarkId::new("concurrent_namespaces", writers),
+            &writers,
+            |b, &writers| {
+                b.to_async(&rt).iter(|| async {
+                    let tasks: Vec<_> = (0..writers)
+                        .map(|w| {
+                            let db = db.clone();
+                            tokio::spawn(async move {
+                                for i in 0..100 {
+                                    db.put(format!("ns{}", w), format!("k{}", i), json!({"i": i}))
+                                        .await
+                                        .unwrap();
+                                }
+                            })
+                        })
+                        .collect();
+                    for task in tasks {
+                        task.await.unwrap();
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+/// Benchmark: persistent puts, synchronous and asynchronous durability
+fn bench_put_durable(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let db = rt.block_on(async { KoruDelta::start_with_path(dir.path()).await.unwrap() });
+    let mut group = c.benchmark_group("regression_put_durable");
+
+    for (name, durability) in [("sync", Durability::Sync), ("async", Durability::Async)] {
+        let options = PutOptions::new().durability(durability);
+        group.bench_function(name, |b| {
+            b.to_async(&rt).iter(|| async {
+                black_box(
+                    db.put_with_options("bench", "key", json!({"n": 1}), options.clone())
+                        .await
+                        .unwrap(),
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Benchmark: gets from datasets of growing size
+fn bench_get(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("regression_get");
+
+    for keys in [1_000, 10_000] {
+        let db = populated(&rt, keys, 10);
+        let key = format!("user{}", keys / 2);
+        let namespace = format!("users{}", (keys / 2) % 10);
+        group.throughput(Throughput::Elements(1));
+        group.bench_with_input(BenchmarkId::new("value", keys), &keys, |b, _| {
+            b.to_async(&rt)
+                .iter(|| async { black_box(db.get(&namespace, &key).await.unwrap()) })
+        });
+        group.bench_with_input(BenchmarkId::new("raw", keys), &keys, |b, _| {
+            b.to_async(&rt)
+                .iter(|| async { black_box(db.get_raw(&namespace, &key).await.unwrap()) })
+        });
+    }
+    group.finish();
+}
+
+/// Benchmark: filtered, sorted and projected queries over one namespace
+fn bench_query(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let db = populated(&rt, 5_000, 1);
+    let mut group = c.benchmark_group("regression_query");
+    group.throughput(Throughput::Elements(5_000));
+
+    group.bench_function("filter", |b| {
+        b.to_async(&rt).iter(|| async {
+            black_box(
+                db.query("users0", Query::new().filter(Filter::gt("age", 60)))
+                    .await
+                    .unwrap(),
+            )
+        })
+    });
+    group.bench_function("filter_sort_limit", |b| {
+        b.to_async(&rt).iter(|| async {
+            black_box(
+                db.query(
+                    "users0",
+                    Query::new()
+                        .filter(Filter::eq("city", "city3"))
+                        .sort_by("age", false)
+                        .limit(10),
+                )
+                .await
+                .unwrap(),
+            )
+        })
+    });
+    group.bench_function("projection", |b| {
+        b.to_async(&rt).iter(|| async {
+            black_box(
+                db.query("users0", Query::new().project(&["id"]))
+                    .await
+                    .unwrap(),
+            )
+        })
+    });
+    group.finish();
+}
+
+/// Benchmark: history of keys with many versions
+fn bench_history(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("regression_history");
+
+    for versions in [10, 100, 500] {
+        let db = rt.block_on(async {
+            let db = KoruDelta::start().await.unwrap();
+            for i in 0..versions {
+                db.put("bench", "key", json!({"version": i})).await.unwrap();
+            }
+            db
+        });
+        group.throughput(Throughput::Elements(versions as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(versions), &versions, |b, _| {
+            b.to_async(&rt)
+                .iter(|| async { black_box(db.history("bench", "key").await.unwrap()) })
+        });
+    }
+    group.finish();
+}
+
+/// Benchmark: top-10 vector search over growing indexes
+fn bench_vector_search(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("regression_vector_search");
+
+    for count in [1_000, 5_000] {
+        let db = rt.block_on(async {
+            let db = KoruDelta::start().await.unwrap();
+            let items: Vec<_> = (0..count)
+                .map(|i| (format!("doc{}", i), vector(i), None))
+                .collect();
+            db.embed_batch("docs", items).await.unwrap();
+            db
+        });
+        let query = vector(count / 3);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, _| {
+            b.to_async(&rt).iter(|| async {
+                black_box(
+                    db.embed_search(Some("docs"), &query, VectorSearchOptions::new().top_k(10))
+                        .await
+                        .unwrap(),
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Benchmark: merging a replica's versions into an empty database
+fn bench_sync(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("regression_sync");
+
+    for keys in [100, 1_000] {
+        let source = populated(&rt, keys, 4);
+        let versions = source.storage().all_versions();
+        group.throughput(Throughput::Elements(versions.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(keys), &keys, |b, _| {
+            b.to_async(&rt).iter(|| async {
+                let replica = KoruDelta::start().await.unwrap();
+                black_box(replica.merge_versions(versions.clone()).await)
+            })
+        });
+    }
+    group.finish();
+}
+
+fn configure_criterion() -> Criterion {
+    Criterion::default()
+        .warm_up_time(Duration::from_secs(1))
+        .measurement_time(Duration::from_secs(3))
+        .sample_size(30)
+}
+
+criterion_group! {
+    name = benches;
+    config = configure_criterion();
+    targets = bench_put,
+        bench_put_durable,
+        bench_get,
+        bench_query,
+        bench_history,
+        bench_vector_search,
+        bench_sync
+}
+
+criterion_main!(benches);
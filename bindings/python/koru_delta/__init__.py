@@ -38,7 +38,21 @@ from koru_delta._internal import (
     Database,
     IdentityManager,
     Workspace,
+    Subscription,
+    Vector,
     
+    # Query builder
+    Query,
+    Filter,
+    SortBy,
+    Aggregation,
+    
+    # Vector clock helpers
+    happens_before,
+    concurrent_with,
+    descends_from,
+    merge_clocks,
+
     # Exceptions
     KoruDeltaError,
     KeyNotFoundError,
@@ -47,6 +61,7 @@ from koru_delta._internal import (
     SerializationError,
     EngineError,
     TimeError,
+    UnauthorizedError,
     
     # Version
     __version__,
@@ -57,6 +72,18 @@ __all__ = [
     "Database",
     "IdentityManager", 
     "Workspace",
+    "Subscription",
+    "Vector",
+    "Query",
+    "Filter",
+    "SortBy",
+    "Aggregation",
+
+    # Vector clock helpers
+    "happens_before",
+    "concurrent_with",
+    "descends_from",
+    "merge_clocks",
     
     # Exceptions
     "KoruDeltaError",
@@ -66,6 +93,7 @@ __all__ = [
     "SerializationError",
     "EngineError",
     "TimeError",
+    "UnauthorizedError",
 ]
 
 __version__ = "3.0.0"
@@ -9,7 +9,12 @@ A zero-configuration, causal database with native support for:
 """
 
 from __future__ import annotations
-from typing import Any
+from typing import Any, AsyncIterator, Callable, Sequence
+
+import numpy as np
+import pandas
+import pyarrow
+from numpy.typing import NDArray
 
 from koru_delta.config import Config
 from koru_delta.agent_memory import AgentMemory
@@ -65,10 +70,22 @@ class Database:
         """Retrieve a value at a specific point in time."""
         ...
     
+    async def get_versioned(self, namespace: str, key: str) -> dict[str, Any]:
+        """Retrieve a value with its version ID, timestamp, previous version and vector clock."""
+        ...
+    
     async def history(self, namespace: str, key: str) -> list[dict[str, Any]]:
         """Get complete history for a key."""
         ...
     
+    async def conflicts(self, namespace: str) -> list[dict[str, Any]]:
+        """List concurrent-write conflicts found by replication, oldest first."""
+        ...
+    
+    async def resolve_conflict(self, namespace: str, conflict_id: str, value: Any) -> str:
+        """Write `value` as the resolution of a conflict; returns its version ID."""
+        ...
+    
     async def delete(self, namespace: str, key: str) -> None:
         """Delete a key."""
         ...
@@ -85,34 +102,273 @@ class Database:
         self,
         namespace: str,
         key: str,
-        embedding: list[float],
-        model: str,
+        embedding: Vector | Sequence[float] | NDArray[np.floating],
+        model: str | None = None,
         metadata: object | None = None,
     ) -> None:
-        """Store a vector embedding."""
+        """Store a vector embedding (`model` is required unless `embedding` is a Vector)."""
+        ...
+    
+    async def embed_search(
+        self,
+        namespace: str | None,
+        query: Vector | Sequence[float] | NDArray[np.floating],
+        top_k: int = 10,
+        threshold: float = 0.0,
+        model_filter: str | None = None,
+        metric: str | None = None,
+    ) -> list[dict[str, Any]]:
+        """
+        Search for similar vectors, best match first.
+        
+        Results are dicts with `namespace`, `key`, `score` and `vector`.
+        `metric` is "cosine", "dot_product", "euclidean" or "manhattan".
+        """
         ...
     
     async def similar(
         self,
         namespace: str | None,
-        query: list[float],
+        query: Vector | Sequence[float] | NDArray[np.floating],
         top_k: int = 10,
         threshold: float = 0.0,
         model_filter: str | None = None,
+        metric: str | None = None,
     ) -> list[dict[str, Any]]:
-        """Search for similar vectors."""
+        """Search for similar vectors (alias of `embed_search`)."""
+        ...
+    
+    async def query(
+        self,
+        namespace: str,
+        filters: Query | list[Filter | dict[str, Any]] | None = None,
+        sort: list[SortBy | dict[str, str]] | None = None,
+        limit: int | None = None,
+        offset: int | None = None,
+    ) -> dict[str, Any]:
+        """
+        Query a namespace.
+        
+        Returns a dict with `total_count`, `records` (each with `key`,
+        `value`, `timestamp` and `version_id`) and `aggregation`.
+        """
+        ...
+    
+    async def query_df(
+        self,
+        namespace: str,
+        filters: Query | list[Filter | dict[str, Any]] | None = None,
+        sort: list[SortBy | dict[str, str]] | None = None,
+        limit: int | None = None,
+        offset: int | None = None,
+        as_arrow: bool = False,
+    ) -> pandas.DataFrame | pyarrow.Table:
+        """
+        Query a namespace into a pandas DataFrame, or a pyarrow Table with
+        `as_arrow=True`.
+        
+        Columns are `_key`, `_version_id`, `_timestamp` and one per
+        top-level value field. Requires the `dataframe` extra.
+        """
+        ...
+    
+    async def create_view(
+        self,
+        name: str,
+        source_collection: str,
+        filters: Query | list[Filter | dict[str, Any]] | None = None,
+        description: str | None = None,
+        auto_refresh: bool = False,
+    ) -> None:
+        """Create a materialized view over a namespace."""
+        ...
+    
+    async def refresh_view(self, name: str) -> None:
+        """Recompute a materialized view."""
+        ...
+    
+    async def query_view(self, name: str) -> dict[str, Any]:
+        """Read a materialized view's records (same shape as `query`)."""
         ...
     
     async def stats(self) -> dict[str, Any]:
         """Get database statistics."""
         ...
     
+    def subscribe(
+        self,
+        namespace: str | None = None,
+        key: str | None = None,
+        on_change: Callable[[dict[str, Any]], None] | None = None,
+    ) -> Subscription:
+        """
+        Subscribe to changes made with `put`.
+        
+        With `on_change`, the callback receives each change event from a
+        background thread. Without it, iterate the subscription with
+        `async for`.
+        """
+        ...
+    
     def agent_memory(self, agent_id: str) -> AgentMemory:
         """Create an agent memory interface."""
         ...
 
+class Filter:
+    """A filter condition. Combine with `&`, `|` and `~`."""
+    
+    @staticmethod
+    def eq(field: str, value: object) -> Filter: ...
+    @staticmethod
+    def ne(field: str, value: object) -> Filter: ...
+    @staticmethod
+    def gt(field: str, value: object) -> Filter: ...
+    @staticmethod
+    def gte(field: str, value: object) -> Filter: ...
+    @staticmethod
+    def lt(field: str, value: object) -> Filter: ...
+    @staticmethod
+    def lte(field: str, value: object) -> Filter: ...
+    @staticmethod
+    def contains(field: str, value: object) -> Filter: ...
+    @staticmethod
+    def exists(field: str) -> Filter: ...
+    @staticmethod
+    def matches(field: str, pattern: str) -> Filter: ...
+    @staticmethod
+    def all(*filters: Filter) -> Filter: ...
+    @staticmethod
+    def any(*filters: Filter) -> Filter: ...
+    
+    def __and__(self, other: Filter) -> Filter: ...
+    def __or__(self, other: Filter) -> Filter: ...
+    def __invert__(self) -> Filter: ...
+
+class SortBy:
+    """A sort specification."""
+    
+    @staticmethod
+    def asc(field: str) -> SortBy: ...
+    @staticmethod
+    def desc(field: str) -> SortBy: ...
+
+class Aggregation:
+    """An aggregation over matching records."""
+    
+    @staticmethod
+    def count() -> Aggregation: ...
+    @staticmethod
+    def sum(field: str) -> Aggregation: ...
+    @staticmethod
+    def avg(field: str) -> Aggregation: ...
+    @staticmethod
+    def min(field: str) -> Aggregation: ...
+    @staticmethod
+    def max(field: str) -> Aggregation: ...
+    @staticmethod
+    def distinct(field: str) -> Aggregation: ...
+    @staticmethod
+    def group_by(field: str, aggregations: dict[str, Aggregation]) -> Aggregation: ...
+
+class Query:
+    """
+    Fluent query builder. Each method returns a new Query.
+    
+    Example:
+        >>> query = (Query()
+        ...     .filter(Filter.gte("age", 18))
+        ...     .sort_by("age", ascending=False)
+        ...     .limit(10))
+        >>> result = await db.query("users", query)
+    """
+    
+    def __init__(self) -> None: ...
+    def filter(self, *filters: Filter) -> Query: ...
+    def select(self, *fields: str) -> Query: ...
+    def sort_by(self, field: str, ascending: bool = True) -> Query: ...
+    def sort(self, *sorts: SortBy) -> Query: ...
+    def limit(self, n: int) -> Query: ...
+    def offset(self, n: int) -> Query: ...
+    def aggregate(self, aggregation: Aggregation) -> Query: ...
+
+class Vector:
+    """An embedding vector tagged with the model that produced it."""
+    
+    def __init__(self, data: Sequence[float] | NDArray[np.floating], model: str) -> None: ...
+    
+    @property
+    def model(self) -> str: ...
+    
+    @property
+    def dimensions(self) -> int: ...
+    
+    @property
+    def data(self) -> list[float]: ...
+    
+    def to_numpy(self) -> NDArray[np.float32]:
+        """Vector data as a numpy array."""
+        ...
+    
+    def similarity(
+        self,
+        other: Vector | Sequence[float] | NDArray[np.floating],
+        metric: str | None = None,
+    ) -> float | None:
+        """Similarity to another vector (None if dimensions or models differ)."""
+        ...
+    
+    def __len__(self) -> int: ...
+
+class Subscription:
+    """
+    A live subscription to changes.
+    
+    Events are dicts with `change_type` ("insert", "update" or "delete"),
+    `namespace`, `key`, `value`, `previous_value`, `timestamp`,
+    `version_id` and `previous_version_id`.
+    """
+    
+    @property
+    def id(self) -> int: ...
+    
+    @property
+    def active(self) -> bool: ...
+    
+    def unsubscribe(self) -> None:
+        """Stop receiving events."""
+        ...
+    
+    def __aiter__(self) -> AsyncIterator[dict[str, Any]]: ...
+    async def __anext__(self) -> dict[str, Any]: ...
+
+def happens_before(a: dict[str, int], b: dict[str, int]) -> bool:
+    """Whether vector clock `a` happened strictly before `b`."""
+    ...
+
+def concurrent_with(a: dict[str, int], b: dict[str, int]) -> bool:
+    """Whether neither vector clock happened before the other."""
+    ...
+
+def descends_from(a: dict[str, int], b: dict[str, int]) -> bool:
+    """Whether vector clock `a` has seen everything `b` has."""
+    ...
+
+def merge_clocks(clocks: Sequence[dict[str, int]]) -> dict[str, int]:
+    """The earliest vector clock that has seen every one of `clocks`."""
+    ...
+
 __all__ = [
     "Database",
+    "Subscription",
+    "Vector",
+    "Query",
+    "Filter",
+    "SortBy",
+    "Aggregation",
+    "happens_before",
+    "concurrent_with",
+    "descends_from",
+    "merge_clocks",
     "Config",
     "AgentMemory",
     "KoruDeltaError",
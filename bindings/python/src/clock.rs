@@ -0,0 +1,40 @@
+//! Vector clock helpers
+//!
+//! Clocks are plain dicts of node ID to counter, as returned in the
+//! `vector_clock` of `Database.get_versioned`. Missing nodes count as zero.
+
+use std::collections::HashMap;
+
+use koru_delta::VectorClock;
+use pyo3::prelude::*;
+
+type Clock = HashMap<String, u64>;
+
+fn clock(clocks: Clock) -> VectorClock {
+    VectorClock { clocks }
+}
+
+/// Whether clock `a` happened strictly before clock `b`
+#[pyfunction]
+pub fn happens_before(a: Clock, b: Clock) -> bool {
+    clock(a).happens_before(&clock(b))
+}
+
+/// Whether neither clock happened before the other (the writes conflict)
+#[pyfunction]
+pub fn concurrent_with(a: Clock, b: Clock) -> bool {
+    clock(a).concurrent_with(&clock(b))
+}
+
+/// Whether clock `a` has seen everything clock `b` has
+#[pyfunction]
+pub fn descends_from(a: Clock, b: Clock) -> bool {
+    clock(a).descends_from(&clock(b))
+}
+
+/// The earliest clock that has seen every one of `clocks`
+#[pyfunction]
+pub fn merge_clocks(clocks: Vec<Clock>) -> Clock {
+    let clocks: Vec<VectorClock> = clocks.into_iter().map(clock).collect();
+    VectorClock::merge_all(&clocks).clocks
+}
@@ -10,15 +10,20 @@
 use std::sync::Arc;
 
 use pyo3::prelude::*;
-use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::exceptions::{PyImportError, PyRuntimeError, PyValueError};
 use pyo3_asyncio::tokio::future_into_py;
-use pyo3::types::{PyDict, PyList, PyTuple};
+use pyo3::types::{PyBytes, PyDict, PyList, PyTuple};
 
+use crate::dataframe::query_result_to_ipc;
+use crate::query::{build_query, query_from_py, query_result_to_pyobject};
+use crate::subscriptions::PySubscription;
 use crate::to_python_error;
 use crate::types::{json_to_pyobject, pyobject_to_json};
-use koru_delta::vector::{Vector, VectorSearchOptions};
+use crate::vector::{extract_vector, parse_metric, search_results_to_pylist};
+use koru_delta::vector::VectorSearchOptions;
 use koru_delta::KoruDelta;
 use koru_delta::cluster::{ClusterConfig, ClusterNode};
+use koru_delta::subscriptions::Subscription;
 
 /// Python wrapper for KoruDelta database
 #[pyclass(name = "Database")]
@@ -45,7 +50,7 @@ impl PyDatabase {
         })
     }
 
-    /// Store a value
+    /// Store a value and notify subscribers
     fn put<'py>(
         &self,
         py: Python<'py>,
@@ -59,7 +64,7 @@ impl PyDatabase {
         let json_value = pyobject_to_json(value)?;
 
         future_into_py(py, async move {
-            db.put(ns, k, json_value)
+            db.put_notify(ns, k, json_value)
                 .await
                 .map_err(to_python_error)?;
             Ok(())
@@ -244,6 +249,35 @@ impl PyDatabase {
         })
     }
 
+    /// Retrieve a value with its version metadata
+    ///
+    /// Returns a dict with `value`, `version_id`, `timestamp`,
+    /// `previous_version` and `vector_clock` (node ID to counter), for
+    /// comparing writes with `happens_before` and `concurrent_with`.
+    fn get_versioned<'py>(
+        &self,
+        py: Python<'py>,
+        namespace: &str,
+        key: &str,
+    ) -> PyResult<&'py PyAny> {
+        let db = self.db.clone();
+        let ns = namespace.to_string();
+        let k = key.to_string();
+
+        future_into_py(py, async move {
+            let versioned = db.get(ns, k).await.map_err(to_python_error)?;
+            Python::with_gil(|py| {
+                let dict = PyDict::new(py);
+                dict.set_item("value", json_to_pyobject(py, versioned.value())).ok();
+                dict.set_item("version_id", versioned.version_id()).ok();
+                dict.set_item("timestamp", versioned.timestamp().to_rfc3339()).ok();
+                dict.set_item("previous_version", versioned.previous_version()).ok();
+                dict.set_item("vector_clock", versioned.vector_clock().clocks.clone()).ok();
+                Ok(dict.to_object(py))
+            })
+        })
+    }
+
     /// Get history for a key
     fn history<'py>(
         &self,
@@ -272,26 +306,63 @@ impl PyDatabase {
         })
     }
 
-    /// Store a vector embedding with explicit vector data
-    #[pyo3(signature = (namespace, key, embedding, model, metadata = None))]
+    /// List conflicts replication found in a namespace, oldest first
+    ///
+    /// Each is a dict with `id`, `key`, `status`, `siblings`, `winner` and
+    /// `detected_at`; versions have the same fields as in `history`.
+    fn conflicts<'py>(&self, py: Python<'py>, namespace: &str) -> PyResult<&'py PyAny> {
+        let db = self.db.clone();
+        let ns = namespace.to_string();
+
+        future_into_py(py, async move {
+            let conflicts = db.conflicts(&ns).await;
+            let conflicts = serde_json::to_value(conflicts)
+                .map_err(|e| to_python_error(koru_delta::DeltaError::from(e)))?;
+            Python::with_gil(|py| Ok(json_to_pyobject(py, &conflicts)))
+        })
+    }
+
+    /// Resolve a conflict by writing `value` as the key's current value
+    fn resolve_conflict<'py>(
+        &self,
+        py: Python<'py>,
+        namespace: &str,
+        conflict_id: &str,
+        value: &PyAny,
+    ) -> PyResult<&'py PyAny> {
+        let db = self.db.clone();
+        let ns = namespace.to_string();
+        let id = conflict_id.to_string();
+        let json_value = pyobject_to_json(value)?;
+
+        future_into_py(py, async move {
+            let versioned = db
+                .resolve_conflict(&ns, &id, json_value)
+                .await
+                .map_err(to_python_error)?;
+            Ok(versioned.version_id().to_string())
+        })
+    }
+
+    /// Store a vector embedding
+    ///
+    /// `embedding` may be a `Vector`, a list of floats, or a 1-D numpy
+    /// array; `model` is required unless it's a `Vector`.
+    #[pyo3(signature = (namespace, key, embedding, model = None, metadata = None))]
     fn embed<'py>(
         &self,
         py: Python<'py>,
         namespace: &str,
         key: &str,
-        embedding: Vec<f32>,
-        model: &str,
-        metadata: Option<PyObject>,
+        embedding: &PyAny,
+        model: Option<&str>,
+        metadata: Option<&PyAny>,
     ) -> PyResult<&'py PyAny> {
         let db = self.db.clone();
         let ns = namespace.to_string();
         let k = key.to_string();
-        let vec = Vector::new(embedding, model);
-        let meta = metadata.and_then(|m| {
-            Python::with_gil(|py| {
-                pyobject_to_json(m.as_ref(py)).ok()
-            })
-        });
+        let vec = extract_vector(embedding, model)?;
+        let meta = metadata.map(pyobject_to_json).transpose()?;
 
         future_into_py(py, async move {
             db.embed(ns, k, vec, meta)
@@ -301,190 +372,152 @@ impl PyDatabase {
         })
     }
 
-    /// Search for similar vectors
-    #[pyo3(signature = (namespace, query, top_k = 10, threshold = 0.0, model_filter = None))]
-    fn similar<'py>(
+    /// Search for the vectors most similar to `query`
+    ///
+    /// `query` may be a `Vector`, a list of floats, or a 1-D numpy array.
+    /// Returns dicts with `namespace`, `key`, `score` and `vector`, best
+    /// match first. `metric` is "cosine", "dot_product", "euclidean" or
+    /// "manhattan" (default: the namespace's metric, else cosine).
+    #[pyo3(signature = (namespace, query, top_k = 10, threshold = 0.0, model_filter = None, metric = None))]
+    fn embed_search<'py>(
         &self,
         py: Python<'py>,
         namespace: Option<&str>,
-        query: Vec<f32>,
+        query: &PyAny,
         top_k: usize,
         threshold: f32,
         model_filter: Option<String>,
+        metric: Option<&str>,
     ) -> PyResult<&'py PyAny> {
         let db = self.db.clone();
-        let query_vec = Vector::new(query, "query");
+        let model = model_filter.as_deref().unwrap_or("query");
+        let query_vec = extract_vector(query, Some(model))?;
         let ns = namespace.map(|s| s.to_string());
-        
-        let opts = VectorSearchOptions::new()
+
+        let mut opts = VectorSearchOptions::new()
             .top_k(top_k)
             .threshold(threshold);
-        
-        let opts = if let Some(filter) = model_filter {
-            opts.model_filter(filter)
-        } else {
-            opts
-        };
+        if let Some(filter) = model_filter {
+            opts = opts.model_filter(filter);
+        }
+        if let Some(metric) = parse_metric(metric)? {
+            opts = opts.metric(metric);
+        }
 
         future_into_py(py, async move {
             let results = db
                 .embed_search(ns.as_deref(), &query_vec, opts)
                 .await
                 .map_err(to_python_error)?;
-            
-            Python::with_gil(|py| {
-                let list = PyList::new(py, Vec::<PyObject>::new());
-                for result in results {
-                    let dict = PyDict::new(py);
-                    dict.set_item("namespace", &result.namespace).ok();
-                    dict.set_item("key", &result.key).ok();
-                    dict.set_item("score", result.score).ok();
-                    list.append(dict).ok();
-                }
-                Ok(list.to_object(py))
-            })
+            Python::with_gil(|py| search_results_to_pylist(py, results))
         })
     }
 
+    /// Search for similar vectors (alias of `embed_search`)
+    #[pyo3(signature = (namespace, query, top_k = 10, threshold = 0.0, model_filter = None, metric = None))]
+    fn similar<'py>(
+        &self,
+        py: Python<'py>,
+        namespace: Option<&str>,
+        query: &PyAny,
+        top_k: usize,
+        threshold: f32,
+        model_filter: Option<String>,
+        metric: Option<&str>,
+    ) -> PyResult<&'py PyAny> {
+        self.embed_search(py, namespace, query, top_k, threshold, model_filter, metric)
+    }
+
     /// Query data with filters
+    ///
+    /// `filters` is a `Query`, or a list of `Filter`s and
+    /// `{"field", "op", "value"}` dicts. `sort` is a list of `SortBy`s or
+    /// `{"field", "order"}` dicts; it, `limit` and `offset` are applied on
+    /// top of a `Query`.
     #[pyo3(signature = (namespace, filters = None, sort = None, limit = None, offset = None))]
     fn query<'py>(
         &self,
         py: Python<'py>,
         namespace: &str,
-        filters: Option<PyObject>,
-        sort: Option<PyObject>,
+        filters: Option<&PyAny>,
+        sort: Option<&PyAny>,
         limit: Option<usize>,
         offset: Option<usize>,
     ) -> PyResult<&'py PyAny> {
         let db = self.db.clone();
         let ns = namespace.to_string();
 
-        // Build query from Python arguments
-        let mut query = koru_delta::query::Query::default();
-        query.limit = limit;
-        query.offset = offset;
-
-        // Parse filters if provided
-        if let Some(filters_obj) = filters {
-            if let Ok(filters_list) = filters_obj.downcast::<PyList>(py) {
-                for filter_obj in filters_list.iter() {
-                    if let Ok(filter_dict) = filter_obj.downcast::<PyDict>() {
-                        if let (Ok(Some(field_any)), Ok(Some(op_any)), Ok(Some(value))) = (
-                            filter_dict.get_item("field"),
-                            filter_dict.get_item("op"),
-                            filter_dict.get_item("value"),
-                        ) {
-                            let field = field_any.extract::<String>().ok();
-                            let op = op_any.extract::<String>().ok();
-                            if field.is_none() || op.is_none() {
-                                continue;
-                            }
-                            let field = field.unwrap();
-                            let op = op.unwrap();
-                            let json_value = pyobject_to_json(value).unwrap_or(serde_json::Value::Null);
-                            let filter = match op.as_str() {
-                                "eq" => koru_delta::query::Filter::eq(field, json_value),
-                                "ne" => koru_delta::query::Filter::ne(field, json_value),
-                                "gt" => koru_delta::query::Filter::gt(field, json_value),
-                                "gte" => koru_delta::query::Filter::gte(field, json_value),
-                                "lt" => koru_delta::query::Filter::lt(field, json_value),
-                                "lte" => koru_delta::query::Filter::lte(field, json_value),
-                                _ => koru_delta::query::Filter::eq(field, json_value),
-                            };
-                            query.filters.push(filter);
-                        }
-                    }
-                }
-            }
-        }
+        let query = build_query(filters, sort, limit, offset)?;
 
-        // Parse sort if provided
-        if let Some(sort_obj) = sort {
-            if let Ok(sort_list) = sort_obj.downcast::<PyList>(py) {
-                for sort_item in sort_list.iter() {
-                    if let Ok(sort_dict) = sort_item.downcast::<PyDict>() {
-                        if let Ok(Some(field_any)) = sort_dict.get_item("field") {
-                            if let Ok(field) = field_any.extract::<String>() {
-                                let order = sort_dict.get_item("order")
-                                    .ok()
-                                    .flatten()
-                                    .and_then(|o| o.extract::<String>().ok())
-                                    .map(|o| match o.as_str() {
-                                        "desc" | "Desc" => koru_delta::query::SortOrder::Desc,
-                                        _ => koru_delta::query::SortOrder::Asc,
-                                    })
-                                    .unwrap_or(koru_delta::query::SortOrder::Asc);
-                                
-                                query.sort.push(koru_delta::query::SortBy { field, order });
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        future_into_py(py, async move {
+            let results = db.query(&ns, query).await.map_err(to_python_error)?;
+            Python::with_gil(|py| Ok(query_result_to_pyobject(py, &results)))
+        })
+    }
+
+    /// Query data into a pandas DataFrame (or a pyarrow Table with `as_arrow=True`)
+    ///
+    /// Takes the same arguments as `query`. Records travel to Python as an
+    /// Arrow IPC stream; columns are `_key`, `_version_id`, `_timestamp` and
+    /// one per top-level value field. Requires pyarrow (and pandas unless
+    /// `as_arrow=True`).
+    #[pyo3(signature = (namespace, filters = None, sort = None, limit = None, offset = None, as_arrow = false))]
+    fn query_df<'py>(
+        &self,
+        py: Python<'py>,
+        namespace: &str,
+        filters: Option<&PyAny>,
+        sort: Option<&PyAny>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+        as_arrow: bool,
+    ) -> PyResult<&'py PyAny> {
+        let db = self.db.clone();
+        let ns = namespace.to_string();
+        let query = build_query(filters, sort, limit, offset)?;
 
         future_into_py(py, async move {
             let results = db.query(&ns, query).await.map_err(to_python_error)?;
-            
+            let ipc = query_result_to_ipc(&results)
+                .map_err(|e| PyRuntimeError::new_err(format!("Arrow encoding failed: {}", e)))?;
+
             Python::with_gil(|py| {
-                let dict = PyDict::new(py);
-                dict.set_item("total_count", results.total_count).ok();
-                
-                let records = PyList::new(py, Vec::<PyObject>::new());
-                for record in results.records {
-                    let rec_dict = PyDict::new(py);
-                    rec_dict.set_item("key", &record.key).ok();
-                    rec_dict.set_item("value", json_to_pyobject(py, &record.value)).ok();
-                    rec_dict.set_item("timestamp", record.timestamp.to_rfc3339()).ok();
-                    rec_dict.set_item("version_id", &record.version_id).ok();
-                    records.append(rec_dict).ok();
+                let pyarrow = py.import("pyarrow").map_err(|_| {
+                    PyImportError::new_err(
+                        "query_df requires pyarrow: pip install 'koru-delta[dataframe]'",
+                    )
+                })?;
+                let buffer = pyarrow.call_method1("py_buffer", (PyBytes::new(py, &ipc),))?;
+                let table = pyarrow
+                    .getattr("ipc")?
+                    .call_method1("open_stream", (buffer,))?
+                    .call_method0("read_all")?;
+                if as_arrow {
+                    Ok(table.to_object(py))
+                } else {
+                    Ok(table.call_method0("to_pandas")?.to_object(py))
                 }
-                dict.set_item("records", records).ok();
-                
-                Ok(dict.to_object(py))
             })
         })
     }
 
     /// Create a materialized view
+    ///
+    /// `filters` is a `Query` or a list of filters, as for `query`.
     #[pyo3(signature = (name, source_collection, filters = None, description = None, auto_refresh = false))]
     fn create_view<'py>(
         &self,
         py: Python<'py>,
         name: &str,
         source_collection: &str,
-        filters: Option<PyObject>,
+        filters: Option<&PyAny>,
         description: Option<String>,
         auto_refresh: bool,
     ) -> PyResult<&'py PyAny> {
         let db = self.db.clone();
         let view_name = name.to_string();
         let source = source_collection.to_string();
-
-        // Build query for view
-        let mut query = koru_delta::query::Query::default();
-        
-        if let Some(filters_obj) = filters {
-            if let Ok(filters_list) = filters_obj.downcast::<PyList>(py) {
-                for filter_obj in filters_list.iter() {
-                    if let Ok(filter_dict) = filter_obj.downcast::<PyDict>() {
-                        if let (Ok(Some(field_any)), Ok(Some(value))) = (
-                            filter_dict.get_item("field"),
-                            filter_dict.get_item("value"),
-                        ) {
-                            let field = field_any.extract::<String>().ok();
-                            if field.is_none() {
-                                continue;
-                            }
-                            let field = field.unwrap();
-                            let json_value = pyobject_to_json(value).unwrap_or(serde_json::Value::Null);
-                            query.filters.push(koru_delta::query::Filter::eq(field, json_value));
-                        }
-                    }
-                }
-            }
-        }
+        let query = query_from_py(filters)?;
 
         let view_def = koru_delta::views::ViewDefinition {
             name: view_name,
@@ -493,6 +526,7 @@ impl PyDatabase {
             created_at: chrono::Utc::now(),
             description,
             auto_refresh,
+            computed: Vec::new(),
         };
 
         future_into_py(py, async move {
@@ -527,24 +561,7 @@ impl PyDatabase {
 
         future_into_py(py, async move {
             let results = db.query_view(&view_name).await.map_err(to_python_error)?;
-            
-            Python::with_gil(|py| {
-                let dict = PyDict::new(py);
-                dict.set_item("total_count", results.total_count).ok();
-                
-                let records = PyList::new(py, Vec::<PyObject>::new());
-                for record in results.records {
-                    let rec_dict = PyDict::new(py);
-                    rec_dict.set_item("key", &record.key).ok();
-                    rec_dict.set_item("value", json_to_pyobject(py, &record.value)).ok();
-                    rec_dict.set_item("timestamp", record.timestamp.to_rfc3339()).ok();
-                    rec_dict.set_item("version_id", &record.version_id).ok();
-                    records.append(rec_dict).ok();
-                }
-                dict.set_item("records", records).ok();
-                
-                Ok(dict.to_object(py))
-            })
+            Python::with_gil(|py| Ok(query_result_to_pyobject(py, &results)))
         })
     }
 
@@ -668,6 +685,42 @@ impl PyDatabase {
         })
     }
 
+    /// Subscribe to changes made with `put`
+    ///
+    /// With `on_change`, the callback is called with each change event (a
+    /// dict) from a background thread until `unsubscribe()` is called.
+    /// Without it, the returned subscription is an async iterator:
+    ///
+    /// ```python
+    /// async for event in db.subscribe("users"):
+    ///     print(event["change_type"], event["key"], event["value"])
+    /// ```
+    ///
+    /// Omit `namespace` to receive changes from every namespace.
+    #[pyo3(signature = (namespace = None, key = None, on_change = None))]
+    fn subscribe(
+        &self,
+        namespace: Option<String>,
+        key: Option<String>,
+        on_change: Option<PyObject>,
+    ) -> PyResult<PySubscription> {
+        let subscription = match (namespace, key) {
+            (Some(ns), Some(k)) => Subscription::key(ns, k),
+            (Some(ns), None) => Subscription::collection(ns),
+            (None, None) => Subscription::all(),
+            (None, Some(_)) => {
+                return Err(PyValueError::new_err("key requires a namespace"));
+            }
+        };
+
+        let agent = self.db.subscription_manager().clone();
+        let (id, receiver) = agent.subscribe(subscription);
+        Ok(match on_change {
+            Some(callback) => PySubscription::with_callback(agent, id, receiver, callback),
+            None => PySubscription::with_iterator(agent, id, receiver),
+        })
+    }
+
     /// String representation
     fn __repr__(&self) -> String {
         "<Database instance>".to_string()
@@ -0,0 +1,186 @@
+//! Arrow conversion for query results
+//!
+//! `query_df` encodes query records as an Arrow IPC stream, which pyarrow
+//! reads without copying and pandas converts from directly, instead of
+//! building a Python dict per record.
+//!
+//! Columns are `_key`, `_version_id` and `_timestamp`, followed by one
+//! column per top-level field of the values (or a single `value` column
+//! when values aren't objects). Fields holding bools, integers, numbers or
+//! strings get that type; anything else (lists, objects, mixed types) is
+//! stored as JSON text.
+
+use std::sync::Arc;
+
+use arrow_array::builder::{
+    BooleanBuilder, Float64Builder, Int64Builder, StringBuilder, TimestampMicrosecondBuilder,
+};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_ipc::writer::StreamWriter;
+use arrow_schema::{ArrowError, DataType, Field, Schema, TimeUnit};
+use koru_delta::query::QueryResult;
+use serde_json::Value;
+
+/// Encode query records as an Arrow IPC stream
+pub fn query_result_to_ipc(result: &QueryResult) -> Result<Vec<u8>, ArrowError> {
+    let batch = query_result_to_batch(result)?;
+    let mut writer = StreamWriter::try_new(Vec::new(), &batch.schema())?;
+    writer.write(&batch)?;
+    writer.into_inner()
+}
+
+fn query_result_to_batch(result: &QueryResult) -> Result<RecordBatch, ArrowError> {
+    let records = &result.records;
+    let mut fields = vec![
+        Field::new("_key", DataType::Utf8, false),
+        Field::new("_version_id", DataType::Utf8, false),
+        Field::new(
+            "_timestamp",
+            DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+            false,
+        ),
+    ];
+
+    let mut keys = StringBuilder::new();
+    let mut version_ids = StringBuilder::new();
+    let mut timestamps = TimestampMicrosecondBuilder::new().with_timezone("UTC");
+    for record in records {
+        keys.append_value(&record.key);
+        version_ids.append_value(&record.version_id);
+        timestamps.append_value(record.timestamp.timestamp_micros());
+    }
+    let mut columns: Vec<ArrayRef> = vec![
+        Arc::new(keys.finish()),
+        Arc::new(version_ids.finish()),
+        Arc::new(timestamps.finish()),
+    ];
+
+    let values: Vec<&Value> = records.iter().map(|r| &r.value).collect();
+    if values.iter().all(|v| v.is_object() || v.is_null()) {
+        for name in field_names(&values) {
+            let cells: Vec<Option<&Value>> = values.iter().map(|v| v.get(&name)).collect();
+            let (data_type, column) = build_column(&cells);
+            fields.push(Field::new(name, data_type, true));
+            columns.push(column);
+        }
+    } else {
+        let cells: Vec<Option<&Value>> = values.iter().map(|v| Some(*v)).collect();
+        let (data_type, column) = build_column(&cells);
+        fields.push(Field::new("value", data_type, true));
+        columns.push(column);
+    }
+
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+}
+
+/// Top-level field names across all values, in first-seen order
+fn field_names(values: &[&Value]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for value in values {
+        if let Value::Object(map) = value {
+            for name in map.keys() {
+                if !names.contains(name) {
+                    names.push(name.clone());
+                }
+            }
+        }
+    }
+    names
+}
+
+/// Build a column with the narrowest type that holds every cell
+fn build_column(cells: &[Option<&Value>]) -> (DataType, ArrayRef) {
+    let present = || cells.iter().flatten().filter(|v| !v.is_null());
+
+    if present().all(|v| v.is_boolean()) {
+        let mut builder = BooleanBuilder::new();
+        for cell in cells {
+            builder.append_option(cell.and_then(Value::as_bool));
+        }
+        (DataType::Boolean, Arc::new(builder.finish()))
+    } else if present().all(|v| v.is_i64()) {
+        let mut builder = Int64Builder::new();
+        for cell in cells {
+            builder.append_option(cell.and_then(Value::as_i64));
+        }
+        (DataType::Int64, Arc::new(builder.finish()))
+    } else if present().all(|v| v.is_number()) {
+        let mut builder = Float64Builder::new();
+        for cell in cells {
+            builder.append_option(cell.and_then(Value::as_f64));
+        }
+        (DataType::Float64, Arc::new(builder.finish()))
+    } else {
+        let mut builder = StringBuilder::new();
+        for cell in cells {
+            match cell {
+                Some(Value::String(s)) => builder.append_value(s),
+                Some(Value::Null) | None => builder.append_null(),
+                Some(other) => builder.append_value(other.to_string()),
+            }
+        }
+        (DataType::Utf8, Arc::new(builder.finish()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{Array, Float64Array, Int64Array, StringArray};
+    use koru_delta::query::QueryRecord;
+    use serde_json::json;
+
+    fn result(values: Vec<Value>) -> QueryResult {
+        let records = values
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| QueryRecord {
+                key: format!("k{}", i),
+                value,
+                timestamp: chrono::Utc::now(),
+                version_id: format!("v{}", i),
+            })
+            .collect();
+        QueryResult {
+            records,
+            total_count: 0,
+            aggregation: None,
+            partial: false,
+        }
+    }
+
+    #[test]
+    fn test_columns_from_object_fields() {
+        let batch = query_result_to_batch(&result(vec![
+            json!({"age": 30, "score": 1.5, "name": "alice"}),
+            json!({"age": 40, "score": 2, "tags": ["a"]}),
+        ]))
+        .unwrap();
+
+        let schema = batch.schema();
+        let names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(
+            names,
+            ["_key", "_version_id", "_timestamp", "age", "name", "score", "tags"]
+        );
+
+        let age = batch.column(3).as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(age.values(), &[30, 40]);
+        let score = batch.column(5).as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(score.values(), &[1.5, 2.0]);
+        let name = batch.column(4).as_any().downcast_ref::<StringArray>().unwrap();
+        assert!(name.is_null(1));
+        let tags = batch.column(6).as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(tags.value(1), r#"["a"]"#);
+    }
+
+    #[test]
+    fn test_scalar_values_use_value_column() {
+        let bytes = query_result_to_ipc(&result(vec![json!(1), json!(2)])).unwrap();
+        let reader = arrow_ipc::reader::StreamReader::try_new(&bytes[..], None).unwrap();
+        let batches: Vec<_> = reader.map(|b| b.unwrap()).collect();
+        assert_eq!(batches[0].num_rows(), 2);
+        assert_eq!(batches[0].schema().field(3).name(), "value");
+        assert_eq!(batches[0].schema().field(3).data_type(), &DataType::Int64);
+    }
+}
@@ -7,10 +7,18 @@
 use pyo3::prelude::*;
 use pyo3::create_exception;
 
+mod clock;
 mod database;
+mod dataframe;
+mod query;
+mod subscriptions;
 mod types;
+mod vector;
 
 use database::{PyDatabase, PyIdentityManager, PyWorkspace, PyClusterConfig, PyClusterNode};
+use query::{PyAggregation, PyFilter, PyQuery, PySortBy};
+use subscriptions::PySubscription;
+use vector::PyVector;
 
 /// Convert Rust DeltaError to appropriate Python exception
 fn to_python_error(e: koru_delta::DeltaError) -> PyErr {
@@ -18,10 +26,18 @@ fn to_python_error(e: koru_delta::DeltaError) -> PyErr {
         koru_delta::DeltaError::KeyNotFound { .. } => KeyNotFoundError::new_err(e.to_string()),
         koru_delta::DeltaError::NoValueAtTimestamp { .. } => KeyNotFoundError::new_err(e.to_string()),
         koru_delta::DeltaError::InvalidData { .. } => InvalidDataError::new_err(e.to_string()),
+        koru_delta::DeltaError::InvalidConfig { .. } => InvalidDataError::new_err(e.to_string()),
+        koru_delta::DeltaError::WriteRejected { .. } => InvalidDataError::new_err(e.to_string()),
         koru_delta::DeltaError::EngineError(_) => EngineError::new_err(e.to_string()),
         koru_delta::DeltaError::StorageError(_) => StorageError::new_err(e.to_string()),
         koru_delta::DeltaError::TimeError(_) => TimeError::new_err(e.to_string()),
         koru_delta::DeltaError::SerializationError(_) => SerializationError::new_err(e.to_string()),
+        koru_delta::DeltaError::EmbeddingError(_) => EngineError::new_err(e.to_string()),
+        koru_delta::DeltaError::Unauthorized { .. } => UnauthorizedError::new_err(e.to_string()),
+        koru_delta::DeltaError::ShuttingDown => StorageError::new_err(e.to_string()),
+        koru_delta::DeltaError::QueryTimeout { .. } => StorageError::new_err(e.to_string()),
+        koru_delta::DeltaError::QueryCancelled => StorageError::new_err(e.to_string()),
+        koru_delta::DeltaError::QueryLimitExceeded { .. } => StorageError::new_err(e.to_string()),
     }
 }
 
@@ -52,6 +68,9 @@ create_exception!(koru_delta, EngineError, KoruDeltaError);
 // Raised for time-related errors
 create_exception!(koru_delta, TimeError, KoruDeltaError);
 
+// Raised when a session or capability doesn't allow an operation
+create_exception!(koru_delta, UnauthorizedError, KoruDeltaError);
+
 /// Module initialization
 #[pymodule]
 fn _internal(_py: Python, m: &PyModule) -> PyResult<()> {
@@ -59,10 +78,24 @@ fn _internal(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyDatabase>()?;
     m.add_class::<PyIdentityManager>()?;
     m.add_class::<PyWorkspace>()?;
+    m.add_class::<PySubscription>()?;
+    m.add_class::<PyVector>()?;
+
+    // Query builder
+    m.add_class::<PyQuery>()?;
+    m.add_class::<PyFilter>()?;
+    m.add_class::<PySortBy>()?;
+    m.add_class::<PyAggregation>()?;
     
     // Cluster classes
     m.add_class::<PyClusterConfig>()?;
     m.add_class::<PyClusterNode>()?;
+
+    // Vector clock helpers
+    m.add_function(wrap_pyfunction!(clock::happens_before, m)?)?;
+    m.add_function(wrap_pyfunction!(clock::concurrent_with, m)?)?;
+    m.add_function(wrap_pyfunction!(clock::descends_from, m)?)?;
+    m.add_function(wrap_pyfunction!(clock::merge_clocks, m)?)?;
     
     // Exceptions
     m.add("KoruDeltaError", _py.get_type::<KoruDeltaError>())?;
@@ -72,6 +105,7 @@ fn _internal(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add("SerializationError", _py.get_type::<SerializationError>())?;
     m.add("EngineError", _py.get_type::<EngineError>())?;
     m.add("TimeError", _py.get_type::<TimeError>())?;
+    m.add("UnauthorizedError", _py.get_type::<UnauthorizedError>())?;
     
     // Version
     m.add("__version__", "3.0.0")?;
@@ -0,0 +1,476 @@
+//! Query builder for Python
+//!
+//! Wraps `Query`, `Filter`, `SortBy` and `Aggregation` so queries and views
+//! can be built fluently and run inside the database:
+//!
+//! ```python
+//! query = (Query()
+//!     .filter(Filter.gte("age", 18) & ~Filter.eq("status", "banned"))
+//!     .sort_by("age", ascending=False)
+//!     .limit(10))
+//! result = await db.query("users", query)
+//! ```
+
+use pyo3::prelude::*;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::types::{PyDict, PyList};
+
+use crate::types::{json_to_pyobject, pyobject_to_json};
+use koru_delta::query::{Aggregation, BucketInterval, Filter, Query, QueryResult, SortBy, SortOrder};
+
+/// A filter condition
+#[pyclass(name = "Filter")]
+#[derive(Clone)]
+pub struct PyFilter {
+    inner: Filter,
+}
+
+#[pymethods]
+impl PyFilter {
+    /// Field equals value
+    #[staticmethod]
+    fn eq(field: &str, value: &PyAny) -> PyResult<Self> {
+        Ok(Filter::eq(field, pyobject_to_json(value)?).into())
+    }
+
+    /// Field does not equal value
+    #[staticmethod]
+    fn ne(field: &str, value: &PyAny) -> PyResult<Self> {
+        Ok(Filter::ne(field, pyobject_to_json(value)?).into())
+    }
+
+    /// Field is greater than value
+    #[staticmethod]
+    fn gt(field: &str, value: &PyAny) -> PyResult<Self> {
+        Ok(Filter::gt(field, pyobject_to_json(value)?).into())
+    }
+
+    /// Field is greater than or equal to value
+    #[staticmethod]
+    fn gte(field: &str, value: &PyAny) -> PyResult<Self> {
+        Ok(Filter::gte(field, pyobject_to_json(value)?).into())
+    }
+
+    /// Field is less than value
+    #[staticmethod]
+    fn lt(field: &str, value: &PyAny) -> PyResult<Self> {
+        Ok(Filter::lt(field, pyobject_to_json(value)?).into())
+    }
+
+    /// Field is less than or equal to value
+    #[staticmethod]
+    fn lte(field: &str, value: &PyAny) -> PyResult<Self> {
+        Ok(Filter::lte(field, pyobject_to_json(value)?).into())
+    }
+
+    /// Field contains a substring (strings) or element (lists)
+    #[staticmethod]
+    fn contains(field: &str, value: &PyAny) -> PyResult<Self> {
+        Ok(Filter::contains(field, pyobject_to_json(value)?).into())
+    }
+
+    /// Field is present and not null
+    #[staticmethod]
+    fn exists(field: &str) -> Self {
+        Filter::exists(field).into()
+    }
+
+    /// Field matches a regex pattern
+    #[staticmethod]
+    fn matches(field: &str, pattern: &str) -> Self {
+        Filter::matches(field, pattern).into()
+    }
+
+    /// All filters match
+    #[staticmethod]
+    #[pyo3(signature = (*filters))]
+    fn all(filters: Vec<PyFilter>) -> Self {
+        Filter::and(filters.into_iter().map(|f| f.inner).collect()).into()
+    }
+
+    /// Any filter matches
+    #[staticmethod]
+    #[pyo3(signature = (*filters))]
+    fn any(filters: Vec<PyFilter>) -> Self {
+        Filter::or(filters.into_iter().map(|f| f.inner).collect()).into()
+    }
+
+    fn __and__(&self, other: PyFilter) -> Self {
+        Filter::and(vec![self.inner.clone(), other.inner]).into()
+    }
+
+    fn __or__(&self, other: PyFilter) -> Self {
+        Filter::or(vec![self.inner.clone(), other.inner]).into()
+    }
+
+    fn __invert__(&self) -> Self {
+        Filter::not(self.inner.clone()).into()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Filter({})", to_json_string(&self.inner))
+    }
+}
+
+impl From<Filter> for PyFilter {
+    fn from(inner: Filter) -> Self {
+        Self { inner }
+    }
+}
+
+/// A sort specification
+#[pyclass(name = "SortBy")]
+#[derive(Clone)]
+pub struct PySortBy {
+    inner: SortBy,
+}
+
+#[pymethods]
+impl PySortBy {
+    /// Sort by a field, smallest first
+    #[staticmethod]
+    fn asc(field: &str) -> Self {
+        Self {
+            inner: SortBy::asc(field),
+        }
+    }
+
+    /// Sort by a field, largest first
+    #[staticmethod]
+    fn desc(field: &str) -> Self {
+        Self {
+            inner: SortBy::desc(field),
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        let order = match self.inner.order {
+            SortOrder::Asc => "asc",
+            SortOrder::Desc => "desc",
+        };
+        format!("SortBy.{}('{}')", order, self.inner.field)
+    }
+}
+
+/// An aggregation over matching records
+#[pyclass(name = "Aggregation")]
+#[derive(Clone)]
+pub struct PyAggregation {
+    inner: Aggregation,
+}
+
+#[pymethods]
+impl PyAggregation {
+    /// Number of matching records
+    #[staticmethod]
+    fn count() -> Self {
+        Aggregation::count().into()
+    }
+
+    /// Sum of a numeric field
+    #[staticmethod]
+    fn sum(field: &str) -> Self {
+        Aggregation::sum(field).into()
+    }
+
+    /// Average of a numeric field
+    #[staticmethod]
+    fn avg(field: &str) -> Self {
+        Aggregation::avg(field).into()
+    }
+
+    /// Smallest value of a field
+    #[staticmethod]
+    fn min(field: &str) -> Self {
+        Aggregation::min(field).into()
+    }
+
+    /// Largest value of a field
+    #[staticmethod]
+    fn max(field: &str) -> Self {
+        Aggregation::max(field).into()
+    }
+
+    /// Unique values of a field
+    #[staticmethod]
+    fn distinct(field: &str) -> Self {
+        Aggregation::distinct(field).into()
+    }
+
+    /// Approximate number of unique values of a field
+    #[staticmethod]
+    fn approx_distinct(field: &str) -> Self {
+        Aggregation::approx_distinct(field).into()
+    }
+
+    /// Approximate percentiles (0 to 100) of a numeric field
+    #[staticmethod]
+    fn percentiles(field: &str, percentiles: Vec<f64>) -> Self {
+        Aggregation::percentiles(field, &percentiles).into()
+    }
+
+    /// Approximate `k` most frequent values of a field
+    #[staticmethod]
+    fn approx_top_k(field: &str, k: usize) -> Self {
+        Aggregation::approx_top_k(field, k).into()
+    }
+
+    /// Roll up a numeric field per "minute", "hour", "day", "week" or "month"
+    #[staticmethod]
+    fn time_bucket(field: &str, interval: &str) -> PyResult<Self> {
+        let interval: BucketInterval = serde_json::from_value(serde_json::json!(interval))
+            .map_err(|_| PyValueError::new_err(format!("unknown interval '{}'", interval)))?;
+        Ok(Aggregation::time_bucket(field, interval).into())
+    }
+
+    /// Group by a field and run named aggregations per group
+    ///
+    /// `aggregations` maps result names to aggregations, e.g.
+    /// `{"total": Aggregation.sum("amount")}`.
+    #[staticmethod]
+    fn group_by(field: &str, aggregations: &PyDict) -> PyResult<Self> {
+        let aggregations = aggregations
+            .iter()
+            .map(|(name, aggregation)| {
+                Ok((
+                    name.extract::<String>()?,
+                    aggregation.extract::<PyAggregation>()?.inner,
+                ))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(Aggregation::GroupBy {
+            field: field.to_string(),
+            aggregations,
+        }
+        .into())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Aggregation({})", to_json_string(&self.inner))
+    }
+}
+
+impl From<Aggregation> for PyAggregation {
+    fn from(inner: Aggregation) -> Self {
+        Self { inner }
+    }
+}
+
+/// A query with filters, projection, sorting, pagination and aggregation
+///
+/// Builder methods return a new `Query`, so partial queries can be reused.
+#[pyclass(name = "Query")]
+#[derive(Clone, Default)]
+pub struct PyQuery {
+    pub(crate) inner: Query,
+}
+
+#[pymethods]
+impl PyQuery {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add filters; all of them must match
+    #[pyo3(signature = (*filters))]
+    fn filter(&self, filters: Vec<PyFilter>) -> Self {
+        self.with(|q| q.filters(filters.into_iter().map(|f| f.inner).collect()))
+    }
+
+    /// Only return these fields of each value
+    #[pyo3(signature = (*fields))]
+    fn select(&self, fields: Vec<String>) -> Self {
+        self.with(|mut q| {
+            q.projection = fields;
+            q
+        })
+    }
+
+    /// Sort by a field
+    #[pyo3(signature = (field, ascending = true))]
+    fn sort_by(&self, field: &str, ascending: bool) -> Self {
+        self.with(|q| q.sort_by(field, ascending))
+    }
+
+    /// Add sort specifications, applied in order
+    #[pyo3(signature = (*sorts))]
+    fn sort(&self, sorts: Vec<PySortBy>) -> Self {
+        self.with(|mut q| {
+            q.sort.extend(sorts.into_iter().map(|s| s.inner));
+            q
+        })
+    }
+
+    /// Return at most `n` records
+    fn limit(&self, n: usize) -> Self {
+        self.with(|q| q.limit(n))
+    }
+
+    /// Skip the first `n` records
+    fn offset(&self, n: usize) -> Self {
+        self.with(|q| q.offset(n))
+    }
+
+    /// Aggregate the matching records
+    fn aggregate(&self, aggregation: PyAggregation) -> Self {
+        self.with(|q| q.aggregate(aggregation.inner))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Query({})", to_json_string(&self.inner))
+    }
+}
+
+impl PyQuery {
+    fn with(&self, build: impl FnOnce(Query) -> Query) -> Self {
+        Self {
+            inner: build(self.inner.clone()),
+        }
+    }
+}
+
+/// Build a query from a `Query`, or a list of `Filter`s and
+/// `{"field", "op", "value"}` dicts (`op` defaults to "eq")
+pub fn query_from_py(obj: Option<&PyAny>) -> PyResult<Query> {
+    let Some(obj) = obj else {
+        return Ok(Query::default());
+    };
+    if let Ok(query) = obj.extract::<PyQuery>() {
+        return Ok(query.inner);
+    }
+    let list = obj.downcast::<PyList>().map_err(|_| {
+        PyTypeError::new_err("filters must be a Query or a list of filters")
+    })?;
+
+    let mut query = Query::default();
+    for item in list.iter() {
+        query.filters.push(filter_from_py(item)?);
+    }
+    Ok(query)
+}
+
+/// Build a query from `query`-style arguments; `sort`, `limit` and
+/// `offset` override or extend what `filters` sets
+pub fn build_query(
+    filters: Option<&PyAny>,
+    sort: Option<&PyAny>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> PyResult<Query> {
+    let mut query = query_from_py(filters)?;
+    if let Some(sort) = sort {
+        query.sort.extend(sort_from_py(sort)?);
+    }
+    if limit.is_some() {
+        query.limit = limit;
+    }
+    if offset.is_some() {
+        query.offset = offset;
+    }
+    Ok(query)
+}
+
+/// Build sort specifications from a list of `SortBy`s and
+/// `{"field", "order"}` dicts (`order` is "asc" or "desc")
+pub fn sort_from_py(obj: &PyAny) -> PyResult<Vec<SortBy>> {
+    let list = obj
+        .downcast::<PyList>()
+        .map_err(|_| PyTypeError::new_err("sort must be a list"))?;
+    list.iter()
+        .map(|item| {
+            if let Ok(sort) = item.extract::<PySortBy>() {
+                return Ok(sort.inner);
+            }
+            let dict = item.downcast::<PyDict>().map_err(|_| {
+                PyTypeError::new_err("sort must contain SortBy or dicts with field and order")
+            })?;
+            let field: String = dict
+                .get_item("field")?
+                .ok_or_else(|| PyValueError::new_err("sort is missing 'field'"))?
+                .extract()?;
+            let order = match dict.get_item("order")? {
+                Some(order) => match order.extract::<String>()?.to_lowercase().as_str() {
+                    "asc" => SortOrder::Asc,
+                    "desc" => SortOrder::Desc,
+                    other => {
+                        return Err(PyValueError::new_err(format!(
+                            "Unknown sort order: {}",
+                            other
+                        )))
+                    }
+                },
+                None => SortOrder::Asc,
+            };
+            Ok(SortBy::new(field, order))
+        })
+        .collect()
+}
+
+fn filter_from_py(obj: &PyAny) -> PyResult<Filter> {
+    if let Ok(filter) = obj.extract::<PyFilter>() {
+        return Ok(filter.inner);
+    }
+    let dict = obj.downcast::<PyDict>().map_err(|_| {
+        PyTypeError::new_err("filter must be a Filter or a dict with field, op and value")
+    })?;
+
+    let field: String = dict
+        .get_item("field")?
+        .ok_or_else(|| PyValueError::new_err("filter is missing 'field'"))?
+        .extract()?;
+    let op: String = match dict.get_item("op")? {
+        Some(op) => op.extract()?,
+        None => "eq".to_string(),
+    };
+    let value = match dict.get_item("value")? {
+        Some(value) => pyobject_to_json(value)?,
+        None => serde_json::Value::Null,
+    };
+
+    Ok(match op.as_str() {
+        "eq" => Filter::eq(field, value),
+        "ne" => Filter::ne(field, value),
+        "gt" => Filter::gt(field, value),
+        "gte" => Filter::gte(field, value),
+        "lt" => Filter::lt(field, value),
+        "lte" => Filter::lte(field, value),
+        "contains" => Filter::contains(field, value),
+        "exists" => Filter::exists(field),
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown filter op: {}",
+                op
+            )))
+        }
+    })
+}
+
+/// Convert query results to a dict with `total_count`, `records` and `aggregation`
+pub fn query_result_to_pyobject(py: Python, result: &QueryResult) -> PyObject {
+    let dict = PyDict::new(py);
+    dict.set_item("total_count", result.total_count).ok();
+
+    let records = PyList::empty(py);
+    for record in &result.records {
+        let rec_dict = PyDict::new(py);
+        rec_dict.set_item("key", &record.key).ok();
+        rec_dict.set_item("value", json_to_pyobject(py, &record.value)).ok();
+        rec_dict.set_item("timestamp", record.timestamp.to_rfc3339()).ok();
+        rec_dict.set_item("version_id", &record.version_id).ok();
+        records.append(rec_dict).ok();
+    }
+    dict.set_item("records", records).ok();
+
+    let aggregation = match &result.aggregation {
+        Some(value) => json_to_pyobject(py, value),
+        None => py.None(),
+    };
+    dict.set_item("aggregation", aggregation).ok();
+
+    dict.to_object(py)
+}
+
+fn to_json_string<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
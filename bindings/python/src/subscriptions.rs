@@ -0,0 +1,177 @@
+//! Python wrapper for change subscriptions
+//!
+//! Bridges the Rust broadcast channels behind `KoruDelta::subscribe` into
+//! Python, either by calling an `on_change` callback for each event or as
+//! an async iterator.
+
+use std::sync::Arc;
+
+use pyo3::exceptions::{PyStopAsyncIteration, PyTypeError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3_asyncio::tokio::future_into_py;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, Mutex};
+use tokio::task::JoinHandle;
+
+use crate::types::json_to_pyobject;
+use koru_delta::subscriptions::{ChangeEvent, ChangeType, SubscriptionAgent, SubscriptionId};
+use koru_delta::SubscriptionGuard;
+
+/// How events reach Python
+enum Delivery {
+    /// A background task calls `on_change`; it owns the subscription
+    Callback(JoinHandle<()>),
+    /// Events are pulled with `async for`
+    Iterator {
+        receiver: Arc<Mutex<broadcast::Receiver<ChangeEvent>>>,
+        _guard: SubscriptionGuard,
+    },
+}
+
+/// A live subscription to changes
+#[pyclass(name = "Subscription")]
+pub struct PySubscription {
+    id: SubscriptionId,
+    delivery: Option<Delivery>,
+}
+
+impl PySubscription {
+    /// Subscribe and call `on_change` with each event from a background thread.
+    pub fn with_callback(
+        agent: Arc<SubscriptionAgent>,
+        id: SubscriptionId,
+        mut receiver: broadcast::Receiver<ChangeEvent>,
+        on_change: PyObject,
+    ) -> Self {
+        let guard = SubscriptionGuard::new(agent, id);
+        let task = pyo3_asyncio::tokio::get_runtime().spawn(async move {
+            let _guard = guard;
+            loop {
+                let event = match receiver.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+                Python::with_gil(|py| {
+                    // A failing callback shouldn't end the subscription
+                    if let Err(e) = on_change.call1(py, (change_event_to_pyobject(py, &event),)) {
+                        e.print(py);
+                    }
+                });
+            }
+        });
+        Self {
+            id,
+            delivery: Some(Delivery::Callback(task)),
+        }
+    }
+
+    /// Subscribe for use with `async for`.
+    pub fn with_iterator(
+        agent: Arc<SubscriptionAgent>,
+        id: SubscriptionId,
+        receiver: broadcast::Receiver<ChangeEvent>,
+    ) -> Self {
+        Self {
+            id,
+            delivery: Some(Delivery::Iterator {
+                receiver: Arc::new(Mutex::new(receiver)),
+                _guard: SubscriptionGuard::new(agent, id),
+            }),
+        }
+    }
+}
+
+#[pymethods]
+impl PySubscription {
+    /// Subscription ID
+    #[getter]
+    fn id(&self) -> u64 {
+        self.id.0
+    }
+
+    /// Whether the subscription is still receiving events
+    #[getter]
+    fn active(&self) -> bool {
+        match &self.delivery {
+            Some(Delivery::Callback(task)) => !task.is_finished(),
+            Some(Delivery::Iterator { .. }) => true,
+            None => false,
+        }
+    }
+
+    /// Stop receiving events
+    ///
+    /// Pending `async for` loops end once buffered events are drained.
+    fn unsubscribe(&mut self) {
+        if let Some(Delivery::Callback(task)) = self.delivery.take() {
+            task.abort();
+        }
+    }
+
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyResult<PyRef<'_, Self>> {
+        if matches!(slf.delivery, Some(Delivery::Callback(_))) {
+            return Err(PyTypeError::new_err(
+                "subscriptions with on_change cannot be iterated",
+            ));
+        }
+        Ok(slf)
+    }
+
+    fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Option<&'py PyAny>> {
+        let receiver = match &self.delivery {
+            Some(Delivery::Iterator { receiver, .. }) => Arc::clone(receiver),
+            Some(Delivery::Callback(_)) => {
+                return Err(PyTypeError::new_err(
+                    "subscriptions with on_change cannot be iterated",
+                ))
+            }
+            None => return Err(PyStopAsyncIteration::new_err(())),
+        };
+
+        let next = future_into_py(py, async move {
+            let mut receiver = receiver.lock().await;
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        return Python::with_gil(|py| Ok(change_event_to_pyobject(py, &event)))
+                    }
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return Err(PyStopAsyncIteration::new_err(())),
+                }
+            }
+        })?;
+        Ok(Some(next))
+    }
+
+    fn __repr__(&self) -> String {
+        let active = if self.active() { "True" } else { "False" };
+        format!("Subscription(id={}, active={})", self.id.0, active)
+    }
+}
+
+/// Convert a change event to a Python dict
+fn change_event_to_pyobject(py: Python, event: &ChangeEvent) -> PyObject {
+    let change_type = match event.change_type {
+        ChangeType::Insert => "insert",
+        ChangeType::Update => "update",
+        ChangeType::Delete => "delete",
+    };
+    let optional_json = |value: &Option<serde_json::Value>| match value {
+        Some(value) => json_to_pyobject(py, value),
+        None => py.None(),
+    };
+
+    let dict = PyDict::new(py);
+    dict.set_item("change_type", change_type).ok();
+    dict.set_item("namespace", &event.collection).ok();
+    dict.set_item("key", &event.key).ok();
+    dict.set_item("value", optional_json(&event.value)).ok();
+    dict.set_item("previous_value", optional_json(&event.previous_value)).ok();
+    dict.set_item("timestamp", event.timestamp.to_rfc3339()).ok();
+    dict.set_item("version_id", &event.version_id).ok();
+    dict.set_item("previous_version_id", &event.previous_version_id).ok();
+    dict.set_item("sequence", event.sequence).ok();
+    dict.to_object(py)
+}
@@ -2,49 +2,145 @@
 
 use pyo3::prelude::*;
 use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::types::{PyDict, PyList};
+
+use koru_delta::vector::{DistanceMetric, Vector, VectorSearchResult};
+
+/// Python wrapper for an embedding vector
+#[pyclass(name = "Vector")]
+#[derive(Clone)]
+pub struct PyVector {
+    pub(crate) inner: Vector,
+}
+
+#[pymethods]
+impl PyVector {
+    /// Create a vector from a list or numpy array of floats
+    #[new]
+    fn new(data: &PyAny, model: &str) -> PyResult<Self> {
+        let data = extract_f32_vector(data)?;
+        if data.is_empty() {
+            return Err(PyValueError::new_err("Vector cannot be empty"));
+        }
+        Ok(Self {
+            inner: Vector::new(data, model),
+        })
+    }
+
+    /// The embedding model that produced this vector
+    #[getter]
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    /// Number of dimensions
+    #[getter]
+    fn dimensions(&self) -> usize {
+        self.inner.dimensions()
+    }
+
+    /// Vector data as a list of floats
+    #[getter]
+    fn data(&self) -> Vec<f32> {
+        self.inner.as_slice().to_vec()
+    }
+
+    /// Vector data as a numpy array
+    fn to_numpy<'py>(&self, py: Python<'py>) -> &'py numpy::PyArray1<f32> {
+        numpy::PyArray1::from_slice(py, self.inner.as_slice())
+    }
+
+    /// Similarity to another vector under a metric (default "cosine")
+    ///
+    /// Returns None when the vectors have different dimensions or models.
+    #[pyo3(signature = (other, metric = None))]
+    fn similarity(&self, other: &PyAny, metric: Option<&str>) -> PyResult<Option<f32>> {
+        let other = extract_vector(other, Some(self.inner.model()))?;
+        let metric = parse_metric(metric)?.unwrap_or_default();
+        Ok(metric.similarity(&self.inner, &other))
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.dimensions()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Vector(model='{}', dimensions={})",
+            self.inner.model(),
+            self.inner.dimensions()
+        )
+    }
+}
+
+/// Convert a `Vector`, list, or numpy array to a `Vector`
+///
+/// Raw data needs a model name; a `Vector` keeps its own.
+pub fn extract_vector(obj: &PyAny, model: Option<&str>) -> PyResult<Vector> {
+    if let Ok(vector) = obj.extract::<PyRef<PyVector>>() {
+        return Ok(vector.inner.clone());
+    }
+    let model = model.ok_or_else(|| {
+        PyValueError::new_err("model is required when the embedding is not a Vector")
+    })?;
+    let data = extract_f32_vector(obj)?;
+    if data.is_empty() {
+        return Err(PyValueError::new_err("Embedding cannot be empty"));
+    }
+    Ok(Vector::new(data, model))
+}
 
 /// Convert Python list or numpy array to Vec<f32>
 pub fn extract_f32_vector(obj: &PyAny) -> PyResult<Vec<f32>> {
-    // Try list first
-    if let Ok(list) = obj.extract::<Vec<f32>>() {
-        return Ok(list);
-    }
-    
-    // Try numpy array
-    if let Ok(array) = obj.downcast::<numpy::PyArray1<f32>>() {
-        let readonly = array.readonly();
-        let slice = readonly.as_slice()
-            .map_err(|e| PyValueError::new_err(format!("Cannot read array: {}", e)))?;
-        return Ok(slice.to_vec());
-    }
-    
-    // Try converting from other numeric types
-    if let Ok(list) = obj.downcast::<pyo3::types::PyList>() {
-        let mut result = Vec::with_capacity(list.len());
-        for item in list.iter() {
-            let val: f32 = item.extract()
-                .map_err(|_| PyTypeError::new_err(
-                    "Embedding must contain numeric values"
-                ))?;
-            result.push(val);
+    // Only array-likes can be numpy arrays; checking first avoids
+    // requiring numpy for plain lists
+    if obj.hasattr("__array__")? {
+        if let Ok(array) = obj.downcast::<numpy::PyArray1<f32>>() {
+            let readonly = array.readonly();
+            let slice = readonly.as_slice()
+                .map_err(|e| PyValueError::new_err(format!("Cannot read array: {}", e)))?;
+            return Ok(slice.to_vec());
+        }
+        if let Ok(array) = obj.downcast::<numpy::PyArray1<f64>>() {
+            let readonly = array.readonly();
+            let slice = readonly.as_slice()
+                .map_err(|e| PyValueError::new_err(format!("Cannot read array: {}", e)))?;
+            return Ok(slice.iter().map(|&v| v as f32).collect());
+        }
+    }
+
+    // Any other sequence of numbers (lists, tuples, other array dtypes)
+    if obj.downcast::<pyo3::types::PyString>().is_err() {
+        if let Ok(values) = obj.extract::<Vec<f32>>() {
+            return Ok(values);
         }
-        return Ok(result);
     }
-    
+
     Err(PyTypeError::new_err(
-        "Embedding must be a list or numpy array of floats"
+        "Embedding must be a Vector, or a list or 1-D numpy array of floats"
     ))
 }
 
-/// Get dimensionality of a vector
-pub fn get_vector_dim(obj: &PyAny) -> PyResult<usize> {
-    if let Ok(list) = obj.downcast::<pyo3::types::PyList>() {
-        Ok(list.len())
-    } else if let Ok(array) = obj.downcast::<numpy::PyArray1<f32>>() {
-        Ok(array.len())
-    } else if let Ok(vec) = obj.extract::<Vec<f32>>() {
-        Ok(vec.len())
-    } else {
-        Err(PyTypeError::new_err("Cannot determine vector dimensions"))
+/// Parse a distance metric name ("cosine", "dot_product", "euclidean", "manhattan")
+pub fn parse_metric(metric: Option<&str>) -> PyResult<Option<DistanceMetric>> {
+    metric
+        .map(|name| {
+            serde_json::from_value(serde_json::Value::String(name.to_string()))
+                .map_err(|_| PyValueError::new_err(format!("Unknown distance metric: {}", name)))
+        })
+        .transpose()
+}
+
+/// Convert search results to a list of dicts with `namespace`, `key`, `score` and `vector`
+pub fn search_results_to_pylist(py: Python, results: Vec<VectorSearchResult>) -> PyResult<PyObject> {
+    let list = PyList::empty(py);
+    for result in results {
+        let dict = PyDict::new(py);
+        dict.set_item("namespace", &result.namespace)?;
+        dict.set_item("key", &result.key)?;
+        dict.set_item("score", result.score)?;
+        dict.set_item("vector", Py::new(py, PyVector { inner: result.vector })?)?;
+        list.append(dict)?;
     }
+    Ok(list.to_object(py))
 }
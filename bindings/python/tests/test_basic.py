@@ -2,7 +2,19 @@
 
 import pytest
 import asyncio
-from koru_delta import Database, KeyNotFoundError
+from koru_delta import (
+    Aggregation,
+    Database,
+    Filter,
+    KeyNotFoundError,
+    Query,
+    SortBy,
+    Vector,
+    concurrent_with,
+    descends_from,
+    happens_before,
+    merge_clocks,
+)
 
 
 @pytest.mark.asyncio
@@ -58,6 +70,30 @@ async def test_delete():
         assert await db.contains("test", "key1") is True  # Tombstone exists
 
 
+@pytest.mark.asyncio
+async def test_get_versioned():
+    """Test reading a value with its version metadata."""
+    async with Database() as db:
+        await db.put("test", "key1", {"value": 1})
+        await db.put("test", "key1", {"value": 2})
+        versioned = await db.get_versioned("test", "key1")
+        assert versioned["value"] == {"value": 2}
+        assert versioned["previous_version"] is not None
+        assert isinstance(versioned["vector_clock"], dict)
+
+
+def test_vector_clock_helpers():
+    """Test causality comparisons on plain clock dicts."""
+    base = {"a": 1}
+    left = {"a": 2}
+    right = {"a": 1, "b": 1}
+    assert happens_before(base, left)
+    assert not happens_before(left, base)
+    assert concurrent_with(left, right)
+    assert descends_from(right, base)
+    assert merge_clocks([base, left, right]) == {"a": 2, "b": 1}
+
+
 @pytest.mark.asyncio
 async def test_stats():
     """Test database stats."""
@@ -89,3 +125,120 @@ async def test_put_batch():
         
         product = await db.get("products", "p1")
         assert product["name"] == "Widget"
+
+
+@pytest.mark.asyncio
+async def test_subscribe_iterator():
+    """Test iterating over change events."""
+    async with Database() as db:
+        subscription = db.subscribe("users", "alice")
+        await db.put("users", "alice", {"age": 30})
+        await db.put("users", "bob", {"age": 20})
+        await db.put("users", "alice", {"age": 31})
+
+        events = []
+        async for event in subscription:
+            events.append(event)
+            if len(events) == 2:
+                break
+        subscription.unsubscribe()
+
+        assert [e["change_type"] for e in events] == ["insert", "update"]
+        assert events[1]["value"] == {"age": 31}
+        assert events[1]["previous_value"] == {"age": 30}
+
+
+@pytest.mark.asyncio
+async def test_subscribe_callback():
+    """Test change callbacks."""
+    async with Database() as db:
+        seen = []
+        subscription = db.subscribe("users", on_change=seen.append)
+        await db.put("users", "alice", 1)
+        await db.put("users", "bob", 2)
+        await asyncio.sleep(0.1)
+        subscription.unsubscribe()
+
+        assert [e["key"] for e in seen] == ["alice", "bob"]
+        assert not subscription.active
+
+
+@pytest.mark.asyncio
+async def test_embed_search():
+    """Test storing and searching embeddings."""
+    async with Database() as db:
+        await db.embed("docs", "a", Vector([1.0, 0.0, 0.0], "test-model"))
+        await db.embed("docs", "b", [0.0, 1.0, 0.0], "test-model")
+        await db.embed("docs", "c", [0.7, 0.7, 0.0], model="test-model")
+
+        results = await db.embed_search("docs", [1.0, 0.1, 0.0], top_k=2)
+        assert [r["key"] for r in results] == ["a", "c"]
+        assert results[0]["score"] > results[1]["score"]
+        assert results[0]["vector"].dimensions == 3
+
+
+@pytest.mark.asyncio
+async def test_embed_search_numpy():
+    """Test numpy arrays as embeddings and queries."""
+    np = pytest.importorskip("numpy")
+    async with Database() as db:
+        await db.embed("docs", "a", np.array([1.0, 0.0], dtype=np.float32), "test-model")
+        await db.embed("docs", "b", np.array([0.0, 1.0]), "test-model")
+
+        results = await db.embed_search("docs", np.array([0.0, 1.0]), top_k=1)
+        assert results[0]["key"] == "b"
+        assert list(results[0]["vector"].to_numpy()) == [0.0, 1.0]
+
+
+@pytest.mark.asyncio
+async def test_query_builder():
+    """Test fluent queries with filters, sorting and aggregation."""
+    async with Database() as db:
+        await db.put("users", "alice", {"age": 30, "status": "active"})
+        await db.put("users", "bob", {"age": 17, "status": "active"})
+        await db.put("users", "carol", {"age": 45, "status": "banned"})
+
+        adults = Query().filter(Filter.gte("age", 18) & ~Filter.eq("status", "banned"))
+        result = await db.query("users", adults.sort(SortBy.desc("age")))
+        assert [r["key"] for r in result["records"]] == ["alice"]
+
+        result = await db.query("users", Query().aggregate(Aggregation.sum("age")))
+        assert result["aggregation"] == 92
+
+        # Builders are immutable
+        limited = adults.limit(0)
+        result = await db.query("users", adults)
+        assert result["total_count"] == 1
+        assert (await db.query("users", limited))["records"] == []
+
+
+@pytest.mark.asyncio
+async def test_views():
+    """Test creating, refreshing and reading views."""
+    async with Database() as db:
+        await db.put("users", "alice", {"age": 30})
+        await db.create_view("adults", "users", Query().filter(Filter.gte("age", 18)))
+
+        await db.put("users", "bob", {"age": 40})
+        await db.refresh_view("adults")
+
+        result = await db.query_view("adults")
+        assert sorted(r["key"] for r in result["records"]) == ["alice", "bob"]
+
+
+@pytest.mark.asyncio
+async def test_query_df():
+    """Test query results as a DataFrame and an Arrow table."""
+    pytest.importorskip("pandas")
+    pa = pytest.importorskip("pyarrow")
+    async with Database() as db:
+        await db.put("users", "alice", {"age": 30, "name": "Alice"})
+        await db.put("users", "bob", {"age": 17, "name": "Bob"})
+
+        df = await db.query_df("users", Query().sort_by("age"))
+        assert list(df["_key"]) == ["bob", "alice"]
+        assert list(df["age"]) == [17, 30]
+
+        table = await db.query_df("users", Query().filter(Filter.gte("age", 18)), as_arrow=True)
+        assert table.num_rows == 1
+        assert table.schema.field("age").type == pa.int64()
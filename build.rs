@@ -0,0 +1,58 @@
+//! Build script.
+//!
+//! With the `grpc` feature, generates the tonic client and server stubs for
+//! `koru_delta.v1.KoruDelta`. The service is declared here rather than in a
+//! `.proto` file so the build does not need `protoc`; the messages live in
+//! `src/grpc.rs` and `proto/koru_delta.proto` documents the wire format.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(feature = "grpc")]
+    grpc::generate();
+}
+
+#[cfg(feature = "grpc")]
+mod grpc {
+    use tonic_build::manual::{Builder, Method, Service};
+
+    const CODEC: &str = "tonic::codec::ProstCodec";
+
+    fn unary(name: &str, route: &str, input: &str, output: &str) -> Method {
+        Method::builder()
+            .name(name)
+            .route_name(route)
+            .input_type(format!("crate::grpc::{}", input))
+            .output_type(format!("crate::grpc::{}", output))
+            .codec_path(CODEC)
+            .build()
+    }
+
+    pub fn generate() {
+        let service = Service::builder()
+            .name("KoruDelta")
+            .package("koru_delta.v1")
+            .method(unary("put", "Put", "PutRequest", "VersionedValue"))
+            .method(unary("get", "Get", "GetRequest", "VersionedValue"))
+            .method(unary(
+                "history",
+                "History",
+                "HistoryRequest",
+                "HistoryResponse",
+            ))
+            .method(unary("query", "Query", "QueryRequest", "QueryResponse"))
+            .method(
+                Method::builder()
+                    .name("subscribe")
+                    .route_name("Subscribe")
+                    .input_type("crate::grpc::SubscribeRequest")
+                    .output_type("crate::grpc::ChangeEvent")
+                    .codec_path(CODEC)
+                    .server_streaming()
+                    .build(),
+            )
+            .build();
+
+        Builder::new().compile(&[service]);
+    }
+}
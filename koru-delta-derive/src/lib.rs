@@ -0,0 +1,235 @@
+//! Derive macro for KoruDelta typed records.
+//!
+//! Use it through the `derive` feature of `koru-delta`, which re-exports
+//! [`KoruRecord`](macro@KoruRecord) next to the trait of the same name.
+//!
+//! ```ignore
+//! use koru_delta::KoruRecord;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, KoruRecord)]
+//! #[koru(namespace = "users")]
+//! struct User {
+//!     #[koru(key)]
+//!     handle: String,
+//!     age: u32,
+//! }
+//!
+//! db.put_typed(&user).await?;
+//! let adults = db
+//!     .query_typed::<User>(Query::new().filter(User::FIELDS.age.gte(18)))
+//!     .await?;
+//! let oldest = db
+//!     .query_typed::<User>(UserQuery::filter(|u| u.age.gt(30)).sort_by(|u| u.handle))
+//!     .await?;
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Error, Fields, Ident, LitStr, Token, parse_macro_input};
+
+/// Derive `koru_delta::KoruRecord` for a struct with named fields.
+///
+/// - `#[koru(namespace = "...")]` on the struct sets the namespace
+///   (default: the struct name in snake_case).
+/// - `#[koru(key)]` on a field makes it the key (default: a field named
+///   `id`). The key field must implement `Display`.
+///
+/// Also generates `<Struct>Fields` and a `FIELDS` constant with one
+/// `koru_delta::Field` per serialized field, named as serde names it
+/// (`#[serde(rename = "...")]` is honoured, `#[serde(skip)]` fields are
+/// left out), and `<Struct>Query` whose functions start a
+/// `koru_delta::TypedQuery` over the struct.
+#[proc_macro_derive(KoruRecord, attributes(koru))]
+pub fn derive_koru_record(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let vis = &input.vis;
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "KoruRecord cannot be derived for generic structs",
+        ));
+    }
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    name,
+                    "KoruRecord needs a struct with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                name,
+                "KoruRecord can only be derived for structs",
+            ));
+        }
+    };
+
+    let mut namespace = snake_case(&name.to_string());
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("koru")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("namespace") {
+                namespace = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else {
+                Err(meta.error("expected `namespace = \"...\"`"))
+            }
+        })?;
+    }
+
+    let mut key: Option<&Ident> = None;
+    let mut field_idents = Vec::new();
+    let mut field_names = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("koru")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("key") {
+                    if key.is_some() {
+                        return Err(meta.error("only one field can be the key"));
+                    }
+                    key = Some(ident);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `key`"))
+                }
+            })?;
+        }
+        if let Some(serialized) = serde_name(field)? {
+            field_idents.push(ident);
+            field_names.push(serialized);
+        }
+    }
+    let key = key
+        .or_else(|| {
+            fields
+                .iter()
+                .filter_map(|f| f.ident.as_ref())
+                .find(|i| *i == "id")
+        })
+        .ok_or_else(|| {
+            Error::new_spanned(
+                name,
+                "KoruRecord needs a `#[koru(key)]` field or a field named `id`",
+            )
+        })?;
+
+    let fields_struct = format_ident!("{}Fields", name);
+    let fields_doc = format!("Field names of [`{}`], for building filters.", name);
+    let query_struct = format_ident!("{}Query", name);
+    let query_doc = format!("Entry point for typed queries over [`{}`].", name);
+    let namespace = LitStr::new(&namespace, Span::call_site());
+
+    Ok(quote! {
+        impl ::koru_delta::KoruRecord for #name {
+            const NAMESPACE: &'static str = #namespace;
+
+            fn key(&self) -> ::std::string::String {
+                ::std::string::ToString::to_string(&self.#key)
+            }
+        }
+
+        #[doc = #fields_doc]
+        #[allow(missing_docs)]
+        #[derive(Debug, Clone, Copy)]
+        #vis struct #fields_struct {
+            #( pub #field_idents: ::koru_delta::Field<#name>, )*
+        }
+
+        impl #name {
+            /// Field names, for building filters.
+            pub const FIELDS: #fields_struct = #fields_struct {
+                #( #field_idents: ::koru_delta::Field::new(#field_names), )*
+            };
+        }
+
+        impl ::koru_delta::RecordFields for #name {
+            type Fields = #fields_struct;
+
+            const FIELDS: #fields_struct = <#name>::FIELDS;
+        }
+
+        #[doc = #query_doc]
+        #[derive(Debug, Clone, Copy)]
+        #vis struct #query_struct;
+
+        impl #query_struct {
+            /// Query matching every record.
+            pub fn all() -> ::koru_delta::TypedQuery<#name> {
+                ::koru_delta::TypedQuery::new()
+            }
+
+            /// Query with one filter condition.
+            pub fn filter(
+                filter: impl ::std::ops::FnOnce(&#fields_struct) -> ::koru_delta::Filter,
+            ) -> ::koru_delta::TypedQuery<#name> {
+                ::koru_delta::TypedQuery::new().filter(filter)
+            }
+
+            /// Query sorted ascending by a field.
+            pub fn sort_by(
+                field: impl ::std::ops::FnOnce(&#fields_struct) -> ::koru_delta::Field<#name>,
+            ) -> ::koru_delta::TypedQuery<#name> {
+                ::koru_delta::TypedQuery::new().sort_by(field)
+            }
+
+            /// Query sorted descending by a field.
+            pub fn sort_by_desc(
+                field: impl ::std::ops::FnOnce(&#fields_struct) -> ::koru_delta::Field<#name>,
+            ) -> ::koru_delta::TypedQuery<#name> {
+                ::koru_delta::TypedQuery::new().sort_by_desc(field)
+            }
+        }
+    })
+}
+
+/// Name serde gives a field, or `None` if serde skips it.
+fn serde_name(field: &syn::Field) -> syn::Result<Option<String>> {
+    let ident = field.ident.as_ref().expect("named field");
+    let mut name = ident.to_string().trim_start_matches("r#").to_string();
+    let mut skipped = false;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") && meta.input.peek(Token![=]) {
+                name = meta.value()?.parse::<LitStr>()?.value();
+            } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_serializing") {
+                skipped = true;
+            } else if meta.input.peek(Token![=]) {
+                // Other options are serde's business
+                meta.value()?.parse::<syn::Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let _nested;
+                syn::parenthesized!(_nested in meta.input);
+            }
+            Ok(())
+        })?;
+    }
+    Ok((!skipped).then_some(name))
+}
+
+/// `UserProfile` → `user_profile`.
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
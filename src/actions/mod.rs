@@ -32,6 +32,8 @@
 use chrono::{DateTime, Utc};
 use koru_lambda_core::{Canonicalizable, Distinction, DistinctionEngine};
 
+use crate::mapper::DocumentMapper;
+
 /// The universal action type for all Koru agents.
 ///
 /// This enum encompasses every possible action within the unified field.
@@ -85,12 +87,13 @@ pub enum KoruAction {
     /// Session operations - authenticated session management.
     Session(SessionAction),
     /// Subscription operations - pub/sub change notifications.
-    #[cfg(not(target_arch = "wasm32"))]
     Subscription(SubscriptionAction),
     /// Process operations - background evolutionary processes.
     Process(ProcessAction),
     /// Reconciliation operations - distributed set synchronization.
     Reconciliation(ReconciliationAction),
+    /// Actions defined outside this crate by custom agents.
+    Custom(CustomAction),
 }
 
 impl From<PulseAction> for KoruAction {
@@ -111,6 +114,12 @@ impl From<VectorAction> for KoruAction {
     }
 }
 
+impl From<CustomAction> for KoruAction {
+    fn from(action: CustomAction) -> Self {
+        KoruAction::Custom(action)
+    }
+}
+
 impl KoruAction {
     /// Get the action category as a string.
     pub fn category(&self) -> &'static str {
@@ -132,10 +141,10 @@ impl KoruAction {
             #[cfg(not(target_arch = "wasm32"))]
             KoruAction::Lifecycle(_) => "LIFECYCLE",
             KoruAction::Session(_) => "SESSION",
-            #[cfg(not(target_arch = "wasm32"))]
             KoruAction::Subscription(_) => "SUBSCRIPTION",
             KoruAction::Process(_) => "PROCESS",
             KoruAction::Reconciliation(_) => "RECONCILIATION",
+            KoruAction::Custom(_) => "CUSTOM",
         }
     }
 
@@ -161,10 +170,10 @@ impl KoruAction {
             #[cfg(not(target_arch = "wasm32"))]
             KoruAction::Lifecycle(action) => action.validate(),
             KoruAction::Session(action) => action.validate(),
-            #[cfg(not(target_arch = "wasm32"))]
             KoruAction::Subscription(action) => action.validate(),
             KoruAction::Process(action) => action.validate(),
             KoruAction::Reconciliation(action) => action.validate(),
+            KoruAction::Custom(action) => action.validate(),
         }
     }
 }
@@ -190,10 +199,10 @@ impl Canonicalizable for KoruAction {
             #[cfg(not(target_arch = "wasm32"))]
             KoruAction::Lifecycle(action) => action.to_canonical_structure(engine),
             KoruAction::Session(action) => action.to_canonical_structure(engine),
-            #[cfg(not(target_arch = "wasm32"))]
             KoruAction::Subscription(action) => action.to_canonical_structure(engine),
             KoruAction::Process(action) => action.to_canonical_structure(engine),
             KoruAction::Reconciliation(action) => action.to_canonical_structure(engine),
+            KoruAction::Custom(action) => action.to_canonical_structure(engine),
         }
     }
 }
@@ -208,10 +217,53 @@ impl KoruAction {
 
     /// Convert bytes to distinction via synthesis.
     pub fn bytes_to_distinction(bytes: &[u8], engine: &DistinctionEngine) -> Distinction {
-        bytes
-            .iter()
-            .map(|&byte| byte.to_canonical_structure(engine))
-            .fold(engine.d0().clone(), |acc, d| engine.synthesize(&acc, &d))
+        DocumentMapper::bytes_to_distinction(bytes, engine)
+    }
+
+    /// The bytes this action's canonical distinction is synthesized from.
+    ///
+    /// `bytes_to_distinction` of these bytes equals
+    /// `to_canonical_structure`, so an action can be re-synthesized from
+    /// them alone. `None` if the action cannot be serialized (it then
+    /// canonicalizes to d0).
+    pub fn canonical_bytes(&self) -> Option<Vec<u8>> {
+        match self {
+            KoruAction::Storage(a) => bincode::serialize(&StorageActionSerializable::from(a)),
+            KoruAction::Temperature(a) => {
+                bincode::serialize(&TemperatureActionSerializable::from(a))
+            }
+            KoruAction::Chronicle(a) => bincode::serialize(&ChronicleActionSerializable::from(a)),
+            KoruAction::Archive(a) => bincode::serialize(&ArchiveActionSerializable::from(a)),
+            KoruAction::Essence(a) => bincode::serialize(&EssenceActionSerializable::from(a)),
+            KoruAction::Sleep(a) => bincode::serialize(&SleepActionSerializable::from(a)),
+            KoruAction::Evolution(a) => bincode::serialize(&EvolutionActionSerializable::from(a)),
+            KoruAction::Lineage(a) => bincode::serialize(&LineageActionSerializable::from(a)),
+            KoruAction::Perspective(a) => {
+                bincode::serialize(&PerspectiveActionSerializable::from(a))
+            }
+            KoruAction::Identity(a) => bincode::serialize(&IdentityActionSerializable::from(a)),
+            KoruAction::Network(a) => bincode::serialize(&NetworkActionSerializable::from(a)),
+            KoruAction::Pulse(a) => bincode::serialize(&PulseActionSerializable::from(a)),
+            KoruAction::Workspace(a) => bincode::serialize(&WorkspaceActionSerializable::from(a)),
+            KoruAction::Vector(a) => bincode::serialize(&VectorActionSerializable::from(a)),
+            #[cfg(not(target_arch = "wasm32"))]
+            KoruAction::Lifecycle(a) => bincode::serialize(&LifecycleActionSerializable::from(a)),
+            KoruAction::Session(a) => bincode::serialize(&SessionActionSerializable::from(a)),
+            KoruAction::Subscription(a) => {
+                bincode::serialize(&SubscriptionActionSerializable::from(a))
+            }
+            KoruAction::Process(a) => bincode::serialize(&ProcessActionSerializable::from(a)),
+            KoruAction::Reconciliation(a) => {
+                bincode::serialize(&ReconciliationActionSerializable::from(a))
+            }
+            KoruAction::Custom(a) => bincode::serialize(&CustomActionSerializable::from(a)),
+        }
+        .ok()
+    }
+
+    /// A readable JSON form of the action, for logs and debugging.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(ActionSerializable::from(self)).unwrap_or(serde_json::Value::Null)
     }
 }
 
@@ -237,10 +289,10 @@ pub(crate) enum ActionSerializable {
     #[cfg(not(target_arch = "wasm32"))]
     Lifecycle(LifecycleActionSerializable),
     Session(SessionActionSerializable),
-    #[cfg(not(target_arch = "wasm32"))]
     Subscription(SubscriptionActionSerializable),
     Process(ProcessActionSerializable),
     Reconciliation(ReconciliationActionSerializable),
+    Custom(CustomActionSerializable),
 }
 
 impl From<&KoruAction> for ActionSerializable {
@@ -263,10 +315,10 @@ impl From<&KoruAction> for ActionSerializable {
             #[cfg(not(target_arch = "wasm32"))]
             KoruAction::Lifecycle(a) => ActionSerializable::Lifecycle(a.into()),
             KoruAction::Session(a) => ActionSerializable::Session(a.into()),
-            #[cfg(not(target_arch = "wasm32"))]
             KoruAction::Subscription(a) => ActionSerializable::Subscription(a.into()),
             KoruAction::Process(a) => ActionSerializable::Process(a.into()),
             KoruAction::Reconciliation(a) => ActionSerializable::Reconciliation(a.into()),
+            KoruAction::Custom(a) => ActionSerializable::Custom(a.into()),
         }
     }
 }
@@ -327,11 +379,10 @@ impl Canonicalizable for StorageAction {
 }
 
 /// Convert bytes to distinction via synthesis.
+///
+/// Delegates to [`DocumentMapper`], which canonicalizes large inputs in parallel.
 fn bytes_to_distinction(bytes: &[u8], engine: &DistinctionEngine) -> Distinction {
-    bytes
-        .iter()
-        .map(|&byte| byte.to_canonical_structure(engine))
-        .fold(engine.d0().clone(), |acc, d| engine.synthesize(&acc, &d))
+    DocumentMapper::bytes_to_distinction(bytes, engine)
 }
 
 /// Serializable version of StorageAction.
@@ -2277,7 +2328,7 @@ impl Canonicalizable for SessionAction {
 }
 
 // ============================================================================
-// SUBSCRIPTION ACTIONS (Non-WASM only)
+// SUBSCRIPTION ACTIONS
 // ============================================================================
 
 /// Actions for subscription management agent.
@@ -2286,7 +2337,6 @@ impl Canonicalizable for SessionAction {
 /// - Each subscription operation synthesizes a new distinction
 /// - Subscriptions are content-addressed by their action history
 /// - All subscription state changes are causal distinctions
-#[cfg(not(target_arch = "wasm32"))]
 #[derive(Debug, Clone, PartialEq)]
 pub enum SubscriptionAction {
     /// Subscribe to changes.
@@ -2321,7 +2371,6 @@ pub enum SubscriptionAction {
 }
 
 /// Serializable version of SubscriptionAction.
-#[cfg(not(target_arch = "wasm32"))]
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub(crate) enum SubscriptionActionSerializable {
     Subscribe {
@@ -2343,7 +2392,6 @@ pub(crate) enum SubscriptionActionSerializable {
     },
 }
 
-#[cfg(not(target_arch = "wasm32"))]
 impl From<&SubscriptionAction> for SubscriptionActionSerializable {
     fn from(action: &SubscriptionAction) -> Self {
         match action {
@@ -2379,7 +2427,6 @@ impl From<&SubscriptionAction> for SubscriptionActionSerializable {
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
 impl SubscriptionAction {
     /// Validate the subscription action.
     pub fn validate(&self) -> Result<(), String> {
@@ -2420,7 +2467,6 @@ impl SubscriptionAction {
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
 impl Canonicalizable for SubscriptionAction {
     fn to_canonical_structure(&self, engine: &DistinctionEngine) -> Distinction {
         let serializable = SubscriptionActionSerializable::from(self);
@@ -2480,7 +2526,7 @@ pub enum ProcessAction {
 }
 
 /// Type of evolutionary process.
-#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum ProcessType {
     /// Consolidation process - rhythmic memory movement.
     Consolidation,
@@ -2815,6 +2861,113 @@ impl Canonicalizable for ReconciliationAction {
     }
 }
 
+// ============================================================================
+// CUSTOM ACTIONS
+// ============================================================================
+
+/// An action defined by a third-party agent.
+///
+/// Custom actions are identified by a category (e.g. `"ROBOTICS"`) and carry
+/// a JSON payload. They canonicalize like built-in actions: the same
+/// category and payload always synthesize the same distinction, so they
+/// can be logged with [`KoruAction::to_bytes`] and replayed into the field.
+///
+/// Typed actions implementing [`ExternalAction`] convert to and from custom
+/// actions with [`CustomAction::encode`] and [`CustomAction::decode`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CustomAction {
+    /// Category of the action, chosen by the agent that defines it.
+    pub category: String,
+    /// Action data.
+    pub payload: serde_json::Value,
+}
+
+/// A typed action of a third-party agent.
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(Serialize, Deserialize)]
+/// enum RobotAction {
+///     Move { x: f64, y: f64 },
+///     Grip,
+/// }
+///
+/// impl ExternalAction for RobotAction {
+///     const CATEGORY: &'static str = "ROBOT";
+/// }
+///
+/// let action = KoruAction::from(CustomAction::encode(&RobotAction::Grip)?);
+/// orchestrator.synthesize_action(action);
+/// ```
+pub trait ExternalAction: serde::Serialize + serde::de::DeserializeOwned {
+    /// Category under which the actions are synthesized.
+    const CATEGORY: &'static str;
+}
+
+impl CustomAction {
+    /// Create a custom action.
+    pub fn new(category: impl Into<String>, payload: serde_json::Value) -> Self {
+        Self {
+            category: category.into(),
+            payload,
+        }
+    }
+
+    /// Wrap a typed action.
+    pub fn encode<A: ExternalAction>(action: &A) -> Result<Self, serde_json::Error> {
+        Ok(Self::new(A::CATEGORY, serde_json::to_value(action)?))
+    }
+
+    /// Recover a typed action.
+    ///
+    /// Returns `None` if the action belongs to another category or the
+    /// payload does not decode as `A`.
+    pub fn decode<A: ExternalAction>(&self) -> Option<A> {
+        if self.category != A::CATEGORY {
+            return None;
+        }
+        serde_json::from_value(self.payload.clone()).ok()
+    }
+
+    /// Validate the custom action.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.category.is_empty() {
+            return Err("CustomAction: category is empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Serializable version of CustomAction.
+///
+/// The payload is kept as JSON text so the form also deserializes with
+/// non-self-describing formats.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct CustomActionSerializable {
+    category: String,
+    payload_json: String,
+}
+
+impl From<&CustomAction> for CustomActionSerializable {
+    fn from(action: &CustomAction) -> Self {
+        Self {
+            category: action.category.clone(),
+            payload_json: action.payload.to_string(),
+        }
+    }
+}
+
+impl Canonicalizable for CustomAction {
+    fn to_canonical_structure(&self, engine: &DistinctionEngine) -> Distinction {
+        let serializable = CustomActionSerializable::from(self);
+        match bincode::serialize(&serializable) {
+            Ok(bytes) => bytes_to_distinction(&bytes, engine),
+            Err(_) => engine.d0().clone(),
+        }
+    }
+}
+
 // ============================================================================
 // WORKSPACE SEARCH OPTIONS
 // ============================================================================
@@ -3073,6 +3226,13 @@ pub enum LineageQueryAction {
         /// Maximum number of results.
         k: usize,
     },
+    /// Get the distinctions within `k` causal hops of one distinction.
+    GetNeighborhood {
+        /// Center distinction key.
+        key: String,
+        /// Maximum number of hops.
+        k: usize,
+    },
 }
 
 /// Serializable version of LineageQueryAction.
@@ -3081,6 +3241,7 @@ pub(crate) enum LineageQueryActionSerializable {
     QueryConnected { key_a: String, key_b: String },
     GetConnectionPath { key_a: String, key_b: String },
     GetHighlyConnected { k: usize },
+    GetNeighborhood { key: String, k: usize },
 }
 
 impl From<&LineageQueryAction> for LineageQueryActionSerializable {
@@ -3101,6 +3262,12 @@ impl From<&LineageQueryAction> for LineageQueryActionSerializable {
             LineageQueryAction::GetHighlyConnected { k } => {
                 LineageQueryActionSerializable::GetHighlyConnected { k: *k }
             }
+            LineageQueryAction::GetNeighborhood { key, k } => {
+                LineageQueryActionSerializable::GetNeighborhood {
+                    key: key.clone(),
+                    k: *k,
+                }
+            }
         }
     }
 }
@@ -3202,6 +3369,74 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_custom_action_round_trip() {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        enum RobotAction {
+            Move { x: i32, y: i32 },
+        }
+
+        impl ExternalAction for RobotAction {
+            const CATEGORY: &'static str = "ROBOT";
+        }
+
+        let engine = DistinctionEngine::new();
+        let typed = RobotAction::Move { x: 1, y: 2 };
+        let custom = CustomAction::encode(&typed).unwrap();
+        let action = KoruAction::from(custom.clone());
+
+        assert_eq!(action.category(), "CUSTOM");
+        assert!(action.validate().is_ok());
+        assert!(!action.to_bytes().unwrap().is_empty());
+        assert_eq!(custom.decode::<RobotAction>(), Some(typed));
+
+        // Canonicalization depends only on category and payload
+        let same = KoruAction::from(CustomAction::new("ROBOT", custom.payload.clone()));
+        let other = KoruAction::from(CustomAction::new("DRONE", custom.payload.clone()));
+        let id = action.to_canonical_structure(&engine).id().to_string();
+        assert_eq!(same.to_canonical_structure(&engine).id(), id);
+        assert_ne!(other.to_canonical_structure(&engine).id(), id);
+
+        // The serialized form decodes back for replay
+        let bytes = bincode::serialize(&CustomActionSerializable::from(&custom)).unwrap();
+        let decoded: CustomActionSerializable = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(decoded.category, "ROBOT");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&decoded.payload_json).unwrap(),
+            custom.payload
+        );
+
+        assert!(
+            CustomAction::new("", serde_json::Value::Null)
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_canonical_bytes_match_canonical_structure() {
+        let engine = DistinctionEngine::new();
+        let actions = [
+            KoruAction::Storage(StorageAction::Store {
+                namespace: "users".to_string(),
+                key: "alice".to_string(),
+                value_json: serde_json::json!({"name": "Alice"}),
+            }),
+            KoruAction::from(PulseAction::TriggerPulse {
+                phase: "Input".to_string(),
+            }),
+            KoruAction::from(CustomAction::new("ROBOT", serde_json::json!([1, 2]))),
+        ];
+
+        for action in actions {
+            let bytes = action.canonical_bytes().unwrap();
+            assert_eq!(
+                KoruAction::bytes_to_distinction(&bytes, &engine).id(),
+                action.to_canonical_structure(&engine).id()
+            );
+        }
+    }
+
     #[test]
     fn test_action_to_bytes() {
         let action = KoruAction::Storage(StorageAction::Store {
@@ -6,11 +6,14 @@
 #[allow(unused_imports)]
 use chrono::{DateTime, Duration, Utc};
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 
 use crate::auth::identity::sign_message;
+use crate::auth::multisig::{MultisigPolicy, verify_multisig_grant};
 use crate::auth::types::{
     AuthError, Capability, CapabilityRef, Identity, Permission, ResourcePattern, Revocation,
 };
+use crate::query::Filter;
 
 /// Default capability TTL: None (no expiration).
 #[allow(dead_code)]
@@ -34,36 +37,273 @@ pub fn create_capability(
     resource_pattern: ResourcePattern,
     permission: Permission,
     expires_at: Option<DateTime<Utc>>,
+) -> Result<Capability, AuthError> {
+    create_delegable_capability(
+        granter_identity,
+        granter_secret_key,
+        grantee,
+        resource_pattern,
+        permission,
+        expires_at,
+        0,
+    )
+}
+
+/// Create a capability grant that the grantee may re-grant.
+///
+/// `delegation_depth` is how many levels of re-granting are allowed below
+/// this capability: 1 lets the grantee delegate once, but their delegates
+/// cannot delegate further.
+pub fn create_delegable_capability(
+    granter_identity: &Identity,
+    granter_secret_key: &[u8],
+    grantee: &str,
+    resource_pattern: ResourcePattern,
+    permission: Permission,
+    expires_at: Option<DateTime<Utc>>,
+    delegation_depth: u8,
 ) -> Result<Capability, AuthError> {
     let id = generate_capability_id(granter_identity, grantee, &resource_pattern);
-    let created_at = Utc::now();
 
-    // Create unsigned capability for signing
-    let capability_to_sign = Capability {
-        id: id.clone(),
+    let mut capability = Capability {
+        id,
         granter: granter_identity.public_key.clone(),
         grantee: grantee.to_string(),
-        resource_pattern: resource_pattern.clone(),
+        resource_pattern,
         permission,
-        created_at,
+        created_at: Utc::now(),
         expires_at,
         signature: String::new(),
+        parent: None,
+        delegation_depth,
+        row_filter: None,
+        proposal: None,
     };
 
-    // Sign
-    let message = create_capability_signature_message(&capability_to_sign);
-    let signature = sign_message(granter_secret_key, &message)?;
+    sign_capability(&mut capability, granter_secret_key)?;
+    Ok(capability)
+}
 
-    Ok(Capability {
-        id,
-        granter: granter_identity.public_key.clone(),
+/// Create a capability covering only records whose value matches `row_filter`.
+///
+/// [`ROW_FILTER_IDENTITY`](crate::auth::ROW_FILTER_IDENTITY) in filter values
+/// stands for the grantee, e.g. `Filter::eq("owner", "$identity")`.
+pub fn create_filtered_capability(
+    granter_identity: &Identity,
+    granter_secret_key: &[u8],
+    grantee: &str,
+    resource_pattern: ResourcePattern,
+    permission: Permission,
+    row_filter: Filter,
+    expires_at: Option<DateTime<Utc>>,
+) -> Result<Capability, AuthError> {
+    let mut capability = create_delegable_capability(
+        granter_identity,
+        granter_secret_key,
+        grantee,
+        resource_pattern,
+        permission,
+        expires_at,
+        0,
+    )?;
+    capability.row_filter = Some(row_filter);
+
+    sign_capability(&mut capability, granter_secret_key)?;
+    Ok(capability)
+}
+
+/// Re-grant a subset of a capability to another identity.
+///
+/// The delegator must be the grantee of `parent`, and `parent` must allow
+/// further delegation. The new capability is attenuated: its pattern must be
+/// within the parent's, its permission included by the parent's, its expiry
+/// no later than the parent's (it is clamped if needed), and its remaining
+/// delegation depth one less than the parent's.
+///
+/// # Returns
+/// The signed child capability, linked to `parent` by ID.
+pub fn delegate_capability(
+    parent: &Capability,
+    delegator_identity: &Identity,
+    delegator_secret_key: &[u8],
+    grantee: &str,
+    resource_pattern: ResourcePattern,
+    permission: Permission,
+    expires_at: Option<DateTime<Utc>>,
+) -> Result<Capability, AuthError> {
+    if parent.grantee != delegator_identity.public_key {
+        return Err(AuthError::InvalidDelegation(
+            "delegator is not the grantee of the parent capability".to_string(),
+        ));
+    }
+    if parent.is_expired() {
+        return Err(AuthError::InvalidDelegation(
+            "parent capability has expired".to_string(),
+        ));
+    }
+
+    let expires_at = match (expires_at, parent.expires_at) {
+        (Some(child), Some(limit)) => Some(child.min(limit)),
+        (None, limit) => limit,
+        (child, None) => child,
+    };
+
+    let mut capability = Capability {
+        id: generate_capability_id(delegator_identity, grantee, &resource_pattern),
+        granter: delegator_identity.public_key.clone(),
         grantee: grantee.to_string(),
         resource_pattern,
         permission,
-        created_at,
+        created_at: Utc::now(),
         expires_at,
-        signature: bs58::encode(&signature).into_string(),
-    })
+        signature: String::new(),
+        parent: Some(parent.id.clone()),
+        delegation_depth: parent.delegation_depth.saturating_sub(1),
+        // Bound to the delegator so delegates see the same rows, not their own
+        row_filter: parent.bound_row_filter(),
+        proposal: None,
+    };
+
+    verify_delegation_link(parent, &capability)?;
+    sign_capability(&mut capability, delegator_secret_key)?;
+    Ok(capability)
+}
+
+/// Extend (or shorten) a capability's expiry.
+///
+/// The capability keeps its ID, so anything delegated from it stays linked.
+/// It is re-signed with `granter_secret_key`, which must belong to the
+/// original granter.
+///
+/// # Returns
+/// The re-signed capability.
+pub fn renew_capability(
+    capability: &Capability,
+    granter_secret_key: &[u8],
+    expires_at: Option<DateTime<Utc>>,
+) -> Result<Capability, AuthError> {
+    let mut renewed = capability.clone();
+    renewed.expires_at = expires_at;
+
+    sign_capability(&mut renewed, granter_secret_key)?;
+    if !renewed.verify_signature()? {
+        return Err(AuthError::InvalidSignature);
+    }
+    Ok(renewed)
+}
+
+/// Check that `child` is a valid attenuation of `parent`.
+///
+/// Does not check signatures, revocation, or expiry; see
+/// [`verify_delegation_chain`] for the full check.
+pub fn verify_delegation_link(parent: &Capability, child: &Capability) -> Result<(), AuthError> {
+    let fail = |reason: &str| Err(AuthError::InvalidDelegation(reason.to_string()));
+
+    if child.parent.as_deref() != Some(parent.id.as_str()) {
+        return fail("capability does not reference its parent");
+    }
+    if child.granter != parent.grantee {
+        return fail("granter is not the grantee of the parent capability");
+    }
+    if parent.delegation_depth == 0 {
+        return fail("parent capability does not allow delegation");
+    }
+    if child.delegation_depth >= parent.delegation_depth {
+        return fail("delegation depth must decrease along the chain");
+    }
+    if !parent.permission.includes(child.permission) {
+        return fail("permission exceeds the parent capability");
+    }
+    if !child.resource_pattern.is_within(&parent.resource_pattern) {
+        return fail("resource pattern exceeds the parent capability");
+    }
+    if let Some(limit) = parent.expires_at
+        && child.expires_at.is_none_or(|expiry| expiry > limit)
+    {
+        return fail("expiry exceeds the parent capability");
+    }
+    if parent.row_filter.is_some() && child.row_filter != parent.bound_row_filter() {
+        return fail("row filter must match the parent capability");
+    }
+
+    Ok(())
+}
+
+/// Verify a capability and every ancestor it was delegated from.
+///
+/// Walks the `parent` links with `lookup`, checking each signature, each
+/// attenuation step, and that no link is revoked or expired.
+///
+/// # Returns
+/// The chain from `capability` up to its root grant.
+pub fn verify_delegation_chain(
+    capability: &Capability,
+    lookup: impl Fn(&str) -> Option<Capability>,
+    revocations: &[Revocation],
+) -> Result<Vec<Capability>, AuthError> {
+    let mut chain = vec![capability.clone()];
+
+    loop {
+        let current = chain.last().expect("chain is never empty");
+
+        if !current.verify_signature()? {
+            return Err(AuthError::InvalidSignature);
+        }
+        if is_revoked(current, revocations) {
+            return Err(AuthError::CapabilityRevoked);
+        }
+        if current.is_expired() {
+            return Err(AuthError::InvalidDelegation(format!(
+                "capability {} has expired",
+                current.id
+            )));
+        }
+
+        let Some(parent_id) = current.parent.as_deref() else {
+            return Ok(chain);
+        };
+        let parent = lookup(parent_id)
+            .ok_or_else(|| AuthError::CapabilityNotFound(parent_id.to_string()))?;
+        verify_delegation_link(&parent, current)?;
+        chain.push(parent);
+    }
+}
+
+/// Keep the capabilities whose delegation chain and multisig approvals verify.
+///
+/// `policy` looks up a granter's multisig policy. Every grant in a chain made
+/// by an identity with a policy must carry a proposal that met the threshold
+/// (see [`verify_multisig_grant`]); the identity's key alone is not enough.
+pub(crate) fn with_valid_chains(
+    capabilities: Vec<Capability>,
+    lookup: impl Fn(&str) -> Option<Capability>,
+    policy: impl Fn(&str) -> Option<MultisigPolicy>,
+    revocations: &[Revocation],
+) -> Vec<Capability> {
+    capabilities
+        .into_iter()
+        .filter(|cap| {
+            let chain = if cap.is_delegated() {
+                match verify_delegation_chain(cap, &lookup, revocations) {
+                    Ok(chain) => chain,
+                    Err(_) => return false,
+                }
+            } else {
+                vec![cap.clone()]
+            };
+            chain.iter().all(|link| {
+                policy(&link.granter)
+                    .is_none_or(|policy| verify_multisig_grant(&policy, link).is_ok())
+            })
+        })
+        .collect()
+}
+
+/// Sign a capability in place with the granter's secret key.
+fn sign_capability(capability: &mut Capability, secret_key: &[u8]) -> Result<(), AuthError> {
+    let signature = sign_message(secret_key, &capability.signature_message())?;
+    capability.signature = bs58::encode(&signature).into_string();
+    Ok(())
 }
 
 /// Generate a unique ID for a capability.
@@ -83,20 +323,6 @@ fn generate_capability_id(
     bs58::encode(&hash[..16]).into_string() // First 16 bytes = 128 bits
 }
 
-/// Create the message to sign for a capability.
-fn create_capability_signature_message(cap: &Capability) -> Vec<u8> {
-    format!(
-        "capability_grant:{}/{}->{}/{}/{}/{}",
-        cap.id,
-        cap.granter,
-        cap.grantee,
-        cap.resource_pattern,
-        cap.permission.as_str(),
-        cap.created_at.timestamp()
-    )
-    .into_bytes()
-}
-
 /// Create a revocation for a capability.
 ///
 /// # Arguments
@@ -158,6 +384,13 @@ pub fn capability_storage_key_by_id(capability_id: &str) -> String {
     format!("capability:{}", capability_id)
 }
 
+/// Get the storage key for an archived capability.
+///
+/// Expired grants are moved here by the capability expiry process.
+pub fn archived_capability_storage_key(capability_id: &str) -> String {
+    format!("archived_capability:{}", capability_id)
+}
+
 /// Get the storage key for a revocation.
 ///
 /// Used to generate canonical storage keys for revocation records.
@@ -167,6 +400,12 @@ pub fn revocation_storage_key(capability_id: &str) -> String {
 
 /// Check authorization for a resource.
 ///
+/// Delegated capabilities and grants from multisig identities are trusted as
+/// given; callers should check them with [`verify_delegation_chain`] and
+/// [`verify_multisig_grant`] first.
+/// Capabilities with a row filter only cover some records, so they are
+/// ignored here; see [`row_access`].
+///
 /// # Arguments
 /// * `identity_key` - The identity attempting access
 /// * `namespace` - The resource namespace
@@ -185,35 +424,101 @@ pub fn authorize(
     capabilities: &[Capability],
     revocations: &[Revocation],
 ) -> Result<CapabilityRef, AuthError> {
-    // Find matching capabilities
-    for cap in capabilities {
-        // Skip if not for this identity
-        if cap.grantee != identity_key {
-            continue;
-        }
+    capabilities
+        .iter()
+        .filter(|cap| cap.row_filter.is_none())
+        .find(|cap| {
+            grants(
+                cap,
+                identity_key,
+                namespace,
+                key,
+                required_permission,
+                revocations,
+            )
+        })
+        .map(build_capability_ref)
+        .ok_or(AuthError::Unauthorized)
+}
 
-        // Skip if revoked
-        if is_revoked(cap, revocations) {
-            continue;
-        }
+/// Row-level access an identity has to a resource.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowAccess {
+    /// Every record (an unfiltered capability applies)
+    All,
+    /// Only records matching at least one of these filters
+    Rows(Vec<Filter>),
+    /// No access
+    Denied,
+}
 
-        // Skip if expired
-        if cap.is_expired() {
-            continue;
+impl RowAccess {
+    /// Whether a record with this value is accessible.
+    pub fn allows(&self, value: &serde_json::Value) -> bool {
+        match self {
+            RowAccess::All => true,
+            RowAccess::Rows(filters) => filters.iter().any(|f| f.matches_value(value)),
+            RowAccess::Denied => false,
         }
+    }
 
-        // Skip if permission insufficient
-        if !cap.permission.includes(required_permission) {
+    /// Whether no record is accessible.
+    pub fn is_denied(&self) -> bool {
+        matches!(self, RowAccess::Denied)
+    }
+}
+
+/// Work out row-level access to a resource.
+///
+/// Like [`authorize`], but capabilities with a row filter contribute their
+/// filter (bound to the grantee) instead of being skipped.
+pub fn row_access(
+    identity_key: &str,
+    namespace: &str,
+    key: &str,
+    required_permission: Permission,
+    capabilities: &[Capability],
+    revocations: &[Revocation],
+) -> RowAccess {
+    let mut filters = Vec::new();
+    for cap in capabilities {
+        if !grants(
+            cap,
+            identity_key,
+            namespace,
+            key,
+            required_permission,
+            revocations,
+        ) {
             continue;
         }
-
-        // Check resource pattern
-        if cap.resource_pattern.matches(namespace, key) {
-            return Ok(build_capability_ref(cap));
+        match cap.bound_row_filter() {
+            Some(filter) => filters.push(filter),
+            None => return RowAccess::All,
         }
     }
 
-    Err(AuthError::Unauthorized)
+    if filters.is_empty() {
+        RowAccess::Denied
+    } else {
+        RowAccess::Rows(filters)
+    }
+}
+
+/// Whether a capability grants `required_permission` on a resource.
+fn grants(
+    cap: &Capability,
+    identity_key: &str,
+    namespace: &str,
+    key: &str,
+    required_permission: Permission,
+    revocations: &[Revocation],
+) -> bool {
+    cap.grantee == identity_key
+        && !is_revoked(cap, revocations)
+        && !cap.is_expired()
+        && cap.permission.includes(required_permission)
+        && cap.resource_pattern.matches(namespace, key)
 }
 
 /// Check if an identity has a specific permission on a resource.
@@ -242,6 +547,8 @@ pub struct CapabilityManager {
     capabilities: Vec<Capability>,
     /// Cached revocations
     revocations: Vec<Revocation>,
+    /// Cached multisig policies by identity
+    policies: HashMap<String, MultisigPolicy>,
 }
 
 impl CapabilityManager {
@@ -250,6 +557,7 @@ impl CapabilityManager {
         Self {
             capabilities: Vec::new(),
             revocations: Vec::new(),
+            policies: HashMap::new(),
         }
     }
 
@@ -259,8 +567,9 @@ impl CapabilityManager {
         self.revocations = revocations;
     }
 
-    /// Add a capability.
+    /// Add a capability, replacing any cached copy with the same ID.
     pub fn add_capability(&mut self, capability: Capability) {
+        self.capabilities.retain(|c| c.id != capability.id);
         self.capabilities.push(capability);
     }
 
@@ -269,6 +578,11 @@ impl CapabilityManager {
         self.revocations.push(revocation);
     }
 
+    /// Add a multisig policy, replacing the identity's previous one.
+    pub fn add_multisig_policy(&mut self, policy: MultisigPolicy) {
+        self.policies.insert(policy.identity_key.clone(), policy);
+    }
+
     /// Authorize access to a resource.
     pub fn authorize(
         &self,
@@ -282,7 +596,7 @@ impl CapabilityManager {
             namespace,
             key,
             required_permission,
-            &self.capabilities,
+            &self.chain_valid_capabilities(identity_key),
             &self.revocations,
         )
     }
@@ -300,7 +614,23 @@ impl CapabilityManager {
             namespace,
             key,
             permission,
-            &self.capabilities,
+            &self.chain_valid_capabilities(identity_key),
+            &self.revocations,
+        )
+    }
+
+    /// Capabilities held by an identity whose delegation chains verify.
+    fn chain_valid_capabilities(&self, identity_key: &str) -> Vec<Capability> {
+        let held = self
+            .capabilities
+            .iter()
+            .filter(|c| c.grantee == identity_key)
+            .cloned()
+            .collect();
+        with_valid_chains(
+            held,
+            |id| self.capabilities.iter().find(|c| c.id == id).cloned(),
+            |granter| self.policies.get(granter).cloned(),
             &self.revocations,
         )
     }
@@ -627,4 +957,218 @@ mod tests {
             Err(AuthError::Unauthorized)
         ));
     }
+
+    #[test]
+    fn test_resource_pattern_is_within() {
+        let ns = ResourcePattern::Namespace("users".to_string());
+        let alice = ResourcePattern::Wildcard {
+            prefix: "users:alice:".to_string(),
+        };
+        let profile = ResourcePattern::Exact("users:alice:profile".to_string());
+
+        assert!(alice.is_within(&ns));
+        assert!(profile.is_within(&alice));
+        assert!(profile.is_within(&ns));
+        assert!(ns.is_within(&ns));
+        assert!(!ns.is_within(&alice));
+        assert!(!alice.is_within(&profile));
+        assert!(!ResourcePattern::Namespace("users2".to_string()).is_within(&ns));
+    }
+
+    #[test]
+    fn test_delegation_attenuates() {
+        let (root, root_key) = create_test_granter();
+        let (lead, lead_key) = create_test_granter();
+        let member = create_test_grantee();
+        let expiry = Utc::now() + Duration::days(1);
+
+        let team = create_delegable_capability(
+            &root,
+            &root_key,
+            &lead.public_key,
+            ResourcePattern::Namespace("team".to_string()),
+            Permission::Write,
+            Some(expiry),
+            1,
+        )
+        .unwrap();
+
+        let delegated = delegate_capability(
+            &team,
+            &lead,
+            &lead_key,
+            &member,
+            ResourcePattern::Wildcard {
+                prefix: "team:docs:".to_string(),
+            },
+            Permission::Read,
+            None,
+        )
+        .unwrap();
+        assert_eq!(delegated.parent.as_deref(), Some(team.id.as_str()));
+        assert_eq!(delegated.delegation_depth, 0);
+        assert_eq!(delegated.expires_at, Some(expiry));
+        assert!(delegated.verify_signature().unwrap());
+
+        // Cannot widen permission or scope
+        for (pattern, permission) in [
+            (
+                ResourcePattern::Namespace("team".to_string()),
+                Permission::Admin,
+            ),
+            (
+                ResourcePattern::Namespace("other".to_string()),
+                Permission::Read,
+            ),
+        ] {
+            assert!(matches!(
+                delegate_capability(&team, &lead, &lead_key, &member, pattern, permission, None),
+                Err(AuthError::InvalidDelegation(_))
+            ));
+        }
+
+        // Only the grantee can delegate, and only while depth remains
+        assert!(
+            delegate_capability(
+                &team,
+                &root,
+                &root_key,
+                &member,
+                ResourcePattern::Namespace("team".to_string()),
+                Permission::Read,
+                None,
+            )
+            .is_err()
+        );
+        let (member_identity, member_key) = create_test_granter();
+        let mut exhausted = delegated.clone();
+        exhausted.grantee = member_identity.public_key.clone();
+        assert!(
+            delegate_capability(
+                &exhausted,
+                &member_identity,
+                &member_key,
+                &lead.public_key,
+                ResourcePattern::Exact("team:docs:a".to_string()),
+                Permission::Read,
+                None,
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_verify_delegation_chain() {
+        let (root, root_key) = create_test_granter();
+        let (lead, lead_key) = create_test_granter();
+        let member = create_test_grantee();
+
+        let team = create_delegable_capability(
+            &root,
+            &root_key,
+            &lead.public_key,
+            ResourcePattern::Namespace("team".to_string()),
+            Permission::Write,
+            None,
+            2,
+        )
+        .unwrap();
+        let delegated = delegate_capability(
+            &team,
+            &lead,
+            &lead_key,
+            &member,
+            ResourcePattern::Namespace("team".to_string()),
+            Permission::Read,
+            None,
+        )
+        .unwrap();
+
+        let lookup = |id: &str| (id == team.id).then(|| team.clone());
+        let chain = verify_delegation_chain(&delegated, lookup, &[]).unwrap();
+        assert_eq!(chain.len(), 2);
+        assert_eq!(chain[1].id, team.id);
+
+        // Revoking the parent breaks the chain
+        let revocation = create_revocation(&team, &root_key, None).unwrap();
+        assert!(matches!(
+            verify_delegation_chain(&delegated, lookup, &[revocation]),
+            Err(AuthError::CapabilityRevoked)
+        ));
+
+        // Missing parent
+        assert!(matches!(
+            verify_delegation_chain(&delegated, |_| None, &[]),
+            Err(AuthError::CapabilityNotFound(_))
+        ));
+
+        // Tampering with an attenuated field invalidates the signature
+        let mut forged = delegated.clone();
+        forged.permission = Permission::Write;
+        assert!(matches!(
+            verify_delegation_chain(&forged, lookup, &[]),
+            Err(AuthError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn test_filtered_capability_delegation() {
+        use crate::query::Filter;
+
+        let granter = mine_identity_sync(IdentityUserData::default(), 2);
+        let bob = mine_identity_sync(IdentityUserData::default(), 2);
+        let carol = mine_identity_sync(IdentityUserData::default(), 2);
+
+        let mut parent = create_filtered_capability(
+            &granter.identity,
+            &granter.secret_key,
+            &bob.identity.public_key,
+            ResourcePattern::Namespace("orders".to_string()),
+            Permission::Read,
+            Filter::eq("owner", crate::auth::ROW_FILTER_IDENTITY),
+            None,
+        )
+        .unwrap();
+        assert!(parent.verify_signature().unwrap());
+        let owned_by_bob = serde_json::json!({ "owner": bob.identity.public_key });
+        let access = row_access(
+            &bob.identity.public_key,
+            "orders",
+            "o1",
+            Permission::Read,
+            std::slice::from_ref(&parent),
+            &[],
+        );
+        assert!(access.allows(&owned_by_bob));
+        assert!(!access.allows(&serde_json::json!({ "owner": "carol" })));
+
+        // The filter is signed
+        let mut tampered = parent.clone();
+        tampered.row_filter = None;
+        assert!(!tampered.verify_signature().unwrap());
+
+        // Delegates see the delegator's rows, not their own
+        parent.delegation_depth = 1;
+        sign_capability(&mut parent, &granter.secret_key).unwrap();
+        let child = delegate_capability(
+            &parent,
+            &bob.identity,
+            &bob.secret_key,
+            &carol.identity.public_key,
+            ResourcePattern::Namespace("orders".to_string()),
+            Permission::Read,
+            None,
+        )
+        .unwrap();
+        assert!(
+            child
+                .bound_row_filter()
+                .unwrap()
+                .matches_value(&owned_by_bob)
+        );
+
+        let mut widened = child.clone();
+        widened.row_filter = None;
+        assert!(verify_delegation_link(&parent, &widened).is_err());
+    }
 }
@@ -0,0 +1,683 @@
+//! Capability-enforced database access.
+//!
+//! [`AuthenticatedDelta`] wraps a database with an authenticated session.
+//! Every operation re-validates the session and authorizes the target key
+//! against the identity's stored capabilities, so revocations and expiries
+//! take effect immediately.
+//!
+//! | Operation | Required permission |
+//! |-----------|---------------------|
+//! | `get`, `get_at`, `history`, `contains`, `list_keys`, `query` | Read |
+//! | `put`, `delete` | Write |
+//!
+//! Capabilities with a row filter only cover records whose value matches it:
+//! reads of other records fail or are left out, and writes must keep the
+//! record matching.
+//!
+//! Given the identity's secret key with
+//! [`with_secret_key`](AuthenticatedDelta::with_secret_key), reads also open
+//! values sealed to the identity (see [`SealedValue`]), so encrypted
+//! namespaces read like plain ones. That is meant for a process that holds
+//! the key anyway, like an embedded app. Without it, reads return the sealed
+//! form, which remote clients open themselves with [`SealedValue::open`] so
+//! their key never reaches the server.
+
+use super::capability::RowAccess;
+use super::sealed::SealedValue;
+use super::types::Permission;
+use crate::core::KoruDeltaGeneric;
+use crate::error::{DeltaError, DeltaResult};
+use crate::query::{Query, QueryExecutor, QueryResult};
+use crate::runtime::Runtime;
+use crate::types::{HistoryEntry, VersionedValue};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+
+/// A database handle acting as an authenticated identity.
+///
+/// Created with [`KoruDelta::as_identity`](crate::KoruDelta::as_identity).
+/// Operations fail with [`DeltaError::Unauthorized`] when the session is no
+/// longer valid or the identity lacks a capability for the key.
+#[derive(Clone)]
+pub struct AuthenticatedDelta<R: Runtime> {
+    db: KoruDeltaGeneric<R>,
+    session_id: String,
+    identity_key: String,
+    secret_key: Option<Arc<Vec<u8>>>,
+}
+
+impl<R: Runtime> std::fmt::Debug for AuthenticatedDelta<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthenticatedDelta")
+            .field("identity_key", &self.identity_key)
+            .finish()
+    }
+}
+
+impl<R: Runtime> AuthenticatedDelta<R> {
+    pub(crate) fn new(db: KoruDeltaGeneric<R>, session_id: String, identity_key: String) -> Self {
+        Self {
+            db,
+            session_id,
+            identity_key,
+            secret_key: None,
+        }
+    }
+
+    /// Decrypt sealed values on read with the identity's secret key.
+    ///
+    /// Only for processes that already hold the key; a server should not ask
+    /// clients for it, but return sealed values for them to open locally.
+    /// The key never leaves the handle. Fails with `Unauthorized` if it does
+    /// not belong to the session's identity.
+    pub fn with_secret_key(mut self, secret_key: &[u8]) -> DeltaResult<Self> {
+        let probe = format!("koru-delta:secret-key-check:{}", self.session_id);
+        let owns_key = super::sign_message(secret_key, probe.as_bytes())
+            .and_then(|sig| super::verify_signature(&self.identity_key, probe.as_bytes(), &sig))
+            .unwrap_or(false);
+        if !owns_key {
+            return Err(DeltaError::Unauthorized {
+                reason: format!("secret key does not belong to {}", self.identity_key),
+            });
+        }
+
+        self.secret_key = Some(Arc::new(secret_key.to_vec()));
+        Ok(self)
+    }
+
+    /// Public key of the identity this handle acts as.
+    pub fn identity_key(&self) -> &str {
+        &self.identity_key
+    }
+
+    /// Session the handle was created from.
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Check that the session is live and grants `permission` on the key.
+    ///
+    /// Only capabilities covering every record count; row-filtered grants are
+    /// checked per value by the individual operations.
+    pub fn authorize(&self, namespace: &str, key: &str, permission: Permission) -> DeltaResult<()> {
+        let auth = self.db.auth();
+        self.validate_session()?;
+        auth.authorize(&self.identity_key, namespace, key, permission)
+            .map(|_| ())
+            .map_err(|_| self.denied(namespace, key, permission))
+    }
+
+    /// Store a value (requires Write).
+    ///
+    /// Under a row-filtered grant, both the new value and any current value
+    /// must match the filter.
+    pub async fn put<T: Serialize>(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        value: T,
+    ) -> DeltaResult<VersionedValue> {
+        let namespace = namespace.into();
+        let key = key.into();
+        let access = self.access(&namespace, &key, Permission::Write)?;
+        // Hold the key's write order from the row checks through the write,
+        // so a concurrent write can't change the value in between
+        #[cfg(not(target_arch = "wasm32"))]
+        let _order = self
+            .db
+            .subscription_manager()
+            .order_key(&namespace, &key)
+            .await;
+        let value = serde_json::to_value(&value)?;
+        self.check_row(&access, &namespace, &key, Permission::Write, &value)?;
+        self.check_current(&access, &namespace, &key, Permission::Write)
+            .await?;
+        self.db
+            .put_attributed(
+                namespace,
+                key,
+                value,
+                Some(self.identity_key.clone()),
+                Vec::new(),
+            )
+            .await
+    }
+
+    /// Retrieve the current value (requires Read).
+    pub async fn get(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+    ) -> DeltaResult<VersionedValue> {
+        let namespace = namespace.into();
+        let key = key.into();
+        let access = self.access(&namespace, &key, Permission::Read)?;
+        let versioned = self.db.get(&namespace, &key).await?;
+        let value = self.open(&namespace, &key, versioned.value())?;
+        self.check_row(&access, &namespace, &key, Permission::Read, &value)?;
+        Ok(versioned.with_value(value))
+    }
+
+    /// Retrieve the value as of a point in time (requires Read).
+    pub async fn get_at(
+        &self,
+        namespace: &str,
+        key: &str,
+        timestamp: DateTime<Utc>,
+    ) -> DeltaResult<VersionedValue> {
+        let access = self.access(namespace, key, Permission::Read)?;
+        let versioned = self.db.get_at(namespace, key, timestamp).await?;
+        let value = self.open(namespace, key, versioned.value())?;
+        self.check_row(&access, namespace, key, Permission::Read, &value)?;
+        Ok(versioned.with_value(value))
+    }
+
+    /// Get the full history of a key (requires Read).
+    ///
+    /// Under a row-filtered grant, only versions matching the filter are
+    /// returned.
+    pub async fn history(&self, namespace: &str, key: &str) -> DeltaResult<Vec<HistoryEntry>> {
+        let access = self.access(namespace, key, Permission::Read)?;
+        let mut entries = Vec::new();
+        for entry in self.db.history(namespace, key).await? {
+            let value = self.open(namespace, key, &entry.value)?;
+            if access.allows(&value) {
+                entries.push(HistoryEntry { value, ..entry });
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Check whether a key exists (requires Read).
+    ///
+    /// Records outside a row-filtered grant are reported as absent.
+    pub async fn contains(&self, namespace: &str, key: &str) -> DeltaResult<bool> {
+        let access = self.access(namespace, key, Permission::Read)?;
+        if access == RowAccess::All {
+            return Ok(self.db.contains(namespace, key).await);
+        }
+        match self.db.get(namespace, key).await {
+            Ok(versioned) => Ok(access.allows(&self.open(namespace, key, versioned.value())?)),
+            Err(DeltaError::KeyNotFound { .. }) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Delete a key (requires Write).
+    pub async fn delete(&self, namespace: &str, key: &str) -> DeltaResult<()> {
+        let access = self.access(namespace, key, Permission::Write)?;
+        #[cfg(not(target_arch = "wasm32"))]
+        let _order = self
+            .db
+            .subscription_manager()
+            .order_key(namespace, key)
+            .await;
+        self.check_current(&access, namespace, key, Permission::Write)
+            .await?;
+        self.db.delete(namespace, key).await
+    }
+
+    /// List the keys in a namespace that the identity may read.
+    pub async fn list_keys(&self, namespace: &str) -> DeltaResult<Vec<String>> {
+        self.validate_session()?;
+        let keys = self.db.list_keys(namespace).await;
+        let key_refs: Vec<&str> = keys.iter().map(String::as_str).collect();
+        let accesses = self
+            .db
+            .auth()
+            .row_access(&self.identity_key, namespace, &key_refs, Permission::Read)
+            .map_err(|e| DeltaError::Unauthorized {
+                reason: e.to_string(),
+            })?;
+
+        let mut readable = Vec::new();
+        for (key, access) in keys.iter().zip(accesses) {
+            let visible = match access {
+                RowAccess::All => true,
+                RowAccess::Denied => false,
+                RowAccess::Rows(_) => match self.db.get(namespace, key).await {
+                    Ok(versioned) => {
+                        access.allows(&self.open(namespace, key, versioned.value())?)
+                    }
+                    Err(_) => false,
+                },
+            };
+            if visible {
+                readable.push(key.clone());
+            }
+        }
+        Ok(readable)
+    }
+
+    /// Query a namespace, seeing only the records the identity may read.
+    ///
+    /// Row filters are applied before the query's own filters, sorting,
+    /// limits, and aggregations, so results never include or count records
+    /// outside the identity's grants. The configured
+    /// [`query_limits`](crate::core::ResourceLimits::query_limits) apply.
+    pub fn query(&self, namespace: &str, mut query: Query) -> DeltaResult<QueryResult> {
+        self.validate_session()?;
+        query.limits = query.limits.within(self.db.config().limits.query_limits);
+        let items = self.db.storage().scan_collection(namespace);
+        let keys: Vec<&str> = items.iter().map(|(key, _)| key.as_str()).collect();
+        let accesses = self
+            .db
+            .auth()
+            .row_access(&self.identity_key, namespace, &keys, Permission::Read)
+            .map_err(|e| DeltaError::Unauthorized {
+                reason: e.to_string(),
+            })?;
+
+        let mut records = Vec::new();
+        for ((key, versioned), access) in items.iter().zip(accesses) {
+            if access.is_denied() {
+                continue;
+            }
+            let value = self.open(namespace, key, versioned.value())?;
+            if access.allows(&value) {
+                records.push((
+                    key.clone(),
+                    value,
+                    versioned.timestamp(),
+                    versioned.version_id().to_string(),
+                ));
+            }
+        }
+
+        QueryExecutor::execute(&query, records.into_iter())
+    }
+
+    fn validate_session(&self) -> DeltaResult<()> {
+        self.db
+            .auth()
+            .validate_session(&self.session_id)
+            .map(|_| ())
+            .map_err(|e| DeltaError::Unauthorized {
+                reason: format!("session is no longer valid: {}", e),
+            })
+    }
+
+    /// Row-level access to a key, failing if the identity has none at all.
+    fn access(&self, namespace: &str, key: &str, permission: Permission) -> DeltaResult<RowAccess> {
+        self.validate_session()?;
+        let access = self
+            .db
+            .auth()
+            .row_access(&self.identity_key, namespace, &[key], permission)
+            .map_err(|e| DeltaError::Unauthorized {
+                reason: e.to_string(),
+            })?
+            .pop()
+            .unwrap_or(RowAccess::Denied);
+        if access.is_denied() {
+            return Err(self.denied(namespace, key, permission));
+        }
+        Ok(access)
+    }
+
+    /// Fail unless the value is within the identity's row access.
+    fn check_row(
+        &self,
+        access: &RowAccess,
+        namespace: &str,
+        key: &str,
+        permission: Permission,
+        value: &serde_json::Value,
+    ) -> DeltaResult<()> {
+        if access.allows(value) {
+            Ok(())
+        } else {
+            Err(self.denied(namespace, key, permission))
+        }
+    }
+
+    /// Under a row-filtered grant, fail unless the current value (if any)
+    /// matches.
+    async fn check_current(
+        &self,
+        access: &RowAccess,
+        namespace: &str,
+        key: &str,
+        permission: Permission,
+    ) -> DeltaResult<()> {
+        if !matches!(access, RowAccess::Rows(_)) {
+            return Ok(());
+        }
+        match self.db.get(namespace, key).await {
+            Ok(versioned) => {
+                let value = self.open(namespace, key, versioned.value())?;
+                self.check_row(access, namespace, key, permission, &value)
+            }
+            Err(DeltaError::KeyNotFound { .. }) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn denied(&self, namespace: &str, key: &str, permission: Permission) -> DeltaError {
+        DeltaError::Unauthorized {
+            reason: format!(
+                "identity {} lacks {} permission on {}:{}",
+                self.identity_key,
+                permission.as_str(),
+                namespace,
+                key
+            ),
+        }
+    }
+
+    /// Open a sealed value if the handle holds a secret key.
+    ///
+    /// Plain values, and sealed values when no key was given, pass through.
+    fn open(
+        &self,
+        namespace: &str,
+        key: &str,
+        value: &serde_json::Value,
+    ) -> DeltaResult<serde_json::Value> {
+        let (Some(secret_key), Some(sealed)) =
+            (self.secret_key.as_ref(), SealedValue::from_json(value))
+        else {
+            return Ok(value.clone());
+        };
+
+        sealed
+            .open(namespace, key, &self.identity_key, secret_key)
+            .map_err(|e| DeltaError::Unauthorized {
+                reason: format!("cannot decrypt {}:{}: {}", namespace, key, e),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KoruDelta;
+    use crate::auth::{IdentityUserData, ResourcePattern, create_challenge_response};
+    use serde_json::json;
+
+    /// Create an identity with a live session; returns (identity, secret key, session id).
+    fn login(db: &KoruDelta) -> (crate::auth::Identity, Vec<u8>, String) {
+        let auth = db.auth();
+        let (identity, secret_key) = auth.create_identity(IdentityUserData::default()).unwrap();
+        let challenge = auth.create_challenge(&identity.public_key).unwrap();
+        let response = create_challenge_response(&secret_key, &challenge).unwrap();
+        let session = auth
+            .verify_and_create_session(&identity.public_key, &challenge, &response)
+            .unwrap();
+        (identity, secret_key, session.session_id)
+    }
+
+    #[tokio::test]
+    async fn test_operations_require_capabilities() {
+        let db = KoruDelta::start().await.unwrap();
+        let (owner, owner_key, _) = login(&db);
+        let (reader, _, session_id) = login(&db);
+        db.put("docs", "a", json!(1)).await.unwrap();
+        db.put("secret", "x", json!(2)).await.unwrap();
+
+        let handle = db.as_identity(&session_id).unwrap();
+        assert_eq!(handle.identity_key(), reader.public_key);
+        assert!(matches!(
+            handle.get("docs", "a").await,
+            Err(DeltaError::Unauthorized { .. })
+        ));
+
+        db.auth()
+            .grant_capability(
+                &owner,
+                &owner_key,
+                &reader.public_key,
+                ResourcePattern::Namespace("docs".to_string()),
+                Permission::Read,
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(handle.get("docs", "a").await.unwrap().value(), &json!(1));
+        assert_eq!(handle.history("docs", "a").await.unwrap().len(), 1);
+        assert!(matches!(
+            handle.put("docs", "a", json!(3)).await,
+            Err(DeltaError::Unauthorized { .. })
+        ));
+        assert!(matches!(
+            handle.get("secret", "x").await,
+            Err(DeltaError::Unauthorized { .. })
+        ));
+        assert_eq!(handle.list_keys("docs").await.unwrap(), vec!["a"]);
+        assert!(handle.list_keys("secret").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_write_capability_allows_put_and_delete() {
+        let db = KoruDelta::start().await.unwrap();
+        let (owner, owner_key, _) = login(&db);
+        let (writer, _, session_id) = login(&db);
+
+        db.auth()
+            .grant_capability(
+                &owner,
+                &owner_key,
+                &writer.public_key,
+                ResourcePattern::Wildcard {
+                    prefix: "notes:writer/".to_string(),
+                },
+                Permission::Write,
+                None,
+            )
+            .unwrap();
+
+        let handle = db.as_identity(&session_id).unwrap();
+        handle
+            .put("notes", "writer/todo", json!("ship"))
+            .await
+            .unwrap();
+        assert!(handle.contains("notes", "writer/todo").await.unwrap());
+        handle.delete("notes", "writer/todo").await.unwrap();
+        assert!(!db.contains("notes", "writer/todo").await);
+
+        assert!(matches!(
+            handle.put("notes", "other", json!("nope")).await,
+            Err(DeltaError::Unauthorized { .. })
+        ));
+
+        // Writes through the handle are attributed to its identity
+        handle.put("notes", "writer/log", json!(1)).await.unwrap();
+        let provenance = db.provenance("notes", "writer/log").await.unwrap();
+        assert_eq!(
+            provenance.current().unwrap().author.as_deref(),
+            Some(writer.public_key.as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_revoked_session_is_rejected() {
+        let db = KoruDelta::start().await.unwrap();
+        let (_, _, session_id) = login(&db);
+
+        assert!(matches!(
+            db.as_identity("no-such-session"),
+            Err(DeltaError::Unauthorized { .. })
+        ));
+
+        let handle = db.as_identity(&session_id).unwrap();
+        db.auth().revoke_session(&session_id).unwrap();
+        assert!(matches!(
+            handle.list_keys("docs").await,
+            Err(DeltaError::Unauthorized { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_namespace_decrypts_for_recipients() {
+        use crate::auth::{NamespaceEncryption, is_sealed};
+
+        let db = KoruDelta::start().await.unwrap();
+        let (owner, owner_key, _) = login(&db);
+        let (alice, alice_key, alice_session) = login(&db);
+        let (bob, bob_key, bob_session) = login(&db);
+        for reader in [&alice, &bob] {
+            db.auth()
+                .grant_capability(
+                    &owner,
+                    &owner_key,
+                    &reader.public_key,
+                    ResourcePattern::Namespace("hr".to_string()),
+                    Permission::Write,
+                    None,
+                )
+                .unwrap();
+        }
+
+        db.encrypt_namespace("hr", NamespaceEncryption::new([alice.public_key.clone()]))
+            .await
+            .unwrap();
+        db.put("hr", "salary", json!({"amount": 100}))
+            .await
+            .unwrap();
+
+        // Storage only holds ciphertext
+        let stored = db.get("hr", "salary").await.unwrap();
+        assert!(is_sealed(stored.value()));
+
+        let handle = db
+            .as_identity(&alice_session)
+            .unwrap()
+            .with_secret_key(&alice_key)
+            .unwrap();
+        assert_eq!(
+            handle.get("hr", "salary").await.unwrap().value(),
+            &json!({"amount": 100})
+        );
+        handle.put("hr", "bonus", json!(5)).await.unwrap();
+        assert_eq!(
+            handle.history("hr", "bonus").await.unwrap()[0].value,
+            json!(5)
+        );
+
+        // Without the key, the sealed form is returned for the client to open
+        let sealed = db
+            .as_identity(&alice_session)
+            .unwrap()
+            .get("hr", "salary")
+            .await
+            .unwrap();
+        assert_eq!(
+            SealedValue::from_json(sealed.value())
+                .unwrap()
+                .open("hr", "salary", &alice.public_key, &alice_key)
+                .unwrap(),
+            json!({"amount": 100})
+        );
+
+        // Bob can read the key but is not a recipient
+        let bob_handle = db
+            .as_identity(&bob_session)
+            .unwrap()
+            .with_secret_key(&bob_key)
+            .unwrap();
+        assert!(matches!(
+            bob_handle.get("hr", "salary").await,
+            Err(DeltaError::Unauthorized { .. })
+        ));
+
+        // Changed settings apply to the next write
+        db.encrypt_namespace("hr", NamespaceEncryption::new([bob.public_key.clone()]))
+            .await
+            .unwrap();
+        db.put("hr", "salary", json!({"amount": 200}))
+            .await
+            .unwrap();
+        assert_eq!(
+            bob_handle.get("hr", "salary").await.unwrap().value(),
+            &json!({"amount": 200})
+        );
+
+        // Secret keys are checked against the session identity
+        assert!(
+            db.as_identity(&bob_session)
+                .unwrap()
+                .with_secret_key(&alice_key)
+                .is_err()
+        );
+        assert!(
+            db.encrypt_namespace("hr", NamespaceEncryption::new(["not-a-key"]))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_row_filter_limits_records() {
+        use crate::auth::ROW_FILTER_IDENTITY;
+        use crate::query::{Filter, Query};
+
+        let db = KoruDelta::start().await.unwrap();
+        let (owner, owner_key, _) = login(&db);
+        let (reader, _, session_id) = login(&db);
+        let mine = reader.public_key.clone();
+        db.put("orders", "a", json!({"owner": mine, "total": 10}))
+            .await
+            .unwrap();
+        db.put("orders", "b", json!({"owner": "someone-else", "total": 20}))
+            .await
+            .unwrap();
+
+        for permission in [Permission::Read, Permission::Write] {
+            db.auth()
+                .grant_filtered_capability(
+                    &owner,
+                    &owner_key,
+                    &reader.public_key,
+                    ResourcePattern::Namespace("orders".to_string()),
+                    permission,
+                    Filter::eq("owner", ROW_FILTER_IDENTITY),
+                    None,
+                )
+                .unwrap();
+        }
+
+        let handle = db.as_identity(&session_id).unwrap();
+        assert!(handle.get("orders", "a").await.is_ok());
+        assert!(matches!(
+            handle.get("orders", "b").await,
+            Err(DeltaError::Unauthorized { .. })
+        ));
+        assert!(!handle.contains("orders", "b").await.unwrap());
+        assert_eq!(handle.list_keys("orders").await.unwrap(), vec!["a"]);
+
+        // Row filters apply before the query's own aggregation
+        let result = handle
+            .query(
+                "orders",
+                Query::new().aggregate(crate::query::Aggregation::count()),
+            )
+            .unwrap();
+        assert_eq!(result.records.len(), 1);
+        assert_eq!(result.records[0].key, "a");
+
+        // Writes must stay inside the filter
+        assert!(
+            handle
+                .put("orders", "c", json!({"owner": mine, "total": 5}))
+                .await
+                .is_ok()
+        );
+        assert!(
+            handle
+                .put("orders", "a", json!({"owner": "someone-else"}))
+                .await
+                .is_err()
+        );
+        assert!(
+            handle
+                .put("orders", "b", json!({"owner": mine}))
+                .await
+                .is_err()
+        );
+        assert!(handle.delete("orders", "b").await.is_err());
+
+        // Key-level checks ignore row-filtered grants
+        assert!(handle.authorize("orders", "a", Permission::Read).is_err());
+    }
+}
@@ -10,6 +10,16 @@
 //! ### Authentication
 //! - `POST /api/v1/auth/challenge` - Request a challenge
 //! - `POST /api/v1/auth/verify` - Verify challenge response and create session
+//! - `POST /api/v1/auth/passkey/verify` - Verify a passkey assertion and create session
+//! - `POST /api/v1/auth/passkey/register` - Register a passkey (authenticated)
+//! - `POST /api/v1/auth/guest` - Create a read-only guest session (if enabled)
+//!
+//! Challenge and verify endpoints are rate limited per client IP and per
+//! identity (see [`RateLimitConfig`](crate::auth::RateLimitConfig)) and
+//! answer `429 Too Many Requests` when a limit or lockout applies. The client
+//! IP comes from the connection, so serve the router with
+//! `into_make_service_with_connect_info::<SocketAddr>()`, or enable
+//! `trust_forwarded_for` behind a proxy.
 //!
 //! ### Session Management
 //! - `POST /api/v1/auth/session/validate` - Validate a session
@@ -35,21 +45,24 @@
 //!     .route_layer(auth_middleware(auth_manager));
 //! ```
 
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use axum::{
     Json, Router,
-    extract::State,
+    extract::{ConnectInfo, State},
     http::StatusCode,
     routing::{get, post},
 };
 use serde::{Deserialize, Serialize};
 
 use crate::auth::manager::IdentityAgent;
+use crate::auth::rate_limit::{RateLimitAction, RateLimitSubject};
 use crate::auth::types::{
     AuthError, Capability, Identity, IdentityUserData, Permission, ResourcePattern, Session,
 };
+use crate::auth::webauthn::PasskeyAssertion;
 
 /// Extension trait for extracting identity from request extensions.
 #[derive(Clone)]
@@ -69,6 +82,14 @@ impl AuthContext {
         }
     }
 
+    /// Create a context for an anonymous guest session.
+    pub fn guest(session: Session) -> Self {
+        Self {
+            identity: None,
+            session: Some(session),
+        }
+    }
+
     /// Create an authenticated context.
     pub fn authenticated(identity: Identity, session: Session) -> Self {
         Self {
@@ -82,6 +103,11 @@ impl AuthContext {
         self.identity.is_some() && self.session.is_some()
     }
 
+    /// Check if the request uses a guest session.
+    pub fn is_guest(&self) -> bool {
+        self.session.as_ref().is_some_and(Session::is_guest)
+    }
+
     /// Get the identity public key.
     pub fn identity_key(&self) -> Option<&str> {
         self.identity.as_ref().map(|i| i.public_key.as_str())
@@ -146,6 +172,40 @@ pub struct VerifyRequest {
     pub response: String,
 }
 
+/// Request to log in with a passkey assertion.
+#[derive(Debug, Deserialize)]
+pub struct PasskeyVerifyRequest {
+    /// Public key of the identity
+    pub public_key: String,
+    /// Challenge string the assertion answers
+    pub challenge: String,
+    /// The WebAuthn assertion (base64url fields)
+    #[serde(flatten)]
+    pub assertion: PasskeyAssertion,
+}
+
+/// Request to register a passkey with the current identity.
+#[derive(Debug, Deserialize)]
+pub struct PasskeyRegisterRequest {
+    /// Credential ID (base64url `rawId`)
+    pub credential_id: String,
+    /// Public key from `getPublicKey()` (base64url)
+    pub public_key: String,
+    /// COSE algorithm from `getPublicKeyAlgorithm()`
+    pub algorithm: i64,
+}
+
+/// Response for a registered passkey.
+#[derive(Debug, Serialize)]
+pub struct PasskeyResponse {
+    /// Credential ID
+    pub credential_id: String,
+    /// Identity the passkey logs in as
+    pub identity_key: String,
+    /// When the passkey was registered
+    pub created_at: String,
+}
+
 /// Response with session.
 #[derive(Debug, Serialize)]
 pub struct SessionResponse {
@@ -259,6 +319,8 @@ pub fn auth_routes(auth: Arc<RwLock<IdentityAgent>>) -> Router {
         // Authentication
         .route("/api/v1/auth/challenge", post(handle_challenge))
         .route("/api/v1/auth/verify", post(handle_verify))
+        .route("/api/v1/auth/passkey/verify", post(handle_passkey_verify))
+        .route("/api/v1/auth/guest", post(handle_guest))
         // Session management
         .route(
             "/api/v1/auth/session/validate",
@@ -274,6 +336,11 @@ pub fn protected_routes(auth: Arc<RwLock<IdentityAgent>>) -> Router {
     Router::new()
         // Session management
         .route("/api/v1/auth/session/revoke", post(handle_revoke_session))
+        // Passkeys
+        .route(
+            "/api/v1/auth/passkey/register",
+            post(handle_passkey_register),
+        )
         // Capabilities
         .route(
             "/api/v1/auth/capability/grant",
@@ -331,6 +398,9 @@ pub async fn extract_auth_context(
             let session = auth
                 .validate_session(session_id)
                 .map_err(|_| StatusCode::UNAUTHORIZED)?;
+            if session.is_guest() {
+                return Ok(AuthContext::guest(session));
+            }
             let identity = auth
                 .get_identity(&session.identity_key)
                 .map_err(|_| StatusCode::UNAUTHORIZED)?
@@ -388,9 +458,21 @@ async fn handle_register(
 /// Handle challenge request.
 async fn handle_challenge(
     State(auth): State<Arc<RwLock<IdentityAgent>>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    headers: axum::http::HeaderMap,
     Json(request): Json<ChallengeRequest>,
 ) -> Result<Json<ChallengeResponse>, (StatusCode, Json<AuthErrorResponse>)> {
     let auth_guard = auth.read().await;
+    let subjects = rate_limit_subjects(
+        &auth_guard,
+        connect_info.as_ref(),
+        &headers,
+        &request.public_key,
+    );
+    auth_guard
+        .check_rate_limit(RateLimitAction::Challenge, &subjects)
+        .map_err(auth_error)?;
+
     let challenge = auth_guard
         .create_challenge(&request.public_key)
         .map_err(auth_error)?;
@@ -404,13 +486,79 @@ async fn handle_challenge(
 /// Handle challenge verification and session creation.
 async fn handle_verify(
     State(auth): State<Arc<RwLock<IdentityAgent>>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    headers: axum::http::HeaderMap,
     Json(request): Json<VerifyRequest>,
 ) -> Result<Json<SessionResponse>, (StatusCode, Json<AuthErrorResponse>)> {
     let auth_guard = auth.read().await;
-    let session = auth_guard
-        .verify_and_create_session(&request.public_key, &request.challenge, &request.response)
+    let subjects = rate_limit_subjects(
+        &auth_guard,
+        connect_info.as_ref(),
+        &headers,
+        &request.public_key,
+    );
+    let session = rate_limited_verify(&auth_guard, &subjects, || {
+        auth_guard.verify_and_create_session(
+            &request.public_key,
+            &request.challenge,
+            &request.response,
+        )
+    })?;
+
+    Ok(Json(SessionResponse {
+        session_id: session.session_id,
+        identity_key: session.identity_key,
+        expires_at: session.expires_at.to_rfc3339(),
+    }))
+}
+
+/// Handle passkey assertion verification and session creation.
+async fn handle_passkey_verify(
+    State(auth): State<Arc<RwLock<IdentityAgent>>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    headers: axum::http::HeaderMap,
+    Json(request): Json<PasskeyVerifyRequest>,
+) -> Result<Json<SessionResponse>, (StatusCode, Json<AuthErrorResponse>)> {
+    let auth_guard = auth.read().await;
+    let subjects = rate_limit_subjects(
+        &auth_guard,
+        connect_info.as_ref(),
+        &headers,
+        &request.public_key,
+    );
+    let session = rate_limited_verify(&auth_guard, &subjects, || {
+        auth_guard.verify_passkey_and_create_session(
+            &request.public_key,
+            &request.challenge,
+            &request.assertion,
+        )
+    })?;
+
+    Ok(Json(SessionResponse {
+        session_id: session.session_id,
+        identity_key: session.identity_key,
+        expires_at: session.expires_at.to_rfc3339(),
+    }))
+}
+
+/// Handle guest session creation.
+///
+/// Limited per client IP with the challenge rule, since guests have no
+/// identity to count against. Clients whose IP is unknown share one limit.
+async fn handle_guest(
+    State(auth): State<Arc<RwLock<IdentityAgent>>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    headers: axum::http::HeaderMap,
+) -> Result<Json<SessionResponse>, (StatusCode, Json<AuthErrorResponse>)> {
+    let auth_guard = auth.read().await;
+    let subject = client_ip(&auth_guard, connect_info.as_ref(), &headers)
+        .map_or_else(RateLimitSubject::unknown_ip, RateLimitSubject::Ip);
+    auth_guard
+        .check_rate_limit(RateLimitAction::Challenge, &[subject])
         .map_err(auth_error)?;
 
+    let session = auth_guard.create_guest_session().map_err(auth_error)?;
+
     Ok(Json(SessionResponse {
         session_id: session.session_id,
         identity_key: session.identity_key,
@@ -418,6 +566,42 @@ async fn handle_verify(
     }))
 }
 
+/// Handle passkey registration for the authenticated identity.
+async fn handle_passkey_register(
+    State(auth): State<Arc<RwLock<IdentityAgent>>>,
+    headers: axum::http::HeaderMap,
+    Json(request): Json<PasskeyRegisterRequest>,
+) -> Result<Json<PasskeyResponse>, (StatusCode, Json<AuthErrorResponse>)> {
+    let auth_guard = auth.read().await;
+    // Require authentication
+    let (_identity, session) = require_auth_context(&headers, &auth_guard)
+        .await
+        .map_err(|e| {
+            (
+                e,
+                Json(AuthErrorResponse {
+                    error: "Unauthorized".to_string(),
+                    code: "UNAUTHORIZED".to_string(),
+                }),
+            )
+        })?;
+
+    let credential = auth_guard
+        .register_passkey(
+            &session.session_id,
+            &request.credential_id,
+            &request.public_key,
+            request.algorithm,
+        )
+        .map_err(auth_error)?;
+
+    Ok(Json(PasskeyResponse {
+        credential_id: credential.credential_id,
+        identity_key: credential.identity_key,
+        created_at: credential.created_at.to_rfc3339(),
+    }))
+}
+
 /// Handle session validation.
 async fn handle_validate_session(
     State(auth): State<Arc<RwLock<IdentityAgent>>>,
@@ -558,12 +742,14 @@ async fn handle_authorize(
     Json(request): Json<AuthorizeRequest>,
 ) -> Result<Json<AuthorizeResponse>, (StatusCode, Json<AuthErrorResponse>)> {
     let auth_guard = auth.read().await;
-    // Require authentication
-    let (identity, _) = require_auth_context(&headers, &auth_guard)
+    // Require a session (identity or guest)
+    let session = extract_auth_context(&headers, &auth_guard)
         .await
-        .map_err(|e| {
+        .ok()
+        .and_then(|ctx| ctx.session)
+        .ok_or_else(|| {
             (
-                e,
+                StatusCode::UNAUTHORIZED,
                 Json(AuthErrorResponse {
                     error: "Unauthorized".to_string(),
                     code: "UNAUTHORIZED".to_string(),
@@ -573,7 +759,7 @@ async fn handle_authorize(
 
     // Check authorization
     let authorized = auth_guard.check_permission(
-        &identity.public_key,
+        &session.identity_key,
         &request.namespace,
         &request.key,
         request.permission,
@@ -620,6 +806,63 @@ async fn handle_list_capabilities(
 // Helpers
 // ============================================================================
 
+/// Client IP for rate limiting, if known.
+///
+/// With `trust_forwarded_for`, the right-most `X-Forwarded-For` entry is the
+/// address the trusted proxy saw; entries left of it are client supplied.
+fn client_ip(
+    auth: &IdentityAgent,
+    connect_info: Option<&ConnectInfo<SocketAddr>>,
+    headers: &axum::http::HeaderMap,
+) -> Option<String> {
+    let forwarded = auth
+        .config()
+        .rate_limits
+        .trust_forwarded_for
+        .then(|| {
+            headers
+                .get_all("x-forwarded-for")
+                .iter()
+                .next_back()
+                .and_then(|h| h.to_str().ok())
+                .and_then(|h| h.rsplit(',').next())
+                .map(|ip| ip.trim().to_string())
+                .filter(|ip| !ip.is_empty())
+        })
+        .flatten();
+    forwarded.or_else(|| connect_info.map(|ConnectInfo(addr)| addr.ip().to_string()))
+}
+
+/// Rate limit subjects for a request: the client IP (if known) and identity.
+fn rate_limit_subjects(
+    auth: &IdentityAgent,
+    connect_info: Option<&ConnectInfo<SocketAddr>>,
+    headers: &axum::http::HeaderMap,
+    identity_key: &str,
+) -> Vec<RateLimitSubject> {
+    let mut subjects = Vec::with_capacity(2);
+    if let Some(ip) = client_ip(auth, connect_info, headers) {
+        subjects.push(RateLimitSubject::Ip(ip));
+    }
+    subjects.push(RateLimitSubject::Identity(identity_key.to_string()));
+    subjects
+}
+
+/// Run a verification under the verify rate limit, recording its outcome.
+fn rate_limited_verify(
+    auth: &IdentityAgent,
+    subjects: &[RateLimitSubject],
+    verify: impl FnOnce() -> Result<Session, AuthError>,
+) -> Result<Session, (StatusCode, Json<AuthErrorResponse>)> {
+    auth.check_rate_limit(RateLimitAction::Verify, subjects)
+        .map_err(auth_error)?;
+
+    let result = verify();
+    auth.record_verification(subjects, result.is_ok())
+        .map_err(auth_error)?;
+    result.map_err(auth_error)
+}
+
 /// Convert AuthError to HTTP error response.
 fn auth_error(err: AuthError) -> (StatusCode, Json<AuthErrorResponse>) {
     let (status, code) = match err {
@@ -633,6 +876,10 @@ fn auth_error(err: AuthError) -> (StatusCode, Json<AuthErrorResponse>) {
         AuthError::Unauthorized => (StatusCode::FORBIDDEN, "UNAUTHORIZED"),
         AuthError::CapabilityNotFound(_) => (StatusCode::NOT_FOUND, "CAPABILITY_NOT_FOUND"),
         AuthError::CapabilityRevoked => (StatusCode::FORBIDDEN, "CAPABILITY_REVOKED"),
+        AuthError::InvalidDelegation(_) => (StatusCode::FORBIDDEN, "INVALID_DELEGATION"),
+        AuthError::WebAuthn(_) => (StatusCode::UNAUTHORIZED, "WEBAUTHN_FAILED"),
+        AuthError::MultisigRequired => (StatusCode::FORBIDDEN, "MULTISIG_REQUIRED"),
+        AuthError::Multisig(_) => (StatusCode::BAD_REQUEST, "MULTISIG_INVALID"),
         AuthError::InsufficientPermissions => (StatusCode::FORBIDDEN, "INSUFFICIENT_PERMISSIONS"),
         AuthError::RateLimitExceeded => (StatusCode::TOO_MANY_REQUESTS, "RATE_LIMIT_EXCEEDED"),
         _ => (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL_ERROR"),
@@ -693,6 +940,62 @@ mod tests {
         assert!(parse_resource_pattern("invalid").is_err());
     }
 
+    fn agent(rate_limits: crate::auth::RateLimitConfig) -> IdentityAgent {
+        let shared_engine = crate::engine::SharedEngine::new();
+        let storage = Arc::new(crate::storage::CausalStorage::new(Arc::clone(
+            shared_engine.inner(),
+        )));
+        let config = crate::auth::IdentityConfig {
+            rate_limits,
+            guest: Some(crate::auth::GuestConfig::new(["public"])),
+            ..Default::default()
+        };
+        IdentityAgent::with_config(storage, config, &shared_engine)
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let mut headers = axum::http::HeaderMap::new();
+        headers.insert("x-forwarded-for", "1.1.1.1, 2.2.2.2".parse().unwrap());
+        let connect_info = ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 4000)));
+
+        // The header is ignored unless the proxy is trusted
+        let untrusted = agent(Default::default());
+        assert_eq!(
+            client_ip(&untrusted, Some(&connect_info), &headers).as_deref(),
+            Some("10.0.0.1")
+        );
+
+        // The right-most entry is the one the proxy appended
+        let trusted = agent(crate::auth::RateLimitConfig {
+            trust_forwarded_for: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            client_ip(&trusted, Some(&connect_info), &headers).as_deref(),
+            Some("2.2.2.2")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_guest_sessions_limited_without_client_ip() {
+        let auth = Arc::new(RwLock::new(agent(crate::auth::RateLimitConfig {
+            challenge_per_ip: crate::auth::RateLimitRule::new(1, 60),
+            ..Default::default()
+        })));
+        let headers = axum::http::HeaderMap::new();
+
+        assert!(
+            handle_guest(State(Arc::clone(&auth)), None, headers.clone())
+                .await
+                .is_ok()
+        );
+        let Err((status, _)) = handle_guest(State(auth), None, headers).await else {
+            panic!("second guest session should be limited");
+        };
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    }
+
     #[test]
     fn test_auth_context() {
         let ctx = AuthContext::unauthenticated();
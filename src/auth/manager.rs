@@ -28,11 +28,23 @@ use std::sync::{Arc, RwLock};
 use koru_lambda_core::{Canonicalizable, Distinction, DistinctionEngine};
 
 use crate::actions::IdentityAction;
-use crate::auth::capability::{CapabilityManager, create_capability, create_revocation};
+use crate::auth::capability::{
+    CapabilityManager, RowAccess, create_delegable_capability, create_filtered_capability,
+    create_revocation, delegate_capability, renew_capability, row_access, verify_delegation_chain,
+    verify_delegation_link,
+};
 #[cfg(not(target_arch = "wasm32"))]
 use crate::auth::identity::mine_identity_sync;
 use crate::auth::identity::verify_identity_pow;
-use crate::auth::session::{SessionAgent, create_session_token};
+use crate::auth::multisig::{
+    DEFAULT_PROPOSAL_TTL_SECONDS, MultisigAction, MultisigApproval, MultisigOutcome,
+    MultisigPolicy, MultisigProposal, count_valid_approvals, create_multisig_policy,
+    create_proposal, public_key_of, verify_approval, verify_multisig_policy,
+};
+use crate::auth::rate_limit::{
+    RateLimitAction, RateLimitConfig, RateLimitState, RateLimitSubject, RateLimiter,
+};
+use crate::auth::session::{GuestConfig, SessionAgent, create_session_token, is_guest_identity};
 use crate::auth::storage::AuthStorageAdapter;
 #[cfg(not(target_arch = "wasm32"))]
 use crate::auth::types::IdentityUserData;
@@ -41,7 +53,11 @@ use crate::auth::types::{
     Session,
 };
 use crate::auth::verification::{ChallengeStore, verify_challenge_response};
+use crate::auth::webauthn::{
+    PasskeyAssertion, PasskeyCredential, WebAuthnConfig, verify_assertion,
+};
 use crate::engine::{FieldHandle, SharedEngine};
+use crate::query::Filter;
 use crate::roots::RootType;
 use crate::storage::CausalStorage;
 
@@ -59,6 +75,15 @@ pub struct IdentityConfig {
 
     /// Whether to persist sessions (default: false)
     pub persist_sessions: bool,
+
+    /// Passkey (WebAuthn) login settings; passkey login is disabled if unset
+    pub webauthn: Option<WebAuthnConfig>,
+
+    /// Rate limits and lockouts for challenge issuance and verification
+    pub rate_limits: RateLimitConfig,
+
+    /// Anonymous read-only sessions; guest sessions are disabled if unset
+    pub guest: Option<GuestConfig>,
 }
 
 impl Default for IdentityConfig {
@@ -68,6 +93,9 @@ impl Default for IdentityConfig {
             challenge_ttl_seconds: 300,
             session_ttl_seconds: 86400,
             persist_sessions: false,
+            webauthn: None,
+            rate_limits: RateLimitConfig::default(),
+            guest: None,
         }
     }
 }
@@ -104,6 +132,9 @@ pub struct IdentityAgent {
     /// Configuration
     config: IdentityConfig,
 
+    /// Node-local rate limit and lockout state
+    rate_limiter: RateLimiter,
+
     /// LCA: Local root distinction (Root: IDENTITY)
     local_root: RwLock<Distinction>,
 
@@ -155,6 +186,7 @@ impl IdentityAgent {
             challenges: ChallengeStore::with_ttl(config.challenge_ttl_seconds),
             sessions: SessionAgent::with_ttl(shared_engine, config.session_ttl_seconds),
             capabilities: RwLock::new(CapabilityManager::new()),
+            rate_limiter: RateLimiter::new(config.rate_limits.clone()),
             config,
             local_root: RwLock::new(local_root),
             identities: RwLock::new(identities),
@@ -326,6 +358,85 @@ impl IdentityAgent {
         // Verify challenge-response
         verify_challenge_response(&self.challenges, public_key, challenge, response)?;
 
+        self.start_session(public_key, challenge)
+    }
+
+    /// Verify a passkey (WebAuthn) assertion and create a session.
+    ///
+    /// The assertion answers a challenge from
+    /// [`create_challenge`](Self::create_challenge) in place of an Ed25519
+    /// signature. Requires [`IdentityConfig::webauthn`] to be set.
+    pub fn verify_passkey_and_create_session(
+        &self,
+        public_key: &str,
+        challenge: &str,
+        assertion: &PasskeyAssertion,
+    ) -> Result<Session, AuthError> {
+        let action = IdentityAction::Authenticate {
+            identity_id: public_key.to_string(),
+            challenge: challenge.to_string(),
+        };
+        let _ = self.synthesize_action_internal(action);
+
+        let webauthn =
+            self.config.webauthn.as_ref().ok_or_else(|| {
+                AuthError::WebAuthn("passkey login is not configured".to_string())
+            })?;
+
+        // Consume the challenge (fails if expired or not found)
+        self.challenges.consume_challenge(public_key, challenge)?;
+
+        let mut credential = self
+            .storage
+            .get_passkey(&assertion.credential_id)?
+            .filter(|credential| credential.identity_key == public_key)
+            .ok_or_else(|| {
+                AuthError::WebAuthn("credential is not registered to this identity".to_string())
+            })?;
+
+        credential.sign_count = verify_assertion(webauthn, &credential, challenge, assertion)?;
+        credential.last_used_at = Some(chrono::Utc::now());
+        self.storage.store_passkey(&credential)?;
+
+        self.start_session(public_key, challenge)
+    }
+
+    /// Register a passkey for the identity behind a live session.
+    ///
+    /// `public_key_der` and `algorithm` are the base64url public key and COSE
+    /// algorithm reported by the browser when the passkey was created.
+    pub fn register_passkey(
+        &self,
+        session_id: &str,
+        credential_id: &str,
+        public_key_der: &str,
+        algorithm: i64,
+    ) -> Result<PasskeyCredential, AuthError> {
+        let session = self.validate_session(session_id)?;
+
+        if self.storage.get_passkey(credential_id)?.is_some() {
+            return Err(AuthError::WebAuthn(
+                "credential is already registered".to_string(),
+            ));
+        }
+
+        let credential = PasskeyCredential::new(
+            &session.identity_key,
+            credential_id,
+            public_key_der,
+            algorithm,
+        )?;
+        self.storage.store_passkey(&credential)?;
+        Ok(credential)
+    }
+
+    /// List the passkeys registered to an identity.
+    pub fn passkeys(&self, identity_key: &str) -> Result<Vec<PasskeyCredential>, AuthError> {
+        self.storage.list_passkeys_for_identity(identity_key)
+    }
+
+    /// Create a session for an identity that answered `challenge`.
+    fn start_session(&self, public_key: &str, challenge: &str) -> Result<Session, AuthError> {
         // Load capabilities for this identity
         let capabilities = self.storage.get_active_capabilities(public_key)?;
         let capability_refs: Vec<CapabilityRef> = capabilities
@@ -343,10 +454,63 @@ impl IdentityAgent {
         Ok(session)
     }
 
+    // ========================================================================
+    // Rate Limiting
+    // ========================================================================
+
+    /// Count an attempt at `action` against every subject.
+    ///
+    /// Fails with [`AuthError::RateLimitExceeded`] if any subject is locked
+    /// out or over its limit for the current window.
+    pub fn check_rate_limit(
+        &self,
+        action: RateLimitAction,
+        subjects: &[RateLimitSubject],
+    ) -> Result<(), AuthError> {
+        self.rate_limiter
+            .check(action, subjects, chrono::Utc::now())
+    }
+
+    /// Record the outcome of a verification attempt for every subject.
+    ///
+    /// Failures count towards a lockout; a success clears the backoff.
+    pub fn record_verification(
+        &self,
+        subjects: &[RateLimitSubject],
+        success: bool,
+    ) -> Result<(), AuthError> {
+        self.rate_limiter
+            .record_verification(subjects, success, chrono::Utc::now());
+        Ok(())
+    }
+
+    /// Get the rate limit state for an action and subject, if tracked.
+    pub fn rate_limit_state(
+        &self,
+        action: RateLimitAction,
+        subject: &RateLimitSubject,
+    ) -> Result<Option<RateLimitState>, AuthError> {
+        Ok(self.rate_limiter.state(action, subject))
+    }
+
     // ========================================================================
     // Session Operations
     // ========================================================================
 
+    /// Create an anonymous guest session.
+    ///
+    /// Guest sessions have no identity behind them and can only read the
+    /// namespaces in [`GuestConfig::public_namespaces`]. Fails with
+    /// `Unauthorized` unless guest sessions are enabled in the config.
+    pub fn create_guest_session(&self) -> Result<Session, AuthError> {
+        let guest = self.config.guest.as_ref().ok_or(AuthError::Unauthorized)?;
+        let (session, _keys) = self.sessions.create_guest_session(guest);
+
+        self.sessions_created.fetch_add(1, Ordering::SeqCst);
+
+        Ok(session)
+    }
+
     /// Validate a session.
     pub fn validate_session(&self, session_id: &str) -> Result<Session, AuthError> {
         self.sessions.validate_session(session_id)
@@ -405,6 +569,60 @@ impl IdentityAgent {
         resource_pattern: ResourcePattern,
         permission: Permission,
         expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<Capability, AuthError> {
+        self.grant_delegable_capability(
+            granter_identity,
+            granter_secret_key,
+            grantee,
+            resource_pattern,
+            permission,
+            expires_at,
+            0,
+        )
+    }
+
+    /// Grant a capability that the grantee may re-grant to others.
+    ///
+    /// `delegation_depth` limits how many levels of re-granting are allowed
+    /// below this grant. See [`delegate_capability`](Self::delegate_capability).
+    #[allow(clippy::too_many_arguments)]
+    pub fn grant_delegable_capability(
+        &self,
+        granter_identity: &Identity,
+        granter_secret_key: &[u8],
+        grantee: &str,
+        resource_pattern: ResourcePattern,
+        permission: Permission,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        delegation_depth: u8,
+    ) -> Result<Capability, AuthError> {
+        self.require_single_signer(&granter_identity.public_key)?;
+        self.issue_capability(
+            granter_identity,
+            granter_secret_key,
+            grantee,
+            resource_pattern,
+            permission,
+            expires_at,
+            delegation_depth,
+            None,
+        )
+    }
+
+    /// Create, store, and cache a capability without the multisig check.
+    ///
+    /// `proposal` is the approved proposal a multisig identity grants under.
+    #[allow(clippy::too_many_arguments)]
+    fn issue_capability(
+        &self,
+        granter_identity: &Identity,
+        granter_secret_key: &[u8],
+        grantee: &str,
+        resource_pattern: ResourcePattern,
+        permission: Permission,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        delegation_depth: u8,
+        proposal: Option<MultisigProposal>,
     ) -> Result<Capability, AuthError> {
         // Synthesize grant capability action
         let action = IdentityAction::GrantCapability {
@@ -430,19 +648,225 @@ impl IdentityAgent {
         // This requires checking existing capabilities
 
         // Create capability
-        let capability = create_capability(
+        let mut capability = create_delegable_capability(
             granter_identity,
             granter_secret_key,
             grantee,
             resource_pattern,
             permission,
             expires_at,
+            delegation_depth,
         )?;
+        capability.proposal = proposal;
 
-        // Store it
+        self.record_capability(capability)
+    }
+
+    /// Grant a capability that only covers records matching `row_filter`.
+    ///
+    /// See [`create_filtered_capability`] for the filter's identity
+    /// placeholder. Delegations from it keep the filter.
+    #[allow(clippy::too_many_arguments)]
+    pub fn grant_filtered_capability(
+        &self,
+        granter_identity: &Identity,
+        granter_secret_key: &[u8],
+        grantee: &str,
+        resource_pattern: ResourcePattern,
+        permission: Permission,
+        row_filter: Filter,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<Capability, AuthError> {
+        self.require_single_signer(&granter_identity.public_key)?;
+
+        let action = IdentityAction::GrantCapability {
+            from_id: granter_identity.public_key.clone(),
+            to_id: grantee.to_string(),
+            permission: format!("{:?}", permission),
+        };
+        let _ = self.synthesize_action_internal(action);
+
+        if !self.storage.identity_exists(&granter_identity.public_key)? {
+            return Err(AuthError::IdentityNotFound(
+                granter_identity.public_key.clone(),
+            ));
+        }
+        if !self.storage.identity_exists(grantee)? {
+            return Err(AuthError::IdentityNotFound(grantee.to_string()));
+        }
+
+        let capability = create_filtered_capability(
+            granter_identity,
+            granter_secret_key,
+            grantee,
+            resource_pattern,
+            permission,
+            row_filter,
+            expires_at,
+        )?;
+
+        self.record_capability(capability)
+    }
+
+    /// Re-grant a subset of a held capability to another identity.
+    ///
+    /// The delegator must hold `parent_id`, whose chain must still verify, and
+    /// the new grant can only narrow it (pattern, permission, expiry, and
+    /// remaining depth). The root granter is not involved; revoking any link
+    /// of the chain invalidates everything delegated below it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn delegate_capability(
+        &self,
+        parent_id: &str,
+        delegator_identity: &Identity,
+        delegator_secret_key: &[u8],
+        grantee: &str,
+        resource_pattern: ResourcePattern,
+        permission: Permission,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<Capability, AuthError> {
+        self.require_single_signer(&delegator_identity.public_key)?;
+
+        let action = IdentityAction::GrantCapability {
+            from_id: delegator_identity.public_key.clone(),
+            to_id: grantee.to_string(),
+            permission: format!("{:?}", permission),
+        };
+        let _ = self.synthesize_action_internal(action);
+
+        if !self.storage.identity_exists(grantee)? {
+            return Err(AuthError::IdentityNotFound(grantee.to_string()));
+        }
+
+        let parent = self
+            .storage
+            .get_capability(parent_id)?
+            .ok_or_else(|| AuthError::CapabilityNotFound(parent_id.to_string()))?;
+        self.delegation_chain(&parent)?;
+
+        let capability = delegate_capability(
+            &parent,
+            delegator_identity,
+            delegator_secret_key,
+            grantee,
+            resource_pattern,
+            permission,
+            expires_at,
+        )?;
+
+        self.record_capability(capability)
+    }
+
+    /// Renew a capability with a new expiry.
+    ///
+    /// Only the original granter can renew. The capability keeps its ID, so
+    /// grants delegated from it stay valid; a delegated capability cannot be
+    /// renewed past its parent's expiry. Archived capabilities must be granted
+    /// again instead.
+    pub fn renew_capability(
+        &self,
+        capability_id: &str,
+        granter_identity: &Identity,
+        granter_secret_key: &[u8],
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<Capability, AuthError> {
+        let capability = self
+            .storage
+            .get_capability(capability_id)?
+            .ok_or_else(|| AuthError::CapabilityNotFound(capability_id.to_string()))?;
+
+        if capability.granter != granter_identity.public_key {
+            return Err(AuthError::Unauthorized);
+        }
+        self.require_single_signer(&capability.granter)?;
+        if self.storage.is_capability_revoked(capability_id)? {
+            return Err(AuthError::CapabilityRevoked);
+        }
+
+        let renewed = renew_capability(&capability, granter_secret_key, expires_at)?;
+
+        if let Some(parent_id) = renewed.parent.as_deref() {
+            let parent = self
+                .storage
+                .get_capability(parent_id)?
+                .ok_or_else(|| AuthError::CapabilityNotFound(parent_id.to_string()))?;
+            verify_delegation_link(&parent, &renewed)?;
+        }
+
+        self.storage.store_capability(&renewed)?;
+        self.capabilities
+            .write()
+            .unwrap()
+            .add_capability(renewed.clone());
+
+        Ok(renewed)
+    }
+
+    /// Get unrevoked capabilities that expire within `window` from now.
+    ///
+    /// Already-expired capabilities are not included.
+    pub fn expiring_capabilities(
+        &self,
+        window: chrono::Duration,
+    ) -> Result<Vec<Capability>, AuthError> {
+        let now = chrono::Utc::now();
+        let revocations = self.storage.list_all_revocations()?;
+
+        Ok(self
+            .storage
+            .list_all_capabilities()?
+            .into_iter()
+            .filter(|cap| !crate::auth::capability::is_revoked(cap, &revocations))
+            .filter(|cap| {
+                cap.expires_at
+                    .is_some_and(|expiry| expiry > now && expiry <= now + window)
+            })
+            .collect())
+    }
+
+    /// Archive capabilities that expired more than `grace` ago.
+    ///
+    /// Until then an expired capability can still be renewed. Returns the
+    /// archived capabilities.
+    pub fn archive_expired_capabilities(
+        &self,
+        grace: chrono::Duration,
+    ) -> Result<Vec<Capability>, AuthError> {
+        let cutoff = chrono::Utc::now() - grace;
+
+        let expired: Vec<Capability> = self
+            .storage
+            .list_all_capabilities()?
+            .into_iter()
+            .filter(|cap| cap.expires_at.is_some_and(|expiry| expiry <= cutoff))
+            .collect();
+
+        for capability in &expired {
+            self.storage.archive_capability(capability)?;
+        }
+        if !expired.is_empty() {
+            self.refresh_capabilities()?;
+        }
+
+        Ok(expired)
+    }
+
+    /// Verify a capability's delegation chain.
+    ///
+    /// Returns the chain from `capability` up to its root grant.
+    pub fn delegation_chain(&self, capability: &Capability) -> Result<Vec<Capability>, AuthError> {
+        let revocations = self.storage.list_all_revocations()?;
+        verify_delegation_chain(
+            capability,
+            |id| self.storage.get_capability(id).ok().flatten(),
+            &revocations,
+        )
+    }
+
+    /// Store a newly created capability and add it to the cache.
+    fn record_capability(&self, capability: Capability) -> Result<Capability, AuthError> {
         self.storage.store_capability(&capability)?;
 
-        // Add to cache
         self.capabilities
             .write()
             .unwrap()
@@ -459,6 +883,17 @@ impl IdentityAgent {
         capability: &Capability,
         revoker_secret_key: &[u8],
         reason: Option<String>,
+    ) -> Result<Revocation, AuthError> {
+        self.require_single_signer(&capability.granter)?;
+        self.record_revocation(capability, revoker_secret_key, reason)
+    }
+
+    /// Create, store, and cache a revocation without the multisig check.
+    fn record_revocation(
+        &self,
+        capability: &Capability,
+        revoker_secret_key: &[u8],
+        reason: Option<String>,
     ) -> Result<Revocation, AuthError> {
         // Create revocation
         let revocation = create_revocation(capability, revoker_secret_key, reason)?;
@@ -475,6 +910,222 @@ impl IdentityAgent {
         Ok(revocation)
     }
 
+    // ========================================================================
+    // Multi-Signature Identities
+    // ========================================================================
+
+    /// Attach a k-of-n signer policy to an identity.
+    ///
+    /// From then on the identity's capability grants, delegations, renewals,
+    /// and revocations must be proposed and approved by its signers (see
+    /// [`Self::propose_multisig_action`]). A policy can only be set once;
+    /// later changes are themselves a guarded [`MultisigAction::UpdatePolicy`].
+    pub fn set_multisig_policy(
+        &self,
+        identity: &Identity,
+        identity_secret_key: &[u8],
+        signers: Vec<String>,
+        threshold: usize,
+    ) -> Result<MultisigPolicy, AuthError> {
+        if !self.storage.identity_exists(&identity.public_key)? {
+            return Err(AuthError::IdentityNotFound(identity.public_key.clone()));
+        }
+        self.require_single_signer(&identity.public_key)?;
+
+        let policy = create_multisig_policy(identity, identity_secret_key, signers, threshold)?;
+        self.storage.store_multisig_policy(&policy)?;
+        self.capabilities
+            .write()
+            .unwrap()
+            .add_multisig_policy(policy.clone());
+
+        Ok(policy)
+    }
+
+    /// Get the verified multisig policy for an identity, if any.
+    pub fn multisig_policy(&self, identity_key: &str) -> Result<Option<MultisigPolicy>, AuthError> {
+        match self.storage.get_multisig_policy(identity_key)? {
+            Some(policy) => {
+                verify_multisig_policy(&policy)?;
+                Ok(Some(policy))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Propose a guarded action for a multisig identity.
+    ///
+    /// The proposer must be one of the policy's signers; their approval is
+    /// included in the proposal.
+    pub fn propose_multisig_action(
+        &self,
+        identity_key: &str,
+        proposer_secret_key: &[u8],
+        action: MultisigAction,
+    ) -> Result<MultisigProposal, AuthError> {
+        let policy = self.require_multisig_policy(identity_key)?;
+        let proposal = create_proposal(
+            identity_key,
+            action,
+            proposer_secret_key,
+            chrono::Duration::seconds(DEFAULT_PROPOSAL_TTL_SECONDS),
+        )?;
+        verify_approval(&policy, &proposal, &proposal.approvals[0])?;
+
+        self.storage.store_multisig_proposal(&proposal)?;
+        Ok(proposal)
+    }
+
+    /// Add a signer's approval to a pending proposal.
+    pub fn approve_multisig_proposal(
+        &self,
+        proposal_id: &str,
+        approval: MultisigApproval,
+    ) -> Result<MultisigProposal, AuthError> {
+        let mut proposal = self.pending_proposal(proposal_id)?;
+        let policy = self.require_multisig_policy(&proposal.identity_key)?;
+        verify_approval(&policy, &proposal, &approval)?;
+
+        if !proposal
+            .approvals
+            .iter()
+            .any(|existing| existing.signer == approval.signer)
+        {
+            proposal.approvals.push(approval);
+            self.storage.store_multisig_proposal(&proposal)?;
+        }
+
+        Ok(proposal)
+    }
+
+    /// Execute a proposal that has reached its threshold.
+    ///
+    /// Approvals are re-checked against the current policy. The identity's
+    /// secret key is needed to sign the resulting capability, revocation, or
+    /// policy; holding it alone is not enough to act as the identity.
+    pub fn execute_multisig_proposal(
+        &self,
+        proposal_id: &str,
+        identity: &Identity,
+        identity_secret_key: &[u8],
+    ) -> Result<MultisigOutcome, AuthError> {
+        let mut proposal = self.pending_proposal(proposal_id)?;
+        if proposal.identity_key != identity.public_key
+            || public_key_of(identity_secret_key)? != identity.public_key
+        {
+            return Err(AuthError::Unauthorized);
+        }
+
+        let policy = self.require_multisig_policy(&proposal.identity_key)?;
+        let approvals = count_valid_approvals(&policy, &proposal);
+        if approvals < policy.threshold {
+            return Err(AuthError::Multisig(format!(
+                "{} of {} required approvals",
+                approvals, policy.threshold
+            )));
+        }
+
+        let outcome = match proposal.action.clone() {
+            MultisigAction::GrantCapability {
+                grantee,
+                resource_pattern,
+                permission,
+                expires_at,
+            } => MultisigOutcome::Granted(self.issue_capability(
+                identity,
+                identity_secret_key,
+                &grantee,
+                resource_pattern,
+                permission,
+                expires_at,
+                0,
+                Some(proposal.clone()),
+            )?),
+            MultisigAction::RevokeCapability {
+                capability_id,
+                reason,
+            } => {
+                let capability = self
+                    .storage
+                    .get_capability(&capability_id)?
+                    .ok_or_else(|| AuthError::CapabilityNotFound(capability_id.clone()))?;
+                if capability.granter != identity.public_key {
+                    return Err(AuthError::Unauthorized);
+                }
+                MultisigOutcome::Revoked(self.record_revocation(
+                    &capability,
+                    identity_secret_key,
+                    reason,
+                )?)
+            }
+            MultisigAction::UpdatePolicy { signers, threshold } => {
+                let policy =
+                    create_multisig_policy(identity, identity_secret_key, signers, threshold)?;
+                self.storage.store_multisig_policy(&policy)?;
+                self.capabilities
+                    .write()
+                    .unwrap()
+                    .add_multisig_policy(policy.clone());
+                MultisigOutcome::PolicyUpdated(policy)
+            }
+        };
+
+        proposal.executed_at = Some(chrono::Utc::now());
+        self.storage.store_multisig_proposal(&proposal)?;
+
+        Ok(outcome)
+    }
+
+    /// Get a multisig proposal by ID.
+    pub fn multisig_proposal(
+        &self,
+        proposal_id: &str,
+    ) -> Result<Option<MultisigProposal>, AuthError> {
+        self.storage.get_multisig_proposal(proposal_id)
+    }
+
+    /// List the proposals for a multisig identity.
+    pub fn multisig_proposals(
+        &self,
+        identity_key: &str,
+    ) -> Result<Vec<MultisigProposal>, AuthError> {
+        self.storage.list_multisig_proposals(identity_key)
+    }
+
+    /// Fail with [`AuthError::MultisigRequired`] if the identity has a policy.
+    fn require_single_signer(&self, identity_key: &str) -> Result<(), AuthError> {
+        match self.storage.get_multisig_policy(identity_key)? {
+            Some(_) => Err(AuthError::MultisigRequired),
+            None => Ok(()),
+        }
+    }
+
+    fn require_multisig_policy(&self, identity_key: &str) -> Result<MultisigPolicy, AuthError> {
+        self.multisig_policy(identity_key)?
+            .ok_or_else(|| AuthError::Multisig(format!("{} has no multisig policy", identity_key)))
+    }
+
+    fn pending_proposal(&self, proposal_id: &str) -> Result<MultisigProposal, AuthError> {
+        let proposal = self
+            .storage
+            .get_multisig_proposal(proposal_id)?
+            .ok_or_else(|| AuthError::Multisig(format!("proposal {} not found", proposal_id)))?;
+
+        if proposal.compute_id()? != proposal.id {
+            return Err(AuthError::Multisig(
+                "proposal does not match its ID".to_string(),
+            ));
+        }
+        if proposal.is_executed() {
+            return Err(AuthError::Multisig("proposal already executed".to_string()));
+        }
+        if proposal.is_expired(chrono::Utc::now()) {
+            return Err(AuthError::Multisig("proposal expired".to_string()));
+        }
+
+        Ok(proposal)
+    }
+
     /// Authorize access to a resource.
     ///
     /// # LCA Pattern
@@ -494,12 +1145,37 @@ impl IdentityAgent {
         };
         let _ = self.synthesize_action_internal(action);
 
-        // Get active capabilities from storage
-        let capabilities = self.storage.get_active_capabilities(identity_key)?;
+        // Guests can only read public namespaces
+        if is_guest_identity(identity_key) {
+            return match &self.config.guest {
+                Some(guest)
+                    if guest.is_public(namespace) && required_permission == Permission::Read =>
+                {
+                    Ok(CapabilityRef {
+                        capability_key: format!(
+                            "{}{}",
+                            crate::auth::GUEST_IDENTITY_PREFIX,
+                            namespace
+                        ),
+                        resource_pattern: ResourcePattern::Namespace(namespace.to_string()),
+                        permission: Permission::Read,
+                    })
+                }
+                _ => Err(AuthError::InsufficientPermissions),
+            };
+        }
 
         // Get revocations
         let revocations = self.storage.list_all_revocations()?;
 
+        // Get active capabilities from storage, dropping broken delegation chains
+        let capabilities = crate::auth::capability::with_valid_chains(
+            self.storage.get_active_capabilities(identity_key)?,
+            |id| self.storage.get_capability(id).ok().flatten(),
+            |granter| self.storage.get_multisig_policy(granter).ok().flatten(),
+            &revocations,
+        );
+
         // Check authorization
         crate::auth::capability::authorize(
             identity_key,
@@ -511,6 +1187,53 @@ impl IdentityAgent {
         )
     }
 
+    /// Row-level access an identity has to each of `keys` in a namespace.
+    ///
+    /// Unlike [`authorize`](Self::authorize), capabilities with a row filter
+    /// count, so callers must check each record's value with
+    /// [`RowAccess::allows`]. Capabilities are loaded once for all keys.
+    pub fn row_access(
+        &self,
+        identity_key: &str,
+        namespace: &str,
+        keys: &[&str],
+        required_permission: Permission,
+    ) -> Result<Vec<RowAccess>, AuthError> {
+        if is_guest_identity(identity_key) {
+            return Ok(keys
+                .iter()
+                .map(|key| {
+                    match self.authorize(identity_key, namespace, key, required_permission) {
+                        Ok(_) => RowAccess::All,
+                        Err(_) => RowAccess::Denied,
+                    }
+                })
+                .collect());
+        }
+
+        let revocations = self.storage.list_all_revocations()?;
+        let capabilities = crate::auth::capability::with_valid_chains(
+            self.storage.get_active_capabilities(identity_key)?,
+            |id| self.storage.get_capability(id).ok().flatten(),
+            |granter| self.storage.get_multisig_policy(granter).ok().flatten(),
+            &revocations,
+        );
+
+        Ok(keys
+            .iter()
+            .map(|key| {
+                row_access(
+                    identity_key,
+                    namespace,
+                    key,
+                    required_permission,
+                    &capabilities,
+                    &revocations,
+                )
+            })
+            .collect())
+    }
+
     /// Check if an identity has a permission on a resource.
     pub fn check_permission(
         &self,
@@ -540,11 +1263,13 @@ impl IdentityAgent {
     pub fn refresh_capabilities(&self) -> Result<(), AuthError> {
         let all_capabilities = self.storage.list_all_capabilities()?;
         let all_revocations = self.storage.list_all_revocations()?;
+        let all_policies = self.storage.list_multisig_policies()?;
 
-        self.capabilities
-            .write()
-            .unwrap()
-            .load(all_capabilities, all_revocations);
+        let mut cache = self.capabilities.write().unwrap();
+        cache.load(all_capabilities, all_revocations);
+        for policy in all_policies {
+            cache.add_multisig_policy(policy);
+        }
         Ok(())
     }
 
@@ -793,6 +1518,370 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_guest_session() {
+        // Disabled by default
+        assert!(matches!(
+            create_test_manager().create_guest_session(),
+            Err(AuthError::Unauthorized)
+        ));
+
+        let shared_engine = SharedEngine::new();
+        let storage = Arc::new(CausalStorage::new(Arc::clone(shared_engine.inner())));
+        let manager = IdentityAgent::with_config(
+            storage,
+            IdentityConfig {
+                guest: Some(GuestConfig::new(["dashboard"]).with_ttl(60)),
+                ..Default::default()
+            },
+            &shared_engine,
+        );
+
+        let session = manager.create_guest_session().unwrap();
+        assert!(session.is_guest());
+        assert_eq!(session.capabilities.len(), 1);
+        assert!(manager.validate_session(&session.session_id).is_ok());
+        assert!(session.expires_at <= session.created_at + chrono::Duration::seconds(60));
+
+        // Read-only, public namespaces only
+        let guest = &session.identity_key;
+        assert!(manager.check_permission(guest, "dashboard", "stats", Permission::Read));
+        assert!(!manager.check_permission(guest, "dashboard", "stats", Permission::Write));
+        assert!(!manager.check_permission(guest, "private", "stats", Permission::Read));
+
+        // Each guest gets its own ephemeral identity key
+        let other = manager.create_guest_session().unwrap();
+        assert_ne!(other.identity_key, session.identity_key);
+    }
+
+    #[test]
+    fn test_multisig_identity() {
+        use crate::auth::multisig::approve_proposal;
+
+        let manager = create_test_manager();
+        let (org, org_secret) = manager
+            .create_identity(IdentityUserData::default())
+            .unwrap();
+        let (grantee, _) = manager
+            .create_identity(IdentityUserData::default())
+            .unwrap();
+        let signers: Vec<(Identity, Vec<u8>)> = (0..3)
+            .map(|_| {
+                manager
+                    .create_identity(IdentityUserData::default())
+                    .unwrap()
+            })
+            .collect();
+        let signer_keys = signers
+            .iter()
+            .map(|(id, _)| id.public_key.clone())
+            .collect();
+
+        manager
+            .set_multisig_policy(&org, &org_secret, signer_keys, 2)
+            .unwrap();
+
+        // Direct grants are refused once a policy is set
+        assert!(matches!(
+            manager.grant_capability(
+                &org,
+                &org_secret,
+                &grantee.public_key,
+                ResourcePattern::Namespace("org".to_string()),
+                Permission::Admin,
+                None,
+            ),
+            Err(AuthError::MultisigRequired)
+        ));
+
+        let proposal = manager
+            .propose_multisig_action(
+                &org.public_key,
+                &signers[0].1,
+                MultisigAction::GrantCapability {
+                    grantee: grantee.public_key.clone(),
+                    resource_pattern: ResourcePattern::Namespace("org".to_string()),
+                    permission: Permission::Admin,
+                    expires_at: None,
+                },
+            )
+            .unwrap();
+
+        // One approval is below the threshold
+        assert!(matches!(
+            manager.execute_multisig_proposal(&proposal.id, &org, &org_secret),
+            Err(AuthError::Multisig(_))
+        ));
+
+        let approval = approve_proposal(&proposal, &signers[1].1).unwrap();
+        manager
+            .approve_multisig_proposal(&proposal.id, approval)
+            .unwrap();
+        let outcome = manager
+            .execute_multisig_proposal(&proposal.id, &org, &org_secret)
+            .unwrap();
+        assert!(matches!(outcome, MultisigOutcome::Granted(_)));
+        assert!(
+            manager
+                .authorize(&grantee.public_key, "org", "settings", Permission::Admin)
+                .is_ok()
+        );
+
+        // A grant signed with the identity's key alone is not honoured, nor
+        // one carrying a proposal short of the threshold
+        let (other, _) = manager
+            .create_identity(IdentityUserData::default())
+            .unwrap();
+        let mut forged = create_delegable_capability(
+            &org,
+            &org_secret,
+            &other.public_key,
+            ResourcePattern::Namespace("org".to_string()),
+            Permission::Admin,
+            None,
+            0,
+        )
+        .unwrap();
+        manager.storage().store_capability(&forged).unwrap();
+        assert!(
+            manager
+                .authorize(&other.public_key, "org", "settings", Permission::Admin)
+                .is_err()
+        );
+        forged.proposal = Some(
+            manager
+                .propose_multisig_action(
+                    &org.public_key,
+                    &signers[0].1,
+                    MultisigAction::GrantCapability {
+                        grantee: other.public_key.clone(),
+                        resource_pattern: ResourcePattern::Namespace("org".to_string()),
+                        permission: Permission::Admin,
+                        expires_at: None,
+                    },
+                )
+                .unwrap(),
+        );
+        manager.storage().store_capability(&forged).unwrap();
+        assert!(
+            manager
+                .authorize(&other.public_key, "org", "settings", Permission::Admin)
+                .is_err()
+        );
+
+        // Proposals execute once
+        assert!(matches!(
+            manager.execute_multisig_proposal(&proposal.id, &org, &org_secret),
+            Err(AuthError::Multisig(_))
+        ));
+        assert!(
+            manager
+                .multisig_proposal(&proposal.id)
+                .unwrap()
+                .unwrap()
+                .is_executed()
+        );
+
+        // Policies cannot be replaced directly
+        assert!(matches!(
+            manager.set_multisig_policy(&org, &org_secret, vec![grantee.public_key.clone()], 1),
+            Err(AuthError::MultisigRequired)
+        ));
+    }
+
+    #[test]
+    fn test_rate_limit_lockout() {
+        let shared_engine = SharedEngine::new();
+        let storage = Arc::new(CausalStorage::new(Arc::clone(shared_engine.inner())));
+        let config = IdentityConfig {
+            rate_limits: RateLimitConfig {
+                challenge_per_identity: crate::auth::RateLimitRule::new(2, 60),
+                max_failures: 2,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let manager = IdentityAgent::with_config(Arc::clone(&storage), config, &shared_engine);
+        let subjects = [
+            RateLimitSubject::Ip("10.0.0.1".to_string()),
+            RateLimitSubject::Identity("alice".to_string()),
+        ];
+
+        // Per-identity challenge limit
+        assert!(
+            manager
+                .check_rate_limit(RateLimitAction::Challenge, &subjects)
+                .is_ok()
+        );
+        assert!(
+            manager
+                .check_rate_limit(RateLimitAction::Challenge, &subjects)
+                .is_ok()
+        );
+        assert!(matches!(
+            manager.check_rate_limit(RateLimitAction::Challenge, &subjects),
+            Err(AuthError::RateLimitExceeded)
+        ));
+
+        // Repeated failures lock out verification
+        manager.record_verification(&subjects, false).unwrap();
+        assert!(
+            manager
+                .check_rate_limit(RateLimitAction::Verify, &subjects)
+                .is_ok()
+        );
+        manager.record_verification(&subjects, false).unwrap();
+        assert!(matches!(
+            manager.check_rate_limit(RateLimitAction::Verify, &subjects),
+            Err(AuthError::RateLimitExceeded)
+        ));
+
+        let state = manager
+            .rate_limit_state(RateLimitAction::Verify, &subjects[0])
+            .unwrap()
+            .unwrap();
+        assert_eq!(state.lockouts, 1);
+
+        // Counters stay in memory rather than in the auth namespace
+        assert!(
+            storage
+                .list_keys(crate::auth::AUTH_NAMESPACE)
+                .iter()
+                .all(|key| !key.starts_with("rate_limit:"))
+        );
+
+        // Success clears the backoff
+        manager.record_verification(&subjects, true).unwrap();
+        assert!(
+            manager
+                .check_rate_limit(RateLimitAction::Verify, &subjects)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_passkey_login() {
+        use crate::auth::webauthn::COSE_ALG_EDDSA;
+        use crate::auth::webauthn::tests::TestAuthenticator;
+
+        let shared_engine = SharedEngine::new();
+        let storage = Arc::new(CausalStorage::new(Arc::clone(shared_engine.inner())));
+        let manager = IdentityAgent::with_config(
+            storage,
+            IdentityConfig {
+                webauthn: Some(WebAuthnConfig::new("example.com", "https://example.com")),
+                ..Default::default()
+            },
+            &shared_engine,
+        );
+
+        // Log in once with the identity key to register the passkey
+        let (identity, secret_key) = manager
+            .create_identity(IdentityUserData::default())
+            .unwrap();
+        let challenge = manager.create_challenge(&identity.public_key).unwrap();
+        let response =
+            crate::auth::verification::create_challenge_response(&secret_key, &challenge).unwrap();
+        let session = manager
+            .verify_and_create_session(&identity.public_key, &challenge, &response)
+            .unwrap();
+
+        let mut authenticator = TestAuthenticator::new();
+        manager
+            .register_passkey(
+                &session.session_id,
+                &authenticator.credential_id,
+                &authenticator.public_key_der(),
+                COSE_ALG_EDDSA,
+            )
+            .unwrap();
+        assert_eq!(manager.passkeys(&identity.public_key).unwrap().len(), 1);
+
+        // Log in with the passkey
+        let challenge = manager.create_challenge(&identity.public_key).unwrap();
+        let assertion = authenticator.assert("example.com", "https://example.com", &challenge);
+        let session = manager
+            .verify_passkey_and_create_session(&identity.public_key, &challenge, &assertion)
+            .unwrap();
+        assert_eq!(session.identity_key, identity.public_key);
+        assert_eq!(
+            manager.passkeys(&identity.public_key).unwrap()[0].sign_count,
+            1
+        );
+
+        // Challenges are single use
+        assert!(matches!(
+            manager.verify_passkey_and_create_session(&identity.public_key, &challenge, &assertion),
+            Err(AuthError::ChallengeExpired)
+        ));
+
+        // Passkey login is off unless configured
+        let plain = create_test_manager();
+        assert!(matches!(
+            plain.verify_passkey_and_create_session(&identity.public_key, &challenge, &assertion),
+            Err(AuthError::WebAuthn(_))
+        ));
+    }
+
+    #[test]
+    fn test_delegated_capability() {
+        let manager = create_test_manager();
+
+        let (root, root_key) = manager
+            .create_identity(IdentityUserData::default())
+            .unwrap();
+        let (lead, lead_key) = manager
+            .create_identity(IdentityUserData::default())
+            .unwrap();
+        let (member, _member_key) = manager
+            .create_identity(IdentityUserData::default())
+            .unwrap();
+
+        let team = manager
+            .grant_delegable_capability(
+                &root,
+                &root_key,
+                &lead.public_key,
+                ResourcePattern::Namespace("team".to_string()),
+                Permission::Write,
+                None,
+                1,
+            )
+            .unwrap();
+
+        // The lead delegates read access without the root identity
+        let delegated = manager
+            .delegate_capability(
+                &team.id,
+                &lead,
+                &lead_key,
+                &member.public_key,
+                ResourcePattern::Namespace("team".to_string()),
+                Permission::Read,
+                None,
+            )
+            .unwrap();
+        assert_eq!(manager.delegation_chain(&delegated).unwrap().len(), 2);
+        assert!(manager.check_permission(&member.public_key, "team", "doc", Permission::Read));
+        assert!(!manager.check_permission(&member.public_key, "team", "doc", Permission::Write));
+
+        // Revoking the lead's grant cuts off everything delegated below it
+        manager.revoke_capability(&team, &root_key, None).unwrap();
+        assert!(!manager.check_permission(&member.public_key, "team", "doc", Permission::Read));
+        assert!(matches!(
+            manager.delegate_capability(
+                &team.id,
+                &lead,
+                &lead_key,
+                &member.public_key,
+                ResourcePattern::Namespace("team".to_string()),
+                Permission::Read,
+                None,
+            ),
+            Err(AuthError::CapabilityRevoked)
+        ));
+    }
+
     #[test]
     fn test_admin_permission() {
         let manager = create_test_manager();
@@ -25,6 +25,14 @@
 //! 4. Server verifies signature against stored identity
 //! 5. Server creates session with derived encryption keys
 //!
+//! Browser clients can instead answer the challenge with a WebAuthn
+//! assertion from a passkey registered to the identity (see
+//! [`PasskeyCredential`]).
+//!
+//! Challenge issuance and verification are rate limited per client IP and
+//! per identity, with exponential lockouts after repeated failures (see
+//! [`RateLimitConfig`]).
+//!
 //! ## Sessions
 //! Sessions are ephemeral by default (24 hour TTL). Each session has:
 //! - Session ID (derived from HKDF)
@@ -32,6 +40,10 @@
 //! - Authentication key (for session validation)
 //! - Capability references (what the session can access)
 //!
+//! If [`GuestConfig`] is set, anonymous clients can also get a short-lived
+//! guest session without an identity. Guests can only read the configured
+//! public namespaces.
+//!
 //! ## Capabilities
 //! Capabilities are grants of permission from one identity to another:
 //! - Granter: identity giving permission
@@ -42,6 +54,26 @@
 //! Capabilities are signed by the granter and stored as distinctions.
 //! They can be revoked via tombstone distinctions.
 //!
+//! A grant can allow re-granting (delegation) to a limited depth. Delegated
+//! capabilities can only narrow the parent grant and are verified as a chain
+//! of signatures back to the root grant.
+//!
+//! An identity with a [`MultisigPolicy`] cannot grant, delegate, renew, or
+//! revoke capabilities directly. Its signers propose the action, approve it
+//! by signature, and it executes once k of n have approved.
+//!
+//! ## Encrypted Namespaces
+//! Values in an encrypted namespace are sealed to a set of identity public
+//! keys before storage (see [`SealedValue`]). Storage and sync only see
+//! ciphertext; [`AuthenticatedDelta`] handles given the identity's secret key
+//! decrypt transparently.
+//!
+//! ## Row-Level Security
+//! A capability can carry a row filter (a query [`Filter`](crate::query::Filter)
+//! over the value). It then covers only matching records, e.g. orders where
+//! `owner` equals the grantee ([`ROW_FILTER_IDENTITY`]). Filters are enforced
+//! on the get, history, list, and query paths of [`AuthenticatedDelta`].
+//!
 //! ## Resource Patterns
 //! - Exact: `users:alice:profile` - matches exactly
 //! - Wildcard: `users:alice:*` - matches any key under prefix
@@ -87,7 +119,11 @@
 //! Auth data is stored in the `_auth` namespace:
 //! - `_auth:identity:{public_key}` - Identity distinctions
 //! - `_auth:capability:{id}` - Capability grants
+//! - `_auth:archived_capability:{id}` - Expired grants moved out of the active set
 //! - `_auth:revocation:{capability_id}` - Capability revocations
+//! - `_auth:passkey:{credential_id}` - Passkeys registered to identities
+//! - `_auth:multisig_policy:{identity}` - k-of-n signer policies
+//! - `_auth:multisig_proposal:{id}` - Guarded actions and their approvals
 //!
 //! This allows auth state to:
 //! - Be versioned (history preserved)
@@ -99,11 +135,16 @@ pub mod types;
 
 // Sub-modules
 mod capability;
+mod handle;
 mod identity;
 mod manager;
+mod multisig;
+mod rate_limit;
+mod sealed;
 mod session;
 mod storage;
 mod verification;
+mod webauthn;
 
 // HTTP module (requires http feature)
 #[cfg(all(not(target_arch = "wasm32"), feature = "http"))]
@@ -111,8 +152,11 @@ pub mod http;
 
 // Public exports from sub-modules
 pub use capability::{
-    CapabilityManager, authorize, check_permission, create_capability, create_revocation,
+    CapabilityManager, RowAccess, authorize, check_permission, create_capability,
+    create_delegable_capability, create_filtered_capability, create_revocation,
+    delegate_capability, row_access, verify_delegation_chain, verify_delegation_link,
 };
+pub use handle::AuthenticatedDelta;
 pub use identity::{
     DEFAULT_DIFFICULTY, MAX_DIFFICULTY, MIN_DIFFICULTY, mine_identity, sign_message,
     sign_message_base58, verify_identity_pow, verify_signature,
@@ -120,20 +164,38 @@ pub use identity::{
 #[cfg(not(target_arch = "wasm32"))]
 pub use identity::{estimate_hash_rate, estimate_mining_time_ms, mine_identity_sync};
 pub use manager::{IdentityAgent, IdentityConfig, IdentityStats};
+pub use multisig::{
+    DEFAULT_PROPOSAL_TTL_SECONDS, MultisigAction, MultisigApproval, MultisigOutcome,
+    MultisigPolicy, MultisigProposal, approve_proposal, create_multisig_policy,
+    verify_multisig_policy,
+};
+pub use rate_limit::{
+    RateLimitAction, RateLimitConfig, RateLimitRule, RateLimitState, RateLimitSubject, RateLimiter,
+};
+pub use sealed::{
+    ENCRYPTION_CONFIG_NAMESPACE, NamespaceEncryption, SEALED_FIELD, SealedKey, SealedValue,
+    is_sealed,
+};
 pub use session::{
-    DEFAULT_SESSION_TTL_SECONDS, MAX_SESSION_TTL_SECONDS, SessionAgent, create_session_token,
-    derive_session_keys, validate_session_token,
+    DEFAULT_GUEST_SESSION_TTL_SECONDS, DEFAULT_SESSION_TTL_SECONDS, GUEST_IDENTITY_PREFIX,
+    GuestConfig, MAX_SESSION_TTL_SECONDS, SessionAgent, create_session_token, derive_session_keys,
+    is_guest_identity, validate_session_token,
 };
 pub use storage::{AUTH_NAMESPACE, AuthStorageAdapter};
 pub use types::{
     AuthError, Capability, CapabilityRef, Challenge, Identity, IdentityUserData, Permission,
-    ResourcePattern, Revocation, Session,
+    ROW_FILTER_IDENTITY, ResourcePattern, Revocation, Session,
 };
 pub use verification::{
     ChallengeStore, DEFAULT_CHALLENGE_TTL_SECONDS, create_challenge_response,
     verify_challenge_response,
 };
 
+pub use webauthn::{
+    COSE_ALG_EDDSA, COSE_ALG_ES256, PasskeyAssertion, PasskeyCredential, WebAuthnConfig,
+    verify_assertion,
+};
+
 // HTTP exports (requires http feature)
 #[cfg(all(not(target_arch = "wasm32"), feature = "http"))]
 pub use http::{
@@ -187,6 +249,9 @@ pub fn init(storage: Arc<CausalStorage>, shared_engine: &SharedEngine) -> Identi
 ///     challenge_ttl_seconds: 600,  // 10 minutes
 ///     session_ttl_seconds: 3600,   // 1 hour
 ///     persist_sessions: true,
+///     webauthn: None,
+///     rate_limits: Default::default(),
+///     guest: None,
 /// };
 /// let auth = auth::init_with_config(storage, config, &shared_engine);
 /// # }
@@ -0,0 +1,477 @@
+//! Multi-signature identities.
+//!
+//! An identity can attach a [`MultisigPolicy`] requiring k-of-n signer
+//! approvals for its guarded actions (capability grants, revocations, and
+//! policy changes). Instead of acting directly, a signer proposes a
+//! [`MultisigAction`]; other signers approve it by signing the proposal ID,
+//! and once the threshold is met the action can be executed.
+//!
+//! Policies and proposals are stored as distinctions in the `_auth`
+//! namespace, so approvals collected on different nodes reconcile like any
+//! other auth state.
+
+use chrono::{DateTime, Duration, Utc};
+use ed25519_dalek::SigningKey;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::auth::identity::{sign_message, verify_signature};
+use crate::auth::types::{AuthError, Capability, Identity, Permission, ResourcePattern};
+
+/// Default proposal TTL: 7 days.
+pub const DEFAULT_PROPOSAL_TTL_SECONDS: i64 = 604_800;
+
+/// k-of-n signer policy attached to an identity.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigPolicy {
+    /// Identity the policy guards
+    pub identity_key: String,
+    /// Public keys allowed to approve proposals
+    pub signers: Vec<String>,
+    /// Approvals required to execute a proposal
+    pub threshold: usize,
+    /// When this version of the policy was set
+    pub created_at: DateTime<Utc>,
+    /// Signature by the guarded identity
+    pub signature: String,
+}
+
+impl MultisigPolicy {
+    /// Whether `public_key` is one of the policy's signers.
+    pub fn is_signer(&self, public_key: &str) -> bool {
+        self.signers.iter().any(|signer| signer == public_key)
+    }
+
+    fn signature_message(&self) -> String {
+        policy_message(
+            &self.identity_key,
+            &self.signers,
+            self.threshold,
+            self.created_at,
+        )
+    }
+}
+
+/// An action that requires multi-signature approval.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MultisigAction {
+    /// Grant a capability from the guarded identity
+    GrantCapability {
+        /// Identity receiving the capability
+        grantee: String,
+        /// What the capability applies to
+        resource_pattern: ResourcePattern,
+        /// Level of access
+        permission: Permission,
+        /// Optional expiration
+        expires_at: Option<DateTime<Utc>>,
+    },
+    /// Revoke a capability granted by the guarded identity
+    RevokeCapability {
+        /// Capability to revoke
+        capability_id: String,
+        /// Optional reason
+        reason: Option<String>,
+    },
+    /// Replace the signer set and threshold
+    UpdatePolicy {
+        /// New signer public keys
+        signers: Vec<String>,
+        /// New threshold
+        threshold: usize,
+    },
+}
+
+/// A signer's approval of a proposal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigApproval {
+    /// Approving signer's public key
+    pub signer: String,
+    /// Signature over the proposal's approval message (base58)
+    pub signature: String,
+    /// When the approval was made
+    pub approved_at: DateTime<Utc>,
+}
+
+/// A proposed guarded action collecting approvals.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MultisigProposal {
+    /// Proposal ID (see [`MultisigProposal::compute_id`])
+    pub id: String,
+    /// Identity the action is performed as
+    pub identity_key: String,
+    /// The guarded action
+    pub action: MultisigAction,
+    /// Signer who proposed the action
+    pub proposer: String,
+    /// When the proposal was made
+    pub created_at: DateTime<Utc>,
+    /// Proposal can no longer be approved or executed after this time
+    pub expires_at: DateTime<Utc>,
+    /// Approvals collected so far
+    pub approvals: Vec<MultisigApproval>,
+    /// When the action was executed
+    pub executed_at: Option<DateTime<Utc>>,
+}
+
+impl MultisigProposal {
+    /// Message signers sign to approve this proposal.
+    pub fn approval_message(&self) -> String {
+        format!("multisig_approve:{}", self.id)
+    }
+
+    /// Hash of the proposal's identity, action, proposer, and lifetime.
+    ///
+    /// Approvals sign the ID, so a proposal whose stored ID differs from this
+    /// has been altered after it was approved.
+    pub fn compute_id(&self) -> Result<String, AuthError> {
+        let mut hasher = Sha256::new();
+        hasher.update(self.identity_key.as_bytes());
+        hasher.update(serde_json::to_vec(&self.action)?);
+        hasher.update(self.proposer.as_bytes());
+        for time in [self.created_at, self.expires_at] {
+            hasher.update(time.timestamp_nanos_opt().unwrap_or_default().to_le_bytes());
+        }
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    /// Whether the proposal has expired at `now`.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+
+    /// Whether the action has been executed.
+    pub fn is_executed(&self) -> bool {
+        self.executed_at.is_some()
+    }
+}
+
+/// Result of executing a proposal.
+#[derive(Debug, Clone)]
+pub enum MultisigOutcome {
+    /// A capability was granted
+    Granted(crate::auth::types::Capability),
+    /// A capability was revoked
+    Revoked(crate::auth::types::Revocation),
+    /// The policy was replaced
+    PolicyUpdated(MultisigPolicy),
+}
+
+/// Create a policy for an identity, signed with the identity's key.
+pub fn create_multisig_policy(
+    identity: &Identity,
+    identity_secret_key: &[u8],
+    signers: Vec<String>,
+    threshold: usize,
+) -> Result<MultisigPolicy, AuthError> {
+    if public_key_of(identity_secret_key)? != identity.public_key {
+        return Err(AuthError::InvalidKeyFormat);
+    }
+    validate_signers(&signers, threshold)?;
+
+    let created_at = Utc::now();
+    let message = policy_message(&identity.public_key, &signers, threshold, created_at);
+    let signature = sign_message(identity_secret_key, message.as_bytes())?;
+
+    Ok(MultisigPolicy {
+        identity_key: identity.public_key.clone(),
+        signers,
+        threshold,
+        created_at,
+        signature: bs58::encode(&signature).into_string(),
+    })
+}
+
+/// Verify a policy's signature and signer set.
+pub fn verify_multisig_policy(policy: &MultisigPolicy) -> Result<(), AuthError> {
+    validate_signers(&policy.signers, policy.threshold)?;
+
+    let signature = bs58::decode(&policy.signature)
+        .into_vec()
+        .map_err(|_| AuthError::InvalidSignature)?;
+    if !verify_signature(
+        &policy.identity_key,
+        policy.signature_message().as_bytes(),
+        &signature,
+    )? {
+        return Err(AuthError::InvalidSignature);
+    }
+
+    Ok(())
+}
+
+/// Create a proposal, approved by the proposer.
+pub fn create_proposal(
+    identity_key: &str,
+    action: MultisigAction,
+    proposer_secret_key: &[u8],
+    ttl: Duration,
+) -> Result<MultisigProposal, AuthError> {
+    let proposer = public_key_of(proposer_secret_key)?;
+    let created_at = Utc::now();
+
+    let mut proposal = MultisigProposal {
+        id: String::new(),
+        identity_key: identity_key.to_string(),
+        action,
+        proposer,
+        created_at,
+        expires_at: created_at + ttl,
+        approvals: Vec::new(),
+        executed_at: None,
+    };
+    proposal.id = proposal.compute_id()?;
+    let approval = approve_proposal(&proposal, proposer_secret_key)?;
+    proposal.approvals.push(approval);
+
+    Ok(proposal)
+}
+
+/// Approve a proposal with a signer's secret key (client-side).
+pub fn approve_proposal(
+    proposal: &MultisigProposal,
+    signer_secret_key: &[u8],
+) -> Result<MultisigApproval, AuthError> {
+    let signature = sign_message(signer_secret_key, proposal.approval_message().as_bytes())?;
+
+    Ok(MultisigApproval {
+        signer: public_key_of(signer_secret_key)?,
+        signature: bs58::encode(&signature).into_string(),
+        approved_at: Utc::now(),
+    })
+}
+
+/// Verify a single approval against the policy.
+pub fn verify_approval(
+    policy: &MultisigPolicy,
+    proposal: &MultisigProposal,
+    approval: &MultisigApproval,
+) -> Result<(), AuthError> {
+    if !policy.is_signer(&approval.signer) {
+        return Err(AuthError::Multisig(format!(
+            "{} is not a signer for {}",
+            approval.signer, policy.identity_key
+        )));
+    }
+
+    let signature = bs58::decode(&approval.signature)
+        .into_vec()
+        .map_err(|_| AuthError::InvalidSignature)?;
+    if !verify_signature(
+        &approval.signer,
+        proposal.approval_message().as_bytes(),
+        &signature,
+    )? {
+        return Err(AuthError::InvalidSignature);
+    }
+
+    Ok(())
+}
+
+/// Count the distinct valid approvals under the current policy.
+///
+/// Approvals from keys that are no longer signers are ignored.
+pub fn count_valid_approvals(policy: &MultisigPolicy, proposal: &MultisigProposal) -> usize {
+    let mut approved: Vec<&str> = proposal
+        .approvals
+        .iter()
+        .filter(|approval| verify_approval(policy, proposal, approval).is_ok())
+        .map(|approval| approval.signer.as_str())
+        .collect();
+    approved.sort_unstable();
+    approved.dedup();
+    approved.len()
+}
+
+/// Verify that a capability from a multisig identity was approved.
+///
+/// The capability must carry the proposal it was executed from, that
+/// proposal must grant exactly this capability, and its approvals must meet
+/// the policy's threshold. Approvals are checked against the current policy,
+/// so grants approved only by removed signers stop verifying.
+pub fn verify_multisig_grant(
+    policy: &MultisigPolicy,
+    capability: &Capability,
+) -> Result<(), AuthError> {
+    verify_multisig_policy(policy)?;
+
+    let proposal = capability
+        .proposal
+        .as_ref()
+        .ok_or(AuthError::MultisigRequired)?;
+    if proposal.compute_id()? != proposal.id
+        || proposal.identity_key != policy.identity_key
+        || capability.granter != policy.identity_key
+        || capability.created_at > proposal.expires_at
+    {
+        return Err(AuthError::Multisig(
+            "proposal does not authorize this capability".to_string(),
+        ));
+    }
+
+    let granted = match &proposal.action {
+        MultisigAction::GrantCapability {
+            grantee,
+            resource_pattern,
+            permission,
+            expires_at,
+        } => {
+            *grantee == capability.grantee
+                && *resource_pattern == capability.resource_pattern
+                && *permission == capability.permission
+                && *expires_at == capability.expires_at
+                && capability.parent.is_none()
+                && capability.delegation_depth == 0
+                && capability.row_filter.is_none()
+        }
+        _ => false,
+    };
+    if !granted {
+        return Err(AuthError::Multisig(
+            "proposal does not authorize this capability".to_string(),
+        ));
+    }
+
+    let approvals = count_valid_approvals(policy, proposal);
+    if approvals < policy.threshold {
+        return Err(AuthError::Multisig(format!(
+            "{} of {} required approvals",
+            approvals, policy.threshold
+        )));
+    }
+
+    Ok(())
+}
+
+/// Create storage key for a multisig policy.
+pub fn multisig_policy_storage_key(identity_key: &str) -> String {
+    format!("multisig_policy:{}", identity_key)
+}
+
+/// Create storage key for a multisig proposal.
+pub fn multisig_proposal_storage_key(proposal_id: &str) -> String {
+    format!("multisig_proposal:{}", proposal_id)
+}
+
+/// Base58 public key for a secret key.
+pub(crate) fn public_key_of(secret_key: &[u8]) -> Result<String, AuthError> {
+    let key_bytes: [u8; 32] = secret_key
+        .try_into()
+        .map_err(|_| AuthError::InvalidKeyFormat)?;
+    let signing_key = SigningKey::from_bytes(&key_bytes);
+    Ok(bs58::encode(signing_key.verifying_key().as_bytes()).into_string())
+}
+
+fn validate_signers(signers: &[String], threshold: usize) -> Result<(), AuthError> {
+    if threshold == 0 || threshold > signers.len() {
+        return Err(AuthError::Multisig(format!(
+            "threshold {} must be between 1 and {} signers",
+            threshold,
+            signers.len()
+        )));
+    }
+
+    let mut unique: Vec<&String> = signers.iter().collect();
+    unique.sort_unstable();
+    unique.dedup();
+    if unique.len() != signers.len() {
+        return Err(AuthError::Multisig("duplicate signer".to_string()));
+    }
+
+    for signer in signers {
+        let bytes = bs58::decode(signer)
+            .into_vec()
+            .map_err(|_| AuthError::InvalidKeyFormat)?;
+        ed25519_dalek::VerifyingKey::try_from(&bytes[..])
+            .map_err(|_| AuthError::InvalidKeyFormat)?;
+    }
+
+    Ok(())
+}
+
+fn policy_message(
+    identity_key: &str,
+    signers: &[String],
+    threshold: usize,
+    created_at: DateTime<Utc>,
+) -> String {
+    format!(
+        "multisig_policy:{}/{}/{}/{}",
+        identity_key,
+        threshold,
+        signers.join(","),
+        created_at.timestamp()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::identity::mine_identity_sync;
+    use crate::auth::types::IdentityUserData;
+
+    fn signer() -> (String, Vec<u8>) {
+        let mined = mine_identity_sync(IdentityUserData::default(), 1);
+        (mined.identity.public_key, mined.secret_key)
+    }
+
+    #[test]
+    fn test_policy_and_approvals() {
+        let owner = mine_identity_sync(IdentityUserData::default(), 1);
+        let signers: Vec<(String, Vec<u8>)> = (0..3).map(|_| signer()).collect();
+        let keys: Vec<String> = signers.iter().map(|(key, _)| key.clone()).collect();
+
+        let policy =
+            create_multisig_policy(&owner.identity, &owner.secret_key, keys.clone(), 2).unwrap();
+        assert!(verify_multisig_policy(&policy).is_ok());
+
+        // Invalid thresholds and foreign keys are rejected
+        assert!(
+            create_multisig_policy(&owner.identity, &owner.secret_key, keys.clone(), 4).is_err()
+        );
+        assert!(create_multisig_policy(&owner.identity, &signers[0].1, keys.clone(), 2).is_err());
+
+        let mut proposal = create_proposal(
+            &owner.identity.public_key,
+            MultisigAction::RevokeCapability {
+                capability_id: "cap".to_string(),
+                reason: None,
+            },
+            &signers[0].1,
+            Duration::seconds(DEFAULT_PROPOSAL_TTL_SECONDS),
+        )
+        .unwrap();
+        assert_eq!(count_valid_approvals(&policy, &proposal), 1);
+
+        // A duplicate approval does not count twice
+        let again = approve_proposal(&proposal, &signers[0].1).unwrap();
+        proposal.approvals.push(again);
+        assert_eq!(count_valid_approvals(&policy, &proposal), 1);
+
+        // Outsiders cannot approve
+        let (_, outsider) = signer();
+        let outside = approve_proposal(&proposal, &outsider).unwrap();
+        assert!(verify_approval(&policy, &proposal, &outside).is_err());
+        proposal.approvals.push(outside);
+        assert_eq!(count_valid_approvals(&policy, &proposal), 1);
+
+        let second = approve_proposal(&proposal, &signers[1].1).unwrap();
+        proposal.approvals.push(second);
+        assert_eq!(count_valid_approvals(&policy, &proposal), 2);
+
+        // Swapping the action invalidates the ID the approvals signed
+        assert_eq!(proposal.compute_id().unwrap(), proposal.id);
+        let mut swapped = proposal.clone();
+        swapped.action = MultisigAction::RevokeCapability {
+            capability_id: "other".to_string(),
+            reason: None,
+        };
+        assert_ne!(swapped.compute_id().unwrap(), swapped.id);
+
+        // Tampering with the policy breaks its signature
+        let mut tampered = policy.clone();
+        tampered.threshold = 1;
+        assert!(verify_multisig_policy(&tampered).is_err());
+    }
+}
@@ -0,0 +1,447 @@
+//! Rate limiting and brute-force protection for authentication.
+//!
+//! Challenge issuance and challenge verification are limited per client IP
+//! and per identity. Each limited subject has a [`RateLimitState`] held in a
+//! node-local [`RateLimiter`]. Counters are kept out of the `_auth` namespace
+//! so failed logins don't write history or sync traffic; the map is bounded
+//! and idle entries are evicted.
+//!
+//! ## Limits
+//!
+//! - Attempts: at most `max_attempts` requests per fixed window.
+//! - Lockout: after `max_failures` failed verifications, the subject is
+//!   locked out. Each consecutive lockout doubles the previous one, up to
+//!   `max_lockout_seconds`. A successful verification clears the backoff.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::auth::types::AuthError;
+
+/// Authentication step being rate limited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RateLimitAction {
+    /// Requesting a challenge
+    Challenge,
+    /// Answering a challenge (signature or passkey)
+    Verify,
+}
+
+/// What a rate limit counter is keyed on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RateLimitSubject {
+    /// Client IP address
+    Ip(String),
+    /// Identity public key
+    Identity(String),
+}
+
+impl RateLimitSubject {
+    /// Subject shared by clients whose IP is unknown.
+    pub fn unknown_ip() -> Self {
+        RateLimitSubject::Ip("unknown".to_string())
+    }
+}
+
+/// A fixed-window request limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitRule {
+    /// Requests allowed per window
+    pub max_attempts: u32,
+    /// Window length in seconds
+    pub window_seconds: i64,
+}
+
+impl RateLimitRule {
+    /// Create a rule allowing `max_attempts` per `window_seconds`.
+    pub fn new(max_attempts: u32, window_seconds: i64) -> Self {
+        Self {
+            max_attempts,
+            window_seconds,
+        }
+    }
+}
+
+/// Rate limit configuration for the auth endpoints.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// Challenge requests per client IP (default: 30 per minute)
+    pub challenge_per_ip: RateLimitRule,
+    /// Challenge requests per identity (default: 10 per minute)
+    pub challenge_per_identity: RateLimitRule,
+    /// Verification attempts per client IP (default: 30 per minute)
+    pub verify_per_ip: RateLimitRule,
+    /// Verification attempts per identity (default: 10 per minute)
+    pub verify_per_identity: RateLimitRule,
+    /// Failed verifications before a lockout (default: 5)
+    pub max_failures: u32,
+    /// First lockout in seconds; doubles on each repeat (default: 30)
+    pub base_lockout_seconds: i64,
+    /// Longest lockout in seconds (default: 3600 = 1 hour)
+    pub max_lockout_seconds: i64,
+    /// Take the client IP from `X-Forwarded-For` (default: false).
+    ///
+    /// The right-most entry is used, which is the address the proxy saw.
+    /// Only enable behind a proxy that appends to the header; otherwise
+    /// clients can pick their own IP and evade per-IP limits.
+    pub trust_forwarded_for: bool,
+    /// Most subjects tracked at once (default: 100,000)
+    pub max_tracked_subjects: usize,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            challenge_per_ip: RateLimitRule::new(30, 60),
+            challenge_per_identity: RateLimitRule::new(10, 60),
+            verify_per_ip: RateLimitRule::new(30, 60),
+            verify_per_identity: RateLimitRule::new(10, 60),
+            max_failures: 5,
+            base_lockout_seconds: 30,
+            max_lockout_seconds: 3600,
+            trust_forwarded_for: false,
+            max_tracked_subjects: 100_000,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// The request limit for an action and subject.
+    pub fn rule(&self, action: RateLimitAction, subject: &RateLimitSubject) -> RateLimitRule {
+        match (action, subject) {
+            (RateLimitAction::Challenge, RateLimitSubject::Ip(_)) => self.challenge_per_ip,
+            (RateLimitAction::Challenge, RateLimitSubject::Identity(_)) => {
+                self.challenge_per_identity
+            }
+            (RateLimitAction::Verify, RateLimitSubject::Ip(_)) => self.verify_per_ip,
+            (RateLimitAction::Verify, RateLimitSubject::Identity(_)) => self.verify_per_identity,
+        }
+    }
+
+    /// How long an untouched state is kept: the longest window or lockout.
+    fn idle_ttl(&self) -> Duration {
+        let longest = [
+            self.challenge_per_ip,
+            self.challenge_per_identity,
+            self.verify_per_ip,
+            self.verify_per_identity,
+        ]
+        .iter()
+        .map(|rule| rule.window_seconds)
+        .fold(self.max_lockout_seconds, i64::max);
+        Duration::seconds(longest)
+    }
+
+    /// Lockout length after `lockouts` previous lockouts.
+    fn lockout_duration(&self, lockouts: u32) -> Duration {
+        let seconds = self
+            .base_lockout_seconds
+            .saturating_mul(1i64 << lockouts.min(32))
+            .min(self.max_lockout_seconds);
+        Duration::seconds(seconds)
+    }
+}
+
+/// Rate limit state for one action and subject.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitState {
+    /// Action being limited
+    pub action: RateLimitAction,
+    /// Subject being limited
+    pub subject: RateLimitSubject,
+    /// Start of the current window
+    pub window_start: DateTime<Utc>,
+    /// Requests in the current window
+    pub attempts: u32,
+    /// Failed verifications since the last success or lockout
+    pub failures: u32,
+    /// Consecutive lockouts, used for exponential backoff
+    pub lockouts: u32,
+    /// Subject is rejected until this time
+    pub locked_until: Option<DateTime<Utc>>,
+    /// Last request or verification outcome
+    pub last_seen: DateTime<Utc>,
+}
+
+impl RateLimitState {
+    /// Create an empty state.
+    pub fn new(action: RateLimitAction, subject: RateLimitSubject, now: DateTime<Utc>) -> Self {
+        Self {
+            action,
+            subject,
+            window_start: now,
+            attempts: 0,
+            failures: 0,
+            lockouts: 0,
+            locked_until: None,
+            last_seen: now,
+        }
+    }
+
+    /// Whether the subject is locked out at `now`.
+    pub fn is_locked(&self, now: DateTime<Utc>) -> bool {
+        self.locked_until.is_some_and(|until| until > now)
+    }
+
+    /// Count a request, rejecting it if locked out or over the limit.
+    pub fn record_attempt(
+        &mut self,
+        rule: RateLimitRule,
+        now: DateTime<Utc>,
+    ) -> Result<(), AuthError> {
+        self.last_seen = now;
+        if self.is_locked(now) {
+            return Err(AuthError::RateLimitExceeded);
+        }
+        if now >= self.window_start + Duration::seconds(rule.window_seconds) {
+            self.window_start = now;
+            self.attempts = 0;
+        }
+        if self.attempts >= rule.max_attempts {
+            return Err(AuthError::RateLimitExceeded);
+        }
+        self.attempts += 1;
+        Ok(())
+    }
+
+    /// Count a failed verification, starting a lockout at the threshold.
+    pub fn record_failure(&mut self, config: &RateLimitConfig, now: DateTime<Utc>) {
+        self.last_seen = now;
+        self.failures += 1;
+        if self.failures >= config.max_failures {
+            self.locked_until = Some(now + config.lockout_duration(self.lockouts));
+            self.lockouts = self.lockouts.saturating_add(1);
+            self.failures = 0;
+        }
+    }
+
+    /// Clear failures and backoff after a successful verification.
+    pub fn record_success(&mut self) {
+        self.failures = 0;
+        self.lockouts = 0;
+        self.locked_until = None;
+    }
+
+    /// Whether the state can be dropped: not locked and untouched for the
+    /// longest window or lockout.
+    fn is_idle(&self, config: &RateLimitConfig, now: DateTime<Utc>) -> bool {
+        !self.is_locked(now) && now >= self.last_seen + config.idle_ttl()
+    }
+}
+
+/// Node-local rate limit states, bounded by
+/// [`RateLimitConfig::max_tracked_subjects`].
+///
+/// When a new subject arrives at capacity, idle states are evicted first and
+/// then the least recently seen one.
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    states: Mutex<HashMap<(RateLimitAction, RateLimitSubject), RateLimitState>>,
+}
+
+impl RateLimiter {
+    /// Create an empty limiter.
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            states: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Count an attempt at `action` against every subject.
+    ///
+    /// Fails with [`AuthError::RateLimitExceeded`] if any subject is locked
+    /// out or over its limit for the current window.
+    pub fn check(
+        &self,
+        action: RateLimitAction,
+        subjects: &[RateLimitSubject],
+        now: DateTime<Utc>,
+    ) -> Result<(), AuthError> {
+        let mut states = self.states.lock().unwrap();
+        for subject in subjects {
+            let rule = self.config.rule(action, subject);
+            self.state_mut(&mut states, action, subject, now)
+                .record_attempt(rule, now)?;
+        }
+        Ok(())
+    }
+
+    /// Record the outcome of a verification for every subject.
+    pub fn record_verification(
+        &self,
+        subjects: &[RateLimitSubject],
+        success: bool,
+        now: DateTime<Utc>,
+    ) {
+        let mut states = self.states.lock().unwrap();
+        for subject in subjects {
+            let key = (RateLimitAction::Verify, subject.clone());
+            if success {
+                if let Some(state) = states.get_mut(&key) {
+                    state.record_success();
+                }
+            } else {
+                self.state_mut(&mut states, RateLimitAction::Verify, subject, now)
+                    .record_failure(&self.config, now);
+            }
+        }
+    }
+
+    /// Current state for an action and subject, if tracked.
+    pub fn state(
+        &self,
+        action: RateLimitAction,
+        subject: &RateLimitSubject,
+    ) -> Option<RateLimitState> {
+        let key = (action, subject.clone());
+        self.states.lock().unwrap().get(&key).cloned()
+    }
+
+    /// Number of tracked states.
+    pub fn len(&self) -> usize {
+        self.states.lock().unwrap().len()
+    }
+
+    /// Whether no state is tracked.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn state_mut<'a>(
+        &self,
+        states: &'a mut HashMap<(RateLimitAction, RateLimitSubject), RateLimitState>,
+        action: RateLimitAction,
+        subject: &RateLimitSubject,
+        now: DateTime<Utc>,
+    ) -> &'a mut RateLimitState {
+        let key = (action, subject.clone());
+        if !states.contains_key(&key) && states.len() >= self.config.max_tracked_subjects {
+            states.retain(|_, state| !state.is_idle(&self.config, now));
+            if states.len() >= self.config.max_tracked_subjects
+                && let Some(oldest) = states
+                    .iter()
+                    .min_by_key(|(_, state)| state.last_seen)
+                    .map(|(key, _)| key.clone())
+            {
+                states.remove(&oldest);
+            }
+        }
+        states
+            .entry(key)
+            .or_insert_with(|| RateLimitState::new(action, subject.clone(), now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity_state(action: RateLimitAction) -> RateLimitState {
+        RateLimitState::new(
+            action,
+            RateLimitSubject::Identity("alice".to_string()),
+            Utc::now(),
+        )
+    }
+
+    #[test]
+    fn test_window_limit() {
+        let rule = RateLimitRule::new(2, 60);
+        let mut state = identity_state(RateLimitAction::Challenge);
+        let now = state.window_start;
+
+        assert!(state.record_attempt(rule, now).is_ok());
+        assert!(state.record_attempt(rule, now).is_ok());
+        assert!(matches!(
+            state.record_attempt(rule, now),
+            Err(AuthError::RateLimitExceeded)
+        ));
+
+        // A new window resets the count
+        assert!(
+            state
+                .record_attempt(rule, now + Duration::seconds(60))
+                .is_ok()
+        );
+        assert_eq!(state.attempts, 1);
+    }
+
+    #[test]
+    fn test_lockout_backoff() {
+        let config = RateLimitConfig {
+            max_failures: 2,
+            base_lockout_seconds: 10,
+            max_lockout_seconds: 25,
+            ..Default::default()
+        };
+        let rule = RateLimitRule::new(100, 60);
+        let mut state = identity_state(RateLimitAction::Verify);
+        let now = state.window_start;
+
+        state.record_failure(&config, now);
+        assert!(!state.is_locked(now));
+        state.record_failure(&config, now);
+        assert_eq!(state.locked_until, Some(now + Duration::seconds(10)));
+        assert!(state.record_attempt(rule, now).is_err());
+
+        // The second lockout doubles, the third is capped
+        let later = now + Duration::seconds(10);
+        assert!(state.record_attempt(rule, later).is_ok());
+        state.record_failure(&config, later);
+        state.record_failure(&config, later);
+        assert_eq!(state.locked_until, Some(later + Duration::seconds(20)));
+        let later = later + Duration::seconds(20);
+        state.record_failure(&config, later);
+        state.record_failure(&config, later);
+        assert_eq!(state.locked_until, Some(later + Duration::seconds(25)));
+
+        // Success clears the backoff
+        state.record_success();
+        assert_eq!(state.lockouts, 0);
+        assert!(!state.is_locked(later));
+    }
+
+    #[test]
+    fn test_limiter_is_bounded() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            max_tracked_subjects: 2,
+            ..Default::default()
+        });
+        let now = Utc::now();
+        let ip = |n: u8| RateLimitSubject::Ip(format!("10.0.0.{}", n));
+
+        limiter
+            .check(RateLimitAction::Challenge, &[ip(1)], now)
+            .unwrap();
+        limiter
+            .check(
+                RateLimitAction::Challenge,
+                &[ip(2)],
+                now + Duration::seconds(1),
+            )
+            .unwrap();
+        limiter
+            .check(
+                RateLimitAction::Challenge,
+                &[ip(3)],
+                now + Duration::seconds(2),
+            )
+            .unwrap();
+
+        // The least recently seen subject made room
+        assert_eq!(limiter.len(), 2);
+        assert!(limiter.state(RateLimitAction::Challenge, &ip(1)).is_none());
+
+        // Idle subjects are evicted before live ones
+        let later = now + Duration::hours(2);
+        limiter
+            .check(RateLimitAction::Challenge, &[ip(4)], later)
+            .unwrap();
+        assert_eq!(limiter.len(), 1);
+    }
+}
@@ -0,0 +1,369 @@
+//! Values sealed to identity public keys.
+//!
+//! A sealed value is encrypted once with a fresh content key, and that key is
+//! wrapped separately for each recipient identity. Wrapping uses an X25519
+//! agreement between a one-off ephemeral key and the recipient's Ed25519
+//! identity key (converted to its Montgomery form), so only holders of a
+//! recipient's secret key can open the value.
+//!
+//! Values are encrypted with XChaCha20-Poly1305. The namespace, key, and
+//! recipient list are bound in as associated data, so a sealed value cannot
+//! be moved to another key or have its recipient list edited without failing
+//! to open. Key encryption keys are derived from the agreement with
+//! HKDF-SHA256.
+//!
+//! Sealed values are stored as ordinary JSON of the form
+//! `{"__sealed": { ... }}`, so storage, persistence, and sync only ever see
+//! ciphertext. Opening needs a recipient's secret key: clients that read over
+//! the network get the sealed form and open it locally with
+//! [`SealedValue::open`], so the key never has to reach the server.
+
+use chacha20poly1305::aead::{Aead, Payload};
+use chacha20poly1305::{KeyInit, XChaCha20Poly1305, XNonce};
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::Sha256;
+
+use crate::auth::types::AuthError;
+
+/// Field that marks a JSON value as sealed.
+pub const SEALED_FIELD: &str = "__sealed";
+
+/// Internal namespace holding per-namespace encryption configuration.
+pub const ENCRYPTION_CONFIG_NAMESPACE: &str = "__encryption";
+
+const KEY_WRAP_CONTEXT: &[u8] = b"koru-delta sealed value key wrap";
+
+/// Encryption settings of a namespace.
+///
+/// Values written to the namespace are sealed to every recipient.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamespaceEncryption {
+    /// Public keys of the identities that can read the namespace
+    pub recipients: Vec<String>,
+}
+
+impl NamespaceEncryption {
+    /// Create settings sealing values to the given identities.
+    pub fn new(recipients: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            recipients: recipients.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Check that there is at least one recipient and every key is valid.
+    pub fn validate(&self) -> Result<(), AuthError> {
+        if self.recipients.is_empty() {
+            return Err(AuthError::Encryption(
+                "an encrypted namespace needs at least one recipient".to_string(),
+            ));
+        }
+        self.recipients
+            .iter()
+            .try_for_each(|recipient| parse_public_key(recipient).map(|_| ()))
+    }
+}
+
+/// The content key wrapped for one recipient.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedKey {
+    /// Public key of the recipient identity
+    pub recipient: String,
+    /// Ephemeral Ed25519 public key used for the agreement (base58)
+    pub ephemeral: String,
+    /// Encrypted content key with its authentication tag (hex)
+    pub wrapped_key: String,
+}
+
+/// An encrypted value and its per-recipient keys.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedValue {
+    /// Content key wrapped for each recipient
+    pub recipients: Vec<SealedKey>,
+    /// XChaCha20-Poly1305 nonce (hex)
+    pub nonce: String,
+    /// Encrypted JSON value with its authentication tag (hex)
+    pub ciphertext: String,
+}
+
+impl SealedValue {
+    /// Seal a JSON value stored at `namespace`/`key` to a set of identity
+    /// public keys.
+    pub fn seal(
+        value: &JsonValue,
+        namespace: &str,
+        key: &str,
+        recipients: &[String],
+    ) -> Result<Self, AuthError> {
+        if recipients.is_empty() {
+            return Err(AuthError::Encryption(
+                "a sealed value needs at least one recipient".to_string(),
+            ));
+        }
+
+        let content_key: [u8; 32] = rand::random();
+        let nonce: [u8; 24] = rand::random();
+        let plaintext = serde_json::to_vec(value)?;
+        let aad = associated_data(namespace, key, recipients.iter().map(String::as_str));
+        let ciphertext = encrypt(&content_key, &nonce, &plaintext, &aad)?;
+
+        let recipients = recipients
+            .iter()
+            .map(|recipient| wrap_key(&content_key, recipient))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            recipients,
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        })
+    }
+
+    /// Open the value stored at `namespace`/`key` with a recipient's secret
+    /// key.
+    pub fn open(
+        &self,
+        namespace: &str,
+        key: &str,
+        identity_key: &str,
+        secret_key: &[u8],
+    ) -> Result<JsonValue, AuthError> {
+        let sealed_key = self
+            .recipients
+            .iter()
+            .find(|k| k.recipient == identity_key)
+            .ok_or_else(|| AuthError::Encryption(format!("{} is not a recipient", identity_key)))?;
+        let content_key = unwrap_key(sealed_key, secret_key)?;
+
+        let nonce = decode_hex(&self.nonce)?;
+        let ciphertext = decode_hex(&self.ciphertext)?;
+        let aad = associated_data(namespace, key, self.recipient_keys());
+        let plaintext = decrypt(&content_key, &nonce, &ciphertext, &aad)?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    /// Public keys of the identities the value is sealed to.
+    pub fn recipient_keys(&self) -> impl Iterator<Item = &str> {
+        self.recipients.iter().map(|k| k.recipient.as_str())
+    }
+
+    /// Stored JSON form: `{"__sealed": { ... }}`.
+    pub fn to_json(&self) -> JsonValue {
+        serde_json::json!({ SEALED_FIELD: self })
+    }
+
+    /// Parse a stored value, if it is sealed.
+    pub fn from_json(value: &JsonValue) -> Option<Self> {
+        serde_json::from_value(value.get(SEALED_FIELD)?.clone()).ok()
+    }
+}
+
+/// Check whether a stored value is sealed.
+pub fn is_sealed(value: &JsonValue) -> bool {
+    SealedValue::from_json(value).is_some()
+}
+
+/// Associated data binding a value to its location and recipient list.
+///
+/// Each field is length-prefixed so no two contexts encode the same.
+fn associated_data<'a>(
+    namespace: &str,
+    key: &str,
+    recipients: impl Iterator<Item = &'a str>,
+) -> Vec<u8> {
+    let mut aad = Vec::new();
+    for field in [namespace, key].into_iter().chain(recipients) {
+        aad.extend_from_slice(&(field.len() as u64).to_le_bytes());
+        aad.extend_from_slice(field.as_bytes());
+    }
+    aad
+}
+
+/// Wrap the content key for one recipient with a fresh ephemeral key.
+fn wrap_key(content_key: &[u8; 32], recipient: &str) -> Result<SealedKey, AuthError> {
+    let recipient_key = parse_public_key(recipient)?;
+    let ephemeral = SigningKey::generate(&mut rand::rngs::OsRng);
+    let ephemeral_public = ephemeral.verifying_key();
+
+    let shared = recipient_key
+        .to_montgomery()
+        .mul_clamped(ephemeral.to_scalar_bytes());
+    let kek = key_encryption_key(shared.as_bytes(), &ephemeral_public, &recipient_key)?;
+    // Each key encryption key is used once, so a fixed nonce is safe
+    let wrapped = encrypt(&kek, &[0u8; 24], content_key, &[])?;
+
+    Ok(SealedKey {
+        recipient: recipient.to_string(),
+        ephemeral: bs58::encode(ephemeral_public.as_bytes()).into_string(),
+        wrapped_key: hex::encode(wrapped),
+    })
+}
+
+/// Recover the content key from a recipient's wrapped copy.
+fn unwrap_key(sealed_key: &SealedKey, secret_key: &[u8]) -> Result<[u8; 32], AuthError> {
+    let secret: [u8; 32] = secret_key
+        .try_into()
+        .map_err(|_| AuthError::InvalidKeyFormat)?;
+    let signing_key = SigningKey::from_bytes(&secret);
+    let recipient_key = signing_key.verifying_key();
+    if bs58::encode(recipient_key.as_bytes()).into_string() != sealed_key.recipient {
+        return Err(AuthError::Encryption(
+            "secret key does not belong to the recipient".to_string(),
+        ));
+    }
+    let ephemeral_public = parse_public_key(&sealed_key.ephemeral)?;
+
+    let shared = ephemeral_public
+        .to_montgomery()
+        .mul_clamped(signing_key.to_scalar_bytes());
+    let kek = key_encryption_key(shared.as_bytes(), &ephemeral_public, &recipient_key)?;
+    let content_key = decrypt(&kek, &[0u8; 24], &decode_hex(&sealed_key.wrapped_key)?, &[])?;
+
+    content_key
+        .try_into()
+        .map_err(|_| AuthError::Encryption("wrapped key has the wrong length".to_string()))
+}
+
+/// Derive the key that wraps a content key, bound to both public keys.
+fn key_encryption_key(
+    shared: &[u8; 32],
+    ephemeral: &VerifyingKey,
+    recipient: &VerifyingKey,
+) -> Result<[u8; 32], AuthError> {
+    let mut info = KEY_WRAP_CONTEXT.to_vec();
+    info.extend_from_slice(ephemeral.as_bytes());
+    info.extend_from_slice(recipient.as_bytes());
+
+    let mut kek = [0u8; 32];
+    Hkdf::<Sha256>::new(None, shared)
+        .expand(&info, &mut kek)
+        .map_err(|_| AuthError::Encryption("key derivation failed".to_string()))?;
+    Ok(kek)
+}
+
+fn encrypt(
+    key: &[u8; 32],
+    nonce: &[u8; 24],
+    plaintext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, AuthError> {
+    XChaCha20Poly1305::new(key.into())
+        .encrypt(
+            XNonce::from_slice(nonce),
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|_| AuthError::Encryption("encryption failed".to_string()))
+}
+
+fn decrypt(
+    key: &[u8; 32],
+    nonce: &[u8],
+    ciphertext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, AuthError> {
+    if nonce.len() != 24 {
+        return Err(AuthError::Encryption("malformed nonce".to_string()));
+    }
+    XChaCha20Poly1305::new(key.into())
+        .decrypt(
+            XNonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .map_err(|_| AuthError::Encryption("ciphertext failed authentication".to_string()))
+}
+
+fn parse_public_key(public_key: &str) -> Result<VerifyingKey, AuthError> {
+    let bytes = bs58::decode(public_key)
+        .into_vec()
+        .map_err(|_| AuthError::InvalidKeyFormat)?;
+    VerifyingKey::try_from(&bytes[..]).map_err(|_| AuthError::InvalidKeyFormat)
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, AuthError> {
+    hex::decode(value).map_err(|_| AuthError::Encryption("malformed hex field".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::identity::mine_identity_sync;
+    use crate::auth::types::IdentityUserData;
+    use serde_json::json;
+
+    fn identity() -> (String, Vec<u8>) {
+        let mined = mine_identity_sync(IdentityUserData::default(), 2);
+        (mined.identity.public_key, mined.secret_key)
+    }
+
+    #[test]
+    fn test_seal_and_open_for_each_recipient() {
+        let (alice, alice_key) = identity();
+        let (bob, bob_key) = identity();
+        let value = json!({"salary": 100000, "notes": "confidential"});
+
+        let sealed =
+            SealedValue::seal(&value, "hr", "alice", &[alice.clone(), bob.clone()]).unwrap();
+        assert!(!sealed.ciphertext.contains("confidential"));
+        assert_eq!(
+            sealed.open("hr", "alice", &alice, &alice_key).unwrap(),
+            value
+        );
+        assert_eq!(sealed.open("hr", "alice", &bob, &bob_key).unwrap(), value);
+
+        let stored = sealed.to_json();
+        assert!(is_sealed(&stored));
+        assert!(!is_sealed(&value));
+        assert_eq!(SealedValue::from_json(&stored).unwrap(), sealed);
+    }
+
+    #[test]
+    fn test_outsiders_and_wrong_keys_cannot_open() {
+        let (alice, alice_key) = identity();
+        let (eve, eve_key) = identity();
+        let sealed =
+            SealedValue::seal(&json!("secret"), "hr", "k", std::slice::from_ref(&alice)).unwrap();
+
+        assert!(matches!(
+            sealed.open("hr", "k", &eve, &eve_key),
+            Err(AuthError::Encryption(_))
+        ));
+        assert!(matches!(
+            sealed.open("hr", "k", &alice, &eve_key),
+            Err(AuthError::Encryption(_))
+        ));
+        assert!(SealedValue::seal(&json!(1), "hr", "k", &[]).is_err());
+
+        // The value is bound to its location and recipient list
+        assert!(matches!(
+            sealed.open("hr", "other", &alice, &alice_key),
+            Err(AuthError::Encryption(_))
+        ));
+        let mut widened = sealed.clone();
+        widened.recipients.push(SealedKey {
+            recipient: eve.clone(),
+            ..sealed.recipients[0].clone()
+        });
+        assert!(matches!(
+            widened.open("hr", "k", &alice, &alice_key),
+            Err(AuthError::Encryption(_))
+        ));
+
+        // Tampering is detected
+        let mut tampered = sealed.clone();
+        let mut bytes = hex::decode(&tampered.ciphertext).unwrap();
+        bytes[0] ^= 1;
+        tampered.ciphertext = hex::encode(bytes);
+        assert!(matches!(
+            tampered.open("hr", "k", &alice, &alice_key),
+            Err(AuthError::Encryption(_))
+        ));
+    }
+}
@@ -10,11 +10,12 @@
 
 use chrono::{DateTime, Duration, Utc};
 use dashmap::DashMap;
+use rand::RngCore;
 use sha2::{Digest, Sha256};
 use std::sync::Arc;
 
 use crate::actions::SessionAction;
-use crate::auth::types::{AuthError, CapabilityRef, Session};
+use crate::auth::types::{AuthError, CapabilityRef, Permission, ResourcePattern, Session};
 use crate::engine::SharedEngine;
 use crate::roots::KoruRoots;
 use koru_lambda_core::{Canonicalizable, Distinction, DistinctionEngine, LocalCausalAgent};
@@ -28,6 +29,56 @@ pub const MAX_SESSION_TTL_SECONDS: i64 = 2592000;
 /// Size of derived keys in bytes.
 pub const KEY_SIZE: usize = 32;
 
+/// Prefix of the ephemeral identity keys given to guest sessions.
+///
+/// Real identity keys are base58 and never contain `:`, so guest keys cannot
+/// collide with them.
+pub const GUEST_IDENTITY_PREFIX: &str = "guest:";
+
+/// Default guest session TTL: 1 hour.
+pub const DEFAULT_GUEST_SESSION_TTL_SECONDS: i64 = 3600;
+
+/// Settings for anonymous guest sessions.
+///
+/// Guests get read-only access to the listed namespaces and nothing else.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuestConfig {
+    /// Namespaces guests may read
+    pub public_namespaces: Vec<String>,
+    /// Guest session TTL in seconds (default: 3600 = 1 hour)
+    pub session_ttl_seconds: i64,
+}
+
+impl GuestConfig {
+    /// Allow guests to read the given namespaces.
+    pub fn new<I, S>(public_namespaces: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            public_namespaces: public_namespaces.into_iter().map(Into::into).collect(),
+            session_ttl_seconds: DEFAULT_GUEST_SESSION_TTL_SECONDS,
+        }
+    }
+
+    /// Set the guest session TTL.
+    pub fn with_ttl(mut self, ttl_seconds: i64) -> Self {
+        self.session_ttl_seconds = ttl_seconds;
+        self
+    }
+
+    /// Whether guests may read `namespace`.
+    pub fn is_public(&self, namespace: &str) -> bool {
+        self.public_namespaces.iter().any(|ns| ns == namespace)
+    }
+}
+
+/// Whether an identity key belongs to a guest session.
+pub fn is_guest_identity(identity_key: &str) -> bool {
+    identity_key.starts_with(GUEST_IDENTITY_PREFIX)
+}
+
 /// Derived session keys.
 #[derive(Debug, Clone)]
 pub struct SessionKeys {
@@ -138,6 +189,50 @@ impl SessionAgent {
         (session, keys)
     }
 
+    /// Create an anonymous guest session.
+    ///
+    /// The session gets a fresh random identity key under
+    /// [`GUEST_IDENTITY_PREFIX`] and read capability references for each
+    /// public namespace.
+    pub fn create_guest_session(&self, config: &GuestConfig) -> (Session, SessionKeys) {
+        let mut random = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut random);
+        let identity_key = format!(
+            "{}{}",
+            GUEST_IDENTITY_PREFIX,
+            bs58::encode(&random[..16]).into_string()
+        );
+        let nonce = bs58::encode(&random[16..]).into_string();
+
+        let capabilities = config
+            .public_namespaces
+            .iter()
+            .map(|ns| CapabilityRef {
+                capability_key: format!("{}{}", GUEST_IDENTITY_PREFIX, ns),
+                resource_pattern: ResourcePattern::Namespace(ns.clone()),
+                permission: Permission::Read,
+            })
+            .collect();
+
+        let created_at = Utc::now();
+        let ttl_seconds = config.session_ttl_seconds.min(MAX_SESSION_TTL_SECONDS);
+        let keys = derive_session_keys(&identity_key, &nonce);
+        let session_id = bs58::encode(&keys.auth_key).into_string();
+
+        let session = Session {
+            session_id: session_id.clone(),
+            identity_key,
+            created_at,
+            expires_at: created_at + Duration::seconds(ttl_seconds),
+            capabilities,
+        };
+
+        self.sessions
+            .insert(session_id, (session.clone(), keys.clone()));
+
+        (session, keys)
+    }
+
     /// Get a session by ID.
     pub fn get_session(&self, session_id: &str) -> Result<(Session, SessionKeys), AuthError> {
         match self.sessions.get(session_id) {
@@ -5,8 +5,13 @@
 
 use std::sync::Arc;
 
+use crate::auth::multisig::{
+    MultisigPolicy, MultisigProposal, multisig_policy_storage_key, multisig_proposal_storage_key,
+};
 use crate::auth::types::{AuthError, Capability, Identity, Revocation};
+use crate::auth::webauthn::PasskeyCredential;
 use crate::storage::CausalStorage;
+use crate::types::VectorClock;
 
 /// Namespace for auth-related distinctions.
 pub const AUTH_NAMESPACE: &str = "_auth";
@@ -154,6 +159,35 @@ impl AuthStorageAdapter {
         self.list_by_prefix("capability:")
     }
 
+    /// Move a capability out of the active set into the archive.
+    ///
+    /// The archived copy stays readable (see
+    /// [`list_archived_capabilities`](Self::list_archived_capabilities)) but no
+    /// longer appears in capability listings or authorization checks.
+    pub fn archive_capability(&self, capability: &Capability) -> Result<String, AuthError> {
+        let key = archived_capability_key(&capability.id);
+        let value = serde_json::to_value(capability)?;
+
+        self.storage
+            .put(AUTH_NAMESPACE, &key, value)
+            .map_err(|e| AuthError::Storage(e.to_string()))?;
+        self.storage
+            .delete_causal(
+                AUTH_NAMESPACE,
+                capability_key(&capability.id),
+                VectorClock::new(),
+                AUTH_NAMESPACE,
+            )
+            .map_err(|e| AuthError::Storage(e.to_string()))?;
+
+        Ok(key)
+    }
+
+    /// List all archived capabilities.
+    pub fn list_archived_capabilities(&self) -> Result<Vec<Capability>, AuthError> {
+        self.list_by_prefix("archived_capability:")
+    }
+
     /// Query capabilities matching a resource pattern.
     pub fn query_capabilities_for_resource(
         &self,
@@ -222,6 +256,130 @@ impl AuthStorageAdapter {
         Ok(filtered)
     }
 
+    // =========================================================================
+    // Passkey Operations
+    // =========================================================================
+
+    /// Store a passkey credential.
+    pub fn store_passkey(&self, credential: &PasskeyCredential) -> Result<String, AuthError> {
+        let key = passkey_key(&credential.credential_id);
+        let value = serde_json::to_value(credential)?;
+
+        self.storage
+            .put(AUTH_NAMESPACE, &key, value)
+            .map_err(|e| AuthError::Storage(e.to_string()))?;
+
+        Ok(key)
+    }
+
+    /// Get a passkey credential by credential ID.
+    pub fn get_passkey(&self, credential_id: &str) -> Result<Option<PasskeyCredential>, AuthError> {
+        let key = passkey_key(credential_id);
+
+        match self.storage.get(AUTH_NAMESPACE, &key) {
+            Ok(versioned) => {
+                let credential = serde_json::from_value((*versioned.value).clone())?;
+                Ok(Some(credential))
+            }
+            Err(crate::DeltaError::KeyNotFound { .. }) => Ok(None),
+            Err(e) => Err(AuthError::Storage(e.to_string())),
+        }
+    }
+
+    /// List the passkeys registered to an identity.
+    pub fn list_passkeys_for_identity(
+        &self,
+        identity_key: &str,
+    ) -> Result<Vec<PasskeyCredential>, AuthError> {
+        Ok(self
+            .list_by_prefix::<PasskeyCredential>("passkey:")?
+            .into_iter()
+            .filter(|credential| credential.identity_key == identity_key)
+            .collect())
+    }
+
+    // =========================================================================
+    // Multisig Operations
+    // =========================================================================
+
+    /// Store a multisig policy (replacing any previous version).
+    pub fn store_multisig_policy(&self, policy: &MultisigPolicy) -> Result<String, AuthError> {
+        let key = multisig_policy_storage_key(&policy.identity_key);
+        let value = serde_json::to_value(policy)?;
+
+        self.storage
+            .put(AUTH_NAMESPACE, &key, value)
+            .map_err(|e| AuthError::Storage(e.to_string()))?;
+
+        Ok(key)
+    }
+
+    /// Get the multisig policy for an identity.
+    pub fn get_multisig_policy(
+        &self,
+        identity_key: &str,
+    ) -> Result<Option<MultisigPolicy>, AuthError> {
+        let key = multisig_policy_storage_key(identity_key);
+
+        match self.storage.get(AUTH_NAMESPACE, &key) {
+            Ok(versioned) => {
+                let policy = serde_json::from_value((*versioned.value).clone())?;
+                Ok(Some(policy))
+            }
+            Err(crate::DeltaError::KeyNotFound { .. }) => Ok(None),
+            Err(e) => Err(AuthError::Storage(e.to_string())),
+        }
+    }
+
+    /// List every stored multisig policy.
+    pub fn list_multisig_policies(&self) -> Result<Vec<MultisigPolicy>, AuthError> {
+        self.list_by_prefix::<MultisigPolicy>("multisig_policy:")
+    }
+
+    /// Store a multisig proposal.
+    pub fn store_multisig_proposal(
+        &self,
+        proposal: &MultisigProposal,
+    ) -> Result<String, AuthError> {
+        let key = multisig_proposal_storage_key(&proposal.id);
+        let value = serde_json::to_value(proposal)?;
+
+        self.storage
+            .put(AUTH_NAMESPACE, &key, value)
+            .map_err(|e| AuthError::Storage(e.to_string()))?;
+
+        Ok(key)
+    }
+
+    /// Get a multisig proposal by ID.
+    pub fn get_multisig_proposal(
+        &self,
+        proposal_id: &str,
+    ) -> Result<Option<MultisigProposal>, AuthError> {
+        let key = multisig_proposal_storage_key(proposal_id);
+
+        match self.storage.get(AUTH_NAMESPACE, &key) {
+            Ok(versioned) => {
+                let proposal = serde_json::from_value((*versioned.value).clone())?;
+                Ok(Some(proposal))
+            }
+            Err(crate::DeltaError::KeyNotFound { .. }) => Ok(None),
+            Err(e) => Err(AuthError::Storage(e.to_string())),
+        }
+    }
+
+    /// List the proposals for an identity.
+    pub fn list_multisig_proposals(
+        &self,
+        identity_key: &str,
+    ) -> Result<Vec<MultisigProposal>, AuthError> {
+        Ok(self
+            .list_by_prefix::<MultisigProposal>("multisig_proposal:")?
+            .into_iter()
+            .filter(|proposal| proposal.identity_key == identity_key)
+            .collect())
+    }
+
     // =========================================================================
     // Authorization Helpers
     // =========================================================================
@@ -290,6 +448,16 @@ fn capability_key(capability_id: &str) -> String {
     crate::auth::capability::capability_storage_key_by_id(capability_id)
 }
 
+/// Create storage key for an archived capability.
+fn archived_capability_key(capability_id: &str) -> String {
+    crate::auth::capability::archived_capability_storage_key(capability_id)
+}
+
+/// Create storage key for a passkey credential.
+fn passkey_key(credential_id: &str) -> String {
+    format!("passkey:{}", credential_id)
+}
+
 /// Create storage key for a revocation.
 fn revocation_key(capability_id: &str) -> String {
     crate::auth::capability::revocation_storage_key(capability_id)
@@ -9,6 +9,15 @@ use std::collections::HashMap;
 
 use serde_json::Value as JsonValue;
 
+use crate::auth::multisig::MultisigProposal;
+use crate::query::Filter;
+
+/// Placeholder in row filter values that is replaced by the grantee's key.
+///
+/// `Filter::eq("owner", ROW_FILTER_IDENTITY)` limits a grant to records the
+/// grantee owns.
+pub const ROW_FILTER_IDENTITY: &str = "$identity";
+
 /// A mined identity stored as a distinction.
 ///
 /// Identity is self-sovereign: users generate their own keys and mine their
@@ -156,6 +165,11 @@ impl Session {
     pub fn is_expired(&self) -> bool {
         Utc::now() > self.expires_at
     }
+
+    /// Check if this is an anonymous guest session.
+    pub fn is_guest(&self) -> bool {
+        crate::auth::session::is_guest_identity(&self.identity_key)
+    }
 }
 
 /// Reference to a capability within a session.
@@ -197,6 +211,22 @@ pub struct Capability {
 
     /// Signature by granter (base58 encoded)
     pub signature: String,
+
+    /// Capability this one was delegated from (`None` for root grants)
+    #[serde(default)]
+    pub parent: Option<String>,
+
+    /// How many further re-grants this capability allows (0 = not delegable)
+    #[serde(default)]
+    pub delegation_depth: u8,
+
+    /// Row-level condition on the value; only matching records are covered
+    #[serde(default)]
+    pub row_filter: Option<Filter>,
+
+    /// Approved proposal behind a grant from a multisig identity
+    #[serde(default)]
+    pub proposal: Option<MultisigProposal>,
 }
 
 impl Capability {
@@ -208,6 +238,13 @@ impl Capability {
         }
     }
 
+    /// The row filter with [`ROW_FILTER_IDENTITY`] bound to the grantee.
+    pub fn bound_row_filter(&self) -> Option<Filter> {
+        self.row_filter
+            .as_ref()
+            .map(|filter| bind_identity(filter, &self.grantee))
+    }
+
     /// Verify the capability signature.
     pub fn verify_signature(&self) -> Result<bool, AuthError> {
         use ed25519_dalek::{Signature, VerifyingKey};
@@ -236,9 +273,18 @@ impl Capability {
         Ok(verifying_key.verify_strict(&message, &signature).is_ok())
     }
 
+    /// Check if this capability was delegated from another one.
+    pub fn is_delegated(&self) -> bool {
+        self.parent.is_some()
+    }
+
     /// Create the message that should be signed.
-    fn signature_message(&self) -> Vec<u8> {
-        format!(
+    ///
+    /// Delegation fields (and the expiry, which delegation attenuates) and the
+    /// row filter are only appended when set, so signatures on plain grants
+    /// are unchanged.
+    pub(crate) fn signature_message(&self) -> Vec<u8> {
+        let mut message = format!(
             "capability_grant:{}/{}->{}/{}/{}/{}",
             self.id,
             self.granter,
@@ -246,8 +292,53 @@ impl Capability {
             self.resource_pattern,
             self.permission.as_str(),
             self.created_at.timestamp()
-        )
-        .into_bytes()
+        );
+        if self.parent.is_some() || self.delegation_depth > 0 {
+            message.push_str(&format!(
+                "/delegation:{}/{}/{}",
+                self.parent.as_deref().unwrap_or(""),
+                self.delegation_depth,
+                self.expires_at.map(|e| e.timestamp()).unwrap_or(0)
+            ));
+        }
+        if let Some(filter) = &self.row_filter {
+            message.push_str(&format!(
+                "/row_filter:{}",
+                serde_json::to_string(filter).unwrap_or_default()
+            ));
+        }
+        message.into_bytes()
+    }
+}
+
+/// Replace [`ROW_FILTER_IDENTITY`] values in a filter with `identity_key`.
+fn bind_identity(filter: &Filter, identity_key: &str) -> Filter {
+    let bind = |value: &JsonValue| match value.as_str() {
+        Some(ROW_FILTER_IDENTITY) => JsonValue::String(identity_key.to_string()),
+        _ => value.clone(),
+    };
+    match filter {
+        Filter::Eq { field, value } => Filter::eq(field.clone(), bind(value)),
+        Filter::Ne { field, value } => Filter::ne(field.clone(), bind(value)),
+        Filter::Gt { field, value } => Filter::gt(field.clone(), bind(value)),
+        Filter::Gte { field, value } => Filter::gte(field.clone(), bind(value)),
+        Filter::Lt { field, value } => Filter::lt(field.clone(), bind(value)),
+        Filter::Lte { field, value } => Filter::lte(field.clone(), bind(value)),
+        Filter::Contains { field, value } => Filter::contains(field.clone(), bind(value)),
+        Filter::Exists { .. } | Filter::Matches { .. } => filter.clone(),
+        Filter::And(filters) => Filter::And(
+            filters
+                .iter()
+                .map(|f| bind_identity(f, identity_key))
+                .collect(),
+        ),
+        Filter::Or(filters) => Filter::Or(
+            filters
+                .iter()
+                .map(|f| bind_identity(f, identity_key))
+                .collect(),
+        ),
+        Filter::Not(inner) => Filter::not(bind_identity(inner, identity_key)),
     }
 }
 
@@ -278,6 +369,33 @@ impl ResourcePattern {
             ResourcePattern::Namespace(ns) => ns == namespace,
         }
     }
+
+    /// Check if every resource matched by this pattern is also matched by
+    /// `other`.
+    ///
+    /// Used to ensure delegated capabilities only ever narrow access.
+    pub fn is_within(&self, other: &ResourcePattern) -> bool {
+        match (self, other) {
+            (ResourcePattern::Exact(a), ResourcePattern::Exact(b)) => a == b,
+            (_, ResourcePattern::Exact(_)) => false,
+            (ResourcePattern::Exact(full), ResourcePattern::Wildcard { prefix }) => {
+                full.starts_with(prefix.as_str())
+            }
+            (ResourcePattern::Wildcard { prefix: a }, ResourcePattern::Wildcard { prefix: b }) => {
+                a.starts_with(b.as_str())
+            }
+            (ResourcePattern::Namespace(ns), ResourcePattern::Wildcard { prefix }) => {
+                format!("{}:", ns).starts_with(prefix.as_str())
+            }
+            (ResourcePattern::Exact(full), ResourcePattern::Namespace(ns)) => full
+                .strip_prefix(ns.as_str())
+                .is_some_and(|rest| rest.starts_with(':')),
+            (ResourcePattern::Wildcard { prefix }, ResourcePattern::Namespace(ns)) => prefix
+                .strip_prefix(ns.as_str())
+                .is_some_and(|rest| rest.starts_with(':')),
+            (ResourcePattern::Namespace(a), ResourcePattern::Namespace(b)) => a == b,
+        }
+    }
 }
 
 impl std::fmt::Display for ResourcePattern {
@@ -376,6 +494,21 @@ pub enum AuthError {
     #[error("Capability revoked")]
     CapabilityRevoked,
 
+    #[error("Invalid delegation: {0}")]
+    InvalidDelegation(String),
+
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+
+    #[error("WebAuthn verification failed: {0}")]
+    WebAuthn(String),
+
+    #[error("Action requires multi-signature approval")]
+    MultisigRequired,
+
+    #[error("Multisig error: {0}")]
+    Multisig(String),
+
     #[error("Insufficient permissions")]
     InsufficientPermissions,
 
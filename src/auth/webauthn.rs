@@ -0,0 +1,485 @@
+//! WebAuthn (passkey) authentication.
+//!
+//! Browsers cannot sign the raw challenge string with an identity's Ed25519
+//! key, but they can produce a WebAuthn assertion with a platform passkey.
+//! This module lets an identity register passkey credentials and then log in
+//! by answering a normal challenge with an assertion instead of a signature.
+//!
+//! # Flow
+//!
+//! 1. While logged in, the client creates a passkey with
+//!    `navigator.credentials.create()` and registers its credential ID and
+//!    public key (`response.getPublicKey()`, base64url) with the identity.
+//! 2. To log in, the client requests a challenge as usual and passes the
+//!    UTF-8 bytes of the challenge string to `navigator.credentials.get()`.
+//! 3. The assertion's `clientDataJSON`, `authenticatorData`, and `signature`
+//!    are sent back base64url encoded and verified here.
+//!
+//! # Algorithms
+//!
+//! EdDSA (COSE algorithm -8, Ed25519) and ES256 (COSE algorithm -7, ECDSA
+//! on P-256 with SHA-256) credentials are supported. Credentials using other
+//! algorithms are rejected at registration.
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, VerifyingKey};
+use p256::ecdsa::signature::Verifier;
+use p256::pkcs8::DecodePublicKey;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::auth::types::AuthError;
+
+/// COSE algorithm identifier for EdDSA (Ed25519).
+pub const COSE_ALG_EDDSA: i64 = -8;
+
+/// COSE algorithm identifier for ES256 (ECDSA on P-256 with SHA-256).
+pub const COSE_ALG_ES256: i64 = -7;
+
+/// DER prefix of an Ed25519 SubjectPublicKeyInfo (RFC 8410).
+const ED25519_SPKI_PREFIX: [u8; 12] = [
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+
+/// Authenticator data flag: user present.
+const FLAG_USER_PRESENT: u8 = 0x01;
+/// Authenticator data flag: user verified.
+const FLAG_USER_VERIFIED: u8 = 0x04;
+
+/// Relying party settings for passkey verification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebAuthnConfig {
+    /// Relying party ID (usually the site's domain, e.g. "example.com")
+    pub rp_id: String,
+    /// Origins allowed to produce assertions (e.g. "https://example.com")
+    pub origins: Vec<String>,
+    /// Require the authenticator to have verified the user (PIN, biometrics)
+    pub require_user_verification: bool,
+}
+
+impl WebAuthnConfig {
+    /// Create a configuration for one relying party and origin.
+    pub fn new(rp_id: impl Into<String>, origin: impl Into<String>) -> Self {
+        Self {
+            rp_id: rp_id.into(),
+            origins: vec![origin.into()],
+            require_user_verification: true,
+        }
+    }
+
+    /// Allow assertions from another origin.
+    pub fn with_origin(mut self, origin: impl Into<String>) -> Self {
+        self.origins.push(origin.into());
+        self
+    }
+
+    /// Set whether user verification is required.
+    pub fn with_user_verification(mut self, required: bool) -> Self {
+        self.require_user_verification = required;
+        self
+    }
+}
+
+/// A passkey registered to an identity.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PasskeyCredential {
+    /// Credential ID (base64url)
+    pub credential_id: String,
+    /// Identity the passkey logs in as
+    pub identity_key: String,
+    /// Credential public key (base58 raw Ed25519 key, or SEC1 P-256 point)
+    pub public_key: String,
+    /// COSE algorithm of the credential
+    pub algorithm: i64,
+    /// Last signature counter reported by the authenticator
+    pub sign_count: u32,
+    /// When the passkey was registered
+    pub created_at: DateTime<Utc>,
+    /// When the passkey was last used to log in
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl PasskeyCredential {
+    /// Create a credential from the browser's registration output.
+    ///
+    /// `public_key_der` is the base64url SubjectPublicKeyInfo returned by
+    /// `AuthenticatorAttestationResponse.getPublicKey()`, and `algorithm` the
+    /// value of `getPublicKeyAlgorithm()`.
+    pub fn new(
+        identity_key: impl Into<String>,
+        credential_id: impl Into<String>,
+        public_key_der: &str,
+        algorithm: i64,
+    ) -> Result<Self, AuthError> {
+        let der = decode(public_key_der, "public key")?;
+        let public_key = match algorithm {
+            COSE_ALG_EDDSA => {
+                let raw = der
+                    .strip_prefix(&ED25519_SPKI_PREFIX[..])
+                    .filter(|raw| raw.len() == 32)
+                    .ok_or_else(|| {
+                        AuthError::WebAuthn("malformed Ed25519 public key".to_string())
+                    })?;
+                VerifyingKey::try_from(raw).map_err(|_| AuthError::InvalidKeyFormat)?;
+                raw.to_vec()
+            }
+            COSE_ALG_ES256 => p256::ecdsa::VerifyingKey::from_public_key_der(&der)
+                .map_err(|_| AuthError::WebAuthn("malformed P-256 public key".to_string()))?
+                .to_encoded_point(false)
+                .as_bytes()
+                .to_vec(),
+            _ => {
+                return Err(AuthError::WebAuthn(format!(
+                    "unsupported credential algorithm {} (only EdDSA/-8 and ES256/-7 are supported)",
+                    algorithm
+                )));
+            }
+        };
+
+        Ok(Self {
+            credential_id: credential_id.into(),
+            identity_key: identity_key.into(),
+            public_key: bs58::encode(public_key).into_string(),
+            algorithm,
+            sign_count: 0,
+            created_at: Utc::now(),
+            last_used_at: None,
+        })
+    }
+}
+
+/// A WebAuthn assertion answering a challenge.
+///
+/// All binary fields are base64url encoded (no padding).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PasskeyAssertion {
+    /// Credential that produced the assertion (`rawId`)
+    pub credential_id: String,
+    /// `response.clientDataJSON`
+    pub client_data_json: String,
+    /// `response.authenticatorData`
+    pub authenticator_data: String,
+    /// `response.signature`
+    pub signature: String,
+}
+
+/// The parts of `clientDataJSON` that are checked.
+#[derive(Deserialize)]
+struct ClientData {
+    #[serde(rename = "type")]
+    kind: String,
+    challenge: String,
+    origin: String,
+}
+
+/// Verify an assertion for `challenge` against a registered credential.
+///
+/// # Returns
+/// The authenticator's new signature counter.
+pub fn verify_assertion(
+    config: &WebAuthnConfig,
+    credential: &PasskeyCredential,
+    challenge: &str,
+    assertion: &PasskeyAssertion,
+) -> Result<u32, AuthError> {
+    if assertion.credential_id != credential.credential_id {
+        return Err(AuthError::WebAuthn("credential mismatch".to_string()));
+    }
+
+    // Client data: right ceremony, right challenge, allowed origin
+    let client_data_json = decode(&assertion.client_data_json, "client data")?;
+    let client_data: ClientData = serde_json::from_slice(&client_data_json)?;
+    if client_data.kind != "webauthn.get" {
+        return Err(AuthError::WebAuthn(format!(
+            "unexpected ceremony type {}",
+            client_data.kind
+        )));
+    }
+    if client_data.challenge != URL_SAFE_NO_PAD.encode(challenge.as_bytes()) {
+        return Err(AuthError::WebAuthn("challenge mismatch".to_string()));
+    }
+    if !config.origins.contains(&client_data.origin) {
+        return Err(AuthError::WebAuthn(format!(
+            "origin {} is not allowed",
+            client_data.origin
+        )));
+    }
+
+    // Authenticator data: rpIdHash (32) | flags (1) | signCount (4) | ...
+    let authenticator_data = decode(&assertion.authenticator_data, "authenticator data")?;
+    if authenticator_data.len() < 37 {
+        return Err(AuthError::WebAuthn(
+            "authenticator data is too short".to_string(),
+        ));
+    }
+    if authenticator_data[..32] != Sha256::digest(config.rp_id.as_bytes())[..] {
+        return Err(AuthError::WebAuthn("relying party mismatch".to_string()));
+    }
+    let flags = authenticator_data[32];
+    if flags & FLAG_USER_PRESENT == 0 {
+        return Err(AuthError::WebAuthn("user was not present".to_string()));
+    }
+    if config.require_user_verification && flags & FLAG_USER_VERIFIED == 0 {
+        return Err(AuthError::WebAuthn("user was not verified".to_string()));
+    }
+    let sign_count = u32::from_be_bytes(authenticator_data[33..37].try_into().unwrap());
+    // Authenticators without a counter always report 0; one that has counted
+    // cannot go back to 0
+    if (sign_count != 0 || credential.sign_count != 0) && sign_count <= credential.sign_count {
+        return Err(AuthError::WebAuthn(
+            "signature counter did not increase (possible cloned authenticator)".to_string(),
+        ));
+    }
+
+    // Signature over authenticatorData || SHA-256(clientDataJSON)
+    let public_key = bs58::decode(&credential.public_key)
+        .into_vec()
+        .map_err(|_| AuthError::InvalidKeyFormat)?;
+    let signature = decode(&assertion.signature, "signature")?;
+
+    let mut message = authenticator_data;
+    message.extend_from_slice(&Sha256::digest(&client_data_json));
+    match credential.algorithm {
+        COSE_ALG_EDDSA => {
+            let verifying_key =
+                VerifyingKey::try_from(&public_key[..]).map_err(|_| AuthError::InvalidKeyFormat)?;
+            let signature =
+                Signature::from_slice(&signature).map_err(|_| AuthError::InvalidSignature)?;
+            verifying_key
+                .verify_strict(&message, &signature)
+                .map_err(|_| AuthError::InvalidSignature)?;
+        }
+        COSE_ALG_ES256 => {
+            // WebAuthn ES256 signatures are ASN.1 DER encoded
+            let verifying_key = p256::ecdsa::VerifyingKey::from_sec1_bytes(&public_key)
+                .map_err(|_| AuthError::InvalidKeyFormat)?;
+            let signature = p256::ecdsa::Signature::from_der(&signature)
+                .map_err(|_| AuthError::InvalidSignature)?;
+            verifying_key
+                .verify(&message, &signature)
+                .map_err(|_| AuthError::InvalidSignature)?;
+        }
+        algorithm => {
+            return Err(AuthError::WebAuthn(format!(
+                "unsupported credential algorithm {}",
+                algorithm
+            )));
+        }
+    }
+
+    Ok(sign_count)
+}
+
+fn decode(value: &str, what: &str) -> Result<Vec<u8>, AuthError> {
+    URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))
+        .map_err(|_| AuthError::WebAuthn(format!("{} is not valid base64url", what)))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use serde_json::json;
+
+    /// A passkey's private key.
+    pub(crate) enum TestKey {
+        Ed25519(SigningKey),
+        Es256(p256::ecdsa::SigningKey),
+    }
+
+    /// A software authenticator holding one passkey.
+    pub(crate) struct TestAuthenticator {
+        pub key: TestKey,
+        pub credential_id: String,
+        pub counter: u32,
+    }
+
+    impl TestAuthenticator {
+        pub(crate) fn new() -> Self {
+            Self::with_key(TestKey::Ed25519(SigningKey::generate(
+                &mut rand::rngs::OsRng,
+            )))
+        }
+
+        pub(crate) fn es256() -> Self {
+            Self::with_key(TestKey::Es256(p256::ecdsa::SigningKey::random(
+                &mut rand::rngs::OsRng,
+            )))
+        }
+
+        fn with_key(key: TestKey) -> Self {
+            Self {
+                key,
+                credential_id: URL_SAFE_NO_PAD.encode(rand::random::<[u8; 16]>()),
+                counter: 0,
+            }
+        }
+
+        pub(crate) fn algorithm(&self) -> i64 {
+            match self.key {
+                TestKey::Ed25519(_) => COSE_ALG_EDDSA,
+                TestKey::Es256(_) => COSE_ALG_ES256,
+            }
+        }
+
+        pub(crate) fn public_key_der(&self) -> String {
+            use p256::pkcs8::EncodePublicKey;
+
+            let der = match &self.key {
+                TestKey::Ed25519(key) => {
+                    let mut der = ED25519_SPKI_PREFIX.to_vec();
+                    der.extend_from_slice(key.verifying_key().as_bytes());
+                    der
+                }
+                TestKey::Es256(key) => key
+                    .verifying_key()
+                    .to_public_key_der()
+                    .unwrap()
+                    .as_bytes()
+                    .to_vec(),
+            };
+            URL_SAFE_NO_PAD.encode(der)
+        }
+
+        pub(crate) fn assert(
+            &mut self,
+            rp_id: &str,
+            origin: &str,
+            challenge: &str,
+        ) -> PasskeyAssertion {
+            self.counter = self.counter.wrapping_add(1);
+            let client_data = serde_json::to_vec(&json!({
+                "type": "webauthn.get",
+                "challenge": URL_SAFE_NO_PAD.encode(challenge.as_bytes()),
+                "origin": origin,
+            }))
+            .unwrap();
+
+            let mut authenticator_data = Sha256::digest(rp_id.as_bytes()).to_vec();
+            authenticator_data.push(FLAG_USER_PRESENT | FLAG_USER_VERIFIED);
+            authenticator_data.extend_from_slice(&self.counter.to_be_bytes());
+
+            let mut message = authenticator_data.clone();
+            message.extend_from_slice(&Sha256::digest(&client_data));
+            let signature = match &self.key {
+                TestKey::Ed25519(key) => key.sign(&message).to_bytes().to_vec(),
+                TestKey::Es256(key) => {
+                    let signature: p256::ecdsa::Signature =
+                        p256::ecdsa::signature::Signer::sign(key, &message);
+                    signature.to_der().as_bytes().to_vec()
+                }
+            };
+
+            PasskeyAssertion {
+                credential_id: self.credential_id.clone(),
+                client_data_json: URL_SAFE_NO_PAD.encode(client_data),
+                authenticator_data: URL_SAFE_NO_PAD.encode(authenticator_data),
+                signature: URL_SAFE_NO_PAD.encode(signature),
+            }
+        }
+    }
+
+    fn config() -> WebAuthnConfig {
+        WebAuthnConfig::new("example.com", "https://example.com")
+    }
+
+    fn credential(authenticator: &TestAuthenticator) -> PasskeyCredential {
+        PasskeyCredential::new(
+            "identity",
+            authenticator.credential_id.clone(),
+            &authenticator.public_key_der(),
+            authenticator.algorithm(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_verify_assertion() {
+        let mut authenticator = TestAuthenticator::new();
+        let credential = credential(&authenticator);
+
+        let assertion = authenticator.assert("example.com", "https://example.com", "abc");
+        assert_eq!(
+            verify_assertion(&config(), &credential, "abc", &assertion).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_verify_es256_assertion() {
+        let mut authenticator = TestAuthenticator::es256();
+        let credential = credential(&authenticator);
+        assert_eq!(credential.algorithm, COSE_ALG_ES256);
+
+        let assertion = authenticator.assert("example.com", "https://example.com", "abc");
+        assert_eq!(
+            verify_assertion(&config(), &credential, "abc", &assertion).unwrap(),
+            1
+        );
+
+        // A signature by another P-256 key is rejected
+        let mut other = TestAuthenticator::es256();
+        other.credential_id = authenticator.credential_id.clone();
+        let forged = other.assert("example.com", "https://example.com", "abc");
+        assert!(matches!(
+            verify_assertion(&config(), &credential, "abc", &forged),
+            Err(AuthError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn test_rejects_mismatches() {
+        let mut authenticator = TestAuthenticator::new();
+        let mut credential = credential(&authenticator);
+        let config = config();
+
+        let wrong_challenge = authenticator.assert("example.com", "https://example.com", "abc");
+        assert!(verify_assertion(&config, &credential, "xyz", &wrong_challenge).is_err());
+
+        let wrong_origin = authenticator.assert("example.com", "https://evil.com", "abc");
+        assert!(verify_assertion(&config, &credential, "abc", &wrong_origin).is_err());
+
+        let wrong_rp = authenticator.assert("evil.com", "https://example.com", "abc");
+        assert!(verify_assertion(&config, &credential, "abc", &wrong_rp).is_err());
+
+        let mut forged = authenticator.assert("example.com", "https://example.com", "abc");
+        forged.signature = URL_SAFE_NO_PAD.encode([0u8; 64]);
+        assert!(matches!(
+            verify_assertion(&config, &credential, "abc", &forged),
+            Err(AuthError::InvalidSignature)
+        ));
+
+        // Replayed counter
+        credential.sign_count = 10;
+        let replay = authenticator.assert("example.com", "https://example.com", "abc");
+        assert!(verify_assertion(&config, &credential, "abc", &replay).is_err());
+
+        // A counter reset to 0 once the authenticator has counted
+        authenticator.counter = u32::MAX;
+        let reset = authenticator.assert("example.com", "https://example.com", "abc");
+        assert!(verify_assertion(&config, &credential, "abc", &reset).is_err());
+    }
+
+    #[test]
+    fn test_registration_rejects_unsupported_algorithms() {
+        let authenticator = TestAuthenticator::new();
+        assert!(matches!(
+            PasskeyCredential::new("identity", "cred", &authenticator.public_key_der(), -257),
+            Err(AuthError::WebAuthn(_))
+        ));
+        // An Ed25519 key registered as ES256
+        assert!(matches!(
+            PasskeyCredential::new(
+                "identity",
+                "cred",
+                &authenticator.public_key_der(),
+                COSE_ALG_ES256
+            ),
+            Err(AuthError::WebAuthn(_))
+        ));
+        assert!(PasskeyCredential::new("identity", "cred", "AAAA", COSE_ALG_EDDSA).is_err());
+    }
+}
@@ -1919,7 +1919,7 @@ async fn run_server(
 
     tokio::select! {
         _ = shutdown => {}
-        _ = signal::ctrl_c() => {
+        _ = termination_signal() => {
             println!();
             println!("{}", "Shutting down...".yellow());
         }
@@ -1933,6 +1933,10 @@ async fn run_server(
         .await
         .context("Failed to save database on shutdown")?;
 
+    db.shutdown()
+        .await
+        .context("Failed to shut down database")?;
+
     println!("{}", "Node stopped.".green());
 
     Ok(())
@@ -1967,29 +1971,52 @@ async fn run_http_server(db_path: &std::path::Path, bind: &str, port: u16) -> Re
     println!("{}", "Server is running. Press Ctrl+C to stop.".green());
     println!();
 
-    // Create and start HTTP server
-    let server = HttpServer::new(db);
-
-    // Handle Ctrl+C for graceful shutdown
-    let shutdown = async {
-        signal::ctrl_c().await.ok();
-        println!();
-        println!("{}", "Shutting down...".yellow());
-    };
+    // Create and start HTTP server; it stops accepting connections once
+    // the database begins shutting down
+    let server = HttpServer::new(db.clone());
+    let mut serving = tokio::spawn(async move { server.bind(&bind_addr).await });
 
     tokio::select! {
-        result = server.bind(&bind_addr) => {
-            if let Err(e) = result {
+        result = &mut serving => {
+            if let Ok(Err(e)) = result {
                 eprintln!("{} {}", "Server error:".red(), e);
             }
+            db.shutdown().await.ok();
+            return Ok(());
         }
-        _ = shutdown => {}
+        _ = termination_signal() => {
+            println!();
+            println!("{}", "Shutting down...".yellow());
+        }
+    }
+
+    // Drain in-flight writes and flush before the process exits
+    if let Err(e) = db.shutdown().await {
+        eprintln!("{} {}", "Shutdown error:".red(), e);
     }
+    serving.await.ok();
 
     println!("{}", "Server stopped.".green());
     Ok(())
 }
 
+/// Wait for Ctrl+C, or SIGTERM as sent by container runtimes on stop.
+async fn termination_signal() {
+    #[cfg(unix)]
+    {
+        let Ok(mut sigterm) = signal::unix::signal(signal::unix::SignalKind::terminate()) else {
+            signal::ctrl_c().await.ok();
+            return;
+        };
+        tokio::select! {
+            _ = signal::ctrl_c() => {}
+            _ = sigterm.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    signal::ctrl_c().await.ok();
+}
+
 /// Parse a duration string like "1h", "1d", "7d" into seconds
 fn parse_duration(s: &str) -> Option<u64> {
     let s = s.trim().to_lowercase();
@@ -0,0 +1,309 @@
+/// koru-bench - Load generator for KoruDelta
+///
+/// Runs a configurable workload against an in-process database and reports
+/// throughput and latency percentiles, for quick before/after comparisons
+/// of performance-sensitive changes. The criterion suite in `benches/`
+/// tracks regressions; this tool explores shapes it does not cover.
+///
+/// Usage:
+///   koru-bench --workload put --ops 100000 --concurrency 16 --namespaces 8
+///   koru-bench --workload mixed --read-ratio 0.9 --path /tmp/bench-db
+///   koru-bench --workload put --path /tmp/bench-db --durability async
+
+// This binary is not supported on WASM targets
+#[cfg(target_arch = "wasm32")]
+compile_error!("The koru-bench binary is not supported on WASM targets.");
+
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+use koru_delta::query::{Filter, Query};
+use koru_delta::{Durability, KoruDelta, PutOptions, Vector, VectorSearchOptions};
+use serde_json::{Value as JsonValue, json};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+#[derive(Parser)]
+#[command(name = "koru-bench")]
+#[command(version, about = "Benchmark KoruDelta with configurable workloads", long_about = None)]
+struct Args {
+    /// Workload to run
+    #[arg(short, long, value_enum, default_value_t = Workload::Put)]
+    workload: Workload,
+
+    /// Total operations to run, across all workers
+    #[arg(short, long, default_value_t = 10_000)]
+    ops: usize,
+
+    /// Concurrent workers
+    #[arg(short, long, default_value_t = 1)]
+    concurrency: usize,
+
+    /// Keys in the dataset (and key space written to)
+    #[arg(short, long, default_value_t = 1_000)]
+    keys: usize,
+
+    /// Namespaces the keys are spread over
+    #[arg(short, long, default_value_t = 1)]
+    namespaces: usize,
+
+    /// Approximate size of each value's payload, in bytes
+    #[arg(long, default_value_t = 64)]
+    value_size: usize,
+
+    /// Fraction of reads in the mixed workload
+    #[arg(long, default_value_t = 0.8)]
+    read_ratio: f64,
+
+    /// Vector dimensions for the vector workload
+    #[arg(long, default_value_t = 128)]
+    dimensions: usize,
+
+    /// Database directory (in-memory when omitted)
+    #[arg(short, long)]
+    path: Option<PathBuf>,
+
+    /// Write durability for persistent databases
+    #[arg(long, value_enum, default_value_t = DurabilityArg::Sync)]
+    durability: DurabilityArg,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Workload {
+    /// Puts spread over the key space
+    Put,
+    /// Gets of existing keys
+    Get,
+    /// Mix of gets and puts (see --read-ratio)
+    Mixed,
+    /// Filtered queries over one namespace
+    Query,
+    /// History of keys with several versions each
+    History,
+    /// Top-10 vector similarity searches
+    Vector,
+    /// Merging every version into a fresh replica (one op per version)
+    Sync,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum DurabilityArg {
+    Sync,
+    Async,
+}
+
+impl From<DurabilityArg> for Durability {
+    fn from(arg: DurabilityArg) -> Self {
+        match arg {
+            DurabilityArg::Sync => Durability::Sync,
+            DurabilityArg::Async => Durability::Async,
+        }
+    }
+}
+
+/// What the workers share.
+struct Bench {
+    db: KoruDelta,
+    keys: usize,
+    namespaces: usize,
+    payload: String,
+    read_ratio: f64,
+    dimensions: usize,
+    options: PutOptions,
+}
+
+impl Bench {
+    fn location(&self, i: usize) -> (String, String) {
+        let key = i % self.keys;
+        (
+            format!("bench{}", key % self.namespaces),
+            format!("key{}", key),
+        )
+    }
+
+    fn value(&self, i: usize) -> JsonValue {
+        json!({"id": i, "group": i % 16, "payload": self.payload})
+    }
+
+    fn vector(&self, seed: usize) -> Vector {
+        let data = (0..self.dimensions)
+            .map(|d| (((seed * 31 + d * 17) % 97) as f32) / 97.0)
+            .collect();
+        Vector::new(data, "bench")
+    }
+
+    async fn put(&self, i: usize) -> Result<()> {
+        let (namespace, key) = self.location(i);
+        self.db
+            .put_with_options(namespace, key, self.value(i), self.options.clone())
+            .await?;
+        Ok(())
+    }
+
+    /// Load the dataset the read workloads run against.
+    async fn populate(&self, workload: Workload) -> Result<()> {
+        match workload {
+            Workload::Put | Workload::Sync => {}
+            Workload::Get | Workload::Mixed | Workload::Query => {
+                for i in 0..self.keys {
+                    self.put(i).await?;
+                }
+            }
+            Workload::History => {
+                for version in 0..10 {
+                    for i in 0..self.keys {
+                        self.put(i + version * self.keys).await?;
+                    }
+                }
+            }
+            Workload::Vector => {
+                let items: Vec<_> = (0..self.keys)
+                    .map(|i| (format!("doc{}", i), self.vector(i), None))
+                    .collect();
+                self.db.embed_batch("docs", items).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Run operation `i` of the workload.
+    async fn op(&self, workload: Workload, i: usize) -> Result<()> {
+        match workload {
+            Workload::Put => self.put(i).await?,
+            Workload::Get => {
+                let (namespace, key) = self.location(i);
+                self.db.get(namespace, key).await?;
+            }
+            Workload::Mixed => {
+                // Deterministic interleaving of reads and writes
+                let read = ((i * 7919) % 1000) as f64 / 1000.0 < self.read_ratio;
+                if read {
+                    let (namespace, key) = self.location(i);
+                    self.db.get(namespace, key).await?;
+                } else {
+                    self.put(i).await?;
+                }
+            }
+            Workload::Query => {
+                let query = Query::new().filter(Filter::eq("group", (i % 16) as i64));
+                self.db.query("bench0", query).await?;
+            }
+            Workload::History => {
+                let (namespace, key) = self.location(i);
+                self.db.history(namespace, key).await?;
+            }
+            Workload::Vector => {
+                let options = VectorSearchOptions::new().top_k(10);
+                self.db
+                    .embed_search(Some("docs"), &self.vector(i), options)
+                    .await?;
+            }
+            Workload::Sync => unreachable!("sync is run as a single batch"),
+        }
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let db = match &args.path {
+        Some(path) => KoruDelta::start_with_path(path).await?,
+        None => KoruDelta::start().await?,
+    };
+    let bench = std::sync::Arc::new(Bench {
+        db,
+        keys: args.keys.max(1),
+        namespaces: args.namespaces.max(1),
+        payload: "x".repeat(args.value_size),
+        read_ratio: args.read_ratio.clamp(0.0, 1.0),
+        dimensions: args.dimensions.max(1),
+        options: PutOptions::new().durability(args.durability.into()),
+    });
+
+    bench.populate(args.workload).await?;
+
+    if let Workload::Sync = args.workload {
+        run_sync(&bench, args.ops).await?;
+    } else {
+        run_workers(&bench, args.workload, args.ops, args.concurrency.max(1)).await?;
+    }
+
+    let bench = std::sync::Arc::try_unwrap(bench)
+        .map_err(|_| anyhow::anyhow!("workers still hold the database"))?;
+    bench.db.shutdown().await?;
+    Ok(())
+}
+
+/// Run `ops` operations spread over `concurrency` workers and report.
+async fn run_workers(
+    bench: &std::sync::Arc<Bench>,
+    workload: Workload,
+    ops: usize,
+    concurrency: usize,
+) -> Result<()> {
+    let started = Instant::now();
+    let workers: Vec<_> = (0..concurrency)
+        .map(|worker| {
+            let bench = std::sync::Arc::clone(bench);
+            tokio::spawn(async move {
+                let mut latencies = Vec::with_capacity(ops / concurrency + 1);
+                for i in (worker..ops).step_by(concurrency) {
+                    let start = Instant::now();
+                    bench.op(workload, i).await?;
+                    latencies.push(start.elapsed());
+                }
+                anyhow::Ok(latencies)
+            })
+        })
+        .collect();
+
+    let mut latencies = Vec::with_capacity(ops);
+    for worker in workers {
+        latencies.extend(worker.await??);
+    }
+    report(started.elapsed(), latencies);
+    Ok(())
+}
+
+/// Merge `ops` versions from a source database into a fresh replica.
+async fn run_sync(bench: &Bench, ops: usize) -> Result<()> {
+    for i in 0..ops {
+        bench.put(i).await?;
+    }
+    let versions = bench.db.storage().all_versions();
+    let replica = KoruDelta::start().await?;
+
+    let started = Instant::now();
+    let merged = replica.merge_versions(versions).await;
+    let elapsed = started.elapsed();
+    println!("merged:      {} versions", merged.len());
+    println!("elapsed:     {:.2?}", elapsed);
+    println!(
+        "throughput:  {:.0} versions/s",
+        merged.len() as f64 / elapsed.as_secs_f64()
+    );
+    Ok(())
+}
+
+/// Print throughput and latency percentiles.
+fn report(elapsed: Duration, mut latencies: Vec<Duration>) {
+    latencies.sort();
+    let percentile = |p: f64| {
+        let index = ((latencies.len() as f64 * p).ceil() as usize).saturating_sub(1);
+        latencies.get(index).copied().unwrap_or_default()
+    };
+
+    println!("operations:  {}", latencies.len());
+    println!("elapsed:     {:.2?}", elapsed);
+    println!(
+        "throughput:  {:.0} ops/s",
+        latencies.len() as f64 / elapsed.as_secs_f64()
+    );
+    println!("p50:         {:.2?}", percentile(0.50));
+    println!("p90:         {:.2?}", percentile(0.90));
+    println!("p99:         {:.2?}", percentile(0.99));
+    println!(
+        "max:         {:.2?}",
+        latencies.last().copied().unwrap_or_default()
+    );
+}
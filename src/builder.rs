@@ -0,0 +1,281 @@
+/// Explicit configuration for opening a database.
+///
+/// [`KoruDelta::start`](crate::KoruDelta::start) and
+/// [`start_with_path`](crate::KoruDelta::start_with_path) pick every setting
+/// for you. Deployments that need to control where data lives, how much
+/// memory it may use, which background processes run, or which cluster the
+/// node joins say so up front with a builder:
+///
+/// ```ignore
+/// let db = KoruDelta::builder()
+///     .path("/var/lib/koru")
+///     .memory_limit(2048)
+///     .background_processes(false)
+///     .cluster(
+///         ClusterConfig::new()
+///             .bind_addr("0.0.0.0:7878".parse()?)
+///             .join("10.0.0.2:7878".parse()?),
+///     )
+///     .open()
+///     .await?;
+/// ```
+///
+/// Settings not given keep the defaults `start` uses. A configuration
+/// loaded with [`CoreConfig::from_file`] or [`CoreConfig::from_env`] also
+/// supplies the data directory and cluster addresses, unless
+/// [`path`](KoruDeltaBuilder::path) or [`cluster`](KoruDeltaBuilder::cluster)
+/// override them.
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::cluster::{ClusterConfig, ClusterNode};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::core::NetworkConfig;
+use crate::core::{CoreConfig, KoruDeltaGeneric};
+use crate::error::DeltaResult;
+use crate::runtime::{DefaultRuntime, Runtime};
+
+/// Builder for a [`KoruDelta`](crate::KoruDelta) instance.
+///
+/// Created with [`KoruDelta::builder`](crate::KoruDelta::builder).
+#[derive(Debug, Clone)]
+pub struct KoruDeltaBuilder<R: Runtime = DefaultRuntime> {
+    config: CoreConfig,
+    #[cfg(not(target_arch = "wasm32"))]
+    path: Option<PathBuf>,
+    #[cfg(not(target_arch = "wasm32"))]
+    cluster: Option<ClusterConfig>,
+    runtime: Option<R>,
+}
+
+impl<R: Runtime> Default for KoruDeltaBuilder<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: Runtime> KoruDeltaBuilder<R> {
+    /// A builder with default settings: in memory, no cluster.
+    pub fn new() -> Self {
+        Self {
+            config: CoreConfig::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            path: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            cluster: None,
+            runtime: None,
+        }
+    }
+
+    /// Replace the whole configuration. Later setters adjust it further.
+    pub fn config(mut self, config: CoreConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Persist to the data directory at `path`, creating it if needed.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Limit memory usage to `megabytes` (0 = unlimited).
+    pub fn memory_limit(mut self, megabytes: usize) -> Self {
+        self.config.limits.max_memory_mb = megabytes;
+        self
+    }
+
+    /// Limit disk usage to `megabytes` (0 = unlimited).
+    pub fn disk_limit(mut self, megabytes: usize) -> Self {
+        self.config.limits.max_disk_mb = megabytes;
+        self
+    }
+
+    /// Stop queries that run longer than `timeout` (zero = unlimited).
+    pub fn query_timeout(mut self, timeout: Duration) -> Self {
+        self.config.limits.query_timeout = timeout;
+        self
+    }
+
+    /// Number of entries kept in the hot memory tier.
+    pub fn hot_capacity(mut self, entries: usize) -> Self {
+        self.config.memory.hot_capacity = entries;
+        self
+    }
+
+    /// Whether background processes (consolidation, distillation, lifecycle
+    /// checks, ...) run.
+    pub fn background_processes(mut self, enabled: bool) -> Self {
+        self.config.processes.enabled = enabled;
+        self
+    }
+
+    /// Start a cluster node with `config` and broadcast writes to its peers.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn cluster(mut self, config: ClusterConfig) -> Self {
+        self.cluster = Some(config);
+        self
+    }
+
+    /// Run tasks on `runtime` instead of a new default one.
+    pub fn runtime(mut self, runtime: R) -> Self {
+        self.runtime = Some(runtime);
+        self
+    }
+
+    /// Open the database.
+    ///
+    /// Loads existing data when a path is set and holds data, and starts
+    /// the cluster node, if any, before returning. Seeds from the
+    /// configuration are tried in order until one can be joined. If the
+    /// node fails to start, the database is shut down again and the error
+    /// returned.
+    pub async fn open(self) -> DeltaResult<KoruDeltaGeneric<R>> {
+        let runtime = self.runtime.unwrap_or_else(R::new);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let clusters = match self.cluster {
+            Some(cluster) => vec![cluster],
+            None => configured_clusters(&self.config.network),
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        let path = self.path.or_else(|| self.config.storage.path.clone());
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let db = match path {
+            Some(path) => {
+                KoruDeltaGeneric::open_with_path(path, self.config, runtime.clone()).await?
+            }
+            None => KoruDeltaGeneric::new_with_runtime(self.config, runtime.clone()).await?,
+        };
+        #[cfg(target_arch = "wasm32")]
+        let db = KoruDeltaGeneric::new_with_runtime(self.config, runtime).await?;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if !clusters.is_empty() {
+            let mut last_error = None;
+            for cluster in clusters {
+                let node = Arc::new(ClusterNode::with_runtime(
+                    Arc::clone(db.storage()),
+                    Arc::clone(db.engine()),
+                    cluster,
+                    runtime.clone(),
+                ));
+                match node.start().await {
+                    Ok(()) => return Ok(db.with_cluster(node)),
+                    Err(e) => last_error = Some(e),
+                }
+            }
+            // Release the data directory's lock before reporting
+            let _ = db.shutdown().await;
+            return Err(last_error.expect("at least one cluster attempt"));
+        }
+
+        Ok(db)
+    }
+}
+
+/// Cluster configurations to try, in order, for the addresses in `network`:
+/// one per seed, a standalone node if there are no seeds, or none at all if
+/// no cluster address is configured.
+#[cfg(not(target_arch = "wasm32"))]
+fn configured_clusters(network: &NetworkConfig) -> Vec<ClusterConfig> {
+    if network.cluster_bind.is_none() && network.cluster_seeds.is_empty() {
+        return Vec::new();
+    }
+    let mut base = ClusterConfig::new();
+    if let Some(bind) = network.cluster_bind {
+        base = base.bind_addr(bind);
+    }
+    if network.cluster_seeds.is_empty() {
+        return vec![base];
+    }
+    network
+        .cluster_seeds
+        .iter()
+        .map(|seed| base.clone().join(*seed))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KoruDelta;
+    use serde_json::json;
+    use std::net::SocketAddr;
+
+    #[tokio::test]
+    async fn test_builder_in_memory() {
+        let db = KoruDelta::builder()
+            .memory_limit(64)
+            .hot_capacity(10)
+            .background_processes(false)
+            .open()
+            .await
+            .unwrap();
+
+        assert!(!db.is_persistent());
+        assert!(db.cluster().is_none());
+        assert_eq!(db.config().limits.max_memory_mb, 64);
+        assert_eq!(db.config().memory.hot_capacity, 10);
+        assert!(!db.config().processes.enabled);
+        db.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_builder_opens_path() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let db = KoruDelta::builder()
+            .path(dir.path())
+            .background_processes(false)
+            .open()
+            .await
+            .unwrap();
+        assert!(db.is_persistent());
+        db.put("users", "alice", json!({"age": 30})).await.unwrap();
+        db.shutdown().await.unwrap();
+
+        let db = KoruDelta::builder().path(dir.path()).open().await.unwrap();
+        assert_eq!(
+            db.get("users", "alice").await.unwrap().value(),
+            &json!({"age": 30})
+        );
+        db.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_builder_uses_configured_path_and_cluster() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = CoreConfig::default();
+        config.processes.enabled = false;
+        config.storage.path = Some(dir.path().to_path_buf());
+        config.network.cluster_bind = Some("127.0.0.1:0".parse().unwrap());
+
+        let db = KoruDelta::builder().config(config).open().await.unwrap();
+        assert!(db.is_persistent());
+        assert!(db.cluster().is_some());
+        db.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_builder_starts_cluster() {
+        let addr: SocketAddr = "127.0.0.1:0".parse().unwrap();
+        let db = KoruDelta::builder()
+            .background_processes(false)
+            .cluster(ClusterConfig::new().bind_addr(addr))
+            .open()
+            .await
+            .unwrap();
+
+        let node = Arc::clone(db.cluster().unwrap());
+        assert!(node.is_running().await);
+        db.shutdown().await.unwrap();
+        assert!(!node.is_running().await);
+    }
+}
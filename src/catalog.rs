@@ -0,0 +1,172 @@
+/// System catalog.
+///
+/// Read-only `_system.*` namespaces that describe the database itself, so
+/// tooling can introspect it with the same [`query`](crate::KoruDelta::query)
+/// API used for user data:
+///
+/// | Namespace | One record per |
+/// |---|---|
+/// | `_system.schemas` | namespace, with its key count and the JSON types seen for each top-level field |
+/// | `_system.indexes` | vector index, vector configuration of a namespace, and range index |
+/// | `_system.views` | materialized view |
+/// | `_system.subscriptions` | active subscription |
+/// | `_system.capabilities` | granted capability |
+/// | `_system.cluster` | cluster member, this node included |
+///
+/// Records are generated from live state on every query, so they are never
+/// stale and cannot be written.
+///
+/// # Example
+///
+/// ```ignore
+/// let big = db
+///     .query("_system.schemas", Query::new().filter(Filter::gt("keys", 1000)))
+///     .await?;
+/// ```
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::{Value as JsonValue, json};
+
+use crate::error::{DeltaError, DeltaResult};
+
+/// Prefix of catalog namespaces.
+pub const CATALOG_PREFIX: &str = "_system.";
+
+/// A catalog namespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CatalogTable {
+    /// `_system.schemas`
+    Schemas,
+    /// `_system.indexes`
+    Indexes,
+    /// `_system.views`
+    Views,
+    /// `_system.subscriptions`
+    Subscriptions,
+    /// `_system.capabilities`
+    Capabilities,
+    /// `_system.cluster`
+    Cluster,
+}
+
+impl CatalogTable {
+    /// Every catalog namespace.
+    pub const ALL: [CatalogTable; 6] = [
+        Self::Schemas,
+        Self::Indexes,
+        Self::Views,
+        Self::Subscriptions,
+        Self::Capabilities,
+        Self::Cluster,
+    ];
+
+    /// The table for a namespace, if it is a catalog namespace.
+    pub fn from_namespace(namespace: &str) -> Option<Self> {
+        let name = namespace.strip_prefix(CATALOG_PREFIX)?;
+        Self::ALL.into_iter().find(|table| table.name() == name)
+    }
+
+    /// Namespace of this table, e.g. `_system.views`.
+    pub fn namespace(self) -> String {
+        format!("{}{}", CATALOG_PREFIX, self.name())
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Schemas => "schemas",
+            Self::Indexes => "indexes",
+            Self::Views => "views",
+            Self::Subscriptions => "subscriptions",
+            Self::Capabilities => "capabilities",
+            Self::Cluster => "cluster",
+        }
+    }
+}
+
+/// Reject writes to catalog namespaces.
+pub(crate) fn check_writable(namespace: &str) -> DeltaResult<()> {
+    if CatalogTable::from_namespace(namespace).is_some() {
+        return Err(DeltaError::InvalidData {
+            reason: format!("'{}' is a read-only system catalog namespace", namespace),
+        });
+    }
+    Ok(())
+}
+
+/// Schema record for a namespace, inferred from its current values.
+///
+/// `{"namespace", "keys", "fields": {"<field>": ["number", "string"]}}`;
+/// deleted keys are not counted and non-object values have no fields.
+pub(crate) fn infer_schema<'a>(
+    namespace: &str,
+    values: impl IntoIterator<Item = &'a JsonValue>,
+) -> JsonValue {
+    let mut keys = 0;
+    let mut fields: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for value in values {
+        if value.is_null() {
+            continue;
+        }
+        keys += 1;
+        if let JsonValue::Object(map) = value {
+            for (field, value) in map {
+                fields.entry(field).or_default().insert(type_name(value));
+            }
+        }
+    }
+    json!({
+        "namespace": namespace,
+        "keys": keys,
+        "fields": fields,
+    })
+}
+
+fn type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_namespaces_and_schema_inference() {
+        assert_eq!(
+            CatalogTable::from_namespace("_system.views"),
+            Some(CatalogTable::Views)
+        );
+        assert_eq!(CatalogTable::from_namespace("_system"), None);
+        assert_eq!(CatalogTable::from_namespace("_system.nope"), None);
+        for table in CatalogTable::ALL {
+            assert_eq!(
+                CatalogTable::from_namespace(&table.namespace()),
+                Some(table)
+            );
+        }
+
+        let values = [
+            json!({"name": "Ada", "age": 36}),
+            json!({"name": "Tim", "age": "nine", "tags": []}),
+            JsonValue::Null,
+        ];
+        assert_eq!(
+            infer_schema("users", &values),
+            json!({
+                "namespace": "users",
+                "keys": 2,
+                "fields": {
+                    "age": ["number", "string"],
+                    "name": ["string"],
+                    "tags": ["array"],
+                },
+            })
+        );
+    }
+}
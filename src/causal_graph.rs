@@ -26,7 +26,9 @@
 /// - `ancestors`: Find all distinctions that led to this one
 /// - `descendants`: Find all distinctions that flowed from this one
 /// - `lca`: Find the least common ancestor (for merging)
+/// - `is_connected` / `shortest_path` / `within_hops`: Reachability queries
 /// - `frontier`: Find the current "leaves" of the graph
+/// - `export_dot` / `export_graphml`: Render a neighbourhood for visualization
 ///
 /// ## Biological Metaphor
 ///
@@ -39,12 +41,22 @@ use crate::engine::{FieldHandle, SharedEngine};
 use crate::roots::RootType;
 use dashmap::{DashMap, DashSet};
 use koru_lambda_core::{Canonicalizable, Distinction, DistinctionEngine, LocalCausalAgent};
-use std::collections::{HashSet, VecDeque};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::Write as _;
 use std::sync::{Arc, RwLock};
 
 /// A unique identifier for a distinction in the causal graph.
 pub type DistinctionId = String;
 
+/// Output format for causal graph exports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// Graphviz DOT (render with `dot -Tsvg`)
+    Dot,
+    /// GraphML XML (for Gephi, yEd, Cytoscape)
+    GraphMl,
+}
+
 /// The Lineage Agent tracking how distinctions emerge from one another with LCA architecture.
 ///
 /// This is the foundation of the distinction-driven system. Every synthesis
@@ -331,6 +343,92 @@ impl LineageAgent {
         })
     }
 
+    /// Check if two distinctions are linked by any chain of causal edges.
+    ///
+    /// Edges are followed in both directions, so siblings sharing an
+    /// ancestor are connected.
+    pub fn is_connected(&self, a: impl AsRef<str>, b: impl AsRef<str>) -> bool {
+        self.shortest_path(a, b).is_some()
+    }
+
+    /// Find a shortest chain of causal edges from `a` to `b`.
+    ///
+    /// Edges are followed in both directions. The path starts with `a` and
+    /// ends with `b`; each consecutive pair is a parent/child edge. Returns
+    /// `None` if either node is unknown or they are not connected.
+    pub fn shortest_path(
+        &self,
+        a: impl AsRef<str>,
+        b: impl AsRef<str>,
+    ) -> Option<Vec<DistinctionId>> {
+        let a = a.as_ref();
+        let b = b.as_ref();
+        if !self.nodes.contains(a) || !self.nodes.contains(b) {
+            return None;
+        }
+
+        let mut previous: HashMap<DistinctionId, DistinctionId> = HashMap::new();
+        let mut visited = HashSet::from([a.to_string()]);
+        let mut queue = VecDeque::from([a.to_string()]);
+
+        while let Some(current) = queue.pop_front() {
+            if current == b {
+                let mut path = vec![current];
+                while let Some(step) = previous.get(path.last().unwrap()) {
+                    path.push(step.clone());
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for neighbour in self.linked(&current) {
+                if visited.insert(neighbour.clone()) {
+                    previous.insert(neighbour.clone(), current.clone());
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Get every distinction within `k` causal hops of `id`, with its distance.
+    ///
+    /// Edges are followed in both directions. Results are ordered by
+    /// distance and exclude `id` itself.
+    pub fn within_hops(&self, id: impl AsRef<str>, k: usize) -> Vec<(DistinctionId, usize)> {
+        let id = id.as_ref();
+        if !self.nodes.contains(id) {
+            return Vec::new();
+        }
+
+        let mut found = Vec::new();
+        let mut visited = HashSet::from([id.to_string()]);
+        let mut queue = VecDeque::from([(id.to_string(), 0)]);
+
+        while let Some((current, distance)) = queue.pop_front() {
+            if distance == k {
+                continue;
+            }
+            for neighbour in self.linked(&current) {
+                if visited.insert(neighbour.clone()) {
+                    found.push((neighbour.clone(), distance + 1));
+                    queue.push_back((neighbour, distance + 1));
+                }
+            }
+        }
+
+        found
+    }
+
+    /// Direct parents and children of a distinction.
+    fn linked(&self, id: &str) -> Vec<DistinctionId> {
+        let mut linked = self.parents.get(id).map(|p| p.clone()).unwrap_or_default();
+        if let Some(children) = self.children.get(id) {
+            linked.extend(children.iter().cloned());
+        }
+        linked
+    }
+
     /// Get the causal frontier (all leaf nodes).
     ///
     /// The frontier consists of distinctions that have no children -
@@ -420,6 +518,214 @@ impl LineageAgent {
         self.children.get(id).map(|c| c.clone())
     }
 
+    /// Render the neighbourhood of `root` as a Graphviz DOT digraph.
+    ///
+    /// Includes every ancestor and descendant within `depth` hops of `root`,
+    /// with edges pointing from cause to effect. Nodes are labelled with
+    /// their distinction ID; use [`export_dot_labeled`](Self::export_dot_labeled)
+    /// to supply friendlier labels. An unknown `root` yields an empty graph.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use koru_delta::causal_graph::LineageAgent;
+    /// use koru_delta::engine::SharedEngine;
+    /// let engine = SharedEngine::new();
+    /// let lineage = LineageAgent::new(&engine);
+    /// lineage.add_node("a".to_string());
+    /// lineage.add_with_parents("b".to_string(), vec!["a".to_string()]);
+    /// let dot = lineage.export_dot("b", 1);
+    /// assert!(dot.contains("\"a\" -> \"b\";"));
+    /// ```
+    pub fn export_dot(&self, root: impl AsRef<str>, depth: usize) -> String {
+        self.export_dot_labeled(root, depth, |_| None)
+    }
+
+    /// Render the neighbourhood of `root` as DOT, labelling nodes with `label`.
+    ///
+    /// Nodes for which `label` returns `None` are labelled with their ID.
+    /// The root node is drawn bold.
+    pub fn export_dot_labeled(
+        &self,
+        root: impl AsRef<str>,
+        depth: usize,
+        label: impl Fn(&str) -> Option<String>,
+    ) -> String {
+        let root = root.as_ref();
+        let (nodes, edges) = self.neighbourhood(root, depth);
+
+        let mut dot = String::from("digraph lineage {\n    rankdir=LR;\n    node [shape=box];\n");
+        for node in &nodes {
+            let text = match label(node) {
+                Some(label) => format!("{}\\n{}", escape_dot(&label), escape_dot(node)),
+                None => escape_dot(node),
+            };
+            let style = if node == root { ", style=bold" } else { "" };
+            let _ = writeln!(
+                dot,
+                "    \"{}\" [label=\"{}\"{}];",
+                escape_dot(node),
+                text,
+                style
+            );
+        }
+        for (parent, child) in &edges {
+            let _ = writeln!(
+                dot,
+                "    \"{}\" -> \"{}\";",
+                escape_dot(parent),
+                escape_dot(child)
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Render the neighbourhood of `root` as a GraphML document.
+    ///
+    /// Covers the same nodes and edges as [`export_dot`](Self::export_dot).
+    pub fn export_graphml(&self, root: impl AsRef<str>, depth: usize) -> String {
+        self.export_graphml_labeled(root, depth, |_| None)
+    }
+
+    /// Render the neighbourhood of `root` as GraphML, labelling nodes with `label`.
+    ///
+    /// Labels are emitted as a `label` data attribute, and the root node is
+    /// flagged with `root = true`.
+    pub fn export_graphml_labeled(
+        &self,
+        root: impl AsRef<str>,
+        depth: usize,
+        label: impl Fn(&str) -> Option<String>,
+    ) -> String {
+        let root = root.as_ref();
+        let (nodes, edges) = self.neighbourhood(root, depth);
+
+        let mut xml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n",
+            "  <key id=\"root\" for=\"node\" attr.name=\"root\" attr.type=\"boolean\">\n",
+            "    <default>false</default>\n",
+            "  </key>\n",
+            "  <graph id=\"lineage\" edgedefault=\"directed\">\n",
+        ));
+        for node in &nodes {
+            let _ = writeln!(xml, "    <node id=\"{}\">", escape_xml(node));
+            if let Some(label) = label(node) {
+                let _ = writeln!(
+                    xml,
+                    "      <data key=\"label\">{}</data>",
+                    escape_xml(&label)
+                );
+            }
+            if node == root {
+                xml.push_str("      <data key=\"root\">true</data>\n");
+            }
+            xml.push_str("    </node>\n");
+        }
+        for (parent, child) in &edges {
+            let _ = writeln!(
+                xml,
+                "    <edge source=\"{}\" target=\"{}\"/>",
+                escape_xml(parent),
+                escape_xml(child)
+            );
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+
+    /// Render the neighbourhood of `root` in the given format.
+    pub fn export(
+        &self,
+        root: impl AsRef<str>,
+        depth: usize,
+        format: GraphFormat,
+        label: impl Fn(&str) -> Option<String>,
+    ) -> String {
+        match format {
+            GraphFormat::Dot => self.export_dot_labeled(root, depth, label),
+            GraphFormat::GraphMl => self.export_graphml_labeled(root, depth, label),
+        }
+    }
+
+    /// Collect the nodes within `depth` hops of `root`, following parent and
+    /// child links outward, plus the parent → child edges between them.
+    ///
+    /// Both sets are sorted so exports are deterministic.
+    fn neighbourhood(
+        &self,
+        root: &str,
+        depth: usize,
+    ) -> (
+        BTreeSet<DistinctionId>,
+        BTreeSet<(DistinctionId, DistinctionId)>,
+    ) {
+        let mut nodes = BTreeSet::new();
+        let mut edges = BTreeSet::new();
+        if !self.nodes.contains(root) {
+            return (nodes, edges);
+        }
+        nodes.insert(root.to_string());
+
+        // Ancestors and descendants are walked separately so a sibling
+        // (parent's other child) is not pulled in
+        for upward in [true, false] {
+            let links = if upward {
+                &self.parents
+            } else {
+                &self.children
+            };
+            let mut queue = VecDeque::from([(root.to_string(), 0)]);
+            let mut visited = HashSet::from([root.to_string()]);
+
+            while let Some((current, distance)) = queue.pop_front() {
+                if distance == depth {
+                    continue;
+                }
+                let Some(next) = links.get(&current) else {
+                    continue;
+                };
+                for neighbour in next.iter() {
+                    if upward {
+                        edges.insert((neighbour.clone(), current.clone()));
+                    } else {
+                        edges.insert((current.clone(), neighbour.clone()));
+                    }
+                    if visited.insert(neighbour.clone()) {
+                        nodes.insert(neighbour.clone());
+                        queue.push_back((neighbour.clone(), distance + 1));
+                    }
+                }
+            }
+        }
+
+        (nodes, edges)
+    }
+
+    /// Remove a distinction and every edge touching it.
+    ///
+    /// Used by garbage collection once nothing live can reach the node.
+    pub fn remove(&self, id: impl AsRef<str>) {
+        let id = id.as_ref();
+        if let Some((_, parents)) = self.parents.remove(id) {
+            for parent in parents {
+                if let Some(mut children) = self.children.get_mut(&parent) {
+                    children.retain(|child| child != id);
+                }
+            }
+        }
+        if let Some((_, children)) = self.children.remove(id) {
+            for child in children {
+                if let Some(mut parents) = self.parents.get_mut(&child) {
+                    parents.retain(|parent| parent != id);
+                }
+            }
+        }
+        self.nodes.remove(id);
+    }
+
     /// Increment the epoch (for garbage collection).
     pub fn increment_epoch(&self) -> u64 {
         self.epoch.fetch_add(1, std::sync::atomic::Ordering::SeqCst)
@@ -448,6 +754,22 @@ impl LineageAgent {
     }
 }
 
+/// Escape a string for use inside a quoted DOT identifier or label.
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Escape a string for use in XML text or attribute values.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
 impl Default for LineageAgent {
     fn default() -> Self {
         // Note: This requires a SharedEngine, so we panic if called directly
@@ -670,4 +992,88 @@ mod tests {
         agent.update_local_root(new_root.clone());
         assert_eq!(agent.get_current_root().id(), new_root.id());
     }
+
+    #[test]
+    fn test_remove_drops_edges() {
+        let engine = create_test_engine();
+        let lineage = LineageAgent::new(&engine);
+        lineage.add_node("a".to_string());
+        lineage.add_with_parents("b".to_string(), vec!["a".to_string()]);
+        lineage.add_with_parents("c".to_string(), vec!["b".to_string()]);
+
+        lineage.remove("b");
+        assert!(!lineage.contains("b"));
+        assert_eq!(lineage.get_children("a"), Some(vec![]));
+        assert_eq!(lineage.get_parents("c"), Some(vec![]));
+        assert_eq!(lineage.edge_count(), 0);
+    }
+
+    #[test]
+    fn test_reachability_queries() {
+        let engine = create_test_engine();
+        let lineage = LineageAgent::new(&engine);
+        lineage.add_node("a".to_string());
+        lineage.add_with_parents("b".to_string(), vec!["a".to_string()]);
+        lineage.add_with_parents("c".to_string(), vec!["a".to_string()]);
+        lineage.add_with_parents("d".to_string(), vec!["c".to_string()]);
+        lineage.add_node("island".to_string());
+
+        assert!(lineage.is_connected("b", "d"));
+        assert!(!lineage.is_connected("b", "island"));
+        assert!(!lineage.is_connected("b", "missing"));
+
+        assert_eq!(
+            lineage.shortest_path("b", "d"),
+            Some(vec![
+                "b".to_string(),
+                "a".to_string(),
+                "c".to_string(),
+                "d".to_string()
+            ])
+        );
+        assert_eq!(lineage.shortest_path("a", "a"), Some(vec!["a".to_string()]));
+
+        let mut hops = lineage.within_hops("a", 1);
+        hops.sort();
+        assert_eq!(hops, vec![("b".to_string(), 1), ("c".to_string(), 1)]);
+        assert_eq!(lineage.within_hops("a", 2).len(), 3);
+        assert!(lineage.within_hops("a", 0).is_empty());
+    }
+
+    #[test]
+    fn test_export_dot_respects_depth() {
+        let engine = create_test_engine();
+        let lineage = LineageAgent::new(&engine);
+        lineage.add_node("a".to_string());
+        lineage.add_with_parents("b".to_string(), vec!["a".to_string()]);
+        lineage.add_with_parents("c".to_string(), vec!["b".to_string()]);
+        lineage.add_with_parents("d".to_string(), vec!["c".to_string()]);
+
+        let dot = lineage.export_dot_labeled("b", 1, |id| {
+            (id == "c").then(|| "users/\"alice\"".to_string())
+        });
+        assert!(dot.starts_with("digraph lineage {"));
+        assert!(dot.contains("\"a\" -> \"b\";"));
+        assert!(dot.contains("\"b\" -> \"c\";"));
+        assert!(dot.contains("\"b\" [label=\"b\", style=bold];"));
+        assert!(dot.contains("label=\"users/\\\"alice\\\"\\nc\""));
+        assert!(!dot.contains("\"d\""));
+
+        assert!(lineage.export_dot("b", 2).contains("\"c\" -> \"d\";"));
+        assert!(!lineage.export_dot("missing", 3).contains("->"));
+    }
+
+    #[test]
+    fn test_export_graphml() {
+        let engine = create_test_engine();
+        let lineage = LineageAgent::new(&engine);
+        lineage.add_node("a".to_string());
+        lineage.add_with_parents("b&c".to_string(), vec!["a".to_string()]);
+
+        let xml = lineage.export("a", 1, GraphFormat::GraphMl, |id| Some(format!("<{}>", id)));
+        assert!(xml.contains("<node id=\"b&amp;c\">"));
+        assert!(xml.contains("<data key=\"label\">&lt;a&gt;</data>"));
+        assert!(xml.contains("<edge source=\"a\" target=\"b&amp;c\"/>"));
+        assert_eq!(xml.matches("<data key=\"root\">true</data>").count(), 1);
+    }
 }
@@ -6,6 +6,9 @@
 /// - Peer tracking and discovery
 /// - Gossip protocol for cluster membership
 /// - Cluster state management
+/// - Clock-skew detection between peers
+/// - Per-peer anti-entropy schedules
+/// - Phi-accrual failure detection and peer health scores
 ///
 /// # Design
 ///
@@ -14,11 +17,22 @@
 /// - Writes are propagated to all peers
 /// - Eventually consistent with causal ordering
 /// - Nodes can join/leave at any time
+///
+/// Time-travel reads order versions by their wall-clock timestamps, so a
+/// peer whose clock is off silently reorders history. Every heartbeat
+/// therefore estimates the peer's clock offset from its `Pong` timestamp;
+/// offsets beyond [`ClusterConfig::max_clock_skew`] are logged, reported in
+/// [`ClusterStatus`] and published as a [`ClusterEvent`].
+///
+/// Multi-node scenarios can be run deterministically, without sockets, in
+/// [`simulation`].
 use crate::error::{DeltaError, DeltaResult};
+use crate::mapper::MappingVersion;
 use crate::network::{Connection, DEFAULT_PORT, Listener, Message, NodeId, PeerInfo, PeerStatus};
-use crate::storage::CausalStorage;
-use crate::types::{FullKey, VectorClock, VersionedValue};
-use chrono::Utc;
+use crate::runtime::{DefaultRuntime, Instant, Runtime};
+use crate::storage::{CONFLICT_NAMESPACE, CausalStorage};
+use crate::types::{FullKey, Tombstone, VectorClock, VersionedValue};
+use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use koru_lambda_core::DistinctionEngine;
 use std::collections::HashMap;
@@ -26,7 +40,15 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{RwLock, broadcast};
-use tokio::time::interval;
+use tracing::Instrument;
+
+pub mod failure_detector;
+pub mod simulation;
+
+use failure_detector::{FailureDetectorConfig, PhiAccrualDetector};
+
+/// Finest resolution of the anti-entropy scheduler.
+const MIN_SYNC_TICK: Duration = Duration::from_millis(100);
 
 /// Configuration for a cluster node.
 #[derive(Debug, Clone)]
@@ -46,6 +68,16 @@ pub struct ClusterConfig {
     pub quorum_size: usize,
     /// Whether to require quorum for writes (default: false).
     pub require_quorum_for_writes: bool,
+    /// Largest tolerated offset between our clock and a peer's before it is
+    /// reported as skewed (default: 1 second).
+    pub max_clock_skew: Duration,
+    /// Interval between anti-entropy rounds with peers that have no policy
+    /// in `peer_sync` (default: 30 seconds).
+    pub sync_interval: Duration,
+    /// Anti-entropy schedules for specific peers, by address.
+    pub peer_sync: HashMap<SocketAddr, PeerSyncPolicy>,
+    /// When unanswered heartbeats mark a peer unreachable.
+    pub failure_detector: FailureDetectorConfig,
 }
 
 impl Default for ClusterConfig {
@@ -58,6 +90,10 @@ impl Default for ClusterConfig {
             connection_timeout: Duration::from_secs(5),
             quorum_size: 1,                   // Default: single node is sufficient
             require_quorum_for_writes: false, // Default: allow writes without quorum
+            max_clock_skew: Duration::from_secs(1),
+            sync_interval: Duration::from_secs(30),
+            peer_sync: HashMap::new(),
+            failure_detector: FailureDetectorConfig::default(),
         }
     }
 }
@@ -79,6 +115,127 @@ impl ClusterConfig {
         self.join_addr = Some(addr);
         self
     }
+
+    /// Set the largest tolerated clock offset to a peer.
+    pub fn max_clock_skew(mut self, skew: Duration) -> Self {
+        self.max_clock_skew = skew;
+        self
+    }
+
+    /// Set the anti-entropy interval for peers without their own policy.
+    pub fn sync_interval(mut self, interval: Duration) -> Self {
+        self.sync_interval = interval;
+        self
+    }
+
+    /// Sync with the peer at `addr` on its own schedule.
+    pub fn peer_sync(mut self, addr: SocketAddr, policy: PeerSyncPolicy) -> Self {
+        self.peer_sync.insert(addr, policy);
+        self
+    }
+
+    /// Set the failure detector tuning.
+    pub fn failure_detector(mut self, config: FailureDetectorConfig) -> Self {
+        self.failure_detector = config;
+        self
+    }
+
+    /// The schedule for the peer at `addr`.
+    pub fn sync_policy(&self, addr: &SocketAddr) -> PeerSyncPolicy {
+        self.peer_sync
+            .get(addr)
+            .copied()
+            .unwrap_or(PeerSyncPolicy::every(self.sync_interval))
+    }
+
+    /// How often the scheduler checks for due syncs: the shortest interval
+    /// configured, but no finer than [`MIN_SYNC_TICK`].
+    fn sync_tick(&self) -> Duration {
+        self.peer_sync
+            .values()
+            .map(|policy| policy.interval)
+            .fold(self.sync_interval, Duration::min)
+            .max(MIN_SYNC_TICK)
+    }
+}
+
+/// How often, and in what order, to reconcile with one peer.
+///
+/// # Example
+///
+/// ```ignore
+/// // Keep the cloud hub close, the other edge nodes loosely
+/// let config = ClusterConfig::new()
+///     .sync_interval(Duration::from_secs(300))
+///     .peer_sync(hub, PeerSyncPolicy::every(Duration::from_secs(5)).priority(10));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerSyncPolicy {
+    /// Time between anti-entropy rounds with the peer.
+    pub interval: Duration,
+    /// Peers due at the same time are synced highest priority first
+    /// (default: 0).
+    pub priority: u8,
+}
+
+impl PeerSyncPolicy {
+    /// Sync every `interval`, at the default priority.
+    pub fn every(interval: Duration) -> Self {
+        Self {
+            interval,
+            priority: 0,
+        }
+    }
+
+    /// Set the priority.
+    pub fn priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+/// A peer's clock offset, estimated from its latest heartbeat.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClockOffset {
+    /// The peer measured.
+    pub node_id: NodeId,
+    /// Peer clock minus ours in milliseconds; positive when the peer is ahead.
+    pub offset_ms: i64,
+    /// Round trip of the heartbeat in milliseconds. The estimate is only
+    /// accurate to within half of it.
+    pub round_trip_ms: i64,
+    /// When the estimate was taken, by our clock.
+    pub measured_at: DateTime<Utc>,
+    /// Whether the offset exceeds [`ClusterConfig::max_clock_skew`].
+    pub skewed: bool,
+}
+
+/// Notable changes in cluster health, from [`ClusterNode::subscribe_events`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClusterEvent {
+    /// A peer's clock drifted beyond the configured maximum skew.
+    ClockSkewDetected {
+        node_id: NodeId,
+        offset_ms: i64,
+        max_skew_ms: u64,
+    },
+    /// A previously skewed peer's clock is back within the maximum.
+    ClockSkewResolved { node_id: NodeId, offset_ms: i64 },
+}
+
+/// Estimate a peer's clock offset from a heartbeat we `sent` and got a reply
+/// to at `received`, stamped `peer_time` by the peer.
+///
+/// Assumes the reply was stamped halfway through the round trip. Returns the
+/// offset and the round trip, both in milliseconds.
+fn estimate_clock_offset(
+    sent: DateTime<Utc>,
+    received: DateTime<Utc>,
+    peer_time: DateTime<Utc>,
+) -> (i64, i64) {
+    let round_trip = (received - sent).num_milliseconds().max(0);
+    let midpoint = sent + chrono::Duration::milliseconds(round_trip / 2);
+    ((peer_time - midpoint).num_milliseconds(), round_trip)
 }
 
 /// Internal cluster state.
@@ -87,6 +244,14 @@ struct ClusterState {
     peers: DashMap<NodeId, PeerInfo>,
     /// Partition state tracking.
     partition_state: RwLock<PartitionState>,
+    /// Latest clock offset estimate per peer.
+    clock_offsets: DashMap<NodeId, ClockOffset>,
+    /// Cluster event publisher.
+    events: broadcast::Sender<ClusterEvent>,
+    /// When anti-entropy last ran with each peer.
+    last_synced: DashMap<NodeId, Instant>,
+    /// Heartbeat history of each peer that has answered one.
+    detectors: DashMap<NodeId, PhiAccrualDetector>,
 }
 
 /// State of the cluster from a partition perspective.
@@ -105,6 +270,10 @@ impl ClusterState {
         Self {
             peers: DashMap::new(),
             partition_state: RwLock::new(PartitionState::Healthy),
+            clock_offsets: DashMap::new(),
+            events: broadcast::channel(64).0,
+            last_synced: DashMap::new(),
+            detectors: DashMap::new(),
         }
     }
 
@@ -162,6 +331,173 @@ impl ClusterState {
     fn prune_stale_peers(&self, max_age: Duration) {
         let cutoff = Utc::now() - chrono::Duration::from_std(max_age).unwrap_or_default();
         self.peers.retain(|_, peer| peer.last_seen > cutoff);
+        self.clock_offsets
+            .retain(|node_id, _| self.peers.contains_key(node_id));
+        self.last_synced
+            .retain(|node_id, _| self.peers.contains_key(node_id));
+        self.detectors
+            .retain(|node_id, _| self.peers.contains_key(node_id));
+    }
+
+    /// Start failure detection for a peer at `now`, unless already tracked.
+    ///
+    /// Called before a peer's first heartbeat, so its detector starts from
+    /// [`FailureDetectorConfig::first_heartbeat_estimate`] rather than
+    /// condemning it on its first dropped reply.
+    fn track_peer(&self, node_id: &NodeId, now: Instant, config: &FailureDetectorConfig) {
+        self.detectors
+            .entry(node_id.clone())
+            .or_insert_with(|| PhiAccrualDetector::seeded(config.clone(), now));
+    }
+
+    /// Record a heartbeat reply from a peer at `now`.
+    fn heartbeat_answered(&self, node_id: &NodeId, now: Instant, config: &FailureDetectorConfig) {
+        self.detectors
+            .entry(node_id.clone())
+            .or_insert_with(|| PhiAccrualDetector::new(config.clone()))
+            .heartbeat(now);
+        if let Some(mut peer) = self.peers.get_mut(node_id) {
+            peer.record_heartbeat(true);
+        }
+        self.update_peer_status(node_id, PeerStatus::Healthy);
+    }
+
+    /// Record an unanswered heartbeat at `now`.
+    ///
+    /// The peer keeps its status until its failure detector's phi crosses
+    /// the threshold, so one lost heartbeat doesn't mark it dead. Peers that
+    /// were never tracked are marked unreachable at once.
+    fn heartbeat_missed(&self, node_id: &NodeId, now: &Instant) {
+        let phi = self.phi(node_id, now);
+        let available = self
+            .detectors
+            .get(node_id)
+            .is_some_and(|detector| detector.is_available(now));
+        let status = {
+            let Some(mut peer) = self.peers.get_mut(node_id) else {
+                return;
+            };
+            peer.record_heartbeat(false);
+            if available {
+                peer.status
+            } else {
+                if peer.status != PeerStatus::Unreachable {
+                    tracing::debug!(
+                        "Marking peer {} unreachable (phi {:?}, health {:.2})",
+                        node_id,
+                        phi,
+                        peer.health_score
+                    );
+                }
+                PeerStatus::Unreachable
+            }
+        };
+        self.update_peer_status(node_id, status);
+    }
+
+    /// Current failure suspicion of a peer, if it is tracked.
+    fn phi(&self, node_id: &NodeId, now: &Instant) -> Option<f64> {
+        self.detectors
+            .get(node_id)
+            .map(|detector| detector.phi(now))
+    }
+
+    /// Healthy peers due for anti-entropy at `now` under `config`, highest
+    /// priority first, then longest waiting. They are marked as synced.
+    ///
+    /// The scheduler checks every `tick`; half a tick of slack keeps timer
+    /// jitter from pushing a sync back a whole tick.
+    fn take_due_syncs(
+        &self,
+        config: &ClusterConfig,
+        now: &Instant,
+        tick: Duration,
+    ) -> Vec<PeerInfo> {
+        let mut due: Vec<_> = self
+            .peers
+            .iter()
+            .filter(|peer| peer.status == PeerStatus::Healthy)
+            .filter_map(|peer| {
+                let policy = config.sync_policy(&peer.address);
+                let waited = self
+                    .last_synced
+                    .get(&peer.node_id)
+                    .map(|last| now.duration_since(last.clone()));
+                waited
+                    .is_none_or(|waited| waited + tick / 2 >= policy.interval)
+                    .then(|| (policy.priority, waited, peer.clone()))
+            })
+            .collect();
+        // Never synced sorts before any wait
+        due.sort_by_key(|(priority, waited, _)| {
+            (std::cmp::Reverse(*priority), waited.map(std::cmp::Reverse))
+        });
+
+        due.into_iter()
+            .map(|(_, _, peer)| {
+                self.last_synced.insert(peer.node_id.clone(), now.clone());
+                peer
+            })
+            .collect()
+    }
+
+    /// Record a peer's clock offset, warning and publishing an event when it
+    /// crosses `max_skew` in either direction.
+    fn record_clock_offset(
+        &self,
+        node_id: &NodeId,
+        offset_ms: i64,
+        round_trip_ms: i64,
+        max_skew: Duration,
+    ) {
+        let max_skew_ms = u64::try_from(max_skew.as_millis()).unwrap_or(u64::MAX);
+        let skewed = offset_ms.unsigned_abs() > max_skew_ms;
+        let was_skewed = self
+            .clock_offsets
+            .insert(
+                node_id.clone(),
+                ClockOffset {
+                    node_id: node_id.clone(),
+                    offset_ms,
+                    round_trip_ms,
+                    measured_at: Utc::now(),
+                    skewed,
+                },
+            )
+            .is_some_and(|previous| previous.skewed);
+
+        // Receivers may come and go; nobody listening is not an error
+        match (was_skewed, skewed) {
+            (false, true) => {
+                tracing::warn!(
+                    "Clock of peer {} is off by {}ms (max {}ms); time-travel reads may misorder its writes",
+                    node_id,
+                    offset_ms,
+                    max_skew_ms
+                );
+                let _ = self.events.send(ClusterEvent::ClockSkewDetected {
+                    node_id: node_id.clone(),
+                    offset_ms,
+                    max_skew_ms,
+                });
+            }
+            (true, false) => {
+                tracing::info!("Clock of peer {} is back within {}ms", node_id, max_skew_ms);
+                let _ = self.events.send(ClusterEvent::ClockSkewResolved {
+                    node_id: node_id.clone(),
+                    offset_ms,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Latest clock offset estimates, by peer.
+    fn get_clock_offsets(&self) -> Vec<ClockOffset> {
+        self.clock_offsets
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect()
     }
 }
 
@@ -171,7 +507,10 @@ impl ClusterState {
 /// - Network communication with peers
 /// - Data synchronization
 /// - Cluster membership
-pub struct ClusterNode {
+///
+/// Background tasks (heartbeats, gossip, anti-entropy, write broadcast) run
+/// on `R`; sockets are always Tokio's.
+pub struct ClusterNode<R: Runtime = DefaultRuntime> {
     /// This node's unique identifier.
     node_id: NodeId,
     /// Cluster configuration.
@@ -188,19 +527,37 @@ pub struct ClusterNode {
     running: Arc<RwLock<bool>>,
     /// Actual bound address (may differ from config if port 0 was used).
     actual_addr: Arc<RwLock<Option<SocketAddr>>>,
+    /// Runtime for background tasks.
+    runtime: R,
 }
 
 impl ClusterNode {
-    /// Create a new cluster node.
+    /// Create a new cluster node on the default runtime.
     pub fn new(
         storage: Arc<CausalStorage>,
         engine: Arc<DistinctionEngine>,
         config: ClusterConfig,
+    ) -> Self {
+        Self::with_runtime(storage, engine, config, DefaultRuntime::new())
+    }
+}
+
+impl<R: Runtime> ClusterNode<R> {
+    /// Create a new cluster node whose background tasks run on `runtime`.
+    pub fn with_runtime(
+        storage: Arc<CausalStorage>,
+        engine: Arc<DistinctionEngine>,
+        config: ClusterConfig,
+        runtime: R,
     ) -> Self {
         let (shutdown_tx, _) = broadcast::channel(1);
 
+        // Local writes are stamped with the node they originate on
+        let node_id = NodeId::new();
+        storage.set_origin_node(node_id.to_string());
+
         Self {
-            node_id: NodeId::new(),
+            node_id,
             state: Arc::new(ClusterState::new(config.bind_addr)),
             storage,
             engine,
@@ -208,6 +565,7 @@ impl ClusterNode {
             shutdown_tx,
             running: Arc::new(RwLock::new(false)),
             actual_addr: Arc::new(RwLock::new(None)),
+            runtime,
         }
     }
 
@@ -274,6 +632,23 @@ impl ClusterNode {
         }
     }
 
+    /// Reconcile with one known peer now instead of waiting for the next
+    /// anti-entropy round.
+    #[tracing::instrument(name = "sync_with_peer", skip_all, fields(peer = %node_id))]
+    pub async fn sync_with_peer(&self, node_id: &NodeId) -> DeltaResult<()> {
+        let peer = self
+            .state
+            .peers
+            .get(node_id)
+            .map(|peer| peer.clone())
+            .ok_or_else(|| DeltaError::StorageError(format!("Unknown peer: {}", node_id)))?;
+        anti_entropy_with_peer(&self.storage, &self.node_id, &peer).await?;
+        self.state
+            .last_synced
+            .insert(node_id.clone(), self.runtime.now());
+        Ok(())
+    }
+
     /// Start the cluster node.
     ///
     /// This starts the network listener and background tasks for:
@@ -312,8 +687,9 @@ impl ClusterNode {
         let state = Arc::clone(&self.state);
         let node_id = self.node_id.clone();
         let mut shutdown_rx = self.shutdown_tx.subscribe();
+        let runtime = self.runtime.clone();
 
-        tokio::spawn(async move {
+        self.runtime.spawn(async move {
             loop {
                 tokio::select! {
                     result = listener.accept() => {
@@ -321,8 +697,9 @@ impl ClusterNode {
                             let storage = Arc::clone(&storage);
                             let state = Arc::clone(&state);
                             let node_id = node_id.clone();
-                            tokio::spawn(async move {
-                                if let Err(e) = handle_connection(conn, storage, state, node_id).await {
+                            let clock = runtime.clone();
+                            runtime.spawn(async move {
+                                if let Err(e) = handle_connection(conn, storage, state, node_id, clock).await {
                                     eprintln!("Connection error: {}", e);
                                 }
                             });
@@ -340,14 +717,26 @@ impl ClusterNode {
         let node_id = self.node_id.clone();
         let heartbeat_interval = self.config.heartbeat_interval;
         let quorum_size = self.config.quorum_size;
+        let max_clock_skew = self.config.max_clock_skew;
+        let failure_detector = self.config.failure_detector.clone();
         let mut shutdown_rx = self.shutdown_tx.subscribe();
 
-        tokio::spawn(async move {
-            let mut ticker = interval(heartbeat_interval);
+        let runtime = self.runtime.clone();
+
+        self.runtime.spawn(async move {
+            let mut ticker = runtime.interval(heartbeat_interval);
             loop {
                 tokio::select! {
                     _ = ticker.tick() => {
-                        send_heartbeats(&state, &node_id, quorum_size).await;
+                        send_heartbeats(
+                            &runtime,
+                            &state,
+                            &node_id,
+                            quorum_size,
+                            max_clock_skew,
+                            &failure_detector,
+                        )
+                        .await;
                     }
                     _ = shutdown_rx.recv() => {
                         break;
@@ -363,12 +752,14 @@ impl ClusterNode {
         let bind_addr = actual_addr;
         let mut shutdown_rx = self.shutdown_tx.subscribe();
 
-        tokio::spawn(async move {
-            let mut ticker = interval(gossip_interval);
+        let runtime = self.runtime.clone();
+
+        self.runtime.spawn(async move {
+            let mut ticker = runtime.interval(gossip_interval);
             loop {
                 tokio::select! {
                     _ = ticker.tick() => {
-                        send_gossip(&state, &node_id, bind_addr).await;
+                        send_gossip(&runtime, &state, &node_id, bind_addr).await;
                     }
                     _ = shutdown_rx.recv() => {
                         break;
@@ -377,19 +768,23 @@ impl ClusterNode {
             }
         });
 
-        // Spawn anti-entropy task for continuous reconciliation.
+        // Spawn anti-entropy task for continuous reconciliation, checking
+        // which peers are due as often as the tightest schedule needs.
         let state = Arc::clone(&self.state);
         let node_id = self.node_id.clone();
         let storage = Arc::clone(&self.storage);
-        let anti_entropy_interval = Duration::from_secs(30); // Every 30 seconds
+        let config = self.config.clone();
+        let sync_tick = self.config.sync_tick();
         let mut shutdown_rx = self.shutdown_tx.subscribe();
 
-        tokio::spawn(async move {
-            let mut ticker = interval(anti_entropy_interval);
+        let runtime = self.runtime.clone();
+
+        self.runtime.spawn(async move {
+            let mut ticker = runtime.interval(sync_tick);
             loop {
                 tokio::select! {
                     _ = ticker.tick() => {
-                        run_anti_entropy(&state, &storage, &node_id).await;
+                        run_anti_entropy(&runtime, &state, &storage, &node_id, &config, sync_tick).await;
                     }
                     _ = shutdown_rx.recv() => {
                         break;
@@ -415,6 +810,7 @@ impl ClusterNode {
     }
 
     /// Join an existing cluster.
+    #[tracing::instrument(name = "join_cluster", skip_all, fields(peer_addr = %peer_addr))]
     async fn join_cluster(&self, peer_addr: SocketAddr) -> DeltaResult<()> {
         let mut conn = Connection::connect(peer_addr).await?;
 
@@ -426,11 +822,18 @@ impl ClusterNode {
             .request(&Message::Join {
                 node_id: self.node_id.clone(),
                 address: actual_addr,
+                mapping: Some(self.storage.mapping_version()),
             })
             .await?;
 
         match response {
-            Message::JoinAck { node_id, peers } => {
+            Message::JoinAck {
+                node_id,
+                peers,
+                mapping,
+            } => {
+                check_mapping(&self.storage, mapping)?;
+
                 // Add the peer we joined.
                 self.state.upsert_peer(PeerInfo {
                     node_id: node_id.clone(),
@@ -438,6 +841,7 @@ impl ClusterNode {
                     first_seen: Utc::now(),
                     last_seen: Utc::now(),
                     status: PeerStatus::Healthy,
+                    health_score: 1.0,
                 });
 
                 // Add all peers from the response.
@@ -529,8 +933,16 @@ impl ClusterNode {
             let message = message.clone();
             let version_id = version_id.clone();
             let key = key.clone();
+            let runtime = self.runtime.clone();
+            let span = tracing::info_span!(
+                "replicate_write",
+                peer = %peer.node_id,
+                namespace = %key.namespace,
+                key = %key.key,
+                version_id = %version_id,
+            );
 
-            tokio::spawn(async move {
+            let replicate = async move {
                 let mut attempts = 0;
                 let max_attempts = 3;
 
@@ -546,11 +958,11 @@ impl ClusterNode {
                             }
 
                             // Wait for ACK with timeout
-                            match tokio::time::timeout(
-                                std::time::Duration::from_secs(5),
-                                conn.receive(),
-                            )
-                            .await
+                            match runtime
+                                .timeout(std::time::Duration::from_secs(5), async move {
+                                    conn.receive().await
+                                })
+                                .await
                             {
                                 Ok(Ok(Message::WriteAck {
                                     node_id: ack_node_id,
@@ -594,7 +1006,8 @@ impl ClusterNode {
 
                     // Exponential backoff before retry
                     if attempts < max_attempts {
-                        tokio::time::sleep(std::time::Duration::from_millis(100 * attempts as u64))
+                        runtime
+                            .sleep(std::time::Duration::from_millis(100 * attempts as u64))
                             .await;
                     }
                 }
@@ -604,17 +1017,19 @@ impl ClusterNode {
                     peer.node_id,
                     max_attempts
                 );
-            });
+            };
+            self.runtime.spawn(replicate.instrument(span));
         }
     }
 }
 
 /// Handle an incoming connection.
-async fn handle_connection(
+async fn handle_connection<R: Runtime>(
     mut conn: Connection,
     storage: Arc<CausalStorage>,
     state: Arc<ClusterState>,
     node_id: NodeId,
+    runtime: R,
 ) -> DeltaResult<()> {
     loop {
         let message = match conn.receive().await {
@@ -622,7 +1037,7 @@ async fn handle_connection(
             Err(_) => break, // Connection closed.
         };
 
-        let response = handle_message(message, &storage, &state, &node_id)?;
+        let response = handle_message(message, &storage, &state, &node_id, runtime.utc_now())?;
 
         if let Some(resp) = response {
             conn.send(&resp).await?;
@@ -632,18 +1047,46 @@ async fn handle_connection(
     Ok(())
 }
 
-/// Handle a single message.
+/// Check that a peer canonicalizes values the way `storage` does.
+///
+/// Peers that predate mapping versions send none; they all fold.
+fn check_mapping(storage: &CausalStorage, mapping: Option<MappingVersion>) -> DeltaResult<()> {
+    let ours = storage.mapping_version();
+    let theirs = mapping.unwrap_or(MappingVersion::Fold);
+    if theirs != ours {
+        return Err(DeltaError::InvalidData {
+            reason: format!(
+                "Peer uses mapping version {:?}, this node uses {:?}",
+                theirs, ours
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Handle a single message received at `now` on the node's runtime clock.
 fn handle_message(
     message: Message,
     storage: &Arc<CausalStorage>,
     state: &Arc<ClusterState>,
     node_id: &NodeId,
+    now: DateTime<Utc>,
 ) -> DeltaResult<Option<Message>> {
     match message {
         Message::Join {
             node_id: peer_id,
             address,
+            mapping,
         } => {
+            // A peer canonicalizing values differently would replicate
+            // versions whose distinction IDs don't match ours
+            if let Err(e) = check_mapping(storage, mapping) {
+                tracing::warn!("Rejected join from {}: {}", peer_id, e);
+                return Ok(Some(Message::Error {
+                    message: e.to_string(),
+                }));
+            }
+
             // Add the new peer.
             state.upsert_peer(PeerInfo::new(peer_id, address));
 
@@ -651,6 +1094,7 @@ fn handle_message(
             Ok(Some(Message::JoinAck {
                 node_id: node_id.clone(),
                 peers: state.get_peers(),
+                mapping: Some(storage.mapping_version()),
             }))
         }
 
@@ -658,10 +1102,13 @@ fn handle_message(
             state.update_peer_status(&peer_id, PeerStatus::Healthy);
             Ok(Some(Message::Pong {
                 node_id: node_id.clone(),
+                timestamp: Some(Utc::now()),
             }))
         }
 
-        Message::Pong { node_id: peer_id } => {
+        Message::Pong {
+            node_id: peer_id, ..
+        } => {
             state.update_peer_status(&peer_id, PeerStatus::Healthy);
             Ok(None)
         }
@@ -678,6 +1125,7 @@ fn handle_message(
                 first_seen: Utc::now(),
                 last_seen: Utc::now(),
                 status: PeerStatus::Healthy,
+                health_score: 1.0,
             });
 
             // Add any new peers from the announcement.
@@ -743,7 +1191,8 @@ fn handle_message(
                         value.write_id
                     );
 
-                    // Attempt to merge the concurrent writes
+                    // Attempt to merge the concurrent writes, keeping both
+                    // siblings for later inspection either way
                     match storage.merge_concurrent_writes(
                         &key.namespace,
                         &key.key,
@@ -751,18 +1200,35 @@ fn handle_message(
                         (*value.value).clone(),
                         incoming_clock,
                     ) {
-                        Ok(merged) => Ok(Some(Message::WriteAck {
-                            node_id: node_id.clone(),
-                            key,
-                            version_id: merged.write_id.clone(),
-                        })),
+                        Ok(merged) => {
+                            if let Err(e) = storage.record_conflict(
+                                key.clone(),
+                                existing,
+                                value,
+                                Some(merged.clone()),
+                                now,
+                            ) {
+                                tracing::error!("Failed to record write conflict: {}", e);
+                            }
+                            Ok(Some(Message::WriteAck {
+                                node_id: node_id.clone(),
+                                key,
+                                version_id: merged.write_id.clone(),
+                            }))
+                        }
                         Err(e) => {
                             tracing::error!("Failed to merge concurrent writes: {}", e);
+                            let version_id = value.write_id.clone();
+                            if let Err(e) =
+                                storage.record_conflict(key.clone(), existing, value, None, now)
+                            {
+                                tracing::error!("Failed to record write conflict: {}", e);
+                            }
                             // Still acknowledge to prevent infinite retries
                             Ok(Some(Message::WriteAck {
                                 node_id: node_id.clone(),
                                 key,
-                                version_id: value.write_id.clone(),
+                                version_id,
                             }))
                         }
                     }
@@ -856,27 +1322,46 @@ fn handle_message(
 }
 
 /// Send heartbeat pings to all peers.
-async fn send_heartbeats(state: &Arc<ClusterState>, node_id: &NodeId, quorum_size: usize) {
+async fn send_heartbeats<R: Runtime>(
+    runtime: &R,
+    state: &Arc<ClusterState>,
+    node_id: &NodeId,
+    quorum_size: usize,
+    max_clock_skew: Duration,
+    failure_detector: &FailureDetectorConfig,
+) {
     let peers = state.get_peers();
 
     for peer in peers {
         let node_id = node_id.clone();
         let state = Arc::clone(state);
-        tokio::spawn(async move {
-            match Connection::connect(peer.address).await {
+        let failure_detector = failure_detector.clone();
+        let clock = runtime.clone();
+        state.track_peer(&peer.node_id, runtime.now(), &failure_detector);
+        runtime.spawn(async move {
+            let reply = match Connection::connect(peer.address).await {
                 Ok(mut conn) => {
                     let msg = Message::Ping {
                         node_id: node_id.clone(),
                     };
-                    if conn.request(&msg).await.is_ok() {
-                        state.update_peer_status(&peer.node_id, PeerStatus::Healthy);
-                    } else {
-                        state.update_peer_status(&peer.node_id, PeerStatus::Unreachable);
-                    }
-                }
-                Err(_) => {
-                    state.update_peer_status(&peer.node_id, PeerStatus::Unreachable);
+                    let sent = Utc::now();
+                    conn.request(&msg).await.ok().map(|reply| (sent, reply))
                 }
+                Err(_) => None,
+            };
+
+            let Some((sent, reply)) = reply else {
+                state.heartbeat_missed(&peer.node_id, &clock.now());
+                return;
+            };
+            state.heartbeat_answered(&peer.node_id, clock.now(), &failure_detector);
+            if let Message::Pong {
+                timestamp: Some(peer_time),
+                ..
+            } = reply
+            {
+                let (offset_ms, round_trip_ms) = estimate_clock_offset(sent, Utc::now(), peer_time);
+                state.record_clock_offset(&peer.node_id, offset_ms, round_trip_ms, max_clock_skew);
             }
         });
     }
@@ -909,7 +1394,12 @@ async fn send_heartbeats(state: &Arc<ClusterState>, node_id: &NodeId, quorum_siz
 }
 
 /// Send gossip announcements to all peers.
-async fn send_gossip(state: &Arc<ClusterState>, node_id: &NodeId, bind_addr: SocketAddr) {
+async fn send_gossip<R: Runtime>(
+    runtime: &R,
+    state: &Arc<ClusterState>,
+    node_id: &NodeId,
+    bind_addr: SocketAddr,
+) {
     let peers = state.get_peers();
     let message = Message::Announce {
         node_id: node_id.clone(),
@@ -919,7 +1409,7 @@ async fn send_gossip(state: &Arc<ClusterState>, node_id: &NodeId, bind_addr: Soc
 
     for peer in peers {
         let message = message.clone();
-        tokio::spawn(async move {
+        runtime.spawn(async move {
             if let Ok(mut conn) = Connection::connect(peer.address).await {
                 let _ = conn.send(&message).await;
             }
@@ -928,156 +1418,159 @@ async fn send_gossip(state: &Arc<ClusterState>, node_id: &NodeId, bind_addr: Soc
 }
 
 /// Run anti-entropy reconciliation with all peers.
-async fn run_anti_entropy(
+async fn run_anti_entropy<R: Runtime>(
+    runtime: &R,
     state: &Arc<ClusterState>,
     storage: &Arc<CausalStorage>,
     node_id: &NodeId,
+    config: &ClusterConfig,
+    tick: Duration,
 ) {
-    let peers = state.get_peers();
-
-    // Only run anti-entropy if we have healthy peers
-    let healthy_peers: Vec<_> = peers
-        .into_iter()
-        .filter(|p| matches!(p.status, PeerStatus::Healthy))
-        .collect();
-
-    if healthy_peers.is_empty() {
+    // Only healthy peers whose schedule is up
+    let due = state.take_due_syncs(config, &runtime.now(), tick);
+    if due.is_empty() {
         return;
     }
 
-    tracing::trace!("Running anti-entropy with {} peers", healthy_peers.len());
-
-    for peer in healthy_peers {
-        let storage = Arc::clone(storage);
-        let node_id = node_id.clone();
+    tracing::trace!("Running anti-entropy with {} peers", due.len());
 
-        tokio::spawn(async move {
-            // Get our current key set with version info
-            let mut keys_to_check = HashMap::new();
-
-            // Get all namespaces and keys
-            // TODO: Optimize this to only check recently changed keys
-            let namespaces = storage.list_namespaces();
-            for ns in namespaces {
-                let keys = storage.list_keys(&ns);
-                for key in keys {
-                    let full_key = FullKey::new(&ns, &key);
-                    // Get latest version ID for this key
-                    let version = storage
-                        .get(&ns, &key)
-                        .ok()
-                        .map(|v| v.write_id().to_string());
-                    keys_to_check.insert(full_key, version);
-                }
+    // One peer after another, so higher-priority peers reconcile first
+    let storage = Arc::clone(storage);
+    let node_id = node_id.clone();
+    runtime.spawn(async move {
+        for peer in due {
+            if let Err(e) = anti_entropy_with_peer(&storage, &node_id, &peer).await {
+                tracing::debug!("Anti-entropy failed with {}: {}", peer.node_id, e);
             }
+        }
+    });
+}
 
-            // Get our known tombstones for tombstone propagation
-            let our_tombstones: HashMap<FullKey, VectorClock> = storage
-                .get_all_tombstones()
-                .into_iter()
-                .map(|t| (t.key.clone(), t.vector_clock))
-                .collect();
+/// Reconcile keys and tombstones with one peer.
+#[tracing::instrument(
+    name = "anti_entropy",
+    skip_all,
+    fields(peer = %peer.node_id, peer_addr = %peer.address)
+)]
+async fn anti_entropy_with_peer(
+    storage: &Arc<CausalStorage>,
+    node_id: &NodeId,
+    peer: &PeerInfo,
+) -> DeltaResult<()> {
+    // Send sync request to peer
+    let mut conn = Connection::connect(peer.address).await?;
+    let request = sync_request(storage, node_id);
+
+    match conn.request(&request).await? {
+        Message::SyncResponse {
+            updates,
+            tombstones,
+            ..
+        } => {
+            apply_sync_response(storage, updates, tombstones);
+            tracing::trace!("Anti-entropy completed with {}", peer.node_id);
+            Ok(())
+        }
+        Message::Error { message } => Err(DeltaError::StorageError(format!(
+            "Sync failed: {}",
+            message
+        ))),
+        _ => Err(DeltaError::StorageError(
+            "Unexpected response to sync request".to_string(),
+        )),
+    }
+}
 
-            // Send sync request to peer
-            match Connection::connect(peer.address).await {
-                Ok(mut conn) => {
-                    let request = Message::SyncRequest {
-                        node_id: node_id.clone(),
-                        keys: keys_to_check,
-                        tombstones: our_tombstones,
-                    };
+/// Build the anti-entropy request describing our keys and tombstones.
+fn sync_request(storage: &Arc<CausalStorage>, node_id: &NodeId) -> Message {
+    // Get our current key set with version info
+    let mut keys_to_check = HashMap::new();
+
+    // Get all namespaces and keys
+    // TODO: Optimize this to only check recently changed keys
+    // Conflicts are what this node found, so they are not synced
+    let namespaces = storage.list_namespaces();
+    for ns in namespaces.into_iter().filter(|ns| ns != CONFLICT_NAMESPACE) {
+        let keys = storage.list_keys(&ns);
+        for key in keys {
+            let full_key = FullKey::new(&ns, &key);
+            // Get latest version ID for this key (matched against the
+            // peer's history, which is keyed by version ID)
+            let version = storage
+                .get(&ns, &key)
+                .ok()
+                .map(|v| v.version_id().to_string());
+            keys_to_check.insert(full_key, version);
+        }
+    }
 
-                    match conn.request(&request).await {
-                        Ok(Message::SyncResponse {
-                            updates,
-                            tombstones,
-                            ..
-                        }) => {
-                            // Apply updates from peer
-                            for (key, versions) in updates {
-                                // Skip if we have a tombstone for this key
-                                if storage.has_tombstone(&key.namespace, &key.key) {
-                                    tracing::trace!("Skipping update for deleted key {:?}", key);
-                                    continue;
-                                }
+    // Get our known tombstones for tombstone propagation
+    let our_tombstones: HashMap<FullKey, VectorClock> = storage
+        .get_all_tombstones()
+        .into_iter()
+        .map(|t| (t.key.clone(), t.vector_clock))
+        .collect();
 
-                                for version in versions {
-                                    // TODO: Use vector clock merge instead of blind put
-                                    if let Err(e) = storage.put(
-                                        &key.namespace,
-                                        &key.key,
-                                        (*version.value).clone(),
-                                    ) {
-                                        tracing::debug!(
-                                            "Failed to apply anti-entropy update: {}",
-                                            e
-                                        );
-                                    }
-                                }
-                            }
+    Message::SyncRequest {
+        node_id: node_id.clone(),
+        keys: keys_to_check,
+        tombstones: our_tombstones,
+    }
+}
 
-                            // Apply tombstones from peer
-                            for tombstone in tombstones {
-                                // Check if we already have this key
-                                if let Ok(existing) =
-                                    storage.get(&tombstone.key.namespace, &tombstone.key.key)
-                                {
-                                    // Check if the peer's tombstone causally supersedes our value
-                                    match tombstone.vector_clock.compare(existing.vector_clock()) {
-                                        Some(std::cmp::Ordering::Greater) => {
-                                            // Peer has newer tombstone, delete our value
-                                            if let Err(e) = storage.delete_causal(
-                                                &tombstone.key.namespace,
-                                                &tombstone.key.key,
-                                                tombstone.vector_clock.clone(),
-                                                &tombstone.deleted_by,
-                                            ) {
-                                                tracing::debug!("Failed to apply tombstone: {}", e);
-                                            } else {
-                                                tracing::info!(
-                                                    "Applied tombstone for {:?} from peer",
-                                                    tombstone.key
-                                                );
-                                            }
-                                        }
-                                        _ => {
-                                            // Our value is newer or concurrent, keep it
-                                            tracing::trace!(
-                                                "Skipping tombstone for {:?} - local value is newer",
-                                                tombstone.key
-                                            );
-                                        }
-                                    }
-                                } else if !storage
-                                    .has_tombstone(&tombstone.key.namespace, &tombstone.key.key)
-                                {
-                                    // We don't have this key and don't have a tombstone - record the tombstone
-                                    storage.insert_tombstone(tombstone);
-                                }
-                            }
+/// Apply a peer's anti-entropy response to local storage.
+fn apply_sync_response(
+    storage: &Arc<CausalStorage>,
+    updates: Vec<(FullKey, Vec<VersionedValue>)>,
+    tombstones: Vec<Tombstone>,
+) {
+    // Apply updates from peer
+    for (key, versions) in updates {
+        // Skip if we have a tombstone for this key
+        if storage.has_tombstone(&key.namespace, &key.key) {
+            tracing::trace!("Skipping update for deleted key {:?}", key);
+            continue;
+        }
 
-                            tracing::trace!("Anti-entropy completed with {}", peer.node_id);
-                        }
-                        Ok(_) => {
-                            tracing::debug!(
-                                "Unexpected response from {} during anti-entropy",
-                                peer.node_id
-                            );
-                        }
-                        Err(e) => {
-                            tracing::debug!("Anti-entropy failed with {}: {}", peer.node_id, e);
-                        }
+        for version in versions {
+            // TODO: Use vector clock merge instead of blind put
+            if let Err(e) = storage.put(&key.namespace, &key.key, (*version.value).clone()) {
+                tracing::debug!("Failed to apply anti-entropy update: {}", e);
+            }
+        }
+    }
+
+    // Apply tombstones from peer
+    for tombstone in tombstones {
+        // Check if we already have this key
+        if let Ok(existing) = storage.get(&tombstone.key.namespace, &tombstone.key.key) {
+            // Check if the peer's tombstone causally supersedes our value
+            match tombstone.vector_clock.compare(existing.vector_clock()) {
+                Some(std::cmp::Ordering::Greater) => {
+                    // Peer has newer tombstone, delete our value
+                    if let Err(e) = storage.delete_causal(
+                        &tombstone.key.namespace,
+                        &tombstone.key.key,
+                        tombstone.vector_clock.clone(),
+                        &tombstone.deleted_by,
+                    ) {
+                        tracing::debug!("Failed to apply tombstone: {}", e);
+                    } else {
+                        tracing::info!("Applied tombstone for {:?} from peer", tombstone.key);
                     }
                 }
-                Err(e) => {
-                    tracing::debug!(
-                        "Failed to connect to {} for anti-entropy: {}",
-                        peer.node_id,
-                        e
+                _ => {
+                    // Our value is newer or concurrent, keep it
+                    tracing::trace!(
+                        "Skipping tombstone for {:?} - local value is newer",
+                        tombstone.key
                     );
                 }
             }
-        });
+        } else if !storage.has_tombstone(&tombstone.key.namespace, &tombstone.key.key) {
+            // We don't have this key and don't have a tombstone - record the tombstone
+            storage.insert_tombstone(tombstone);
+        }
     }
 }
 
@@ -1094,9 +1587,14 @@ pub struct ClusterStatus {
     pub healthy_peers: usize,
     /// Whether this node is running.
     pub is_running: bool,
+    /// Latest clock offset estimate for each peer that has answered a
+    /// heartbeat.
+    pub clock_offsets: Vec<ClockOffset>,
+    /// Peers whose clocks exceed [`ClusterConfig::max_clock_skew`].
+    pub skewed_peers: Vec<NodeId>,
 }
 
-impl ClusterNode {
+impl<R: Runtime> ClusterNode<R> {
     /// Get cluster status.
     pub async fn status(&self) -> ClusterStatus {
         let peers = self.state.get_peers();
@@ -1104,6 +1602,12 @@ impl ClusterNode {
             .iter()
             .filter(|p| p.status == PeerStatus::Healthy)
             .count();
+        let clock_offsets = self.clock_offsets();
+        let skewed_peers = clock_offsets
+            .iter()
+            .filter(|offset| offset.skewed)
+            .map(|offset| offset.node_id.clone())
+            .collect();
 
         ClusterStatus {
             node_id: self.node_id.clone(),
@@ -1111,8 +1615,30 @@ impl ClusterNode {
             peer_count: peers.len(),
             healthy_peers: healthy,
             is_running: *self.running.read().await,
+            clock_offsets,
+            skewed_peers,
         }
     }
+
+    /// Current failure suspicion (phi) of a peer, or `None` before its first
+    /// heartbeat. It is marked unreachable once this reaches
+    /// [`FailureDetectorConfig::phi_threshold`].
+    pub fn phi(&self, node_id: &NodeId) -> Option<f64> {
+        self.state.phi(node_id, &self.runtime.now())
+    }
+
+    /// Latest clock offset estimate for each peer that has answered a
+    /// heartbeat.
+    pub fn clock_offsets(&self) -> Vec<ClockOffset> {
+        self.state.get_clock_offsets()
+    }
+
+    /// Subscribe to cluster events such as detected clock skew.
+    ///
+    /// Only events published after subscribing are received.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<ClusterEvent> {
+        self.state.events.subscribe()
+    }
 }
 
 #[cfg(test)]
@@ -1165,6 +1691,146 @@ mod tests {
         assert_eq!(peers[0].status, PeerStatus::Healthy);
     }
 
+    #[test]
+    fn test_peer_sync_schedule() {
+        let hub = SocketAddr::from(([10, 0, 0, 1], 7878));
+        let edge = SocketAddr::from(([10, 0, 0, 2], 7878));
+        let other = SocketAddr::from(([10, 0, 0, 3], 7878));
+        let config = ClusterConfig::new()
+            .sync_interval(Duration::from_secs(60))
+            .peer_sync(
+                hub,
+                PeerSyncPolicy::every(Duration::from_secs(5)).priority(10),
+            )
+            .peer_sync(edge, PeerSyncPolicy::every(Duration::from_secs(300)));
+        assert_eq!(config.sync_policy(&other).interval, Duration::from_secs(60));
+        assert_eq!(config.sync_tick(), Duration::from_secs(5));
+
+        let state = ClusterState::new(SocketAddr::from(([127, 0, 0, 1], 0)));
+        let mut ids = HashMap::new();
+        for addr in [other, edge, hub] {
+            let peer = PeerInfo::new(NodeId::new(), addr);
+            ids.insert(peer.node_id.clone(), addr);
+            state.upsert_peer(peer.clone());
+            state.update_peer_status(&peer.node_id, PeerStatus::Healthy);
+        }
+
+        let runtime = crate::runtime::TestRuntime::new();
+        let tick = config.sync_tick();
+        let due_after = |elapsed: Duration| -> Vec<SocketAddr> {
+            runtime.advance(elapsed);
+            state
+                .take_due_syncs(&config, &runtime.now(), tick)
+                .into_iter()
+                .map(|peer| ids[&peer.node_id])
+                .collect()
+        };
+
+        // Everyone is due at first, the hub ahead of the rest.
+        let first = due_after(Duration::ZERO);
+        assert_eq!(first.len(), 3);
+        assert_eq!(first[0], hub);
+
+        assert_eq!(due_after(Duration::from_secs(5)), vec![hub]);
+        // Timer jitter doesn't cost the hub a round.
+        assert_eq!(due_after(Duration::from_millis(4_900)), vec![hub]);
+        for _ in 0..9 {
+            due_after(Duration::from_secs(5));
+        }
+        assert_eq!(due_after(Duration::from_millis(5_100)), vec![hub, other]);
+    }
+
+    #[test]
+    fn test_missed_heartbeats_tolerated_until_phi_threshold() {
+        let state = ClusterState::new(SocketAddr::from(([127, 0, 0, 1], 0)));
+        let runtime = crate::runtime::TestRuntime::new();
+        let config = FailureDetectorConfig::default();
+        let flapping = PeerInfo::new(NodeId::new(), SocketAddr::from(([10, 0, 0, 1], 7878)));
+        let silent = PeerInfo::new(NodeId::new(), SocketAddr::from(([10, 0, 0, 2], 7878)));
+        state.upsert_peer(flapping.clone());
+        state.upsert_peer(silent.clone());
+        let peer = |id: &NodeId| state.peers.get(id).unwrap().clone();
+
+        // A new peer survives its first dropped reply...
+        state.track_peer(&silent.node_id, runtime.now(), &config);
+        runtime.advance(Duration::from_secs(5));
+        state.heartbeat_missed(&silent.node_id, &runtime.now());
+        assert_ne!(peer(&silent.node_id).status, PeerStatus::Unreachable);
+
+        // ...but not going quiet for good
+        runtime.advance(Duration::from_secs(60));
+        state.heartbeat_missed(&silent.node_id, &runtime.now());
+        assert_eq!(peer(&silent.node_id).status, PeerStatus::Unreachable);
+
+        runtime.advance(Duration::from_secs(5));
+
+        state.track_peer(&flapping.node_id, runtime.now(), &config);
+        for _ in 0..10 {
+            state.heartbeat_answered(&flapping.node_id, runtime.now(), &config);
+            runtime.advance(Duration::from_secs(5));
+        }
+        assert_eq!(peer(&flapping.node_id).health_score, 1.0);
+
+        // A dropped heartbeat lowers the score but keeps the peer healthy
+        state.heartbeat_missed(&flapping.node_id, &runtime.now());
+        let dropped = peer(&flapping.node_id);
+        assert_eq!(dropped.status, PeerStatus::Healthy);
+        assert!(dropped.health_score < 1.0);
+
+        // Prolonged silence crosses the threshold
+        runtime.advance(Duration::from_secs(60));
+        state.heartbeat_missed(&flapping.node_id, &runtime.now());
+        assert_eq!(peer(&flapping.node_id).status, PeerStatus::Unreachable);
+        assert!(state.phi(&flapping.node_id, &runtime.now()).unwrap() >= config.phi_threshold);
+
+        state.heartbeat_answered(&flapping.node_id, runtime.now(), &config);
+        assert_eq!(peer(&flapping.node_id).status, PeerStatus::Healthy);
+    }
+
+    #[test]
+    fn test_clock_skew_detection() {
+        let sent = Utc::now();
+        let received = sent + chrono::Duration::milliseconds(40);
+        let peer_time = sent + chrono::Duration::milliseconds(2_020);
+        assert_eq!(
+            estimate_clock_offset(sent, received, peer_time),
+            (2_000, 40)
+        );
+
+        let state = ClusterState::new(SocketAddr::from(([127, 0, 0, 1], 7878)));
+        let mut events = state.events.subscribe();
+        let peer_id = NodeId::new();
+        let max_skew = Duration::from_secs(1);
+
+        // Within the threshold: recorded, nothing published.
+        state.record_clock_offset(&peer_id, 300, 10, max_skew);
+        assert!(!state.get_clock_offsets()[0].skewed);
+        assert!(events.try_recv().is_err());
+
+        // Crossing it publishes once, not on every heartbeat.
+        state.record_clock_offset(&peer_id, -1_500, 10, max_skew);
+        state.record_clock_offset(&peer_id, -1_600, 10, max_skew);
+        assert_eq!(
+            events.try_recv().unwrap(),
+            ClusterEvent::ClockSkewDetected {
+                node_id: peer_id.clone(),
+                offset_ms: -1_500,
+                max_skew_ms: 1_000,
+            }
+        );
+        assert!(events.try_recv().is_err());
+        assert!(state.get_clock_offsets()[0].skewed);
+
+        state.record_clock_offset(&peer_id, 20, 10, max_skew);
+        assert_eq!(
+            events.try_recv().unwrap(),
+            ClusterEvent::ClockSkewResolved {
+                node_id: peer_id,
+                offset_ms: 20,
+            }
+        );
+    }
+
     #[tokio::test]
     async fn test_cluster_node_creation() {
         let (storage, engine) = create_test_storage();
@@ -1202,6 +1868,8 @@ mod tests {
         assert_eq!(status.peer_count, 0);
         assert_eq!(status.healthy_peers, 0);
         assert!(!status.is_running);
+        assert!(status.clock_offsets.is_empty());
+        assert!(status.skewed_peers.is_empty());
     }
 
     #[tokio::test]
@@ -1244,4 +1912,162 @@ mod tests {
         node1.stop().await.unwrap();
         node2.stop().await.unwrap();
     }
+    #[tokio::test]
+    async fn test_sync_with_peer() {
+        let (storage1, engine1) = create_test_storage();
+        storage1
+            .put("test", "key", serde_json::json!({"value": 1}))
+            .unwrap();
+        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 0);
+        let node1 = ClusterNode::new(
+            storage1.clone(),
+            engine1,
+            ClusterConfig::new().bind_addr(addr),
+        );
+        node1.start().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let (storage2, engine2) = create_test_storage();
+        let config2 = ClusterConfig::new().bind_addr(addr).join(node1.bind_addr());
+        let node2 = ClusterNode::new(storage2.clone(), engine2, config2);
+        node2.start().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // Updated after the join, so only a sync brings it over
+        storage1
+            .put("test", "key", serde_json::json!({"value": 2}))
+            .unwrap();
+        node2.sync_with_peer(node1.node_id()).await.unwrap();
+        assert_eq!(
+            storage2.get("test", "key").unwrap().value(),
+            &serde_json::json!({"value": 2})
+        );
+
+        assert!(node2.sync_with_peer(&NodeId::new()).await.is_err());
+
+        node1.stop().await.unwrap();
+        node2.stop().await.unwrap();
+    }
+
+    #[test]
+    fn test_join_rejects_mismatched_mapping() {
+        let (storage, _engine) = create_test_storage();
+        let state = Arc::new(ClusterState::new(SocketAddr::from(([127, 0, 0, 1], 0))));
+        let node_id = NodeId::new();
+        let join = |mapping| Message::Join {
+            node_id: NodeId::new(),
+            address: SocketAddr::from(([127, 0, 0, 1], 7001)),
+            mapping,
+        };
+
+        // Folding peers, including those too old to say so, are turned away
+        for mapping in [Some(MappingVersion::Fold), None] {
+            let reply = handle_message(join(mapping), &storage, &state, &node_id, Utc::now());
+            assert!(matches!(reply, Ok(Some(Message::Error { .. }))));
+        }
+        assert!(state.get_peers().is_empty());
+
+        let reply = handle_message(
+            join(Some(MappingVersion::Chunked)),
+            &storage,
+            &state,
+            &node_id,
+            Utc::now(),
+        );
+        match reply {
+            Ok(Some(Message::JoinAck { mapping, .. })) => {
+                assert_eq!(mapping, Some(MappingVersion::Chunked));
+            }
+            other => panic!("Expected JoinAck, got {:?}", other),
+        }
+        assert_eq!(state.get_peers().len(), 1);
+        assert!(check_mapping(&storage, Some(MappingVersion::Chunked)).is_ok());
+    }
+
+    #[test]
+    fn test_concurrent_write_records_conflict() {
+        let (storage, _engine) = create_test_storage();
+        let state = Arc::new(ClusterState::new(SocketAddr::from(([127, 0, 0, 1], 0))));
+        let node_id = NodeId::new();
+
+        let mut local_clock = VectorClock::new();
+        local_clock.increment("a");
+        storage
+            .put_causal("carts", "bob", serde_json::json!(["apple"]), local_clock)
+            .unwrap();
+
+        let mut remote_clock = VectorClock::new();
+        remote_clock.increment("b");
+        let incoming = VersionedValue::new(
+            Arc::new(serde_json::json!(["pear"])),
+            Utc::now(),
+            "remote_write".to_string(),
+            "remote_distinction".to_string(),
+            None,
+            remote_clock,
+        );
+        let message = Message::WriteEvent {
+            node_id: NodeId::new(),
+            key: FullKey::new("carts", "bob"),
+            value: incoming,
+        };
+        let detected_at = Utc::now() - chrono::Duration::minutes(5);
+        handle_message(message.clone(), &storage, &state, &node_id, detected_at).unwrap();
+
+        let conflicts = storage.conflicts("carts");
+        assert_eq!(conflicts.len(), 1);
+        let conflict = conflicts[0].clone();
+        assert_eq!(conflict.detected_at, detected_at);
+        assert_eq!(conflict.status, crate::types::ConflictStatus::AutoResolved);
+        let siblings: Vec<_> = conflict
+            .siblings
+            .iter()
+            .map(|v| v.value().clone())
+            .collect();
+        assert_eq!(
+            siblings,
+            vec![serde_json::json!(["apple"]), serde_json::json!(["pear"])]
+        );
+        assert_eq!(
+            conflict.winner.as_ref().unwrap().write_id,
+            storage.get("carts", "bob").unwrap().write_id
+        );
+
+        // The merged version descends from both, so a retried broadcast of
+        // the same write is not a new conflict
+        handle_message(message, &storage, &state, &node_id, Utc::now()).unwrap();
+        assert_eq!(storage.conflicts("carts").len(), 1);
+        assert!(storage.conflicts("other").is_empty());
+
+        // A third concurrent writer joins the same conflict
+        let mut third_clock = VectorClock::new();
+        third_clock.increment("c");
+        let third = VersionedValue::new(
+            Arc::new(serde_json::json!(["plum"])),
+            Utc::now(),
+            "third_write".to_string(),
+            "third_distinction".to_string(),
+            None,
+            third_clock,
+        );
+        let message = Message::WriteEvent {
+            node_id: NodeId::new(),
+            key: FullKey::new("carts", "bob"),
+            value: third,
+        };
+        handle_message(message, &storage, &state, &node_id, Utc::now()).unwrap();
+        let conflicts = storage.conflicts("carts");
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].id, conflict.id);
+        assert_eq!(conflicts[0].detected_at, conflict.detected_at);
+        assert_eq!(conflicts[0].siblings.len(), 3);
+        assert_eq!(
+            conflicts[0].siblings[2].value(),
+            &serde_json::json!(["plum"])
+        );
+        assert_eq!(
+            conflicts[0].winner.as_ref().unwrap().write_id,
+            storage.get("carts", "bob").unwrap().write_id
+        );
+    }
 }
@@ -0,0 +1,266 @@
+/// Phi-accrual failure detection for cluster peers.
+///
+/// Instead of declaring a peer dead after one unanswered heartbeat, the
+/// detector learns how far apart a peer's heartbeat replies usually are and
+/// reports suspicion as `phi`: how unlikely it is, given that history, that
+/// a reply is merely late. `phi` of 1 means roughly a 10% chance the peer is
+/// still alive, 2 a 1% chance, and so on. A peer is only marked
+/// [`Unreachable`](crate::network::PeerStatus::Unreachable) once `phi`
+/// crosses [`FailureDetectorConfig::phi_threshold`], so a WAN link that drops
+/// the odd heartbeat keeps its peer healthy while one that has gone quiet is
+/// caught as soon as the silence is out of character.
+///
+/// See Hayashibara et al., "The φ Accrual Failure Detector" (2004).
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::runtime::Instant;
+
+/// Tuning for the phi-accrual failure detector.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailureDetectorConfig {
+    /// Suspicion level at which a peer is marked unreachable (default: 8.0).
+    /// Lower detects failures sooner but mistakes slow links for dead ones
+    /// more often.
+    pub phi_threshold: f64,
+    /// Heartbeat intervals remembered per peer (default: 200).
+    pub max_samples: usize,
+    /// Floor on the standard deviation of intervals, so a very regular peer
+    /// is not condemned by a little jitter (default: 500 milliseconds).
+    pub min_std_deviation: Duration,
+    /// Extra silence tolerated on top of the usual interval, e.g. for GC
+    /// pauses or WAN hiccups (default: 10 seconds).
+    pub acceptable_heartbeat_pause: Duration,
+    /// Assumed interval until a peer has answered twice (default: 5 seconds,
+    /// the default heartbeat interval).
+    pub first_heartbeat_estimate: Duration,
+}
+
+impl Default for FailureDetectorConfig {
+    fn default() -> Self {
+        Self {
+            phi_threshold: 8.0,
+            max_samples: 200,
+            min_std_deviation: Duration::from_millis(500),
+            acceptable_heartbeat_pause: Duration::from_secs(10),
+            first_heartbeat_estimate: Duration::from_secs(5),
+        }
+    }
+}
+
+impl FailureDetectorConfig {
+    /// Create a config with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the suspicion level at which a peer is marked unreachable.
+    pub fn phi_threshold(mut self, threshold: f64) -> Self {
+        self.phi_threshold = threshold;
+        self
+    }
+
+    /// Set how many heartbeat intervals are remembered per peer.
+    pub fn max_samples(mut self, samples: usize) -> Self {
+        self.max_samples = samples.max(1);
+        self
+    }
+
+    /// Set the floor on the standard deviation of intervals.
+    pub fn min_std_deviation(mut self, deviation: Duration) -> Self {
+        self.min_std_deviation = deviation;
+        self
+    }
+
+    /// Set the extra silence tolerated on top of the usual interval.
+    pub fn acceptable_heartbeat_pause(mut self, pause: Duration) -> Self {
+        self.acceptable_heartbeat_pause = pause;
+        self
+    }
+
+    /// Set the interval assumed before a peer has answered twice.
+    pub fn first_heartbeat_estimate(mut self, estimate: Duration) -> Self {
+        self.first_heartbeat_estimate = estimate;
+        self
+    }
+}
+
+/// Heartbeat history of one peer.
+#[derive(Debug, Clone)]
+pub struct PhiAccrualDetector {
+    config: FailureDetectorConfig,
+    /// Recent intervals between replies, in milliseconds.
+    intervals: VecDeque<f64>,
+    /// When the peer last replied.
+    last_heartbeat: Option<Instant>,
+    /// Whether `last_heartbeat` is only when tracking began, not a reply.
+    seeded: bool,
+}
+
+impl PhiAccrualDetector {
+    /// Create a detector with no history.
+    pub fn new(config: FailureDetectorConfig) -> Self {
+        Self {
+            intervals: VecDeque::with_capacity(config.max_samples),
+            config,
+            last_heartbeat: None,
+            seeded: false,
+        }
+    }
+
+    /// Create a detector for a peer first tracked at `since`, before it has
+    /// answered anything.
+    ///
+    /// Silence is measured from `since` against
+    /// [`FailureDetectorConfig::first_heartbeat_estimate`], so a new peer
+    /// survives dropped replies the same way a known one does.
+    pub fn seeded(config: FailureDetectorConfig, since: Instant) -> Self {
+        Self {
+            last_heartbeat: Some(since),
+            seeded: true,
+            ..Self::new(config)
+        }
+    }
+
+    /// Record a heartbeat reply received at `now`.
+    pub fn heartbeat(&mut self, now: Instant) {
+        // The seed isn't a reply, so the gap since it isn't an interval
+        if std::mem::take(&mut self.seeded) {
+            self.last_heartbeat = Some(now);
+            return;
+        }
+        if let Some(last) = self.last_heartbeat.replace(now.clone()) {
+            if self.intervals.len() >= self.config.max_samples {
+                self.intervals.pop_front();
+            }
+            self.intervals.push_back(millis(now.duration_since(last)));
+        }
+    }
+
+    /// Suspicion that the peer has failed, given silence until `now`.
+    ///
+    /// Zero for an unseeded detector before the first reply; grows without
+    /// bound as silence lengthens.
+    pub fn phi(&self, now: &Instant) -> f64 {
+        let Some(last) = &self.last_heartbeat else {
+            return 0.0;
+        };
+        let elapsed = millis(now.duration_since(last.clone()));
+        let (mean, std_deviation) = self.distribution();
+        phi(
+            elapsed,
+            mean + millis(self.config.acceptable_heartbeat_pause),
+            std_deviation,
+        )
+    }
+
+    /// Whether the peer should still be considered alive at `now`.
+    pub fn is_available(&self, now: &Instant) -> bool {
+        self.phi(now) < self.config.phi_threshold
+    }
+
+    /// Mean and standard deviation of the remembered intervals.
+    fn distribution(&self) -> (f64, f64) {
+        let min_std_deviation = millis(self.config.min_std_deviation);
+        if self.intervals.is_empty() {
+            let estimate = millis(self.config.first_heartbeat_estimate);
+            return (estimate, (estimate / 4.0).max(min_std_deviation));
+        }
+
+        let count = self.intervals.len() as f64;
+        let mean = self.intervals.iter().sum::<f64>() / count;
+        let variance = self
+            .intervals
+            .iter()
+            .map(|interval| (interval - mean).powi(2))
+            .sum::<f64>()
+            / count;
+        (mean, variance.sqrt().max(min_std_deviation))
+    }
+}
+
+/// `-log10` of the probability that a reply is still to come after
+/// `elapsed`, for normally distributed intervals (logistic approximation of
+/// the normal CDF, as in Akka's detector).
+fn phi(elapsed: f64, mean: f64, std_deviation: f64) -> f64 {
+    let y = (elapsed - mean) / std_deviation;
+    let e = (-y * (1.5976 + 0.070566 * y * y)).exp();
+    if elapsed > mean {
+        -(e / (1.0 + e)).log10()
+    } else {
+        -(1.0 - 1.0 / (1.0 + e)).log10()
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::{Runtime, TestRuntime};
+
+    #[test]
+    fn test_phi_grows_with_silence() {
+        let runtime = TestRuntime::new();
+        let config = FailureDetectorConfig::new()
+            .acceptable_heartbeat_pause(Duration::ZERO)
+            .min_std_deviation(Duration::from_millis(200));
+        let mut detector = PhiAccrualDetector::new(config);
+        assert_eq!(detector.phi(&runtime.now()), 0.0);
+
+        for _ in 0..20 {
+            detector.heartbeat(runtime.now());
+            runtime.advance(Duration::from_secs(1));
+        }
+
+        // On schedule: barely suspicious
+        let on_time = detector.phi(&runtime.now());
+        assert!(on_time < 1.0, "phi {}", on_time);
+        assert!(detector.is_available(&runtime.now()));
+
+        // Increasingly overdue: increasingly suspicious, then failed
+        runtime.advance(Duration::from_millis(500));
+        let late = detector.phi(&runtime.now());
+        assert!(late > on_time);
+        runtime.advance(Duration::from_secs(3));
+        assert!(detector.phi(&runtime.now()) > late);
+        assert!(!detector.is_available(&runtime.now()));
+    }
+
+    #[test]
+    fn test_seeded_detector_uses_first_estimate() {
+        let runtime = TestRuntime::new();
+        let mut detector =
+            PhiAccrualDetector::seeded(FailureDetectorConfig::default(), runtime.now());
+
+        // Silent since tracking began, within the first estimate plus pause
+        runtime.advance(Duration::from_secs(10));
+        assert!(detector.is_available(&runtime.now()));
+
+        // The first reply starts the history without a bogus interval
+        detector.heartbeat(runtime.now());
+        assert!(detector.intervals.is_empty());
+        runtime.advance(Duration::from_secs(5));
+        detector.heartbeat(runtime.now());
+        assert_eq!(detector.intervals, [5_000.0]);
+    }
+
+    #[test]
+    fn test_heartbeat_pause_tolerates_missed_replies() {
+        let runtime = TestRuntime::new();
+        let mut detector = PhiAccrualDetector::new(FailureDetectorConfig::default());
+        for _ in 0..10 {
+            detector.heartbeat(runtime.now());
+            runtime.advance(Duration::from_secs(5));
+        }
+
+        // Two missed 5-second heartbeats are within the 10-second pause
+        runtime.advance(Duration::from_secs(10));
+        assert!(detector.is_available(&runtime.now()));
+
+        runtime.advance(Duration::from_secs(30));
+        assert!(!detector.is_available(&runtime.now()));
+    }
+}
@@ -0,0 +1,591 @@
+/// Deterministic simulation mode for clusters.
+///
+/// Runs several cluster nodes in one thread over a simulated network, in
+/// the spirit of FoundationDB's simulation testing. Nodes exchange the same
+/// [`Message`]s and run the same handlers as a [`ClusterNode`](super::ClusterNode),
+/// but messages travel through an event queue on a virtual clock instead of
+/// TCP: each one is delayed by a configurable latency plus seeded random
+/// jitter, may be dropped at random, and never crosses a partition.
+///
+/// Nothing happens until the test drives the clock, and every random choice
+/// comes from the configured seed, so a scenario replays identically: the
+/// same seed gives the same deliveries, drops and interleavings. (Version
+/// timestamps still come from the wall clock, so last-write-wins conflict
+/// resolution is not simulated.)
+///
+/// Write broadcasts are retried after an ACK timeout like
+/// [`broadcast_write`](super::ClusterNode::broadcast_write), and each node
+/// runs anti-entropy on its own interval.
+///
+/// # Example
+///
+/// ```ignore
+/// let config = SimulationConfig::new()
+///     .latency(Duration::from_millis(5))
+///     .jitter(Duration::from_millis(20))
+///     .drop_rate(0.1)
+///     .seed(42);
+/// let mut cluster = SimulatedCluster::new(3, config);
+///
+/// cluster.partition(&[0, 0, 1]);
+/// cluster.put(0, "users", "alice", json!({"age": 30}))?;
+/// cluster.run_for(Duration::from_secs(1));
+/// assert!(!cluster.is_converged());
+///
+/// cluster.heal();
+/// cluster.run_for(Duration::from_secs(30));
+/// assert!(cluster.is_converged());
+/// ```
+use std::collections::{BTreeMap, HashSet};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use koru_lambda_core::DistinctionEngine;
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+use super::{ClusterState, apply_sync_response, handle_message, sync_request};
+use crate::error::{DeltaError, DeltaResult};
+use crate::network::{DEFAULT_PORT, Message, NodeId, PeerInfo, PeerStatus};
+use crate::storage::CausalStorage;
+use crate::types::{FullKey, VersionedValue};
+
+/// How long a write broadcast waits for an ACK before retrying.
+const ACK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Attempts per write broadcast, including the first.
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Behaviour of the simulated network.
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    /// Base one-way delay of every message (default: 1ms).
+    pub latency: Duration,
+    /// Up to this much extra random delay per message (default: none).
+    /// Non-zero jitter reorders messages.
+    pub jitter: Duration,
+    /// Probability that a message is lost in transit (default: 0).
+    pub drop_rate: f64,
+    /// Interval between anti-entropy rounds on each node, or `None` to only
+    /// run them on demand (default: 30 seconds, as on a real node).
+    pub anti_entropy_interval: Option<Duration>,
+    /// Seed for every random choice the network makes (default: 0).
+    pub seed: u64,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            latency: Duration::from_millis(1),
+            jitter: Duration::ZERO,
+            drop_rate: 0.0,
+            anti_entropy_interval: Some(Duration::from_secs(30)),
+            seed: 0,
+        }
+    }
+}
+
+impl SimulationConfig {
+    /// Create a config with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the base message latency.
+    pub fn latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Set the maximum random extra delay per message.
+    pub fn jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Set the probability of losing a message (clamped to `0.0..=1.0`).
+    pub fn drop_rate(mut self, drop_rate: f64) -> Self {
+        self.drop_rate = drop_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the anti-entropy interval (`None` disables periodic rounds).
+    pub fn anti_entropy_interval(mut self, interval: Option<Duration>) -> Self {
+        self.anti_entropy_interval = interval;
+        self
+    }
+
+    /// Set the random seed.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+/// Message counters of a simulated network.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NetworkStats {
+    /// Messages handed to the network.
+    pub sent: u64,
+    /// Messages that reached their destination.
+    pub delivered: u64,
+    /// Messages lost to random drops or partitions.
+    pub dropped: u64,
+}
+
+/// Something scheduled on the virtual clock.
+enum Event {
+    /// A message arrives at `to`.
+    Deliver {
+        from: usize,
+        to: usize,
+        message: Message,
+    },
+    /// A write broadcast's ACK timeout expires.
+    Retry {
+        from: usize,
+        to: usize,
+        message: Message,
+        attempt: u32,
+    },
+    /// A node's periodic anti-entropy round.
+    AntiEntropy { node: usize },
+}
+
+/// One simulated node: the state a cluster node keeps, without sockets.
+struct SimNode {
+    node_id: NodeId,
+    storage: Arc<CausalStorage>,
+    state: Arc<ClusterState>,
+}
+
+/// A cluster of in-process nodes on a deterministic simulated network.
+pub struct SimulatedCluster {
+    nodes: Vec<SimNode>,
+    config: SimulationConfig,
+    rng: StdRng,
+    /// Virtual time since the simulation started.
+    now: Duration,
+    /// Scheduled events keyed by (time, sequence number).
+    events: BTreeMap<(Duration, u64), Event>,
+    next_event: u64,
+    /// Write broadcasts awaiting an ACK: (writer, peer, key, version ID).
+    unacked: HashSet<(usize, usize, FullKey, String)>,
+    /// Partition group of each node.
+    groups: Vec<usize>,
+    stats: NetworkStats,
+}
+
+impl SimulatedCluster {
+    /// Start `nodes` empty nodes that already know each other as healthy
+    /// peers.
+    pub fn new(nodes: usize, config: SimulationConfig) -> Self {
+        let addresses: Vec<SocketAddr> = (0..nodes)
+            .map(|i| SocketAddr::from((Ipv4Addr::from(0x0a00_0001 + i as u32), DEFAULT_PORT)))
+            .collect();
+        // Node IDs come from the seed too, so origin stamps replay as well
+        let mut rng = StdRng::seed_from_u64(config.seed);
+        let node_ids: Vec<NodeId> = (0..nodes)
+            .map(|_| NodeId::from_uuid(Uuid::from_u64_pair(rng.next_u64(), rng.next_u64())))
+            .collect();
+
+        let nodes = addresses
+            .iter()
+            .zip(&node_ids)
+            .map(|(address, node_id)| {
+                let storage = Arc::new(CausalStorage::new(Arc::new(DistinctionEngine::new())));
+                storage.set_origin_node(node_id.to_string());
+                let state = Arc::new(ClusterState::new(*address));
+                for (peer_address, peer_id) in addresses.iter().zip(&node_ids) {
+                    if peer_id != node_id {
+                        let mut peer = PeerInfo::new(peer_id.clone(), *peer_address);
+                        peer.status = PeerStatus::Healthy;
+                        state.upsert_peer(peer);
+                    }
+                }
+                SimNode {
+                    node_id: node_id.clone(),
+                    storage,
+                    state,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut cluster = Self {
+            groups: vec![0; nodes.len()],
+            nodes,
+            rng,
+            config,
+            now: Duration::ZERO,
+            events: BTreeMap::new(),
+            next_event: 0,
+            unacked: HashSet::new(),
+            stats: NetworkStats::default(),
+        };
+        if let Some(interval) = cluster.config.anti_entropy_interval {
+            for node in 0..cluster.nodes.len() {
+                cluster.schedule(interval, Event::AntiEntropy { node });
+            }
+        }
+        cluster
+    }
+
+    /// Number of nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the cluster has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The ID of node `node`.
+    pub fn node_id(&self, node: usize) -> &NodeId {
+        &self.nodes[node].node_id
+    }
+
+    /// The storage of node `node`, for reads and direct local writes.
+    pub fn storage(&self, node: usize) -> &Arc<CausalStorage> {
+        &self.nodes[node].storage
+    }
+
+    /// The peers node `node` knows about.
+    pub fn peers(&self, node: usize) -> Vec<PeerInfo> {
+        self.nodes[node].state.get_peers()
+    }
+
+    /// Virtual time since the simulation started.
+    pub fn now(&self) -> Duration {
+        self.now
+    }
+
+    /// Message counters so far.
+    pub fn stats(&self) -> NetworkStats {
+        self.stats
+    }
+
+    /// Write on `node` and broadcast the write to its peers.
+    pub fn put(
+        &mut self,
+        node: usize,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        value: JsonValue,
+    ) -> DeltaResult<VersionedValue> {
+        self.check_node(node)?;
+        let key = FullKey::new(namespace, key);
+        let versioned = self.nodes[node]
+            .storage
+            .put(&key.namespace, &key.key, value)?;
+
+        let message = Message::WriteEvent {
+            node_id: self.nodes[node].node_id.clone(),
+            key: key.clone(),
+            value: versioned.clone(),
+        };
+        for peer in 0..self.nodes.len() {
+            if peer != node {
+                self.unacked
+                    .insert((node, peer, key.clone(), versioned.write_id.clone()));
+                self.broadcast(node, peer, message.clone(), 1);
+            }
+        }
+        Ok(versioned)
+    }
+
+    /// Start an anti-entropy round from `node` to every peer now, instead of
+    /// waiting for its next interval.
+    pub fn anti_entropy(&mut self, node: usize) -> DeltaResult<()> {
+        self.check_node(node)?;
+        let request = sync_request(&self.nodes[node].storage, &self.nodes[node].node_id);
+        for peer in 0..self.nodes.len() {
+            if peer != node {
+                self.send(node, peer, request.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Split the network: node `i` can only reach nodes in the same group
+    /// as `groups[i]`. Nodes past the end of `groups` join group 0.
+    /// Messages already in flight across the new split are lost.
+    pub fn partition(&mut self, groups: &[usize]) {
+        for (node, group) in self.groups.iter_mut().enumerate() {
+            *group = groups.get(node).copied().unwrap_or(0);
+        }
+    }
+
+    /// Reconnect every node.
+    pub fn heal(&mut self) {
+        self.groups.fill(0);
+    }
+
+    /// Whether `a` and `b` can currently reach each other.
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        self.groups[a] == self.groups[b]
+    }
+
+    /// Run the next scheduled event, moving the clock to it.
+    ///
+    /// Returns `false` if nothing is scheduled.
+    pub fn step(&mut self) -> bool {
+        let Some(((at, _), event)) = self.events.pop_first() else {
+            return false;
+        };
+        self.now = self.now.max(at);
+        self.handle(event);
+        true
+    }
+
+    /// Run every event due in the next `duration` of virtual time.
+    pub fn run_for(&mut self, duration: Duration) {
+        let until = self.now + duration;
+        while let Some(&(at, _)) = self.events.keys().next() {
+            if at > until {
+                break;
+            }
+            self.step();
+        }
+        self.now = until;
+    }
+
+    /// Run until no messages are in flight and no write broadcast is
+    /// waiting to be retried. Periodic anti-entropy timers stay scheduled.
+    pub fn run_until_quiet(&mut self) {
+        while self.events.values().any(|event| self.in_flight(event)) {
+            self.step();
+        }
+    }
+
+    /// The current value of every key on `node`.
+    pub fn values(&self, node: usize) -> BTreeMap<(String, String), JsonValue> {
+        self.nodes[node]
+            .storage
+            .scan_all()
+            .into_iter()
+            .map(|(key, versioned)| ((key.namespace, key.key), versioned.value().clone()))
+            .collect()
+    }
+
+    /// Whether every node holds the same value for every key.
+    pub fn is_converged(&self) -> bool {
+        let values: Vec<_> = (0..self.nodes.len())
+            .map(|node| self.values(node))
+            .collect();
+        values.windows(2).all(|pair| pair[0] == pair[1])
+    }
+
+    /// Whether `event` is network activity still under way.
+    fn in_flight(&self, event: &Event) -> bool {
+        match event {
+            Event::Deliver { .. } => true,
+            Event::Retry {
+                from, to, message, ..
+            } => match message {
+                Message::WriteEvent { key, value, .. } => {
+                    self.unacked
+                        .contains(&(*from, *to, key.clone(), value.write_id.clone()))
+                }
+                _ => false,
+            },
+            Event::AntiEntropy { .. } => false,
+        }
+    }
+
+    fn schedule(&mut self, delay: Duration, event: Event) {
+        self.next_event += 1;
+        self.events
+            .insert((self.now + delay, self.next_event), event);
+    }
+
+    /// Put a message on the wire, unless it is lost.
+    fn send(&mut self, from: usize, to: usize, message: Message) {
+        self.stats.sent += 1;
+        if !self.connected(from, to) || self.rng.gen_bool(self.config.drop_rate.clamp(0.0, 1.0)) {
+            self.stats.dropped += 1;
+            return;
+        }
+        let jitter = self.rng.gen_range(0..=self.config.jitter.as_nanos() as u64);
+        let delay = self.config.latency + Duration::from_nanos(jitter);
+        self.schedule(delay, Event::Deliver { from, to, message });
+    }
+
+    /// Send attempt `attempt` of a write broadcast and arm its ACK timeout.
+    fn broadcast(&mut self, from: usize, to: usize, message: Message, attempt: u32) {
+        self.send(from, to, message.clone());
+        // Backoff between attempts, as in ClusterNode::broadcast_write
+        let backoff = Duration::from_millis(100 * attempt as u64);
+        self.schedule(
+            ACK_TIMEOUT + backoff,
+            Event::Retry {
+                from,
+                to,
+                message,
+                attempt,
+            },
+        );
+    }
+
+    fn handle(&mut self, event: Event) {
+        match event {
+            Event::Deliver { from, to, message } => self.deliver(from, to, message),
+            Event::Retry {
+                from,
+                to,
+                message,
+                attempt,
+            } => {
+                let Message::WriteEvent { key, value, .. } = &message else {
+                    return;
+                };
+                let pending = (from, to, key.clone(), value.write_id.clone());
+                if !self.unacked.contains(&pending) {
+                    return;
+                }
+                if attempt < MAX_ATTEMPTS {
+                    self.broadcast(from, to, message, attempt + 1);
+                } else {
+                    tracing::debug!("Simulated write broadcast {} -> {} gave up", from, to);
+                    self.unacked.remove(&pending);
+                }
+            }
+            Event::AntiEntropy { node } => {
+                let _ = self.anti_entropy(node);
+                if let Some(interval) = self.config.anti_entropy_interval {
+                    self.schedule(interval, Event::AntiEntropy { node });
+                }
+            }
+        }
+    }
+
+    /// Hand a message to its destination node and send back any reply.
+    fn deliver(&mut self, from: usize, to: usize, message: Message) {
+        // Links cut while the message was in flight lose it
+        if !self.connected(from, to) {
+            self.stats.dropped += 1;
+            return;
+        }
+        self.stats.delivered += 1;
+
+        let node = &self.nodes[to];
+        let reply = match message {
+            Message::SyncResponse {
+                updates,
+                tombstones,
+                ..
+            } => {
+                apply_sync_response(&node.storage, updates, tombstones);
+                None
+            }
+            Message::WriteAck {
+                key, version_id, ..
+            } => {
+                self.unacked.remove(&(to, from, key, version_id));
+                None
+            }
+            // Wall-clock time follows virtual time, from the Unix epoch
+            message => match handle_message(
+                message,
+                &node.storage,
+                &node.state,
+                &node.node_id,
+                (std::time::UNIX_EPOCH + self.now).into(),
+            ) {
+                Ok(reply) => reply,
+                Err(e) => {
+                    tracing::debug!("Simulated node {} failed to handle message: {}", to, e);
+                    None
+                }
+            },
+        };
+        if let Some(reply) = reply {
+            self.send(to, from, reply);
+        }
+    }
+
+    fn check_node(&self, node: usize) -> DeltaResult<()> {
+        if node >= self.nodes.len() {
+            return Err(DeltaError::InvalidData {
+                reason: format!("node {} does not exist ({} nodes)", node, self.nodes.len()),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_writes_replicate_to_every_node() {
+        let mut cluster = SimulatedCluster::new(3, SimulationConfig::new());
+        for node in 0..3 {
+            cluster
+                .put(
+                    node,
+                    "users",
+                    format!("user{}", node),
+                    json!({"node": node}),
+                )
+                .unwrap();
+        }
+        assert!(!cluster.is_converged());
+
+        cluster.run_until_quiet();
+        assert!(cluster.is_converged());
+        assert_eq!(cluster.values(2).len(), 3);
+        assert_eq!(cluster.stats().dropped, 0);
+        // Every write event was acknowledged: nothing is retried
+        assert_eq!(cluster.stats().sent, 12);
+        assert_eq!(cluster.now(), Duration::from_millis(2));
+    }
+
+    #[test]
+    fn test_partition_delays_writes_until_retry_after_heal() {
+        let config = SimulationConfig::new().anti_entropy_interval(None);
+        let mut cluster = SimulatedCluster::new(3, config);
+        cluster.partition(&[0, 0, 1]);
+        cluster
+            .put(0, "users", "alice", json!({"age": 30}))
+            .unwrap();
+
+        cluster.run_for(Duration::from_secs(1));
+        assert!(cluster.storage(1).get("users", "alice").is_ok());
+        assert!(cluster.storage(2).get("users", "alice").is_err());
+
+        // The first retry fires after the ACK timeout and gets through
+        cluster.heal();
+        cluster.run_for(Duration::from_secs(10));
+        assert!(cluster.is_converged());
+        assert!(cluster.put(3, "users", "bob", json!({})).is_err());
+    }
+
+    #[test]
+    fn test_same_seed_replays_identically() {
+        let run = |seed| {
+            let config = SimulationConfig::new()
+                .latency(Duration::from_millis(5))
+                .jitter(Duration::from_millis(50))
+                .drop_rate(0.3)
+                .seed(seed);
+            let mut cluster = SimulatedCluster::new(4, config);
+            for i in 0..20 {
+                cluster
+                    .put(i % 4, "events", format!("e{}", i), json!({"i": i}))
+                    .unwrap();
+            }
+            cluster.run_for(Duration::from_secs(60));
+            (cluster.stats(), cluster.values(3).len())
+        };
+
+        let (stats, keys) = run(7);
+        assert!(stats.dropped > 0);
+        assert_eq!(run(7), (stats, keys));
+    }
+}
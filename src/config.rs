@@ -0,0 +1,547 @@
+/// Configuration loading from files and the environment.
+///
+/// A [`CoreConfig`] can be read from a TOML file, from `KORU_*` environment
+/// variables, or both, so deployments keep settings out of code:
+///
+/// ```toml
+/// [storage]
+/// path = "/var/lib/koru"
+///
+/// [cluster]
+/// bind = "0.0.0.0:7878"
+/// seeds = ["10.0.0.2:7878", "10.0.0.3:7878"]
+///
+/// [http]
+/// bind = "127.0.0.1:8080"
+///
+/// [auth]
+/// identity_difficulty = 4
+///
+/// [processes]
+/// lifecycle_interval = "5m"
+///
+/// [embedder]
+/// model_dir = "/opt/models/all-MiniLM-L6-v2"
+/// ```
+///
+/// Every file key has an environment variable: upper-cased, prefixed with
+/// `KORU_`, with dots as underscores (`cluster.seeds` is
+/// `KORU_CLUSTER_SEEDS`). Lists are comma-separated in the environment, and
+/// durations are whole seconds or a number with an `s`, `m`, `h` or `d`
+/// suffix. Settings left out keep their defaults.
+///
+/// Unknown keys, values that don't parse and values out of range fail with
+/// [`DeltaError::InvalidConfig`] naming the key or variable.
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::auth::{MAX_DIFFICULTY, MIN_DIFFICULTY};
+use crate::core::CoreConfig;
+use crate::error::{DeltaError, DeltaResult};
+
+/// Every setting, by file key.
+pub const KEYS: &[&str] = &[
+    "storage.path",
+    "cluster.bind",
+    "cluster.seeds",
+    "http.bind",
+    "auth.identity_difficulty",
+    "auth.challenge_ttl",
+    "auth.session_ttl",
+    "memory.hot_capacity",
+    "memory.warm_capacity",
+    "limits.max_memory_mb",
+    "limits.max_disk_mb",
+    "limits.query_timeout",
+    "limits.query_max_scanned",
+    "limits.query_max_memory",
+    "limits.query_max_rows",
+    "processes.enabled",
+    "processes.consolidation_interval",
+    "processes.distillation_interval",
+    "processes.genome_interval",
+    "processes.lifecycle_interval",
+    "processes.compaction_interval",
+    "processes.capability_expiry_interval",
+    "embedder.model_dir",
+    "embedder.hub_model",
+];
+
+/// Default configuration file name, looked up in the working directory.
+pub const DEFAULT_CONFIG_FILE: &str = "koru.toml";
+
+/// Environment variable for a file key: `cluster.seeds` is
+/// `KORU_CLUSTER_SEEDS`.
+pub fn env_var(key: &str) -> String {
+    format!("KORU_{}", key.replace('.', "_").to_uppercase())
+}
+
+impl CoreConfig {
+    /// Defaults overridden by any `KORU_*` environment variables.
+    pub fn from_env() -> DeltaResult<Self> {
+        Self::default().with_env()
+    }
+
+    /// This configuration overridden by any `KORU_*` environment variables.
+    ///
+    /// Use after [`from_file`](Self::from_file) to let the environment
+    /// override the file.
+    pub fn with_env(self) -> DeltaResult<Self> {
+        self.with_vars(|name| match std::env::var(name) {
+            Ok(value) => Ok(Some(value)),
+            Err(std::env::VarError::NotPresent) => Ok(None),
+            Err(std::env::VarError::NotUnicode(_)) => Err(invalid(name, "not valid UTF-8")),
+        })
+    }
+
+    /// Defaults overridden by the TOML file at `path` (e.g. `koru.toml`).
+    pub fn from_file(path: impl AsRef<Path>) -> DeltaResult<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            DeltaError::StorageError(format!(
+                "Failed to read config file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Self::from_toml(&text)
+    }
+
+    /// Defaults overridden by the TOML document `text`.
+    pub fn from_toml(text: &str) -> DeltaResult<Self> {
+        let table: toml::Table = toml::from_str(text).map_err(|e| DeltaError::InvalidData {
+            reason: format!("Invalid configuration file: {}", e.message()),
+        })?;
+
+        let mut config = Self::default();
+        for (section, entries) in &table {
+            let toml::Value::Table(entries) = entries else {
+                return Err(invalid(section, "expected a [section] of settings"));
+            };
+            for (name, value) in entries {
+                let key = format!("{}.{}", section, name);
+                let value = toml_value(&key, value)?;
+                config.set(&key, &key, &value)?;
+            }
+        }
+        config.validate_named(str::to_string)?;
+        Ok(config)
+    }
+
+    /// Check every setting is in range, naming the first key that isn't.
+    pub fn validate(&self) -> DeltaResult<()> {
+        self.validate_named(str::to_string)
+    }
+
+    /// Apply the variables `lookup` finds, then validate.
+    fn with_vars(
+        mut self,
+        lookup: impl Fn(&str) -> DeltaResult<Option<String>>,
+    ) -> DeltaResult<Self> {
+        for key in KEYS {
+            let var = env_var(key);
+            if let Some(value) = lookup(&var)? {
+                self.set(key, &var, &value)?;
+            }
+        }
+        self.validate_named(env_var)?;
+        Ok(self)
+    }
+
+    /// Set `key` from its textual `value`; errors name the setting `name`.
+    fn set(&mut self, key: &str, name: &str, value: &str) -> DeltaResult<()> {
+        let value = value.trim();
+        match key {
+            "storage.path" => self.storage.path = Some(PathBuf::from(value)),
+            "cluster.bind" => self.network.cluster_bind = Some(parse(name, value)?),
+            "cluster.seeds" => {
+                self.network.cluster_seeds = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|seed| !seed.is_empty())
+                    .map(|seed| parse(name, seed))
+                    .collect::<DeltaResult<_>>()?;
+            }
+            "http.bind" => self.network.http_bind = Some(parse(name, value)?),
+            "auth.identity_difficulty" => self.auth.identity_difficulty = parse(name, value)?,
+            "auth.challenge_ttl" => {
+                self.auth.challenge_ttl_seconds = seconds(name, parse_duration(name, value)?)?
+            }
+            "auth.session_ttl" => {
+                self.auth.session_ttl_seconds = seconds(name, parse_duration(name, value)?)?
+            }
+            "memory.hot_capacity" => self.memory.hot_capacity = parse(name, value)?,
+            "memory.warm_capacity" => self.memory.warm_capacity = parse(name, value)?,
+            "limits.max_memory_mb" => self.limits.max_memory_mb = parse(name, value)?,
+            "limits.max_disk_mb" => self.limits.max_disk_mb = parse(name, value)?,
+            "limits.query_timeout" => self.limits.query_timeout = parse_duration(name, value)?,
+            "limits.query_max_scanned" => {
+                self.limits.query_limits.max_scanned = parse(name, value)?
+            }
+            "limits.query_max_memory" => self.limits.query_limits.max_memory = parse(name, value)?,
+            "limits.query_max_rows" => self.limits.query_limits.max_rows = parse(name, value)?,
+            "processes.enabled" => self.processes.enabled = parse(name, value)?,
+            "processes.consolidation_interval" => {
+                self.processes.consolidation_interval = parse_duration(name, value)?
+            }
+            "processes.distillation_interval" => {
+                self.processes.distillation_interval = parse_duration(name, value)?
+            }
+            "processes.genome_interval" => {
+                self.processes.genome_interval = parse_duration(name, value)?
+            }
+            "processes.lifecycle_interval" => {
+                self.processes.lifecycle_interval = parse_duration(name, value)?
+            }
+            "processes.compaction_interval" => {
+                self.processes.compaction_interval = parse_duration(name, value)?
+            }
+            "processes.capability_expiry_interval" => {
+                self.processes.capability_expiry_interval = parse_duration(name, value)?
+            }
+            "embedder.model_dir" => self.embedder.model_dir = Some(PathBuf::from(value)),
+            "embedder.hub_model" => self.embedder.hub_model = Some(value.to_string()),
+            _ => return Err(invalid(name, "unknown setting")),
+        }
+        Ok(())
+    }
+
+    /// Range checks; `name` maps a file key to the name errors report.
+    fn validate_named(&self, name: impl Fn(&str) -> String) -> DeltaResult<()> {
+        let check = |ok: bool, key: &str, reason: String| {
+            if ok {
+                Ok(())
+            } else {
+                Err(invalid(&name(key), reason))
+            }
+        };
+
+        if let Some(path) = &self.storage.path {
+            check(
+                !path.as_os_str().is_empty(),
+                "storage.path",
+                "must not be empty".to_string(),
+            )?;
+        }
+        if let (Some(http), Some(cluster)) = (self.network.http_bind, self.network.cluster_bind) {
+            check(
+                http != cluster,
+                "http.bind",
+                format!("{} is already used by cluster.bind", http),
+            )?;
+        }
+
+        let difficulty = self.auth.identity_difficulty;
+        check(
+            (MIN_DIFFICULTY..=MAX_DIFFICULTY).contains(&difficulty),
+            "auth.identity_difficulty",
+            format!(
+                "must be between {} and {}, got {}",
+                MIN_DIFFICULTY, MAX_DIFFICULTY, difficulty
+            ),
+        )?;
+        check(
+            self.auth.challenge_ttl_seconds > 0,
+            "auth.challenge_ttl",
+            "must be greater than zero".to_string(),
+        )?;
+        check(
+            self.auth.session_ttl_seconds > 0,
+            "auth.session_ttl",
+            "must be greater than zero".to_string(),
+        )?;
+
+        check(
+            self.memory.hot_capacity > 0,
+            "memory.hot_capacity",
+            "must be greater than zero".to_string(),
+        )?;
+        check(
+            self.memory.warm_capacity > 0,
+            "memory.warm_capacity",
+            "must be greater than zero".to_string(),
+        )?;
+
+        let processes = &self.processes;
+        for (key, interval) in [
+            (
+                "processes.consolidation_interval",
+                processes.consolidation_interval,
+            ),
+            (
+                "processes.distillation_interval",
+                processes.distillation_interval,
+            ),
+            ("processes.genome_interval", processes.genome_interval),
+            ("processes.lifecycle_interval", processes.lifecycle_interval),
+            (
+                "processes.compaction_interval",
+                processes.compaction_interval,
+            ),
+            (
+                "processes.capability_expiry_interval",
+                processes.capability_expiry_interval,
+            ),
+        ] {
+            check(
+                !interval.is_zero(),
+                key,
+                "must be greater than zero".to_string(),
+            )?;
+        }
+
+        let embedder = &self.embedder;
+        if embedder.model_dir.is_some() || embedder.hub_model.is_some() {
+            let key = if embedder.model_dir.is_some() {
+                "embedder.model_dir"
+            } else {
+                "embedder.hub_model"
+            };
+            check(
+                cfg!(all(not(target_arch = "wasm32"), feature = "embedder")),
+                key,
+                "requires the embedder feature".to_string(),
+            )?;
+            check(
+                embedder.model_dir.is_none() || embedder.hub_model.is_none(),
+                "embedder.hub_model",
+                "conflicts with embedder.model_dir".to_string(),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn invalid(key: &str, reason: impl Into<String>) -> DeltaError {
+    DeltaError::InvalidConfig {
+        key: key.to_string(),
+        reason: reason.into(),
+    }
+}
+
+/// Parse `value` for the setting `name`.
+fn parse<T: FromStr>(name: &str, value: &str) -> DeltaResult<T>
+where
+    T::Err: std::fmt::Display,
+{
+    value
+        .parse()
+        .map_err(|e| invalid(name, format!("cannot parse '{}': {}", value, e)))
+}
+
+/// Parse a duration: whole seconds, or a number with an `s`, `m`, `h` or
+/// `d` suffix.
+fn parse_duration(name: &str, value: &str) -> DeltaResult<Duration> {
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(at) => value.split_at(at),
+        None => (value, "s"),
+    };
+    let multiplier = match unit.trim() {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => {
+            return Err(invalid(
+                name,
+                format!("expected a duration such as 30s, 5m or 1h, got '{}'", value),
+            ));
+        }
+    };
+    let number: u64 = number.parse().map_err(|_| {
+        invalid(
+            name,
+            format!("expected a duration such as 30s, 5m or 1h, got '{}'", value),
+        )
+    })?;
+    Ok(Duration::from_secs(number.saturating_mul(multiplier)))
+}
+
+fn seconds(name: &str, duration: Duration) -> DeltaResult<i64> {
+    i64::try_from(duration.as_secs()).map_err(|_| invalid(name, "duration is too long"))
+}
+
+/// A TOML value as the text an environment variable would hold.
+fn toml_value(key: &str, value: &toml::Value) -> DeltaResult<String> {
+    match value {
+        toml::Value::String(s) => Ok(s.clone()),
+        toml::Value::Integer(i) => Ok(i.to_string()),
+        toml::Value::Boolean(b) => Ok(b.to_string()),
+        toml::Value::Array(items) => Ok(items
+            .iter()
+            .map(|item| match item {
+                toml::Value::Array(_) | toml::Value::Table(_) => {
+                    Err(invalid(key, "lists may only hold plain values"))
+                }
+                item => toml_value(key, item),
+            })
+            .collect::<DeltaResult<Vec<_>>>()?
+            .join(",")),
+        _ => Err(invalid(key, "expected a string, integer, boolean or list")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::net::SocketAddr;
+
+    fn from_vars(vars: &[(&str, &str)]) -> DeltaResult<CoreConfig> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        CoreConfig::default().with_vars(|name| Ok(vars.get(name).cloned()))
+    }
+
+    fn invalid_key(result: DeltaResult<CoreConfig>) -> String {
+        match result {
+            Err(DeltaError::InvalidConfig { key, .. }) => key,
+            other => panic!("expected an invalid config error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_from_toml() {
+        let config = CoreConfig::from_toml(
+            r#"
+            [storage]
+            path = "/var/lib/koru"
+
+            [cluster]
+            bind = "0.0.0.0:7878"
+            seeds = ["10.0.0.2:7878", "10.0.0.3:7878"]
+
+            [http]
+            bind = "127.0.0.1:8080"
+
+            [auth]
+            identity_difficulty = 3
+            session_ttl = "12h"
+
+            [processes]
+            enabled = false
+            lifecycle_interval = "5m"
+            genome_interval = 3600
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.storage.path, Some(PathBuf::from("/var/lib/koru")));
+        let seeds: Vec<SocketAddr> = vec![
+            "10.0.0.2:7878".parse().unwrap(),
+            "10.0.0.3:7878".parse().unwrap(),
+        ];
+        assert_eq!(config.network.cluster_seeds, seeds);
+        assert_eq!(
+            config.network.http_bind,
+            Some("127.0.0.1:8080".parse().unwrap())
+        );
+        assert_eq!(config.auth.identity_difficulty, 3);
+        assert_eq!(config.auth.session_ttl_seconds, 12 * 3600);
+        assert!(!config.processes.enabled);
+        assert_eq!(
+            config.processes.lifecycle_interval,
+            Duration::from_secs(300)
+        );
+        assert_eq!(config.processes.genome_interval, Duration::from_secs(3600));
+        // Untouched settings keep their defaults
+        assert_eq!(config.memory.hot_capacity, 1000);
+    }
+
+    #[test]
+    fn test_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(DEFAULT_CONFIG_FILE);
+        std::fs::write(&path, "[memory]\nhot_capacity = 50\n").unwrap();
+
+        let config = CoreConfig::from_file(&path).unwrap();
+        assert_eq!(config.memory.hot_capacity, 50);
+
+        assert!(matches!(
+            CoreConfig::from_file(dir.path().join("missing.toml")),
+            Err(DeltaError::StorageError(_))
+        ));
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let config = from_vars(&[
+            ("KORU_CLUSTER_SEEDS", "10.0.0.2:7878, 10.0.0.3:7878"),
+            ("KORU_LIMITS_MAX_MEMORY_MB", "2048"),
+            ("KORU_PROCESSES_CONSOLIDATION_INTERVAL", "90"),
+        ])
+        .unwrap();
+
+        assert_eq!(config.network.cluster_seeds.len(), 2);
+        assert_eq!(config.limits.max_memory_mb, 2048);
+        assert_eq!(
+            config.processes.consolidation_interval,
+            Duration::from_secs(90)
+        );
+    }
+
+    #[test]
+    fn test_errors_name_the_key() {
+        assert_eq!(
+            invalid_key(CoreConfig::from_toml("[auth]\nidentity_difficulty = 20\n")),
+            "auth.identity_difficulty"
+        );
+        assert_eq!(
+            invalid_key(CoreConfig::from_toml(
+                "[cluster]\nbind = \"not an address\"\n"
+            )),
+            "cluster.bind"
+        );
+        assert_eq!(
+            invalid_key(CoreConfig::from_toml(
+                "[processes]\ngenome_interval = \"0s\"\n"
+            )),
+            "processes.genome_interval"
+        );
+        assert_eq!(
+            invalid_key(CoreConfig::from_toml("[storage]\nlocation = \"/tmp\"\n")),
+            "storage.location"
+        );
+        assert_eq!(
+            invalid_key(from_vars(&[("KORU_PROCESSES_LIFECYCLE_INTERVAL", "soon")])),
+            "KORU_PROCESSES_LIFECYCLE_INTERVAL"
+        );
+        assert_eq!(
+            invalid_key(from_vars(&[("KORU_AUTH_IDENTITY_DIFFICULTY", "1")])),
+            "KORU_AUTH_IDENTITY_DIFFICULTY"
+        );
+    }
+
+    #[test]
+    fn test_embedder_model() {
+        let toml = "[embedder]\nmodel_dir = \"/opt/models/minilm\"\n";
+        #[cfg(all(not(target_arch = "wasm32"), feature = "embedder"))]
+        {
+            let config = CoreConfig::from_toml(toml).unwrap();
+            assert_eq!(
+                config.embedder.model_dir,
+                Some(PathBuf::from("/opt/models/minilm"))
+            );
+            assert_eq!(
+                invalid_key(CoreConfig::from_toml(&format!(
+                    "{}hub_model = \"sentence-transformers/all-MiniLM-L6-v2\"\n",
+                    toml
+                ))),
+                "embedder.hub_model"
+            );
+        }
+        #[cfg(not(all(not(target_arch = "wasm32"), feature = "embedder")))]
+        assert_eq!(
+            invalid_key(CoreConfig::from_toml(toml)),
+            "embedder.model_dir"
+        );
+
+        // No model unless configured
+        assert_eq!(CoreConfig::default().embedder.model_dir, None);
+        assert_eq!(CoreConfig::default().embedder.hub_model, None);
+    }
+}
@@ -41,48 +41,92 @@
 //! }
 //! ```
 
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::time::Duration;
 
 use chrono::{DateTime, Utc};
+use dashmap::DashMap;
 #[cfg(not(target_arch = "wasm32"))]
 use futures::FutureExt;
 use koru_lambda_core::{Canonicalizable, Distinction, DistinctionEngine, LocalCausalAgent};
 use serde::Serialize;
+use serde_json::value::RawValue;
+#[cfg(not(target_arch = "wasm32"))]
+use tracing::Instrument;
+use tracing::field::Empty;
+use tracing::{Span, instrument};
 #[cfg(not(target_arch = "wasm32"))]
 use tracing::{debug, error, info, trace, warn};
 #[cfg(target_arch = "wasm32")]
 use tracing::{debug, info, trace, warn};
 
-use crate::actions::StorageAction;
-use crate::auth::{IdentityAgent, IdentityConfig};
-use crate::engine::{FieldHandle, SharedEngine};
+use crate::actions::{StorageAction, TemperatureLevel};
+use crate::auth::{
+    AuthenticatedDelta, ENCRYPTION_CONFIG_NAMESPACE, IdentityAgent, IdentityConfig,
+    NamespaceEncryption, SealedValue,
+};
+use crate::builder::KoruDeltaBuilder;
+use crate::catalog::{self, CatalogTable};
+use crate::causal_graph::GraphFormat;
+use crate::durability::{Durability, Durable, PutOptions, PutReceipt};
+use crate::engine::{FieldHandle, FieldStats, SharedEngine};
 use crate::error::DeltaResult;
+use crate::hooks::{HookFuture, HookId, PendingWrite, PostWriteHook, WriteEvent, WriteHooks};
 #[cfg(not(target_arch = "wasm32"))]
-use crate::lifecycle::{LifecycleAgent, LifecycleConfig};
+use crate::lifecycle::{
+    KeyAccessStats, LifecycleAgent, LifecycleConfig, NamespaceAccessStats, TierExecutor,
+};
 use crate::memory::{
-    ArchiveAgent, ChronicleAgent, EssenceAgent, TemperatureAgent, TemperatureConfig,
+    ArchiveAgent, ArchiveEpoch, ChronicleAgent, EssenceAgent, ExpressionResult, Genome,
+    TemperatureAgent, TemperatureConfig,
+};
+use crate::orchestrator::{
+    BackgroundProcess, ProcessProgress, ProcessReport, ProcessScheduler, RunProgress,
+    SYSTEM_NAMESPACE,
 };
-use crate::query::{HistoryQuery, Query, QueryExecutor, QueryResult};
+use crate::query::{
+    Aggregation, CancellationToken, FieldPushdown, HistoryQuery, KeyChanges, Query, QueryControl,
+    QueryExecutor, QueryLimits, QueryResult,
+};
+use crate::rag::{ChunkConfig, RetrievedChunk, StoredDocument};
+use crate::range_index::{RangeIndex, RangeIndexInfo};
+use crate::record::KoruRecord;
 use crate::roots::RootType;
-use crate::runtime::sync::RwLock;
-use crate::runtime::{DefaultRuntime, Runtime, WatchReceiver, WatchSender};
+use crate::runtime::sync::{Mutex, RwLock};
+use crate::runtime::{DefaultRuntime, JoinHandle, Runtime, WatchReceiver, WatchSender};
+use crate::sketch::{ApproxAggregate, TrackedAggregate};
 use crate::storage::CausalStorage;
-#[cfg(not(target_arch = "wasm32"))]
 use crate::subscriptions::{ChangeEvent, Subscription, SubscriptionAgent, SubscriptionId};
+use crate::tenant::{TENANT_NAMESPACE, Tenant, TenantQuota, Tenants, validate_tenant_name};
+use crate::transaction::ReadTransaction;
 use crate::types::{
-    ConnectedDistinction, FullKey, HistoryEntry, RandomCombination, UnconnectedPair, VersionedValue,
+    CompactionPolicy, ConnectedDistinction, DREAMS_NAMESPACE, Dream, DreamStatus, FullKey,
+    GcReport, HistoryCompactionReport, HistoryEntry, Provenance, RandomCombination,
+    UnconnectedPair, VersionedValue, WriteConflict,
+};
+use crate::vector::{
+    EmbeddingProvider, ExplainedSearchResult, LocalEmbeddingProvider, TextEmbedder, Vector,
+    VectorIndex, VectorNamespaceConfig, VectorSearchOptions, VectorSearchResult,
 };
-use crate::vector::{Vector, VectorIndex, VectorSearchOptions, VectorSearchResult};
 use crate::views::{PerspectiveAgent, ViewDefinition, ViewInfo};
 
 #[cfg(not(target_arch = "wasm32"))]
 use crate::cluster::ClusterNode;
 
 /// Configuration for KoruDelta.
+///
+/// Build it in code, or load it with [`CoreConfig::from_file`] and
+/// [`CoreConfig::from_env`].
 #[derive(Debug, Clone, Default)]
 pub struct CoreConfig {
+    /// Storage location
+    pub storage: StorageConfig,
+    /// Cluster and HTTP addresses
+    pub network: NetworkConfig,
     /// Memory tier configuration
     pub memory: MemoryConfig,
     /// Process configuration
@@ -93,6 +137,44 @@ pub struct CoreConfig {
     pub reconciliation: ReconciliationConfig,
     /// Resource limits (memory, disk)
     pub limits: ResourceLimits,
+    /// Idempotent write configuration
+    pub idempotency: IdempotencyConfig,
+    /// Local text embedding model
+    pub embedder: EmbedderConfig,
+}
+
+/// Where the database keeps its data.
+#[derive(Debug, Clone, Default)]
+pub struct StorageConfig {
+    /// Data directory; in memory only if unset
+    pub path: Option<PathBuf>,
+}
+
+/// Which local model embeds text.
+///
+/// Text is embedded with the lexical
+/// [`HashingEmbedder`](crate::vector::HashingEmbedder) unless a sentence
+/// transformer is configured here, which requires the `embedder` feature.
+/// Nothing is downloaded unless `hub_model` is set.
+#[derive(Debug, Clone, Default)]
+pub struct EmbedderConfig {
+    /// Directory holding a sentence-transformers model to load
+    pub model_dir: Option<PathBuf>,
+    /// Hugging Face Hub model to download into the local cache and load,
+    /// e.g. `"sentence-transformers/all-MiniLM-L6-v2"`
+    pub hub_model: Option<String>,
+}
+
+/// Addresses the database listens on and connects to.
+#[derive(Debug, Clone, Default)]
+pub struct NetworkConfig {
+    /// Address the cluster node binds to; no cluster if unset and there are
+    /// no seeds
+    pub cluster_bind: Option<SocketAddr>,
+    /// Cluster peers to join, tried in order
+    pub cluster_seeds: Vec<SocketAddr>,
+    /// Address the HTTP API binds to
+    pub http_bind: Option<SocketAddr>,
 }
 
 /// Resource limits for the database.
@@ -106,6 +188,10 @@ pub struct ResourceLimits {
     pub max_open_files: usize,
     /// Maximum concurrent connections (0 = unlimited)
     pub max_connections: usize,
+    /// Longest a query may run (zero = unlimited)
+    pub query_timeout: Duration,
+    /// Bounds on the work each query may do, tightened by the query's own
+    pub query_limits: QueryLimits,
 }
 
 impl Default for ResourceLimits {
@@ -115,6 +201,8 @@ impl Default for ResourceLimits {
             max_disk_mb: 10 * 1024, // 10GB default
             max_open_files: 256,
             max_connections: 100,
+            query_timeout: Duration::ZERO,
+            query_limits: QueryLimits::default(),
         }
     }
 }
@@ -141,6 +229,12 @@ pub struct ProcessConfig {
     pub distillation_interval: Duration,
     /// Genome update interval
     pub genome_interval: Duration,
+    /// Lifecycle (tier transition) check interval
+    pub lifecycle_interval: Duration,
+    /// Vector index compaction check interval
+    pub compaction_interval: Duration,
+    /// Capability expiry check interval
+    pub capability_expiry_interval: Duration,
 }
 
 /// Reconciliation configuration.
@@ -169,6 +263,24 @@ impl Default for ProcessConfig {
             consolidation_interval: Duration::from_secs(300),
             distillation_interval: Duration::from_secs(3600),
             genome_interval: Duration::from_secs(86400),
+            lifecycle_interval: Duration::from_secs(300),
+            compaction_interval: Duration::from_secs(600),
+            capability_expiry_interval: Duration::from_secs(300),
+        }
+    }
+}
+
+/// Idempotent write configuration.
+#[derive(Debug, Clone)]
+pub struct IdempotencyConfig {
+    /// How long a token deduplicates retries of the write it made
+    pub window: Duration,
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(86400),
         }
     }
 }
@@ -182,6 +294,72 @@ impl Default for ReconciliationConfig {
     }
 }
 
+/// Number of hot keys [`KoruDeltaGeneric::access_stats`] reports.
+#[cfg(not(target_arch = "wasm32"))]
+const ACCESS_STATS_HOT_KEYS: usize = 10;
+
+/// How long [`KoruDeltaGeneric::shutdown`] waits for writes and background
+/// processes to finish.
+const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often shutdown checks whether in-flight writes have drained.
+const WRITE_DRAIN_POLL: Duration = Duration::from_millis(10);
+
+/// File in the data directory holding the persisted vector index.
+#[cfg(not(target_arch = "wasm32"))]
+const VECTOR_INDEX_FILE: &str = "vectors.hnsw";
+
+/// Tracks in-flight writes so shutdown can wait for them.
+///
+/// Shared by every clone of a database. Once closed, new writes are
+/// rejected with [`DeltaError::ShuttingDown`](crate::error::DeltaError::ShuttingDown).
+#[derive(Debug, Default)]
+struct WriteTracker {
+    closed: AtomicBool,
+    in_flight: AtomicUsize,
+}
+
+impl WriteTracker {
+    /// Register a write, or fail if shutdown has begun.
+    fn begin(self: &Arc<Self>) -> DeltaResult<WriteGuard> {
+        // Count first so shutdown never sees zero while this write proceeds
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let guard = WriteGuard(Arc::clone(self));
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(crate::error::DeltaError::ShuttingDown);
+        }
+        Ok(guard)
+    }
+
+    /// Stop accepting writes.
+    fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+    }
+
+    fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+}
+
+/// Marks a write as in flight until dropped.
+struct WriteGuard(Arc<WriteTracker>);
+
+impl WriteGuard {
+    /// Keep the write in flight until the returned guard is dropped too,
+    /// e.g. for work it hands to a background task.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    fn share(&self) -> WriteGuard {
+        self.0.in_flight.fetch_add(1, Ordering::SeqCst);
+        WriteGuard(Arc::clone(&self.0))
+    }
+}
+
+impl Drop for WriteGuard {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
 /// The main KoruDelta database instance - Storage Agent.
 ///
 /// KoruDelta is the Storage Agent in the unified consciousness field.
@@ -216,6 +394,9 @@ pub struct KoruDeltaGeneric<R: Runtime> {
     /// Database path for persistence (None = in-memory only)
     #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
     db_path: Option<PathBuf>,
+    /// Group commit for WAL appends (None = in-memory only)
+    #[cfg(not(target_arch = "wasm32"))]
+    wal: Option<Arc<crate::persistence::GroupCommit>>,
     /// The underlying storage engine
     storage: Arc<CausalStorage>,
     /// The shared field engine (for LCA operations)
@@ -226,9 +407,12 @@ pub struct KoruDeltaGeneric<R: Runtime> {
     local_root: Distinction,
     /// View manager for materialized views
     views: Arc<PerspectiveAgent>,
-    /// Subscription manager for change notifications (non-WASM only)
-    #[cfg(not(target_arch = "wasm32"))]
+    /// Subscription manager for change notifications
     subscriptions: Arc<SubscriptionAgent>,
+    /// Validators and post-write hooks, by namespace
+    hooks: Arc<WriteHooks>,
+    /// Quotas, usage and metrics of opened tenants
+    tenants: Arc<Tenants>,
     /// Memory tiers
     hot: Arc<RwLock<TemperatureAgent>>,
     warm: Arc<RwLock<ChronicleAgent>>,
@@ -241,9 +425,26 @@ pub struct KoruDeltaGeneric<R: Runtime> {
     lifecycle: Arc<LifecycleAgent>,
     /// Vector index for similarity search
     vector_index: VectorIndex,
+    /// Embedding provider used by `embed_text` and `search_text`
+    embedder: Arc<std::sync::RwLock<Arc<dyn EmbeddingProvider>>>,
     /// Cluster node for distributed operation (optional)
     #[cfg(not(target_arch = "wasm32"))]
-    cluster: Option<Arc<ClusterNode>>,
+    cluster: Option<Arc<ClusterNode<R>>>,
+    /// Schedule and pause state of background processes
+    scheduler: Arc<ProcessScheduler>,
+    /// In-flight writes, drained on shutdown
+    writes: Arc<WriteTracker>,
+    /// Held shared by writes and exclusively by garbage collection
+    #[cfg(not(target_arch = "wasm32"))]
+    write_gate: Arc<RwLock<()>>,
+    /// Serializes idempotent writes per namespace, key and token, so
+    /// concurrent retries apply once without holding up other writes
+    idempotency_locks: Arc<DashMap<(String, String, String), Arc<Mutex<()>>>>,
+    /// Parsed namespace encryption settings, keyed by namespace and tagged
+    /// with the write ID of the settings version they were parsed from
+    encryption_configs: Arc<DashMap<String, (String, Option<NamespaceEncryption>)>>,
+    /// Background process tasks, awaited on shutdown
+    tasks: Arc<std::sync::Mutex<Vec<JoinHandle<()>>>>,
     /// Shutdown signal
     shutdown_tx: WatchSender<bool>,
     #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
@@ -268,7 +469,11 @@ impl<R: Runtime> std::fmt::Debug for KoruDeltaGeneric<R> {
 impl<R: Runtime> KoruDeltaGeneric<R> {
     /// Start a new KoruDelta instance with default configuration.
     ///
-    /// This is the zero-configuration entry point (in-memory only).
+    /// This is the zero-configuration entry point. Data lives in memory
+    /// only: no files are read or written. Background processes
+    /// (consolidation, distillation, lifecycle checks, ...) run on their
+    /// usual schedules; use [`start_ephemeral`](Self::start_ephemeral) for an
+    /// instance without them.
     pub async fn start() -> DeltaResult<Self> {
         info!("Starting KoruDelta in-memory instance");
         let runtime = R::new();
@@ -277,6 +482,20 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         Ok(db)
     }
 
+    /// Start a throwaway in-memory instance, for unit tests and short-lived
+    /// caches.
+    ///
+    /// Nothing ever touches the disk: there is no write-ahead log, lock file
+    /// or flush task. No background processes are spawned either, so the
+    /// instance does no work beyond the calls made on it. Everything is lost
+    /// when the last handle is dropped.
+    pub async fn start_ephemeral() -> DeltaResult<Self> {
+        info!("Starting KoruDelta ephemeral instance");
+        let mut config = CoreConfig::default();
+        config.processes.enabled = false;
+        Self::new_with_runtime(config, R::new()).await
+    }
+
     /// Start a new KoruDelta instance with persistence at the given path.
     ///
     /// If the path exists and contains a database, it will be loaded.
@@ -289,15 +508,22 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
     /// ```
     #[cfg(not(target_arch = "wasm32"))]
     pub async fn start_with_path(path: impl Into<PathBuf>) -> DeltaResult<Self> {
+        Self::open_with_path(path.into(), CoreConfig::default(), R::new()).await
+    }
+
+    /// Open (or create) the database at `path` with the given configuration
+    /// and runtime.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) async fn open_with_path(
+        path: PathBuf,
+        config: CoreConfig,
+        runtime: R,
+    ) -> DeltaResult<Self> {
         use crate::persistence;
 
-        let path = path.into();
         let path_display = path.display().to_string();
         info!(db_path = %path_display, "Starting KoruDelta with persistence");
 
-        let config = CoreConfig::default();
-        let runtime = R::new();
-
         // Create the shared field engine (LCA foundation)
         let shared_engine = SharedEngine::new();
         let field = FieldHandle::new(&shared_engine);
@@ -327,6 +553,22 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         };
 
         let storage = Arc::new(storage);
+        storage.restore_range_indexes();
+
+        // The vector index is saved on clean shutdown; after a crash, before
+        // it was first saved, or if the saved file can't be read, rebuild it
+        // from the stored vectors
+        let vector_index_path = path.join(VECTOR_INDEX_FILE);
+        let saved = vector_index_path.exists();
+        let vector_index =
+            VectorIndex::open_hnsw(vector_index_path, crate::vector::HnswConfig::default());
+        let unreadable = vector_index.load_error();
+        if let Some(ref e) = unreadable {
+            warn!(error = %e, "Saved vector index is unreadable");
+        }
+        if lock_state == persistence::LockState::Unclean || !saved || unreadable.is_some() {
+            Self::reindex_vectors(&storage, &vector_index);
+        }
 
         // Initialize memory tiers with LCA agents
         let hot = Arc::new(RwLock::new(TemperatureAgent::with_config(
@@ -351,23 +593,34 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         // Initialize views with LCA perspective agent
         let views = Arc::new(PerspectiveAgent::new(Arc::clone(&storage), &shared_engine));
 
-        // Initialize subscriptions (non-WASM only)
-        #[cfg(not(target_arch = "wasm32"))]
+        // Initialize subscriptions
         let subscriptions = Arc::new(SubscriptionAgent::new(&shared_engine));
 
         // Initialize lifecycle manager (non-WASM only)
         #[cfg(not(target_arch = "wasm32"))]
-        let lifecycle = Arc::new(LifecycleAgent::with_config(
-            &shared_engine,
-            LifecycleConfig::default(),
-        ));
+        let lifecycle = Arc::new(
+            LifecycleAgent::with_config(&shared_engine, LifecycleConfig::default())
+                .with_audit(Arc::clone(&storage), Arc::clone(&subscriptions))
+                .with_executor(TierExecutor::new(
+                    Arc::clone(&hot),
+                    Arc::clone(&warm),
+                    Arc::clone(&cold),
+                    Arc::clone(&storage),
+                )),
+        );
 
         // Shutdown channel using runtime
         let (shutdown_tx, shutdown_rx) = runtime.watch_channel(false);
+        let scheduler = Arc::new(Self::process_scheduler(&runtime, &config));
+
+        let embedder = Arc::new(std::sync::RwLock::new(
+            crate::vector::default_embedding_provider(&config.embedder),
+        ));
 
         let db = Self {
             runtime,
             config,
+            wal: Some(Arc::new(persistence::GroupCommit::new(&path))),
             db_path: Some(path),
             storage,
             shared_engine,
@@ -381,11 +634,20 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
             #[cfg(not(target_arch = "wasm32"))]
             lifecycle,
             views,
-            #[cfg(not(target_arch = "wasm32"))]
             subscriptions,
-            vector_index: VectorIndex::new_flat(),
+            hooks: Arc::default(),
+            tenants: Arc::default(),
+            vector_index,
+            embedder,
             #[cfg(not(target_arch = "wasm32"))]
             cluster: None,
+            scheduler,
+            writes: Arc::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            write_gate: Arc::new(RwLock::new(())),
+            idempotency_locks: Arc::new(DashMap::new()),
+            encryption_configs: Arc::new(DashMap::new()),
+            tasks: Arc::default(),
             shutdown_tx,
             shutdown_rx,
         };
@@ -399,6 +661,23 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         Ok(db)
     }
 
+    /// Replace the contents of `index` with every vector in `storage`.
+    fn reindex_vectors(storage: &CausalStorage, index: &VectorIndex) {
+        let vectors: Vec<(FullKey, Vector)> = storage
+            .scan_all()
+            .into_iter()
+            .filter_map(|(key, versioned)| {
+                crate::vector::json_to_vector(versioned.value()).map(|vector| (key, vector))
+            })
+            .collect();
+        info!(
+            vectors = vectors.len(),
+            "Rebuilding vector index from storage"
+        );
+        index.clear();
+        index.add_batch(vectors);
+    }
+
     /// Create a new KoruDelta with the given configuration.
     pub async fn new(config: CoreConfig) -> DeltaResult<Self> {
         let runtime = R::new();
@@ -440,24 +719,36 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         // Initialize views with LCA perspective agent
         let views = Arc::new(PerspectiveAgent::new(Arc::clone(&storage), &shared_engine));
 
-        // Initialize subscriptions (non-WASM only)
-        #[cfg(not(target_arch = "wasm32"))]
+        // Initialize subscriptions
         let subscriptions = Arc::new(SubscriptionAgent::new(&shared_engine));
 
         // Initialize lifecycle manager (non-WASM only)
         #[cfg(not(target_arch = "wasm32"))]
-        let lifecycle = Arc::new(LifecycleAgent::with_config(
-            &shared_engine,
-            LifecycleConfig::default(),
-        ));
+        let lifecycle = Arc::new(
+            LifecycleAgent::with_config(&shared_engine, LifecycleConfig::default())
+                .with_audit(Arc::clone(&storage), Arc::clone(&subscriptions))
+                .with_executor(TierExecutor::new(
+                    Arc::clone(&hot),
+                    Arc::clone(&warm),
+                    Arc::clone(&cold),
+                    Arc::clone(&storage),
+                )),
+        );
 
         // Shutdown channel using runtime
         let (shutdown_tx, shutdown_rx) = runtime.watch_channel(false);
+        let scheduler = Arc::new(Self::process_scheduler(&runtime, &config));
+
+        let embedder = Arc::new(std::sync::RwLock::new(
+            crate::vector::default_embedding_provider(&config.embedder),
+        ));
 
         let db = Self {
             runtime,
             config,
             db_path: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            wal: None,
             storage,
             shared_engine,
             field,
@@ -470,11 +761,20 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
             #[cfg(not(target_arch = "wasm32"))]
             lifecycle,
             views,
-            #[cfg(not(target_arch = "wasm32"))]
             subscriptions,
+            hooks: Arc::default(),
+            tenants: Arc::default(),
             vector_index: VectorIndex::new_flat(),
+            embedder,
             #[cfg(not(target_arch = "wasm32"))]
             cluster: None,
+            scheduler,
+            writes: Arc::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            write_gate: Arc::new(RwLock::new(())),
+            idempotency_locks: Arc::new(DashMap::new()),
+            encryption_configs: Arc::new(DashMap::new()),
+            tasks: Arc::default(),
             shutdown_tx,
             shutdown_rx,
         };
@@ -488,86 +788,244 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         Ok(db)
     }
 
+    /// Configure a database explicitly: data directory, limits, background
+    /// processes, cluster membership.
+    ///
+    /// ```ignore
+    /// let db = KoruDelta::builder()
+    ///     .path("/var/lib/koru")
+    ///     .memory_limit(2048)
+    ///     .cluster(ClusterConfig::new().bind_addr("0.0.0.0:7878".parse()?))
+    ///     .open()
+    ///     .await?;
+    /// ```
+    pub fn builder() -> KoruDeltaBuilder<R> {
+        KoruDeltaBuilder::new()
+    }
+
+    /// The configuration this instance was opened with.
+    pub fn config(&self) -> &CoreConfig {
+        &self.config
+    }
+
+    /// Whether writes are persisted to disk, i.e. the instance was started
+    /// with [`start_with_path`](Self::start_with_path).
+    pub fn is_persistent(&self) -> bool {
+        self.db_path.is_some()
+    }
+
     /// Attach a cluster node for distributed operation.
     ///
     /// This enables automatic broadcast of writes to cluster peers.
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn with_cluster(mut self, cluster: Arc<ClusterNode>) -> Self {
+    pub fn with_cluster(mut self, cluster: Arc<ClusterNode<R>>) -> Self {
         self.cluster = Some(cluster);
         self
     }
 
-    /// Start background processes (consolidation, distillation, genome update).
+    /// Get the attached cluster node, if any.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn cluster(&self) -> Option<&Arc<ClusterNode<R>>> {
+        self.cluster.as_ref()
+    }
+
+    /// Pause background processes.
+    ///
+    /// Running ticks finish; later ticks are skipped until
+    /// [`resume_background_processes`](Self::resume_background_processes).
+    pub fn pause_background_processes(&self) {
+        self.scheduler.pause_all();
+        info!("Background processes paused");
+    }
+
+    /// Resume paused background processes.
+    ///
+    /// Processes paused individually through the
+    /// [`scheduler`](Self::scheduler) stay paused.
+    pub fn resume_background_processes(&self) {
+        self.scheduler.resume_all();
+        info!("Background processes resumed");
+    }
+
+    /// Whether background processes are paused.
+    pub fn background_processes_paused(&self) -> bool {
+        self.scheduler.all_paused()
+    }
+
+    /// Scheduler for the background processes.
+    ///
+    /// Lists processes and pauses, resumes, or reschedules them individually.
+    pub fn scheduler(&self) -> &ProcessScheduler {
+        &self.scheduler
+    }
+
+    /// Build the scheduler for the background processes in `config`.
+    fn process_scheduler(runtime: &R, config: &CoreConfig) -> ProcessScheduler {
+        let processes = &config.processes;
+        ProcessScheduler::new(
+            runtime,
+            &[
+                (
+                    BackgroundProcess::Consolidation,
+                    processes.consolidation_interval,
+                ),
+                (
+                    BackgroundProcess::Distillation,
+                    processes.distillation_interval,
+                ),
+                (BackgroundProcess::GenomeUpdate, processes.genome_interval),
+                #[cfg(not(target_arch = "wasm32"))]
+                (
+                    BackgroundProcess::LifecycleCheck,
+                    processes.lifecycle_interval,
+                ),
+                (
+                    BackgroundProcess::IndexCompaction,
+                    processes.compaction_interval,
+                ),
+                #[cfg(not(target_arch = "wasm32"))]
+                (
+                    BackgroundProcess::CapabilityExpiry,
+                    processes.capability_expiry_interval,
+                ),
+            ],
+        )
+    }
+
+    /// Start background processes (consolidation, distillation, genome update,
+    /// lifecycle checks, index compaction, capability expiry).
     #[cfg(not(target_arch = "wasm32"))]
     async fn start_background_processes(&self) {
+        // Consolidation: Move data between tiers
         let hot = Arc::clone(&self.hot);
         let warm = Arc::clone(&self.warm);
         let cold = Arc::clone(&self.cold);
         let deep = Arc::clone(&self.deep);
         let storage = Arc::clone(&self.storage);
-        let mut shutdown = self.shutdown_rx.clone();
-        let runtime = self.runtime.clone();
-
-        let consolidation_interval = self.config.processes.consolidation_interval;
-        let distillation_interval = self.config.processes.distillation_interval;
-        let genome_interval = self.config.processes.genome_interval;
-
-        // Spawn consolidation task
-        let runtime_clone = runtime.clone();
-        runtime.spawn(async move {
-            let mut interval = runtime_clone.interval(consolidation_interval);
-            loop {
-                futures::select! {
-                    _ = interval.tick().fuse() => {
-                        // Consolidation: Move data between tiers
-                        Self::run_consolidation(
-                            &hot, &warm, &cold, &deep, &storage
-                        ).await;
-                    }
-                    _ = Self::watch_shutdown(&mut shutdown).fuse() => {
-                        break;
-                    }
-                }
+        self.spawn_process(BackgroundProcess::Consolidation, move |progress| {
+            let (hot, warm, cold, deep, storage) = (
+                Arc::clone(&hot),
+                Arc::clone(&warm),
+                Arc::clone(&cold),
+                Arc::clone(&deep),
+                Arc::clone(&storage),
+            );
+            async move {
+                Self::run_consolidation(&hot, &warm, &cold, &deep, &storage, &progress).await;
             }
         });
 
-        // Spawn distillation task
+        // Distillation: Remove noise, keep essence
         let hot = Arc::clone(&self.hot);
         let warm = Arc::clone(&self.warm);
         let cold = Arc::clone(&self.cold);
         let storage = Arc::clone(&self.storage);
-        let mut shutdown = self.shutdown_rx.clone();
-        let runtime_clone = runtime.clone();
+        self.spawn_process(BackgroundProcess::Distillation, move |progress| {
+            let (hot, warm, cold, storage) = (
+                Arc::clone(&hot),
+                Arc::clone(&warm),
+                Arc::clone(&cold),
+                Arc::clone(&storage),
+            );
+            async move {
+                Self::run_distillation(&hot, &warm, &cold, &storage, &progress).await;
+            }
+        });
 
-        runtime.spawn(async move {
-            let mut interval = runtime_clone.interval(distillation_interval);
-            loop {
-                futures::select! {
-                    _ = interval.tick().fuse() => {
-                        // Distillation: Remove noise, keep essence
-                        Self::run_distillation(
-                            &hot, &warm, &cold, &storage
-                        ).await;
-                    }
-                    _ = Self::watch_shutdown(&mut shutdown).fuse() => {
-                        break;
-                    }
-                }
+        // Lifecycle: Score, plan, and execute tier transitions
+        let lifecycle = Arc::clone(&self.lifecycle);
+        self.spawn_process(BackgroundProcess::LifecycleCheck, move |progress| {
+            let lifecycle = Arc::clone(&lifecycle);
+            async move {
+                let report = lifecycle.run_check().await;
+                progress.scanned(report.planned as u64);
+                progress.promoted(report.promotions as u64);
+                progress.archived(report.demotions as u64);
             }
         });
 
-        // Spawn genome update task
+        // Genome update: Extract causal topology
         let deep = Arc::clone(&self.deep);
+        self.spawn_process(BackgroundProcess::GenomeUpdate, move |_| {
+            let deep = Arc::clone(&deep);
+            async move {
+                Self::run_genome_update(&deep).await;
+            }
+        });
+
+        // Compaction: Reclaim tombstoned vectors from the ANN graph
+        let vector_index = self.vector_index.clone();
+        let compaction = crate::processes::IndexCompactionProcess::new();
+        self.spawn_process(BackgroundProcess::IndexCompaction, move |progress| {
+            let reclaimed = compaction.run(&vector_index);
+            if reclaimed > 0 {
+                debug!(reclaimed, "Vector index compacted");
+                progress.archived(reclaimed as u64);
+            }
+            std::future::ready(())
+        });
+
+        // Capability expiry: Warn before grants lapse, archive expired ones
+        let auth = Arc::clone(&self.auth);
+        let expiry = crate::processes::CapabilityExpiryProcess::new()
+            .with_audit(Arc::clone(&self.storage), Arc::clone(&self.subscriptions));
+        self.spawn_process(BackgroundProcess::CapabilityExpiry, move |progress| {
+            match expiry.run(&auth) {
+                Ok(events) if !events.is_empty() => {
+                    debug!(events = events.len(), "Capability expiry events published");
+                    progress.scanned(events.len() as u64);
+                }
+                Ok(_) => {}
+                Err(e) => warn!(error = %e, "Capability expiry check failed"),
+            }
+            std::future::ready(())
+        });
+    }
+
+    /// Spawn the loop driving one background process.
+    ///
+    /// Calls `run` on every tick of the process's schedule, skipping ticks
+    /// while it is paused and restarting the timer when it is rescheduled.
+    /// Each run reports its progress through the [`RunProgress`] it is given,
+    /// and runs that touched anything are recorded in [`SYSTEM_NAMESPACE`].
+    #[cfg(not(target_arch = "wasm32"))]
+    fn spawn_process<F, Fut>(&self, process: BackgroundProcess, mut run: F)
+    where
+        F: FnMut(RunProgress) -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let Some(mut schedule) = self.scheduler.watch_interval(process) else {
+            return;
+        };
+        let scheduler = Arc::clone(&self.scheduler);
+        let storage = Arc::clone(&self.storage);
         let mut shutdown = self.shutdown_rx.clone();
-        let runtime_clone = runtime.clone();
+        let runtime = self.runtime.clone();
 
-        runtime.spawn(async move {
-            let mut interval = runtime_clone.interval(genome_interval);
+        let handle = self.runtime.spawn(async move {
+            let mut interval = runtime.interval(schedule.borrow_and_update());
             loop {
                 futures::select! {
                     _ = interval.tick().fuse() => {
-                        // Genome update: Extract causal topology
-                        Self::run_genome_update(&deep).await;
+                        if scheduler.is_paused(process) {
+                            continue;
+                        }
+                        let Some(progress) = scheduler.begin_run(process) else {
+                            continue;
+                        };
+                        run(progress.clone()).await;
+                        let report = scheduler.finish_run(process, &progress);
+                        if report.has_activity() {
+                            Self::store_process_report(&storage, &report);
+                        }
+                    }
+                    changed = schedule.changed().fuse() => {
+                        if changed.is_err() {
+                            break;
+                        }
+                        // Restart the period from now, skipping the immediate first tick
+                        interval = runtime.interval(schedule.borrow_and_update());
+                        interval.tick().await;
                     }
                     _ = Self::watch_shutdown(&mut shutdown).fuse() => {
                         break;
@@ -575,6 +1033,49 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
                 }
             }
         });
+        self.tasks.lock().unwrap().push(handle);
+    }
+
+    /// Record a run report in [`SYSTEM_NAMESPACE`].
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    fn store_process_report(storage: &CausalStorage, report: &ProcessReport) {
+        let key = ProcessReport::storage_key(report.process);
+        let stored = serde_json::to_value(report)
+            .map_err(Into::into)
+            .and_then(|value| storage.put(SYSTEM_NAMESPACE, key, value));
+        if let Err(e) = stored {
+            warn!(error = %e, process = report.process.name(), "Failed to record process report");
+        }
+    }
+
+    /// Report of the last run of `process` that touched any items.
+    ///
+    /// Read from [`SYSTEM_NAMESPACE`], so it survives restarts. `None` if
+    /// no such run has been recorded.
+    pub fn last_run_report(
+        &self,
+        process: BackgroundProcess,
+    ) -> DeltaResult<Option<ProcessReport>> {
+        match self
+            .storage
+            .get(SYSTEM_NAMESPACE, ProcessReport::storage_key(process))
+        {
+            Ok(versioned) if !versioned.value().is_null() => {
+                Ok(Some(serde_json::from_value(versioned.value().clone())?))
+            }
+            Ok(_) | Err(crate::error::DeltaError::KeyNotFound { .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Watch the progress of a background process's runs.
+    ///
+    /// `None` if the process is not scheduled.
+    pub fn process_progress(
+        &self,
+        process: BackgroundProcess,
+    ) -> Option<WatchReceiver<ProcessProgress>> {
+        self.scheduler.watch_progress(process)
     }
 
     /// Helper to watch for shutdown signal.
@@ -602,6 +1103,7 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         cold: &Arc<RwLock<ArchiveAgent>>,
         _deep: &Arc<RwLock<EssenceAgent>>,
         _storage: &Arc<CausalStorage>,
+        progress: &RunProgress,
     ) {
         // Check TemperatureAgent utilization
         let hot_util = {
@@ -625,6 +1127,7 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
             let warm = warm.read().await;
             warm.find_demotion_candidates(10)
         };
+        progress.scanned(demotion_candidates.len() as u64);
 
         // Demote low-access items from warm to cold
         if !demotion_candidates.is_empty() {
@@ -635,6 +1138,7 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
                 warm.demote(&id);
                 // In full implementation, would move to cold
                 cold.consolidate_distinction(&id);
+                progress.archived(1);
             }
         }
 
@@ -656,18 +1160,21 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         warm: &Arc<RwLock<ChronicleAgent>>,
         cold: &Arc<RwLock<ArchiveAgent>>,
         _storage: &Arc<CausalStorage>,
+        progress: &RunProgress,
     ) {
         // Find promotion candidates (high fitness) in warm
         let promotion_candidates = {
             let warm = warm.read().await;
             warm.find_promotion_candidates(10)
         };
+        progress.scanned(promotion_candidates.len() as u64);
 
         // Promote high-fitness items (mark for hot consideration)
         if !promotion_candidates.is_empty() {
             let warm = warm.write().await;
             for (_, id) in promotion_candidates {
                 warm.promote(&id);
+                progress.promoted(1);
             }
         }
 
@@ -723,6 +1230,9 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         // Get the storage agent's local root
         let local_root = shared_engine.root(RootType::Storage).clone();
 
+        let vector_index = VectorIndex::new_flat();
+        Self::reindex_vectors(&storage, &vector_index);
+
         // Initialize memory tiers with LCA agents
         let hot = Arc::new(RwLock::new(TemperatureAgent::with_config(
             TemperatureConfig {
@@ -743,27 +1253,38 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
             &shared_engine,
         ));
 
+        // Rebuild range indexes from their stored definitions
+        storage.restore_range_indexes();
+
         // Initialize views with LCA perspective agent
         let views = Arc::new(PerspectiveAgent::new(Arc::clone(&storage), &shared_engine));
 
-        // Initialize subscriptions (non-WASM only)
-        #[cfg(not(target_arch = "wasm32"))]
+        // Initialize subscriptions
         let subscriptions = Arc::new(SubscriptionAgent::new(&shared_engine));
 
         // Initialize lifecycle manager (non-WASM only)
         #[cfg(not(target_arch = "wasm32"))]
-        let lifecycle = Arc::new(LifecycleAgent::with_config(
-            &shared_engine,
-            LifecycleConfig::default(),
-        ));
+        let lifecycle = Arc::new(
+            LifecycleAgent::with_config(&shared_engine, LifecycleConfig::default())
+                .with_audit(Arc::clone(&storage), Arc::clone(&subscriptions))
+                .with_executor(TierExecutor::new(
+                    Arc::clone(&hot),
+                    Arc::clone(&warm),
+                    Arc::clone(&cold),
+                    Arc::clone(&storage),
+                )),
+        );
 
         // Shutdown channel using runtime
         let (shutdown_tx, shutdown_rx) = runtime.watch_channel(false);
+        let scheduler = Arc::new(Self::process_scheduler(&runtime, &config));
 
         Self {
             runtime,
             config: CoreConfig::default(),
             db_path: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            wal: None,
             storage,
             shared_engine,
             field,
@@ -776,11 +1297,22 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
             #[cfg(not(target_arch = "wasm32"))]
             lifecycle,
             views,
-            #[cfg(not(target_arch = "wasm32"))]
             subscriptions,
-            vector_index: VectorIndex::new_flat(),
+            hooks: Arc::default(),
+            tenants: Arc::default(),
+            vector_index,
+            embedder: Arc::new(std::sync::RwLock::new(
+                crate::vector::default_embedding_provider(&config.embedder),
+            )),
             #[cfg(not(target_arch = "wasm32"))]
             cluster: None,
+            scheduler,
+            writes: Arc::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            write_gate: Arc::new(RwLock::new(())),
+            idempotency_locks: Arc::new(DashMap::new()),
+            encryption_configs: Arc::new(DashMap::new()),
+            tasks: Arc::default(),
             shutdown_tx,
             shutdown_rx,
         }
@@ -793,79 +1325,581 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         key: impl Into<String>,
         value: T,
     ) -> DeltaResult<VersionedValue> {
-        let namespace = namespace.into();
-        let key = key.into();
-        trace!("Serializing value");
-        let json_value = serde_json::to_value(value)?;
-
-        // Store in storage (source of truth)
-        trace!("Storing in CausalStorage");
-        let versioned = self.storage.put(&namespace, &key, json_value)?;
-        let version_id = versioned.version_id().to_string();
-        debug!(version = %version_id, "Value stored");
-
-        // Persist to WAL if db_path is set
-        #[cfg(not(target_arch = "wasm32"))]
-        if let Some(ref db_path) = self.db_path {
-            use crate::persistence;
-            trace!("Persisting to WAL");
-            if let Err(e) = persistence::append_write(db_path, &namespace, &key, &versioned).await {
-                error!(error = %e, "Failed to persist write to WAL");
-            } else {
-                trace!("Write persisted to WAL");
-            }
-        }
-
-        // Broadcast to cluster if configured
-        #[cfg(not(target_arch = "wasm32"))]
-        if let Some(ref cluster) = self.cluster {
-            let full_key = FullKey::new(&namespace, &key);
-            let value_clone = versioned.clone();
-            let cluster_clone = Arc::clone(cluster);
-            tokio::spawn(async move {
-                trace!("Broadcasting write to cluster");
-                cluster_clone.broadcast_write(full_key, value_clone).await;
-            });
-        }
-
-        // Promote to hot memory
-        {
-            let full_key = FullKey::new(&namespace, &key);
-            let hot = self.hot.write().await;
-            hot.put(full_key, versioned.clone());
-            trace!("Value promoted to hot memory");
-        }
-
-        // Auto-refresh views (fire and forget, non-WASM only)
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            let views = Arc::clone(&self.views);
-            tokio::spawn(async move {
-                let _ = views.refresh_stale(chrono::Duration::seconds(0));
-            });
-        }
-
-        info!(version = %version_id, "Put operation completed");
-        Ok(versioned)
+        self.put_attributed(namespace, key, value, None, Vec::new())
+            .await
     }
 
-    /// Store a value with causal parent links in the graph.
-    ///
-    /// This establishes causal relationships in the graph while storing the value.
-    /// Use this when a distinction is caused by prior distinctions.
+    /// Store a value that was derived from other keys' versions.
     ///
-    /// # Arguments
-    ///
-    /// * `namespace` - The namespace to store in
-    /// * `key` - The key for this value
-    /// * `value` - The value to store
-    /// * `parent_keys` - Keys of parent distinctions that caused this one
+    /// `causes` are write IDs ([`VersionedValue::write_id`]) of existing
+    /// versions, usually of other keys. The links are recorded in the
+    /// reference graph, persisted and replicated with the write, and can be
+    /// queried with [`causes`](Self::causes) and
+    /// [`dependents`](Self::dependents). Fails with
+    /// [`DeltaError::InvalidData`](crate::error::DeltaError::InvalidData) if a
+    /// cause is unknown.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// // Store inference with causal link to observation
-    /// db.put_with_causal_links(
+    /// let order = db.put("orders", "1001", json!({"total": 40})).await?;
+    /// let customer = db.get("customers", "bob").await?;
+    /// db.put_with_causes(
+    ///     "invoices",
+    ///     "1001",
+    ///     json!({"amount": 40}),
+    ///     &[order.write_id(), customer.write_id()],
+    /// )
+    /// .await?;
+    /// ```
+    pub async fn put_with_causes<T: Serialize>(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        value: T,
+        causes: &[&str],
+    ) -> DeltaResult<VersionedValue> {
+        let causes = causes.iter().map(|cause| cause.to_string()).collect();
+        self.put_attributed(namespace, key, value, None, causes)
+            .await
+    }
+
+    /// Store a value, recording the identity that wrote it and its causes.
+    ///
+    /// Used by authenticated handles so [`provenance`](Self::provenance)
+    /// can report who made each write.
+    pub(crate) async fn put_attributed<T: Serialize>(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        value: T,
+        author: Option<String>,
+        causes: Vec<String>,
+    ) -> DeltaResult<VersionedValue> {
+        self.put_version(
+            namespace,
+            key,
+            value,
+            author,
+            causes,
+            None,
+            Durability::Sync,
+        )
+        .await
+        .map(PutReceipt::into_version)
+    }
+
+    /// Store a value with per-write options.
+    ///
+    /// With [`Durability::Async`] the call returns as soon as the write is
+    /// applied in memory, and the receipt's
+    /// [`durable`](PutReceipt::durable) future resolves once it has been
+    /// flushed to the write-ahead log. With the default
+    /// [`Durability::Sync`] this is the same as [`put`](Self::put).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let options = PutOptions::new().durability(Durability::Async);
+    /// let receipt = db.put_with_options("events", "e1", json!({"kind": "click"}), options).await?;
+    /// receipt.durable().await?;
+    /// ```
+    pub async fn put_with_options<T: Serialize>(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        value: T,
+        options: PutOptions,
+    ) -> DeltaResult<PutReceipt> {
+        self.put_version(
+            namespace,
+            key,
+            value,
+            None,
+            Vec::new(),
+            None,
+            options.durability,
+        )
+        .await
+    }
+
+    /// Store a value, deduplicating retries of the same request.
+    ///
+    /// The first call with a given `idempotency_token` stores the value and
+    /// records the token in the version's metadata. Calls for the same key
+    /// with the same token within the configured
+    /// [`window`](IdempotencyConfig::window) store nothing and return the
+    /// version the first call made, so clients with at-least-once delivery
+    /// can retry freely. Tokens are scoped to the key.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let first = db.put_idempotent("orders", "1001", json!({"total": 40}), "req-7f3a").await?;
+    /// // The client timed out and retried
+    /// let retry = db.put_idempotent("orders", "1001", json!({"total": 40}), "req-7f3a").await?;
+    /// assert_eq!(first.write_id(), retry.write_id());
+    /// ```
+    pub async fn put_idempotent<T: Serialize>(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        value: T,
+        idempotency_token: impl Into<String>,
+    ) -> DeltaResult<VersionedValue> {
+        let namespace = namespace.into();
+        let key = key.into();
+        let token = idempotency_token.into();
+
+        // Checking for the token and recording it are atomic per token
+        let lock_key = (namespace.clone(), key.clone(), token.clone());
+        let lock = Arc::clone(
+            &self
+                .idempotency_locks
+                .entry(lock_key.clone())
+                .or_insert_with(|| Arc::new(Mutex::new(()))),
+        );
+        let result = {
+            let _idempotent = lock.lock().await;
+            self.put_idempotent_locked(namespace, key, value, token)
+                .await
+        };
+        // Drop the lock once nobody else is waiting on it; a later retry
+        // finds the recorded token without it
+        self.idempotency_locks
+            .remove_if(&lock_key, |_, held| Arc::strong_count(held) == 2);
+        result
+    }
+
+    /// Store an idempotent write unless its token was already recorded,
+    /// with the token's lock held.
+    async fn put_idempotent_locked<T: Serialize>(
+        &self,
+        namespace: String,
+        key: String,
+        value: T,
+        token: String,
+    ) -> DeltaResult<VersionedValue> {
+        let since = chrono::Duration::from_std(self.config.idempotency.window)
+            .ok()
+            .and_then(|window| Utc::now().checked_sub_signed(window))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        if let Some(original) = self
+            .storage
+            .find_idempotent(&namespace, &key, &token, since)
+        {
+            debug!(namespace = %namespace, key = %key, "Idempotent retry deduplicated");
+            return Ok(original);
+        }
+        self.put_version(
+            namespace,
+            key,
+            value,
+            None,
+            Vec::new(),
+            Some(token),
+            Durability::Sync,
+        )
+        .await
+        .map(PutReceipt::into_version)
+    }
+
+    /// Store a new version with its metadata: the shared write path.
+    #[instrument(
+        name = "put",
+        skip_all,
+        fields(namespace = Empty, key = Empty, version_id = Empty)
+    )]
+    #[allow(clippy::too_many_arguments)]
+    async fn put_version<T: Serialize>(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        value: T,
+        author: Option<String>,
+        causes: Vec<String>,
+        idempotency_token: Option<String>,
+        durability: Durability,
+    ) -> DeltaResult<PutReceipt> {
+        let write = self.writes.begin()?;
+        #[cfg(not(target_arch = "wasm32"))]
+        let _gate = self.write_gate.read().await;
+        let namespace = namespace.into();
+        let key = key.into();
+        let span = Span::current();
+        span.record("namespace", namespace.as_str());
+        span.record("key", key.as_str());
+        trace!("Serializing value");
+        let json_value = serde_json::to_value(value)?;
+        catalog::check_writable(&namespace)?;
+        self.hooks.validate(&PendingWrite {
+            namespace: &namespace,
+            key: &key,
+            value: &json_value,
+        })?;
+        let post_write = self.hooks.post_write_hooks(&namespace);
+        let written = (!post_write.is_empty()).then(|| json_value.clone());
+        let json_value = self.seal_for_namespace(&namespace, &key, json_value)?;
+
+        // Store in storage (source of truth)
+        trace!("Storing in CausalStorage");
+        let versioned = match idempotency_token {
+            Some(token) => self
+                .storage
+                .put_idempotent(&namespace, &key, json_value, author, token)?,
+            None => self
+                .storage
+                .put_attributed(&namespace, &key, json_value, author, causes)?,
+        };
+        self.unindex_overwritten(&namespace, &key, &versioned);
+        let version_id = versioned.version_id().to_string();
+        span.record("version_id", version_id.as_str());
+        debug!(version = %version_id, "Value stored");
+
+        // Persist to WAL if db_path is set (grouped with concurrent writes)
+        let durable = self
+            .persist(
+                FullKey::new(&namespace, &key),
+                &versioned,
+                durability,
+                &write,
+            )
+            .await;
+
+        // Broadcast to cluster if configured
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(ref cluster) = self.cluster {
+            let full_key = FullKey::new(&namespace, &key);
+            let value_clone = versioned.clone();
+            let cluster_clone = Arc::clone(cluster);
+            self.runtime.spawn(
+                async move {
+                    trace!("Broadcasting write to cluster");
+                    cluster_clone.broadcast_write(full_key, value_clone).await;
+                }
+                .in_current_span(),
+            );
+        }
+
+        // Promote to hot memory
+        {
+            let full_key = FullKey::new(&namespace, &key);
+            let hot = self.hot.write().await;
+            hot.put(full_key.clone(), versioned.clone());
+            trace!("Value promoted to hot memory");
+
+            // Track the access for lifecycle scoring (non-WASM only)
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                drop(hot);
+                self.lifecycle
+                    .record_write(&full_key, &versioned.write_id().to_string())
+                    .await;
+            }
+        }
+
+        // Auto-refresh views (fire and forget, non-WASM only)
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let views = Arc::clone(&self.views);
+            self.runtime.spawn(async move {
+                let _ = views.refresh_stale(chrono::Duration::seconds(0));
+            });
+        }
+
+        if let Some(value) = written {
+            self.spawn_post_write_hooks(post_write, &namespace, &key, &versioned, value);
+        }
+
+        info!(version = %version_id, "Put operation completed");
+        Ok(PutReceipt::new(versioned, durable))
+    }
+
+    /// Append a write to the WAL, if the database is persistent.
+    ///
+    /// Synchronous writes wait for the flush here. Asynchronous ones are
+    /// queued now, so the log keeps the order writes were applied in, and
+    /// flushed on a background task that keeps `write` in flight so
+    /// shutdown waits for it.
+    #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+    async fn persist(
+        &self,
+        full_key: FullKey,
+        versioned: &VersionedValue,
+        durability: Durability,
+        write: &WriteGuard,
+    ) -> Durable {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(ref wal) = self.wal {
+            trace!("Persisting to WAL");
+            let queued = wal.enqueue(vec![(full_key, versioned.clone())]);
+            if durability == Durability::Sync {
+                let result = wal.flush(queued).await;
+                match result {
+                    Ok(()) => trace!("Write persisted to WAL"),
+                    Err(ref e) => error!(error = %e, "Failed to persist write to WAL"),
+                }
+                return Durable::ready(result);
+            }
+
+            let (done, durable) = Durable::pending();
+            let wal = Arc::clone(wal);
+            let write = write.share();
+            self.runtime.spawn(
+                async move {
+                    let _write = write;
+                    let result = wal.flush(queued).await;
+                    if let Err(ref e) = result {
+                        error!(error = %e, "Failed to persist write to WAL");
+                    }
+                    let _ = done.send(result.map_err(|e| e.to_string()));
+                }
+                .in_current_span(),
+            );
+            return durable;
+        }
+        Durable::ready(Ok(()))
+    }
+
+    /// Drop a key from the vector index unless its new version holds a
+    /// vector, so deleted and overwritten embeddings stop matching searches.
+    fn unindex_overwritten(&self, namespace: &str, key: &str, versioned: &VersionedValue) {
+        if crate::vector::json_to_vector(versioned.value()).is_none() {
+            self.vector_index.remove(namespace, key);
+        }
+    }
+
+    /// Run post-write hooks for an applied write, each on its own task.
+    fn spawn_post_write_hooks(
+        &self,
+        hooks: Vec<PostWriteHook>,
+        namespace: &str,
+        key: &str,
+        versioned: &VersionedValue,
+        value: serde_json::Value,
+    ) {
+        let event = WriteEvent {
+            namespace: namespace.to_string(),
+            key: key.to_string(),
+            version: versioned.clone().with_value(value),
+        };
+        for hook in hooks {
+            self.runtime.spawn(hook(event.clone()));
+        }
+    }
+
+    /// Register a validator for writes to `namespace`.
+    ///
+    /// Validators run synchronously, in registration order, before each
+    /// local write to the namespace is applied (deletes are writes of
+    /// `null`). Returning `Err(reason)` rejects the write, which then fails
+    /// with [`DeltaError::WriteRejected`](crate::error::DeltaError::WriteRejected);
+    /// a batch is rejected as a whole. Values are seen as the caller wrote
+    /// them, before namespace encryption.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// db.add_validator("users", |write| {
+    ///     if write.value.is_null() || write.value["email"].is_string() {
+    ///         Ok(())
+    ///     } else {
+    ///         Err("users need an email".into())
+    ///     }
+    /// });
+    /// ```
+    pub fn add_validator<F>(&self, namespace: impl Into<String>, validator: F) -> HookId
+    where
+        F: Fn(&PendingWrite<'_>) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.hooks
+            .add_validator(namespace.into(), Arc::new(validator))
+    }
+
+    /// Register an async hook run after each write to `namespace`.
+    ///
+    /// Each hook runs on its own task once the write is applied, so it can
+    /// neither slow down nor fail the write. Use it for side effects such as
+    /// notifying other systems.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// db.add_post_write_hook("orders", move |event| {
+    ///     let mailer = mailer.clone();
+    ///     async move { mailer.order_changed(&event.key).await }
+    /// });
+    /// ```
+    pub fn add_post_write_hook<F, Fut>(&self, namespace: impl Into<String>, hook: F) -> HookId
+    where
+        F: Fn(WriteEvent) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        self.hooks.add_post_write(
+            namespace.into(),
+            Arc::new(move |event| -> HookFuture { Box::pin(hook(event)) }),
+        )
+    }
+
+    /// Remove a validator or post-write hook.
+    ///
+    /// Returns `false` if it was not registered.
+    pub fn remove_hook(&self, id: HookId) -> bool {
+        self.hooks.remove(id)
+    }
+
+    /// Keep an approximate aggregate over `namespace` up to date as it is
+    /// written.
+    ///
+    /// `aggregation` must be one of the sketch-backed aggregations
+    /// ([`approx_distinct`](Aggregation::approx_distinct),
+    /// [`percentiles`](Aggregation::percentiles) or
+    /// [`approx_top_k`](Aggregation::approx_top_k)). The sketch is seeded
+    /// from the current values, then every later local write is folded in
+    /// by a post-write hook, so reading it never scans the namespace.
+    /// Sketches only grow: overwritten values still count, making the
+    /// result an aggregate over all versions written.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let latency = db
+    ///     .track_aggregate("requests", Aggregation::percentiles("ms", &[50.0, 99.0]))
+    ///     .await?;
+    /// println!("p99: {}", latency.value()["p99"]);
+    /// ```
+    pub async fn track_aggregate(
+        &self,
+        namespace: impl Into<String>,
+        aggregation: Aggregation,
+    ) -> DeltaResult<TrackedAggregate> {
+        let namespace = namespace.into();
+        let sketch = ApproxAggregate::new(&aggregation).ok_or_else(|| {
+            crate::error::DeltaError::InvalidData {
+                reason: format!("{:?} is not an approximate aggregation", aggregation),
+            }
+        })?;
+        let state = Arc::new(std::sync::Mutex::new(sketch));
+
+        // Hook first, so writes made while seeding are not missed
+        let hook = {
+            let state = Arc::clone(&state);
+            self.add_post_write_hook(namespace.clone(), move |event| {
+                state.lock().unwrap().add(event.version.value());
+                std::future::ready(())
+            })
+        };
+        let current = match self.query(&namespace, Query::new()).await {
+            Ok(current) => current,
+            Err(e) => {
+                self.remove_hook(hook);
+                return Err(e);
+            }
+        };
+        {
+            let mut sketch = state.lock().unwrap();
+            for record in &current.records {
+                sketch.add(&record.value);
+            }
+        }
+
+        Ok(TrackedAggregate { hook, state })
+    }
+
+    /// Keep an ordered index over a numeric field of a namespace.
+    ///
+    /// The index is filled from the current values, then kept up to date as
+    /// writes land, replicated ones included. Its definition is stored, so
+    /// the index is rebuilt when the database is reopened. Creating an index
+    /// that already exists returns it unchanged.
+    ///
+    /// Once indexed, queries sorted on the field with a limit (and at most
+    /// numeric range filters on the same field) read only the keys they
+    /// return instead of scanning the namespace. The index itself answers
+    /// range scans, top-N and percentiles: see [`RangeIndex`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// db.create_range_index("orders", "total").await?;
+    /// let largest = db
+    ///     .query("orders", Query::new().sort_by("total", false).limit(10))
+    ///     .await?;
+    /// let median = db.range_index("orders", "total").unwrap().percentile(50.0);
+    /// ```
+    pub async fn create_range_index(
+        &self,
+        namespace: &str,
+        field: &str,
+    ) -> DeltaResult<RangeIndexInfo> {
+        use crate::range_index::RANGE_INDEX_NAMESPACE;
+
+        if field.is_empty() {
+            return Err(crate::error::DeltaError::InvalidData {
+                reason: "Range index field must not be empty".to_string(),
+            });
+        }
+        let definition = serde_json::json!({"namespace": namespace, "field": field});
+        self.put(
+            RANGE_INDEX_NAMESPACE,
+            &format!("{}/{}", namespace, field),
+            definition,
+        )
+        .await?;
+
+        // Fill the index with local writes paused, so none is seen stale
+        #[cfg(not(target_arch = "wasm32"))]
+        let _gate = self.write_gate.write().await;
+        let index = self.storage.create_range_index(namespace, field);
+        debug!(namespace = %namespace, field = %field, entries = index.len(), "Range index created");
+        Ok(index.info())
+    }
+
+    /// Get the range index on a namespace's field, if there is one.
+    pub fn range_index(&self, namespace: &str, field: &str) -> Option<Arc<RangeIndex>> {
+        self.storage.range_index(namespace, field)
+    }
+
+    /// List every range index.
+    pub fn list_range_indexes(&self) -> Vec<RangeIndexInfo> {
+        self.storage
+            .range_indexes()
+            .iter()
+            .map(|index| index.info())
+            .collect()
+    }
+
+    /// Drop a range index and its stored definition.
+    pub async fn drop_range_index(&self, namespace: &str, field: &str) -> DeltaResult<()> {
+        use crate::range_index::RANGE_INDEX_NAMESPACE;
+
+        let key = format!("{}/{}", namespace, field);
+        if !self.storage.drop_range_index(namespace, field) {
+            return Err(crate::error::DeltaError::KeyNotFound {
+                namespace: RANGE_INDEX_NAMESPACE.to_string(),
+                key,
+            });
+        }
+        self.delete(RANGE_INDEX_NAMESPACE, &key).await
+    }
+
+    /// Store a value with causal parent links in the graph.
+    ///
+    /// This establishes causal relationships in the graph while storing the value.
+    /// Use this when a distinction is caused by prior distinctions.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - The namespace to store in
+    /// * `key` - The key for this value
+    /// * `value` - The value to store
+    /// * `parent_keys` - Keys of parent distinctions that caused this one
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Store inference with causal link to observation
+    /// db.put_with_causal_links(
     ///     "concepts",
     ///     "inference_weather",
     ///     json!({"conclusion": "rain"}),
@@ -913,6 +1947,11 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
     ///
     /// Returns a vector of `VersionedValue` results, one per item, in the same order.
     ///
+    /// The batch is all or nothing: every item is validated before any is
+    /// stored, the batch is written to the WAL as a single record, and no
+    /// reader sees any of it until that record is synced. If storing an item
+    /// or writing the batch fails, nothing is kept and the error is returned.
+    ///
     /// # Performance
     ///
     /// For N items with persistence enabled:
@@ -931,6 +1970,7 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
     /// ```
     ///
     /// For simpler usage with owned strings, see `put_batch_values`.
+    #[instrument(name = "put_batch", skip_all, fields(count = items.len()))]
     pub async fn put_batch<T: Serialize>(
         &self,
         items: Vec<(impl Into<String>, impl Into<String>, T)>,
@@ -938,43 +1978,58 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         if items.is_empty() {
             return Ok(Vec::new());
         }
+        let _write = self.writes.begin()?;
+        #[cfg(not(target_arch = "wasm32"))]
+        let _gate = self.write_gate.read().await;
 
         #[cfg(not(target_arch = "wasm32"))]
         let start = std::time::Instant::now();
         let count = items.len();
         trace!(count, "Starting batch put operation");
 
-        // Convert all items upfront
+        // Convert and validate all items upfront
         let mut converted_items = Vec::with_capacity(items.len());
+        let mut written = Vec::with_capacity(items.len());
         for (ns, key, value) in items {
             let namespace = ns.into();
             let key = key.into();
             let json_value = serde_json::to_value(value)?;
+            catalog::check_writable(&namespace)?;
+            self.hooks.validate(&PendingWrite {
+                namespace: &namespace,
+                key: &key,
+                value: &json_value,
+            })?;
+            // Keep the plaintext only where a post-write hook will see it
+            let post_write = self.hooks.post_write_hooks(&namespace);
+            written.push((!post_write.is_empty()).then(|| (post_write, json_value.clone())));
+            let json_value = self.seal_for_namespace(&namespace, &key, json_value)?;
             converted_items.push((namespace, key, json_value));
         }
 
-        // Store in storage (source of truth)
-        trace!("Storing batch in CausalStorage");
-        let versioned_values = self.storage.put_batch(converted_items.clone())?;
+        // Stage in storage (source of truth); readers see none of the batch
+        // until it is durable
+        trace!("Staging batch in CausalStorage");
+        let staged = self.storage.stage_batch(converted_items.clone())?;
 
         // Persist to WAL if db_path is set (single fsync for entire batch)
         #[cfg(not(target_arch = "wasm32"))]
-        if let Some(ref db_path) = self.db_path {
-            use crate::persistence;
+        if let Some(ref wal) = self.wal {
             trace!("Persisting batch to WAL");
-
-            let write_refs: Vec<(&str, &str, &VersionedValue)> = converted_items
-                .iter()
-                .zip(versioned_values.iter())
-                .map(|((ns, key, _), versioned)| (ns.as_str(), key.as_str(), versioned))
-                .collect();
-
-            if let Err(e) = persistence::append_write_batch(db_path, write_refs).await {
-                error!(error = %e, "Failed to persist batch to WAL");
-            } else {
-                trace!("Batch persisted to WAL");
+            if let Err(e) = wal.commit(staged.clone()).await {
+                error!(error = %e, "Failed to persist batch to WAL, discarding it");
+                self.storage.discard_batch(&staged);
+                return Err(e);
             }
+            trace!("Batch persisted to WAL");
+        }
+
+        self.storage.publish_batch(&staged);
+        for (key, versioned) in &staged {
+            self.unindex_overwritten(&key.namespace, &key.key, versioned);
         }
+        let versioned_values: Vec<VersionedValue> =
+            staged.into_iter().map(|(_, versioned)| versioned).collect();
 
         // Broadcast to cluster if configured (fire and forget)
         #[cfg(not(target_arch = "wasm32"))]
@@ -985,10 +2040,13 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
                 let full_key = FullKey::new(namespace, key);
                 let value_clone = versioned.clone();
                 let cluster_clone = Arc::clone(cluster);
-                tokio::spawn(async move {
-                    trace!("Broadcasting write to cluster");
-                    cluster_clone.broadcast_write(full_key, value_clone).await;
-                });
+                self.runtime.spawn(
+                    async move {
+                        trace!("Broadcasting write to cluster");
+                        cluster_clone.broadcast_write(full_key, value_clone).await;
+                    }
+                    .in_current_span(),
+                );
             }
         }
 
@@ -1008,11 +2066,21 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         #[cfg(not(target_arch = "wasm32"))]
         {
             let views = Arc::clone(&self.views);
-            tokio::spawn(async move {
+            self.runtime.spawn(async move {
                 let _ = views.refresh_stale(chrono::Duration::seconds(0));
             });
         }
 
+        for (((namespace, key, _), versioned), written) in converted_items
+            .iter()
+            .zip(versioned_values.iter())
+            .zip(written)
+        {
+            if let Some((post_write, value)) = written {
+                self.spawn_post_write_hooks(post_write, namespace, key, versioned, value);
+            }
+        }
+
         #[cfg(not(target_arch = "wasm32"))]
         let elapsed = start.elapsed();
         #[cfg(not(target_arch = "wasm32"))]
@@ -1046,6 +2114,9 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         namespace: impl Into<String>,
         items: Vec<(String, serde_json::Value)>,
     ) -> DeltaResult<Vec<VersionedValue>> {
+        let _write = self.writes.begin()?;
+        #[cfg(not(target_arch = "wasm32"))]
+        let _gate = self.write_gate.read().await;
         let namespace = namespace.into();
         let batch: Vec<(String, String, serde_json::Value)> = items
             .into_iter()
@@ -1053,26 +2124,95 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
             .collect();
 
         // Convert to the format expected by storage
+        let post_write = self.hooks.post_write_hooks(&namespace);
         let mut converted = Vec::with_capacity(batch.len());
+        let mut written = Vec::new();
         for (ns, key, value) in batch {
+            catalog::check_writable(&ns)?;
+            self.hooks.validate(&PendingWrite {
+                namespace: &ns,
+                key: &key,
+                value: &value,
+            })?;
+            if !post_write.is_empty() {
+                written.push((key.clone(), value.clone()));
+            }
+            let value = self.seal_for_namespace(&ns, &key, value)?;
             converted.push((ns, key, value));
         }
 
-        self.storage.put_batch(converted)
+        let keys: Vec<String> = converted.iter().map(|(_, key, _)| key.clone()).collect();
+        let versioned_values = self.storage.put_batch(converted)?;
+        for (key, versioned) in keys.iter().zip(&versioned_values) {
+            self.unindex_overwritten(&namespace, key, versioned);
+        }
+        if !post_write.is_empty() {
+            for ((key, value), versioned) in written.into_iter().zip(&versioned_values) {
+                self.spawn_post_write_hooks(post_write.clone(), &namespace, &key, versioned, value);
+            }
+        }
+        Ok(versioned_values)
     }
 
     /// Get the current value for a key.
     ///
     /// Searches through memory tiers: Hot → Warm → Cold → Deep → Storage
     /// On hit in lower tiers, promotes value up for faster future access.
+    #[instrument(
+        name = "get",
+        skip_all,
+        fields(namespace = Empty, key = Empty, version_id = Empty)
+    )]
     pub async fn get(
         &self,
         namespace: impl Into<String>,
         key: impl Into<String>,
     ) -> DeltaResult<VersionedValue> {
-        let namespace = namespace.into();
-        let key = key.into();
-        let full_key = FullKey::new(&namespace, &key);
+        let full_key = FullKey::new(namespace, key);
+        let span = Span::current();
+        span.record("namespace", full_key.namespace.as_str());
+        span.record("key", full_key.key.as_str());
+        let result = self.get_tiered(&full_key).await;
+        if let Ok(ref versioned) = result {
+            span.record("version_id", versioned.version_id());
+            self.hot
+                .read()
+                .await
+                .record_access(&full_key, versioned.write_id());
+        }
+
+        // Track the access for lifecycle scoring (non-WASM only)
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Ok(ref versioned) = result {
+            self.lifecycle
+                .record_access(&full_key, &versioned.write_id().to_string())
+                .await;
+        }
+
+        result
+    }
+
+    /// Get the current value for a key as raw JSON text.
+    ///
+    /// For readers that forward values (e.g. onto the network) rather than
+    /// inspect them: each version's value is serialized into one buffer
+    /// once, when it is written, so this only clones an `Arc` instead of
+    /// deep-cloning the JSON tree, and the result can be handed to other
+    /// tasks without further copies.
+    /// Use [`VersionedValue::field`] to read single fields without copying.
+    pub async fn get_raw(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+    ) -> DeltaResult<Arc<RawValue>> {
+        Ok(self.get(namespace, key).await?.to_raw())
+    }
+
+    /// Tiered lookup behind [`get`](Self::get).
+    async fn get_tiered(&self, full_key: &FullKey) -> DeltaResult<VersionedValue> {
+        let namespace = full_key.namespace.clone();
+        let key = full_key.key.clone();
+        let full_key = full_key.clone();
         trace!("Starting tiered memory lookup");
 
         // Tier 1: Hot memory (fastest)
@@ -1151,11 +2291,12 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         if let Some(crate::memory::Evicted {
             distinction_id: _,
             versioned,
+            key: evicted_key,
         }) = evicted
         {
             drop(hot);
             let warm = self.warm.write().await;
-            warm.put(key, versioned);
+            warm.put(evicted_key.unwrap_or(key), versioned);
         }
     }
 
@@ -1201,6 +2342,67 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         self.storage.get_at(namespace, key, timestamp)
     }
 
+    /// Begin a read transaction pinned to the current state.
+    ///
+    /// Gets and queries through the transaction all see the same snapshot,
+    /// even while writes proceed. Beginning one copies nothing and holds no
+    /// lock, so it can be kept across awaits for the length of a request.
+    pub fn begin_read(&self) -> ReadTransaction {
+        ReadTransaction::begin(Arc::clone(&self.storage))
+    }
+
+    /// Conflicts replication found in a namespace, oldest first.
+    ///
+    /// Each lists the concurrent sibling versions and the one that won, so
+    /// last-write-wins choices can be audited. Conflicts are stored in the
+    /// [`CONFLICT_NAMESPACE`](crate::storage::CONFLICT_NAMESPACE) namespace
+    /// until [pruned](Self::prune_conflicts).
+    pub async fn conflicts(&self, namespace: &str) -> Vec<WriteConflict> {
+        self.storage.conflicts(namespace)
+    }
+
+    /// Drop settled conflicts detected more than `age` ago, by the runtime
+    /// clock.
+    ///
+    /// Unresolved conflicts are kept until resolved. Returns how many
+    /// conflicts were dropped.
+    pub async fn prune_conflicts(&self, age: chrono::Duration) -> DeltaResult<usize> {
+        self.storage.prune_conflicts(self.runtime.utc_now() - age)
+    }
+
+    /// Re-resolve a conflict by writing `value` as the key's current value.
+    ///
+    /// `value` is often one of the conflict's siblings, or a merge of them.
+    /// The write goes through [`put_notify`](Self::put_notify), so it is
+    /// persisted, replicated and seen by subscribers, and the conflict is marked
+    /// [`Resolved`](crate::types::ConflictStatus::Resolved) with it as winner.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// for conflict in db.conflicts("carts").await {
+    ///     let items = merge_items(&conflict.siblings);
+    ///     db.resolve_conflict("carts", &conflict.id, items).await?;
+    /// }
+    /// ```
+    pub async fn resolve_conflict<T: Serialize>(
+        &self,
+        namespace: &str,
+        conflict_id: &str,
+        value: T,
+    ) -> DeltaResult<VersionedValue> {
+        let conflict = self
+            .storage
+            .conflict(conflict_id)
+            .filter(|conflict| conflict.key.namespace == namespace)
+            .ok_or_else(|| crate::error::DeltaError::InvalidData {
+                reason: format!("No conflict '{}' in namespace '{}'", conflict_id, namespace),
+            })?;
+        let winner = self.put_notify(namespace, conflict.key.key, value).await?;
+        self.storage.settle_conflict(conflict_id, winner.clone())?;
+        Ok(winner)
+    }
+
     /// Get complete history for a key.
     pub async fn history(&self, namespace: &str, key: &str) -> DeltaResult<Vec<HistoryEntry>> {
         self.storage.history(namespace, key)
@@ -1243,6 +2445,91 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         Ok(entries)
     }
 
+    /// Value changes of every key in `namespace`, for churn and change-rate
+    /// analysis.
+    ///
+    /// Returns, per key with at least one selected change, the
+    /// `(timestamp, old_value, new_value)` transitions chosen by
+    /// `history_query` (see [`QueryExecutor::execute_changes`]): the time
+    /// window, filters matched against either side of a change, and
+    /// `latest` per key. Keys come in order, and the base query's `limit`
+    /// caps how many are returned. Keys not written since the window opened
+    /// are skipped without reading their history.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Subscriptions that became or stopped being active this week
+    /// let churn = db
+    ///     .query_changes(
+    ///         "subscriptions",
+    ///         HistoryQuery::new()
+    ///             .with_query(Query::new().filter(Filter::eq("status", "active")))
+    ///             .from(week_start),
+    ///     )
+    ///     .await?;
+    /// ```
+    pub async fn query_changes(
+        &self,
+        namespace: &str,
+        history_query: HistoryQuery,
+    ) -> DeltaResult<Vec<KeyChanges>> {
+        let mut results = Vec::new();
+        for key in self.storage.list_keys(namespace) {
+            if history_query
+                .query
+                .limit
+                .is_some_and(|limit| results.len() >= limit)
+            {
+                break;
+            }
+            if let (Some(from), Ok(current)) =
+                (history_query.from_time, self.storage.get(namespace, &key))
+            {
+                if current.timestamp() < from {
+                    continue;
+                }
+            }
+            let history = self.storage.history(namespace, &key)?;
+            let changes = QueryExecutor::execute_changes(&history_query, &history);
+            if !changes.is_empty() {
+                results.push(KeyChanges { key, changes });
+            }
+        }
+        Ok(results)
+    }
+
+    /// Aggregate a key's history.
+    ///
+    /// Runs the base query's aggregation over the versions selected by
+    /// `history_query`, e.g. an hourly rollup of a sensor reading:
+    ///
+    /// ```ignore
+    /// let hourly = db
+    ///     .aggregate_history(
+    ///         "sensors",
+    ///         "kitchen",
+    ///         HistoryQuery::new().with_query(
+    ///             Query::new().aggregate(Aggregation::time_bucket("temp", BucketInterval::Hour)),
+    ///         ),
+    ///     )
+    ///     .await?;
+    /// ```
+    pub async fn aggregate_history(
+        &self,
+        namespace: &str,
+        key: &str,
+        history_query: HistoryQuery,
+    ) -> DeltaResult<serde_json::Value> {
+        let aggregation = history_query.query.aggregation.clone().ok_or_else(|| {
+            crate::error::DeltaError::InvalidData {
+                reason: "history aggregation needs a query with an aggregation".to_string(),
+            }
+        })?;
+        let entries = self.query_history(namespace, key, history_query).await?;
+        Ok(QueryExecutor::aggregate_history(&aggregation, &entries))
+    }
+
     // ============================================================================
     // Vector / Embedding Operations (AI Infrastructure)
     // ============================================================================
@@ -1276,6 +2563,7 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
     ) -> DeltaResult<VersionedValue> {
         let namespace = namespace.into();
         let key = key.into();
+        self.check_vector_model(&namespace, &vector)?;
 
         // Serialize vector with metadata
         let value = crate::vector::vector_to_json(&vector, metadata);
@@ -1291,6 +2579,68 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         Ok(versioned)
     }
 
+    /// Store many vector embeddings in one namespace.
+    ///
+    /// Values are written with a single batch (one WAL fsync) and the vectors
+    /// are added to the index in one construction pass, instead of N
+    /// incremental inserts.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - The namespace for all embeddings
+    /// * `items` - Vector of (key, vector, metadata) tuples
+    ///
+    /// # Returns
+    ///
+    /// One `VersionedValue` per item, in the same order.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let items = vec![
+    ///     ("doc1", v1, Some(json!({"title": "AI"}))),
+    ///     ("doc2", v2, None),
+    /// ];
+    /// db.embed_batch("docs", items).await?;
+    /// ```
+    pub async fn embed_batch(
+        &self,
+        namespace: impl Into<String>,
+        items: Vec<(impl Into<String>, Vector, Option<serde_json::Value>)>,
+    ) -> DeltaResult<Vec<VersionedValue>> {
+        let entries = items
+            .into_iter()
+            .map(|(key, vector, metadata)| {
+                let value = crate::vector::vector_to_json(&vector, metadata);
+                (key.into(), vector, value)
+            })
+            .collect();
+        self.store_embeddings(namespace.into(), entries).await
+    }
+
+    /// Store pre-serialized embedding values and index their vectors in one pass.
+    async fn store_embeddings(
+        &self,
+        namespace: String,
+        entries: Vec<(String, Vector, serde_json::Value)>,
+    ) -> DeltaResult<Vec<VersionedValue>> {
+        let count = entries.len();
+        let mut values = Vec::with_capacity(count);
+        let mut vectors = Vec::with_capacity(count);
+        for (key, vector, value) in entries {
+            self.check_vector_model(&namespace, &vector)?;
+            vectors.push((FullKey::new(&namespace, &key), vector));
+            values.push((key, value));
+        }
+
+        // Store in database first; the index only reflects stored embeddings
+        let versioned = self.put_batch_in_ns(&namespace, values).await?;
+        self.vector_index.add_batch(vectors);
+
+        debug!(namespace = %namespace, count, "Vector embeddings stored in batch");
+        Ok(versioned)
+    }
+
     /// Search for similar vectors using cosine similarity.
     ///
     /// Performs approximate nearest neighbor search on stored embeddings.
@@ -1319,8 +2669,26 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         &self,
         namespace: Option<&str>,
         query: &Vector,
-        options: VectorSearchOptions,
+        mut options: VectorSearchOptions,
     ) -> DeltaResult<Vec<VectorSearchResult>> {
+        // Score with the namespace's metric, rejecting mismatched queries
+        if let Some(ns) = namespace {
+            if let Some(config) = self.vector_namespace_config(ns) {
+                if let Some(metric) = options.metric {
+                    if metric != config.metric {
+                        return Err(crate::error::DeltaError::InvalidData {
+                            reason: format!(
+                                "Namespace '{}' uses the {} metric, but the query asked for {}",
+                                ns, config.metric, metric
+                            ),
+                        });
+                    }
+                }
+                self.check_vector_model(ns, query)?;
+                options.metric = Some(config.metric);
+            }
+        }
+
         // Search the vector index
         let mut results = self.vector_index.search(query, &options);
 
@@ -1336,6 +2704,126 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         Ok(results)
     }
 
+    /// Search for similar vectors and explain why each hit was retrieved.
+    ///
+    /// Runs [`embed_search`](Self::embed_search), then explains every hit
+    /// against the query: the synthesis path from the query (direct, or via
+    /// another hit that bridges to it) and a proximity breakdown into
+    /// geometric similarity, shared strong relations, and temporal closeness
+    /// to the most recently written hit.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// for hit in db.embed_search_explain(Some("docs"), &query, VectorSearchOptions::new()).await? {
+    ///     println!("{}: {}", hit.result.key, hit.explanation.description);
+    /// }
+    /// ```
+    pub async fn embed_search_explain(
+        &self,
+        namespace: Option<&str>,
+        query: &Vector,
+        options: VectorSearchOptions,
+    ) -> DeltaResult<Vec<ExplainedSearchResult>> {
+        let results = self.embed_search(namespace, query, options).await?;
+
+        let mut written_at = Vec::with_capacity(results.len());
+        for result in &results {
+            let timestamp = self
+                .get(&result.namespace, &result.key)
+                .await
+                .ok()
+                .map(|v| v.timestamp());
+            written_at.push(timestamp);
+        }
+        let newest = written_at.iter().flatten().max().copied();
+
+        // Causal component: 1.0 for the newest hit, halving per hour older
+        let hits: Vec<(Vector, f32)> = results
+            .iter()
+            .zip(&written_at)
+            .map(|(result, timestamp)| {
+                let causal = match (newest, timestamp) {
+                    (Some(newest), Some(t)) => {
+                        let hours = (newest - *t).num_milliseconds() as f32 / 3_600_000.0;
+                        0.5f32.powf(hours.max(0.0))
+                    }
+                    _ => 0.0,
+                };
+                (result.vector.clone(), causal)
+            })
+            .collect();
+
+        let explained = crate::vector::snsw::explain_hits(query, &hits)
+            .into_iter()
+            .zip(results)
+            .map(|((explanation, proximity), result)| ExplainedSearchResult {
+                result,
+                explanation,
+                proximity,
+            })
+            .collect();
+        Ok(explained)
+    }
+
+    /// Configure how vectors in a namespace are compared.
+    ///
+    /// The configuration is stored like any other value, so it survives
+    /// restarts. Searches of the namespace then score with its metric, and
+    /// embeddings or queries from a different model are rejected if a model
+    /// is set.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let config = VectorNamespaceConfig::new(DistanceMetric::Euclidean)
+    ///     .with_model("text-embedding-3-small");
+    /// db.configure_vector_namespace("docs", config).await?;
+    /// ```
+    pub async fn configure_vector_namespace(
+        &self,
+        namespace: impl Into<String>,
+        config: VectorNamespaceConfig,
+    ) -> DeltaResult<()> {
+        use crate::vector::VECTOR_CONFIG_NAMESPACE;
+
+        let namespace = namespace.into();
+        let value = serde_json::to_value(&config)?;
+        self.put(VECTOR_CONFIG_NAMESPACE, &namespace, value).await?;
+        debug!(namespace = %namespace, metric = %config.metric, "Vector namespace configured");
+        Ok(())
+    }
+
+    /// Get the vector configuration of a namespace, if one was set.
+    pub fn vector_namespace_config(&self, namespace: &str) -> Option<VectorNamespaceConfig> {
+        use crate::vector::VECTOR_CONFIG_NAMESPACE;
+
+        let versioned = self.storage.get(VECTOR_CONFIG_NAMESPACE, namespace).ok()?;
+        serde_json::from_value(versioned.value().clone()).ok()
+    }
+
+    /// Reject vectors from a model other than the namespace's configured one.
+    fn check_vector_model(&self, namespace: &str, vector: &Vector) -> DeltaResult<()> {
+        let Some(expected) = self
+            .vector_namespace_config(namespace)
+            .and_then(|config| config.model)
+        else {
+            return Ok(());
+        };
+
+        if vector.model() != expected {
+            return Err(crate::error::DeltaError::InvalidData {
+                reason: format!(
+                    "Namespace '{}' requires vectors from model '{}', got '{}'",
+                    namespace,
+                    expected,
+                    vector.model()
+                ),
+            });
+        }
+        Ok(())
+    }
+
     // =========================================================================
     // TTL (Time-To-Live) Support - ALIS AI Integration
     // =========================================================================
@@ -1470,9 +2958,6 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
                     warn!(error = %e, namespace = %namespace, key = %key, "Failed to remove expired item");
                 }
             }
-
-            // Remove from vector index if present
-            self.vector_index.remove(&namespace, &key);
         }
 
         // Clean up TTL index
@@ -1971,6 +3456,62 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         Ok(results)
     }
 
+    /// Check if two causal graph nodes are linked by any chain of edges.
+    ///
+    /// Nodes are version write IDs ([`VersionedValue::write_id`]) or the
+    /// `namespace:key` nodes created by
+    /// [`put_with_causal_links`](Self::put_with_causal_links). Edges are
+    /// followed in both directions.
+    pub async fn is_connected(&self, a: &str, b: &str) -> bool {
+        let action = crate::actions::LineageQueryAction::QueryConnected {
+            key_a: a.to_string(),
+            key_b: b.to_string(),
+        };
+        let _ = action.to_canonical_structure(self.shared_engine.inner());
+
+        self.storage.causal_graph().is_connected(a, b)
+    }
+
+    /// Find a shortest chain of causal edges between two graph nodes.
+    ///
+    /// Unlike [`get_connection_path`](Self::get_connection_path), every
+    /// consecutive pair in the returned path is a direct parent/child edge.
+    /// The path starts at `a` and ends at `b`; `None` means the nodes are
+    /// unknown or not connected.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let draft = db.put("docs", "readme", json!("draft")).await?;
+    /// db.put("docs", "readme", json!("review")).await?;
+    /// let last = db.put("docs", "readme", json!("final")).await?;
+    /// let path = db.shortest_causal_path(draft.write_id(), last.write_id()).await;
+    /// assert_eq!(path.map(|p| p.len()), Some(3));
+    /// ```
+    pub async fn shortest_causal_path(&self, a: &str, b: &str) -> Option<Vec<String>> {
+        let action = crate::actions::LineageQueryAction::GetConnectionPath {
+            key_a: a.to_string(),
+            key_b: b.to_string(),
+        };
+        let _ = action.to_canonical_structure(self.shared_engine.inner());
+
+        self.storage.causal_graph().shortest_path(a, b)
+    }
+
+    /// Get every causal graph node within `k` hops of `id`.
+    ///
+    /// Returns `(node, distance)` pairs ordered by distance, excluding `id`.
+    /// Edges are followed in both directions.
+    pub async fn causal_neighborhood(&self, id: &str, k: usize) -> Vec<(String, usize)> {
+        let action = crate::actions::LineageQueryAction::GetNeighborhood {
+            key: id.to_string(),
+            k,
+        };
+        let _ = action.to_canonical_structure(self.shared_engine.inner());
+
+        self.storage.causal_graph().within_hops(id, k)
+    }
+
     /// Find similar distinctions that are not causally connected.
     ///
     /// This method uses the vector index for efficient similarity search,
@@ -2175,7 +3716,7 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
     /// # Algorithm
     ///
     /// 1. Pick random starting distinction from the graph
-    /// 2. Follow random causal link (parent or child)
+    /// 2. Follow random causal link (parent, child, cause or dependent)
     /// 3. Repeat for `steps` iterations
     /// 4. Record end distinction
     /// 5. Compute novelty score (path length / connectivity ratio)
@@ -2216,12 +3757,21 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         let _ = action.to_canonical_structure(self.shared_engine.inner());
 
         let graph = self.storage.causal_graph();
+        let references = self.storage.reference_graph();
         let all_nodes = graph.all_nodes();
 
         if all_nodes.is_empty() {
             return Ok(Vec::new());
         }
 
+        // Graph nodes are write IDs; walks report the keys they belong to
+        let keys: std::collections::HashMap<String, FullKey> = self
+            .storage
+            .all_versions()
+            .into_iter()
+            .map(|(key, versioned)| (versioned.write_id, key))
+            .collect();
+
         use rand::seq::SliceRandom;
         use rand::thread_rng;
 
@@ -2232,13 +3782,9 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
             // Pick random starting node
             let start_node = all_nodes.choose(&mut rng).cloned().unwrap_or_default();
 
-            // Parse start node
-            let parts: Vec<&str> = start_node.split(':').collect();
-            if parts.len() < 2 {
+            let Some(start) = keys.get(&start_node) else {
                 continue;
-            }
-            let start_namespace = parts[0].to_string();
-            let start_key = parts[1..].join(":");
+            };
 
             // Perform random walk
             let mut current = start_node.clone();
@@ -2246,7 +3792,7 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
             let mut valid_walk = true;
 
             for _ in 0..steps {
-                // Get neighbors (parents + children)
+                // Get neighbors (parents + children, causes + dependents)
                 let mut neighbors: Vec<String> = Vec::new();
 
                 if let Some(parents) = graph.get_parents(&current) {
@@ -2255,6 +3801,8 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
                 if let Some(children) = graph.get_children(&current) {
                     neighbors.extend(children.iter().cloned());
                 }
+                neighbors.extend(references.references(&current));
+                neighbors.extend(references.referrers(&current));
 
                 // Remove duplicates while preserving order
                 let mut seen = std::collections::HashSet::new();
@@ -2291,13 +3839,9 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
                 continue;
             }
 
-            // Parse end node
-            let end_parts: Vec<&str> = current.split(':').collect();
-            if end_parts.len() < 2 {
+            let Some(end) = keys.get(&current) else {
                 continue;
-            }
-            let end_namespace = end_parts[0].to_string();
-            let end_key = end_parts[1..].join(":");
+            };
 
             // Skip if start == end (no interesting journey)
             if start_node == current {
@@ -2320,10 +3864,10 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
             let normalized_novelty = novelty_score.clamp(0.0, 1.0);
 
             combinations.push(RandomCombination::new(
-                start_namespace,
-                start_key,
-                end_namespace,
-                end_key,
+                &start.namespace,
+                &start.key,
+                &end.namespace,
+                &end.key,
                 path,
                 normalized_novelty,
             ));
@@ -2339,6 +3883,133 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         Ok(combinations)
     }
 
+    /// Dream: run random walks and store what they find for review.
+    ///
+    /// Each combination between two different keys becomes a [`Dream`] in
+    /// [`DREAMS_NAMESPACE`], written with the start and end versions as its
+    /// causes. A pair of keys is only dreamed once, so combinations that were
+    /// already stored (or reviewed) are skipped.
+    ///
+    /// Returns the new dreams, most novel first.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// for dream in db.dream(5, 10).await? {
+    ///     println!("{} ~ {}", dream.combination.start_key, dream.combination.end_key);
+    /// }
+    /// ```
+    pub async fn dream(&self, n: usize, steps: usize) -> DeltaResult<Vec<Dream>> {
+        let mut dreams = Vec::new();
+        for combination in self.random_walk_combinations(n, steps).await? {
+            let start = FullKey::new(&combination.start_namespace, &combination.start_key);
+            let end = FullKey::new(&combination.end_namespace, &combination.end_key);
+            if start == end
+                || start.namespace == DREAMS_NAMESPACE
+                || end.namespace == DREAMS_NAMESPACE
+            {
+                continue;
+            }
+
+            let id = Dream::id_for(&start, &end);
+            if self.storage.get(DREAMS_NAMESPACE, &id).is_ok() {
+                continue;
+            }
+            let (Ok(start_version), Ok(end_version)) = (
+                self.storage.get(&start.namespace, &start.key),
+                self.storage.get(&end.namespace, &end.key),
+            ) else {
+                continue;
+            };
+
+            let dream = Dream {
+                id,
+                combination,
+                sources: vec![
+                    start_version.write_id().to_string(),
+                    end_version.write_id().to_string(),
+                ],
+                status: DreamStatus::Pending,
+                dreamed_at: Utc::now(),
+                reviewed_at: None,
+            };
+            self.store_dream(&dream).await?;
+            dreams.push(dream);
+        }
+        Ok(dreams)
+    }
+
+    /// All stored dreams, pending and reviewed, most novel first.
+    pub async fn dreams(&self) -> DeltaResult<Vec<Dream>> {
+        let mut dreams = self
+            .storage
+            .scan_collection(DREAMS_NAMESPACE)
+            .into_iter()
+            .filter(|(_, versioned)| !versioned.value().is_null())
+            .map(|(_, versioned)| serde_json::from_value::<Dream>(versioned.value().clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        dreams.sort_by(|a, b| {
+            b.combination
+                .novelty_score
+                .partial_cmp(&a.combination.novelty_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        Ok(dreams)
+    }
+
+    /// Get a stored dream.
+    pub async fn get_dream(&self, id: &str) -> DeltaResult<Dream> {
+        let versioned = self.storage.get(DREAMS_NAMESPACE, id)?;
+        if versioned.value().is_null() {
+            return Err(crate::error::DeltaError::KeyNotFound {
+                namespace: DREAMS_NAMESPACE.to_string(),
+                key: id.to_string(),
+            });
+        }
+        Ok(serde_json::from_value(versioned.value().clone())?)
+    }
+
+    /// Accept a pending dream as a meaningful connection.
+    pub async fn accept_dream(&self, id: &str) -> DeltaResult<Dream> {
+        self.review_dream(id, DreamStatus::Accepted).await
+    }
+
+    /// Discard a pending dream.
+    ///
+    /// The dream stays stored as discarded, so the same combination is not
+    /// dreamed again.
+    pub async fn discard_dream(&self, id: &str) -> DeltaResult<Dream> {
+        self.review_dream(id, DreamStatus::Discarded).await
+    }
+
+    /// Move a pending dream to `status`.
+    async fn review_dream(&self, id: &str, status: DreamStatus) -> DeltaResult<Dream> {
+        let mut dream = self.get_dream(id).await?;
+        if dream.status != DreamStatus::Pending {
+            return Err(crate::error::DeltaError::InvalidData {
+                reason: format!("Dream {} was already reviewed", id),
+            });
+        }
+        dream.status = status;
+        dream.reviewed_at = Some(Utc::now());
+        self.store_dream(&dream).await?;
+        Ok(dream)
+    }
+
+    /// Write a dream, linked to its source versions.
+    async fn store_dream(&self, dream: &Dream) -> DeltaResult<()> {
+        self.put_attributed(
+            DREAMS_NAMESPACE,
+            &dream.id,
+            dream,
+            None,
+            dream.sources.clone(),
+        )
+        .await?;
+        Ok(())
+    }
+
     /// Simplified: Store content with an auto-generated distinction-based embedding.
     ///
     /// This is the high-level convenience method for semantic storage.
@@ -2376,30 +4047,294 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         self.embed(&namespace, &key, vector, metadata).await
     }
 
-    /// Simplified: Search for content similar to the given text/content.
-    ///
-    /// This generates an embedding from the query content and finds similar items.
+    /// Embed text with a local embedder.
     ///
-    /// # Arguments
+    /// The default is set by [`EmbedderConfig`]: a
+    /// [`HashingEmbedder`](crate::vector::HashingEmbedder), which needs no
+    /// model files, unless a sentence transformer is configured. Any local
+    /// model can be used by implementing [`TextEmbedder`].
+    pub fn set_embedder(&self, embedder: Arc<dyn TextEmbedder>) {
+        self.set_embedding_provider(Arc::new(LocalEmbeddingProvider::new(embedder)));
+    }
+
+    /// Embed text with any provider, e.g. a remote OpenAI-compatible endpoint.
+    pub fn set_embedding_provider(&self, provider: Arc<dyn EmbeddingProvider>) {
+        *self.embedder.write().unwrap() = provider;
+    }
+
+    /// Get the provider used to embed text.
+    pub fn embedding_provider(&self) -> Arc<dyn EmbeddingProvider> {
+        Arc::clone(&self.embedder.read().unwrap())
+    }
+
+    /// Store text together with an embedding generated locally.
     ///
-    /// * `namespace` - Optional namespace to search (None = all)
-    /// * `query_content` - The content to find similar items to
-    /// * `top_k` - Maximum number of results
+    /// The stored value holds the vector (like [`embed`](Self::embed)) plus a
+    /// `text` field with the original text.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let results = db.find_similar(
-    ///     Some("docs"),
-    ///     json!({"text": "artificial intelligence"}),
-    ///     5
-    /// ).await?;
+    /// db.embed_text("docs", "k1", "Fires are spreading near the river").await?;
+    /// let hits = db.search_text(Some("docs"), "river fire", VectorSearchOptions::new()).await?;
     /// ```
-    pub async fn find_similar(
+    pub async fn embed_text(
         &self,
-        namespace: Option<&str>,
-        query_content: impl Serialize,
-        top_k: usize,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        text: impl Into<String>,
+    ) -> DeltaResult<VersionedValue> {
+        let namespace = namespace.into();
+        let key = key.into();
+        let text = text.into();
+
+        let vector = self.embedding_provider().embed(&text).await?;
+        self.check_vector_model(&namespace, &vector)?;
+
+        let mut value = crate::vector::vector_to_json(&vector, None);
+        value["text"] = serde_json::Value::String(text);
+        let versioned = self.put(&namespace, &key, value).await?;
+        self.vector_index
+            .add(FullKey::new(&namespace, &key), vector);
+
+        debug!(namespace = %namespace, key = %key, "Text embedding stored");
+        Ok(versioned)
+    }
+
+    /// Search for stored embeddings similar to a text.
+    pub async fn search_text(
+        &self,
+        namespace: Option<&str>,
+        text: &str,
+        options: VectorSearchOptions,
+    ) -> DeltaResult<Vec<VectorSearchResult>> {
+        let query = self.embedding_provider().embed(text).await?;
+        self.embed_search(namespace, &query, options).await
+    }
+
+    /// Store many texts with embeddings generated in batches.
+    ///
+    /// All texts are embedded first (the provider batches requests), then
+    /// stored in one batch, each value holding the vector and its `text`.
+    pub async fn embed_texts(
+        &self,
+        namespace: impl Into<String>,
+        items: Vec<(impl Into<String>, impl Into<String>)>,
+    ) -> DeltaResult<Vec<VersionedValue>> {
+        let namespace = namespace.into();
+        let (keys, texts): (Vec<String>, Vec<String>) = items
+            .into_iter()
+            .map(|(key, text)| (key.into(), text.into()))
+            .unzip();
+
+        let vectors = self.embedding_provider().embed_batch(&texts).await?;
+        if vectors.len() != texts.len() {
+            return Err(crate::error::DeltaError::EmbeddingError(format!(
+                "Expected {} embeddings, got {}",
+                texts.len(),
+                vectors.len()
+            )));
+        }
+
+        let entries = keys
+            .into_iter()
+            .zip(vectors)
+            .zip(texts)
+            .map(|((key, vector), text)| {
+                let mut value = crate::vector::vector_to_json(&vector, None);
+                value["text"] = serde_json::Value::String(text);
+                (key, vector, value)
+            })
+            .collect();
+        self.store_embeddings(namespace, entries).await
+    }
+
+    /// Store a document for retrieval, split into embedded chunks.
+    ///
+    /// The full text is stored at `namespace`/`key`. Each chunk is embedded
+    /// with the configured provider and stored in the namespace's chunk
+    /// namespace, linked to the document version it was cut from. Storing a
+    /// new version re-chunks it and retires chunks the new text no longer has.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// db.store_document("manuals", "pump", text, ChunkConfig::new(500).with_overlap(50)).await?;
+    /// ```
+    pub async fn store_document(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        text: impl Into<String>,
+        config: ChunkConfig,
+    ) -> DeltaResult<StoredDocument> {
+        let namespace = namespace.into();
+        let key = key.into();
+        let text = text.into();
+
+        let chunks = crate::rag::chunk_text(&text, &config);
+        if chunks.is_empty() {
+            return Err(crate::error::DeltaError::InvalidData {
+                reason: "Cannot store a document without any text".to_string(),
+            });
+        }
+
+        // Embed before writing anything, so a provider failure stores nothing
+        let texts: Vec<String> = chunks.iter().map(|c| c.text.clone()).collect();
+        let vectors = self.embedding_provider().embed_batch(&texts).await?;
+        if vectors.len() != texts.len() {
+            return Err(crate::error::DeltaError::EmbeddingError(format!(
+                "Expected {} embeddings, got {}",
+                texts.len(),
+                vectors.len()
+            )));
+        }
+
+        let previous_chunks = self
+            .get(&namespace, &key)
+            .await
+            .ok()
+            .and_then(|v| v.value().get("chunk_count").and_then(|c| c.as_u64()))
+            .unwrap_or(0) as usize;
+
+        let document = self
+            .put(
+                &namespace,
+                &key,
+                serde_json::json!({ "text": text, "chunk_count": chunks.len() }),
+            )
+            .await?;
+        let version_id = document.version_id().to_string();
+
+        let chunk_ns = crate::rag::chunk_namespace(&namespace);
+        let entries = chunks
+            .iter()
+            .zip(vectors)
+            .map(|(chunk, vector)| {
+                let mut value = crate::vector::vector_to_json(&vector, None);
+                value["text"] = serde_json::Value::String(chunk.text.clone());
+                value["chunk_index"] = chunk.index.into();
+                value["start"] = chunk.start.into();
+                value["end"] = chunk.end.into();
+                value["source"] = serde_json::json!({
+                    "namespace": namespace,
+                    "key": key,
+                    "version": version_id,
+                });
+                (crate::rag::chunk_key(&key, chunk.index), vector, value)
+            })
+            .collect();
+        self.store_embeddings(chunk_ns.clone(), entries).await?;
+
+        for stale in chunks.len()..previous_chunks {
+            self.delete_embed(&chunk_ns, crate::rag::chunk_key(&key, stale))
+                .await?;
+        }
+
+        debug!(namespace = %namespace, key = %key, chunks = chunks.len(), "Document stored");
+        Ok(StoredDocument {
+            namespace,
+            key,
+            version_id,
+            chunks: chunks.len(),
+        })
+    }
+
+    /// Retrieve the document chunks most similar to a query.
+    ///
+    /// Searches chunks of every document stored with
+    /// [`store_document`](Self::store_document). Each result carries its
+    /// source key and version, how many revisions the source has, and whether
+    /// the chunk still reflects the source's current version.
+    pub async fn retrieve(&self, query_text: &str, k: usize) -> DeltaResult<Vec<RetrievedChunk>> {
+        let query = self.embedding_provider().embed(query_text).await?;
+
+        // Other embeddings share the index; widen the search until k chunks
+        // are found or the index is exhausted
+        let mut top_k = k.saturating_mul(4).max(1);
+        let hits = loop {
+            let options = VectorSearchOptions::new()
+                .top_k(top_k)
+                .model_filter(query.model());
+            let results = self.vector_index.search(&query, &options);
+            let exhausted = results.len() < top_k;
+            let hits: Vec<VectorSearchResult> = results
+                .into_iter()
+                .filter(|r| crate::rag::source_namespace(&r.namespace).is_some())
+                .take(k)
+                .collect();
+            if hits.len() >= k || exhausted {
+                break hits;
+            }
+            top_k = top_k.saturating_mul(2);
+        };
+
+        let mut retrieved = Vec::with_capacity(hits.len());
+        for hit in hits {
+            let Ok(chunk) = self.get(&hit.namespace, &hit.key).await else {
+                continue;
+            };
+            let value = chunk.value();
+            let source = &value["source"];
+            let (Some(namespace), Some(source_key), Some(source_version)) = (
+                source["namespace"].as_str(),
+                source["key"].as_str(),
+                source["version"].as_str(),
+            ) else {
+                continue;
+            };
+
+            let revisions = self
+                .history(namespace, source_key)
+                .await
+                .map(|h| h.len())
+                .unwrap_or(0);
+            let is_current = self
+                .get(namespace, source_key)
+                .await
+                .is_ok_and(|current| current.version_id() == source_version);
+
+            retrieved.push(RetrievedChunk {
+                namespace: namespace.to_string(),
+                source_key: source_key.to_string(),
+                chunk_index: value["chunk_index"].as_u64().unwrap_or(0) as usize,
+                text: value["text"].as_str().unwrap_or_default().to_string(),
+                score: hit.score,
+                source_version: source_version.to_string(),
+                stored_at: chunk.timestamp(),
+                revisions,
+                is_current,
+            });
+        }
+
+        debug!(results = retrieved.len(), "Chunk retrieval completed");
+        Ok(retrieved)
+    }
+
+    /// Simplified: Search for content similar to the given text/content.
+    ///
+    /// This generates an embedding from the query content and finds similar items.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - Optional namespace to search (None = all)
+    /// * `query_content` - The content to find similar items to
+    /// * `top_k` - Maximum number of results
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let results = db.find_similar(
+    ///     Some("docs"),
+    ///     json!({"text": "artificial intelligence"}),
+    ///     5
+    /// ).await?;
+    /// ```
+    pub async fn find_similar(
+        &self,
+        namespace: Option<&str>,
+        query_content: impl Serialize,
+        top_k: usize,
     ) -> DeltaResult<Vec<crate::vector::VectorSearchResult>> {
         let query_json = serde_json::to_value(&query_content)?;
         let query_vector = crate::vector::Vector::synthesize(&query_json, 128);
@@ -2553,10 +4488,7 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         let namespace = namespace.into();
         let key = key.into();
 
-        // Remove from index
-        self.vector_index.remove(&namespace, &key);
-
-        // Store null value (mark as deleted)
+        // Store null value (mark as deleted), which drops it from the index
         let versioned = self.put(&namespace, &key, serde_json::Value::Null).await?;
 
         debug!(namespace = %namespace, key = %key, "Vector embedding deleted (index removed)");
@@ -2564,21 +4496,225 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
     }
 
     /// Query with full filter, sort, projection, and aggregation support.
+    ///
+    /// The query stops after its [`timeout`](Query::timeout), or the
+    /// configured [`query_timeout`](ResourceLimits::query_timeout) if that
+    /// is shorter, and is bounded by the tighter of its own
+    /// [`limits`](Query::limits) and the configured
+    /// [`query_limits`](ResourceLimits::query_limits).
     pub async fn query(&self, namespace: &str, query: Query) -> DeltaResult<QueryResult> {
-        let items = self
-            .storage
-            .scan_collection(namespace)
-            .into_iter()
-            .map(|(key, value)| {
-                (
+        self.query_cancellable(namespace, query, CancellationToken::new())
+            .await
+    }
+
+    /// Query, stopping early if `token` is cancelled from another task.
+    ///
+    /// Timeouts and limits apply as for [`query`](Self::query). A stopped
+    /// query fails with `QueryCancelled`, `QueryTimeout` or
+    /// `QueryLimitExceeded`, or returns the records
+    /// matched so far if it [allows partial results](Query::allow_partial).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let token = CancellationToken::new();
+    /// let handle = token.clone();
+    /// tokio::spawn(async move {
+    ///     tokio::time::sleep(Duration::from_secs(1)).await;
+    ///     handle.cancel();
+    /// });
+    /// let result = db.query_cancellable("events", Query::new(), token).await;
+    /// ```
+    #[instrument(name = "query", skip_all, fields(namespace = %namespace, results = Empty))]
+    pub async fn query_cancellable(
+        &self,
+        namespace: &str,
+        mut query: Query,
+        token: CancellationToken,
+    ) -> DeltaResult<QueryResult> {
+        query.limits = query.limits.within(self.config.limits.query_limits);
+        let control = self.query_control(&query, token);
+        if let Some(table) = CatalogTable::from_namespace(namespace) {
+            let now = Utc::now();
+            let items = self
+                .catalog_records(table)
+                .into_iter()
+                .map(|(key, value)| (key, value, now, String::new()));
+            return QueryExecutor::execute_with(&query, items, &control);
+        }
+
+        let pushdown = FieldPushdown::new(&query);
+        let result = if let Some(scan) = self.storage.indexed_scan(namespace, &query) {
+            // A range index picked the page, so only its keys are read
+            let items = scan.keys.into_iter().filter_map(|key| {
+                let value = self.storage.get(namespace, &key).ok()?;
+                Some((
                     key,
-                    value.value().clone(),
+                    pushdown.read(value.value()),
                     value.timestamp(),
                     value.version_id().to_string(),
-                )
+                ))
             });
+            let mut page = query.clone();
+            page.offset = None;
+            let mut result = QueryExecutor::execute_with(&page, items, &control)?;
+            result.total_count = scan.total_count;
+            result
+        } else {
+            let items = self
+                .storage
+                .scan_collection(namespace)
+                .into_iter()
+                .map(|(key, value)| {
+                    (
+                        key,
+                        pushdown.read(value.value()),
+                        value.timestamp(),
+                        value.version_id().to_string(),
+                    )
+                });
+            QueryExecutor::execute_with(&query, items, &control)?
+        };
+        Span::current().record("results", result.total_count);
 
-        QueryExecutor::execute(&query, items)
+        // Query hits count as reads for the heat model
+        let hot = self.hot.read().await;
+        for record in &result.records {
+            hot.record_access(&FullKey::new(namespace, &record.key), &record.version_id);
+        }
+        Ok(result)
+    }
+
+    /// Stop conditions for `query`: `token`, and the shorter of its own
+    /// timeout and the configured one, timed by the runtime's clock.
+    fn query_control(&self, query: &Query, token: CancellationToken) -> QueryControl {
+        let configured = Some(self.config.limits.query_timeout).filter(|t| !t.is_zero());
+        let requested = query.timeout_ms.map(Duration::from_millis);
+        let control = QueryControl::new().with_token(token);
+        match requested.into_iter().chain(configured).min() {
+            Some(timeout) => {
+                let runtime = self.runtime.clone();
+                let start = runtime.now();
+                control.with_deadline(timeout, move || {
+                    runtime.now().duration_since(start.clone()) >= timeout
+                })
+            }
+            None => control,
+        }
+    }
+
+    /// Records of a system catalog namespace, generated from live state.
+    fn catalog_records(&self, table: CatalogTable) -> Vec<(String, serde_json::Value)> {
+        fn to_value(value: &impl Serialize) -> serde_json::Value {
+            serde_json::to_value(value).unwrap_or_default()
+        }
+
+        match table {
+            CatalogTable::Schemas => self
+                .storage
+                .list_namespaces()
+                .into_iter()
+                .map(|namespace| {
+                    let values = self.storage.scan_collection(&namespace);
+                    let schema =
+                        catalog::infer_schema(&namespace, values.iter().map(|(_, v)| v.value()));
+                    (namespace, schema)
+                })
+                .collect(),
+            CatalogTable::Indexes => {
+                use crate::vector::VECTOR_CONFIG_NAMESPACE;
+
+                let stats = self.vector_index.stats();
+                let mut records = vec![(
+                    "vectors".to_string(),
+                    serde_json::json!({
+                        "kind": "vector",
+                        "namespace": null,
+                        "vectors": stats.vectors,
+                        "tombstones": stats.tombstones,
+                        "memory_bytes": stats.memory_bytes,
+                    }),
+                )];
+                for (namespace, config) in self.storage.scan_collection(VECTOR_CONFIG_NAMESPACE) {
+                    if !config.value().is_null() {
+                        records.push((
+                            format!("vectors:{}", namespace),
+                            serde_json::json!({
+                                "kind": "vector_config",
+                                "namespace": namespace,
+                                "config": config.value(),
+                            }),
+                        ));
+                    }
+                }
+                for index in self.storage.range_indexes() {
+                    let info = index.info();
+                    records.push((
+                        format!("range:{}.{}", info.namespace, info.field),
+                        serde_json::json!({
+                            "kind": "range",
+                            "namespace": info.namespace,
+                            "field": info.field,
+                            "entries": info.entries,
+                            "non_numeric": info.non_numeric,
+                            "min": info.min,
+                            "max": info.max,
+                        }),
+                    ));
+                }
+                records
+            }
+            CatalogTable::Views => self
+                .views
+                .list_views()
+                .into_iter()
+                .map(|view| (view.name.clone(), to_value(&view)))
+                .collect(),
+            CatalogTable::Subscriptions => self
+                .subscriptions
+                .list_subscriptions()
+                .into_iter()
+                .map(|info| (info.id.0.to_string(), to_value(&info)))
+                .collect(),
+            CatalogTable::Capabilities => self
+                .auth
+                .storage()
+                .list_all_capabilities()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|capability| (capability.id.clone(), to_value(&capability)))
+                .collect(),
+            CatalogTable::Cluster => {
+                let node_id = self.storage.origin_node().unwrap_or("local").to_string();
+                #[allow(unused_mut)]
+                let mut records = vec![(
+                    node_id.clone(),
+                    serde_json::json!({"node_id": node_id, "local": true, "status": "active"}),
+                )];
+                #[cfg(not(target_arch = "wasm32"))]
+                if let Some(cluster) = &self.cluster {
+                    records[0].1["address"] = serde_json::json!(cluster.bind_addr());
+                    for peer in cluster.peers() {
+                        let mut value = to_value(&peer);
+                        value["local"] = serde_json::json!(false);
+                        records.push((peer.node_id.to_string(), value));
+                    }
+                }
+                records
+            }
+        }
+    }
+
+    /// Temperature of a key, driven by how often it is read.
+    ///
+    /// Each [`get`](Self::get) and query hit heats the key up, and heat
+    /// decays over time. Keys that are never read are
+    /// [`Cold`](TemperatureLevel::Cold), however recently they were written.
+    pub async fn temperature(&self, namespace: &str, key: &str) -> TemperatureLevel {
+        self.hot
+            .read()
+            .await
+            .temperature(&FullKey::new(namespace, key))
     }
 
     /// Check if a key exists.
@@ -2616,904 +4752,3168 @@ impl<R: Runtime> KoruDeltaGeneric<R> {
         Ok(())
     }
 
-    /// List all keys in a namespace.
-    pub async fn list_keys(&self, namespace: &str) -> Vec<String> {
-        self.storage.list_keys(namespace)
-    }
-
-    /// List all namespaces.
-    pub async fn list_namespaces(&self) -> Vec<String> {
-        self.storage.list_namespaces()
+    /// Store a typed record in its namespace, under its key.
+    pub async fn put_typed<T: KoruRecord>(&self, record: &T) -> DeltaResult<VersionedValue> {
+        self.put(T::NAMESPACE, record.key(), record).await
     }
 
-    /// Get database statistics.
-    pub async fn stats(&self) -> DatabaseStats {
-        DatabaseStats {
-            key_count: self.storage.key_count(),
-            total_versions: self.storage.total_version_count(),
-            namespace_count: self.storage.list_namespaces().len(),
+    /// Get a typed record by key.
+    ///
+    /// Fails with `KeyNotFound` if the key was never written or is deleted,
+    /// and with a serialization error if the stored value does not fit `T`.
+    pub async fn get_typed<T: KoruRecord>(&self, key: &str) -> DeltaResult<T> {
+        let versioned = self.get(T::NAMESPACE, key).await?;
+        if versioned.value().is_null() {
+            return Err(crate::error::DeltaError::KeyNotFound {
+                namespace: T::NAMESPACE.to_string(),
+                key: key.to_string(),
+            });
         }
+        Ok(serde_json::from_value(versioned.value().clone())?)
     }
 
-    /// Get auth manager.
-    pub fn auth(&self) -> Arc<IdentityAgent> {
-        Arc::clone(&self.auth)
+    /// Query a record type's namespace, returning typed records.
+    ///
+    /// Takes a [`Query`] or a [`TypedQuery`](crate::record::TypedQuery).
+    /// Projections and aggregations are ignored, since results must
+    /// deserialize as `T`; deleted keys are skipped.
+    pub async fn query_typed<T: KoruRecord>(&self, query: impl Into<Query>) -> DeltaResult<Vec<T>> {
+        let mut query = query.into();
+        query.projection.clear();
+        query.aggregation = None;
+        self.query(T::NAMESPACE, query)
+            .await?
+            .records
+            .into_iter()
+            .filter(|record| !record.value.is_null())
+            .map(|record| serde_json::from_value(record.value).map_err(Into::into))
+            .collect()
     }
 
-    /// Get lifecycle manager for memory consolidation (non-WASM only).
+    /// Merge versions received from a remote replica.
     ///
-    /// The lifecycle manager handles automatic Hot→Warm→Cold→Deep
-    /// transitions based on access patterns and importance scores.
-    #[cfg(not(target_arch = "wasm32"))]
-    pub fn lifecycle(&self) -> &LifecycleAgent {
-        &self.lifecycle
+    /// Versions keep their original IDs and timestamps; a key's current value
+    /// only moves forward (see [`CausalStorage::merge_version`]). New versions
+    /// are written to the WAL when persistence is enabled.
+    ///
+    /// Returns the versions that were new to this database. Nothing is
+    /// merged once shutdown has begun.
+    #[instrument(name = "merge_versions", skip_all, fields(count = versions.len(), merged = Empty))]
+    pub async fn merge_versions(
+        &self,
+        versions: Vec<(FullKey, VersionedValue)>,
+    ) -> Vec<(FullKey, VersionedValue)> {
+        let mut merged = Vec::new();
+        let Ok(_write) = self.writes.begin() else {
+            return merged;
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        let _gate = self.write_gate.read().await;
+
+        for (full_key, versioned) in versions {
+            if !self
+                .storage
+                .merge_version(&full_key.namespace, &full_key.key, versioned.clone())
+            {
+                continue;
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some(ref wal) = self.wal
+                && let Err(e) = wal
+                    .commit(vec![(full_key.clone(), versioned.clone())])
+                    .await
+            {
+                error!(error = %e, "Failed to persist merged version to WAL");
+            }
+
+            // Keep hot memory in step if the merged version is now current
+            let is_current = self
+                .storage
+                .get(&full_key.namespace, &full_key.key)
+                .is_ok_and(|current| current.write_id == versioned.write_id);
+            if is_current {
+                self.hot
+                    .write()
+                    .await
+                    .put(full_key.clone(), versioned.clone());
+            }
+            merged.push((full_key, versioned));
+        }
+
+        Span::current().record("merged", merged.len());
+        merged
     }
 
-    /// Create a workspace.
+    /// Explain which writes produced a key's current value, like `git blame`.
     ///
-    /// Workspaces provide isolated, versioned storage with natural lifecycle.
-    /// Each workspace is independent - data in one doesn't affect others.
+    /// Returns every write in the value's causal history (with author
+    /// identity, timestamp and originating node when known) and, for object
+    /// values, the write that last set each top-level field.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let db = KoruDelta::start().await?;
-    ///
-    /// // General purpose workspace
-    /// let project = db.workspace("project-alpha");
-    /// project.store("config", data, MemoryPattern::Reference).await?;
-    ///
-    /// // AI agent workspace
-    /// let agent = db.workspace("agent-42").ai_context();
-    /// agent.remember_episode("User asked about Python").await?;
-    ///
-    /// // Audit workspace
-    /// let audit = db.workspace("audit-2026");
-    /// audit.store("tx-123", transaction, MemoryPattern::Event).await?;
+    /// let provenance = db.provenance("users", "alice").await?;
+    /// if let Some(write) = provenance.blame("email") {
+    ///     println!("email set by {:?} at {}", write.author, write.timestamp);
+    /// }
     /// ```
-    pub fn workspace(&self, name: impl Into<String>) -> crate::memory::Workspace<R> {
-        crate::memory::Workspace::new(self.clone(), name)
+    pub async fn provenance(&self, namespace: &str, key: &str) -> DeltaResult<Provenance> {
+        self.storage.provenance(namespace, key)
     }
 
-    /// Get storage reference.
-    pub fn storage(&self) -> &Arc<CausalStorage> {
-        &self.storage
+    /// Get the versions a key's current value was derived from.
+    ///
+    /// Returns the causes declared with [`put_with_causes`](Self::put_with_causes),
+    /// each with the key it belongs to.
+    pub async fn causes(
+        &self,
+        namespace: &str,
+        key: &str,
+    ) -> DeltaResult<Vec<(FullKey, VersionedValue)>> {
+        self.storage.causes(namespace, key)
     }
 
-    /// Get distinction engine reference.
-    pub fn engine(&self) -> &Arc<DistinctionEngine> {
-        self.shared_engine.inner()
+    /// Get the versions that declared `write_id` as one of their causes.
+    ///
+    /// The inverse of [`causes`](Self::causes): answers "what was derived
+    /// from this record?".
+    pub async fn dependents(&self, write_id: &str) -> Vec<(FullKey, VersionedValue)> {
+        self.storage.dependents(write_id)
     }
 
-    // =========================================================================
-    // Views API
-    // =========================================================================
-
-    /// Create a materialized view.
-    pub async fn create_view(&self, definition: ViewDefinition) -> DeltaResult<ViewInfo> {
-        // First let the view manager validate and execute the query
-        let info = self.views.create_view(definition.clone())?;
-
-        // Persist the view definition to WAL via normal put (ensures durability)
-        // PerspectiveAgent already stored it in storage, but we need WAL persistence
-        #[cfg(not(target_arch = "wasm32"))]
-        if self.db_path.is_some() {
-            use crate::views::VIEW_NAMESPACE;
-            let def_value = serde_json::to_value(&definition)?;
-            self.put(VIEW_NAMESPACE, &definition.name, def_value)
-                .await?;
-        }
+    /// Extract a genome of the database's causal structure.
+    ///
+    /// The genome records the causal graph's roots and topology (not
+    /// values) and is kept in deep memory under the returned ID. Versions it
+    /// names are pinned against garbage collection. Use
+    /// [`export_genome`](Self::export_genome) to carry it to another node.
+    pub async fn extract_genome(&self) -> String {
+        let graph = self.storage.causal_graph();
+        let deep = self.deep.read().await;
+        let genome = deep.extract_genome(graph, graph.current_epoch() as usize, graph.node_count());
+        info!(
+            genome = %genome.id(),
+            roots = genome.roots.len(),
+            paths = genome.topology.paths.len(),
+            "Genome extracted"
+        );
+        genome.id()
+    }
 
-        Ok(info)
+    /// Get a stored genome.
+    pub async fn genome(&self, genome_id: &str) -> Option<Genome> {
+        self.deep.read().await.get_genome(genome_id)
     }
 
-    /// List all views.
-    pub async fn list_views(&self) -> Vec<ViewInfo> {
-        self.views.list_views()
+    /// Serialize a stored genome for transfer to another node.
+    pub async fn export_genome(&self, genome_id: &str) -> DeltaResult<Vec<u8>> {
+        let genome =
+            self.genome(genome_id)
+                .await
+                .ok_or_else(|| crate::error::DeltaError::InvalidData {
+                    reason: format!("Unknown genome: {}", genome_id),
+                })?;
+        Ok(EssenceAgent::serialize_genome(&genome)?)
     }
 
-    /// Refresh a view.
-    pub async fn refresh_view(&self, name: &str) -> DeltaResult<ViewInfo> {
-        self.views.refresh_view(name)
+    /// Store a genome exported from another node, returning its ID.
+    pub async fn import_genome(&self, bytes: &[u8]) -> DeltaResult<String> {
+        let genome = EssenceAgent::deserialize_genome(bytes)?;
+        let id = genome.id();
+        self.deep.read().await.store_genome(&id, genome);
+        Ok(id)
     }
 
-    /// Query a view.
-    pub async fn query_view(&self, name: &str) -> DeltaResult<QueryResult> {
-        self.views.query_view(name)
+    /// Regrow the causal structure recorded in a stored genome.
+    ///
+    /// Intended for disaster recovery on a fresh node: import the genome,
+    /// then regenerate from it. The genome's roots and causal edges are
+    /// added to this database's causal graph (edges already present are
+    /// left alone); values are not part of a genome and must be synced from
+    /// peers or restored from a backup.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // On the old node
+    /// let id = old.extract_genome().await;
+    /// let bytes = old.export_genome(&id).await?;
+    ///
+    /// // On the fresh node
+    /// let id = fresh.import_genome(&bytes).await?;
+    /// let result = fresh.regenerate_from_genome(&id).await?;
+    /// println!("restored {} distinctions", result.distinctions_restored);
+    /// ```
+    pub async fn regenerate_from_genome(&self, genome_id: &str) -> DeltaResult<ExpressionResult> {
+        let deep = self.deep.read().await;
+        let genome =
+            deep.get_genome(genome_id)
+                .ok_or_else(|| crate::error::DeltaError::InvalidData {
+                    reason: format!("Unknown genome: {}", genome_id),
+                })?;
+        let result = deep.express_genome_into(&genome, self.storage.causal_graph());
+        info!(
+            genome = genome_id,
+            restored = result.distinctions_restored,
+            "Regenerated causal structure from genome"
+        );
+        Ok(result)
     }
 
-    /// Delete a materialized view.
-    pub async fn delete_view(&self, name: &str) -> DeltaResult<()> {
-        self.views.delete_view(name)?;
+    /// Import SQLite tables into namespaces.
+    ///
+    /// `tables` maps table names to namespaces. Each row becomes one JSON
+    /// object keyed by the row's primary key (see [`crate::sqlite`]). Tables
+    /// are read in full before anything is written, so a missing table
+    /// fails the import without partial writes.
+    #[cfg(all(not(target_arch = "wasm32"), feature = "sqlite"))]
+    pub async fn import_sqlite(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        tables: &[(&str, &str)],
+    ) -> DeltaResult<Vec<crate::sqlite::TableTransfer>> {
+        let contents = {
+            let conn = crate::sqlite::open(path.as_ref())?;
+            tables
+                .iter()
+                .map(|(table, _)| crate::sqlite::read_table(&conn, table))
+                .collect::<DeltaResult<Vec<_>>>()?
+        };
 
-        // Persist the deletion to WAL
-        #[cfg(not(target_arch = "wasm32"))]
-        if self.db_path.is_some() {
-            use crate::views::VIEW_NAMESPACE;
-            self.put(VIEW_NAMESPACE, name, serde_json::Value::Null)
-                .await?;
+        let mut transfers = Vec::with_capacity(tables.len());
+        for ((table, namespace), rows) in tables.iter().zip(contents) {
+            let count = rows.len();
+            self.put_batch_in_ns(*namespace, rows).await?;
+            info!(table, namespace, rows = count, "Imported SQLite table");
+            transfers.push(crate::sqlite::TableTransfer {
+                table: table.to_string(),
+                namespace: namespace.to_string(),
+                rows: count,
+            });
         }
-
-        Ok(())
-    }
-
-    /// Get view manager.
-    pub fn view_manager(&self) -> &Arc<PerspectiveAgent> {
-        &self.views
+        Ok(transfers)
     }
 
-    // =========================================================================
-    // Subscriptions API (non-WASM only)
-    // =========================================================================
-
-    /// Subscribe to changes.
-    #[cfg(not(target_arch = "wasm32"))]
-    pub async fn subscribe(
+    /// Export namespaces to SQLite tables.
+    ///
+    /// `namespaces` maps namespaces to table names; tables are created if
+    /// needed and rows for exported keys are replaced. See
+    /// [`ExportMode`](crate::sqlite::ExportMode) for the table layouts.
+    #[cfg(all(not(target_arch = "wasm32"), feature = "sqlite"))]
+    pub async fn export_sqlite(
         &self,
-        subscription: Subscription,
-    ) -> (
-        SubscriptionId,
-        tokio::sync::broadcast::Receiver<ChangeEvent>,
-    ) {
-        self.subscriptions.subscribe(subscription)
-    }
+        path: impl AsRef<std::path::Path>,
+        namespaces: &[(&str, &str)],
+        mode: crate::sqlite::ExportMode,
+    ) -> DeltaResult<Vec<crate::sqlite::TableTransfer>> {
+        use crate::sqlite::{ExportMode, ExportRow, TableTransfer};
+
+        let mut conn = crate::sqlite::open(path.as_ref())?;
+        let mut transfers = Vec::with_capacity(namespaces.len());
+        for (namespace, table) in namespaces {
+            let mut rows = Vec::new();
+            for key in self.storage.list_keys(namespace) {
+                match mode {
+                    ExportMode::Heads => {
+                        let head = self.storage.get(*namespace, &key)?;
+                        if head.value().is_null() {
+                            continue;
+                        }
+                        rows.push(ExportRow {
+                            key,
+                            seq: 0,
+                            value: Some(head.value().clone()),
+                            version_id: head.version_id().to_string(),
+                            timestamp: head.timestamp(),
+                        });
+                    }
+                    ExportMode::History => {
+                        let history = self.storage.history(*namespace, &key)?;
+                        rows.extend(history.into_iter().enumerate().map(|(seq, entry)| {
+                            ExportRow {
+                                key: key.clone(),
+                                seq,
+                                value: Some(entry.value).filter(|v| !v.is_null()),
+                                version_id: entry.version_id,
+                                timestamp: entry.timestamp,
+                            }
+                        }));
+                    }
+                }
+            }
 
-    /// Unsubscribe from changes.
-    #[cfg(not(target_arch = "wasm32"))]
-    pub async fn unsubscribe(&self, id: SubscriptionId) -> DeltaResult<()> {
-        self.subscriptions.unsubscribe(id)
+            crate::sqlite::write_table(&mut conn, table, &rows, mode)?;
+            info!(
+                namespace,
+                table,
+                rows = rows.len(),
+                "Exported namespace to SQLite"
+            );
+            transfers.push(TableTransfer {
+                table: table.to_string(),
+                namespace: namespace.to_string(),
+                rows: rows.len(),
+            });
+        }
+        Ok(transfers)
     }
 
-    /// List all subscriptions.
-    #[cfg(not(target_arch = "wasm32"))]
-    pub async fn list_subscriptions(&self) -> Vec<crate::subscriptions::SubscriptionInfo> {
-        self.subscriptions.list_subscriptions()
+    /// List the archive's sealed epochs, oldest first.
+    ///
+    /// Each epoch reports the time range it covered and how many
+    /// distinctions it holds. The open epoch is not listed.
+    pub async fn epochs(&self) -> Vec<ArchiveEpoch> {
+        self.cold.read().await.sealed_epochs()
     }
 
-    /// Get subscription manager.
-    #[cfg(not(target_arch = "wasm32"))]
-    pub fn subscription_manager(&self) -> &Arc<SubscriptionAgent> {
-        &self.subscriptions
+    /// Query the archived (Cold) versions held by a sealed epoch.
+    ///
+    /// Records are keyed `namespace:key`, since an epoch spans namespaces.
+    /// Browsing does not move anything; use
+    /// [`rehydrate_epoch`](Self::rehydrate_epoch) to bring versions back.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let epoch = db.epochs().await.last().unwrap().number;
+    /// let orders = db
+    ///     .epoch_contents(epoch, Query::new().filter(Filter::eq("status", "shipped")))
+    ///     .await?;
+    /// ```
+    pub async fn epoch_contents(
+        &self,
+        epoch_number: usize,
+        query: Query,
+    ) -> DeltaResult<QueryResult> {
+        let entries = self.sealed_epoch_entries(epoch_number).await?;
+        let items = entries.into_iter().filter_map(|(id, key)| {
+            let versioned = self.storage.version(&id)?;
+            Some((
+                key.to_canonical_string(),
+                versioned.value().clone(),
+                versioned.timestamp(),
+                versioned.version_id().to_string(),
+            ))
+        });
+        QueryExecutor::execute(&query, items)
     }
 
-    /// Store a value and notify subscribers (non-WASM only).
-    #[cfg(not(target_arch = "wasm32"))]
-    pub async fn put_notify<T: Serialize>(
+    /// Move archived versions of `keys` from a sealed epoch into Warm.
+    ///
+    /// Returns how many versions were rehydrated. Keys the epoch does not
+    /// hold are ignored.
+    pub async fn rehydrate_epoch(
         &self,
-        namespace: impl Into<String>,
-        key: impl Into<String>,
-        value: T,
-    ) -> DeltaResult<VersionedValue> {
-        let namespace = namespace.into();
-        let key = key.into();
+        epoch_number: usize,
+        keys: &[FullKey],
+    ) -> DeltaResult<usize> {
+        let entries = self.sealed_epoch_entries(epoch_number).await?;
+        let cold = self.cold.write().await;
+        let warm = self.warm.write().await;
+        let mut rehydrated = 0;
+        for (id, key) in entries.into_iter().filter(|(_, key)| keys.contains(key)) {
+            let Some(versioned) = self.storage.version(&id) else {
+                continue;
+            };
+            if cold.remove(&id) {
+                warm.put(key, versioned);
+                rehydrated += 1;
+            }
+        }
+        debug!(
+            epoch = epoch_number,
+            rehydrated, "Rehydrated archived versions"
+        );
+        Ok(rehydrated)
+    }
 
-        // Get previous value and check if key exists before put
-        let (exists, previous_value) = match self.get(&namespace, &key).await {
-            Ok(v) => (true, Some(v.value().clone())),
-            Err(_) => (false, None),
+    /// Entries of a sealed epoch, or `InvalidData` if there is none.
+    async fn sealed_epoch_entries(
+        &self,
+        epoch_number: usize,
+    ) -> DeltaResult<Vec<(String, FullKey)>> {
+        let cold = self.cold.read().await;
+        let entries = if epoch_number < cold.current_epoch() {
+            cold.epoch_entries(epoch_number)
+        } else {
+            None
         };
+        entries.ok_or_else(|| crate::error::DeltaError::InvalidData {
+            reason: format!("Unknown sealed epoch: {}", epoch_number),
+        })
+    }
 
-        // Store the value
-        let versioned = self.put(&namespace, &key, value).await?;
+    /// Reclaim versions that nothing live can reach.
+    ///
+    /// Runs a mark-and-sweep pass: every live key's head and full history,
+    /// the declared causes of anything kept, and distinctions named by stored
+    /// genomes are marked, and all other versions are dropped from memory,
+    /// along with the engine distinctions of their values. A deleted key
+    /// keeps only its deletion and a tombstone, so its history is reclaimed
+    /// but peers can't bring it back (see
+    /// [`CausalStorage::collect_garbage`]). With persistence enabled,
+    /// the WAL is then compacted and orphaned value files deleted, so the
+    /// collected versions do not come back on restart.
+    ///
+    /// Writes wait while collection runs.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let report = db.collect_garbage().await?;
+    /// println!("freed {} versions, {} bytes", report.versions_collected, report.bytes_reclaimed);
+    /// ```
+    pub async fn collect_garbage(&self) -> DeltaResult<GcReport> {
+        #[cfg(not(target_arch = "wasm32"))]
+        let _gate = self.write_gate.write().await;
 
-        // Determine change type
-        let change_type = if exists {
-            crate::subscriptions::ChangeType::Update
-        } else {
-            crate::subscriptions::ChangeType::Insert
-        };
+        // Writes already queued for the WAL must land before it is rewritten
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(ref wal) = self.wal {
+            wal.sync().await?;
+        }
 
-        // Notify subscribers
-        let event = ChangeEvent {
-            change_type,
-            collection: namespace.clone(),
-            key: key.clone(),
-            value: Some(versioned.value().clone()),
-            previous_value,
-            timestamp: Utc::now(),
-            version_id: Some(versioned.version_id().to_string()),
-            previous_version_id: versioned.previous_version().map(|s| s.to_string()),
-        };
-        self.subscriptions.notify(event);
+        let pinned = self.deep.read().await.pinned_distinctions();
+        let report = self.storage.collect_garbage(&pinned);
 
-        // Auto-refresh views for this collection
-        let _ = self.views.refresh_for_collection(&namespace);
+        #[cfg(not(target_arch = "wasm32"))]
+        let report = {
+            let mut report = report;
+            if let Some(ref db_path) = self.db_path {
+                crate::persistence::compact(db_path, &self.storage, &mut report).await?;
+            }
+            report
+        };
 
-        Ok(versioned)
+        info!(
+            versions = report.versions_collected,
+            values = report.values_collected,
+            distinctions = report.distinctions_collected,
+            bytes = report.bytes_reclaimed,
+            "Garbage collection completed"
+        );
+        Ok(report)
     }
 
-    // =========================================================================
-    // Lifecycle
-    // =========================================================================
+    /// Fold runs of a key's old versions into summary versions.
+    ///
+    /// Versions the policy allows are replaced, run by run, with the newest
+    /// version of each run carrying aggregate metadata for the rest (see
+    /// [`CausalStorage::compact_history`]). The key's first and current
+    /// versions, versions other writes declared as causes and pinned
+    /// distinctions are always kept. With persistence enabled, the WAL is
+    /// compacted too so the folded versions do not come back on restart.
+    ///
+    /// Writes wait while compaction runs.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let policy = CompactionPolicy::new()
+    ///     .keep_recent(5)
+    ///     .older_than(chrono::Duration::days(30));
+    /// let report = db.compact_history("metrics", "cpu", policy).await?;
+    /// println!("folded {} versions", report.versions_removed);
+    /// ```
+    pub async fn compact_history(
+        &self,
+        namespace: &str,
+        key: &str,
+        policy: CompactionPolicy,
+    ) -> DeltaResult<HistoryCompactionReport> {
+        #[cfg(not(target_arch = "wasm32"))]
+        let _gate = self.write_gate.write().await;
 
-    /// Shutdown the database.
-    pub async fn shutdown(self) -> DeltaResult<()> {
-        info!("Shutting down KoruDelta");
+        // Writes already queued for the WAL must land before it is rewritten
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(ref wal) = self.wal {
+            wal.sync().await?;
+        }
 
-        let _ = self.shutdown_tx.send(true);
-        trace!("Shutdown signal sent to background processes");
+        let pinned = self.deep.read().await.pinned_distinctions();
+        let report = self
+            .storage
+            .compact_history(namespace, key, &policy, &pinned)?;
 
-        // Release database lock
         #[cfg(not(target_arch = "wasm32"))]
-        if let Some(ref db_path) = self.db_path {
-            use crate::persistence;
-            if let Err(e) = persistence::release_lock(db_path).await {
-                error!(error = %e, "Failed to release database lock");
-            } else {
-                trace!("Database lock released");
+        let report = {
+            let mut report = report;
+            if let Some(ref db_path) = self.db_path
+                && report.versions_removed > 0
+            {
+                let mut gc = GcReport::default();
+                crate::persistence::compact(db_path, &self.storage, &mut gc).await?;
+                report.wal_entries_removed = gc.wal_entries_removed;
+                report.bytes_reclaimed = gc.bytes_reclaimed;
             }
-        }
+            report
+        };
 
-        // TODO: Wait for background processes to complete
-        info!("KoruDelta shutdown complete");
-        Ok(())
+        info!(
+            namespace,
+            key,
+            runs = report.runs_compacted,
+            versions = report.versions_removed,
+            "History compaction completed"
+        );
+        Ok(report)
     }
 
-    // =========================================================================
-    // LCA (Local Causal Agent) Operations
-    // =========================================================================
-
-    /// Perform a storage action via causal synthesis.
+    /// Export the causal graph around a key's current version.
     ///
-    /// This is the LCA way: ΔNew = ΔLocal_Root ⊕ ΔAction
+    /// Renders ancestors and descendants within `depth` hops of the head as
+    /// Graphviz DOT or GraphML, with nodes labelled by `namespace/key` and
+    /// version ID.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let action = StorageAction::Store {
-    ///     namespace: "users".to_string(),
-    ///     key: "alice".to_string(),
-    ///     value_json: json!({"name": "Alice"}),
-    /// };
-    /// let new_root = db.synthesize_storage_action(action).await?;
+    /// use koru_delta::causal_graph::GraphFormat;
+    ///
+    /// let dot = db.export_lineage("users", "alice", 10, GraphFormat::Dot).await?;
+    /// std::fs::write("alice.dot", dot)?; // dot -Tsvg alice.dot > alice.svg
     /// ```
-    pub async fn synthesize_storage_action(
-        &mut self,
-        action: StorageAction,
-    ) -> DeltaResult<Distinction> {
-        // Validate the action
-        action
-            .validate()
-            .map_err(|e| crate::error::DeltaError::InvalidData { reason: e })?;
-
-        // Synthesize: ΔNew = ΔLocal_Root ⊕ ΔAction
-        let action_distinction = action.to_canonical_structure(self.field.engine());
-        let new_root = self.field.synthesize(&self.local_root, &action_distinction);
-
-        // Execute the action (this creates the causal effect)
-        self.execute_storage_action(&action).await?;
+    pub async fn export_lineage(
+        &self,
+        namespace: &str,
+        key: &str,
+        depth: usize,
+        format: GraphFormat,
+    ) -> DeltaResult<String> {
+        self.storage.export_lineage(namespace, key, depth, format)
+    }
 
-        // Update local root to the new synthesis
-        self.local_root = new_root.clone();
+    /// List all keys in a namespace.
+    pub async fn list_keys(&self, namespace: &str) -> Vec<String> {
+        self.storage.list_keys(namespace)
+    }
 
-        Ok(new_root)
+    /// List all namespaces.
+    pub async fn list_namespaces(&self) -> Vec<String> {
+        self.storage.list_namespaces()
     }
 
-    /// Execute a storage action (the causal effect).
+    /// Get database statistics.
     ///
-    /// This performs the actual storage operation based on the action type.
-    async fn execute_storage_action(&self, action: &StorageAction) -> DeltaResult<()> {
-        match action {
-            StorageAction::Store {
-                namespace,
-                key,
-                value_json,
-            } => {
-                // Store via the existing put mechanism
-                let _ = self
-                    .put(namespace.clone(), key.clone(), value_json.clone())
-                    .await?;
-            }
-            StorageAction::Retrieve { namespace, key } => {
-                // Retrieve is handled by get, but we don't need the value here
-                let _ = self.get(namespace.clone(), key.clone()).await?;
-            }
-            StorageAction::History { namespace, key } => {
-                let _ = self.history(namespace, key).await?;
-            }
-            StorageAction::Query { .. } => {
-                // Query all collections
-                let namespaces = self.storage.list_namespaces();
-                for ns in namespaces {
-                    self.query(&ns, Query::new()).await?;
-                }
-            }
-            StorageAction::Delete { namespace, key } => {
-                self.delete(namespace, key).await?;
-            }
+    /// Counting versions per namespace walks every key's history, so this is
+    /// meant for dashboards and diagnostics rather than hot paths.
+    pub async fn stats(&self) -> DatabaseStats {
+        let mut namespaces: BTreeMap<String, NamespaceStats> = BTreeMap::new();
+        for (full_key, _) in self.storage.scan_all() {
+            namespaces.entry(full_key.namespace).or_default().keys += 1;
+        }
+        for (full_key, _) in self.storage.all_versions() {
+            namespaces.entry(full_key.namespace).or_default().versions += 1;
         }
-        Ok(())
-    }
 
-    /// Get the current local root distinction.
-    ///
-    /// This is the agent's causal perspective.
-    pub fn local_root(&self) -> &Distinction {
-        &self.local_root
+        let hot = self.hot.read().await.stats();
+        let warm = self.warm.read().await.stats();
+        let cold = self.cold.read().await.stats();
+        let deep = self.deep.read().await.stats();
+        let vectors = self.vector_index.stats();
+
+        DatabaseStats {
+            key_count: self.storage.key_count(),
+            total_versions: self.storage.total_version_count(),
+            namespace_count: namespaces.len(),
+            namespaces,
+            tiers: TierStats {
+                hot_entries: hot.current_size,
+                hot_capacity: hot.capacity,
+                warm_entries: warm.current_size,
+                warm_capacity: warm.capacity,
+                cold_epochs: cold.epoch_count,
+                cold_distinctions: cold.total_distinctions,
+                deep_genomes: deep.genome_count,
+            },
+            indexes: IndexStats {
+                vectors: vectors.vectors,
+                vector_tombstones: vectors.tombstones,
+                vector_memory_bytes: vectors.memory_bytes,
+                views: self.views.view_count(),
+            },
+            subscriptions: self.subscriptions.subscription_count(),
+            field: self.shared_engine.stats(),
+        }
     }
 
-    /// Get the shared field engine.
-    pub fn shared_engine(&self) -> &SharedEngine {
-        &self.shared_engine
+    /// Database statistics as JSON, for dashboards and monitoring.
+    pub async fn stats_json(&self) -> serde_json::Value {
+        serde_json::to_value(self.stats().await).unwrap_or_default()
     }
 
-    /// Get the field handle for synthesis operations.
-    pub fn field(&self) -> &FieldHandle {
-        &self.field
+    /// Get auth manager.
+    pub fn auth(&self) -> Arc<IdentityAgent> {
+        Arc::clone(&self.auth)
     }
-}
 
-// ============================================================================
-// Local Causal Agent Implementation
-// ============================================================================
+    /// Act as the identity behind an authenticated session.
+    ///
+    /// The returned handle authorizes every read and write against the
+    /// identity's stored capabilities. Fails with `Unauthorized` if the
+    /// session is unknown, expired, or revoked.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let alice = db.as_identity(&session.session_id)?;
+    /// alice.put("notes", "todo", json!("ship it")).await?; // needs Write on notes:todo
+    /// ```
+    pub fn as_identity(&self, session_id: &str) -> DeltaResult<AuthenticatedDelta<R>> {
+        let session = self.auth.validate_session(session_id).map_err(|e| {
+            crate::error::DeltaError::Unauthorized {
+                reason: format!("invalid session: {}", e),
+            }
+        })?;
+        Ok(AuthenticatedDelta::new(
+            self.clone(),
+            session.session_id,
+            session.identity_key,
+        ))
+    }
+
+    /// Register a tenant, or change the quota of an existing one.
+    ///
+    /// The registration is stored in [`TENANT_NAMESPACE`], so it survives
+    /// restarts. Returns a handle confined to the tenant's namespaces; see
+    /// [`Tenant`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let acme = db.create_tenant("acme", TenantQuota { max_keys: 10_000, ..Default::default() }).await?;
+    /// acme.put("users", "alice", json!({"plan": "pro"})).await?; // stored in acme/users
+    /// ```
+    pub async fn create_tenant(&self, name: &str, quota: TenantQuota) -> DeltaResult<Tenant<R>> {
+        validate_tenant_name(name)?;
+        let tenant = Tenant::new(
+            self.clone(),
+            name.to_string(),
+            self.tenants.state(name, quota),
+        );
+        tenant.set_quota(quota).await?;
+        debug!(tenant = %name, max_keys = quota.max_keys, max_bytes = quota.max_bytes, "Tenant registered");
+        Ok(tenant)
+    }
+
+    /// Open a registered tenant.
+    ///
+    /// Fails with `KeyNotFound` if the tenant was never created with
+    /// [`create_tenant`](Self::create_tenant).
+    pub async fn tenant(&self, name: &str) -> DeltaResult<Tenant<R>> {
+        validate_tenant_name(name)?;
+        let versioned = self
+            .storage
+            .get(TENANT_NAMESPACE, name)
+            .ok()
+            .filter(|versioned| !versioned.value().is_null())
+            .ok_or_else(|| crate::error::DeltaError::KeyNotFound {
+                namespace: TENANT_NAMESPACE.to_string(),
+                key: name.to_string(),
+            })?;
+        let quota: TenantQuota = serde_json::from_value(versioned.value().clone())?;
+        Ok(Tenant::new(
+            self.clone(),
+            name.to_string(),
+            self.tenants.state(name, quota),
+        ))
+    }
+
+    /// Names of the registered tenants, sorted.
+    pub async fn list_tenants(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .storage
+            .scan_collection(TENANT_NAMESPACE)
+            .into_iter()
+            .filter(|(_, versioned)| !versioned.value().is_null())
+            .map(|(name, _)| name)
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Encrypt a namespace for a set of identities.
+    ///
+    /// From now on every value written to the namespace is sealed to each
+    /// recipient's public key before it reaches storage, so persistence and
+    /// sync only ever carry ciphertext. Existing values are left as they are.
+    /// Plain reads return the sealed form; read through
+    /// [`as_identity`](Self::as_identity) with the identity's secret key to
+    /// decrypt.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// db.encrypt_namespace("hr", NamespaceEncryption::new([alice.public_key.clone()])).await?;
+    /// db.put("hr", "salary", json!(100_000)).await?; // stored sealed
+    ///
+    /// let alice_db = db.as_identity(&session_id)?.with_secret_key(&alice_secret)?;
+    /// let salary = alice_db.get("hr", "salary").await?; // decrypted
+    /// ```
+    pub async fn encrypt_namespace(
+        &self,
+        namespace: impl Into<String>,
+        encryption: NamespaceEncryption,
+    ) -> DeltaResult<()> {
+        let namespace = namespace.into();
+        encryption
+            .validate()
+            .map_err(|e| crate::error::DeltaError::InvalidData {
+                reason: e.to_string(),
+            })?;
+
+        let value = serde_json::to_value(&encryption)?;
+        self.put(ENCRYPTION_CONFIG_NAMESPACE, &namespace, value)
+            .await?;
+        debug!(namespace = %namespace, recipients = encryption.recipients.len(), "Namespace encrypted");
+        Ok(())
+    }
+
+    /// Get the encryption settings of a namespace, if it is encrypted.
+    ///
+    /// Settings are parsed once per version and cached, since every write
+    /// to the namespace needs them.
+    pub fn namespace_encryption(&self, namespace: &str) -> Option<NamespaceEncryption> {
+        let Ok(versioned) = self.storage.get(ENCRYPTION_CONFIG_NAMESPACE, namespace) else {
+            self.encryption_configs.remove(namespace);
+            return None;
+        };
+        if let Some(cached) = self.encryption_configs.get(namespace)
+            && cached.0 == versioned.write_id
+        {
+            return cached.1.clone();
+        }
+
+        let encryption: Option<NamespaceEncryption> =
+            serde_json::from_value(versioned.value().clone()).ok();
+        self.encryption_configs.insert(
+            namespace.to_string(),
+            (versioned.write_id, encryption.clone()),
+        );
+        encryption
+    }
+
+    /// Seal a value to the namespace's recipients if the namespace is encrypted.
+    ///
+    /// Values that are already sealed (e.g. by a client) and deletion
+    /// tombstones pass through unchanged.
+    fn seal_for_namespace(
+        &self,
+        namespace: &str,
+        key: &str,
+        value: serde_json::Value,
+    ) -> DeltaResult<serde_json::Value> {
+        if value.is_null() || crate::auth::is_sealed(&value) {
+            return Ok(value);
+        }
+        let Some(encryption) = self.namespace_encryption(namespace) else {
+            return Ok(value);
+        };
+
+        let sealed =
+            SealedValue::seal(&value, namespace, key, &encryption.recipients).map_err(|e| {
+                crate::error::DeltaError::InvalidData {
+                    reason: e.to_string(),
+                }
+            })?;
+        Ok(sealed.to_json())
+    }
+
+    /// Get lifecycle manager for memory consolidation (non-WASM only).
+    ///
+    /// The lifecycle manager handles automatic Hot→Warm→Cold→Deep
+    /// transitions based on access patterns and importance scores.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn lifecycle(&self) -> &LifecycleAgent {
+        &self.lifecycle
+    }
+
+    /// Summarize reads and writes in a namespace (non-WASM only).
+    ///
+    /// Counts come from the lifecycle access tracker and cover this process
+    /// only, since it started. `hot_keys` lists the busiest keys first, to
+    /// find hotspots without external profiling.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let stats = db.access_stats("users").await;
+    /// for key in &stats.hot_keys {
+    ///     println!("{}: {} reads, {} writes", key.key, key.reads, key.writes);
+    /// }
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn access_stats(&self, namespace: &str) -> NamespaceAccessStats {
+        self.lifecycle
+            .access_stats(namespace, ACCESS_STATS_HOT_KEYS)
+            .await
+    }
+
+    /// Reads and writes of one key since the process started (non-WASM
+    /// only). `None` if the key hasn't been accessed.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn key_access_stats(&self, namespace: &str, key: &str) -> Option<KeyAccessStats> {
+        self.lifecycle
+            .key_access_stats(&FullKey::new(namespace, key))
+            .await
+    }
+
+    /// Create a workspace.
+    ///
+    /// Workspaces provide isolated, versioned storage with natural lifecycle.
+    /// Each workspace is independent - data in one doesn't affect others.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let db = KoruDelta::start().await?;
+    ///
+    /// // General purpose workspace
+    /// let project = db.workspace("project-alpha");
+    /// project.store("config", data, MemoryPattern::Reference).await?;
+    ///
+    /// // AI agent workspace
+    /// let agent = db.workspace("agent-42").ai_context();
+    /// agent.remember_episode("User asked about Python").await?;
+    ///
+    /// // Audit workspace
+    /// let audit = db.workspace("audit-2026");
+    /// audit.store("tx-123", transaction, MemoryPattern::Event).await?;
+    /// ```
+    pub fn workspace(&self, name: impl Into<String>) -> crate::memory::Workspace<R> {
+        crate::memory::Workspace::new(self.clone(), name)
+    }
+
+    /// Get storage reference.
+    pub fn storage(&self) -> &Arc<CausalStorage> {
+        &self.storage
+    }
+
+    /// Get distinction engine reference.
+    pub fn engine(&self) -> &Arc<DistinctionEngine> {
+        self.shared_engine.inner()
+    }
+
+    // =========================================================================
+    // Views API
+    // =========================================================================
+
+    /// Create a materialized view.
+    pub async fn create_view(&self, definition: ViewDefinition) -> DeltaResult<ViewInfo> {
+        // First let the view manager validate and execute the query
+        let info = self.views.create_view(definition.clone())?;
+
+        // Persist the view definition to WAL via normal put (ensures durability)
+        // PerspectiveAgent already stored it in storage, but we need WAL persistence
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.db_path.is_some() {
+            use crate::views::VIEW_NAMESPACE;
+            let def_value = serde_json::to_value(&definition)?;
+            self.put(VIEW_NAMESPACE, &definition.name, def_value)
+                .await?;
+        }
+
+        Ok(info)
+    }
+
+    /// List all views.
+    pub async fn list_views(&self) -> Vec<ViewInfo> {
+        self.views.list_views()
+    }
+
+    /// Refresh a view.
+    pub async fn refresh_view(&self, name: &str) -> DeltaResult<ViewInfo> {
+        self.views.refresh_view(name)
+    }
+
+    /// Query a view.
+    pub async fn query_view(&self, name: &str) -> DeltaResult<QueryResult> {
+        self.views.query_view(name)
+    }
+
+    /// Delete a materialized view.
+    pub async fn delete_view(&self, name: &str) -> DeltaResult<()> {
+        self.views.delete_view(name)?;
+
+        // Persist the deletion to WAL
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.db_path.is_some() {
+            use crate::views::VIEW_NAMESPACE;
+            self.put(VIEW_NAMESPACE, name, serde_json::Value::Null)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Get view manager.
+    pub fn view_manager(&self) -> &Arc<PerspectiveAgent> {
+        &self.views
+    }
+
+    // =========================================================================
+    // Subscriptions API
+    // =========================================================================
+
+    /// Subscribe to changes.
+    pub async fn subscribe(
+        &self,
+        subscription: Subscription,
+    ) -> (
+        SubscriptionId,
+        crate::runtime::sync::broadcast::Receiver<ChangeEvent>,
+    ) {
+        self.subscriptions.subscribe(subscription)
+    }
+
+    /// Unsubscribe from changes.
+    pub async fn unsubscribe(&self, id: SubscriptionId) -> DeltaResult<()> {
+        self.subscriptions.unsubscribe(id)
+    }
+
+    /// List all subscriptions.
+    pub async fn list_subscriptions(&self) -> Vec<crate::subscriptions::SubscriptionInfo> {
+        self.subscriptions.list_subscriptions()
+    }
+
+    /// Get subscription manager.
+    pub fn subscription_manager(&self) -> &Arc<SubscriptionAgent> {
+        &self.subscriptions
+    }
+
+    /// Store a value and notify subscribers.
+    pub async fn put_notify<T: Serialize>(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        value: T,
+    ) -> DeltaResult<VersionedValue> {
+        let namespace = namespace.into();
+        let key = key.into();
+
+        // Publish in the order concurrent writers to the key store
+        #[cfg(not(target_arch = "wasm32"))]
+        let _order = self.subscriptions.order_key(&namespace, &key).await;
+
+        // Get previous value and check if key exists before put
+        let (exists, previous_value) = match self.get(&namespace, &key).await {
+            Ok(v) => (true, Some(v.value().clone())),
+            Err(_) => (false, None),
+        };
+
+        // Store the value
+        let versioned = self.put(&namespace, &key, value).await?;
+
+        // Determine change type
+        let change_type = if exists {
+            crate::subscriptions::ChangeType::Update
+        } else {
+            crate::subscriptions::ChangeType::Insert
+        };
+
+        // Notify subscribers
+        let event = ChangeEvent {
+            change_type,
+            collection: namespace.clone(),
+            key: key.clone(),
+            value: Some(versioned.value().clone()),
+            previous_value,
+            timestamp: versioned.timestamp(),
+            version_id: Some(versioned.version_id().to_string()),
+            previous_version_id: versioned.previous_version().map(|s| s.to_string()),
+            sequence: 0,
+        };
+        self.subscriptions.notify(event);
+
+        // Auto-refresh views for this collection
+        let _ = self.views.refresh_for_collection(&namespace);
+
+        Ok(versioned)
+    }
+
+    /// Delete a key and notify subscribers.
+    ///
+    /// Returns the tombstone version. Subscribers only hear about keys that
+    /// existed.
+    pub async fn delete_notify(&self, namespace: &str, key: &str) -> DeltaResult<VersionedValue> {
+        #[cfg(not(target_arch = "wasm32"))]
+        let _order = self.subscriptions.order_key(namespace, key).await;
+        let previous = self.get(namespace, key).await.ok();
+
+        let tombstone = self.put(namespace, key, serde_json::Value::Null).await?;
+
+        if let Some(previous) = previous.filter(|p| !p.value().is_null()) {
+            let mut event = ChangeEvent::delete(namespace, key, &previous);
+            event.timestamp = tombstone.timestamp();
+            event.version_id = Some(tombstone.version_id().to_string());
+            self.subscriptions.notify(event);
+        }
+
+        let _ = self.views.refresh_for_collection(namespace);
+
+        Ok(tombstone)
+    }
+
+    /// Store a batch of values in one namespace and notify subscribers.
+    ///
+    /// The batch is stored with [`put_batch`](Self::put_batch) while the
+    /// write-order locks of all its keys are held, so subscribers see each
+    /// key's changes in the order they were stored. Null values are deletes
+    /// and are notified like [`delete_notify`](Self::delete_notify).
+    pub async fn put_batch_notify(
+        &self,
+        namespace: &str,
+        items: Vec<(String, serde_json::Value)>,
+    ) -> DeltaResult<Vec<VersionedValue>> {
+        #[cfg(not(target_arch = "wasm32"))]
+        let _order = self
+            .subscriptions
+            .order_keys(namespace, items.iter().map(|(key, _)| key.as_str()))
+            .await;
+
+        let keys: Vec<String> = items.iter().map(|(key, _)| key.clone()).collect();
+        let mut previous = std::collections::HashMap::new();
+        for key in &keys {
+            if !previous.contains_key(key) {
+                previous.insert(key.clone(), self.get(namespace, key).await.ok());
+            }
+        }
+
+        let versions = self
+            .put_batch(
+                items
+                    .into_iter()
+                    .map(|(key, value)| (namespace, key, value))
+                    .collect(),
+            )
+            .await?;
+
+        for (key, versioned) in keys.into_iter().zip(&versions) {
+            // A key written twice in the batch follows on from its first write
+            let previous = previous
+                .insert(key.clone(), Some(versioned.clone()))
+                .flatten();
+            if versioned.value().is_null() {
+                if let Some(previous) = previous.filter(|p| !p.value().is_null()) {
+                    let mut event = ChangeEvent::delete(namespace, &key, &previous);
+                    event.timestamp = versioned.timestamp();
+                    event.version_id = Some(versioned.version_id().to_string());
+                    self.subscriptions.notify(event);
+                }
+                continue;
+            }
+            let change_type = if previous.is_some() {
+                crate::subscriptions::ChangeType::Update
+            } else {
+                crate::subscriptions::ChangeType::Insert
+            };
+            self.subscriptions.notify(ChangeEvent {
+                change_type,
+                collection: namespace.to_string(),
+                key,
+                value: Some(versioned.value().clone()),
+                previous_value: previous.map(|p| p.value().clone()),
+                timestamp: versioned.timestamp(),
+                version_id: Some(versioned.version_id().to_string()),
+                previous_version_id: versioned.previous_version().map(|s| s.to_string()),
+                sequence: 0,
+            });
+        }
+
+        let _ = self.views.refresh_for_collection(namespace);
+
+        Ok(versions)
+    }
+
+    // =========================================================================
+    // Lifecycle
+    // =========================================================================
+
+    /// Shutdown the database, waiting up to 30 seconds for work to finish.
+    ///
+    /// See [`shutdown_with_timeout`](Self::shutdown_with_timeout).
+    pub async fn shutdown(self) -> DeltaResult<()> {
+        self.shutdown_with_timeout(DEFAULT_SHUTDOWN_TIMEOUT).await
+    }
+
+    /// Shutdown the database gracefully.
+    ///
+    /// Shutdown applies to every clone of the database:
+    /// 1. New writes are rejected with `DeltaError::ShuttingDown`
+    /// 2. Background processes and servers waiting on
+    ///    [`shutdown_signal`](Self::shutdown_signal) are told to stop
+    /// 3. In-flight writes complete
+    /// 4. Background processes finish their current run
+    /// 5. The cluster node stops and the WAL is flushed
+    /// 6. The database lock is released
+    ///
+    /// Acknowledged writes are already in the WAL, except
+    /// [`Durability::Async`] ones still being flushed, which count as in
+    /// flight. If in-flight writes are
+    /// still running when `timeout` expires, the database is marked as
+    /// uncleanly shut down so the next start recovers, and an error is
+    /// returned.
+    pub async fn shutdown_with_timeout(self, timeout: Duration) -> DeltaResult<()> {
+        info!("Shutting down KoruDelta");
+        let started = self.runtime.now();
+
+        self.writes.close();
+        let _ = self.shutdown_tx.send(true);
+        trace!("Shutdown signal sent to background processes");
+
+        let drained = self
+            .runtime
+            .timeout(
+                timeout,
+                Self::drain_writes(self.runtime.clone(), Arc::clone(&self.writes)),
+            )
+            .await
+            .is_ok();
+        if drained {
+            trace!("In-flight writes drained");
+        } else {
+            warn!(
+                in_flight = self.writes.in_flight(),
+                "Shutdown timed out waiting for writes"
+            );
+        }
+
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap());
+        let remaining = timeout.saturating_sub(self.runtime.now().duration_since(started));
+        if self
+            .runtime
+            .timeout(remaining, futures::future::join_all(tasks))
+            .await
+            .is_err()
+        {
+            warn!("Shutdown timed out waiting for background processes");
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(ref cluster) = self.cluster {
+            if let Err(e) = cluster.stop().await {
+                warn!(error = %e, "Failed to stop cluster node");
+            } else {
+                trace!("Cluster node stopped");
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(ref db_path) = self.db_path {
+            use crate::persistence;
+            persistence::flush(db_path).await?;
+            trace!("WAL flushed");
+
+            // A stale index must not be loaded on the next start: without
+            // the file, or after an unclean shutdown, it is rebuilt instead
+            let mut clean = drained;
+            if let Err(e) = self.vector_index.persist() {
+                warn!(error = %e, "Failed to persist vector index");
+                match tokio::fs::remove_file(db_path.join(VECTOR_INDEX_FILE)).await {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => {
+                        warn!(error = %e, "Failed to remove stale vector index");
+                        clean = false;
+                    }
+                }
+            }
+
+            if clean {
+                persistence::release_lock(db_path).await?;
+                trace!("Database lock released");
+            } else {
+                persistence::mark_unclean_shutdown(db_path).await?;
+            }
+        }
+
+        if !drained {
+            return Err(crate::error::DeltaError::StorageError(format!(
+                "Shutdown timed out with {} writes in flight",
+                self.writes.in_flight()
+            )));
+        }
+
+        info!("KoruDelta shutdown complete");
+        Ok(())
+    }
+
+    /// Wait until no writes are in flight.
+    async fn drain_writes(runtime: R, writes: Arc<WriteTracker>) {
+        while writes.in_flight() > 0 {
+            runtime.sleep(WRITE_DRAIN_POLL).await;
+        }
+    }
+
+    /// A future that resolves once shutdown has begun.
+    ///
+    /// Servers use it to stop accepting connections, e.g. with axum's
+    /// `with_graceful_shutdown`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn shutdown_signal(&self) -> impl std::future::Future<Output = ()> + Send + use<R> {
+        let mut shutdown = self.shutdown_rx.clone();
+        async move {
+            if !shutdown.borrow_and_update() {
+                Self::watch_shutdown(&mut shutdown).await;
+            }
+        }
+    }
+
+    // =========================================================================
+    // LCA (Local Causal Agent) Operations
+    // =========================================================================
+
+    /// Perform a storage action via causal synthesis.
+    ///
+    /// This is the LCA way: ΔNew = ΔLocal_Root ⊕ ΔAction
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let action = StorageAction::Store {
+    ///     namespace: "users".to_string(),
+    ///     key: "alice".to_string(),
+    ///     value_json: json!({"name": "Alice"}),
+    /// };
+    /// let new_root = db.synthesize_storage_action(action).await?;
+    /// ```
+    pub async fn synthesize_storage_action(
+        &mut self,
+        action: StorageAction,
+    ) -> DeltaResult<Distinction> {
+        // Validate the action
+        action
+            .validate()
+            .map_err(|e| crate::error::DeltaError::InvalidData { reason: e })?;
+
+        // Synthesize: ΔNew = ΔLocal_Root ⊕ ΔAction
+        let action_distinction = action.to_canonical_structure(self.field.engine());
+        let new_root = self.field.synthesize(&self.local_root, &action_distinction);
+
+        // Execute the action (this creates the causal effect)
+        self.execute_storage_action(&action).await?;
+
+        // Update local root to the new synthesis
+        self.local_root = new_root.clone();
+
+        Ok(new_root)
+    }
+
+    /// Execute a storage action (the causal effect).
+    ///
+    /// This performs the actual storage operation based on the action type.
+    async fn execute_storage_action(&self, action: &StorageAction) -> DeltaResult<()> {
+        match action {
+            StorageAction::Store {
+                namespace,
+                key,
+                value_json,
+            } => {
+                // Store via the existing put mechanism
+                let _ = self
+                    .put(namespace.clone(), key.clone(), value_json.clone())
+                    .await?;
+            }
+            StorageAction::Retrieve { namespace, key } => {
+                // Retrieve is handled by get, but we don't need the value here
+                let _ = self.get(namespace.clone(), key.clone()).await?;
+            }
+            StorageAction::History { namespace, key } => {
+                let _ = self.history(namespace, key).await?;
+            }
+            StorageAction::Query { .. } => {
+                // Query all collections
+                let namespaces = self.storage.list_namespaces();
+                for ns in namespaces {
+                    self.query(&ns, Query::new()).await?;
+                }
+            }
+            StorageAction::Delete { namespace, key } => {
+                self.delete(namespace, key).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the current local root distinction.
+    ///
+    /// This is the agent's causal perspective.
+    pub fn local_root(&self) -> &Distinction {
+        &self.local_root
+    }
+
+    /// Get the shared field engine.
+    pub fn shared_engine(&self) -> &SharedEngine {
+        &self.shared_engine
+    }
+
+    /// Get the field handle for synthesis operations.
+    pub fn field(&self) -> &FieldHandle {
+        &self.field
+    }
+}
+
+// ============================================================================
+// Local Causal Agent Implementation
+// ============================================================================
+
+impl<R: Runtime> LocalCausalAgent for KoruDeltaGeneric<R> {
+    type ActionData = StorageAction;
+
+    /// Get the current local root distinction.
+    ///
+    /// This is the Storage Agent's causal anchor (Root: STORAGE).
+    fn get_current_root(&self) -> &Distinction {
+        &self.local_root
+    }
+
+    /// Synthesize a new state from local root + action data.
+    ///
+    /// Formula: ΔNew = ΔLocal_Root ⊕ ΔAction_Data
+    ///
+    /// This method:
+    /// 1. Canonicalizes the action data into a distinction
+    /// 2. Synthesizes local_root ⊕ action_distinction
+    /// 3. Executes the storage action (causal effect)
+    /// 4. Returns the new distinction representing the state transition
+    fn synthesize_action(
+        &mut self,
+        action_data: Self::ActionData,
+        _engine: &Arc<DistinctionEngine>,
+    ) -> Distinction {
+        // Validate the action
+        if let Err(e) = action_data.validate() {
+            tracing::warn!("Invalid action: {}", e);
+            return self.local_root.clone();
+        }
+
+        // Canonicalize action into distinction
+        let action_distinction = action_data.to_canonical_structure(self.field.engine());
+
+        // Synthesize: ΔNew = ΔLocal ⊕ ΔAction
+        let new_root = self.field.synthesize(&self.local_root, &action_distinction);
+
+        // Update local root
+        self.local_root = new_root.clone();
+
+        new_root
+    }
+
+    /// Update the local root to a new distinction.
+    ///
+    /// This moves the agent's perspective forward in the causal chain.
+    fn update_local_root(&mut self, new_root: Distinction) {
+        self.local_root = new_root;
+    }
+}
+
+/// Database statistics.
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseStats {
+    /// Number of unique keys
+    pub key_count: usize,
+    /// Total number of versions
+    pub total_versions: usize,
+    /// Number of namespaces
+    pub namespace_count: usize,
+    /// Key and version counts per namespace
+    pub namespaces: BTreeMap<String, NamespaceStats>,
+    /// Occupancy of the memory tiers
+    pub tiers: TierStats,
+    /// Sizes of the vector index and materialized views
+    pub indexes: IndexStats,
+    /// Number of active subscriptions
+    pub subscriptions: usize,
+    /// Shared field engine statistics
+    pub field: FieldStats,
+}
+
+/// Key and version counts for one namespace.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct NamespaceStats {
+    /// Number of live keys
+    pub keys: usize,
+    /// Number of versions across those keys' histories
+    pub versions: usize,
+}
+
+/// Occupancy of the memory tiers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TierStats {
+    /// Entries in hot memory
+    pub hot_entries: usize,
+    /// Hot memory capacity
+    pub hot_capacity: usize,
+    /// Entries in warm memory
+    pub warm_entries: usize,
+    /// Warm memory capacity
+    pub warm_capacity: usize,
+    /// Epochs in cold memory
+    pub cold_epochs: usize,
+    /// Distinctions archived across cold epochs
+    pub cold_distinctions: usize,
+    /// Genomes in deep memory
+    pub deep_genomes: usize,
+}
+
+/// Sizes of the secondary indexes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct IndexStats {
+    /// Live vectors in the vector index
+    pub vectors: usize,
+    /// Deleted vectors not yet compacted away
+    pub vector_tombstones: usize,
+    /// Approximate bytes used by the vector index
+    pub vector_memory_bytes: usize,
+    /// Number of materialized views
+    pub views: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    async fn create_test_db() -> KoruDelta {
+        let config = CoreConfig::default();
+        KoruDelta::new(config).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_core_creation() {
+        let db = create_test_db().await;
+        let stats = db.stats().await;
+        assert_eq!(stats.key_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_start_ephemeral() {
+        let db = KoruDelta::start_ephemeral().await.unwrap();
+        assert!(!db.is_persistent());
+        assert!(db.tasks.lock().unwrap().is_empty());
+
+        db.put("cache", "k", json!(1)).await.unwrap();
+        assert_eq!(db.get("cache", "k").await.unwrap().value(), &json!(1));
+        db.shutdown().await.unwrap();
+
+        // The default in-memory instance runs background processes
+        let db = KoruDelta::start().await.unwrap();
+        assert!(!db.is_persistent());
+        assert!(!db.tasks.lock().unwrap().is_empty());
+        db.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_access_stats() {
+        let db = create_test_db().await;
+        db.put("users", "alice", json!(1)).await.unwrap();
+        db.put("users", "alice", json!(2)).await.unwrap();
+        db.put("users", "bob", json!(3)).await.unwrap();
+        for _ in 0..3 {
+            db.get("users", "alice").await.unwrap();
+        }
+        db.put("orders", "o1", json!({})).await.unwrap();
+
+        let stats = db.access_stats("users").await;
+        assert_eq!(stats.keys, 2);
+        assert_eq!(stats.reads, 3);
+        assert_eq!(stats.writes, 3);
+        assert!(stats.last_access.is_some());
+        assert_eq!(stats.hot_keys[0].key, "alice");
+        assert_eq!(stats.hot_keys[1].key, "bob");
+
+        let alice = db.key_access_stats("users", "alice").await.unwrap();
+        assert_eq!((alice.reads, alice.writes), (3, 2));
+        assert!(db.key_access_stats("users", "carol").await.is_none());
+        assert_eq!(db.access_stats("empty").await.keys, 0);
+    }
+
+    #[tokio::test]
+    async fn test_stats_breakdown() {
+        let db = create_test_db().await;
+        db.put("users", "alice", json!(1)).await.unwrap();
+        db.put("users", "alice", json!(2)).await.unwrap();
+        db.put("users", "bob", json!(3)).await.unwrap();
+        db.put("orders", "o1", json!({})).await.unwrap();
+        let _subscription = db.subscribe(Subscription::all()).await;
+
+        let stats = db.stats().await;
+        assert_eq!(
+            stats.namespaces["users"],
+            NamespaceStats {
+                keys: 2,
+                versions: 3
+            }
+        );
+        assert_eq!(stats.namespaces["orders"].keys, 1);
+        assert_eq!(stats.tiers.hot_entries, 3);
+        assert_eq!(stats.subscriptions, 1);
+
+        let json = db.stats_json().await;
+        assert_eq!(json["namespaces"]["users"]["versions"], 3);
+        assert_eq!(json["tiers"]["hot_capacity"], stats.tiers.hot_capacity);
+        assert!(json["field"]["distinction_count"].is_u64());
+    }
+
+    #[tokio::test]
+    async fn test_causal_path_queries() {
+        let db = create_test_db().await;
+        let v1 = db.put("docs", "readme", json!("draft")).await.unwrap();
+        let v2 = db.put("docs", "readme", json!("review")).await.unwrap();
+        let v3 = db.put("docs", "readme", json!("final")).await.unwrap();
+        let other = db.put("docs", "license", json!("MIT")).await.unwrap();
+
+        assert!(db.is_connected(v1.write_id(), v3.write_id()).await);
+        assert!(!db.is_connected(v1.write_id(), other.write_id()).await);
+
+        let path = db
+            .shortest_causal_path(v3.write_id(), v1.write_id())
+            .await
+            .unwrap();
+        assert_eq!(path, vec![v3.write_id, v2.write_id.clone(), v1.write_id]);
+
+        let neighborhood = db.causal_neighborhood(&v2.write_id, 1).await;
+        assert_eq!(neighborhood.len(), 2);
+        assert!(neighborhood.iter().all(|(_, distance)| *distance == 1));
+    }
+
+    #[tokio::test]
+    async fn test_put_and_get() {
+        let db = create_test_db().await;
+
+        let value = json!({"name": "Alice", "age": 30});
+        db.put("users", "alice", value.clone()).await.unwrap();
+
+        let retrieved = db.get("users", "alice").await.unwrap();
+        assert_eq!(*retrieved.value(), value);
+    }
+
+    #[tokio::test]
+    async fn test_get_raw_and_borrowed_access() {
+        let db = create_test_db().await;
+
+        let value = json!({"name": "Alice", "address": {"city": "Paris"}, "tags": ["a", "b"]});
+        db.put("users", "alice", value.clone()).await.unwrap();
+
+        let raw = db.get_raw("users", "alice").await.unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(raw.get()).unwrap();
+        assert_eq!(parsed, value);
+        // Serialized once per version, not per read
+        let again = db.get_raw("users", "alice").await.unwrap();
+        assert!(Arc::ptr_eq(&raw, &again));
+
+        let versioned = db.get("users", "alice").await.unwrap();
+        assert_eq!(versioned.str_field("address.city"), Some("Paris"));
+        assert_eq!(versioned.field("tags.1"), Some(&json!("b")));
+        assert_eq!(versioned.field("address.zip"), None);
+
+        // Gets share the stored value rather than copying it
+        let again = db.get("users", "alice").await.unwrap();
+        assert!(Arc::ptr_eq(
+            &versioned.shared_value(),
+            &again.shared_value()
+        ));
+        assert_eq!(again.into_value(), value);
+
+        assert!(matches!(
+            db.get_raw("users", "bob").await,
+            Err(crate::error::DeltaError::KeyNotFound { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_contains_key() {
+        let db = create_test_db().await;
+
+        assert!(!db.contains_key("users", "alice").await);
+
+        db.put("users", "alice", json!({"name": "Alice"}))
+            .await
+            .unwrap();
+
+        assert!(db.contains_key("users", "alice").await);
+    }
+
+    #[tokio::test]
+    async fn test_list_keys() {
+        let db = create_test_db().await;
+
+        db.put("users", "alice", json!({"name": "Alice"}))
+            .await
+            .unwrap();
+        db.put("users", "bob", json!({"name": "Bob"}))
+            .await
+            .unwrap();
+
+        let keys = db.list_keys("users").await;
+        assert_eq!(keys.len(), 2);
+        assert!(keys.contains(&"alice".to_string()));
+        assert!(keys.contains(&"bob".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_put_batch() {
+        let db = create_test_db().await;
+
+        // Test empty batch
+        let empty: Vec<(&str, &str, serde_json::Value)> = vec![];
+        let results = db.put_batch(empty).await.unwrap();
+        assert!(results.is_empty());
+
+        // Test batch with multiple items
+        let items = vec![
+            ("users", "alice", json!({"name": "Alice"})),
+            ("users", "bob", json!({"name": "Bob"})),
+            ("orders", "123", json!({"total": 100})),
+        ];
+
+        let results = db.put_batch(items).await.unwrap();
+        assert_eq!(results.len(), 3);
+
+        // Verify each item was stored
+        let alice = db.get("users", "alice").await.unwrap();
+        assert_eq!(alice.value().get("name").unwrap(), "Alice");
+
+        let bob = db.get("users", "bob").await.unwrap();
+        assert_eq!(bob.value().get("name").unwrap(), "Bob");
+
+        let order = db.get("orders", "123").await.unwrap();
+        assert_eq!(order.value().get("total").unwrap(), 100);
+
+        // Verify batch creates distinct versions
+        assert_ne!(results[0].version_id(), results[1].version_id());
+    }
+
+    #[tokio::test]
+    async fn test_history() {
+        let db = create_test_db().await;
+
+        db.put("doc", "readme", json!({"version": 1}))
+            .await
+            .unwrap();
+        db.put("doc", "readme", json!({"version": 2}))
+            .await
+            .unwrap();
+        db.put("doc", "readme", json!({"version": 3}))
+            .await
+            .unwrap();
+
+        let history = db.history("doc", "readme").await.unwrap();
+        assert_eq!(history.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_conflict() {
+        let db = create_test_db().await;
+        let existing = db.put("carts", "bob", json!(["apple"])).await.unwrap();
+        let mut incoming = existing.clone().with_value(json!(["pear"]));
+        incoming.write_id = "remote_write".to_string();
+        let conflict = db
+            .storage()
+            .record_conflict(
+                FullKey::new("carts", "bob"),
+                existing.clone(),
+                incoming,
+                Some(existing),
+                Utc::now() - chrono::Duration::hours(1),
+            )
+            .unwrap();
+        assert_eq!(db.conflicts("carts").await.len(), 1);
+        assert!(db.conflicts("users").await.is_empty());
+
+        // Conflicts are only found in their own namespace
+        assert!(
+            db.resolve_conflict("users", &conflict.id, json!([]))
+                .await
+                .is_err()
+        );
+
+        let winner = db
+            .resolve_conflict("carts", &conflict.id, json!(["apple", "pear"]))
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get("carts", "bob").await.unwrap().value(),
+            &json!(["apple", "pear"])
+        );
+        let resolved = &db.conflicts("carts").await[0];
+        assert_eq!(resolved.status, crate::types::ConflictStatus::Resolved);
+        assert_eq!(resolved.winner.as_ref().unwrap().write_id, winner.write_id);
+
+        // Settled conflicts are dropped once older than the retention age
+        assert_eq!(
+            db.prune_conflicts(chrono::Duration::days(1)).await.unwrap(),
+            0
+        );
+        assert_eq!(
+            db.prune_conflicts(chrono::Duration::minutes(1))
+                .await
+                .unwrap(),
+            1
+        );
+        assert!(db.conflicts("carts").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_time_travel() {
+        let db = create_test_db().await;
+
+        db.put("doc", "readme", json!({"version": 1}))
+            .await
+            .unwrap();
+        let t2 = Utc::now();
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        db.put("doc", "readme", json!({"version": 2}))
+            .await
+            .unwrap();
+
+        let v_at_t2 = db.get_at("doc", "readme", t2).await.unwrap();
+        assert_eq!(v_at_t2.value()["version"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_query_with_filter() {
+        use crate::query::Filter;
+
+        let db = create_test_db().await;
+
+        db.put("users", "alice", json!({"name": "Alice", "age": 30}))
+            .await
+            .unwrap();
+        db.put("users", "bob", json!({"name": "Bob", "age": 25}))
+            .await
+            .unwrap();
+        db.put("users", "charlie", json!({"name": "Charlie", "age": 35}))
+            .await
+            .unwrap();
+
+        let result = db
+            .query("users", Query::new().filter(Filter::gt("age", 25)))
+            .await
+            .unwrap();
+
+        assert_eq!(result.records.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_stats() {
+        let db = create_test_db().await;
+
+        let stats1 = db.stats().await;
+        assert_eq!(stats1.key_count, 0);
+        assert_eq!(stats1.total_versions, 0);
+
+        db.put("users", "alice", json!({"user": "alice", "v": 1}))
+            .await
+            .unwrap();
+        db.put("users", "alice", json!({"user": "alice", "v": 2}))
+            .await
+            .unwrap();
+        db.put("users", "bob", json!({"user": "bob", "v": 1}))
+            .await
+            .unwrap();
+
+        let stats2 = db.stats().await;
+        assert_eq!(stats2.key_count, 2);
+        assert_eq!(stats2.total_versions, 3);
+        assert_eq!(stats2.namespace_count, 1);
+    }
+
+    // =========================================================================
+    // LCA (Local Causal Agent) Tests
+    // =========================================================================
+
+    #[tokio::test]
+    async fn test_lca_local_root_exists() {
+        let db = create_test_db().await;
+
+        // The local root should be initialized
+        let root = db.local_root();
+        assert!(!root.id().is_empty());
+
+        // It should be the STORAGE root
+        let expected_root = db.shared_engine().root(RootType::Storage);
+        assert_eq!(root.id(), expected_root.id());
+    }
+
+    #[tokio::test]
+    async fn test_lca_synthesize_storage_action() {
+        use crate::actions::StorageAction;
+
+        let mut db = create_test_db().await;
+        let initial_root = db.local_root().clone();
+
+        // Synthesize a store action
+        let action = StorageAction::Store {
+            namespace: "users".to_string(),
+            key: "alice".to_string(),
+            value_json: json!({"name": "Alice"}),
+        };
+
+        let new_root = db.synthesize_storage_action(action).await.unwrap();
+
+        // The new root should be different from initial
+        assert_ne!(new_root.id(), initial_root.id());
+
+        // The local root should be updated
+        assert_eq!(db.local_root().id(), new_root.id());
+
+        // The data should actually be stored
+        let retrieved = db.get("users", "alice").await.unwrap();
+        assert_eq!(retrieved.value()["name"], "Alice");
+    }
+
+    #[tokio::test]
+    async fn test_lca_local_causal_agent_trait() {
+        use crate::actions::StorageAction;
+        use koru_lambda_core::LocalCausalAgent;
+
+        let mut db = create_test_db().await;
+        let engine = Arc::new(DistinctionEngine::new());
+
+        // Test get_current_root
+        let root = db.get_current_root();
+        assert!(!root.id().is_empty());
+
+        // Test synthesize_action
+        let action = StorageAction::Retrieve {
+            namespace: "users".to_string(),
+            key: "alice".to_string(),
+        };
+
+        let new_root = db.synthesize_action(action, &engine);
+        assert!(!new_root.id().is_empty());
+
+        // The root should have changed (even though retrieval doesn't store)
+        // because synthesis still happens
+    }
+
+    #[tokio::test]
+    async fn test_lca_shared_engine() {
+        let db = create_test_db().await;
+
+        // The shared engine should be accessible
+        let engine = db.shared_engine();
+        let stats = engine.stats();
+
+        // Should have distinctions (12 roots are created during initialization,
+        // each synthesized from d0/d1, so there should be many distinctions)
+        assert!(
+            stats.distinction_count >= 12,
+            "Expected at least 12 distinctions (roots), got {}",
+            stats.distinction_count
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lca_field_handle() {
+        let db = create_test_db().await;
+
+        // The field handle should provide access to d0 and d1
+        let d0 = db.field().d0();
+        let d1 = db.field().d1();
+
+        assert!(!d0.id().is_empty());
+        assert!(!d1.id().is_empty());
+        assert_ne!(d0.id(), d1.id());
+    }
+
+    #[tokio::test]
+    async fn test_lca_causal_chain() {
+        use crate::actions::StorageAction;
+
+        let mut db = create_test_db().await;
+        let root1 = db.local_root().clone();
+
+        // First action
+        let action1 = StorageAction::Store {
+            namespace: "test".to_string(),
+            key: "key1".to_string(),
+            value_json: json!(1),
+        };
+        let root2 = db.synthesize_storage_action(action1).await.unwrap();
+        assert_ne!(root1.id(), root2.id());
+
+        // Second action
+        let action2 = StorageAction::Store {
+            namespace: "test".to_string(),
+            key: "key2".to_string(),
+            value_json: json!(2),
+        };
+        let root3 = db.synthesize_storage_action(action2).await.unwrap();
+        assert_ne!(root2.id(), root3.id());
+
+        // Third action
+        let action3 = StorageAction::Store {
+            namespace: "test".to_string(),
+            key: "key3".to_string(),
+            value_json: json!(3),
+        };
+        let root4 = db.synthesize_storage_action(action3).await.unwrap();
+        assert_ne!(root3.id(), root4.id());
+
+        // Each root should be unique (causal chain)
+        assert_ne!(root1.id(), root3.id());
+        assert_ne!(root1.id(), root4.id());
+        assert_ne!(root2.id(), root4.id());
+    }
 
-impl<R: Runtime> LocalCausalAgent for KoruDeltaGeneric<R> {
-    type ActionData = StorageAction;
+    // ============================================================================
+    // ALIS AI Integration Tests
+    // ============================================================================
 
-    /// Get the current local root distinction.
-    ///
-    /// This is the Storage Agent's causal anchor (Root: STORAGE).
-    fn get_current_root(&self) -> &Distinction {
-        &self.local_root
-    }
+    #[tokio::test]
+    async fn test_ttl_storage_and_expiration() {
+        let db = create_test_db().await;
 
-    /// Synthesize a new state from local root + action data.
-    ///
-    /// Formula: ΔNew = ΔLocal_Root ⊕ ΔAction_Data
-    ///
-    /// This method:
-    /// 1. Canonicalizes the action data into a distinction
-    /// 2. Synthesizes local_root ⊕ action_distinction
-    /// 3. Executes the storage action (causal effect)
-    /// 4. Returns the new distinction representing the state transition
-    fn synthesize_action(
-        &mut self,
-        action_data: Self::ActionData,
-        _engine: &Arc<DistinctionEngine>,
-    ) -> Distinction {
-        // Validate the action
-        if let Err(e) = action_data.validate() {
-            tracing::warn!("Invalid action: {}", e);
-            return self.local_root.clone();
-        }
+        // Store with short TTL
+        db.put_with_ttl("test", "key1", json!({"data": "value"}), 1)
+            .await
+            .unwrap();
 
-        // Canonicalize action into distinction
-        let action_distinction = action_data.to_canonical_structure(self.field.engine());
+        // Should appear in expiring soon list
+        let expiring = db.list_expiring_soon(10).await;
+        assert!(!expiring.is_empty());
+        let found = expiring
+            .iter()
+            .any(|(ns, key, _)| ns == "test" && key == "key1");
+        assert!(found, "Key should be in expiring list");
 
-        // Synthesize: ΔNew = ΔLocal ⊕ ΔAction
-        let new_root = self.field.synthesize(&self.local_root, &action_distinction);
+        // Wait for expiration
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
 
-        // Update local root
-        self.local_root = new_root.clone();
+        // Cleanup should remove it
+        let cleaned = db.cleanup_expired().await.unwrap();
+        assert_eq!(cleaned, 1);
 
-        new_root
+        // Should no longer be in expiring list
+        let expiring_after = db.list_expiring_soon(10).await;
+        let still_exists = expiring_after
+            .iter()
+            .any(|(ns, key, _)| ns == "test" && key == "key1");
+        assert!(!still_exists, "Key should be removed after cleanup");
     }
 
-    /// Update the local root to a new distinction.
-    ///
-    /// This moves the agent's perspective forward in the causal chain.
-    fn update_local_root(&mut self, new_root: Distinction) {
-        self.local_root = new_root;
-    }
-}
+    #[tokio::test]
+    async fn test_ttl_list_expiring_soon() {
+        let db = create_test_db().await;
 
-/// Database statistics.
-#[derive(Debug, Clone)]
-pub struct DatabaseStats {
-    /// Number of unique keys
-    pub key_count: usize,
-    /// Total number of versions
-    pub total_versions: usize,
-    /// Number of namespaces
-    pub namespace_count: usize,
-}
+        // Store items with different TTLs
+        db.put_with_ttl("test", "short", json!({}), 5)
+            .await
+            .unwrap();
+        db.put_with_ttl("test", "long", json!({}), 100)
+            .await
+            .unwrap();
+        db.put_with_ttl("other", "medium", json!({}), 50)
+            .await
+            .unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use serde_json::json;
+        // List items expiring within 10 seconds
+        let expiring = db.list_expiring_soon(10).await;
+        assert_eq!(expiring.len(), 1);
+        assert_eq!(expiring[0].1, "short");
 
-    async fn create_test_db() -> KoruDelta {
-        let config = CoreConfig::default();
-        KoruDelta::new(config).await.unwrap()
+        // List items expiring within 60 seconds
+        let expiring_60 = db.list_expiring_soon(60).await;
+        assert_eq!(expiring_60.len(), 2);
     }
 
     #[tokio::test]
-    async fn test_core_creation() {
+    async fn test_graph_connectivity() {
         let db = create_test_db().await;
-        let stats = db.stats().await;
-        assert_eq!(stats.key_count, 0);
+
+        // Create causal chain: A → B → C
+        db.put("graph", "A", json!({"next": "B"})).await.unwrap();
+        db.put("graph", "B", json!({"next": "C"})).await.unwrap();
+        db.put("graph", "C", json!({})).await.unwrap();
+
+        // Note: In the actual implementation, causality is tracked through
+        // the causal graph when using put_with_parents or through synthesis
+        // For this test, we're checking the API exists and returns a result
+        let _connected = db.are_connected("graph", "A", "C").await.unwrap();
+        let _path = db.get_connection_path("graph", "A", "C").await.unwrap();
     }
 
     #[tokio::test]
-    async fn test_put_and_get() {
+    async fn test_get_highly_connected() {
         let db = create_test_db().await;
 
-        let value = json!({"name": "Alice", "age": 30});
-        db.put("users", "alice", value.clone()).await.unwrap();
+        // Create some distinctions
+        for i in 0..5 {
+            db.put("test", &format!("key{}", i), json!({"index": i}))
+                .await
+                .unwrap();
+        }
 
-        let retrieved = db.get("users", "alice").await.unwrap();
-        assert_eq!(*retrieved.value(), value);
+        // Get highly connected (should return results even if empty)
+        let results: Vec<ConnectedDistinction> =
+            db.get_highly_connected(Some("test"), 3).await.unwrap();
+
+        // Results should be a vector (may be empty if no causal graph connections)
+        assert!(results.len() <= 5);
     }
 
     #[tokio::test]
-    async fn test_contains_key() {
+    async fn test_find_similar_unconnected_pairs() {
         let db = create_test_db().await;
 
-        assert!(!db.contains_key("users", "alice").await);
+        // Store similar content
+        db.put_similar("ns1", "a", "machine learning is powerful", None)
+            .await
+            .unwrap();
+        db.put_similar("ns2", "b", "machine learning enables ai", None)
+            .await
+            .unwrap();
 
-        db.put("users", "alice", json!({"name": "Alice"}))
+        // Find similar unconnected pairs
+        let pairs: Vec<UnconnectedPair> = db
+            .find_similar_unconnected_pairs(None, 10, 0.5)
             .await
             .unwrap();
 
-        assert!(db.contains_key("users", "alice").await);
+        // Should return a vector (may be empty depending on similarity threshold)
+        assert!(pairs.len() <= 10);
     }
 
     #[tokio::test]
-    async fn test_list_keys() {
+    async fn test_random_walk_combinations() {
         let db = create_test_db().await;
 
-        db.put("users", "alice", json!({"name": "Alice"}))
+        // Create some distinctions for random walks
+        for i in 0..5 {
+            db.put("walk", &format!("node{}", i), json!({"index": i}))
+                .await
+                .unwrap();
+        }
+
+        // Generate random walk combinations
+        let combinations: Vec<RandomCombination> = db.random_walk_combinations(3, 5).await.unwrap();
+
+        // Should return a vector
+        assert!(combinations.len() <= 3);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_sqlite_import_and_export() {
+        use crate::sqlite::ExportMode;
+        use rusqlite::Connection;
+
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("app.db");
+        {
+            let conn = Connection::open(&source).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, avatar BLOB);
+                 INSERT INTO users VALUES (1, 'Alice', x'0102'), (2, 'Bob', NULL);
+                 CREATE TABLE events (msg TEXT);
+                 INSERT INTO events VALUES ('boot');",
+            )
+            .unwrap();
+        }
+
+        let db = create_test_db().await;
+        let imported = db
+            .import_sqlite(&source, &[("users", "people"), ("events", "log")])
             .await
             .unwrap();
-        db.put("users", "bob", json!({"name": "Bob"}))
+        assert_eq!(imported[0].rows, 2);
+        assert_eq!(imported[1].rows, 1);
+        let alice = db.get("people", "1").await.unwrap();
+        assert_eq!(
+            alice.value(),
+            &json!({"id": 1, "name": "Alice", "avatar": "AQI="})
+        );
+        assert_eq!(
+            db.get("log", "1").await.unwrap().value(),
+            &json!({"msg": "boot"})
+        );
+
+        // A missing table writes nothing
+        assert!(
+            db.import_sqlite(&source, &[("events", "other"), ("nope", "x")])
+                .await
+                .is_err()
+        );
+        assert!(db.list_keys("other").await.is_empty());
+
+        db.put("people", "1", json!({"id": 1, "name": "Alicia"}))
             .await
             .unwrap();
+        db.delete("people", "2").await.unwrap();
 
-        let keys = db.list_keys("users").await;
-        assert_eq!(keys.len(), 2);
-        assert!(keys.contains(&"alice".to_string()));
-        assert!(keys.contains(&"bob".to_string()));
+        let target = dir.path().join("export.db");
+        db.export_sqlite(&target, &[("people", "heads")], ExportMode::Heads)
+            .await
+            .unwrap();
+        let exported = db
+            .export_sqlite(&target, &[("people", "history")], ExportMode::History)
+            .await
+            .unwrap();
+        assert_eq!(exported[0].rows, 4);
+
+        let conn = Connection::open(&target).unwrap();
+        let heads: Vec<(String, String)> = conn
+            .prepare("SELECT key, json_extract(value, '$.name') FROM heads ORDER BY key")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(heads, vec![("1".to_string(), "Alicia".to_string())]);
+        let deleted: Option<String> = conn
+            .query_row(
+                "SELECT value FROM history WHERE key = '2' ORDER BY seq DESC LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(deleted, None);
     }
 
     #[tokio::test]
-    async fn test_put_batch() {
-        let db = create_test_db().await;
+    async fn test_typed_records() {
+        use crate::record::Field;
 
-        // Test empty batch
-        let empty: Vec<(&str, &str, serde_json::Value)> = vec![];
-        let results = db.put_batch(empty).await.unwrap();
-        assert!(results.is_empty());
+        #[derive(Debug, PartialEq, Serialize, serde::Deserialize)]
+        struct User {
+            handle: String,
+            age: u32,
+        }
 
-        // Test batch with multiple items
-        let items = vec![
-            ("users", "alice", json!({"name": "Alice"})),
-            ("users", "bob", json!({"name": "Bob"})),
-            ("orders", "123", json!({"total": 100})),
-        ];
+        impl KoruRecord for User {
+            const NAMESPACE: &'static str = "users";
 
-        let results = db.put_batch(items).await.unwrap();
-        assert_eq!(results.len(), 3);
+            fn key(&self) -> String {
+                self.handle.clone()
+            }
+        }
 
-        // Verify each item was stored
-        let alice = db.get("users", "alice").await.unwrap();
-        assert_eq!(alice.value().get("name").unwrap(), "Alice");
+        struct UserFields {
+            handle: Field<User>,
+            age: Field<User>,
+        }
 
-        let bob = db.get("users", "bob").await.unwrap();
-        assert_eq!(bob.value().get("name").unwrap(), "Bob");
+        impl crate::record::RecordFields for User {
+            type Fields = UserFields;
 
-        let order = db.get("orders", "123").await.unwrap();
-        assert_eq!(order.value().get("total").unwrap(), 100);
+            const FIELDS: UserFields = UserFields {
+                handle: Field::new("handle"),
+                age: Field::new("age"),
+            };
+        }
+
+        const AGE: Field<User> = Field::new("age");
+
+        let db = create_test_db().await;
+        for (handle, age) in [("alice", 30), ("bob", 12), ("carol", 41)] {
+            let user = User {
+                handle: handle.to_string(),
+                age,
+            };
+            db.put_typed(&user).await.unwrap();
+        }
+
+        let alice: User = db.get_typed("alice").await.unwrap();
+        assert_eq!(alice.age, 30);
+        db.delete("users", "carol").await.unwrap();
+        assert!(matches!(
+            db.get_typed::<User>("carol").await,
+            Err(crate::error::DeltaError::KeyNotFound { .. })
+        ));
+
+        let adults = db
+            .query_typed::<User>(Query::new().filter(AGE.gte(18)).project(&["handle"]))
+            .await
+            .unwrap();
+        assert_eq!(adults, vec![alice]);
+
+        db.put_typed(&User {
+            handle: "dave".to_string(),
+            age: 25,
+        })
+        .await
+        .unwrap();
+        let query = crate::record::TypedQuery::<User>::new()
+            .filter(|u| u.age.gte(18))
+            .sort_by_desc(|u| u.handle);
+        let handles: Vec<String> = db
+            .query_typed::<User>(query)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|user| user.handle)
+            .collect();
+        assert_eq!(handles, vec!["dave", "alice"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_put_notify_is_sequenced_in_write_order() {
+        let db = Arc::new(create_test_db().await);
+        let (_id, mut rx) = db.subscribe(Subscription::key("counters", "hits")).await;
+
+        let writers: Vec<_> = (0..16)
+            .map(|i| {
+                let db = Arc::clone(&db);
+                tokio::spawn(async move { db.put_notify("counters", "hits", json!(i)).await })
+            })
+            .collect();
+        for writer in writers {
+            writer.await.unwrap().unwrap();
+        }
 
-        // Verify batch creates distinct versions
-        assert_ne!(results[0].version_id(), results[1].version_id());
+        // Events are numbered in the order the versions were stored, and
+        // each one's previous value is the value before it
+        let mut previous_value = None;
+        let mut values = Vec::new();
+        for sequence in 1..=16 {
+            let event = rx.try_recv().unwrap();
+            assert_eq!(event.sequence, sequence);
+            assert_eq!(event.previous_value, previous_value);
+            previous_value = event.value.clone();
+            values.push(event.value.unwrap());
+        }
+        let history = db.history("counters", "hits").await.unwrap();
+        let stored: Vec<_> = history.into_iter().map(|entry| entry.value).collect();
+        assert_eq!(values, stored);
+        assert_eq!(db.subscription_manager().sequence("counters", "hits"), 16);
     }
 
     #[tokio::test]
-    async fn test_history() {
-        let db = create_test_db().await;
-
-        db.put("doc", "readme", json!({"version": 1}))
-            .await
-            .unwrap();
-        db.put("doc", "readme", json!({"version": 2}))
+    async fn test_read_transaction_is_consistent_across_writes() {
+        let db = Arc::new(create_test_db().await);
+        db.put("accounts", "a", json!({"balance": 50}))
             .await
             .unwrap();
-        db.put("doc", "readme", json!({"version": 3}))
+        db.put("accounts", "b", json!({"balance": 50}))
             .await
             .unwrap();
 
-        let history = db.history("doc", "readme").await.unwrap();
-        assert_eq!(history.len(), 3);
-    }
+        let txn = db.begin_read();
+        let writer = {
+            let db = Arc::clone(&db);
+            tokio::spawn(async move {
+                // Transfer 10 at a time from a to b
+                for i in 1..=5 {
+                    db.put("accounts", "a", json!({"balance": 50 - i * 10}))
+                        .await
+                        .unwrap();
+                    db.put("accounts", "b", json!({"balance": 50 + i * 10}))
+                        .await
+                        .unwrap();
+                    tokio::task::yield_now().await;
+                }
+            })
+        };
 
-    #[tokio::test]
-    async fn test_time_travel() {
-        let db = create_test_db().await;
+        let a = txn.get("accounts", "a").await.unwrap();
+        tokio::task::yield_now().await;
+        let b = txn.get("accounts", "b").await.unwrap();
+        writer.await.unwrap();
+        assert_eq!(
+            a.value()["balance"].as_i64().unwrap() + b.value()["balance"].as_i64().unwrap(),
+            100
+        );
 
-        db.put("doc", "readme", json!({"version": 1}))
-            .await
-            .unwrap();
-        let t2 = Utc::now();
-        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
-        db.put("doc", "readme", json!({"version": 2}))
+        // Still pinned after every write has landed
+        let total = txn
+            .query(
+                "accounts",
+                Query::new().aggregate(crate::query::Aggregation::sum("balance")),
+            )
             .await
             .unwrap();
-
-        let v_at_t2 = db.get_at("doc", "readme", t2).await.unwrap();
-        assert_eq!(v_at_t2.value()["version"], 1);
+        assert_eq!(total.aggregation, Some(json!(100.0)));
+        assert_eq!(
+            txn.get("accounts", "a").await.unwrap().value(),
+            &json!({"balance": 50})
+        );
+        assert_eq!(
+            db.begin_read().get("accounts", "a").await.unwrap().value(),
+            &json!({"balance": 0})
+        );
     }
 
     #[tokio::test]
-    async fn test_query_with_filter() {
-        use crate::query::Filter;
-
+    async fn test_read_transaction_survives_replicated_delete() {
         let db = create_test_db().await;
-
-        db.put("users", "alice", json!({"name": "Alice", "age": 30}))
-            .await
-            .unwrap();
-        db.put("users", "bob", json!({"name": "Bob", "age": 25}))
+        let original = db
+            .put("accounts", "a", json!({"balance": 50}))
             .await
             .unwrap();
-        db.put("users", "charlie", json!({"name": "Charlie", "age": 35}))
-            .await
+
+        let txn = db.begin_read();
+        db.storage()
+            .delete_causal("accounts", "a", crate::types::VectorClock::new(), "node-2")
             .unwrap();
+        assert!(db.storage().get("accounts", "a").is_err());
+        assert!(db.begin_read().get("accounts", "a").await.is_err());
 
-        let result = db
-            .query("users", Query::new().filter(Filter::gt("age", 25)))
+        // The open transaction still sees the key as it was
+        assert_eq!(
+            txn.get("accounts", "a").await.unwrap().write_id(),
+            original.write_id()
+        );
+        assert_eq!(txn.list_keys("accounts").await, ["a"]);
+
+        // A later write follows on from the tombstone
+        let rewritten = db
+            .put("accounts", "a", json!({"balance": 10}))
             .await
             .unwrap();
-
-        assert_eq!(result.records.len(), 2);
+        let tombstone = db
+            .storage()
+            .version(rewritten.previous_version().unwrap())
+            .unwrap();
+        assert!(tombstone.value().is_null());
+        assert_eq!(tombstone.previous_version(), Some(original.write_id()));
+        assert_eq!(
+            txn.get("accounts", "a").await.unwrap().write_id(),
+            original.write_id()
+        );
     }
 
     #[tokio::test]
-    async fn test_stats() {
-        let db = create_test_db().await;
+    async fn test_browse_and_rehydrate_epochs() {
+        // Consolidation rotates epochs, so keep background processes off
+        let mut config = CoreConfig::default();
+        config.processes.enabled = false;
+        let db = KoruDelta::new(config).await.unwrap();
+        let mut archived = Vec::new();
+        for (key, status) in [("1", "shipped"), ("2", "open"), ("3", "shipped")] {
+            let versioned = db
+                .put("orders", key, json!({"status": status}))
+                .await
+                .unwrap();
+            archived.push((FullKey::new("orders", key), versioned));
+        }
+        {
+            let cold = db.cold.read().await;
+            for (key, versioned) in &archived {
+                cold.store(
+                    versioned.write_id().to_string(),
+                    key.clone(),
+                    versioned.timestamp(),
+                );
+            }
+            cold.rotate_epoch();
+        }
 
-        let stats1 = db.stats().await;
-        assert_eq!(stats1.key_count, 0);
-        assert_eq!(stats1.total_versions, 0);
+        let epochs = db.epochs().await;
+        assert_eq!(epochs.len(), 1);
+        assert_eq!(epochs[0].distinctions, 3);
+        assert!(epochs[0].start_time <= epochs[0].end_time);
+        let epoch = epochs[0].number;
 
-        db.put("users", "alice", json!({"user": "alice", "v": 1}))
-            .await
-            .unwrap();
-        db.put("users", "alice", json!({"user": "alice", "v": 2}))
-            .await
-            .unwrap();
-        db.put("users", "bob", json!({"user": "bob", "v": 1}))
+        let shipped = db
+            .epoch_contents(epoch, Query::new().filter(Filter::eq("status", "shipped")))
             .await
             .unwrap();
+        let keys: Vec<_> = shipped.records.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(keys, vec!["orders:1", "orders:3"]);
 
-        let stats2 = db.stats().await;
-        assert_eq!(stats2.key_count, 2);
-        assert_eq!(stats2.total_versions, 3);
-        assert_eq!(stats2.namespace_count, 1);
-    }
+        // The open epoch cannot be browsed
+        assert!(matches!(
+            db.epoch_contents(epoch + 1, Query::new()).await,
+            Err(crate::error::DeltaError::InvalidData { .. })
+        ));
 
-    // =========================================================================
-    // LCA (Local Causal Agent) Tests
-    // =========================================================================
+        let key = FullKey::new("orders", "3");
+        assert_eq!(db.rehydrate_epoch(epoch, &[key.clone()]).await.unwrap(), 1);
+        assert!(db.warm.read().await.get_by_key(&key).is_some());
+        assert_eq!(db.epochs().await[0].distinctions, 2);
+        assert_eq!(db.rehydrate_epoch(epoch, &[key]).await.unwrap(), 0);
+    }
 
     #[tokio::test]
-    async fn test_lca_local_root_exists() {
+    async fn test_reads_drive_temperature() {
         let db = create_test_db().await;
+        for key in ["a", "b", "c"] {
+            db.put("temps", key, json!({"key": key})).await.unwrap();
+        }
 
-        // The local root should be initialized
-        let root = db.local_root();
-        assert!(!root.id().is_empty());
+        // Writes alone leave keys cold
+        assert_eq!(db.temperature("temps", "a").await, TemperatureLevel::Cold);
 
-        // It should be the STORAGE root
-        let expected_root = db.shared_engine().root(RootType::Storage);
-        assert_eq!(root.id(), expected_root.id());
+        db.get("temps", "a").await.unwrap();
+        assert_eq!(db.temperature("temps", "a").await, TemperatureLevel::Warm);
+        db.get("temps", "a").await.unwrap();
+        assert_eq!(db.temperature("temps", "a").await, TemperatureLevel::Hot);
+
+        // Query hits are reads too
+        let query = Query::new().filter(Filter::eq("key", "b"));
+        let result = db.query("temps", query).await.unwrap();
+        assert_eq!(result.records.len(), 1);
+        assert_eq!(db.temperature("temps", "b").await, TemperatureLevel::Warm);
+        assert_eq!(db.temperature("temps", "c").await, TemperatureLevel::Cold);
     }
 
     #[tokio::test]
-    async fn test_lca_synthesize_storage_action() {
-        use crate::actions::StorageAction;
-
-        let mut db = create_test_db().await;
-        let initial_root = db.local_root().clone();
-
-        // Synthesize a store action
-        let action = StorageAction::Store {
-            namespace: "users".to_string(),
-            key: "alice".to_string(),
-            value_json: json!({"name": "Alice"}),
-        };
-
-        let new_root = db.synthesize_storage_action(action).await.unwrap();
+    async fn test_dreams_are_stored_for_review() {
+        let db = create_test_db().await;
 
-        // The new root should be different from initial
-        assert_ne!(new_root.id(), initial_root.id());
+        // Two sources and a record derived from both, so every walk crosses keys
+        let a = db
+            .put("notes", "a", json!({"text": "tides"}))
+            .await
+            .unwrap();
+        let b = db.put("notes", "b", json!({"text": "moon"})).await.unwrap();
+        db.put_with_causes(
+            "notes",
+            "c",
+            json!({"text": "orbit"}),
+            &[a.write_id(), b.write_id()],
+        )
+        .await
+        .unwrap();
 
-        // The local root should be updated
-        assert_eq!(db.local_root().id(), new_root.id());
+        let dreams = db.dream(20, 1).await.unwrap();
+        assert!(!dreams.is_empty());
+        let mut ids: Vec<_> = dreams.iter().map(|d| d.id.clone()).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), dreams.len());
+
+        // Dreams are stored with links to the versions they came from
+        let dream = &dreams[0];
+        assert_eq!(dream.status, DreamStatus::Pending);
+        let causes = db.causes(DREAMS_NAMESPACE, &dream.id).await.unwrap();
+        let cause_ids: Vec<_> = causes
+            .iter()
+            .map(|(_, v)| v.write_id().to_string())
+            .collect();
+        assert_eq!(cause_ids, dream.sources);
+        assert_eq!(db.dreams().await.unwrap().len(), dreams.len());
+
+        // Review
+        let accepted = db.accept_dream(&dream.id).await.unwrap();
+        assert_eq!(accepted.status, DreamStatus::Accepted);
+        assert!(accepted.reviewed_at.is_some());
+        assert!(matches!(
+            db.discard_dream(&dream.id).await,
+            Err(crate::error::DeltaError::InvalidData { .. })
+        ));
+        assert!(matches!(
+            db.accept_dream("dream_missing").await,
+            Err(crate::error::DeltaError::KeyNotFound { .. })
+        ));
+        let reloaded = db.get_dream(&dream.id).await.unwrap();
+        assert_eq!(reloaded.status, DreamStatus::Accepted);
+        assert_eq!(
+            db.causes(DREAMS_NAMESPACE, &dream.id).await.unwrap().len(),
+            2
+        );
+        if let Some(other) = dreams.get(1) {
+            let discarded = db.discard_dream(&other.id).await.unwrap();
+            assert_eq!(discarded.status, DreamStatus::Discarded);
+        }
 
-        // The data should actually be stored
-        let retrieved = db.get("users", "alice").await.unwrap();
-        assert_eq!(retrieved.value()["name"], "Alice");
+        // Known pairs are not dreamed again
+        let again = db.dream(20, 1).await.unwrap();
+        assert!(again.iter().all(|d| !ids.contains(&d.id)));
     }
 
     #[tokio::test]
-    async fn test_lca_local_causal_agent_trait() {
-        use crate::actions::StorageAction;
-        use koru_lambda_core::LocalCausalAgent;
-
-        let mut db = create_test_db().await;
-        let engine = Arc::new(DistinctionEngine::new());
-
-        // Test get_current_root
-        let root = db.get_current_root();
-        assert!(!root.id().is_empty());
-
-        // Test synthesize_action
-        let action = StorageAction::Retrieve {
-            namespace: "users".to_string(),
-            key: "alice".to_string(),
-        };
+    async fn test_alis_ai_full_workflow() {
+        // This test validates the complete ALIS AI workflow:
+        // 1. Store prediction with TTL
+        // 2. Check expiring soon list
+        // 3. Query graph connectivity
+        // 4. Find synthesis candidates
+        // 5. Generate creative combinations
 
-        let new_root = db.synthesize_action(action, &engine);
-        assert!(!new_root.id().is_empty());
+        let db = create_test_db().await;
 
-        // The root should have changed (even though retrieval doesn't store)
-        // because synthesis still happens
-    }
+        // Step 1: Store predictions with TTL
+        db.put_with_ttl(
+            "predictions",
+            "weather",
+            json!({"prediction": "sunny", "confidence": 0.8}),
+            60,
+        )
+        .await
+        .unwrap();
 
-    #[tokio::test]
-    async fn test_lca_shared_engine() {
-        let db = create_test_db().await;
+        // Step 2: Verify it appears in expiring soon list
+        let expiring = db.list_expiring_soon(100).await;
+        let found = expiring
+            .iter()
+            .any(|(ns, key, _)| ns == "predictions" && key == "weather");
+        assert!(found, "Prediction should be in expiring list");
 
-        // The shared engine should be accessible
-        let engine = db.shared_engine();
-        let stats = engine.stats();
+        // Step 3: Store observations
+        db.put_similar("observations", "sky", "clear blue sky", None)
+            .await
+            .unwrap();
+        db.put_similar("observations", "temperature", "warm afternoon", None)
+            .await
+            .unwrap();
 
-        // Should have distinctions (12 roots are created during initialization,
-        // each synthesized from d0/d1, so there should be many distinctions)
-        assert!(
-            stats.distinction_count >= 12,
-            "Expected at least 12 distinctions (roots), got {}",
-            stats.distinction_count
-        );
-    }
+        // Step 4: Get highly connected distinctions
+        let connected = db
+            .get_highly_connected(Some("observations"), 5)
+            .await
+            .unwrap();
+        // Should not panic, returns vector
+        let _ = connected.len();
 
-    #[tokio::test]
-    async fn test_lca_field_handle() {
-        let db = create_test_db().await;
+        // Step 5: Find synthesis candidates
+        let pairs = db
+            .find_similar_unconnected_pairs(None, 5, 0.6)
+            .await
+            .unwrap();
+        // Should not panic, returns vector
+        let _ = pairs.len();
 
-        // The field handle should provide access to d0 and d1
-        let d0 = db.field().d0();
-        let d1 = db.field().d1();
+        // Step 6: Generate dream combinations
+        let dreams = db.random_walk_combinations(2, 3).await.unwrap();
+        // Should not panic, returns vector
+        let _ = dreams.len();
 
-        assert!(!d0.id().is_empty());
-        assert!(!d1.id().is_empty());
-        assert_ne!(d0.id(), d1.id());
+        // All operations completed successfully
     }
 
     #[tokio::test]
-    async fn test_lca_causal_chain() {
-        use crate::actions::StorageAction;
-
-        let mut db = create_test_db().await;
-        let root1 = db.local_root().clone();
-
-        // First action
-        let action1 = StorageAction::Store {
-            namespace: "test".to_string(),
-            key: "key1".to_string(),
-            value_json: json!(1),
-        };
-        let root2 = db.synthesize_storage_action(action1).await.unwrap();
-        assert_ne!(root1.id(), root2.id());
+    async fn test_scheduler_drives_background_processes() {
+        use crate::runtime::TestRuntime;
 
-        // Second action
-        let action2 = StorageAction::Store {
-            namespace: "test".to_string(),
-            key: "key2".to_string(),
-            value_json: json!(2),
-        };
-        let root3 = db.synthesize_storage_action(action2).await.unwrap();
-        assert_ne!(root2.id(), root3.id());
+        let runtime = TestRuntime::new();
+        let db = KoruDeltaGeneric::new_with_runtime(CoreConfig::default(), runtime.clone())
+            .await
+            .unwrap();
+        let runs = |process| db.scheduler().get(process).unwrap().runs;
 
-        // Third action
-        let action3 = StorageAction::Store {
-            namespace: "test".to_string(),
-            key: "key3".to_string(),
-            value_json: json!(3),
-        };
-        let root4 = db.synthesize_storage_action(action3).await.unwrap();
-        assert_ne!(root3.id(), root4.id());
+        // Every process runs once on start, then waits for its interval
+        runtime.run_until_idle();
+        assert_eq!(runs(BackgroundProcess::Consolidation), 1);
+        assert_eq!(runs(BackgroundProcess::Distillation), 1);
 
-        // Each root should be unique (causal chain)
-        assert_ne!(root1.id(), root3.id());
-        assert_ne!(root1.id(), root4.id());
-        assert_ne!(root2.id(), root4.id());
-    }
+        db.scheduler()
+            .pause(BackgroundProcess::Distillation)
+            .unwrap();
+        db.scheduler()
+            .reschedule(BackgroundProcess::Consolidation, Duration::from_secs(10))
+            .unwrap();
 
-    // ============================================================================
-    // ALIS AI Integration Tests
-    // ============================================================================
+        runtime.advance(Duration::from_secs(30));
+        assert_eq!(runs(BackgroundProcess::Consolidation), 4);
+
+        runtime.advance(Duration::from_secs(3600));
+        assert_eq!(runs(BackgroundProcess::Distillation), 1);
+    }
 
     #[tokio::test]
-    async fn test_ttl_storage_and_expiration() {
-        let db = create_test_db().await;
+    async fn test_process_reports_skip_idle_runs() {
+        use crate::runtime::TestRuntime;
 
-        // Store with short TTL
-        db.put_with_ttl("test", "key1", json!({"data": "value"}), 1)
+        let runtime = TestRuntime::new();
+        let db = KoruDeltaGeneric::new_with_runtime(CoreConfig::default(), runtime.clone())
             .await
             .unwrap();
+        let mut progress = db
+            .process_progress(BackgroundProcess::Consolidation)
+            .unwrap();
 
-        // Should appear in expiring soon list
-        let expiring = db.list_expiring_soon(10).await;
-        assert!(!expiring.is_empty());
-        let found = expiring
-            .iter()
-            .any(|(ns, key, _)| ns == "test" && key == "key1");
-        assert!(found, "Key should be in expiring list");
+        // The first runs on an empty database find nothing to record
+        runtime.run_until_idle();
+        assert!(progress.has_changed().unwrap());
+        assert!(!progress.borrow_and_update().running);
+        assert!(
+            db.last_run_report(BackgroundProcess::Consolidation)
+                .unwrap()
+                .is_none()
+        );
+        assert!(db.list_namespaces().await.is_empty());
 
-        // Wait for expiration
-        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        let run = db
+            .scheduler()
+            .begin_run(BackgroundProcess::Distillation)
+            .unwrap();
+        run.scanned(3);
+        run.promoted(1);
+        let report = db
+            .scheduler()
+            .finish_run(BackgroundProcess::Distillation, &run);
+        KoruDeltaGeneric::<TestRuntime>::store_process_report(&db.storage, &report);
+
+        let stored = db
+            .last_run_report(BackgroundProcess::Distillation)
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored, report);
+        assert_eq!(db.list_namespaces().await, vec![SYSTEM_NAMESPACE]);
+    }
 
-        // Cleanup should remove it
-        let cleaned = db.cleanup_expired().await.unwrap();
-        assert_eq!(cleaned, 1);
+    #[tokio::test]
+    async fn test_shutdown_rejects_writes_and_releases_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = KoruDelta::start_with_path(dir.path()).await.unwrap();
+        let other = db.clone();
+        db.put("users", "alice", json!({"name": "Alice"}))
+            .await
+            .unwrap();
 
-        // Should no longer be in expiring list
-        let expiring_after = db.list_expiring_soon(10).await;
-        let still_exists = expiring_after
-            .iter()
-            .any(|(ns, key, _)| ns == "test" && key == "key1");
-        assert!(!still_exists, "Key should be removed after cleanup");
+        db.shutdown().await.unwrap();
+        assert!(matches!(
+            other.put("users", "bob", json!({})).await,
+            Err(crate::error::DeltaError::ShuttingDown)
+        ));
+        drop(other);
+
+        // The lock is clean, so the database reopens with the write intact
+        let db = KoruDelta::start_with_path(dir.path()).await.unwrap();
+        let value = db.get("users", "alice").await.unwrap();
+        assert_eq!(value.value()["name"], "Alice");
     }
 
     #[tokio::test]
-    async fn test_ttl_list_expiring_soon() {
-        let db = create_test_db().await;
+    async fn test_put_with_causes_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = KoruDelta::start_with_path(dir.path()).await.unwrap();
+        let order = db.put("orders", "1", json!({"total": 40})).await.unwrap();
+        db.put_with_causes("invoices", "1", json!({"amount": 40}), &[order.write_id()])
+            .await
+            .unwrap();
+        db.shutdown().await.unwrap();
 
-        // Store items with different TTLs
-        db.put_with_ttl("test", "short", json!({}), 5)
+        let db = KoruDelta::start_with_path(dir.path()).await.unwrap();
+        let causes = db.causes("invoices", "1").await.unwrap();
+        assert_eq!(causes.len(), 1);
+        assert_eq!(causes[0].0, FullKey::new("orders", "1"));
+        assert_eq!(causes[0].1.write_id, order.write_id);
+
+        let dependents = db.dependents(order.write_id()).await;
+        assert_eq!(dependents.len(), 1);
+        assert_eq!(dependents[0].0, FullKey::new("invoices", "1"));
+    }
+
+    #[tokio::test]
+    async fn test_async_durability() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = KoruDelta::start_with_path(dir.path()).await.unwrap();
+        let options = PutOptions::new().durability(Durability::Async);
+
+        let first = db
+            .put_with_options("events", "e1", json!({"kind": "click"}), options.clone())
             .await
             .unwrap();
-        db.put_with_ttl("test", "long", json!({}), 100)
+        // Applied in memory before it is flushed
+        assert_eq!(
+            db.get("events", "e1").await.unwrap().value()["kind"],
+            "click"
+        );
+        assert_eq!(first.version().value()["kind"], "click");
+        first.durable().await.unwrap();
+
+        // Shutdown waits for async writes that are still being flushed
+        let second = db
+            .put_with_options("events", "e2", json!({"kind": "scroll"}), options)
             .await
             .unwrap();
-        db.put_with_ttl("other", "medium", json!({}), 50)
+        let version = second.into_version();
+        db.shutdown().await.unwrap();
+
+        let db = KoruDelta::start_with_path(dir.path()).await.unwrap();
+        assert_eq!(db.list_keys("events").await, vec!["e1", "e2"]);
+        assert_eq!(
+            db.get("events", "e2").await.unwrap().write_id(),
+            version.write_id()
+        );
+
+        // Synchronous receipts are durable on return
+        let sync = db
+            .put_with_options("events", "e3", json!({}), PutOptions::new())
             .await
             .unwrap();
+        sync.durable().await.unwrap();
+    }
 
-        // List items expiring within 10 seconds
-        let expiring = db.list_expiring_soon(10).await;
-        assert_eq!(expiring.len(), 1);
-        assert_eq!(expiring[0].1, "short");
+    #[tokio::test]
+    async fn test_write_hooks() {
+        let db = create_test_db().await;
+        db.add_validator("orders", |write| match write.value["total"].as_f64() {
+            Some(total) if total < 0.0 => Err("negative total".to_string()),
+            _ => Ok(()),
+        });
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let hook = db.add_post_write_hook("orders", move |event| {
+            let tx = tx.clone();
+            async move {
+                let _ = tx.send(event);
+            }
+        });
 
-        // List items expiring within 60 seconds
-        let expiring_60 = db.list_expiring_soon(60).await;
-        assert_eq!(expiring_60.len(), 2);
+        db.put("orders", "1", json!({"total": 10})).await.unwrap();
+        let event = rx.recv().await.unwrap();
+        assert_eq!(event.key, "1");
+        assert_eq!(event.version.value(), &json!({"total": 10}));
+
+        // Rejected writes are not applied and do not reach post-write hooks
+        assert!(matches!(
+            db.put("orders", "2", json!({"total": -5})).await,
+            Err(crate::error::DeltaError::WriteRejected { .. })
+        ));
+        assert!(!db.contains("orders", "2").await);
+        let batch = db
+            .put_batch(vec![
+                ("orders", "3", json!({"total": 1})),
+                ("orders", "4", json!({"total": -1})),
+            ])
+            .await;
+        assert!(batch.is_err());
+        assert!(!db.contains("orders", "3").await);
+
+        // Other namespaces are unaffected
+        db.put("refunds", "1", json!({"total": -5})).await.unwrap();
+
+        db.put_batch_in_ns("orders", vec![("5".to_string(), json!({"total": 2}))])
+            .await
+            .unwrap();
+        assert_eq!(rx.recv().await.unwrap().key, "5");
+
+        assert!(db.remove_hook(hook));
+        db.put("orders", "6", json!({"total": 3})).await.unwrap();
+        assert!(rx.try_recv().is_err());
     }
 
     #[tokio::test]
-    async fn test_graph_connectivity() {
+    async fn test_approximate_aggregations() {
         let db = create_test_db().await;
+        for (key, country, ms) in [("1", "se", 10), ("2", "se", 20), ("3", "no", 30)] {
+            db.put("visits", key, json!({"country": country, "ms": ms}))
+                .await
+                .unwrap();
+        }
 
-        // Create causal chain: A → B → C
-        db.put("graph", "A", json!({"next": "B"})).await.unwrap();
-        db.put("graph", "B", json!({"next": "C"})).await.unwrap();
-        db.put("graph", "C", json!({})).await.unwrap();
+        let result = db
+            .query(
+                "visits",
+                Query::new().aggregate(Aggregation::percentiles("ms", &[0.0, 100.0])),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.aggregation, Some(json!({"p0": 10.0, "p100": 30.0})));
 
-        // Note: In the actual implementation, causality is tracked through
-        // the causal graph when using put_with_parents or through synthesis
-        // For this test, we're checking the API exists and returns a result
-        let _connected = db.are_connected("graph", "A", "C").await.unwrap();
-        let _path = db.get_connection_path("graph", "A", "C").await.unwrap();
+        let countries = db
+            .track_aggregate("visits", Aggregation::approx_distinct("country"))
+            .await
+            .unwrap();
+        assert_eq!(countries.value(), json!(2));
+        db.put("visits", "4", json!({"country": "dk", "ms": 5}))
+            .await
+            .unwrap();
+        for _ in 0..100 {
+            if countries.value() == json!(3) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(countries.value(), json!(3));
+        assert!(db.remove_hook(countries.hook_id()));
+
+        assert!(matches!(
+            db.track_aggregate("visits", Aggregation::count()).await,
+            Err(crate::error::DeltaError::InvalidData { .. })
+        ));
     }
 
     #[tokio::test]
-    async fn test_get_highly_connected() {
-        let db = create_test_db().await;
+    async fn test_range_index_serves_sorted_queries() {
+        use crate::query::{Filter, SortOrder};
 
-        // Create some distinctions
-        for i in 0..5 {
-            db.put("test", &format!("key{}", i), json!({"index": i}))
+        let dir = tempfile::tempdir().unwrap();
+        let db = KoruDelta::start_with_path(dir.path()).await.unwrap();
+        for (key, total) in [("1", 10), ("2", 50), ("3", 30), ("4", 20)] {
+            db.put("orders", key, json!({"total": total}))
                 .await
                 .unwrap();
         }
+        let info = db.create_range_index("orders", "total").await.unwrap();
+        assert_eq!(info.entries, 4);
+        db.put("orders", "5", json!({"total": 40})).await.unwrap();
 
-        // Get highly connected (should return results even if empty)
-        let results: Vec<ConnectedDistinction> =
-            db.get_highly_connected(Some("test"), 3).await.unwrap();
+        let result = db
+            .query("orders", Query::new().sort_by("total", false).limit(2))
+            .await
+            .unwrap();
+        let keys: Vec<_> = result.records.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(keys, vec!["2", "5"]);
+        assert_eq!(result.total_count, 5);
 
-        // Results should be a vector (may be empty if no causal graph connections)
-        assert!(results.len() <= 5);
+        let result = db
+            .query(
+                "orders",
+                Query::new()
+                    .filter(Filter::gte("total", 30))
+                    .sort_by("total", true)
+                    .limit(2),
+            )
+            .await
+            .unwrap();
+        let keys: Vec<_> = result.records.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(keys, vec!["3", "5"]);
+        assert_eq!(result.total_count, 3);
+
+        // Deleted keys leave the index
+        db.delete("orders", "2").await.unwrap();
+        let index = db.range_index("orders", "total").unwrap();
+        assert_eq!(index.top(1, SortOrder::Desc), vec![("5".to_string(), 40.0)]);
+        assert_eq!(index.percentile(50.0), Some(20.0));
+        let catalog = db
+            .query(
+                "_system.indexes",
+                Query::new().filter(Filter::eq("kind", "range")),
+            )
+            .await
+            .unwrap();
+        assert_eq!(catalog.records[0].value["entries"], json!(4));
+        db.shutdown().await.unwrap();
+
+        // Definitions survive a restart
+        let db = KoruDelta::start_with_path(dir.path()).await.unwrap();
+        assert_eq!(db.range_index("orders", "total").unwrap().len(), 4);
+        db.drop_range_index("orders", "total").await.unwrap();
+        assert!(db.list_range_indexes().is_empty());
+        assert!(db.drop_range_index("orders", "total").await.is_err());
     }
 
     #[tokio::test]
-    async fn test_find_similar_unconnected_pairs() {
+    async fn test_time_bucket_history() {
         let db = create_test_db().await;
+        for temp in [20, 22, 27] {
+            db.put("sensors", "kitchen", json!({"temp": temp}))
+                .await
+                .unwrap();
+        }
 
-        // Store similar content
-        db.put_similar("ns1", "a", "machine learning is powerful", None)
+        let hourly = db
+            .aggregate_history(
+                "sensors",
+                "kitchen",
+                HistoryQuery::new().with_query(Query::new().aggregate(Aggregation::time_bucket(
+                    "temp",
+                    crate::query::BucketInterval::Hour,
+                ))),
+            )
             .await
             .unwrap();
-        db.put_similar("ns2", "b", "machine learning enables ai", None)
+        let buckets = hourly.as_array().unwrap();
+        let count: u64 = buckets.iter().map(|b| b["count"].as_u64().unwrap()).sum();
+        assert_eq!(count, 3);
+        let last = buckets.last().unwrap();
+        assert_eq!(last["max"], json!(27));
+        assert!(last["bucket"].as_str().unwrap().ends_with(":00:00Z"));
+
+        assert!(matches!(
+            db.aggregate_history("sensors", "kitchen", HistoryQuery::new())
+                .await,
+            Err(crate::error::DeltaError::InvalidData { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_query_changes() {
+        let db = create_test_db().await;
+        db.put("subs", "a", json!({"status": "active"}))
+            .await
+            .unwrap();
+        db.put("subs", "b", json!({"status": "trial"}))
+            .await
+            .unwrap();
+        db.put("subs", "c", json!({"status": "active"}))
+            .await
+            .unwrap();
+        let window = Utc::now();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        db.put("subs", "a", json!({"status": "cancelled"}))
+            .await
+            .unwrap();
+        db.put("subs", "b", json!({"status": "active"}))
+            .await
+            .unwrap();
+        db.put("subs", "c", json!({"status": "active"}))
             .await
             .unwrap();
 
-        // Find similar unconnected pairs
-        let pairs: Vec<UnconnectedPair> = db
-            .find_similar_unconnected_pairs(None, 10, 0.5)
+        let churn = db
+            .query_changes(
+                "subs",
+                HistoryQuery::new()
+                    .with_query(Query::new().filter(crate::query::Filter::eq("status", "active")))
+                    .from(window),
+            )
             .await
             .unwrap();
+        let keys: Vec<&str> = churn.iter().map(|k| k.key.as_str()).collect();
+        assert_eq!(keys, vec!["a", "b"]);
+        assert_eq!(churn[0].changes[0].old_value, json!({"status": "active"}));
+        assert_eq!(
+            churn[0].changes[0].new_value,
+            json!({"status": "cancelled"})
+        );
 
-        // Should return a vector (may be empty depending on similarity threshold)
-        assert!(pairs.len() <= 10);
+        let everything = db
+            .query_changes(
+                "subs",
+                HistoryQuery::new().with_query(Query::new().limit(2)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(everything.len(), 2);
+        assert_eq!(everything[0].changes.len(), 2);
     }
 
     #[tokio::test]
-    async fn test_random_walk_combinations() {
+    async fn test_system_catalog() {
         let db = create_test_db().await;
+        db.put("users", "ada", json!({"name": "Ada", "age": 36}))
+            .await
+            .unwrap();
+        db.put("users", "tim", json!({"name": "Tim"}))
+            .await
+            .unwrap();
+        db.create_view(ViewDefinition::new("adults", "users"))
+            .await
+            .unwrap();
 
-        // Create some distinctions for random walks
-        for i in 0..5 {
-            db.put("walk", &format!("node{}", i), json!({"index": i}))
-                .await
-                .unwrap();
-        }
+        let schemas = db
+            .query(
+                "_system.schemas",
+                Query::new().filter(crate::query::Filter::eq("namespace", "users")),
+            )
+            .await
+            .unwrap();
+        assert_eq!(schemas.records.len(), 1);
+        assert_eq!(schemas.records[0].value["keys"], json!(2));
+        assert_eq!(
+            schemas.records[0].value["fields"],
+            json!({"age": ["number"], "name": ["string"]})
+        );
 
-        // Generate random walk combinations
-        let combinations: Vec<RandomCombination> = db.random_walk_combinations(3, 5).await.unwrap();
+        let views = db.query("_system.views", Query::new()).await.unwrap();
+        assert_eq!(views.records[0].key, "adults");
+        assert_eq!(views.records[0].value["source_collection"], json!("users"));
 
-        // Should return a vector
-        assert!(combinations.len() <= 3);
+        let cluster = db.query("_system.cluster", Query::new()).await.unwrap();
+        assert_eq!(cluster.records.len(), 1);
+        assert_eq!(cluster.records[0].value["local"], json!(true));
+
+        assert!(matches!(
+            db.put("_system.views", "fake", json!({})).await,
+            Err(crate::error::DeltaError::InvalidData { .. })
+        ));
     }
 
     #[tokio::test]
-    async fn test_alis_ai_full_workflow() {
-        // This test validates the complete ALIS AI workflow:
-        // 1. Store prediction with TTL
-        // 2. Check expiring soon list
-        // 3. Query graph connectivity
-        // 4. Find synthesis candidates
-        // 5. Generate creative combinations
+    async fn test_put_idempotent_deduplicates_retries() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = KoruDelta::start_with_path(dir.path()).await.unwrap();
+        let first = db
+            .put_idempotent("orders", "1", json!({"total": 40}), "req-1")
+            .await
+            .unwrap();
+        assert_eq!(first.idempotency_token.as_deref(), Some("req-1"));
+        db.put("orders", "1", json!({"total": 45})).await.unwrap();
 
-        let db = create_test_db().await;
+        // A retry returns the original write, even after later writes
+        let retry = db
+            .put_idempotent("orders", "1", json!({"total": 40}), "req-1")
+            .await
+            .unwrap();
+        assert_eq!(retry.write_id(), first.write_id());
+        assert_eq!(db.history("orders", "1").await.unwrap().len(), 2);
 
-        // Step 1: Store predictions with TTL
-        db.put_with_ttl(
-            "predictions",
-            "weather",
-            json!({"prediction": "sunny", "confidence": 0.8}),
-            60,
-        )
-        .await
-        .unwrap();
+        // Tokens are scoped to the key
+        db.put_idempotent("orders", "2", json!({"total": 10}), "req-1")
+            .await
+            .unwrap();
+        assert!(db.contains("orders", "2").await);
+        db.shutdown().await.unwrap();
 
-        // Step 2: Verify it appears in expiring soon list
-        let expiring = db.list_expiring_soon(100).await;
-        let found = expiring
-            .iter()
-            .any(|(ns, key, _)| ns == "predictions" && key == "weather");
-        assert!(found, "Prediction should be in expiring list");
+        // The token is persisted with the version
+        let db = KoruDelta::start_with_path(dir.path()).await.unwrap();
+        let retry = db
+            .put_idempotent("orders", "1", json!({"total": 40}), "req-1")
+            .await
+            .unwrap();
+        assert_eq!(retry.write_id(), first.write_id());
+        assert_eq!(db.history("orders", "1").await.unwrap().len(), 2);
+    }
 
-        // Step 3: Store observations
-        db.put_similar("observations", "sky", "clear blue sky", None)
+    #[tokio::test]
+    async fn test_put_idempotent_concurrent_retries_apply_once() {
+        let db = create_test_db().await;
+        let retries =
+            (0..8).map(|_| db.put_idempotent("orders", "1", json!({"total": 40}), "req-1"));
+        let others = (0..8).map(|i| {
+            db.put_idempotent(
+                "orders",
+                format!("other-{}", i),
+                json!(i),
+                format!("req-{}", i),
+            )
+        });
+        let (retries, _) = futures::join!(
+            futures::future::join_all(retries),
+            futures::future::join_all(others)
+        );
+
+        let first = retries[0].as_ref().unwrap().write_id().to_string();
+        assert!(
+            retries
+                .iter()
+                .all(|retry| retry.as_ref().unwrap().write_id() == first)
+        );
+        assert_eq!(db.history("orders", "1").await.unwrap().len(), 1);
+        assert!(db.idempotency_locks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_put_idempotent_window_expires() {
+        let mut config = CoreConfig::default();
+        config.idempotency.window = Duration::ZERO;
+        let db = KoruDelta::new(config).await.unwrap();
+        let first = db
+            .put_idempotent("orders", "1", json!({"total": 40}), "req-1")
             .await
             .unwrap();
-        db.put_similar("observations", "temperature", "warm afternoon", None)
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let retry = db
+            .put_idempotent("orders", "1", json!({"total": 40}), "req-1")
             .await
             .unwrap();
+        assert_ne!(retry.write_id(), first.write_id());
+    }
 
-        // Step 4: Get highly connected distinctions
-        let connected = db
-            .get_highly_connected(Some("observations"), 5)
+    #[tokio::test]
+    async fn test_genome_regenerates_structure_on_fresh_node() {
+        let old = create_test_db().await;
+        let v1 = old.put("users", "alice", json!({"v": 1})).await.unwrap();
+        let v2 = old.put("users", "alice", json!({"v": 2})).await.unwrap();
+        let bob = old.put("users", "bob", json!({"v": 1})).await.unwrap();
+
+        let id = old.extract_genome().await;
+        let bytes = old.export_genome(&id).await.unwrap();
+        assert!(old.export_genome("missing").await.is_err());
+
+        let fresh = create_test_db().await;
+        let imported = fresh.import_genome(&bytes).await.unwrap();
+        assert_eq!(imported, id);
+        let result = fresh.regenerate_from_genome(&imported).await.unwrap();
+        assert_eq!(
+            result.distinctions_restored,
+            old.storage.causal_graph().node_count()
+        );
+
+        let graph = fresh.storage.causal_graph();
+        assert!(graph.contains(bob.write_id()));
+        assert_eq!(
+            graph.ancestors(v2.write_id()),
+            old.storage.causal_graph().ancestors(v2.write_id())
+        );
+        assert!(
+            graph
+                .ancestors(v2.write_id())
+                .contains(&v1.write_id().to_string())
+        );
+        assert!(fresh.regenerate_from_genome("missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_collect_garbage_compacts_wal() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = KoruDelta::start_with_path(dir.path()).await.unwrap();
+        db.put("users", "alice", json!({"name": "Alice"}))
             .await
             .unwrap();
-        // Should not panic, returns vector
-        let _ = connected.len();
-
-        // Step 5: Find synthesis candidates
-        let pairs = db
-            .find_similar_unconnected_pairs(None, 5, 0.6)
+        let bob = db
+            .put("users", "bob", json!({"name": "Bob"}))
             .await
             .unwrap();
-        // Should not panic, returns vector
-        let _ = pairs.len();
+        db.delete("users", "bob").await.unwrap();
+
+        // Only the deletion of bob is kept
+        let report = db.collect_garbage().await.unwrap();
+        assert_eq!(report.versions_collected, 1);
+        assert_eq!(report.wal_entries_removed, 1);
+        assert_eq!(report.value_files_removed, 1);
+        assert!(report.distinctions_collected > 0);
+        assert!(report.bytes_reclaimed > 0);
+        assert!(db.storage.has_tombstone("users", "bob"));
+
+        // A peer still holding bob's old version can't bring bob back
+        let merged = db
+            .merge_versions(vec![(FullKey::new("users", "bob"), bob)])
+            .await;
+        assert!(merged.is_empty());
+        assert!(db.get("users", "bob").await.unwrap().value().is_null());
+
+        // Writes after compaction land after the compacted log
+        db.put("users", "carol", json!({"name": "Carol"}))
+            .await
+            .unwrap();
+        db.shutdown().await.unwrap();
 
-        // Step 6: Generate dream combinations
-        let dreams = db.random_walk_combinations(2, 3).await.unwrap();
-        // Should not panic, returns vector
-        let _ = dreams.len();
+        let db = KoruDelta::start_with_path(dir.path()).await.unwrap();
+        assert!(db.get("users", "alice").await.is_ok());
+        assert!(db.get("users", "carol").await.is_ok());
+        assert!(db.get("users", "bob").await.unwrap().value().is_null());
+        assert_eq!(db.history("users", "bob").await.unwrap().len(), 1);
+    }
 
-        // All operations completed successfully
+    #[tokio::test]
+    async fn test_compact_history_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = KoruDelta::start_with_path(dir.path()).await.unwrap();
+        for n in 0..6 {
+            db.put("metrics", "cpu", json!({"load": n})).await.unwrap();
+        }
+
+        let policy = CompactionPolicy::new().keep_recent(2);
+        let report = db.compact_history("metrics", "cpu", policy).await.unwrap();
+        assert_eq!(report.runs_compacted, 1);
+        assert_eq!(report.versions_removed, 2);
+        assert_eq!(report.wal_entries_removed, 2);
+
+        let history = db.history("metrics", "cpu").await.unwrap();
+        assert_eq!(history.len(), 4);
+        assert_eq!(history[1].summary.as_ref().unwrap().versions, 3);
+        db.shutdown().await.unwrap();
+
+        let db = KoruDelta::start_with_path(dir.path()).await.unwrap();
+        let history = db.history("metrics", "cpu").await.unwrap();
+        assert_eq!(history.len(), 4);
+        assert_eq!(history[0].value, json!({"load": 0}));
+        assert_eq!(history[1].value, json!({"load": 3}));
+        assert_eq!(history[1].summary.as_ref().unwrap().versions, 3);
+        assert_eq!(
+            db.get("metrics", "cpu").await.unwrap().value(),
+            &json!({"load": 5})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_waits_for_in_flight_writes() {
+        let db = KoruDelta::start().await.unwrap();
+        let write = db.writes.begin().unwrap();
+
+        let shutdown = tokio::spawn(db.clone().shutdown());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!shutdown.is_finished());
+
+        drop(write);
+        shutdown.await.unwrap().unwrap();
+
+        // Gives up once the timeout expires
+        let db = KoruDelta::start().await.unwrap();
+        let _write = db.writes.begin().unwrap();
+        let result = db
+            .clone()
+            .shutdown_with_timeout(Duration::from_millis(50))
+            .await;
+        assert!(result.is_err());
     }
 }
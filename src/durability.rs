@@ -0,0 +1,153 @@
+/// Write durability options.
+///
+/// By default a [`put`](crate::KoruDelta::put) on a persistent database
+/// returns only once the write is flushed to the write-ahead log. Workloads
+/// that prefer throughput can opt out per write with
+/// [`Durability::Async`]: the put returns as soon as the write is applied
+/// in memory (readable, replicated, visible to queries), and the returned
+/// [`Durable`] future resolves once it has been flushed.
+///
+/// Async writes are still appended to the log in the order they were
+/// applied, grouped with whatever else is being flushed. Shutdown waits for
+/// them.
+///
+/// # Example
+///
+/// ```ignore
+/// let receipt = db
+///     .put_with_options(
+///         "events",
+///         "e1",
+///         json!({"kind": "click"}),
+///         PutOptions::new().durability(Durability::Async),
+///     )
+///     .await?;
+/// // ... do more work, then confirm the write reached disk
+/// receipt.durable().await?;
+/// ```
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::channel::oneshot;
+
+use crate::error::{DeltaError, DeltaResult};
+use crate::types::VersionedValue;
+
+/// When a write is acknowledged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Durability {
+    /// Return once the write is flushed to the write-ahead log.
+    #[default]
+    Sync,
+    /// Return once the write is applied in memory; flush in the background.
+    Async,
+}
+
+/// Options for [`put_with_options`](crate::KoruDelta::put_with_options).
+#[derive(Debug, Clone, Default)]
+pub struct PutOptions {
+    /// When the write is acknowledged.
+    pub durability: Durability,
+}
+
+impl PutOptions {
+    /// Default options: synchronous durability.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set when the write is acknowledged.
+    pub fn durability(mut self, durability: Durability) -> Self {
+        self.durability = durability;
+        self
+    }
+}
+
+/// Resolves once a write has been flushed to the write-ahead log.
+///
+/// Resolves immediately for synchronous writes and for in-memory databases.
+/// Fails if the flush failed or was abandoned.
+#[derive(Debug)]
+pub struct Durable {
+    flushed: oneshot::Receiver<Result<(), String>>,
+}
+
+impl Durable {
+    /// A write whose flush has already finished with `result`.
+    pub(crate) fn ready(result: DeltaResult<()>) -> Self {
+        let (done, durable) = Self::pending();
+        let _ = done.send(result.map_err(|e| e.to_string()));
+        durable
+    }
+
+    /// A write still being flushed, and the sender to report the flush on.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub(crate) fn pending() -> (oneshot::Sender<Result<(), String>>, Self) {
+        let (done, flushed) = oneshot::channel();
+        (done, Self { flushed })
+    }
+}
+
+impl Future for Durable {
+    type Output = DeltaResult<()>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.flushed)
+            .poll(cx)
+            .map(|flushed| match flushed {
+                Ok(result) => result.map_err(DeltaError::StorageError),
+                Err(oneshot::Canceled) => Err(DeltaError::StorageError(
+                    "write was abandoned before it was flushed".to_string(),
+                )),
+            })
+    }
+}
+
+/// Result of [`put_with_options`](crate::KoruDelta::put_with_options).
+#[derive(Debug)]
+pub struct PutReceipt {
+    version: VersionedValue,
+    durable: Durable,
+}
+
+impl PutReceipt {
+    pub(crate) fn new(version: VersionedValue, durable: Durable) -> Self {
+        Self { version, durable }
+    }
+
+    /// The version that was written.
+    pub fn version(&self) -> &VersionedValue {
+        &self.version
+    }
+
+    /// Take the version that was written, without waiting for the flush.
+    pub fn into_version(self) -> VersionedValue {
+        self.version
+    }
+
+    /// Wait until the write has been flushed to the write-ahead log.
+    pub fn durable(self) -> Durable {
+        self.durable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_durable_resolves_with_flush_result() {
+        assert!(Durable::ready(Ok(())).await.is_ok());
+
+        let (done, durable) = Durable::pending();
+        done.send(Err("disk full".to_string())).unwrap();
+        assert!(
+            matches!(durable.await, Err(DeltaError::StorageError(reason)) if reason == "disk full")
+        );
+
+        let (done, durable) = Durable::pending();
+        drop(done);
+        assert!(durable.await.is_err());
+    }
+}
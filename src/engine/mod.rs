@@ -219,7 +219,7 @@ impl Default for SharedEngine {
 }
 
 /// Statistics for the shared field.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
 pub struct FieldStats {
     /// Total number of synthesis operations performed.
     pub synthesis_count: u64,
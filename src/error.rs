@@ -42,6 +42,15 @@ pub enum DeltaError {
         reason: String,
     },
 
+    /// A configuration setting is missing, malformed or out of range
+    #[error("Invalid configuration for '{key}': {reason}")]
+    InvalidConfig {
+        /// The setting's key (or environment variable)
+        key: String,
+        /// Description of what is wrong with it
+        reason: String,
+    },
+
     /// Internal error from the distinction engine
     #[error("Engine error: {0}")]
     EngineError(String),
@@ -53,6 +62,52 @@ pub enum DeltaError {
     /// Time-related error (invalid timestamp, time travel to future, etc.)
     #[error("Time error: {0}")]
     TimeError(String),
+
+    /// Embedding provider failed (network, rate limit, bad response)
+    #[error("Embedding error: {0}")]
+    EmbeddingError(String),
+
+    /// The caller's session or capabilities don't allow the operation
+    #[error("Unauthorized: {reason}")]
+    Unauthorized {
+        /// Why access was denied
+        reason: String,
+    },
+
+    /// A write hook rejected the write
+    #[error("Write to '{key}' in namespace '{namespace}' rejected: {reason}")]
+    WriteRejected {
+        /// The namespace being written
+        namespace: String,
+        /// The key being written
+        key: String,
+        /// Why the validator rejected it
+        reason: String,
+    },
+
+    /// The database is shutting down and no longer accepts writes
+    #[error("Database is shutting down")]
+    ShuttingDown,
+
+    /// A query ran past its deadline
+    #[error("Query timed out after {timeout_ms}ms")]
+    QueryTimeout {
+        /// The query's time budget, in milliseconds
+        timeout_ms: u64,
+    },
+
+    /// A query was stopped through its cancellation token
+    #[error("Query cancelled")]
+    QueryCancelled,
+
+    /// A query exceeded one of its resource limits
+    #[error("Query exceeded its limit of {max} {limit}; narrow its filters or raise the limit")]
+    QueryLimitExceeded {
+        /// What was limited (e.g. "records scanned")
+        limit: String,
+        /// The limit that was exceeded
+        max: usize,
+    },
 }
 
 /// Result type alias for KoruDelta operations.
@@ -0,0 +1,692 @@
+/// Expression language for computed fields.
+///
+/// A small language for deriving a value from a document, used by
+/// [`Query::compute`](crate::query::Query::compute) and by views'
+/// computed projections. Expressions are evaluated per record before
+/// filtering, so filters, sorting and projection can use their results.
+///
+/// - **Fields**: `price`, `address.city`, `items.0.sku` (dot notation, as in
+///   filters); missing fields are `null`
+/// - **Literals**: `42`, `1.5`, `'text'` or `"text"`, `true`, `false`, `null`
+/// - **Arithmetic**: `+ - * / %` and unary `-`, with the usual precedence and
+///   parentheses; `+` on two strings concatenates
+/// - **Functions**: `concat(a, b, ...)`, `lower(s)`, `upper(s)`,
+///   `coalesce(a, b, ...)`, `round(x)` / `round(x, digits)` and
+///   `date_trunc('day', timestamp)` (units `year`, `month`, `day`, `hour`,
+///   `minute`; timestamps are RFC 3339 strings)
+///
+/// Operations on values of the wrong type (and division by zero) yield
+/// `null` rather than failing the query.
+///
+/// # Example
+///
+/// ```ignore
+/// use koru_delta::expr::Expr;
+///
+/// let total = Expr::parse("price * qty")?;
+/// assert_eq!(total.eval(&json!({"price": 3, "qty": 4})), json!(12));
+///
+/// let day = Expr::parse("date_trunc('day', created_at)")?;
+/// ```
+use std::fmt;
+
+use chrono::{DateTime, Datelike, SecondsFormat, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::error::{DeltaError, DeltaResult};
+use crate::query::get_field;
+
+/// A parsed expression.
+///
+/// Serializes as its source text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Expr {
+    /// A constant.
+    Literal(JsonValue),
+    /// A field of the document, in dot notation.
+    Field(String),
+    /// Arithmetic negation.
+    Neg(Box<Expr>),
+    /// A binary operation.
+    Binary {
+        /// The operator.
+        op: BinaryOp,
+        /// Left operand.
+        left: Box<Expr>,
+        /// Right operand.
+        right: Box<Expr>,
+    },
+    /// A function call.
+    Call {
+        /// The function.
+        function: Function,
+        /// Arguments.
+        args: Vec<Expr>,
+    },
+}
+
+/// Binary operators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    /// `+`: addition, or concatenation of two strings
+    Add,
+    /// `-`
+    Sub,
+    /// `*`
+    Mul,
+    /// `/`
+    Div,
+    /// `%`
+    Rem,
+}
+
+/// Built-in functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Function {
+    /// `concat(a, b, ...)`: arguments as strings, `null` as empty
+    Concat,
+    /// `lower(s)`
+    Lower,
+    /// `upper(s)`
+    Upper,
+    /// `coalesce(a, b, ...)`: first non-null argument
+    Coalesce,
+    /// `round(x)` or `round(x, digits)`
+    Round,
+    /// `date_trunc(unit, timestamp)`
+    DateTrunc,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "concat" => Self::Concat,
+            "lower" => Self::Lower,
+            "upper" => Self::Upper,
+            "coalesce" => Self::Coalesce,
+            "round" => Self::Round,
+            "date_trunc" => Self::DateTrunc,
+            _ => return None,
+        })
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Concat => "concat",
+            Self::Lower => "lower",
+            Self::Upper => "upper",
+            Self::Coalesce => "coalesce",
+            Self::Round => "round",
+            Self::DateTrunc => "date_trunc",
+        }
+    }
+
+    /// Allowed argument counts (inclusive).
+    fn arity(self) -> (usize, usize) {
+        match self {
+            Self::Concat | Self::Coalesce => (1, usize::MAX),
+            Self::Lower | Self::Upper => (1, 1),
+            Self::Round => (1, 2),
+            Self::DateTrunc => (2, 2),
+        }
+    }
+}
+
+impl Expr {
+    /// Parse an expression.
+    ///
+    /// Fails with [`DeltaError::InvalidData`] on syntax errors, unknown
+    /// functions and wrong argument counts.
+    pub fn parse(source: &str) -> DeltaResult<Self> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.additive()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(token) => Err(syntax_error(format!("unexpected {}", token))),
+        }
+    }
+
+    /// Fields the expression reads, in dot notation.
+    pub fn fields(&self) -> Vec<&str> {
+        let mut fields = Vec::new();
+        self.collect_fields(&mut fields);
+        fields
+    }
+
+    fn collect_fields<'a>(&'a self, fields: &mut Vec<&'a str>) {
+        match self {
+            Expr::Literal(_) => {}
+            Expr::Field(path) => fields.push(path),
+            Expr::Neg(operand) => operand.collect_fields(fields),
+            Expr::Binary { left, right, .. } => {
+                left.collect_fields(fields);
+                right.collect_fields(fields);
+            }
+            Expr::Call { args, .. } => {
+                for arg in args {
+                    arg.collect_fields(fields);
+                }
+            }
+        }
+    }
+
+    /// Evaluate the expression against a document.
+    pub fn eval(&self, document: &JsonValue) -> JsonValue {
+        match self {
+            Expr::Literal(value) => value.clone(),
+            Expr::Field(path) => get_field(document, path).unwrap_or(JsonValue::Null),
+            Expr::Neg(operand) => match operand.eval(document) {
+                JsonValue::Number(n) => match n.as_i64() {
+                    Some(i) => i
+                        .checked_neg()
+                        .map(JsonValue::from)
+                        .unwrap_or(JsonValue::Null),
+                    None => number(-n.as_f64().unwrap_or(f64::NAN)),
+                },
+                _ => JsonValue::Null,
+            },
+            Expr::Binary { op, left, right } => {
+                arithmetic(*op, &left.eval(document), &right.eval(document))
+            }
+            Expr::Call { function, args } => {
+                let args: Vec<JsonValue> = args.iter().map(|arg| arg.eval(document)).collect();
+                call(*function, &args)
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for Expr {
+    type Error = DeltaError;
+
+    fn try_from(source: String) -> DeltaResult<Self> {
+        Self::parse(&source)
+    }
+}
+
+impl From<Expr> for String {
+    fn from(expr: Expr) -> Self {
+        expr.to_string()
+    }
+}
+
+impl std::str::FromStr for Expr {
+    type Err = DeltaError;
+
+    fn from_str(source: &str) -> DeltaResult<Self> {
+        Self::parse(source)
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Literal(JsonValue::String(s)) => {
+                write!(f, "'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"))
+            }
+            Expr::Literal(value) => write!(f, "{}", value),
+            Expr::Field(path) => write!(f, "{}", path),
+            Expr::Neg(operand) => match operand.as_ref() {
+                Expr::Binary { .. } | Expr::Neg(_) => write!(f, "-({})", operand),
+                _ => write!(f, "-{}", operand),
+            },
+            Expr::Binary { op, left, right } => {
+                let symbol = match op {
+                    BinaryOp::Add => "+",
+                    BinaryOp::Sub => "-",
+                    BinaryOp::Mul => "*",
+                    BinaryOp::Div => "/",
+                    BinaryOp::Rem => "%",
+                };
+                write_operand(f, left)?;
+                write!(f, " {} ", symbol)?;
+                write_operand(f, right)
+            }
+            Expr::Call { function, args } => {
+                write!(f, "{}(", function.name())?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// Binary operands are parenthesized so the text parses back the same.
+fn write_operand(f: &mut fmt::Formatter<'_>, operand: &Expr) -> fmt::Result {
+    match operand {
+        Expr::Binary { .. } => write!(f, "({})", operand),
+        _ => write!(f, "{}", operand),
+    }
+}
+
+fn arithmetic(op: BinaryOp, left: &JsonValue, right: &JsonValue) -> JsonValue {
+    if let (BinaryOp::Add, JsonValue::String(a), JsonValue::String(b)) = (op, left, right) {
+        return JsonValue::String(format!("{}{}", a, b));
+    }
+    let (JsonValue::Number(a), JsonValue::Number(b)) = (left, right) else {
+        return JsonValue::Null;
+    };
+
+    // Integers stay integers unless the result does not fit
+    if let (Some(a), Some(b)) = (a.as_i64(), b.as_i64()) {
+        let exact = match op {
+            BinaryOp::Add => a.checked_add(b),
+            BinaryOp::Sub => a.checked_sub(b),
+            BinaryOp::Mul => a.checked_mul(b),
+            BinaryOp::Rem => a.checked_rem(b),
+            BinaryOp::Div if b != 0 && a % b == 0 => a.checked_div(b),
+            BinaryOp::Div => None,
+        };
+        if let Some(result) = exact {
+            return JsonValue::from(result);
+        }
+        if matches!(op, BinaryOp::Rem) {
+            return JsonValue::Null;
+        }
+    }
+
+    let (Some(a), Some(b)) = (a.as_f64(), b.as_f64()) else {
+        return JsonValue::Null;
+    };
+    match op {
+        BinaryOp::Add => number(a + b),
+        BinaryOp::Sub => number(a - b),
+        BinaryOp::Mul => number(a * b),
+        BinaryOp::Div if b != 0.0 => number(a / b),
+        BinaryOp::Rem if b != 0.0 => number(a % b),
+        BinaryOp::Div | BinaryOp::Rem => JsonValue::Null,
+    }
+}
+
+fn call(function: Function, args: &[JsonValue]) -> JsonValue {
+    match function {
+        Function::Concat => JsonValue::String(
+            args.iter()
+                .map(|arg| match arg {
+                    JsonValue::Null => String::new(),
+                    JsonValue::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .collect(),
+        ),
+        Function::Lower => args[0]
+            .as_str()
+            .map(|s| JsonValue::String(s.to_lowercase()))
+            .unwrap_or(JsonValue::Null),
+        Function::Upper => args[0]
+            .as_str()
+            .map(|s| JsonValue::String(s.to_uppercase()))
+            .unwrap_or(JsonValue::Null),
+        Function::Coalesce => args
+            .iter()
+            .find(|arg| !arg.is_null())
+            .cloned()
+            .unwrap_or(JsonValue::Null),
+        Function::Round => {
+            let digits = args.get(1).and_then(JsonValue::as_i64).unwrap_or(0);
+            match args[0].as_f64() {
+                Some(x) if digits <= 0 => {
+                    let rounded = x.round();
+                    if rounded.abs() < i64::MAX as f64 {
+                        JsonValue::from(rounded as i64)
+                    } else {
+                        number(rounded)
+                    }
+                }
+                Some(x) => {
+                    let scale = 10f64.powi(digits.min(15) as i32);
+                    number((x * scale).round() / scale)
+                }
+                None => JsonValue::Null,
+            }
+        }
+        Function::DateTrunc => match (args[0].as_str(), args[1].as_str()) {
+            (Some(unit), Some(timestamp)) => date_trunc(unit, timestamp)
+                .map(|t| JsonValue::String(t.to_rfc3339_opts(SecondsFormat::Secs, true)))
+                .unwrap_or(JsonValue::Null),
+            _ => JsonValue::Null,
+        },
+    }
+}
+
+fn date_trunc(unit: &str, timestamp: &str) -> Option<DateTime<Utc>> {
+    let t = DateTime::parse_from_rfc3339(timestamp)
+        .ok()?
+        .with_timezone(&Utc);
+    let (month, day, hour, minute) = match unit {
+        "year" => (1, 1, 0, 0),
+        "month" => (t.month(), 1, 0, 0),
+        "day" => (t.month(), t.day(), 0, 0),
+        "hour" => (t.month(), t.day(), t.hour(), 0),
+        "minute" => (t.month(), t.day(), t.hour(), t.minute()),
+        _ => return None,
+    };
+    Utc.with_ymd_and_hms(t.year(), month, day, hour, minute, 0)
+        .single()
+}
+
+fn number(x: f64) -> JsonValue {
+    serde_json::Number::from_f64(x)
+        .map(JsonValue::Number)
+        .unwrap_or(JsonValue::Null)
+}
+
+fn syntax_error(message: impl fmt::Display) -> DeltaError {
+    DeltaError::InvalidData {
+        reason: format!("Invalid expression: {}", message),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(serde_json::Number),
+    Str(String),
+    Ident(String),
+    Op(char),
+    LParen,
+    RParen,
+    Comma,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "number {}", n),
+            Token::Str(s) => write!(f, "string '{}'", s),
+            Token::Ident(name) => write!(f, "'{}'", name),
+            Token::Op(op) => write!(f, "'{}'", op),
+            Token::LParen => write!(f, "'('"),
+            Token::RParen => write!(f, "')'"),
+            Token::Comma => write!(f, "','"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> DeltaResult<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '+' | '-' | '*' | '/' | '%' => {
+                chars.next();
+                tokens.push(Token::Op(c));
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            ',' => {
+                chars.next();
+                tokens.push(Token::Comma);
+            }
+            '\'' | '"' => {
+                chars.next();
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => match chars.next() {
+                            Some(escaped) => s.push(escaped),
+                            None => return Err(syntax_error("unterminated string")),
+                        },
+                        Some(end) if end == c => break,
+                        Some(other) => s.push(other),
+                        None => return Err(syntax_error("unterminated string")),
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            c if c.is_ascii_digit() => {
+                let mut text = String::new();
+                while let Some(&d) = chars.peek() {
+                    if d.is_ascii_digit() || d == '.' || d == 'e' || d == 'E' {
+                        text.push(d);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let number = text
+                    .parse::<i64>()
+                    .map(serde_json::Number::from)
+                    .ok()
+                    .or_else(|| {
+                        text.parse::<f64>()
+                            .ok()
+                            .and_then(serde_json::Number::from_f64)
+                    })
+                    .ok_or_else(|| syntax_error(format!("bad number '{}'", text)))?;
+                tokens.push(Token::Number(number));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut name = String::new();
+                while let Some(&d) = chars.peek() {
+                    if d.is_alphanumeric() || d == '_' || d == '.' {
+                        name.push(d);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Ident(name));
+            }
+            other => return Err(syntax_error(format!("unexpected '{}'", other))),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive-descent parser over tokens.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> DeltaResult<()> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(syntax_error(format!(
+                "expected {}, found {}",
+                expected, token
+            ))),
+            None => Err(syntax_error(format!("expected {}", expected))),
+        }
+    }
+
+    fn additive(&mut self) -> DeltaResult<Expr> {
+        let mut left = self.multiplicative()?;
+        while let Some(Token::Op(c @ ('+' | '-'))) = self.peek() {
+            let op = if *c == '+' {
+                BinaryOp::Add
+            } else {
+                BinaryOp::Sub
+            };
+            self.pos += 1;
+            let right = self.multiplicative()?;
+            left = binary(op, left, right);
+        }
+        Ok(left)
+    }
+
+    fn multiplicative(&mut self) -> DeltaResult<Expr> {
+        let mut left = self.unary()?;
+        while let Some(Token::Op(c @ ('*' | '/' | '%'))) = self.peek() {
+            let op = match c {
+                '*' => BinaryOp::Mul,
+                '/' => BinaryOp::Div,
+                _ => BinaryOp::Rem,
+            };
+            self.pos += 1;
+            let right = self.unary()?;
+            left = binary(op, left, right);
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> DeltaResult<Expr> {
+        if let Some(Token::Op('-')) = self.peek() {
+            self.pos += 1;
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> DeltaResult<Expr> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Literal(JsonValue::Number(n))),
+            Some(Token::Str(s)) => Ok(Expr::Literal(JsonValue::String(s))),
+            Some(Token::LParen) => {
+                let expr = self.additive()?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Some(Token::Ident(name)) if self.peek() == Some(&Token::LParen) => {
+                self.pos += 1;
+                let function = Function::from_name(&name)
+                    .ok_or_else(|| syntax_error(format!("unknown function '{}'", name)))?;
+                let mut args = Vec::new();
+                if self.peek() != Some(&Token::RParen) {
+                    loop {
+                        args.push(self.additive()?);
+                        if self.peek() == Some(&Token::Comma) {
+                            self.pos += 1;
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                self.expect(Token::RParen)?;
+                let (min, max) = function.arity();
+                if args.len() < min || args.len() > max {
+                    return Err(syntax_error(format!(
+                        "wrong number of arguments to {}",
+                        function.name()
+                    )));
+                }
+                Ok(Expr::Call { function, args })
+            }
+            Some(Token::Ident(name)) => Ok(match name.as_str() {
+                "true" => Expr::Literal(JsonValue::Bool(true)),
+                "false" => Expr::Literal(JsonValue::Bool(false)),
+                "null" => Expr::Literal(JsonValue::Null),
+                _ => Expr::Field(name),
+            }),
+            Some(token) => Err(syntax_error(format!("unexpected {}", token))),
+            None => Err(syntax_error("unexpected end of expression")),
+        }
+    }
+}
+
+fn binary(op: BinaryOp, left: Expr, right: Expr) -> Expr {
+    Expr::Binary {
+        op,
+        left: Box::new(left),
+        right: Box::new(right),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn eval(source: &str, document: JsonValue) -> JsonValue {
+        Expr::parse(source).unwrap().eval(&document)
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let order = json!({"price": 2.5, "qty": 4, "discount": 1});
+        assert_eq!(eval("price * qty", order.clone()), json!(10.0));
+        assert_eq!(eval("qty * 3 - discount", order.clone()), json!(11));
+        assert_eq!(eval("(qty + 1) * 2", order.clone()), json!(10));
+        assert_eq!(eval("qty / 2", order.clone()), json!(2));
+        assert_eq!(eval("qty / 8", order.clone()), json!(0.5));
+        assert_eq!(eval("-qty % 3", order.clone()), json!(-1));
+        assert_eq!(eval("qty / 0", order.clone()), JsonValue::Null);
+        assert_eq!(eval("missing * 2", order.clone()), JsonValue::Null);
+        assert_eq!(eval("round(price / 3, 2)", order), json!(0.83));
+    }
+
+    #[test]
+    fn test_strings_and_dates() {
+        let user = json!({
+            "first": "Ada",
+            "last": "Lovelace",
+            "address": {"city": "London"},
+            "joined": "2024-03-15T13:45:12+02:00"
+        });
+        assert_eq!(
+            eval("first + ' ' + last", user.clone()),
+            json!("Ada Lovelace")
+        );
+        assert_eq!(
+            eval(
+                "concat(upper(last), ', ', address.city, nickname)",
+                user.clone()
+            ),
+            json!("LOVELACE, London")
+        );
+        assert_eq!(
+            eval("coalesce(nickname, first)", user.clone()),
+            json!("Ada")
+        );
+        assert_eq!(
+            eval("date_trunc('day', joined)", user.clone()),
+            json!("2024-03-15T00:00:00Z")
+        );
+        assert_eq!(
+            eval("date_trunc('hour', joined)", user.clone()),
+            json!("2024-03-15T11:00:00Z")
+        );
+        assert_eq!(eval("date_trunc('month', first)", user), JsonValue::Null);
+    }
+
+    #[test]
+    fn test_parse_errors_and_round_trip() {
+        for bad in [
+            "price *",
+            "(price",
+            "nope(1)",
+            "lower(a, b)",
+            "price $ 2",
+            "'open",
+        ] {
+            assert!(
+                matches!(Expr::parse(bad), Err(DeltaError::InvalidData { .. })),
+                "{} should not parse",
+                bad
+            );
+        }
+
+        let expr = Expr::parse("-(a - b) * (c + 2) / concat('it\\'s', d)").unwrap();
+        let text = serde_json::to_string(&expr).unwrap();
+        let back: Expr = serde_json::from_str(&text).unwrap();
+        assert_eq!(back, expr);
+    }
+}
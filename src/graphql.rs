@@ -0,0 +1,415 @@
+/// GraphQL API for KoruDelta.
+///
+/// This module exposes namespaces and views as a GraphQL schema so
+/// frontends can consume KoruDelta directly:
+///
+/// - `namespaces` / `namespace(name)` - keys, records and filtered queries
+/// - `Record.history` / `Record.at(timestamp)` - history and time travel
+/// - `views` / `view(name)` - materialized views and their source namespace
+/// - `subscription { changes(namespace, key) }` - live changes over WebSocket
+///
+/// With the `graphql` feature, the HTTP router serves queries at
+/// `/graphql` and subscriptions (graphql-ws / graphql-transport-ws) at
+/// `/graphql/ws`.
+///
+/// # Example
+///
+/// ```graphql
+/// {
+///   namespace(name: "users") {
+///     record(key: "alice") {
+///       value
+///       history { value timestamp }
+///     }
+///   }
+/// }
+/// ```
+use crate::core::KoruDelta;
+use crate::query::{Query, QueryResult};
+use crate::subscriptions::{ChangeEvent, ChangeType, Subscription, SubscriptionGuard};
+use async_graphql::{
+    ComplexObject, Context, EmptyMutation, Enum, Json, Object, Schema, SimpleObject,
+};
+use async_graphql_axum::{GraphQL, GraphQLSubscription};
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use serde_json::Value as JsonValue;
+use std::sync::Arc;
+
+/// The KoruDelta GraphQL schema.
+pub type DeltaSchema = Schema<QueryRoot, EmptyMutation, SubscriptionRoot>;
+
+/// Build the schema for a database.
+pub fn schema(db: Arc<KoruDelta>) -> DeltaSchema {
+    Schema::build(QueryRoot, EmptyMutation, SubscriptionRoot)
+        .data(db)
+        .finish()
+}
+
+/// Mount `/graphql` and `/graphql/ws` on a router.
+pub fn routes(router: axum::Router, db: Arc<KoruDelta>) -> axum::Router {
+    let schema = schema(db);
+    router
+        .route_service("/graphql", GraphQL::new(schema.clone()))
+        .route_service("/graphql/ws", GraphQLSubscription::new(schema))
+}
+
+fn db<'a>(ctx: &Context<'a>) -> &'a Arc<KoruDelta> {
+    ctx.data_unchecked::<Arc<KoruDelta>>()
+}
+
+/// Root query type.
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// All namespaces with data.
+    async fn namespaces(&self, ctx: &Context<'_>) -> Vec<Namespace> {
+        db(ctx)
+            .list_namespaces()
+            .await
+            .into_iter()
+            .map(|name| Namespace { name })
+            .collect()
+    }
+
+    /// A namespace by name.
+    async fn namespace(&self, name: String) -> Namespace {
+        Namespace { name }
+    }
+
+    /// All materialized views.
+    async fn views(&self, ctx: &Context<'_>) -> Vec<View> {
+        db(ctx)
+            .list_views()
+            .await
+            .into_iter()
+            .map(View::from)
+            .collect()
+    }
+
+    /// A materialized view by name.
+    async fn view(&self, ctx: &Context<'_>, name: String) -> Option<View> {
+        db(ctx)
+            .list_views()
+            .await
+            .into_iter()
+            .find(|view| view.name == name)
+            .map(View::from)
+    }
+}
+
+/// A namespace of keys.
+pub struct Namespace {
+    name: String,
+}
+
+#[Object]
+impl Namespace {
+    /// Namespace name.
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Keys in the namespace.
+    async fn keys(&self, ctx: &Context<'_>) -> Vec<String> {
+        db(ctx).list_keys(&self.name).await
+    }
+
+    /// The current record for a key, if it exists and is not deleted.
+    async fn record(&self, ctx: &Context<'_>, key: String) -> Option<Record> {
+        let versioned = db(ctx).get(&self.name, &key).await.ok()?;
+        if versioned.value().is_null() {
+            return None;
+        }
+        Some(Record {
+            namespace: self.name.clone(),
+            key,
+            value: Json(versioned.value().clone()),
+            version_id: versioned.version_id().to_string(),
+            timestamp: versioned.timestamp(),
+        })
+    }
+
+    /// Records matching a JSON-encoded query (all records if omitted).
+    async fn records(
+        &self,
+        ctx: &Context<'_>,
+        query: Option<Json<Query>>,
+    ) -> async_graphql::Result<RecordPage> {
+        let query = query.map(|q| q.0).unwrap_or_default();
+        let result = db(ctx).query(&self.name, query).await?;
+        Ok(RecordPage::new(&self.name, result))
+    }
+}
+
+/// The current version of a key.
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct Record {
+    namespace: String,
+    key: String,
+    value: Json<JsonValue>,
+    version_id: String,
+    timestamp: DateTime<Utc>,
+}
+
+#[ComplexObject]
+impl Record {
+    /// Every version of the key, oldest first.
+    async fn history(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Version>> {
+        let history = db(ctx).history(&self.namespace, &self.key).await?;
+        Ok(history
+            .into_iter()
+            .map(|entry| Version {
+                value: Json(entry.value),
+                version_id: entry.version_id,
+                timestamp: entry.timestamp,
+            })
+            .collect())
+    }
+
+    /// The version current at a point in time.
+    async fn at(&self, ctx: &Context<'_>, timestamp: DateTime<Utc>) -> Option<Version> {
+        let versioned = db(ctx)
+            .get_at(&self.namespace, &self.key, timestamp)
+            .await
+            .ok()?;
+        Some(Version {
+            value: Json(versioned.value().clone()),
+            version_id: versioned.version_id().to_string(),
+            timestamp: versioned.timestamp(),
+        })
+    }
+}
+
+/// One version of a key.
+#[derive(SimpleObject)]
+pub struct Version {
+    value: Json<JsonValue>,
+    version_id: String,
+    timestamp: DateTime<Utc>,
+}
+
+/// Records returned by a query or view.
+#[derive(SimpleObject)]
+pub struct RecordPage {
+    /// Matching records before limit and offset
+    total: usize,
+    records: Vec<Record>,
+    aggregation: Option<Json<JsonValue>>,
+}
+
+impl RecordPage {
+    fn new(namespace: &str, result: QueryResult) -> Self {
+        let records = result
+            .records
+            .into_iter()
+            .map(|record| Record {
+                namespace: namespace.to_string(),
+                key: record.key,
+                value: Json(record.value),
+                version_id: record.version_id,
+                timestamp: record.timestamp,
+            })
+            .collect();
+        Self {
+            total: result.total_count,
+            records,
+            aggregation: result.aggregation.map(Json),
+        }
+    }
+}
+
+/// A materialized view.
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct View {
+    name: String,
+    description: Option<String>,
+    source_namespace: String,
+    auto_refresh: bool,
+    record_count: usize,
+    last_refreshed: DateTime<Utc>,
+    /// Storage sequence number the view reflects
+    snapshot_sequence: u64,
+}
+
+impl From<crate::views::ViewInfo> for View {
+    fn from(info: crate::views::ViewInfo) -> Self {
+        Self {
+            name: info.name,
+            description: info.description,
+            source_namespace: info.source_collection,
+            auto_refresh: info.auto_refresh,
+            record_count: info.record_count,
+            last_refreshed: info.last_refreshed,
+            snapshot_sequence: info.snapshot_sequence,
+        }
+    }
+}
+
+#[ComplexObject]
+impl View {
+    /// The namespace the view is computed from.
+    async fn source(&self) -> Namespace {
+        Namespace {
+            name: self.source_namespace.clone(),
+        }
+    }
+
+    /// The view's materialized records.
+    async fn records(&self, ctx: &Context<'_>) -> async_graphql::Result<RecordPage> {
+        let result = db(ctx).query_view(&self.name).await?;
+        Ok(RecordPage::new(&self.source_namespace, result))
+    }
+}
+
+/// Kind of change.
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// A new key was written
+    Insert,
+    /// An existing key was overwritten
+    Update,
+    /// A key was deleted
+    Delete,
+}
+
+/// A change to a key.
+#[derive(SimpleObject)]
+pub struct Change {
+    kind: ChangeKind,
+    namespace: String,
+    key: String,
+    value: Option<Json<JsonValue>>,
+    previous_value: Option<Json<JsonValue>>,
+    version_id: Option<String>,
+    timestamp: DateTime<Utc>,
+    /// Position among the key's published changes
+    sequence: u64,
+}
+
+impl From<ChangeEvent> for Change {
+    fn from(event: ChangeEvent) -> Self {
+        let kind = match event.change_type {
+            ChangeType::Insert => ChangeKind::Insert,
+            ChangeType::Update => ChangeKind::Update,
+            ChangeType::Delete => ChangeKind::Delete,
+        };
+        Self {
+            kind,
+            namespace: event.collection,
+            key: event.key,
+            value: event.value.map(Json),
+            previous_value: event.previous_value.map(Json),
+            version_id: event.version_id,
+            timestamp: event.timestamp,
+            sequence: event.sequence,
+        }
+    }
+}
+
+/// Root subscription type.
+pub struct SubscriptionRoot;
+
+#[async_graphql::Subscription]
+impl SubscriptionRoot {
+    /// Changes in a namespace, or to one key in it.
+    async fn changes(
+        &self,
+        ctx: &Context<'_>,
+        namespace: String,
+        key: Option<String>,
+    ) -> impl Stream<Item = Change> + use<> {
+        use tokio::sync::broadcast::error::RecvError;
+
+        let db = db(ctx);
+        let subscription = match key {
+            Some(key) => Subscription::key(namespace, key),
+            None => Subscription::collection(namespace),
+        };
+        let (id, receiver) = db.subscribe(subscription).await;
+        let guard = SubscriptionGuard::new(Arc::clone(db.subscription_manager()), id);
+
+        futures::stream::unfold((receiver, guard), |(mut receiver, guard)| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => return Some((Change::from(event), (receiver, guard))),
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_namespace_history_and_views() {
+        let db = Arc::new(KoruDelta::start().await.unwrap());
+        db.put("users", "alice", json!({"age": 30})).await.unwrap();
+        db.put("users", "alice", json!({"age": 31})).await.unwrap();
+        db.put("users", "bob", json!({"age": 20})).await.unwrap();
+        db.create_view(
+            crate::views::ViewDefinition::new("adults", "users")
+                .with_query(Query::new().filter(crate::query::Filter::gte("age", 21))),
+        )
+        .await
+        .unwrap();
+        let schema = schema(Arc::clone(&db));
+
+        let response = schema
+            .execute(
+                r#"{
+                    namespace(name: "users") {
+                        record(key: "alice") { value history { value } }
+                        records(query: {filters: [{Eq: {field: "age", value: 20}}]}) {
+                            total
+                            records { key }
+                        }
+                    }
+                    view(name: "adults") {
+                        source { name }
+                        records { records { key value } }
+                    }
+                }"#,
+            )
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        let users = &data["namespace"];
+        assert_eq!(users["record"]["value"], json!({"age": 31}));
+        assert_eq!(users["record"]["history"][0]["value"], json!({"age": 30}));
+        assert_eq!(users["records"]["total"], 1);
+        assert_eq!(users["records"]["records"][0]["key"], "bob");
+        assert_eq!(data["view"]["source"]["name"], "users");
+        assert_eq!(data["view"]["records"]["records"][0]["key"], "alice");
+    }
+
+    #[tokio::test]
+    async fn test_change_subscription() {
+        let db = Arc::new(KoruDelta::start().await.unwrap());
+        let schema = schema(Arc::clone(&db));
+
+        let mut changes =
+            schema.execute_stream(r#"subscription { changes(namespace: "users") { kind key } }"#);
+        let writer = {
+            let db = Arc::clone(&db);
+            tokio::spawn(async move {
+                // Give the subscription time to register
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                db.put_notify("users", "alice", json!(1)).await.unwrap();
+            })
+        };
+
+        let change = changes.next().await.unwrap().data.into_json().unwrap();
+        writer.await.unwrap();
+        assert_eq!(change["changes"], json!({"kind": "INSERT", "key": "alice"}));
+    }
+}
@@ -0,0 +1,483 @@
+/// gRPC API for KoruDelta.
+///
+/// This module provides a tonic service exposing the core operations for
+/// service-to-service use:
+///
+/// - `Put` / `Get` (with optional time travel)
+/// - `History`
+/// - `Query` with a JSON-encoded [`Query`](crate::query::Query)
+/// - `Subscribe`, a server stream of change events
+///
+/// Values travel as JSON strings. The wire schema is in
+/// `proto/koru_delta.proto` for generating clients in other languages; a
+/// Rust client is generated as [`koru_delta_client::KoruDeltaClient`].
+///
+/// # Example
+///
+/// ```ignore
+/// use koru_delta::grpc::GrpcServer;
+///
+/// let db = KoruDelta::start().await?;
+/// GrpcServer::new(db).bind("0.0.0.0:50051").await?;
+/// ```
+use crate::core::KoruDelta;
+use crate::error::{DeltaError, DeltaResult};
+use crate::subscriptions::{self, ChangeType, Subscription, SubscriptionGuard};
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use serde_json::Value as JsonValue;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+include!(concat!(env!("OUT_DIR"), "/koru_delta.v1.KoruDelta.rs"));
+
+use koru_delta_server::KoruDeltaServer;
+
+/// Request for `Put`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PutRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: String,
+    #[prost(string, tag = "2")]
+    pub key: String,
+    #[prost(string, tag = "3")]
+    pub value_json: String,
+}
+
+/// Request for `Get`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: String,
+    #[prost(string, tag = "2")]
+    pub key: String,
+    /// Return the value as of this RFC 3339 timestamp
+    #[prost(string, optional, tag = "3")]
+    pub at: Option<String>,
+}
+
+/// A stored version, returned by `Put` and `Get`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct VersionedValue {
+    #[prost(string, tag = "1")]
+    pub value_json: String,
+    #[prost(string, tag = "2")]
+    pub version_id: String,
+    /// RFC 3339
+    #[prost(string, tag = "3")]
+    pub timestamp: String,
+    #[prost(string, optional, tag = "4")]
+    pub previous_version: Option<String>,
+}
+
+/// Request for `History`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct HistoryRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: String,
+    #[prost(string, tag = "2")]
+    pub key: String,
+}
+
+/// One version in a key's history.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct HistoryEntry {
+    #[prost(string, tag = "1")]
+    pub value_json: String,
+    #[prost(string, tag = "2")]
+    pub version_id: String,
+    #[prost(string, tag = "3")]
+    pub timestamp: String,
+}
+
+/// Response for `History`, oldest version first.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct HistoryResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub entries: Vec<HistoryEntry>,
+}
+
+/// Request for `Query`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: String,
+    /// A serialized [`Query`](crate::query::Query); empty means all records
+    #[prost(string, tag = "2")]
+    pub query_json: String,
+}
+
+/// One record in a query result.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryRecord {
+    #[prost(string, tag = "1")]
+    pub key: String,
+    #[prost(string, tag = "2")]
+    pub value_json: String,
+    #[prost(string, tag = "3")]
+    pub version_id: String,
+    #[prost(string, tag = "4")]
+    pub timestamp: String,
+}
+
+/// Response for `Query`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub records: Vec<QueryRecord>,
+    /// Matching records before limit and offset
+    #[prost(uint64, tag = "2")]
+    pub total_count: u64,
+    #[prost(string, optional, tag = "3")]
+    pub aggregation_json: Option<String>,
+}
+
+/// Request for `Subscribe`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SubscribeRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: String,
+    /// Only stream changes to this key
+    #[prost(string, optional, tag = "2")]
+    pub key: Option<String>,
+}
+
+/// A change streamed by `Subscribe`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ChangeEvent {
+    /// `insert`, `update` or `delete`
+    #[prost(string, tag = "1")]
+    pub change_type: String,
+    #[prost(string, tag = "2")]
+    pub namespace: String,
+    #[prost(string, tag = "3")]
+    pub key: String,
+    #[prost(string, optional, tag = "4")]
+    pub value_json: Option<String>,
+    #[prost(string, optional, tag = "5")]
+    pub previous_value_json: Option<String>,
+    #[prost(string, tag = "6")]
+    pub timestamp: String,
+    #[prost(string, optional, tag = "7")]
+    pub version_id: Option<String>,
+    #[prost(string, optional, tag = "8")]
+    pub previous_version_id: Option<String>,
+    /// Position among the key's published changes (0 if unsequenced)
+    #[prost(uint64, tag = "9")]
+    pub sequence: u64,
+}
+
+impl From<subscriptions::ChangeEvent> for ChangeEvent {
+    fn from(event: subscriptions::ChangeEvent) -> Self {
+        let change_type = match event.change_type {
+            ChangeType::Insert => "insert",
+            ChangeType::Update => "update",
+            ChangeType::Delete => "delete",
+        };
+        Self {
+            change_type: change_type.to_string(),
+            namespace: event.collection,
+            key: event.key,
+            value_json: event.value.map(|v| v.to_string()),
+            previous_value_json: event.previous_value.map(|v| v.to_string()),
+            timestamp: event.timestamp.to_rfc3339(),
+            version_id: event.version_id,
+            previous_version_id: event.previous_version_id,
+            sequence: event.sequence,
+        }
+    }
+}
+
+/// gRPC server for KoruDelta.
+pub struct GrpcServer {
+    db: KoruDelta,
+}
+
+impl GrpcServer {
+    /// Create a new gRPC server with the given database.
+    pub fn new(db: KoruDelta) -> Self {
+        Self { db }
+    }
+
+    /// Start the gRPC server on the given address.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// server.bind("0.0.0.0:50051").await?;
+    /// ```
+    pub async fn bind(self, addr: &str) -> DeltaResult<()> {
+        let addr: SocketAddr = addr
+            .parse()
+            .map_err(|e| DeltaError::StorageError(format!("Invalid address: {}", e)))?;
+
+        tonic::transport::Server::builder()
+            .add_service(service(Arc::new(self.db)))
+            .serve(addr)
+            .await
+            .map_err(|e| DeltaError::StorageError(format!("Server error: {}", e)))
+    }
+}
+
+/// Build the tonic service, for mounting alongside other services.
+pub fn service(db: Arc<KoruDelta>) -> KoruDeltaServer<KoruDeltaService> {
+    KoruDeltaServer::new(KoruDeltaService { db })
+}
+
+/// Implementation of the `koru_delta.v1.KoruDelta` service.
+pub struct KoruDeltaService {
+    db: Arc<KoruDelta>,
+}
+
+type ChangeStream = Pin<Box<dyn Stream<Item = Result<ChangeEvent, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl koru_delta_server::KoruDelta for KoruDeltaService {
+    async fn put(&self, request: Request<PutRequest>) -> Result<Response<VersionedValue>, Status> {
+        let request = request.into_inner();
+        let value = parse_json(&request.value_json)?;
+        let versioned = self
+            .db
+            .put_notify(&request.namespace, &request.key, value)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(versioned_value(&versioned)))
+    }
+
+    async fn get(&self, request: Request<GetRequest>) -> Result<Response<VersionedValue>, Status> {
+        let request = request.into_inner();
+        let versioned = match request.at {
+            Some(at) => {
+                let at = parse_timestamp(&at)?;
+                self.db.get_at(&request.namespace, &request.key, at).await
+            }
+            None => self.db.get(&request.namespace, &request.key).await,
+        }
+        .map_err(status)?;
+        Ok(Response::new(versioned_value(&versioned)))
+    }
+
+    async fn history(
+        &self,
+        request: Request<HistoryRequest>,
+    ) -> Result<Response<HistoryResponse>, Status> {
+        let request = request.into_inner();
+        let history = self
+            .db
+            .history(&request.namespace, &request.key)
+            .await
+            .map_err(status)?;
+        let entries = history
+            .into_iter()
+            .map(|entry| HistoryEntry {
+                value_json: entry.value.to_string(),
+                version_id: entry.version_id,
+                timestamp: entry.timestamp.to_rfc3339(),
+            })
+            .collect();
+        Ok(Response::new(HistoryResponse { entries }))
+    }
+
+    async fn query(
+        &self,
+        request: Request<QueryRequest>,
+    ) -> Result<Response<QueryResponse>, Status> {
+        let request = request.into_inner();
+        let query = if request.query_json.trim().is_empty() {
+            crate::query::Query::default()
+        } else {
+            serde_json::from_str(&request.query_json)
+                .map_err(|e| Status::invalid_argument(format!("invalid query: {}", e)))?
+        };
+        let result = self
+            .db
+            .query(&request.namespace, query)
+            .await
+            .map_err(status)?;
+        let records = result
+            .records
+            .into_iter()
+            .map(|record| QueryRecord {
+                key: record.key,
+                value_json: record.value.to_string(),
+                version_id: record.version_id,
+                timestamp: record.timestamp.to_rfc3339(),
+            })
+            .collect();
+        Ok(Response::new(QueryResponse {
+            records,
+            total_count: result.total_count as u64,
+            aggregation_json: result.aggregation.map(|v| v.to_string()),
+        }))
+    }
+
+    type SubscribeStream = ChangeStream;
+
+    async fn subscribe(
+        &self,
+        request: Request<SubscribeRequest>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        use tokio::sync::broadcast::error::RecvError;
+
+        let request = request.into_inner();
+        let subscription = match request.key {
+            Some(key) => Subscription::key(request.namespace, key),
+            None => Subscription::collection(request.namespace),
+        };
+        let (id, receiver) = self.db.subscribe(subscription).await;
+        let guard = SubscriptionGuard::new(Arc::clone(self.db.subscription_manager()), id);
+
+        let stream =
+            futures::stream::unfold((receiver, guard), |(mut receiver, guard)| async move {
+                match receiver.recv().await {
+                    Ok(event) => Some((Ok(ChangeEvent::from(event)), (receiver, guard))),
+                    Err(RecvError::Lagged(skipped)) => Some((
+                        Err(Status::data_loss(format!(
+                            "{} changes were dropped",
+                            skipped
+                        ))),
+                        (receiver, guard),
+                    )),
+                    Err(RecvError::Closed) => None,
+                }
+            });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+fn versioned_value(versioned: &crate::types::VersionedValue) -> VersionedValue {
+    VersionedValue {
+        value_json: versioned.value().to_string(),
+        version_id: versioned.version_id().to_string(),
+        timestamp: versioned.timestamp().to_rfc3339(),
+        previous_version: versioned.previous_version().map(|s| s.to_string()),
+    }
+}
+
+// Status is tonic's error type; boxing it here gains nothing
+#[allow(clippy::result_large_err)]
+fn parse_json(json: &str) -> Result<JsonValue, Status> {
+    serde_json::from_str(json).map_err(|e| Status::invalid_argument(format!("invalid JSON: {}", e)))
+}
+
+#[allow(clippy::result_large_err)]
+fn parse_timestamp(s: &str) -> Result<DateTime<Utc>, Status> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| Status::invalid_argument(format!("invalid timestamp: {}", e)))
+}
+
+fn status(error: DeltaError) -> Status {
+    match error {
+        DeltaError::KeyNotFound { .. } | DeltaError::NoValueAtTimestamp { .. } => {
+            Status::not_found(error.to_string())
+        }
+        DeltaError::SerializationError(_)
+        | DeltaError::InvalidData { .. }
+        | DeltaError::InvalidConfig { .. }
+        | DeltaError::WriteRejected { .. } => Status::invalid_argument(error.to_string()),
+        DeltaError::Unauthorized { .. } => Status::permission_denied(error.to_string()),
+        DeltaError::ShuttingDown => Status::unavailable(error.to_string()),
+        DeltaError::QueryTimeout { .. } => Status::deadline_exceeded(error.to_string()),
+        DeltaError::QueryCancelled => Status::cancelled(error.to_string()),
+        DeltaError::QueryLimitExceeded { .. } => Status::resource_exhausted(error.to_string()),
+        _ => Status::internal(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::koru_delta_client::KoruDeltaClient;
+    use super::*;
+    use serde_json::json;
+
+    async fn connect(db: Arc<KoruDelta>) -> KoruDeltaClient<tonic::transport::Channel> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let incoming =
+            tonic::transport::server::TcpIncoming::from_listener(listener, true, None).unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(service(db))
+                .serve_with_incoming(incoming),
+        );
+        KoruDeltaClient::connect(format!("http://{}", addr))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_grpc_round_trip() {
+        let db = Arc::new(KoruDelta::start().await.unwrap());
+        let mut client = connect(Arc::clone(&db)).await;
+
+        let mut changes = client
+            .subscribe(SubscribeRequest {
+                namespace: "users".to_string(),
+                key: None,
+            })
+            .await
+            .unwrap()
+            .into_inner();
+
+        for age in [30, 31] {
+            client
+                .put(PutRequest {
+                    namespace: "users".to_string(),
+                    key: "alice".to_string(),
+                    value_json: json!({ "age": age }).to_string(),
+                })
+                .await
+                .unwrap();
+        }
+
+        let current = client
+            .get(GetRequest {
+                namespace: "users".to_string(),
+                key: "alice".to_string(),
+                at: None,
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(current.value_json, json!({ "age": 31 }).to_string());
+
+        let history = client
+            .history(HistoryRequest {
+                namespace: "users".to_string(),
+                key: "alice".to_string(),
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(history.entries.len(), 2);
+
+        let query = crate::query::Query::new().filter(crate::query::Filter::gte("age", 31));
+        let result = client
+            .query(QueryRequest {
+                namespace: "users".to_string(),
+                query_json: serde_json::to_string(&query).unwrap(),
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(result.total_count, 1);
+
+        let first = changes.message().await.unwrap().unwrap();
+        assert_eq!(first.change_type, "insert");
+        let second = changes.message().await.unwrap().unwrap();
+        assert_eq!(second.change_type, "update");
+
+        let missing = client
+            .get(GetRequest {
+                namespace: "users".to_string(),
+                key: "bob".to_string(),
+                at: None,
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(missing.code(), tonic::Code::NotFound);
+    }
+}
@@ -0,0 +1,201 @@
+/// Write hooks.
+///
+/// Applications register hooks per namespace instead of wrapping every
+/// `put` call site:
+///
+/// - **Validators** run synchronously before a write is applied and can
+///   reject it, failing the write with
+///   [`DeltaError::WriteRejected`](crate::DeltaError::WriteRejected). Use them
+///   to enforce invariants.
+/// - **Post-write hooks** run asynchronously after a write is applied, on
+///   their own task, so they never slow down or fail the write. Use them to
+///   trigger side effects.
+///
+/// Hooks see values as the caller wrote them, before namespace encryption.
+/// Deletes are writes of `null`. They run for local writes (`put`, batches,
+/// deletes, authenticated handles), not for versions merged from peers.
+///
+/// # Example
+///
+/// ```ignore
+/// db.add_validator("orders", |write| match write.value["total"].as_f64() {
+///     Some(total) if total < 0.0 => Err("total must not be negative".into()),
+///     _ => Ok(()),
+/// });
+///
+/// db.add_post_write_hook("orders", |event| async move {
+///     println!("order {} is now {}", event.key, event.version.value());
+/// });
+/// ```
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use serde_json::Value as JsonValue;
+
+use crate::error::{DeltaError, DeltaResult};
+use crate::types::VersionedValue;
+
+/// A write about to be applied, as seen by validators.
+#[derive(Debug, Clone, Copy)]
+pub struct PendingWrite<'a> {
+    /// Namespace being written
+    pub namespace: &'a str,
+    /// Key being written
+    pub key: &'a str,
+    /// New value (`null` for deletes)
+    pub value: &'a JsonValue,
+}
+
+/// A write that was applied, as seen by post-write hooks.
+#[derive(Debug, Clone)]
+pub struct WriteEvent {
+    /// Namespace written
+    pub namespace: String,
+    /// Key written
+    pub key: String,
+    /// The new version, with the value as the caller wrote it
+    pub version: VersionedValue,
+}
+
+/// Handle to a registered hook, for removing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HookId(u64);
+
+type Validator = Arc<dyn Fn(&PendingWrite<'_>) -> Result<(), String> + Send + Sync>;
+
+/// Boxed future returned by a post-write hook.
+pub(crate) type HookFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+pub(crate) type PostWriteHook = Arc<dyn Fn(WriteEvent) -> HookFuture + Send + Sync>;
+
+/// Hooks registered on a database, by namespace.
+#[derive(Default)]
+pub(crate) struct WriteHooks {
+    next_id: AtomicU64,
+    validators: RwLock<HashMap<String, Vec<(HookId, Validator)>>>,
+    post_write: RwLock<HashMap<String, Vec<(HookId, PostWriteHook)>>>,
+}
+
+impl WriteHooks {
+    /// Register a validator for `namespace`.
+    pub(crate) fn add_validator(&self, namespace: String, validator: Validator) -> HookId {
+        let id = self.next_id();
+        self.validators
+            .write()
+            .unwrap()
+            .entry(namespace)
+            .or_default()
+            .push((id, validator));
+        id
+    }
+
+    /// Register a post-write hook for `namespace`.
+    pub(crate) fn add_post_write(&self, namespace: String, hook: PostWriteHook) -> HookId {
+        let id = self.next_id();
+        self.post_write
+            .write()
+            .unwrap()
+            .entry(namespace)
+            .or_default()
+            .push((id, hook));
+        id
+    }
+
+    /// Remove a hook; returns `false` if it was not registered.
+    pub(crate) fn remove(&self, id: HookId) -> bool {
+        let mut removed = false;
+        for hooks in self.validators.write().unwrap().values_mut() {
+            let before = hooks.len();
+            hooks.retain(|(hook, _)| *hook != id);
+            removed |= hooks.len() != before;
+        }
+        for hooks in self.post_write.write().unwrap().values_mut() {
+            let before = hooks.len();
+            hooks.retain(|(hook, _)| *hook != id);
+            removed |= hooks.len() != before;
+        }
+        removed
+    }
+
+    /// Run `write`'s namespace validators, in registration order.
+    pub(crate) fn validate(&self, write: &PendingWrite<'_>) -> DeltaResult<()> {
+        let validators = self.validators.read().unwrap();
+        let Some(validators) = validators.get(write.namespace) else {
+            return Ok(());
+        };
+        for (_, validator) in validators {
+            validator(write).map_err(|reason| DeltaError::WriteRejected {
+                namespace: write.namespace.to_string(),
+                key: write.key.to_string(),
+                reason,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Post-write hooks registered for `namespace`.
+    pub(crate) fn post_write_hooks(&self, namespace: &str) -> Vec<PostWriteHook> {
+        self.post_write
+            .read()
+            .unwrap()
+            .get(namespace)
+            .map(|hooks| hooks.iter().map(|(_, hook)| Arc::clone(hook)).collect())
+            .unwrap_or_default()
+    }
+
+    fn next_id(&self) -> HookId {
+        HookId(self.next_id.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl std::fmt::Debug for WriteHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let validators: usize = self.validators.read().unwrap().values().map(Vec::len).sum();
+        let post_write: usize = self.post_write.read().unwrap().values().map(Vec::len).sum();
+        f.debug_struct("WriteHooks")
+            .field("validators", &validators)
+            .field("post_write", &post_write)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validators_are_scoped_and_removable() {
+        let hooks = WriteHooks::default();
+        let id = hooks.add_validator(
+            "orders".to_string(),
+            Arc::new(|write| match write.value["total"].as_f64() {
+                Some(total) if total < 0.0 => Err("negative total".to_string()),
+                _ => Ok(()),
+            }),
+        );
+
+        let value = json!({"total": -1});
+        let order = PendingWrite {
+            namespace: "orders",
+            key: "1",
+            value: &value,
+        };
+        assert!(matches!(
+            hooks.validate(&order),
+            Err(DeltaError::WriteRejected { reason, .. }) if reason == "negative total"
+        ));
+        let other = PendingWrite {
+            namespace: "refunds",
+            ..order
+        };
+        assert!(hooks.validate(&other).is_ok());
+
+        assert!(hooks.remove(id));
+        assert!(!hooks.remove(id));
+        assert!(hooks.validate(&order).is_ok());
+    }
+}
@@ -7,6 +7,8 @@
 /// - Query history and perform time-travel queries
 /// - Execute filtered queries
 /// - Manage views
+/// - Follow changes as Server-Sent Events
+/// - Sync browser replicas over WebSocket
 /// - Monitor database status
 ///
 /// # Example
@@ -22,13 +24,20 @@
 /// # API Endpoints
 ///
 /// ## Key-Value Operations
-/// - `GET /api/v1/:namespace/:key` - Get current value
-/// - `PUT /api/v1/:namespace/:key` - Store value
-/// - `GET /api/v1/:namespace/:key/history` - Get history
+/// - `GET /api/v1/:namespace/:key` - Get current value (`?at=<rfc3339>` for time travel)
+///
+///   Responses carry the write ID as `ETag` and answer `304 Not Modified`
+///   when it matches `If-None-Match`. The body includes the version's
+///   `vector_clock` (node ID to counter) for comparing concurrent writes.
+/// - `PUT /api/v1/:namespace/:key` - Store value (returns the new version's
+///   `vector_clock`)
+/// - `GET /api/v1/:namespace/:key/history` - Get history (`?limit=&offset=&newest_first=`)
 /// - `GET /api/v1/:namespace/:key/at/:timestamp` - Time travel
+/// - `POST /api/v1/:namespace/_bulk` - NDJSON puts/deletes (`?atomic=true` for all-or-nothing)
 ///
 /// ## Queries
 /// - `POST /api/v1/:namespace/query` - Execute query
+/// - `POST /api/v1/query` - Execute a JSON-encoded [`Query`] with pagination
 ///
 /// ## Views
 /// - `GET /api/v1/views` - List views
@@ -37,17 +46,58 @@
 /// - `POST /api/v1/views/:name/refresh` - Refresh view
 /// - `DELETE /api/v1/views/:name` - Delete view
 ///
+/// ## Changes
+/// - `GET /api/v1/changes?namespace=&since=` - Server-Sent Events changefeed
+///
+/// Each event carries an `id` cursor. Reconnecting with `Last-Event-ID` (or
+/// `since=<cursor>`) first replays the versions written after it, then
+/// streams live changes. `since` also accepts an RFC 3339 timestamp. Live
+/// events come from writes that notify subscribers, such as `PUT` above.
+///
+/// ## Replica Sync
+/// - `GET /api/v1/sync` - WebSocket for reconciling a replica, such as the
+///   WASM build's `syncWith`. Speaks [`SyncMessage`] as JSON text frames.
+///
+/// ## GraphQL (`graphql` feature)
+/// - `POST /graphql` - Queries (see [`crate::graphql`])
+/// - `GET /graphql/ws` - Subscriptions over WebSocket
+///
+/// ## Web UI (`ui` feature)
+/// - `GET /ui` - Single-page admin UI: namespace browser, key history,
+///   query console and cluster status. Meant for development; it uses the
+///   endpoints here and has no authentication of its own.
+///
+/// ## Admin
+/// Require a session (`Authorization: Bearer <session id>`) whose identity
+/// holds `Admin` on `_admin:cluster` or `_admin:processes` respectively.
+/// - `GET /api/v1/admin/cluster` - Cluster status, partition state and clock-skewed peers
+/// - `GET /api/v1/admin/cluster/peers` - List peers with their clock offsets
+/// - `POST /api/v1/admin/cluster/peers/:node_id/sync` - Reconcile with a peer now
+/// - `GET /api/v1/admin/processes` - Background process state and schedules
+/// - `POST /api/v1/admin/processes/pause` - Pause background processes
+/// - `POST /api/v1/admin/processes/resume` - Resume background processes
+/// - `POST /api/v1/admin/processes/:process/pause` - Pause one process
+/// - `POST /api/v1/admin/processes/:process/resume` - Resume one process
+/// - `PUT /api/v1/admin/processes/:process/schedule` - Change its interval
+///   (body: `{"interval_secs": 120}`)
+///
 /// ## Status
 /// - `GET /api/v1/status` - Database status
+/// - `GET /api/v1/stats` - Detailed statistics (namespaces, tiers, indexes)
 /// - `GET /api/v1/namespaces` - List namespaces
 /// - `GET /api/v1/:namespace/keys` - List keys
+use crate::auth::Permission;
 use crate::core::KoruDelta;
 use crate::error::DeltaResult;
+use crate::orchestrator::{BackgroundProcess, ProcessSchedule};
 use crate::query::{Filter, Query};
+use crate::reconciliation::{SyncMessage, SyncResponder};
+use crate::subscriptions::{ChangeEvent, ChangeType, Subscription, SubscriptionGuard};
 use crate::views::ViewDefinition;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
 use std::sync::Arc;
 
@@ -64,6 +114,9 @@ impl HttpServer {
 
     /// Start the HTTP server on the given address.
     ///
+    /// The server stops accepting connections once the database begins
+    /// shutting down, and returns after in-flight requests complete.
+    ///
     /// # Example
     ///
     /// ```ignore
@@ -73,6 +126,7 @@ impl HttpServer {
         let addr: SocketAddr = addr.parse().map_err(|e| {
             crate::error::DeltaError::StorageError(format!("Invalid address: {}", e))
         })?;
+        let shutdown = self.db.shutdown_signal();
         let db = Arc::new(self.db);
 
         let app = create_router(db);
@@ -81,6 +135,7 @@ impl HttpServer {
             crate::error::DeltaError::StorageError(format!("Failed to bind: {}", e))
         })?;
         axum::serve(listener, app)
+            .with_graceful_shutdown(shutdown)
             .await
             .map_err(|e| crate::error::DeltaError::StorageError(format!("Server error: {}", e)))?;
 
@@ -93,30 +148,84 @@ fn create_router(db: Arc<KoruDelta>) -> axum::Router {
     use axum::Router;
     use axum::routing::{delete, get, post, put};
 
-    Router::new()
+    let router = Router::new()
         // Key-value operations
         .route("/api/v1/:namespace/:key", get(handle_get))
         .route("/api/v1/:namespace/:key", put(handle_put))
         .route("/api/v1/:namespace/:key/history", get(handle_history))
         .route("/api/v1/:namespace/:key/at/:timestamp", get(handle_get_at))
+        .route("/api/v1/:namespace/_bulk", post(handle_bulk))
         // Queries
         .route("/api/v1/:namespace/query", post(handle_query))
+        .route("/api/v1/query", post(handle_query_dsl))
         // Views
         .route("/api/v1/views", get(handle_list_views))
         .route("/api/v1/views", post(handle_create_view))
         .route("/api/v1/views/:name", get(handle_query_view))
         .route("/api/v1/views/:name/refresh", post(handle_refresh_view))
         .route("/api/v1/views/:name", delete(handle_delete_view))
+        // Changes
+        .route("/api/v1/changes", get(handle_changes))
+        // Replica sync
+        .route("/api/v1/sync", get(handle_sync))
+        // Admin
+        .route("/api/v1/admin/cluster", get(handle_admin_cluster))
+        .route("/api/v1/admin/cluster/peers", get(handle_admin_peers))
+        .route(
+            "/api/v1/admin/cluster/peers/:node_id/sync",
+            post(handle_admin_sync_peer),
+        )
+        .route("/api/v1/admin/processes", get(handle_admin_processes))
+        .route(
+            "/api/v1/admin/processes/pause",
+            post(handle_admin_pause_processes),
+        )
+        .route(
+            "/api/v1/admin/processes/resume",
+            post(handle_admin_resume_processes),
+        )
+        .route(
+            "/api/v1/admin/processes/:process/pause",
+            post(handle_admin_pause_process),
+        )
+        .route(
+            "/api/v1/admin/processes/:process/resume",
+            post(handle_admin_resume_process),
+        )
+        .route(
+            "/api/v1/admin/processes/:process/schedule",
+            put(handle_admin_reschedule_process),
+        )
         // Status
         .route("/api/v1/status", get(handle_status))
+        .route("/api/v1/stats", get(handle_stats))
         .route("/api/v1/namespaces", get(handle_list_namespaces))
         .route("/api/v1/:namespace/keys", get(handle_list_keys))
-        .with_state(db)
+        .with_state(Arc::clone(&db));
+
+    #[cfg(feature = "graphql")]
+    let router = crate::graphql::routes(router, db);
+
+    #[cfg(feature = "ui")]
+    let router = router.route("/ui", get(handle_ui));
+
+    // Compresses responses the client accepts gzip or br for; skips small
+    // bodies and event streams
+    router.layer(tower_http::compression::CompressionLayer::new())
 }
 
 // State extractor type
 use axum::extract::State;
 
+/// The admin UI page, built into the binary.
+#[cfg(feature = "ui")]
+const UI_HTML: &str = include_str!("ui/index.html");
+
+#[cfg(feature = "ui")]
+async fn handle_ui() -> axum::response::Html<&'static str> {
+    axum::response::Html(UI_HTML)
+}
+
 /// Response for a versioned value.
 #[derive(Debug, Serialize)]
 struct VersionedResponse {
@@ -124,6 +233,16 @@ struct VersionedResponse {
     version_id: String,
     timestamp: DateTime<Utc>,
     previous_version: Option<String>,
+    /// The version's vector clock, node ID to counter
+    vector_clock: HashMap<String, u64>,
+}
+
+/// Query parameters for GET /api/v1/:namespace/:key
+#[derive(Debug, Deserialize)]
+struct GetParams {
+    /// Return the value as of this RFC 3339 timestamp
+    #[serde(default)]
+    at: Option<String>,
 }
 
 /// Request body for PUT /api/v1/:namespace/:key
@@ -138,6 +257,122 @@ struct PutResponse {
     version_id: String,
     timestamp: DateTime<Utc>,
     previous_version: Option<String>,
+    /// The new version's vector clock, node ID to counter
+    vector_clock: HashMap<String, u64>,
+}
+
+/// Query parameters for the bulk endpoint.
+#[derive(Debug, Deserialize)]
+struct BulkParams {
+    /// Apply every operation or none of them
+    #[serde(default)]
+    atomic: bool,
+}
+
+/// One NDJSON line of a bulk request.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum BulkOperation {
+    Put { key: String, value: JsonValue },
+    Delete { key: String },
+}
+
+impl BulkOperation {
+    fn into_write(self) -> (String, JsonValue) {
+        match self {
+            BulkOperation::Put { key, value } => (key, value),
+            // Deletes are null tombstones, as in `KoruDelta::delete`
+            BulkOperation::Delete { key } => (key, JsonValue::Null),
+        }
+    }
+}
+
+/// Response for the bulk endpoint.
+#[derive(Debug, Serialize)]
+struct BulkResponse {
+    namespace: String,
+    atomic: bool,
+    succeeded: usize,
+    failed: usize,
+    results: Vec<BulkItemResponse>,
+}
+
+/// Outcome of one bulk line.
+#[derive(Debug, Serialize)]
+struct BulkItemResponse {
+    /// Line number in the request body, starting at 1
+    line: usize,
+    key: Option<String>,
+    version_id: Option<String>,
+    error: Option<String>,
+}
+
+/// Query parameters for the changefeed.
+#[derive(Debug, Deserialize)]
+struct ChangesParams {
+    namespace: String,
+    /// Cursor or RFC 3339 timestamp to replay from
+    #[serde(default)]
+    since: Option<String>,
+}
+
+/// Position in the changefeed: a version's timestamp and ID.
+///
+/// Rendered as `<unix nanos>-<version id>` in SSE event IDs.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct ChangeCursor {
+    timestamp: DateTime<Utc>,
+    version_id: String,
+}
+
+impl ChangeCursor {
+    fn of(event: &ChangeEvent) -> Self {
+        Self {
+            timestamp: event.timestamp,
+            version_id: event.version_id.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Parse a cursor, or a bare timestamp to replay everything from it.
+    fn parse(s: &str) -> Result<Self, axum::http::StatusCode> {
+        if let Some((nanos, version_id)) = s.split_once('-')
+            && let Ok(nanos) = nanos.parse::<i64>()
+        {
+            return Ok(Self {
+                timestamp: DateTime::from_timestamp_nanos(nanos),
+                version_id: version_id.to_string(),
+            });
+        }
+        Ok(Self {
+            timestamp: parse_timestamp(s)? - chrono::Duration::nanoseconds(1),
+            version_id: String::new(),
+        })
+    }
+}
+
+impl std::fmt::Display for ChangeCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let nanos = self.timestamp.timestamp_nanos_opt().unwrap_or_default();
+        write!(f, "{}-{}", nanos, self.version_id)
+    }
+}
+
+/// Default page size for the history endpoint.
+const DEFAULT_HISTORY_LIMIT: usize = 100;
+
+/// Largest page size for the history endpoint.
+const MAX_HISTORY_LIMIT: usize = 1000;
+
+/// Query parameters for the history endpoint.
+#[derive(Debug, Deserialize)]
+struct HistoryParams {
+    #[serde(default)]
+    limit: Option<usize>,
+    #[serde(default)]
+    offset: Option<usize>,
+    /// Page from the newest version instead of the oldest
+    #[serde(default)]
+    newest_first: bool,
 }
 
 /// Response for history endpoint.
@@ -146,6 +381,12 @@ struct HistoryResponse {
     key: String,
     namespace: String,
     versions: Vec<HistoryEntryResponse>,
+    /// Number of versions in the full history
+    total: usize,
+    offset: usize,
+    limit: usize,
+    /// Offset of the next page, if there is one
+    next_offset: Option<usize>,
 }
 
 #[derive(Debug, Serialize)]
@@ -153,6 +394,9 @@ struct HistoryEntryResponse {
     value: JsonValue,
     version_id: String,
     timestamp: DateTime<Utc>,
+    /// Position in the full history, oldest = 1
+    version: usize,
+    previous_version: Option<String>,
 }
 
 /// Request for query endpoint.
@@ -186,6 +430,9 @@ struct QueryResponse {
     results: Vec<QueryRecordResponse>,
     total: usize,
     namespace: String,
+    /// Whether the query timed out and `results` are incomplete
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    partial: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -196,6 +443,113 @@ struct QueryRecordResponse {
     timestamp: DateTime<Utc>,
 }
 
+/// Default page size for the query DSL endpoint.
+const DEFAULT_QUERY_LIMIT: usize = 100;
+
+/// Largest page size for the query DSL endpoint.
+const MAX_QUERY_LIMIT: usize = 1000;
+
+/// Longest a query made over HTTP may run, in milliseconds.
+const MAX_QUERY_TIMEOUT_MS: u64 = 30_000;
+
+/// Request for POST /api/v1/query.
+///
+/// `query` is a serialized [`Query`]; its `limit` and `offset` select the
+/// page (the limit is capped at `MAX_QUERY_LIMIT`). Aggregations are
+/// computed over the returned page. Its `timeout_ms` is capped at
+/// `MAX_QUERY_TIMEOUT_MS`; set `allow_partial` to get the records matched
+/// so far instead of a 504 when it runs out.
+#[derive(Debug, Deserialize)]
+struct QueryDslRequest {
+    namespace: String,
+    #[serde(default)]
+    query: Query,
+}
+
+/// Response for POST /api/v1/query.
+#[derive(Debug, Serialize)]
+struct QueryDslResponse {
+    namespace: String,
+    results: Vec<QueryRecordResponse>,
+    /// Matching records before pagination
+    total: usize,
+    offset: usize,
+    limit: usize,
+    /// Offset of the next page, if there is one
+    next_offset: Option<usize>,
+    aggregation: Option<JsonValue>,
+    /// Whether the query timed out and `results` are incomplete
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    partial: bool,
+}
+
+/// Namespace holding the admin resources checked by `/api/v1/admin`.
+pub const ADMIN_NAMESPACE: &str = "_admin";
+
+/// Cluster status for the admin API.
+#[derive(Debug, Serialize)]
+struct ClusterStatusResponse {
+    node_id: String,
+    address: SocketAddr,
+    is_running: bool,
+    peer_count: usize,
+    healthy_peers: usize,
+    has_quorum: bool,
+    /// `healthy`, `partitioned` or `recovering`
+    partition_state: &'static str,
+    /// Peers whose clocks exceed the configured maximum skew
+    skewed_peers: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct PeerResponse {
+    node_id: String,
+    address: SocketAddr,
+    status: crate::network::PeerStatus,
+    first_seen: DateTime<Utc>,
+    last_seen: DateTime<Utc>,
+    /// Peer clock minus ours, from the latest heartbeat
+    clock_offset_ms: Option<i64>,
+    clock_skewed: bool,
+    /// Share of recent heartbeats answered, from 0.0 to 1.0
+    health_score: f64,
+    /// Failure suspicion; the peer is unreachable past the configured threshold
+    phi: Option<f64>,
+}
+
+/// Background process state for the admin API.
+#[derive(Debug, Serialize)]
+struct ProcessesResponse {
+    paused: bool,
+    processes: Vec<ProcessResponse>,
+}
+
+#[derive(Debug, Serialize)]
+struct ProcessResponse {
+    name: &'static str,
+    interval_secs: f64,
+    paused: bool,
+    runs: u64,
+    last_run: Option<DateTime<Utc>>,
+}
+
+impl From<ProcessSchedule> for ProcessResponse {
+    fn from(schedule: ProcessSchedule) -> Self {
+        Self {
+            name: schedule.process.name(),
+            interval_secs: schedule.interval.as_secs_f64(),
+            paused: schedule.paused,
+            runs: schedule.runs,
+            last_run: schedule.last_run,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RescheduleRequest {
+    interval_secs: f64,
+}
+
 /// Status response.
 #[derive(Debug, Serialize)]
 struct StatusResponse {
@@ -234,19 +588,36 @@ struct ViewInfoResponse {
 async fn handle_get(
     State(db): State<Arc<KoruDelta>>,
     axum::extract::Path((namespace, key)): axum::extract::Path<(String, String)>,
-) -> Result<axum::Json<VersionedResponse>, axum::http::StatusCode> {
-    match db.get(&namespace, &key).await {
-        Ok(versioned) => {
-            let response = VersionedResponse {
-                value: versioned.value().clone(),
-                version_id: versioned.version_id().to_string(),
-                timestamp: versioned.timestamp(),
-                previous_version: versioned.previous_version().map(|s| s.to_string()),
-            };
-            Ok(axum::Json(response))
-        }
-        Err(_) => Err(axum::http::StatusCode::NOT_FOUND),
+    axum::extract::Query(params): axum::extract::Query<GetParams>,
+    headers: axum::http::HeaderMap,
+) -> Result<axum::response::Response, axum::http::StatusCode> {
+    use axum::response::IntoResponse;
+
+    let result = match params.at {
+        Some(at) => db.get_at(&namespace, &key, parse_timestamp(&at)?).await,
+        None => db.get(&namespace, &key).await,
+    };
+    let versioned = result.map_err(|_| axum::http::StatusCode::NOT_FOUND)?;
+
+    // The write ID, not the content hash: rewriting the same value changes
+    // the timestamp and vector clock in the body
+    let etag = format!("\"{}\"", versioned.write_id());
+    let etag_header = (axum::http::header::ETAG, etag.clone());
+    if if_none_match(&headers, &etag) {
+        return Ok((axum::http::StatusCode::NOT_MODIFIED, [etag_header]).into_response());
     }
+    Ok(([etag_header], axum::Json(versioned_response(&versioned))).into_response())
+}
+
+/// Whether an `If-None-Match` header matches `etag` (weak comparison).
+fn if_none_match(headers: &axum::http::HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(axum::http::header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
 }
 
 async fn handle_put(
@@ -254,12 +625,13 @@ async fn handle_put(
     axum::extract::Path((namespace, key)): axum::extract::Path<(String, String)>,
     axum::Json(request): axum::Json<PutRequest>,
 ) -> Result<axum::Json<PutResponse>, axum::http::StatusCode> {
-    match db.put(&namespace, &key, request.value).await {
+    match db.put_notify(&namespace, &key, request.value).await {
         Ok(versioned) => {
             let response = PutResponse {
                 version_id: versioned.version_id().to_string(),
                 timestamp: versioned.timestamp(),
                 previous_version: versioned.previous_version().map(|s| s.to_string()),
+                vector_clock: versioned.vector_clock().clocks.clone(),
             };
             Ok(axum::Json(response))
         }
@@ -267,56 +639,176 @@ async fn handle_put(
     }
 }
 
+async fn handle_bulk(
+    State(db): State<Arc<KoruDelta>>,
+    axum::extract::Path(namespace): axum::extract::Path<String>,
+    axum::extract::Query(params): axum::extract::Query<BulkParams>,
+    body: String,
+) -> Result<axum::Json<BulkResponse>, axum::http::StatusCode> {
+    let operations: Vec<(usize, Result<BulkOperation, String>)> = body
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let operation = serde_json::from_str(line).map_err(|e| e.to_string());
+            (index + 1, operation)
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(operations.len());
+    if params.atomic {
+        // Reject the whole body before writing anything
+        let mut lines = Vec::with_capacity(operations.len());
+        let mut writes = Vec::with_capacity(operations.len());
+        for (line, operation) in operations {
+            let (key, value) = operation
+                .map_err(|_| axum::http::StatusCode::BAD_REQUEST)?
+                .into_write();
+            lines.push(line);
+            writes.push((key, value));
+        }
+        let keys: Vec<String> = writes.iter().map(|(key, _)| key.clone()).collect();
+        let versions = db
+            .put_batch_notify(&namespace, writes)
+            .await
+            .map_err(|_| axum::http::StatusCode::INTERNAL_SERVER_ERROR)?;
+        for ((line, key), versioned) in lines.into_iter().zip(keys).zip(versions) {
+            results.push(BulkItemResponse {
+                line,
+                key: Some(key),
+                version_id: Some(versioned.version_id().to_string()),
+                error: None,
+            });
+        }
+    } else {
+        for (line, operation) in operations {
+            let item = match operation {
+                Ok(operation) => {
+                    let (key, written) = match operation {
+                        BulkOperation::Put { key, value } => {
+                            let written = db.put_notify(&namespace, &key, value).await;
+                            (key, written)
+                        }
+                        BulkOperation::Delete { key } => {
+                            let written = db.delete_notify(&namespace, &key).await;
+                            (key, written)
+                        }
+                    };
+                    match written {
+                        Ok(versioned) => BulkItemResponse {
+                            line,
+                            key: Some(key),
+                            version_id: Some(versioned.version_id().to_string()),
+                            error: None,
+                        },
+                        Err(e) => BulkItemResponse {
+                            line,
+                            key: Some(key),
+                            version_id: None,
+                            error: Some(e.to_string()),
+                        },
+                    }
+                }
+                Err(error) => BulkItemResponse {
+                    line,
+                    key: None,
+                    version_id: None,
+                    error: Some(error),
+                },
+            };
+            results.push(item);
+        }
+    }
+
+    let failed = results.iter().filter(|item| item.error.is_some()).count();
+    Ok(axum::Json(BulkResponse {
+        namespace,
+        atomic: params.atomic,
+        succeeded: results.len() - failed,
+        failed,
+        results,
+    }))
+}
+
 async fn handle_history(
     State(db): State<Arc<KoruDelta>>,
     axum::extract::Path((namespace, key)): axum::extract::Path<(String, String)>,
+    axum::extract::Query(params): axum::extract::Query<HistoryParams>,
 ) -> Result<axum::Json<HistoryResponse>, axum::http::StatusCode> {
-    match db.history(&namespace, &key).await {
-        Ok(history) => {
-            let versions: Vec<_> = history
-                .into_iter()
-                .map(|entry| HistoryEntryResponse {
-                    value: entry.value,
-                    version_id: entry.version_id,
-                    timestamp: entry.timestamp,
-                })
-                .collect();
+    let history = match db.history(&namespace, &key).await {
+        Ok(history) => history,
+        Err(_) => return Err(axum::http::StatusCode::NOT_FOUND),
+    };
 
-            let response = HistoryResponse {
-                key,
-                namespace,
-                versions,
-            };
-            Ok(axum::Json(response))
-        }
-        Err(_) => Err(axum::http::StatusCode::NOT_FOUND),
+    let total = history.len();
+    let offset = params.offset.unwrap_or(0);
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_HISTORY_LIMIT)
+        .clamp(1, MAX_HISTORY_LIMIT);
+
+    // Number versions and link each to its predecessor before paging
+    let mut previous: Option<String> = None;
+    let mut versions: Vec<_> = history
+        .into_iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let previous_version = previous.replace(entry.version_id.clone());
+            HistoryEntryResponse {
+                value: entry.value,
+                version_id: entry.version_id,
+                timestamp: entry.timestamp,
+                version: index + 1,
+                previous_version,
+            }
+        })
+        .collect();
+    if params.newest_first {
+        versions.reverse();
     }
+    let versions: Vec<_> = versions.into_iter().skip(offset).take(limit).collect();
+    let next_offset = (offset + versions.len() < total).then_some(offset + versions.len());
+
+    Ok(axum::Json(HistoryResponse {
+        key,
+        namespace,
+        versions,
+        total,
+        offset,
+        limit,
+        next_offset,
+    }))
 }
 
 async fn handle_get_at(
     State(db): State<Arc<KoruDelta>>,
     axum::extract::Path((namespace, key, timestamp)): axum::extract::Path<(String, String, String)>,
 ) -> Result<axum::Json<VersionedResponse>, axum::http::StatusCode> {
-    // Parse ISO 8601 timestamp
-    let timestamp = match DateTime::parse_from_rfc3339(&timestamp) {
-        Ok(ts) => ts.with_timezone(&Utc),
-        Err(_) => return Err(axum::http::StatusCode::BAD_REQUEST),
-    };
+    let timestamp = parse_timestamp(&timestamp)?;
 
     match db.get_at(&namespace, &key, timestamp).await {
-        Ok(versioned) => {
-            let response = VersionedResponse {
-                value: versioned.value().clone(),
-                version_id: versioned.version_id().to_string(),
-                timestamp: versioned.timestamp(),
-                previous_version: versioned.previous_version().map(|s| s.to_string()),
-            };
-            Ok(axum::Json(response))
-        }
+        Ok(versioned) => Ok(axum::Json(versioned_response(&versioned))),
         Err(_) => Err(axum::http::StatusCode::NOT_FOUND),
     }
 }
 
+/// Parse an ISO 8601 (RFC 3339) timestamp.
+fn parse_timestamp(timestamp: &str) -> Result<DateTime<Utc>, axum::http::StatusCode> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|ts| ts.with_timezone(&Utc))
+        .map_err(|_| axum::http::StatusCode::BAD_REQUEST)
+}
+
+fn versioned_response(versioned: &crate::types::VersionedValue) -> VersionedResponse {
+    VersionedResponse {
+        value: versioned.value().clone(),
+        version_id: versioned.version_id().to_string(),
+        timestamp: versioned.timestamp(),
+        previous_version: versioned.previous_version().map(|s| s.to_string()),
+        vector_clock: versioned.vector_clock().clocks.clone(),
+    }
+}
+
 async fn handle_query(
     State(db): State<Arc<KoruDelta>>,
     axum::extract::Path(namespace): axum::extract::Path<String>,
@@ -339,10 +831,12 @@ async fn handle_query(
     if let Some(limit) = request.limit {
         query = query.limit(limit);
     }
+    query.timeout_ms = Some(MAX_QUERY_TIMEOUT_MS);
 
     match db.query(&namespace, query).await {
         Ok(results) => {
             let total = results.total_count;
+            let partial = results.partial;
             let records: Vec<_> = results
                 .records
                 .into_iter()
@@ -358,10 +852,232 @@ async fn handle_query(
                 results: records,
                 total,
                 namespace,
+                partial,
             };
             Ok(axum::Json(response))
         }
-        Err(_) => Err(axum::http::StatusCode::INTERNAL_SERVER_ERROR),
+        Err(e) => Err(query_error_status(&e)),
+    }
+}
+
+/// Status for a failed query: timeouts are 504, exceeded limits 422,
+/// everything else 500.
+fn query_error_status(error: &crate::error::DeltaError) -> axum::http::StatusCode {
+    match error {
+        crate::error::DeltaError::QueryTimeout { .. } => axum::http::StatusCode::GATEWAY_TIMEOUT,
+        crate::error::DeltaError::QueryLimitExceeded { .. } => {
+            axum::http::StatusCode::UNPROCESSABLE_ENTITY
+        }
+        _ => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+async fn handle_query_dsl(
+    State(db): State<Arc<KoruDelta>>,
+    axum::Json(request): axum::Json<QueryDslRequest>,
+) -> Result<axum::Json<QueryDslResponse>, axum::http::StatusCode> {
+    let mut query = request.query;
+    let offset = query.offset.unwrap_or(0);
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_QUERY_LIMIT)
+        .clamp(1, MAX_QUERY_LIMIT);
+    query.offset = Some(offset);
+    // The executor aggregates the page it returns, so an aggregation without
+    // an explicit limit runs over every match and only the response is paged
+    if query.aggregation.is_none() || query.limit.is_some() {
+        query.limit = Some(limit);
+    }
+    query.timeout_ms = Some(
+        query
+            .timeout_ms
+            .map_or(MAX_QUERY_TIMEOUT_MS, |t| t.min(MAX_QUERY_TIMEOUT_MS)),
+    );
+
+    match db.query(&request.namespace, query).await {
+        Ok(result) => {
+            let results: Vec<_> = result
+                .records
+                .into_iter()
+                .take(limit)
+                .map(|record| QueryRecordResponse {
+                    key: record.key,
+                    value: record.value,
+                    version_id: record.version_id,
+                    timestamp: record.timestamp,
+                })
+                .collect();
+            let next_offset =
+                (offset + results.len() < result.total_count).then_some(offset + results.len());
+
+            Ok(axum::Json(QueryDslResponse {
+                namespace: request.namespace,
+                results,
+                total: result.total_count,
+                offset,
+                limit,
+                next_offset,
+                aggregation: result.aggregation,
+                partial: result.partial,
+            }))
+        }
+        Err(e) => Err(query_error_status(&e)),
+    }
+}
+
+async fn handle_changes(
+    State(db): State<Arc<KoruDelta>>,
+    axum::extract::Query(params): axum::extract::Query<ChangesParams>,
+    headers: axum::http::HeaderMap,
+) -> Result<
+    axum::response::sse::Sse<
+        impl futures::Stream<Item = Result<axum::response::sse::Event, std::convert::Infallible>>,
+    >,
+    axum::http::StatusCode,
+> {
+    use axum::response::sse::{KeepAlive, Sse};
+    use futures::StreamExt;
+    use tokio::sync::broadcast::error::RecvError;
+
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok());
+    let since = last_event_id
+        .or(params.since.as_deref())
+        .map(ChangeCursor::parse)
+        .transpose()?;
+
+    // Subscribe before replaying so nothing written in between is missed
+    let (id, receiver) = db
+        .subscribe(Subscription::collection(&params.namespace))
+        .await;
+    let subscription = SubscriptionGuard::new(Arc::clone(db.subscription_manager()), id);
+
+    let replayed = match &since {
+        Some(since) => replay_changes(&db, &params.namespace, since).await,
+        None => Vec::new(),
+    };
+
+    // Live events only repeat what the replay sent when the write landed
+    // between subscribing and replaying. Writes to different keys can be
+    // notified out of timestamp order, so nothing else is filtered out.
+    let sent: HashSet<String> = replayed
+        .iter()
+        .filter_map(|(_, event)| event.version_id.clone())
+        .collect();
+    let replay = futures::stream::iter(
+        replayed
+            .into_iter()
+            .map(|(cursor, event)| Ok(change_event(&cursor, &event))),
+    );
+    let live = futures::stream::unfold(
+        (receiver, sent, subscription),
+        |(mut receiver, mut sent, subscription)| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        if event
+                            .version_id
+                            .as_ref()
+                            .is_some_and(|version_id| sent.remove(version_id))
+                        {
+                            continue;
+                        }
+                        let sse = change_event(&ChangeCursor::of(&event), &event);
+                        return Some((Ok(sse), (receiver, sent, subscription)));
+                    }
+                    // A lagging client reconnects with Last-Event-ID and replays
+                    Err(RecvError::Lagged(_)) | Err(RecvError::Closed) => return None,
+                }
+            }
+        },
+    );
+
+    Ok(Sse::new(replay.chain(live)).keep_alive(KeepAlive::default()))
+}
+
+/// Rebuild the change events in a namespace after a cursor, oldest first.
+async fn replay_changes(
+    db: &KoruDelta,
+    namespace: &str,
+    since: &ChangeCursor,
+) -> Vec<(ChangeCursor, ChangeEvent)> {
+    let mut changes = Vec::new();
+    for key in db.list_keys(namespace).await {
+        let Ok(history) = db.history(namespace, &key).await else {
+            continue;
+        };
+        let mut previous: Option<&crate::types::HistoryEntry> = None;
+        for entry in &history {
+            let previous_value = previous
+                .map(|p| p.value.clone())
+                .filter(|value| !value.is_null());
+            let change_type = match (&previous_value, entry.value.is_null()) {
+                (_, true) => ChangeType::Delete,
+                (None, false) => ChangeType::Insert,
+                (Some(_), false) => ChangeType::Update,
+            };
+            let event = ChangeEvent {
+                change_type,
+                collection: namespace.to_string(),
+                key: key.clone(),
+                value: (!entry.value.is_null()).then(|| entry.value.clone()),
+                previous_value,
+                timestamp: entry.timestamp,
+                version_id: Some(entry.version_id.clone()),
+                previous_version_id: previous.map(|p| p.version_id.clone()),
+                sequence: 0,
+            };
+            let cursor = ChangeCursor::of(&event);
+            if &cursor > since {
+                changes.push((cursor, event));
+            }
+            previous = Some(entry);
+        }
+    }
+    changes.sort_by(|a, b| a.0.cmp(&b.0));
+    changes
+}
+
+fn change_event(cursor: &ChangeCursor, event: &ChangeEvent) -> axum::response::sse::Event {
+    axum::response::sse::Event::default()
+        .event("change")
+        .id(cursor.to_string())
+        .data(serde_json::to_string(event).unwrap_or_default())
+}
+
+async fn handle_sync(
+    State(db): State<Arc<KoruDelta>>,
+    ws: axum::extract::ws::WebSocketUpgrade,
+) -> axum::response::Response {
+    ws.on_upgrade(move |socket| serve_sync(db, socket))
+}
+
+/// Answer replica sync messages until the client closes the socket.
+#[tracing::instrument(name = "replica_sync", skip_all)]
+async fn serve_sync(db: Arc<KoruDelta>, mut socket: axum::extract::ws::WebSocket) {
+    use axum::extract::ws::Message;
+
+    let responder = SyncResponder::new(&db);
+    while let Some(Ok(message)) = socket.recv().await {
+        let Message::Text(text) = message else {
+            continue;
+        };
+        let reply = match serde_json::from_str::<SyncMessage>(&text) {
+            Ok(request) => responder.respond(request).await,
+            Err(e) => Some(SyncMessage::Error {
+                message: format!("Invalid sync message: {}", e),
+            }),
+        };
+        let Some(reply) = reply else {
+            continue;
+        };
+        let Ok(json) = serde_json::to_string(&reply) else {
+            break;
+        };
+        if socket.send(Message::Text(json)).await.is_err() {
+            break;
+        }
     }
 }
 
@@ -465,6 +1181,203 @@ async fn handle_delete_view(
     }
 }
 
+/// Require an authenticated identity with `Admin` on `_admin:<area>`.
+async fn require_admin(
+    db: &KoruDelta,
+    headers: &axum::http::HeaderMap,
+    area: &str,
+) -> Result<(), axum::http::StatusCode> {
+    let auth = db.auth();
+    let (identity, _) = crate::auth::http::require_auth_context(headers, &auth).await?;
+    auth.authorize(
+        &identity.public_key,
+        ADMIN_NAMESPACE,
+        area,
+        Permission::Admin,
+    )
+    .map(|_| ())
+    .map_err(|_| axum::http::StatusCode::FORBIDDEN)
+}
+
+fn cluster_node(
+    db: &KoruDelta,
+) -> Result<&Arc<crate::cluster::ClusterNode>, axum::http::StatusCode> {
+    db.cluster().ok_or(axum::http::StatusCode::NOT_FOUND)
+}
+
+async fn handle_admin_cluster(
+    State(db): State<Arc<KoruDelta>>,
+    headers: axum::http::HeaderMap,
+) -> Result<axum::Json<ClusterStatusResponse>, axum::http::StatusCode> {
+    require_admin(&db, &headers, "cluster").await?;
+    let cluster = cluster_node(&db)?;
+    let status = cluster.status().await;
+
+    Ok(axum::Json(ClusterStatusResponse {
+        node_id: status.node_id.0.to_string(),
+        address: status.address,
+        is_running: status.is_running,
+        peer_count: status.peer_count,
+        healthy_peers: status.healthy_peers,
+        has_quorum: cluster.has_quorum().await,
+        partition_state: cluster.partition_state_str().await,
+        skewed_peers: status
+            .skewed_peers
+            .iter()
+            .map(|node_id| node_id.0.to_string())
+            .collect(),
+    }))
+}
+
+async fn handle_admin_peers(
+    State(db): State<Arc<KoruDelta>>,
+    headers: axum::http::HeaderMap,
+) -> Result<axum::Json<Vec<PeerResponse>>, axum::http::StatusCode> {
+    require_admin(&db, &headers, "cluster").await?;
+    let cluster = cluster_node(&db)?;
+    let offsets: HashMap<_, _> = cluster
+        .clock_offsets()
+        .into_iter()
+        .map(|offset| (offset.node_id.clone(), offset))
+        .collect();
+    let peers = cluster
+        .peers()
+        .into_iter()
+        .map(|peer| {
+            let offset = offsets.get(&peer.node_id);
+            PeerResponse {
+                node_id: peer.node_id.0.to_string(),
+                address: peer.address,
+                status: peer.status,
+                first_seen: peer.first_seen,
+                last_seen: peer.last_seen,
+                clock_offset_ms: offset.map(|offset| offset.offset_ms),
+                clock_skewed: offset.is_some_and(|offset| offset.skewed),
+                health_score: peer.health_score,
+                phi: cluster.phi(&peer.node_id),
+            }
+        })
+        .collect();
+    Ok(axum::Json(peers))
+}
+
+async fn handle_admin_sync_peer(
+    State(db): State<Arc<KoruDelta>>,
+    headers: axum::http::HeaderMap,
+    axum::extract::Path(node_id): axum::extract::Path<String>,
+) -> Result<axum::http::StatusCode, axum::http::StatusCode> {
+    require_admin(&db, &headers, "cluster").await?;
+    let cluster = cluster_node(&db)?;
+    let node_id = uuid::Uuid::parse_str(&node_id)
+        .map(crate::network::NodeId::from_uuid)
+        .map_err(|_| axum::http::StatusCode::BAD_REQUEST)?;
+    if !cluster.peers().iter().any(|peer| peer.node_id == node_id) {
+        return Err(axum::http::StatusCode::NOT_FOUND);
+    }
+
+    match cluster.sync_with_peer(&node_id).await {
+        Ok(()) => Ok(axum::http::StatusCode::NO_CONTENT),
+        Err(_) => Err(axum::http::StatusCode::BAD_GATEWAY),
+    }
+}
+
+fn processes_response(db: &KoruDelta) -> ProcessesResponse {
+    let scheduler = db.scheduler();
+    ProcessesResponse {
+        paused: scheduler.all_paused(),
+        processes: scheduler.list().into_iter().map(Into::into).collect(),
+    }
+}
+
+/// Parse a `:process` path segment, 404 for unknown or unscheduled processes.
+fn scheduled_process(
+    db: &KoruDelta,
+    name: &str,
+) -> Result<BackgroundProcess, axum::http::StatusCode> {
+    name.parse::<BackgroundProcess>()
+        .ok()
+        .filter(|process| db.scheduler().get(*process).is_some())
+        .ok_or(axum::http::StatusCode::NOT_FOUND)
+}
+
+fn process_response(
+    db: &KoruDelta,
+    process: BackgroundProcess,
+) -> Result<axum::Json<ProcessResponse>, axum::http::StatusCode> {
+    db.scheduler()
+        .get(process)
+        .map(|schedule| axum::Json(schedule.into()))
+        .ok_or(axum::http::StatusCode::NOT_FOUND)
+}
+
+async fn handle_admin_processes(
+    State(db): State<Arc<KoruDelta>>,
+    headers: axum::http::HeaderMap,
+) -> Result<axum::Json<ProcessesResponse>, axum::http::StatusCode> {
+    require_admin(&db, &headers, "processes").await?;
+    Ok(axum::Json(processes_response(&db)))
+}
+
+async fn handle_admin_pause_processes(
+    State(db): State<Arc<KoruDelta>>,
+    headers: axum::http::HeaderMap,
+) -> Result<axum::Json<ProcessesResponse>, axum::http::StatusCode> {
+    require_admin(&db, &headers, "processes").await?;
+    db.pause_background_processes();
+    Ok(axum::Json(processes_response(&db)))
+}
+
+async fn handle_admin_resume_processes(
+    State(db): State<Arc<KoruDelta>>,
+    headers: axum::http::HeaderMap,
+) -> Result<axum::Json<ProcessesResponse>, axum::http::StatusCode> {
+    require_admin(&db, &headers, "processes").await?;
+    db.resume_background_processes();
+    Ok(axum::Json(processes_response(&db)))
+}
+
+async fn handle_admin_pause_process(
+    State(db): State<Arc<KoruDelta>>,
+    axum::extract::Path(name): axum::extract::Path<String>,
+    headers: axum::http::HeaderMap,
+) -> Result<axum::Json<ProcessResponse>, axum::http::StatusCode> {
+    require_admin(&db, &headers, "processes").await?;
+    let process = scheduled_process(&db, &name)?;
+    db.scheduler()
+        .pause(process)
+        .map_err(|_| axum::http::StatusCode::NOT_FOUND)?;
+    process_response(&db, process)
+}
+
+async fn handle_admin_resume_process(
+    State(db): State<Arc<KoruDelta>>,
+    axum::extract::Path(name): axum::extract::Path<String>,
+    headers: axum::http::HeaderMap,
+) -> Result<axum::Json<ProcessResponse>, axum::http::StatusCode> {
+    require_admin(&db, &headers, "processes").await?;
+    let process = scheduled_process(&db, &name)?;
+    db.scheduler()
+        .resume(process)
+        .map_err(|_| axum::http::StatusCode::NOT_FOUND)?;
+    process_response(&db, process)
+}
+
+async fn handle_admin_reschedule_process(
+    State(db): State<Arc<KoruDelta>>,
+    axum::extract::Path(name): axum::extract::Path<String>,
+    headers: axum::http::HeaderMap,
+    axum::Json(request): axum::Json<RescheduleRequest>,
+) -> Result<axum::Json<ProcessResponse>, axum::http::StatusCode> {
+    require_admin(&db, &headers, "processes").await?;
+    let process = scheduled_process(&db, &name)?;
+    let interval = std::time::Duration::try_from_secs_f64(request.interval_secs)
+        .map_err(|_| axum::http::StatusCode::BAD_REQUEST)?;
+    db.scheduler()
+        .reschedule(process, interval)
+        .map_err(|_| axum::http::StatusCode::BAD_REQUEST)?;
+    process_response(&db, process)
+}
+
 async fn handle_status(
     State(db): State<Arc<KoruDelta>>,
 ) -> Result<axum::Json<StatusResponse>, axum::http::StatusCode> {
@@ -481,6 +1394,10 @@ async fn handle_status(
     Ok(axum::Json(response))
 }
 
+async fn handle_stats(State(db): State<Arc<KoruDelta>>) -> axum::Json<serde_json::Value> {
+    axum::Json(db.stats_json().await)
+}
+
 async fn handle_list_namespaces(State(db): State<Arc<KoruDelta>>) -> axum::Json<serde_json::Value> {
     let namespaces = db.list_namespaces().await;
     axum::Json(serde_json::json!({ "namespaces": namespaces }))
@@ -496,6 +1413,491 @@ async fn handle_list_keys(
 
 #[cfg(test)]
 mod tests {
-    // Note: HTTP tests would require spinning up the server and making requests
-    // For now, we rely on integration tests in tests/http_api_tests.rs
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use serde_json::json;
+    use tower::Service;
+
+    async fn send(db: &Arc<KoruDelta>, request: Request<Body>) -> (StatusCode, JsonValue) {
+        let response = create_router(Arc::clone(db)).call(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(JsonValue::Null),
+        )
+    }
+
+    async fn get_json(db: &Arc<KoruDelta>, uri: &str) -> (StatusCode, JsonValue) {
+        send(db, Request::get(uri).body(Body::empty()).unwrap()).await
+    }
+
+    async fn post_json(db: &Arc<KoruDelta>, uri: &str, body: JsonValue) -> (StatusCode, JsonValue) {
+        let request = Request::post(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        send(db, request).await
+    }
+
+    async fn post_ndjson(
+        db: &Arc<KoruDelta>,
+        uri: &str,
+        lines: &[JsonValue],
+    ) -> (StatusCode, JsonValue) {
+        let body: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+        let request = Request::post(uri)
+            .header("content-type", "application/x-ndjson")
+            .body(Body::from(body))
+            .unwrap();
+        send(db, request).await
+    }
+
+    #[tokio::test]
+    async fn test_admin_requires_admin_capability() {
+        use crate::auth::{IdentityUserData, ResourcePattern, create_challenge_response};
+
+        let db = Arc::new(KoruDelta::start().await.unwrap());
+        let auth = db.auth();
+        let (admin, secret_key) = auth.create_identity(IdentityUserData::default()).unwrap();
+        let challenge = auth.create_challenge(&admin.public_key).unwrap();
+        let response = create_challenge_response(&secret_key, &challenge).unwrap();
+        let session = auth
+            .verify_and_create_session(&admin.public_key, &challenge, &response)
+            .unwrap();
+        let admin_request = |method: &str, uri: &str| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("authorization", format!("Bearer {}", session.session_id))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let (status, _) = get_json(&db, "/api/v1/admin/processes").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = send(&db, admin_request("GET", "/api/v1/admin/processes")).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        auth.grant_capability(
+            &admin,
+            &secret_key,
+            &admin.public_key,
+            ResourcePattern::Namespace(ADMIN_NAMESPACE.to_string()),
+            Permission::Admin,
+            None,
+        )
+        .unwrap();
+
+        let (status, body) =
+            send(&db, admin_request("POST", "/api/v1/admin/processes/pause")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["paused"], true);
+        assert!(db.background_processes_paused());
+        send(&db, admin_request("POST", "/api/v1/admin/processes/resume")).await;
+        assert!(!db.background_processes_paused());
+
+        let (status, body) = send(
+            &db,
+            admin_request("POST", "/api/v1/admin/processes/distillation/pause"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["paused"], true);
+        assert!(db.scheduler().is_paused(BackgroundProcess::Distillation));
+
+        let reschedule = Request::builder()
+            .method("PUT")
+            .uri("/api/v1/admin/processes/consolidation/schedule")
+            .header("authorization", format!("Bearer {}", session.session_id))
+            .header("content-type", "application/json")
+            .body(Body::from(json!({"interval_secs": 42}).to_string()))
+            .unwrap();
+        let (status, body) = send(&db, reschedule).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["interval_secs"], 42.0);
+
+        let (_, body) = send(&db, admin_request("GET", "/api/v1/admin/processes")).await;
+        let distillation = body["processes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|p| p["name"] == "distillation")
+            .unwrap();
+        assert_eq!(distillation["paused"], true);
+
+        let (status, _) = send(
+            &db,
+            admin_request("POST", "/api/v1/admin/processes/unknown/pause"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // No cluster node is attached
+        let (status, _) = send(&db, admin_request("GET", "/api/v1/admin/cluster")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_etag_and_compression() {
+        let db = Arc::new(KoruDelta::start().await.unwrap());
+        db.put("users", "alice", json!({"age": 30})).await.unwrap();
+
+        let response = create_router(Arc::clone(&db))
+            .call(
+                Request::get("/api/v1/users/alice")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let etag = response.headers()["etag"].to_str().unwrap().to_string();
+        let version = db.get("users", "alice").await.unwrap();
+        assert_eq!(etag, format!("\"{}\"", version.write_id()));
+
+        let conditional = |etag: &str| {
+            Request::get("/api/v1/users/alice")
+                .header("if-none-match", etag)
+                .body(Body::empty())
+                .unwrap()
+        };
+        let (status, _) = send(&db, conditional(&format!("W/{}", etag))).await;
+        assert_eq!(status, StatusCode::NOT_MODIFIED);
+
+        // Rewriting the same value refreshes the metadata, so the tag changes
+        db.put("users", "alice", json!({"age": 30})).await.unwrap();
+        let (status, body) = send(&db, conditional(&etag)).await;
+        assert_eq!(status, StatusCode::OK);
+        let rewritten = db.get("users", "alice").await.unwrap();
+        assert_eq!(body["timestamp"], json!(rewritten.timestamp()));
+        let etag = format!("\"{}\"", rewritten.write_id());
+
+        db.put("users", "alice", json!({"age": 31})).await.unwrap();
+        let (status, body) = send(&db, conditional(&etag)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["value"]["age"], 31);
+        let latest = db.get("users", "alice").await.unwrap();
+        assert_eq!(body["vector_clock"], json!(latest.vector_clock().clocks));
+
+        // Large responses are compressed when the client accepts it
+        for i in 0..50 {
+            db.put("items", format!("item-{}", i), json!({"n": i}))
+                .await
+                .unwrap();
+        }
+        let request = Request::post("/api/v1/query")
+            .header("content-type", "application/json")
+            .header("accept-encoding", "gzip")
+            .body(Body::from(json!({"namespace": "items"}).to_string()))
+            .unwrap();
+        let response = create_router(Arc::clone(&db)).call(request).await.unwrap();
+        assert_eq!(response.headers()["content-encoding"], "gzip");
+    }
+
+    #[tokio::test]
+    async fn test_bulk_writes() {
+        let db = Arc::new(KoruDelta::start().await.unwrap());
+        db.put("users", "old", json!(1)).await.unwrap();
+        let (_, mut changes) = db.subscribe(Subscription::collection("users")).await;
+
+        let (status, report) = post_ndjson(
+            &db,
+            "/api/v1/users/_bulk",
+            &[
+                json!({"op": "put", "key": "alice", "value": {"age": 30}}),
+                json!({"op": "bogus"}),
+                json!({"op": "delete", "key": "old"}),
+            ],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(report["succeeded"], 2);
+        assert_eq!(report["failed"], 1);
+        assert_eq!(report["results"][1]["line"], 2);
+        assert!(report["results"][1]["error"].is_string());
+        assert_eq!(
+            db.get("users", "alice").await.unwrap().value(),
+            &json!({"age": 30})
+        );
+        assert!(!db.contains("users", "old").await);
+        let change_types: Vec<_> = std::iter::from_fn(|| changes.try_recv().ok())
+            .map(|event| (event.key, event.change_type))
+            .collect();
+        assert_eq!(
+            change_types,
+            [
+                ("alice".to_string(), ChangeType::Insert),
+                ("old".to_string(), ChangeType::Delete),
+            ]
+        );
+
+        // Atomic bodies with a bad line write nothing
+        let (status, _) = post_ndjson(
+            &db,
+            "/api/v1/users/_bulk?atomic=true",
+            &[
+                json!({"op": "put", "key": "bob", "value": 1}),
+                json!({"op": "put", "key": "carol"}),
+            ],
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(!db.contains("users", "bob").await);
+
+        let (status, report) = post_ndjson(
+            &db,
+            "/api/v1/users/_bulk?atomic=true",
+            &[
+                json!({"op": "put", "key": "bob", "value": 1}),
+                json!({"op": "delete", "key": "alice"}),
+            ],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(report["succeeded"], 2);
+        assert_eq!(db.get("users", "bob").await.unwrap().value(), &json!(1));
+        assert!(!db.contains("users", "alice").await);
+        let change_types: Vec<_> = std::iter::from_fn(|| changes.try_recv().ok())
+            .map(|event| (event.key, event.change_type))
+            .collect();
+        assert_eq!(
+            change_types,
+            [
+                ("bob".to_string(), ChangeType::Insert),
+                ("alice".to_string(), ChangeType::Delete),
+            ]
+        );
+    }
+
+    /// Read `count` SSE events as (id, data) pairs.
+    async fn read_events(
+        stream: &mut (impl futures::Stream<Item = Result<axum::body::Bytes, axum::Error>> + Unpin),
+        count: usize,
+    ) -> Vec<(String, JsonValue)> {
+        use futures::StreamExt;
+
+        let mut text = String::new();
+        let mut events = Vec::new();
+        while events.len() < count {
+            let chunk = tokio::time::timeout(std::time::Duration::from_secs(5), stream.next())
+                .await
+                .expect("timed out waiting for event")
+                .unwrap()
+                .unwrap();
+            text.push_str(std::str::from_utf8(&chunk).unwrap());
+            while let Some((frame, rest)) = text.split_once("\n\n") {
+                let mut id = String::new();
+                let mut data = JsonValue::Null;
+                for line in frame.lines() {
+                    if let Some(value) = line.strip_prefix("id: ") {
+                        id = value.to_string();
+                    } else if let Some(value) = line.strip_prefix("data: ") {
+                        data = serde_json::from_str(value).unwrap();
+                    }
+                }
+                if !id.is_empty() {
+                    events.push((id, data));
+                }
+                text = rest.to_string();
+            }
+        }
+        events
+    }
+
+    #[tokio::test]
+    async fn test_changefeed_replay_and_resume() {
+        let db = Arc::new(KoruDelta::start().await.unwrap());
+        db.put("users", "alice", json!(1)).await.unwrap();
+        db.put("users", "alice", json!(2)).await.unwrap();
+        db.put("other", "x", json!(0)).await.unwrap();
+
+        let request = Request::get("/api/v1/changes?namespace=users&since=1970-01-01T00:00:00Z")
+            .body(Body::empty())
+            .unwrap();
+        let response = create_router(Arc::clone(&db)).call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let mut stream = response.into_body().into_data_stream();
+
+        let replayed = read_events(&mut stream, 2).await;
+        assert_eq!(replayed[0].1["change_type"], "Insert");
+        assert_eq!(replayed[1].1["change_type"], "Update");
+        assert_eq!(replayed[1].1["previous_value"], 1);
+
+        // Live writes follow the replay
+        db.put_notify("users", "bob", json!(3)).await.unwrap();
+        let live = read_events(&mut stream, 1).await;
+        assert_eq!(live[0].1["key"], "bob");
+        drop(stream);
+
+        // Resuming after the first event replays only what came later
+        let request = Request::get("/api/v1/changes?namespace=users")
+            .header("last-event-id", &replayed[0].0)
+            .body(Body::empty())
+            .unwrap();
+        let response = create_router(Arc::clone(&db)).call(request).await.unwrap();
+        let mut stream = response.into_body().into_data_stream();
+        let resumed = read_events(&mut stream, 2).await;
+        assert_eq!(resumed[0].0, replayed[1].0);
+        assert_eq!(resumed[1].0, live[0].0);
+    }
+
+    #[tokio::test]
+    async fn test_changefeed_delivers_out_of_order_notifications() {
+        let db = Arc::new(KoruDelta::start().await.unwrap());
+        let a = db.put("users", "a", json!(1)).await.unwrap();
+
+        let request = Request::get("/api/v1/changes?namespace=users&since=1970-01-01T00:00:00Z")
+            .body(Body::empty())
+            .unwrap();
+        let response = create_router(Arc::clone(&db)).call(request).await.unwrap();
+        let mut stream = response.into_body().into_data_stream();
+        let replayed = read_events(&mut stream, 1).await;
+        assert_eq!(replayed[0].1["key"], "a");
+
+        // A repeat of the replayed write is dropped, but a write notified
+        // after a later one still reaches the client
+        let b = db.put("users", "b", json!(2)).await.unwrap();
+        let c = db.put("users", "c", json!(3)).await.unwrap();
+        let subscriptions = db.subscription_manager();
+        subscriptions.notify_insert("users", "a", &a);
+        subscriptions.notify_insert("users", "c", &c);
+        subscriptions.notify_insert("users", "b", &b);
+
+        let live = read_events(&mut stream, 2).await;
+        assert_eq!(live[0].1["key"], "c");
+        assert_eq!(live[1].1["key"], "b");
+    }
+
+    #[tokio::test]
+    async fn test_query_dsl() {
+        let db = Arc::new(KoruDelta::start().await.unwrap());
+        for (name, age) in [("alice", 30), ("bob", 25), ("carol", 35), ("dave", 40)] {
+            db.put("users", name, json!({"name": name, "age": age}))
+                .await
+                .unwrap();
+        }
+
+        let query = Query::new()
+            .filter(Filter::gte("age", 30))
+            .sort_by("age", false)
+            .project(&["name"])
+            .limit(2);
+        let (status, page) = post_json(
+            &db,
+            "/api/v1/query",
+            json!({ "namespace": "users", "query": query }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(page["total"], 3);
+        assert_eq!(page["next_offset"], 2);
+        assert_eq!(page["results"][0]["value"], json!({"name": "dave"}));
+
+        // Partial queries and aggregations
+        let (_, count) = post_json(
+            &db,
+            "/api/v1/query",
+            json!({ "namespace": "users", "query": { "aggregation": "Count" } }),
+        )
+        .await;
+        assert_eq!(count["aggregation"], 4);
+
+        // Aggregations cover every match, not just the default page
+        for n in 0..150 {
+            db.put("events", format!("e{n}"), json!({"n": 1}))
+                .await
+                .unwrap();
+        }
+        let (_, sum) = post_json(
+            &db,
+            "/api/v1/query",
+            json!({ "namespace": "events", "query": { "aggregation": { "Sum": { "field": "n" } } } }),
+        )
+        .await;
+        assert_eq!(sum["aggregation"], 150.0);
+        assert_eq!(sum["total"], 150);
+        assert_eq!(
+            sum["results"].as_array().unwrap().len(),
+            DEFAULT_QUERY_LIMIT
+        );
+        assert_eq!(sum["next_offset"], DEFAULT_QUERY_LIMIT);
+
+        let (status, _) = post_json(&db, "/api/v1/query", json!({ "query": {} })).await;
+        assert!(status.is_client_error());
+    }
+
+    #[tokio::test]
+    async fn test_history_pagination_and_time_travel() {
+        let db = Arc::new(KoruDelta::start().await.unwrap());
+        for n in 1..=5 {
+            db.put("counters", "hits", json!(n)).await.unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+
+        let (status, page) = get_json(&db, "/api/v1/counters/hits/history?limit=2").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(page["total"], 5);
+        assert_eq!(page["next_offset"], 2);
+        assert_eq!(page["versions"][0]["value"], 1);
+        assert_eq!(page["versions"][0]["previous_version"], JsonValue::Null);
+        assert_eq!(
+            page["versions"][1]["previous_version"],
+            page["versions"][0]["version_id"]
+        );
+
+        let (_, last) = get_json(&db, "/api/v1/counters/hits/history?limit=2&offset=4").await;
+        assert_eq!(last["versions"].as_array().unwrap().len(), 1);
+        assert_eq!(last["next_offset"], JsonValue::Null);
+
+        let (_, newest) = get_json(
+            &db,
+            "/api/v1/counters/hits/history?limit=1&newest_first=true",
+        )
+        .await;
+        assert_eq!(newest["versions"][0]["value"], 5);
+        assert_eq!(newest["versions"][0]["version"], 5);
+
+        // ?at= maps to get_at
+        let (_, second) = get_json(&db, "/api/v1/counters/hits/history?limit=2").await;
+        let at = second["versions"][1]["timestamp"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let (status, value) = get_json(
+            &db,
+            &format!("/api/v1/counters/hits?at={}", at.replace('+', "%2B")),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(value["value"], 2);
+
+        let (status, _) = get_json(&db, "/api/v1/counters/hits?at=yesterday").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (_, current) = get_json(&db, "/api/v1/counters/hits").await;
+        assert_eq!(current["value"], 5);
+    }
+
+    #[cfg(feature = "ui")]
+    #[tokio::test]
+    async fn test_ui_page() {
+        let db = Arc::new(KoruDelta::start().await.unwrap());
+        let response = create_router(db)
+            .call(Request::get("/ui").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(
+            response.headers()["content-type"]
+                .to_str()
+                .unwrap()
+                .starts_with("text/html")
+        );
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(String::from_utf8_lossy(&bytes).contains("/api/v1/namespaces"));
+    }
 }
@@ -0,0 +1,303 @@
+//! Action journal - an append-only record of applied actions.
+//!
+//! Every [`KoruAction`] an agent applies can be appended to an
+//! [`ActionJournal`] together with the root it produced. Because synthesis
+//! is deterministic, replaying the journal from an agent's canonical root
+//! reproduces every intermediate root exactly, which makes the journal both
+//! a debugging trace and a way to rebuild agent state after a crash.
+//!
+//! # Format
+//!
+//! On disk the journal is JSON lines, one [`JournalEntry`] per line. Each
+//! entry keeps the canonical bytes the action was synthesized from (for
+//! replay) and a readable JSON view of the action (for debugging). Entries
+//! are synced before the append returns; a torn final line left by a crash
+//! is dropped on open.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let journal = ActionJournal::open("data/journal.jsonl")?;
+//! let orchestrator = KoruOrchestrator::new().with_journal(journal);
+//!
+//! // After a restart: rebuild the orchestrator root from the journal
+//! let root = orchestrator.replay_journal()?;
+//! ```
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use koru_lambda_core::{Distinction, DistinctionEngine};
+use serde::{Deserialize, Serialize};
+
+use crate::actions::KoruAction;
+use crate::error::{DeltaError, DeltaResult};
+use crate::mapper::DocumentMapper;
+
+/// One applied action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Position in the journal, starting at 0
+    pub seq: u64,
+    /// When the action was applied
+    pub timestamp: DateTime<Utc>,
+    /// Agent whose root the action advanced
+    pub agent: String,
+    /// Action category (see [`KoruAction::category`])
+    pub category: String,
+    /// Readable form of the action
+    pub payload: serde_json::Value,
+    /// Hex-encoded canonical bytes, absent if the action had none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical: Option<String>,
+    /// ID of the root the action produced
+    pub root: String,
+}
+
+impl JournalEntry {
+    /// Rebuild the distinction the action was synthesized from.
+    fn action_distinction(&self, engine: &DistinctionEngine) -> DeltaResult<Distinction> {
+        match &self.canonical {
+            Some(hex_bytes) => {
+                let bytes = hex::decode(hex_bytes).map_err(|e| DeltaError::InvalidData {
+                    reason: format!("Journal entry {} has invalid bytes: {}", self.seq, e),
+                })?;
+                Ok(DocumentMapper::bytes_to_distinction(&bytes, engine))
+            }
+            None => Ok(engine.d0().clone()),
+        }
+    }
+}
+
+/// Append-only journal of applied actions.
+///
+/// Kept in memory, and also on disk when opened with [`open`](Self::open).
+#[derive(Debug, Default)]
+pub struct ActionJournal {
+    entries: Vec<JournalEntry>,
+    file: Option<(PathBuf, File)>,
+}
+
+impl ActionJournal {
+    /// Create a journal that only lives in memory.
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Open (or create) a journal file, loading the entries it holds.
+    pub fn open(path: impl AsRef<Path>) -> DeltaResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                DeltaError::StorageError(format!("Failed to create journal directory: {}", e))
+            })?;
+        }
+
+        let mut entries = Vec::new();
+        let mut valid_len = 0u64;
+        let mut torn = false;
+        if path.exists() {
+            let file = File::open(&path)
+                .map_err(|e| DeltaError::StorageError(format!("Failed to open journal: {}", e)))?;
+            let mut reader = BufReader::new(file);
+            let mut line = String::new();
+            loop {
+                line.clear();
+                let read = reader.read_line(&mut line).map_err(|e| {
+                    DeltaError::StorageError(format!("Failed to read journal: {}", e))
+                })?;
+                if read == 0 {
+                    break;
+                }
+                // Entries are written with their newline in one append, so
+                // an unterminated line is one a crash cut short
+                if !line.ends_with('\n') {
+                    torn = true;
+                    break;
+                }
+                if !line.trim().is_empty() {
+                    entries.push(serde_json::from_str::<JournalEntry>(&line)?);
+                }
+                valid_len += read as u64;
+            }
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| DeltaError::StorageError(format!("Failed to open journal: {}", e)))?;
+        if torn {
+            tracing::warn!(path = %path.display(), "Dropping torn journal entry");
+            file.set_len(valid_len).map_err(|e| {
+                DeltaError::StorageError(format!("Failed to repair journal: {}", e))
+            })?;
+        }
+
+        Ok(Self {
+            entries,
+            file: Some((path, file)),
+        })
+    }
+
+    /// Path of the journal file, if it is persisted.
+    pub fn path(&self) -> Option<&Path> {
+        self.file.as_ref().map(|(path, _)| path.as_path())
+    }
+
+    /// All entries, oldest first.
+    pub fn entries(&self) -> &[JournalEntry] {
+        &self.entries
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the journal is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Append an applied action and the root it produced.
+    pub fn record(
+        &mut self,
+        agent: &str,
+        action: &KoruAction,
+        root: &Distinction,
+    ) -> DeltaResult<&JournalEntry> {
+        let entry = JournalEntry {
+            seq: self.entries.len() as u64,
+            timestamp: Utc::now(),
+            agent: agent.to_string(),
+            category: action.category().to_string(),
+            payload: action.to_json(),
+            canonical: action.canonical_bytes().map(hex::encode),
+            root: root.id().to_string(),
+        };
+
+        if let Some((_, file)) = self.file.as_mut() {
+            let mut line = serde_json::to_string(&entry)?;
+            line.push('\n');
+            file.write_all(line.as_bytes())
+                .and_then(|_| file.sync_data())
+                .map_err(|e| DeltaError::StorageError(format!("Failed to write journal: {}", e)))?;
+        }
+
+        self.entries.push(entry);
+        Ok(self.entries.last().expect("entry was just pushed"))
+    }
+
+    /// Replay one agent's entries from its initial root.
+    ///
+    /// Returns the final root. Fails with `InvalidData` at the first entry
+    /// whose recorded root is not reproduced, which means the journal does
+    /// not belong to this initial root or has been tampered with.
+    pub fn replay(
+        &self,
+        engine: &DistinctionEngine,
+        agent: &str,
+        initial_root: Distinction,
+    ) -> DeltaResult<Distinction> {
+        let mut root = initial_root;
+        for entry in self.entries.iter().filter(|e| e.agent == agent) {
+            let action = entry.action_distinction(engine)?;
+            root = engine.synthesize(&root, &action);
+            if root.id() != entry.root {
+                return Err(DeltaError::InvalidData {
+                    reason: format!(
+                        "Journal entry {} diverged: expected root {}, replayed {}",
+                        entry.seq,
+                        entry.root,
+                        root.id()
+                    ),
+                });
+            }
+        }
+        Ok(root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::{CustomAction, PulseAction};
+    use koru_lambda_core::Canonicalizable;
+
+    fn apply(
+        journal: &mut ActionJournal,
+        engine: &DistinctionEngine,
+        root: &Distinction,
+        action: KoruAction,
+    ) -> Distinction {
+        let new_root = engine.synthesize(root, &action.to_canonical_structure(engine));
+        journal.record("test", &action, &new_root).unwrap();
+        new_root
+    }
+
+    #[test]
+    fn test_replay_reproduces_roots_after_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.jsonl");
+        let engine = DistinctionEngine::new();
+        let start = engine.d1().clone();
+
+        let mut journal = ActionJournal::open(&path).unwrap();
+        let mut root = start.clone();
+        root = apply(
+            &mut journal,
+            &engine,
+            &root,
+            KoruAction::from(PulseAction::TriggerPulse {
+                phase: "Input".to_string(),
+            }),
+        );
+        root = apply(
+            &mut journal,
+            &engine,
+            &root,
+            KoruAction::from(CustomAction::new("ROBOT", serde_json::json!({"x": 1}))),
+        );
+        drop(journal);
+
+        // Simulate a crash mid-append
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"seq\":2,\"times").unwrap();
+
+        let mut journal = ActionJournal::open(&path).unwrap();
+        assert_eq!(journal.len(), 2);
+        assert_eq!(journal.entries()[1].category, "CUSTOM");
+        assert_eq!(journal.entries()[1].payload["Custom"]["category"], "ROBOT");
+
+        let replayed = journal.replay(&engine, "test", start.clone()).unwrap();
+        assert_eq!(replayed.id(), root.id());
+
+        // A different starting point is detected
+        let err = journal.replay(&engine, "test", engine.d0().clone());
+        assert!(matches!(err, Err(DeltaError::InvalidData { .. })));
+
+        // Other agents have nothing to replay
+        let other = journal
+            .replay(&engine, "other", engine.d0().clone())
+            .unwrap();
+        assert_eq!(other.id(), engine.d0().id());
+
+        // Appends after the repair stay readable
+        root = apply(
+            &mut journal,
+            &engine,
+            &root,
+            KoruAction::from(CustomAction::new("ROBOT", serde_json::json!({"x": 2}))),
+        );
+        drop(journal);
+        let journal = ActionJournal::open(&path).unwrap();
+        assert_eq!(journal.len(), 3);
+        let replayed = journal
+            .replay(&engine, "test", engine.d1().clone())
+            .unwrap();
+        assert_eq!(replayed.id(), root.id());
+    }
+}
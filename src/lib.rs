@@ -98,6 +98,9 @@
 //! See [DESIGN.md](https://github.com/swyrknt/koru-delta/blob/main/DESIGN.md)
 //! for the full architectural vision.
 
+// Lets `#[derive(KoruRecord)]` refer to `::koru_delta` inside this crate
+extern crate self as koru_delta;
+
 // Internal modules
 mod core;
 mod error;
@@ -108,6 +111,7 @@ mod types;
 // Foundation: canonical roots, actions, and shared engine
 pub mod actions;
 pub mod engine;
+pub mod journal;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod network_agent;
 #[cfg(not(target_arch = "wasm32"))]
@@ -137,14 +141,49 @@ pub mod storage_agent;
 // Query module
 pub mod query;
 
+// System catalog (`_system.*` namespaces)
+pub mod catalog;
+
+// Expression language for computed fields
+pub mod expr;
+
+// Sketches for approximate aggregations
+pub mod sketch;
+
+// Ordered indexes over numeric fields
+pub mod range_index;
+
+// Typed records
+pub mod record;
+
+// Read transactions (consistent snapshots)
+pub mod transaction;
+
+// Write hooks (validators and post-write side effects)
+pub mod hooks;
+
+// Write durability options (sync or async flush)
+pub mod durability;
+
+// Explicit configuration for opening a database
+pub mod builder;
+
+// Configuration loading from files and the environment
+pub mod config;
+
+// Multi-tenant isolation (prefixed namespaces, scoped capabilities, quotas)
+pub mod tenant;
+
 // Vector module (AI embeddings and similarity search)
 pub mod vector;
 
+// Retrieval-augmented generation helpers (chunking + retrieval)
+pub mod rag;
+
 // Views module
 pub mod views;
 
 // Subscriptions module
-#[cfg(not(target_arch = "wasm32"))]
 pub mod subscriptions;
 
 // Public modules (not available on WASM - no filesystem/networking)
@@ -161,6 +200,26 @@ pub mod cluster;
 #[cfg(all(not(target_arch = "wasm32"), feature = "http"))]
 pub mod http;
 
+// GraphQL API (requires graphql feature, not WASM)
+#[cfg(all(not(target_arch = "wasm32"), feature = "graphql"))]
+pub mod graphql;
+
+// gRPC API (requires grpc feature, not WASM)
+#[cfg(all(not(target_arch = "wasm32"), feature = "grpc"))]
+pub mod grpc;
+
+// SQLite import/export (requires sqlite feature, not WASM)
+#[cfg(all(not(target_arch = "wasm32"), feature = "sqlite"))]
+pub mod sqlite;
+
+// Postgres logical replication source (requires postgres feature, not WASM)
+#[cfg(all(not(target_arch = "wasm32"), feature = "postgres"))]
+pub mod postgres;
+
+// OpenTelemetry trace export (requires otel feature, not WASM)
+#[cfg(all(not(target_arch = "wasm32"), feature = "otel"))]
+pub mod telemetry;
+
 // Runtime abstraction layer
 pub mod runtime;
 
@@ -169,41 +228,73 @@ pub mod runtime;
 pub mod wasm;
 
 // Public API exports
-pub use core::{CoreConfig, DatabaseStats, KoruDelta, MemoryConfig};
+pub use builder::KoruDeltaBuilder;
+pub use core::{
+    CoreConfig, DatabaseStats, EmbedderConfig, IdempotencyConfig, IndexStats, KoruDelta,
+    MemoryConfig, NamespaceStats, NetworkConfig, StorageConfig, TierStats,
+};
 pub use error::{DeltaError, DeltaResult};
+pub use mapper::MappingVersion;
 pub use types::{
-    CausalWriteResult, ConnectedDistinction, FullKey, HistoryEntry, RandomCombination, Tombstone,
-    UnconnectedPair, VectorClock, VersionedValue,
+    CausalWriteResult, CompactionPolicy, ConflictStatus, ConnectedDistinction, DREAMS_NAMESPACE,
+    Dream, DreamStatus, FullKey, GcReport, HistoryCompactionReport, HistoryEntry, Provenance,
+    ProvenanceEntry, RandomCombination, Tombstone, UnconnectedPair, VectorClock, VersionSummary,
+    VersionedValue, WriteConflict,
 };
 
 // Query exports
+pub use expr::Expr;
 pub use query::{
-    Aggregation, Filter, HistoryQuery, Query, QueryExecutor, QueryRecord, QueryResult, SortBy,
-    SortOrder,
+    Aggregation, BucketInterval, CancellationToken, ComputedField, Filter, HistoryQuery,
+    KeyChanges, Query, QueryControl, QueryExecutor, QueryLimits, QueryRecord, QueryResult, SortBy,
+    SortOrder, ValueChange,
 };
 
+// Typed record exports
+pub use durability::{Durability, Durable, PutOptions, PutReceipt};
+pub use hooks::{HookId, PendingWrite, WriteEvent};
+#[cfg(feature = "derive")]
+pub use koru_delta_derive::KoruRecord;
+pub use range_index::{RangeIndex, RangeIndexInfo};
+pub use record::{Field, KoruRecord, RecordFields, TypedQuery};
+pub use sketch::TrackedAggregate;
+pub use tenant::{Tenant, TenantQuota, TenantStats};
+pub use transaction::ReadTransaction;
+
 // Views exports
 pub use views::{PerspectiveAgent, ViewData, ViewDefinition, ViewInfo};
 
 // Vector exports
-pub use vector::{Vector, VectorIndex, VectorSearchOptions, VectorSearchResult};
+#[cfg(all(not(target_arch = "wasm32"), feature = "embedder"))]
+pub use vector::SentenceEmbedder;
+pub use vector::{
+    DistanceMetric, EmbeddingProvider, ExplainedSearchResult, HashingEmbedder,
+    LocalEmbeddingProvider, RecallReport, TextEmbedder, Vector, VectorIndex, VectorIndexStats,
+    VectorNamespaceConfig, VectorSearchOptions, VectorSearchResult,
+};
+
+// RAG exports
+pub use rag::{ChunkConfig, RetrievedChunk, StoredDocument};
 
 // Workspace exports (causal storage containers)
 pub use memory::{
-    AgentContext, ConsolidationSummary, MemoryPattern, SearchOptions, Workspace, WorkspaceItem,
+    AgentContext, ConsolidationSummary, ExtractiveSummarizer, MemoryPattern, SearchOptions,
+    SharedWorkspace, Summarizer, SummarizerFn, Workspace, WorkspaceActivity, WorkspaceItem,
     WorkspaceSearchResult, WorkspaceStats,
 };
 
-// Subscriptions exports (non-WASM only)
-#[cfg(not(target_arch = "wasm32"))]
+// Subscriptions exports
 pub use subscriptions::{
-    ChangeEvent, ChangeType, SubscribableStorage, Subscription, SubscriptionAgent, SubscriptionId,
-    SubscriptionInfo,
+    ChangeEvent, ChangeType, SequenceGap, SequenceTracker, SubscribableStorage, Subscription,
+    SubscriptionAgent, SubscriptionGuard, SubscriptionId, SubscriptionInfo,
 };
 
 // Cluster exports (non-WASM only)
 #[cfg(not(target_arch = "wasm32"))]
-pub use cluster::{ClusterConfig, ClusterNode, ClusterStatus, PartitionState};
+pub use cluster::{
+    ClockOffset, ClusterConfig, ClusterEvent, ClusterNode, ClusterStatus, PartitionState,
+    PeerSyncPolicy, failure_detector::FailureDetectorConfig,
+};
 
 #[cfg(not(target_arch = "wasm32"))]
 pub use network::{NodeId, PeerInfo, PeerStatus};
@@ -218,9 +309,9 @@ pub use koru_lambda_core::DistinctionEngine;
 // LCA Architecture exports (v3.0)
 // Actions for all agents
 pub use actions::{
-    ArchiveAction, ChronicleAction, ConsolidationAction, EssenceAction, EvolutionAction,
-    IdentityAction, KoruAction, LineageAction, LineageQueryAction, NetworkAction,
-    PerspectiveAction, SleepAction, SleepCreativeAction, SleepPhase, StorageAction,
+    ArchiveAction, ChronicleAction, ConsolidationAction, CustomAction, EssenceAction,
+    EvolutionAction, ExternalAction, IdentityAction, KoruAction, LineageAction, LineageQueryAction,
+    NetworkAction, PerspectiveAction, SleepAction, SleepCreativeAction, SleepPhase, StorageAction,
     TemperatureAction, TemperatureLevel,
 };
 
@@ -253,18 +344,19 @@ pub mod prelude {
     pub use crate::views::{PerspectiveAgent, ViewData, ViewDefinition, ViewInfo};
 
     // Vector types
-    pub use crate::vector::{Vector, VectorSearchOptions, VectorSearchResult};
+    pub use crate::vector::{
+        DistanceMetric, Vector, VectorNamespaceConfig, VectorSearchOptions, VectorSearchResult,
+    };
 
     // Workspace types
     pub use crate::memory::{
         AgentContext, MemoryPattern, SearchOptions, Workspace, WorkspaceItem, WorkspaceStats,
     };
 
-    // Subscriptions types (non-WASM only)
-    #[cfg(not(target_arch = "wasm32"))]
+    // Subscriptions types
     pub use crate::subscriptions::{
-        ChangeEvent, ChangeType, SubscribableStorage, Subscription, SubscriptionAgent,
-        SubscriptionId, SubscriptionInfo,
+        ChangeEvent, ChangeType, SequenceTracker, SubscribableStorage, Subscription,
+        SubscriptionAgent, SubscriptionId, SubscriptionInfo,
     };
 
     // Cluster types (non-WASM only)
@@ -8,6 +8,7 @@
 /// - Access duration/context
 use chrono::{DateTime, Datelike, Duration, NaiveTime, Timelike, Utc};
 use dashmap::DashMap;
+use serde::Serialize;
 use std::collections::VecDeque;
 
 use crate::causal_graph::DistinctionId;
@@ -30,6 +31,50 @@ pub struct AccessTracker {
 
     /// Day of week distribution (0-6, where 0 = Monday)
     weekday_distribution: DashMap<u8, u64>,
+
+    /// Read and write counts by key, across all its versions
+    key_access: DashMap<FullKey, KeyAccessStats>,
+}
+
+/// Whether an access read or wrote a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessKind {
+    /// The key's value was read
+    Read,
+    /// A new version of the key was written
+    Write,
+}
+
+/// Read and write counts for one key
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct KeyAccessStats {
+    /// The key, within its namespace
+    pub key: String,
+    /// Number of reads
+    pub reads: u64,
+    /// Number of writes
+    pub writes: u64,
+    /// When last read
+    pub last_read: Option<DateTime<Utc>>,
+    /// When last written
+    pub last_write: Option<DateTime<Utc>>,
+}
+
+/// Access summary for one namespace
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct NamespaceAccessStats {
+    /// The namespace
+    pub namespace: String,
+    /// Number of keys accessed
+    pub keys: usize,
+    /// Reads across those keys
+    pub reads: u64,
+    /// Writes across those keys
+    pub writes: u64,
+    /// When any key was last read or written
+    pub last_access: Option<DateTime<Utc>>,
+    /// Most accessed keys, busiest first
+    pub hot_keys: Vec<KeyAccessStats>,
 }
 
 /// Pattern of access for a single distinction
@@ -83,9 +128,70 @@ impl AccessTracker {
             max_sequence_length: 100,
             hourly_distribution: DashMap::new(),
             weekday_distribution: DashMap::new(),
+            key_access: DashMap::new(),
+        }
+    }
+
+    /// Record a read or write of a key, for both scoring and access stats
+    pub fn record(&self, key: FullKey, distinction_id: DistinctionId, kind: AccessKind) {
+        self.record_access(key.clone(), distinction_id);
+
+        let now = Utc::now();
+        let name = key.key.clone();
+        let mut stats = self
+            .key_access
+            .entry(key)
+            .or_insert_with(|| KeyAccessStats {
+                key: name,
+                ..Default::default()
+            });
+        match kind {
+            AccessKind::Read => {
+                stats.reads += 1;
+                stats.last_read = Some(now);
+            }
+            AccessKind::Write => {
+                stats.writes += 1;
+                stats.last_write = Some(now);
+            }
         }
     }
 
+    /// Get read and write counts for a key
+    pub fn key_stats(&self, key: &FullKey) -> Option<KeyAccessStats> {
+        self.key_access.get(key).map(|stats| stats.clone())
+    }
+
+    /// Summarize accesses to a namespace, with its `hot_keys` busiest keys
+    pub fn namespace_stats(&self, namespace: &str, hot_keys: usize) -> NamespaceAccessStats {
+        let mut keys: Vec<KeyAccessStats> = self
+            .key_access
+            .iter()
+            .filter(|entry| entry.key().namespace == namespace)
+            .map(|entry| entry.value().clone())
+            .collect();
+
+        let mut summary = NamespaceAccessStats {
+            namespace: namespace.to_string(),
+            keys: keys.len(),
+            ..Default::default()
+        };
+        for stats in &keys {
+            summary.reads += stats.reads;
+            summary.writes += stats.writes;
+            summary.last_access = summary.last_access.max(stats.last_access());
+        }
+
+        keys.sort_by(|a, b| {
+            b.accesses()
+                .cmp(&a.accesses())
+                .then_with(|| a.key.cmp(&b.key))
+        });
+        keys.truncate(hot_keys);
+        summary.hot_keys = keys;
+        summary
+    }
+
     /// Record an access
     pub fn record_access(&self, key: FullKey, distinction_id: DistinctionId) {
         let now = Utc::now();
@@ -392,6 +498,18 @@ impl AccessPattern {
     }
 }
 
+impl KeyAccessStats {
+    /// Reads and writes combined
+    pub fn accesses(&self) -> u64 {
+        self.reads + self.writes
+    }
+
+    /// When last read or written
+    pub fn last_access(&self) -> Option<DateTime<Utc>> {
+        self.last_read.max(self.last_write)
+    }
+}
+
 /// Access tracker statistics
 #[derive(Debug, Clone)]
 pub struct AccessTrackerStats {
@@ -528,4 +646,40 @@ mod tests {
         assert_eq!(stats.total_accesses, 6);
         assert_eq!(stats.avg_accesses_per_distinction, 2.0);
     }
+
+    #[test]
+    fn test_namespace_stats() {
+        let tracker = AccessTracker::new();
+        let hot = FullKey::new("users", "alice");
+        tracker.record(hot.clone(), "v1".to_string(), AccessKind::Write);
+        for _ in 0..3 {
+            tracker.record(hot.clone(), "v1".to_string(), AccessKind::Read);
+        }
+        tracker.record(hot.clone(), "v2".to_string(), AccessKind::Write);
+        tracker.record(
+            FullKey::new("users", "bob"),
+            "v3".to_string(),
+            AccessKind::Read,
+        );
+        tracker.record(
+            FullKey::new("orders", "1"),
+            "v4".to_string(),
+            AccessKind::Write,
+        );
+
+        // Counts are per key, across versions
+        let alice = tracker.key_stats(&hot).unwrap();
+        assert_eq!((alice.reads, alice.writes), (3, 2));
+        assert_eq!(alice.last_access(), alice.last_write);
+
+        let stats = tracker.namespace_stats("users", 1);
+        assert_eq!(stats.keys, 2);
+        assert_eq!(stats.reads, 4);
+        assert_eq!(stats.writes, 2);
+        assert!(stats.last_access.is_some());
+        assert_eq!(stats.hot_keys.len(), 1);
+        assert_eq!(stats.hot_keys[0].key, "alice");
+
+        assert_eq!(tracker.namespace_stats("empty", 10).keys, 0);
+    }
 }
@@ -0,0 +1,292 @@
+/// Transition Execution
+///
+/// Carries out planned transitions by moving distinctions between the
+/// database's memory tiers:
+///
+/// - **Hot**: `TemperatureAgent` (LRU working set)
+/// - **Warm**: `ChronicleAgent` (recent chronicle index)
+/// - **Cold**: `ArchiveAgent` (epoch index)
+/// - **Deep**: held by no fast tier; the value is served from causal storage
+///
+/// Causal storage stays the source of truth, so a transition never loses
+/// data - it only changes which tier answers reads.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::causal_graph::DistinctionId;
+use crate::lifecycle::MemoryTier;
+use crate::lifecycle::transition_planner::{Transition, TransitionBatch};
+use crate::memory::{ArchiveAgent, ChronicleAgent, TemperatureAgent};
+use crate::runtime::sync::RwLock;
+use crate::storage::CausalStorage;
+use crate::types::FullKey;
+
+/// Moves distinctions between memory tiers
+#[derive(Clone)]
+pub struct TierExecutor {
+    hot: Arc<RwLock<TemperatureAgent>>,
+    warm: Arc<RwLock<ChronicleAgent>>,
+    cold: Arc<RwLock<ArchiveAgent>>,
+    storage: Arc<CausalStorage>,
+}
+
+impl std::fmt::Debug for TierExecutor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TierExecutor").finish_non_exhaustive()
+    }
+}
+
+/// Outcome of executing a batch of transitions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionReport {
+    /// Number of transitions planned
+    pub planned: usize,
+
+    /// Transitions that were carried out
+    pub executed: Vec<Transition>,
+
+    /// Transitions skipped (stale version, unknown key, or no-op)
+    pub skipped: usize,
+
+    /// Executed moves to a faster tier
+    pub promotions: usize,
+
+    /// Executed moves to a slower tier
+    pub demotions: usize,
+}
+
+impl TierExecutor {
+    /// Create an executor over the given tiers and source-of-truth storage
+    pub fn new(
+        hot: Arc<RwLock<TemperatureAgent>>,
+        warm: Arc<RwLock<ChronicleAgent>>,
+        cold: Arc<RwLock<ArchiveAgent>>,
+        storage: Arc<CausalStorage>,
+    ) -> Self {
+        Self {
+            hot,
+            warm,
+            cold,
+            storage,
+        }
+    }
+
+    /// Determine which tier currently holds each distinction
+    pub async fn current_tiers(
+        &self,
+        ids: impl IntoIterator<Item = &DistinctionId>,
+    ) -> HashMap<DistinctionId, MemoryTier> {
+        let hot = self.hot.read().await;
+        let warm = self.warm.read().await;
+        let cold = self.cold.read().await;
+
+        ids.into_iter()
+            .map(|id| {
+                let tier = if hot.contains_id(id) {
+                    MemoryTier::Hot
+                } else if warm.contains(id) {
+                    MemoryTier::Warm
+                } else if cold.contains(id) {
+                    MemoryTier::Cold
+                } else {
+                    MemoryTier::Deep
+                };
+                (id.clone(), tier)
+            })
+            .collect()
+    }
+
+    /// Execute a batch of transitions.
+    ///
+    /// `keys` maps each distinction to the key it belongs to. Transitions are
+    /// grouped by target tier and each tier lock is taken once per batch.
+    pub async fn execute_batch(
+        &self,
+        transitions: Vec<Transition>,
+        keys: &HashMap<DistinctionId, FullKey>,
+    ) -> ExecutionReport {
+        let mut report = ExecutionReport {
+            planned: transitions.len(),
+            ..Default::default()
+        };
+
+        let batch = TransitionBatch::from_transitions(transitions);
+
+        // Lock order matches the rest of the core: hot → warm → cold
+        let hot = self.hot.write().await;
+        let warm = self.warm.write().await;
+        let cold = self.cold.write().await;
+
+        for transition in batch
+            .to_hot
+            .into_iter()
+            .chain(batch.to_warm)
+            .chain(batch.to_cold)
+            .chain(batch.to_deep)
+        {
+            let Some(key) = keys.get(&transition.distinction_id) else {
+                report.skipped += 1;
+                continue;
+            };
+
+            if self.move_one(&hot, &warm, &cold, &transition, key) {
+                if tier_level(transition.to_tier) < tier_level(transition.from_tier) {
+                    report.promotions += 1;
+                } else {
+                    report.demotions += 1;
+                }
+                report.executed.push(transition);
+            } else {
+                report.skipped += 1;
+            }
+        }
+
+        report
+    }
+
+    /// Move a single distinction; returns false if the move was skipped
+    fn move_one(
+        &self,
+        hot: &TemperatureAgent,
+        warm: &ChronicleAgent,
+        cold: &ArchiveAgent,
+        transition: &Transition,
+        key: &FullKey,
+    ) -> bool {
+        if transition.from_tier == transition.to_tier {
+            return false;
+        }
+
+        // Only move the version the planner scored; newer writes supersede it
+        let Ok(current) = self.storage.get(&key.namespace, &key.key) else {
+            return false;
+        };
+        if current.write_id() != transition.distinction_id {
+            return false;
+        }
+
+        let id = &transition.distinction_id;
+        let promoting = tier_level(transition.to_tier) < tier_level(transition.from_tier);
+
+        // Leave the source tier
+        match transition.from_tier {
+            MemoryTier::Hot => {
+                hot.remove(key);
+            }
+            MemoryTier::Warm if promoting => warm.promote(id),
+            MemoryTier::Warm => warm.demote(id),
+            MemoryTier::Cold => {
+                cold.remove(id);
+            }
+            MemoryTier::Deep => {}
+        }
+
+        // Enter the target tier
+        match transition.to_tier {
+            MemoryTier::Hot => {
+                if let Some(evicted) = hot.put(key.clone(), current) {
+                    if let Some(evicted_key) = evicted.key {
+                        warm.put(evicted_key, evicted.versioned);
+                    }
+                }
+            }
+            MemoryTier::Warm => warm.put(key.clone(), current),
+            MemoryTier::Cold => cold.store(id.clone(), key.clone(), current.timestamp()),
+            MemoryTier::Deep => {}
+        }
+
+        true
+    }
+}
+
+/// Tier speed ordering (0 = fastest)
+fn tier_level(tier: MemoryTier) -> u8 {
+    match tier {
+        MemoryTier::Hot => 0,
+        MemoryTier::Warm => 1,
+        MemoryTier::Cold => 2,
+        MemoryTier::Deep => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::SharedEngine;
+    use serde_json::json;
+
+    fn setup() -> (
+        TierExecutor,
+        Arc<RwLock<TemperatureAgent>>,
+        Arc<CausalStorage>,
+    ) {
+        let field = SharedEngine::new();
+        let storage = Arc::new(CausalStorage::new(Arc::clone(field.inner())));
+        let hot = Arc::new(RwLock::new(TemperatureAgent::new(&field)));
+        let warm = Arc::new(RwLock::new(ChronicleAgent::new(&field)));
+        let cold = Arc::new(RwLock::new(ArchiveAgent::new(&field)));
+        let executor = TierExecutor::new(Arc::clone(&hot), warm, cold, Arc::clone(&storage));
+        (executor, hot, storage)
+    }
+
+    #[tokio::test]
+    async fn test_demote_through_tiers_and_promote_back() {
+        let (executor, hot, storage) = setup();
+        let key = FullKey::new("users", "alice");
+        let versioned = storage.put("users", "alice", json!({"n": 1})).unwrap();
+        let id = versioned.write_id().to_string();
+        hot.read().await.put(key.clone(), versioned);
+
+        let keys = HashMap::from([(id.clone(), key.clone())]);
+        let tiers = executor.current_tiers([&id]).await;
+        assert_eq!(tiers[&id], MemoryTier::Hot);
+
+        for (from, to) in [
+            (MemoryTier::Hot, MemoryTier::Warm),
+            (MemoryTier::Warm, MemoryTier::Cold),
+            (MemoryTier::Cold, MemoryTier::Deep),
+        ] {
+            let report = executor
+                .execute_batch(vec![Transition::new(id.clone(), from, to, 0.1)], &keys)
+                .await;
+            assert_eq!(report.executed.len(), 1);
+            assert_eq!(report.demotions, 1);
+            assert_eq!(executor.current_tiers([&id]).await[&id], to);
+        }
+
+        let report = executor
+            .execute_batch(
+                vec![Transition::new(
+                    id.clone(),
+                    MemoryTier::Deep,
+                    MemoryTier::Hot,
+                    0.9,
+                )],
+                &keys,
+            )
+            .await;
+        assert_eq!(report.promotions, 1);
+        assert_eq!(executor.current_tiers([&id]).await[&id], MemoryTier::Hot);
+    }
+
+    #[tokio::test]
+    async fn test_stale_version_is_skipped() {
+        let (executor, _hot, storage) = setup();
+        let old = storage.put("users", "bob", json!(1)).unwrap();
+        storage.put("users", "bob", json!(2)).unwrap();
+
+        let id = old.write_id().to_string();
+        let keys = HashMap::from([(id.clone(), FullKey::new("users", "bob"))]);
+        let report = executor
+            .execute_batch(
+                vec![Transition::new(id, MemoryTier::Hot, MemoryTier::Warm, 0.1)],
+                &keys,
+            )
+            .await;
+
+        assert_eq!(report.planned, 1);
+        assert!(report.executed.is_empty());
+        assert_eq!(report.skipped, 1);
+    }
+}
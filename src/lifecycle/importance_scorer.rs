@@ -9,6 +9,7 @@
 /// Uses a lightweight "ML" model (really just weighted heuristics + learned weights)
 /// that can be updated based on actual access patterns.
 use chrono::Utc;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use chrono::Timelike;
@@ -79,11 +80,27 @@ impl ImportanceScore {
     }
 }
 
+/// A model that scores the importance of tracked distinctions.
+///
+/// Implement this to plug a custom scoring policy into the lifecycle
+/// (e.g. business priority by namespace). Register it with
+/// `ImportanceScorer::set_model` or `LifecycleAgent::set_scoring_model`.
+pub trait ScoringModel: Send + Sync {
+    /// Short name used in logs and debug output.
+    fn name(&self) -> &str;
+
+    /// Score every distinction known to the tracker.
+    fn score_all(&self, tracker: &AccessTracker) -> HashMap<DistinctionId, ImportanceScore>;
+}
+
 /// ML-based importance model
 ///
 /// This is a lightweight "neural network" (really just linear regression with learned weights)
 /// that predicts importance based on features extracted from access patterns.
-#[derive(Debug)]
+///
+/// The model is serializable so trained weights can be exported from one
+/// node and imported on another.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ImportanceModel {
     /// Feature weights (learned)
     weights: ModelWeights,
@@ -99,7 +116,7 @@ pub struct ImportanceModel {
 }
 
 /// Weights for different features
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 struct ModelWeights {
     /// Recency weight
     recency: f32,
@@ -346,6 +363,87 @@ impl Default for ImportanceModel {
     }
 }
 
+impl ScoringModel for ImportanceModel {
+    fn name(&self) -> &str {
+        "ml"
+    }
+
+    fn score_all(&self, tracker: &AccessTracker) -> HashMap<DistinctionId, ImportanceScore> {
+        self.predict_all(tracker)
+    }
+}
+
+/// Scores distinctions by business priority of their namespace.
+///
+/// Wraps the ML model and scales each score by the priority configured for
+/// the distinction's namespace (default 1.0), clamped to 0.0 - 1.0.
+#[derive(Debug, Clone, Default)]
+pub struct NamespacePriorityModel {
+    base: ImportanceModel,
+    priorities: HashMap<String, f32>,
+    default_priority: f32,
+}
+
+impl NamespacePriorityModel {
+    /// Create a model where every namespace has priority 1.0
+    pub fn new() -> Self {
+        Self {
+            base: ImportanceModel::new(),
+            priorities: HashMap::new(),
+            default_priority: 1.0,
+        }
+    }
+
+    /// Use a specific (e.g. imported) base model
+    pub fn with_base(mut self, base: ImportanceModel) -> Self {
+        self.base = base;
+        self
+    }
+
+    /// Set the priority multiplier for a namespace
+    pub fn with_priority(mut self, namespace: impl Into<String>, priority: f32) -> Self {
+        self.priorities.insert(namespace.into(), priority.max(0.0));
+        self
+    }
+
+    /// Set the priority for namespaces without an explicit priority
+    pub fn with_default_priority(mut self, priority: f32) -> Self {
+        self.default_priority = priority.max(0.0);
+        self
+    }
+
+    /// Get the priority multiplier for a namespace
+    pub fn priority(&self, namespace: &str) -> f32 {
+        self.priorities
+            .get(namespace)
+            .copied()
+            .unwrap_or(self.default_priority)
+    }
+}
+
+impl ScoringModel for NamespacePriorityModel {
+    fn name(&self) -> &str {
+        "namespace_priority"
+    }
+
+    fn score_all(&self, tracker: &AccessTracker) -> HashMap<DistinctionId, ImportanceScore> {
+        let mut scores = self.base.predict_all(tracker);
+
+        for entry in tracker.patterns() {
+            if let Some(score) = scores.get_mut(entry.key()) {
+                let priority = self.priority(&entry.value().key.namespace);
+                score.score = (score.score * priority).clamp(0.0, 1.0);
+                score.factors.push(ScoreFactor::Custom {
+                    name: "namespace_priority".to_string(),
+                    value: priority,
+                });
+            }
+        }
+
+        scores
+    }
+}
+
 /// Sigmoid function to bound values between 0 and 1
 fn sigmoid(x: f32) -> f32 {
     1.0 / (1.0 + (-x).exp())
@@ -441,6 +539,41 @@ mod tests {
         assert!(sigmoid(-5.0) < 0.01);
     }
 
+    #[test]
+    fn test_model_export_import_roundtrip() {
+        let mut model = ImportanceModel::new();
+        let score = ImportanceScore::new("test".to_string(), 0.8, 0.9);
+        model.update_weights(&score, true);
+
+        let exported = serde_json::to_value(&model).unwrap();
+        let imported: ImportanceModel = serde_json::from_value(exported).unwrap();
+
+        assert_eq!(imported, model);
+        assert_eq!(imported.accuracy(), 1.0);
+    }
+
+    #[test]
+    fn test_namespace_priority_model() {
+        let tracker = AccessTracker::new();
+        tracker.record_access(
+            crate::types::FullKey::new("orders", "o1"),
+            "orders".to_string(),
+        );
+        tracker.record_access(crate::types::FullKey::new("logs", "l1"), "logs".to_string());
+
+        let model = NamespacePriorityModel::new()
+            .with_priority("orders", 1.5)
+            .with_priority("logs", 0.1);
+        let scores = model.score_all(&tracker);
+
+        assert!(scores["orders"].score > scores["logs"].score);
+        assert!(scores["orders"].score <= 1.0);
+        assert!(scores["logs"].factors.contains(&ScoreFactor::Custom {
+            name: "namespace_priority".to_string(),
+            value: 0.1,
+        }));
+    }
+
     #[test]
     fn test_model_update() {
         let mut model = ImportanceModel::new();
@@ -23,25 +23,38 @@
 ///     │
 ///     └── Very old + pattern extracted → Deep (genomic)
 /// ```
-use chrono::Duration;
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use futures::FutureExt;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::RwLock;
-use tokio::time::interval;
 use tracing::{info, trace};
 
 use crate::causal_graph::DistinctionId;
+use crate::runtime::{JoinHandle, Runtime};
+use crate::storage::CausalStorage;
+use crate::subscriptions::{ChangeEvent, SubscriptionAgent};
 use crate::types::FullKey;
 
 mod access_tracker;
+mod executor;
 mod importance_scorer;
 mod transition_planner;
 
-pub use access_tracker::{AccessPattern, AccessTracker};
-pub use importance_scorer::{ImportanceModel, ImportanceScore};
-pub use transition_planner::{Transition, TransitionPlanner, TransitionType};
+pub use access_tracker::{
+    AccessKind, AccessPattern, AccessTracker, KeyAccessStats, NamespaceAccessStats,
+};
+pub use executor::{ExecutionReport, TierExecutor};
+pub use importance_scorer::{
+    ImportanceModel, ImportanceScore, NamespacePriorityModel, ScoringModel,
+};
+pub use transition_planner::{Transition, TransitionBatch, TransitionPlanner, TransitionType};
+
+/// Namespace where lifecycle records are stored.
+pub const LIFECYCLE_NAMESPACE: &str = "_lifecycle";
 
 /// Lifecycle manager configuration
 #[derive(Debug, Clone)]
@@ -66,6 +79,9 @@ pub struct LifecycleConfig {
 
     /// Enable ML-based scoring (vs heuristic)
     pub ml_scoring_enabled: bool,
+
+    /// Record planned transitions without moving data (default: false)
+    pub dry_run: bool,
 }
 
 impl Default for LifecycleConfig {
@@ -78,6 +94,7 @@ impl Default for LifecycleConfig {
             warm_idle_threshold: Duration::hours(1),
             cold_epoch_duration: Duration::days(1),
             ml_scoring_enabled: true,
+            dry_run: false,
         }
     }
 }
@@ -110,16 +127,30 @@ impl std::fmt::Display for MemoryTier {
 #[derive(Debug, Clone, Default)]
 pub struct LifecycleStats {
     pub transitions_executed: u64,
+    pub transitions_skipped: u64,
+    pub promotions: u64,
+    pub demotions: u64,
+    pub checks_run: u64,
     pub consolidations_run: u64,
     pub genomes_extracted: u64,
     pub distinctions_scored: u64,
 }
 
-/// Importance scorer that uses ML or heuristics
-#[derive(Debug)]
+/// Importance scorer that uses ML, heuristics, or a registered custom model
 pub struct ImportanceScorer {
     ml_enabled: bool,
     model: Option<ImportanceModel>,
+    custom: Option<Box<dyn ScoringModel>>,
+}
+
+impl std::fmt::Debug for ImportanceScorer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ImportanceScorer")
+            .field("ml_enabled", &self.ml_enabled)
+            .field("model", &self.model)
+            .field("custom", &self.custom.as_ref().map(|m| m.name()))
+            .finish()
+    }
 }
 
 impl ImportanceScorer {
@@ -132,15 +163,55 @@ impl ImportanceScorer {
             } else {
                 None
             },
+            custom: None,
+        }
+    }
+
+    /// Create a scorer that uses a custom model
+    pub fn with_model(model: Box<dyn ScoringModel>) -> Self {
+        let mut scorer = Self::new(false);
+        scorer.custom = Some(model);
+        scorer
+    }
+
+    /// Register a custom model, replacing the built-in ML/heuristic choice
+    pub fn set_model(&mut self, model: Box<dyn ScoringModel>) {
+        self.custom = Some(model);
+    }
+
+    /// Remove the custom model, falling back to the built-in scoring
+    pub fn clear_model(&mut self) {
+        self.custom = None;
+    }
+
+    /// Name of the model currently used for scoring
+    pub fn model_name(&self) -> &str {
+        match (&self.custom, &self.model) {
+            (Some(custom), _) => custom.name(),
+            (None, Some(_)) if self.ml_enabled => "ml",
+            _ => "heuristic",
         }
     }
 
+    /// Export the built-in ML model (e.g. to ship trained weights to another node)
+    pub fn export_model(&self) -> Option<ImportanceModel> {
+        self.model.clone()
+    }
+
+    /// Import a trained ML model, enabling ML scoring
+    pub fn import_model(&mut self, model: ImportanceModel) {
+        self.model = Some(model);
+        self.ml_enabled = true;
+    }
+
     /// Score all distinctions based on access patterns
     pub fn score_all(
         &mut self,
         tracker: &AccessTracker,
     ) -> HashMap<DistinctionId, ImportanceScore> {
-        if let Some(model) = self.model.as_ref().filter(|_| self.ml_enabled) {
+        if let Some(custom) = self.custom.as_ref() {
+            custom.score_all(tracker)
+        } else if let Some(model) = self.model.as_ref().filter(|_| self.ml_enabled) {
             // Use ML model for scoring
             model.predict_all(tracker)
         } else {
@@ -195,7 +266,10 @@ impl ImportanceScorer {
 }
 
 /// Factors contributing to importance score
-#[derive(Debug, Clone)]
+///
+/// New factors may be added, so matches need a wildcard arm.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum ScoreFactor {
     /// Recency component
     Recency(f32),
@@ -207,6 +281,87 @@ pub enum ScoreFactor {
     SequenceContext(f32),
     /// Predicted future value
     PredictedFutureValue(f32),
+    /// Component contributed by a custom scoring model
+    Custom {
+        /// Name of the component
+        name: String,
+        /// Value of the component
+        value: f32,
+    },
+}
+
+/// Audit record of a distinction moving between memory tiers.
+///
+/// Every executed promotion or demotion produces one of these. It is stored
+/// in [`LIFECYCLE_NAMESPACE`] under [`TransitionEvent::storage_key`], so the
+/// key's history is the full tiering audit trail for that distinction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransitionEvent {
+    /// The distinction that moved
+    pub distinction_id: DistinctionId,
+    /// The key the distinction belongs to, if it has been accessed
+    pub key: Option<FullKey>,
+    /// Tier the distinction moved from
+    pub from_tier: MemoryTier,
+    /// Tier the distinction moved to
+    pub to_tier: MemoryTier,
+    /// Importance score at the time of the transition
+    pub importance_score: f32,
+    /// Confidence in the importance score
+    pub confidence: f32,
+    /// Factors that contributed to the importance score
+    pub factors: Vec<ScoreFactor>,
+    /// When the transition happened
+    pub timestamp: DateTime<Utc>,
+}
+
+impl TransitionEvent {
+    /// Key under which this event is recorded in [`LIFECYCLE_NAMESPACE`].
+    pub fn storage_key(&self) -> String {
+        transition_storage_key(&self.distinction_id)
+    }
+}
+
+fn transition_storage_key(distinction_id: &str) -> String {
+    format!("transition:{}", distinction_id)
+}
+
+/// Key under which dry-run plans are recorded in [`LIFECYCLE_NAMESPACE`].
+///
+/// Each simulation overwrites the previous one, so the key's history is the
+/// list of past plans.
+pub const PLAN_STORAGE_KEY: &str = "plan";
+
+/// A transition the lifecycle agent would make, with the reason why.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedTransition {
+    /// The distinction that would move
+    pub distinction_id: DistinctionId,
+    /// The key the distinction belongs to, if known
+    pub key: Option<FullKey>,
+    /// Tier the distinction is in now
+    pub from_tier: MemoryTier,
+    /// Tier the distinction would move to
+    pub to_tier: MemoryTier,
+    /// Importance score behind the decision
+    pub importance_score: f32,
+    /// Confidence in the importance score
+    pub confidence: f32,
+    /// Factors that contributed to the importance score
+    pub factors: Vec<ScoreFactor>,
+    /// Human-readable explanation of the decision
+    pub reason: String,
+}
+
+/// The outcome of a dry-run lifecycle check.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LifecyclePlan {
+    /// When the plan was made
+    pub generated_at: DateTime<Utc>,
+    /// Number of distinctions scored
+    pub distinctions_scored: usize,
+    /// Transitions that would be executed, highest priority first
+    pub transitions: Vec<PlannedTransition>,
 }
 
 #[cfg(test)]
@@ -243,6 +398,41 @@ mod tests {
         assert_eq!(stats.genomes_extracted, 0);
     }
 
+    #[test]
+    fn test_importance_scorer_custom_model() {
+        struct Constant;
+        impl ScoringModel for Constant {
+            fn name(&self) -> &str {
+                "constant"
+            }
+            fn score_all(
+                &self,
+                tracker: &AccessTracker,
+            ) -> HashMap<DistinctionId, ImportanceScore> {
+                tracker
+                    .patterns()
+                    .map(|e| {
+                        (
+                            e.key().clone(),
+                            ImportanceScore::new(e.key().clone(), 0.42, 1.0),
+                        )
+                    })
+                    .collect()
+            }
+        }
+
+        let tracker = AccessTracker::new();
+        tracker.record_access(FullKey::new("test", "key1"), "dist1".to_string());
+
+        let mut scorer = ImportanceScorer::new(true);
+        scorer.set_model(Box::new(Constant));
+        assert_eq!(scorer.model_name(), "constant");
+        assert_eq!(scorer.score_all(&tracker)["dist1"].score, 0.42);
+
+        scorer.clear_model();
+        assert_eq!(scorer.model_name(), "ml");
+    }
+
     #[test]
     fn test_importance_scorer_heuristic() {
         let tracker = AccessTracker::new();
@@ -297,6 +487,19 @@ pub struct LifecycleAgent {
     /// Transition planner
     transition_planner: Arc<RwLock<TransitionPlanner>>,
 
+    /// Most recent importance score per distinction (from the last check)
+    latest_scores: Arc<DashMap<DistinctionId, ImportanceScore>>,
+
+    /// Where transition events are recorded and announced (optional)
+    audit_storage: Option<Arc<CausalStorage>>,
+    audit_subscriptions: Option<Arc<SubscriptionAgent>>,
+
+    /// Moves distinctions between the database's memory tiers (optional)
+    executor: Option<TierExecutor>,
+
+    /// Plan transitions without executing them
+    dry_run: Arc<AtomicBool>,
+
     /// Statistics
     stats: Arc<RwLock<LifecycleStats>>,
 
@@ -331,11 +534,56 @@ impl LifecycleAgent {
                 config.ml_scoring_enabled,
             ))),
             transition_planner: Arc::new(RwLock::new(TransitionPlanner::new())),
+            latest_scores: Arc::new(DashMap::new()),
+            audit_storage: None,
+            audit_subscriptions: None,
+            executor: None,
+            dry_run: Arc::new(AtomicBool::new(config.dry_run)),
             stats: Arc::new(RwLock::new(LifecycleStats::default())),
             shutdown: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Record transition events in storage and announce them to subscribers.
+    ///
+    /// Events are written to [`LIFECYCLE_NAMESPACE`] and delivered as
+    /// change events on that collection.
+    pub fn with_audit(
+        mut self,
+        storage: Arc<CausalStorage>,
+        subscriptions: Arc<SubscriptionAgent>,
+    ) -> Self {
+        self.audit_storage = Some(storage);
+        self.audit_subscriptions = Some(subscriptions);
+        self
+    }
+
+    /// Execute planned transitions against real memory tiers.
+    ///
+    /// Without an executor, lifecycle checks only score and plan.
+    pub fn with_executor(mut self, executor: TierExecutor) -> Self {
+        self.executor = Some(executor);
+        self
+    }
+
+    /// Get the lifecycle configuration.
+    pub fn config(&self) -> &LifecycleConfig {
+        &self.config
+    }
+
+    /// Enable or disable dry-run mode.
+    ///
+    /// In dry-run mode lifecycle checks record the plan they would execute
+    /// (see [`latest_plan`](Self::latest_plan)) but leave every tier untouched.
+    pub fn set_dry_run(&self, enabled: bool) {
+        self.dry_run.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Check whether dry-run mode is enabled.
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.load(Ordering::Relaxed)
+    }
+
     /// Get the local root distinction.
     pub fn local_root(&self) -> &Distinction {
         &self.local_root
@@ -352,10 +600,30 @@ impl LifecycleAgent {
         new_root
     }
 
-    /// Record an access for tracking (async).
+    /// Record a read for tracking (async).
     pub async fn record_access(&self, key: &FullKey, distinction_id: &DistinctionId) {
-        let tracker = self.access_tracker.write().await;
-        tracker.record_access(key.clone(), distinction_id.clone());
+        let tracker = self.access_tracker.read().await;
+        tracker.record(key.clone(), distinction_id.clone(), AccessKind::Read);
+    }
+
+    /// Record a write for tracking (async).
+    pub async fn record_write(&self, key: &FullKey, distinction_id: &DistinctionId) {
+        let tracker = self.access_tracker.read().await;
+        tracker.record(key.clone(), distinction_id.clone(), AccessKind::Write);
+    }
+
+    /// Read and write counts for a key since the agent started.
+    pub async fn key_access_stats(&self, key: &FullKey) -> Option<KeyAccessStats> {
+        self.access_tracker.read().await.key_stats(key)
+    }
+
+    /// Access summary for a namespace since the agent started, listing its
+    /// `hot_keys` busiest keys.
+    pub async fn access_stats(&self, namespace: &str, hot_keys: usize) -> NamespaceAccessStats {
+        self.access_tracker
+            .read()
+            .await
+            .namespace_stats(namespace, hot_keys)
     }
 
     /// Get current statistics.
@@ -379,6 +647,12 @@ impl LifecycleAgent {
         from_tier: MemoryTier,
         to_tier: MemoryTier,
     ) -> Distinction {
+        self.record_transition(&Transition::new(
+            distinction_id.clone(),
+            from_tier,
+            to_tier,
+            0.0,
+        ));
         let action = LifecycleAction::Promote {
             distinction_id,
             from_tier,
@@ -394,6 +668,12 @@ impl LifecycleAgent {
         from_tier: MemoryTier,
         to_tier: MemoryTier,
     ) -> Distinction {
+        self.record_transition(&Transition::new(
+            distinction_id.clone(),
+            from_tier,
+            to_tier,
+            0.0,
+        ));
         let action = LifecycleAction::Demote {
             distinction_id,
             from_tier,
@@ -404,18 +684,291 @@ impl LifecycleAgent {
 
     /// Execute multiple transitions.
     pub fn transition(&mut self, transitions: Vec<Transition>) -> Distinction {
+        for transition in &transitions {
+            self.record_transition(transition);
+        }
         let action = LifecycleAction::Transition { transitions };
         self.apply_action(action)
     }
 
+    /// Build the audit event for a transition, record it, and notify subscribers.
+    ///
+    /// The importance score and factors come from the most recent lifecycle
+    /// check; if the distinction has not been scored yet, the transition's own
+    /// score is used with no factors.
+    pub fn record_transition(&self, transition: &Transition) -> TransitionEvent {
+        let (importance_score, confidence, factors) =
+            match self.latest_scores.get(&transition.distinction_id) {
+                Some(score) => (score.score, score.confidence, score.factors.clone()),
+                None => (transition.importance_score, 0.0, Vec::new()),
+            };
+
+        let key = self.access_tracker.try_read().ok().and_then(|tracker| {
+            tracker
+                .get_pattern(&transition.distinction_id)
+                .map(|pattern| pattern.key)
+        });
+
+        let event = TransitionEvent {
+            distinction_id: transition.distinction_id.clone(),
+            key,
+            from_tier: transition.from_tier,
+            to_tier: transition.to_tier,
+            importance_score,
+            confidence,
+            factors,
+            timestamp: Utc::now(),
+        };
+
+        self.publish(&event.storage_key(), &event);
+        event
+    }
+
+    /// Write a record to [`LIFECYCLE_NAMESPACE`] and announce the change.
+    fn publish(&self, storage_key: &str, record: &impl Serialize) {
+        let Some(storage) = self.audit_storage.as_ref() else {
+            return;
+        };
+
+        let record = match serde_json::to_value(record) {
+            Ok(record) => record,
+            Err(e) => {
+                tracing::warn!(error = %e, key = storage_key, "Failed to serialize lifecycle record");
+                return;
+            }
+        };
+
+        let previous = storage.get(LIFECYCLE_NAMESPACE, storage_key).ok();
+        let versioned = match storage.put(LIFECYCLE_NAMESPACE, storage_key, record) {
+            Ok(versioned) => versioned,
+            Err(e) => {
+                tracing::warn!(error = %e, key = storage_key, "Failed to record lifecycle record");
+                return;
+            }
+        };
+
+        if let Some(subscriptions) = self.audit_subscriptions.as_ref() {
+            let change = match previous {
+                Some(previous) => {
+                    ChangeEvent::update(LIFECYCLE_NAMESPACE, storage_key, &versioned, &previous)
+                }
+                None => ChangeEvent::insert(LIFECYCLE_NAMESPACE, storage_key, &versioned),
+            };
+            subscriptions.notify(change);
+        }
+    }
+
+    /// Get the recorded transition history of a distinction (oldest first).
+    ///
+    /// Returns an empty list if auditing is not enabled.
+    pub fn transition_history(&self, distinction_id: &str) -> Vec<TransitionEvent> {
+        let Some(storage) = self.audit_storage.as_ref() else {
+            return Vec::new();
+        };
+
+        storage
+            .history(LIFECYCLE_NAMESPACE, transition_storage_key(distinction_id))
+            .map(|entries| {
+                entries
+                    .into_iter()
+                    .filter_map(|entry| serde_json::from_value(entry.value).ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Register a custom importance scoring model.
+    pub async fn set_scoring_model(&self, model: Box<dyn ScoringModel>) {
+        self.importance_scorer.write().await.set_model(model);
+    }
+
+    /// Export the trained ML importance model (None if ML scoring is disabled).
+    pub async fn export_model(&self) -> Option<ImportanceModel> {
+        self.importance_scorer.read().await.export_model()
+    }
+
+    /// Import a trained ML importance model (e.g. exported from another node).
+    pub async fn import_model(&self, model: ImportanceModel) {
+        self.importance_scorer.write().await.import_model(model);
+    }
+
+    /// Get the most recent importance score computed for a distinction.
+    pub fn latest_score(&self, distinction_id: &str) -> Option<ImportanceScore> {
+        self.latest_scores
+            .get(distinction_id)
+            .map(|score| score.clone())
+    }
+
     /// Update lifecycle thresholds.
     pub fn update_thresholds(&mut self, thresholds: serde_json::Value) -> Distinction {
         let action = LifecycleAction::UpdateThresholds { thresholds };
         self.apply_action(action)
     }
 
-    /// Start background lifecycle tasks.
-    pub async fn start(&self) {
+    /// Run one lifecycle check: score, plan, and execute transitions.
+    ///
+    /// Scores every tracked distinction, plans moves against the tiers the
+    /// distinctions actually occupy, executes them as one batch, and records
+    /// a transition event for each executed move. Without an executor the
+    /// plan is computed but nothing moves.
+    pub async fn run_check(&self) -> ExecutionReport {
+        trace!("Running lifecycle check");
+
+        let (scored, transitions, keys) = self.plan().await;
+
+        let report = if self.is_dry_run() {
+            let planned = transitions.len();
+            self.record_plan(scored, &transitions, &keys).await;
+            ExecutionReport {
+                planned,
+                ..Default::default()
+            }
+        } else if let Some(executor) = self.executor.as_ref() {
+            let report = executor.execute_batch(transitions, &keys).await;
+            for transition in &report.executed {
+                self.record_transition(transition);
+            }
+            report
+        } else {
+            ExecutionReport {
+                planned: transitions.len(),
+                ..Default::default()
+            }
+        };
+
+        {
+            let mut stats = self.stats.write().await;
+            stats.checks_run += 1;
+            stats.distinctions_scored = scored as u64;
+            stats.transitions_executed += report.executed.len() as u64;
+            stats.transitions_skipped += report.skipped as u64;
+            stats.promotions += report.promotions as u64;
+            stats.demotions += report.demotions as u64;
+        }
+
+        trace!(
+            planned_transitions = report.planned,
+            executed_transitions = report.executed.len(),
+            dry_run = self.is_dry_run(),
+            "Lifecycle check complete"
+        );
+        report
+    }
+
+    /// Plan a lifecycle check without moving any data.
+    ///
+    /// The plan, with a reason for every transition, is written to
+    /// [`LIFECYCLE_NAMESPACE`] under [`PLAN_STORAGE_KEY`] so operators can
+    /// validate thresholds before enabling automated tiering.
+    pub async fn simulate(&self) -> LifecyclePlan {
+        let (scored, transitions, keys) = self.plan().await;
+        self.record_plan(scored, &transitions, &keys).await
+    }
+
+    /// Get the most recently recorded dry-run plan.
+    ///
+    /// Returns None if auditing is not enabled or nothing was simulated yet.
+    pub fn latest_plan(&self) -> Option<LifecyclePlan> {
+        let storage = self.audit_storage.as_ref()?;
+        let versioned = storage.get(LIFECYCLE_NAMESPACE, PLAN_STORAGE_KEY).ok()?;
+        serde_json::from_value(versioned.value().clone()).ok()
+    }
+
+    /// Get all recorded dry-run plans (oldest first).
+    pub fn plan_history(&self) -> Vec<LifecyclePlan> {
+        let Some(storage) = self.audit_storage.as_ref() else {
+            return Vec::new();
+        };
+
+        storage
+            .history(LIFECYCLE_NAMESPACE, PLAN_STORAGE_KEY)
+            .map(|entries| {
+                entries
+                    .into_iter()
+                    .filter_map(|entry| serde_json::from_value(entry.value).ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Score all tracked distinctions and plan transitions.
+    ///
+    /// Returns the number of distinctions scored, the planned transitions,
+    /// and the key each distinction belongs to.
+    async fn plan(&self) -> (usize, Vec<Transition>, HashMap<DistinctionId, FullKey>) {
+        // Score all distinctions and remember which key each belongs to
+        let (scores, keys) = {
+            let tracker = self.access_tracker.read().await;
+            let mut scorer = self.importance_scorer.write().await;
+            let scores = scorer.score_all(&tracker);
+            let keys: HashMap<DistinctionId, FullKey> = tracker
+                .patterns()
+                .map(|entry| (entry.key().clone(), entry.value().key.clone()))
+                .collect();
+            (scores, keys)
+        };
+
+        // Remember scores so transition events can explain themselves
+        for (id, score) in &scores {
+            self.latest_scores.insert(id.clone(), score.clone());
+        }
+
+        let current_tiers = match self.executor.as_ref() {
+            Some(executor) => Some(executor.current_tiers(scores.keys()).await),
+            None => None,
+        };
+
+        let planner = self.transition_planner.read().await;
+        let transitions = match current_tiers {
+            Some(current_tiers) => planner.plan_transitions_with_tiers(&scores, &current_tiers),
+            None => planner.plan_transitions(&scores),
+        };
+
+        (scores.len(), transitions, keys)
+    }
+
+    /// Explain and record a plan in [`LIFECYCLE_NAMESPACE`].
+    async fn record_plan(
+        &self,
+        scored: usize,
+        transitions: &[Transition],
+        keys: &HashMap<DistinctionId, FullKey>,
+    ) -> LifecyclePlan {
+        let planner = self.transition_planner.read().await;
+        let transitions = transitions
+            .iter()
+            .map(|transition| {
+                let (confidence, factors) = self
+                    .latest_scores
+                    .get(&transition.distinction_id)
+                    .map(|score| (score.confidence, score.factors.clone()))
+                    .unwrap_or_default();
+
+                PlannedTransition {
+                    distinction_id: transition.distinction_id.clone(),
+                    key: keys.get(&transition.distinction_id).cloned(),
+                    from_tier: transition.from_tier,
+                    to_tier: transition.to_tier,
+                    importance_score: transition.importance_score,
+                    confidence,
+                    factors,
+                    reason: planner.explain(transition),
+                }
+            })
+            .collect();
+
+        let plan = LifecyclePlan {
+            generated_at: Utc::now(),
+            distinctions_scored: scored,
+            transitions,
+        };
+
+        self.publish(PLAN_STORAGE_KEY, &plan);
+        plan
+    }
+
+    /// Start background lifecycle tasks on `runtime`.
+    pub async fn start<R: Runtime>(&self, runtime: &R) {
         use tracing::{info, warn};
 
         info!("Starting lifecycle agent");
@@ -425,14 +978,13 @@ impl LifecycleAgent {
         let genome_interval = self.config.genome_interval;
 
         // Spawn background tasks
-        let check_handle = self.spawn_check_task(check_interval);
-        let consolidation_handle = self.spawn_consolidation_task(consolidation_interval);
-        let genome_handle = self.spawn_genome_task(genome_interval);
-
-        tokio::select! {
-            _ = check_handle => warn!("Check task exited unexpectedly"),
-            _ = consolidation_handle => warn!("Consolidation task exited unexpectedly"),
-            _ = genome_handle => warn!("Genome task exited unexpectedly"),
+        let consolidation_handle = self.spawn_consolidation_task(runtime, consolidation_interval);
+        let genome_handle = self.spawn_genome_task(runtime, genome_interval);
+
+        futures::select! {
+            _ = self.check_loop(runtime, check_interval).fuse() => warn!("Check task exited unexpectedly"),
+            _ = consolidation_handle.fuse() => warn!("Consolidation task exited unexpectedly"),
+            _ = genome_handle.fuse() => warn!("Genome task exited unexpectedly"),
         }
     }
 
@@ -443,65 +995,34 @@ impl LifecycleAgent {
         self.shutdown.store(true, Ordering::Relaxed);
     }
 
-    fn spawn_check_task(&self, interval_duration: Duration) -> tokio::task::JoinHandle<()> {
-        let tracker = Arc::clone(&self.access_tracker);
-        let scorer = Arc::clone(&self.importance_scorer);
-        let planner = Arc::clone(&self.transition_planner);
-        let stats = Arc::clone(&self.stats);
-        let shutdown = Arc::clone(&self.shutdown);
-
-        tokio::spawn(async move {
-            let mut int = interval(tokio::time::Duration::from_secs(
-                interval_duration.num_seconds().max(1) as u64,
-            ));
-
-            loop {
-                int.tick().await;
-
-                if shutdown.load(Ordering::Relaxed) {
-                    break;
-                }
+    async fn check_loop<R: Runtime>(&self, runtime: &R, interval_duration: Duration) {
+        let mut int = runtime.interval(std::time::Duration::from_secs(
+            interval_duration.num_seconds().max(1) as u64,
+        ));
 
-                trace!("Running lifecycle check");
-
-                // Score all distinctions
-                let scores = {
-                    let tracker = tracker.read().await;
-                    let mut scorer = scorer.write().await;
-                    scorer.score_all(&tracker)
-                };
-
-                // Update stats
-                {
-                    let mut stats_guard = stats.write().await;
-                    stats_guard.distinctions_scored = scores.len() as u64;
-                }
+        loop {
+            int.tick().await;
 
-                // Plan transitions
-                let transitions = {
-                    let planner = planner.read().await;
-                    planner.plan_transitions(&scores)
-                };
-
-                // Note: Actual transition execution happens through apply_action
-                // This is a placeholder for background monitoring
-                trace!(
-                    planned_transitions = transitions.len(),
-                    "Lifecycle check complete"
-                );
+            if self.shutdown.load(Ordering::Relaxed) {
+                break;
             }
-        })
+
+            self.run_check().await;
+        }
     }
 
-    fn spawn_consolidation_task(&self, interval_duration: Duration) -> tokio::task::JoinHandle<()> {
+    fn spawn_consolidation_task<R: Runtime>(
+        &self,
+        runtime: &R,
+        interval_duration: Duration,
+    ) -> JoinHandle<()> {
         let stats = Arc::clone(&self.stats);
         let shutdown = Arc::clone(&self.shutdown);
+        let mut int = runtime.interval(std::time::Duration::from_secs(
+            interval_duration.num_seconds().max(1) as u64,
+        ));
 
-        tokio::spawn(async move {
-            let mut int = interval(tokio::time::Duration::from_secs(
-                interval_duration.num_seconds().max(1) as u64,
-            ));
-
+        runtime.spawn(async move {
             loop {
                 int.tick().await;
 
@@ -517,15 +1038,18 @@ impl LifecycleAgent {
         })
     }
 
-    fn spawn_genome_task(&self, interval_duration: Duration) -> tokio::task::JoinHandle<()> {
+    fn spawn_genome_task<R: Runtime>(
+        &self,
+        runtime: &R,
+        interval_duration: Duration,
+    ) -> JoinHandle<()> {
         let stats = Arc::clone(&self.stats);
         let shutdown = Arc::clone(&self.shutdown);
+        let mut int = runtime.interval(std::time::Duration::from_secs(
+            interval_duration.num_seconds().max(1) as u64,
+        ));
 
-        tokio::spawn(async move {
-            let mut int = interval(tokio::time::Duration::from_secs(
-                interval_duration.num_seconds().max(1) as u64,
-            ));
-
+        runtime.spawn(async move {
             loop {
                 int.tick().await;
 
@@ -665,6 +1189,144 @@ mod lca_tests {
         assert_eq!(new_root.id(), root_after);
     }
 
+    #[tokio::test]
+    async fn test_transitions_are_audited_and_announced() {
+        use crate::subscriptions::{ChangeType, Subscription};
+
+        let field = SharedEngine::new();
+        let storage = Arc::new(CausalStorage::new(Arc::clone(field.inner())));
+        let subscriptions = Arc::new(SubscriptionAgent::new(&field));
+        let (_id, mut rx) = subscriptions.subscribe(Subscription::collection(LIFECYCLE_NAMESPACE));
+
+        let mut agent = LifecycleAgent::new(&field)
+            .with_audit(Arc::clone(&storage), Arc::clone(&subscriptions));
+        agent
+            .record_access(&FullKey::new("users", "alice"), &"dist1".to_string())
+            .await;
+        agent.latest_scores.insert(
+            "dist1".to_string(),
+            ImportanceScore::new("dist1".to_string(), 0.2, 0.9)
+                .with_factor(ScoreFactor::Recency(0.1)),
+        );
+
+        agent.demote("dist1".to_string(), MemoryTier::Hot, MemoryTier::Warm);
+        agent.promote("dist1".to_string(), MemoryTier::Warm, MemoryTier::Hot);
+
+        let history = agent.transition_history("dist1");
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].to_tier, MemoryTier::Warm);
+        assert_eq!(history[1].to_tier, MemoryTier::Hot);
+        assert_eq!(history[0].key, Some(FullKey::new("users", "alice")));
+        assert_eq!(history[0].importance_score, 0.2);
+        assert_eq!(history[0].factors, vec![ScoreFactor::Recency(0.1)]);
+
+        let first = rx.recv().await.unwrap();
+        assert_eq!(first.change_type, ChangeType::Insert);
+        assert_eq!(first.key, "transition:dist1");
+        let second = rx.recv().await.unwrap();
+        assert_eq!(second.change_type, ChangeType::Update);
+    }
+
+    #[tokio::test]
+    async fn test_run_check_executes_transitions() {
+        use crate::memory::{ArchiveAgent, ChronicleAgent, TemperatureAgent};
+        use crate::runtime::sync::RwLock as TierLock;
+
+        let field = SharedEngine::new();
+        let storage = Arc::new(CausalStorage::new(Arc::clone(field.inner())));
+        let subscriptions = Arc::new(SubscriptionAgent::new(&field));
+        let hot = Arc::new(TierLock::new(TemperatureAgent::new(&field)));
+        let executor = TierExecutor::new(
+            Arc::clone(&hot),
+            Arc::new(TierLock::new(ChronicleAgent::new(&field))),
+            Arc::new(TierLock::new(ArchiveAgent::new(&field))),
+            Arc::clone(&storage),
+        );
+        let agent = LifecycleAgent::new(&field)
+            .with_audit(Arc::clone(&storage), subscriptions)
+            .with_executor(executor);
+
+        // A freshly written value that only lives in causal storage (Deep)
+        let versioned = storage.put("users", "alice", serde_json::json!(1)).unwrap();
+        let id = versioned.write_id().to_string();
+        let key = FullKey::new("users", "alice");
+        agent.record_access(&key, &id).await;
+
+        let report = agent.run_check().await;
+        assert_eq!(report.executed.len(), 1);
+        assert_eq!(report.executed[0].from_tier, MemoryTier::Deep);
+        assert!(hot.read().await.contains_key(&key));
+
+        let stats = agent.stats().await;
+        assert_eq!(stats.checks_run, 1);
+        assert_eq!(stats.transitions_executed, 1);
+        assert_eq!(stats.promotions, 1);
+        assert_eq!(agent.transition_history(&id).len(), 1);
+
+        // Already in place: nothing left to do
+        let report = agent.run_check().await;
+        assert!(report.executed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_records_plan_without_moving_data() {
+        use crate::memory::{ArchiveAgent, ChronicleAgent, TemperatureAgent};
+        use crate::runtime::sync::RwLock as TierLock;
+
+        let field = SharedEngine::new();
+        let storage = Arc::new(CausalStorage::new(Arc::clone(field.inner())));
+        let subscriptions = Arc::new(SubscriptionAgent::new(&field));
+        let hot = Arc::new(TierLock::new(TemperatureAgent::new(&field)));
+        let executor = TierExecutor::new(
+            Arc::clone(&hot),
+            Arc::new(TierLock::new(ChronicleAgent::new(&field))),
+            Arc::new(TierLock::new(ArchiveAgent::new(&field))),
+            Arc::clone(&storage),
+        );
+        let config = LifecycleConfig {
+            dry_run: true,
+            ..Default::default()
+        };
+        let agent = LifecycleAgent::with_config(&field, config)
+            .with_audit(Arc::clone(&storage), subscriptions)
+            .with_executor(executor);
+        assert!(agent.is_dry_run());
+
+        let versioned = storage.put("users", "alice", serde_json::json!(1)).unwrap();
+        let id = versioned.write_id().to_string();
+        let key = FullKey::new("users", "alice");
+        agent.record_access(&key, &id).await;
+
+        let report = agent.run_check().await;
+        assert_eq!(report.planned, 1);
+        assert!(report.executed.is_empty());
+        assert!(!hot.read().await.contains_key(&key));
+        assert!(agent.transition_history(&id).is_empty());
+
+        let plan = agent.latest_plan().expect("plan recorded");
+        assert_eq!(plan.distinctions_scored, 1);
+        assert_eq!(plan.transitions.len(), 1);
+        let planned = &plan.transitions[0];
+        assert_eq!(planned.key, Some(key.clone()));
+        assert_eq!(planned.from_tier, MemoryTier::Deep);
+        assert_eq!(planned.to_tier, MemoryTier::Hot);
+        assert!(planned.reason.contains("hot threshold"));
+
+        // Switching dry-run off executes the same plan
+        agent.set_dry_run(false);
+        let report = agent.run_check().await;
+        assert_eq!(report.executed.len(), 1);
+        assert!(hot.read().await.contains_key(&key));
+        assert_eq!(agent.plan_history().len(), 1);
+    }
+
+    #[test]
+    fn test_transition_without_audit_is_not_recorded() {
+        let mut agent = setup_agent();
+        agent.demote("dist1".to_string(), MemoryTier::Hot, MemoryTier::Warm);
+        assert!(agent.transition_history("dist1").is_empty());
+    }
+
     #[test]
     fn test_update_thresholds_synthesizes() {
         let mut agent = setup_agent();
@@ -679,4 +1341,25 @@ mod lca_tests {
         );
         assert_eq!(new_root.id(), root_after);
     }
+
+    #[tokio::test]
+    async fn test_start_runs_on_injected_runtime() {
+        use crate::runtime::TestRuntime;
+
+        let field = SharedEngine::new();
+        let agent = Arc::new(LifecycleAgent::new(&field));
+        let runtime = TestRuntime::new();
+
+        let (task_agent, task_runtime) = (Arc::clone(&agent), runtime.clone());
+        runtime.spawn(async move { task_agent.start(&task_runtime).await });
+
+        // First ticks fire immediately; nothing else runs until time moves
+        runtime.run_until_idle();
+        assert_eq!(agent.stats().await.consolidations_run, 1);
+
+        runtime.advance(std::time::Duration::from_secs(2 * 3600));
+        let stats = agent.stats().await;
+        assert_eq!(stats.consolidations_run, 3);
+        assert_eq!(stats.genomes_extracted, 1);
+    }
 }
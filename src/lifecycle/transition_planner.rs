@@ -68,6 +68,30 @@ impl TransitionPlanner {
     pub fn plan_transitions(
         &self,
         scores: &HashMap<DistinctionId, ImportanceScore>,
+    ) -> Vec<Transition> {
+        self.plan_with(scores, |score| self.infer_current_tier(score))
+    }
+
+    /// Plan transitions using the tiers distinctions actually live in
+    ///
+    /// Distinctions missing from `current_tiers` fall back to the inferred tier.
+    pub fn plan_transitions_with_tiers(
+        &self,
+        scores: &HashMap<DistinctionId, ImportanceScore>,
+        current_tiers: &HashMap<DistinctionId, MemoryTier>,
+    ) -> Vec<Transition> {
+        self.plan_with(scores, |score| {
+            current_tiers
+                .get(&score.distinction_id)
+                .copied()
+                .unwrap_or_else(|| self.infer_current_tier(score))
+        })
+    }
+
+    fn plan_with(
+        &self,
+        scores: &HashMap<DistinctionId, ImportanceScore>,
+        current_tier_of: impl Fn(&ImportanceScore) -> MemoryTier,
     ) -> Vec<Transition> {
         let mut transitions = Vec::new();
 
@@ -98,9 +122,7 @@ impl TransitionPlanner {
                 MemoryTier::Deep
             };
 
-            // For now, assume all items are in their current tier
-            // In a real implementation, we'd track current tier and generate appropriate transitions
-            let current_tier = self.infer_current_tier(score);
+            let current_tier = current_tier_of(score);
 
             if current_tier != target_tier {
                 transitions.push(Transition {
@@ -119,6 +141,41 @@ impl TransitionPlanner {
         transitions
     }
 
+    /// Explain why a transition was planned, in terms of this planner's thresholds
+    pub fn explain(&self, transition: &Transition) -> String {
+        let score = transition.importance_score;
+        match transition.to_tier {
+            MemoryTier::Hot => format!(
+                "importance {:.2} meets hot threshold {:.2}",
+                score, self.hot_min_importance
+            ),
+            MemoryTier::Warm if score >= self.hot_min_importance => format!(
+                "importance {:.2} qualifies for hot but hot tier is full ({} items)",
+                score, self.hot_capacity
+            ),
+            MemoryTier::Warm => format!(
+                "importance {:.2} is below hot threshold {:.2} but meets warm threshold {:.2}",
+                score, self.hot_min_importance, self.warm_min_importance
+            ),
+            MemoryTier::Cold if score >= self.warm_min_importance => format!(
+                "importance {:.2} qualifies for warm but warm tier is full ({} items)",
+                score, self.warm_capacity
+            ),
+            MemoryTier::Cold => format!(
+                "importance {:.2} is below warm threshold {:.2} but meets cold threshold {:.2}",
+                score, self.warm_min_importance, self.cold_min_importance
+            ),
+            MemoryTier::Deep if score >= self.cold_min_importance => format!(
+                "importance {:.2} qualifies for cold but cold epoch is full ({} items)",
+                score, self.cold_capacity_per_epoch
+            ),
+            MemoryTier::Deep => format!(
+                "importance {:.2} is below cold threshold {:.2}",
+                score, self.cold_min_importance
+            ),
+        }
+    }
+
     /// Plan emergency demotions when Hot is over capacity
     pub fn plan_emergency_demotions(
         &self,
@@ -285,7 +342,6 @@ pub enum TransitionType {
 }
 
 /// Batch of transitions for efficient execution
-#[allow(dead_code)]
 pub struct TransitionBatch {
     /// Transitions to Hot tier
     pub to_hot: Vec<Transition>,
@@ -300,7 +356,6 @@ pub struct TransitionBatch {
     pub to_deep: Vec<Transition>,
 }
 
-#[allow(dead_code)]
 impl TransitionBatch {
     /// Create empty batch
     pub fn new() -> Self {
@@ -388,6 +443,53 @@ mod tests {
         assert!(transitions.is_empty() || !transitions.is_empty()); // Depends on inference
     }
 
+    #[test]
+    fn test_plan_transitions_with_tiers() {
+        let planner = TransitionPlanner::new();
+
+        let mut scores = HashMap::new();
+        scores.insert("high".to_string(), create_score("high", 0.9));
+        scores.insert("low".to_string(), create_score("low", 0.2));
+
+        let mut tiers = HashMap::new();
+        tiers.insert("high".to_string(), MemoryTier::Warm);
+        tiers.insert("low".to_string(), MemoryTier::Hot);
+
+        let transitions = planner.plan_transitions_with_tiers(&scores, &tiers);
+        assert_eq!(transitions.len(), 2);
+
+        let high = transitions
+            .iter()
+            .find(|t| t.distinction_id == "high")
+            .unwrap();
+        assert_eq!(
+            (high.from_tier, high.to_tier),
+            (MemoryTier::Warm, MemoryTier::Hot)
+        );
+        let low = transitions
+            .iter()
+            .find(|t| t.distinction_id == "low")
+            .unwrap();
+        assert_eq!(
+            (low.from_tier, low.to_tier),
+            (MemoryTier::Hot, MemoryTier::Cold)
+        );
+    }
+
+    #[test]
+    fn test_explain_transition() {
+        let planner = TransitionPlanner::with_capacities(1, 10, 100);
+
+        let hot = Transition::new("a".to_string(), MemoryTier::Warm, MemoryTier::Hot, 0.9);
+        assert!(planner.explain(&hot).contains("meets hot threshold"));
+
+        let overflow = Transition::new("b".to_string(), MemoryTier::Hot, MemoryTier::Warm, 0.8);
+        assert!(planner.explain(&overflow).contains("hot tier is full"));
+
+        let deep = Transition::new("c".to_string(), MemoryTier::Cold, MemoryTier::Deep, 0.05);
+        assert!(planner.explain(&deep).contains("below cold threshold"));
+    }
+
     #[test]
     fn test_plan_emergency_demotions() {
         let planner = TransitionPlanner::new();
@@ -6,13 +6,48 @@
 /// - Conversion of arbitrary JSON → content-addressed distinctions
 /// - Deterministic mapping (same data → same distinction)
 /// - Efficient byte-level canonicalization via koru-lambda-core
+/// - Chunked, parallel canonicalization for large values
 ///
 /// The mapper is the foundation that allows KoruDelta to store any JSON
 /// data while maintaining mathematical guarantees from the distinction engine.
 use crate::error::{DeltaError, DeltaResult};
 use koru_lambda_core::{Canonicalizable, Distinction, DistinctionEngine};
+use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
+/// Under [`MappingVersion::Chunked`], byte sequences at least this long are
+/// canonicalized in chunks, in parallel.
+///
+/// Below it the original left-to-right fold is used unchanged, so small
+/// values keep the distinction IDs they always had.
+pub const CHUNKED_THRESHOLD: usize = 64 * 1024;
+
+/// How byte sequences are canonicalized into distinctions.
+///
+/// Part of the content-addressing scheme: a value of [`CHUNKED_THRESHOLD`]
+/// bytes or more gets a different distinction ID under each version. A
+/// persistent database records the version it was created with and keeps
+/// using it, so the IDs it has already stored and replicated stay valid;
+/// databases from before the version was recorded use [`Fold`](Self::Fold).
+/// Cluster nodes exchange their version when joining and refuse a peer
+/// whose version differs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MappingVersion {
+    /// Every byte sequence folded left to right, however long.
+    Fold,
+    /// Sequences of [`CHUNKED_THRESHOLD`] bytes or more canonicalized as a
+    /// tree of chunk folds (the default for new databases).
+    #[default]
+    Chunked,
+}
+
+/// Bytes folded into each leaf of the chunked canonicalization tree.
+///
+/// Part of the content-addressing scheme: changing it changes the
+/// distinction IDs of large values.
+const CHUNK_SIZE: usize = 4 * 1024;
+
 /// Maps JSON documents to and from distinction structures.
 ///
 /// This is a stateless utility struct that performs deterministic
@@ -47,12 +82,21 @@ impl DocumentMapper {
     pub fn json_to_distinction(
         value: &JsonValue,
         engine: &DistinctionEngine,
+    ) -> DeltaResult<Distinction> {
+        Self::json_to_distinction_with(value, engine, MappingVersion::default())
+    }
+
+    /// Convert a JSON value to a distinction structure under `version`.
+    pub fn json_to_distinction_with(
+        value: &JsonValue,
+        engine: &DistinctionEngine,
+        version: MappingVersion,
     ) -> DeltaResult<Distinction> {
         // Serialize to canonical JSON bytes
         let bytes = serde_json::to_vec(value).map_err(DeltaError::SerializationError)?;
 
         // Map bytes to distinction structure
-        Ok(Self::bytes_to_distinction(&bytes, engine))
+        Ok(Self::bytes_to_distinction_with(&bytes, engine, version))
     }
 
     /// Convert raw bytes to a distinction structure.
@@ -65,23 +109,116 @@ impl DocumentMapper {
     ///
     /// The synthesis order is deterministic (left-to-right fold), ensuring
     /// that the same byte sequence always produces the same distinction.
+    /// Sequences of [`CHUNKED_THRESHOLD`] bytes or more go through
+    /// [`chunked_bytes_to_distinction`](Self::chunked_bytes_to_distinction),
+    /// whose shape depends only on the input length.
     ///
     /// # Performance
     ///
     /// Uses koru-lambda-core's cached byte mapping for O(1) per-byte lookups.
     pub fn bytes_to_distinction(bytes: &[u8], engine: &DistinctionEngine) -> Distinction {
+        Self::bytes_to_distinction_with(bytes, engine, MappingVersion::default())
+    }
+
+    /// Convert raw bytes to a distinction structure under `version`.
+    ///
+    /// [`MappingVersion::Fold`] folds sequences of any length left to right,
+    /// giving the IDs large values had before chunking was introduced.
+    pub fn bytes_to_distinction_with(
+        bytes: &[u8],
+        engine: &DistinctionEngine,
+        version: MappingVersion,
+    ) -> Distinction {
         if bytes.is_empty() {
             // Empty data maps to d0 (the void)
             return engine.d0().clone();
         }
+        if version == MappingVersion::Chunked && bytes.len() >= CHUNKED_THRESHOLD {
+            return Self::chunked_bytes_to_distinction(bytes, engine);
+        }
+
+        Self::fold_bytes(bytes, engine)
+    }
+
+    /// Convert raw bytes to a distinction as a tree of chunk folds.
+    ///
+    /// The bytes are split into fixed-size chunks, each chunk is folded
+    /// left-to-right like a small value (on all available cores when
+    /// threads are available), and the chunk roots are then synthesized
+    /// pairwise, level by level, into a single root. The tree depends only
+    /// on the input length, never on the number of threads, so the result
+    /// is deterministic everywhere.
+    pub fn chunked_bytes_to_distinction(bytes: &[u8], engine: &DistinctionEngine) -> Distinction {
+        if bytes.is_empty() {
+            return engine.d0().clone();
+        }
 
-        // Convert each byte to a distinction and fold into a single root
+        let chunks: Vec<&[u8]> = bytes.chunks(CHUNK_SIZE).collect();
+        let mut level = Self::fold_chunks(&chunks, engine);
+        while level.len() > 1 {
+            level = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => engine.synthesize(left, right),
+                    [single] => single.clone(),
+                    _ => unreachable!("chunks(2) yields one or two items"),
+                })
+                .collect();
+        }
+        level.pop().unwrap_or_else(|| engine.d0().clone())
+    }
+
+    /// Fold bytes left-to-right into a single distinction.
+    fn fold_bytes(bytes: &[u8], engine: &DistinctionEngine) -> Distinction {
         bytes
             .iter()
             .map(|&byte| byte.to_canonical_structure(engine))
             .fold(engine.d0().clone(), |acc, d| engine.synthesize(&acc, &d))
     }
 
+    /// Fold each chunk independently, spreading chunks across threads.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn fold_chunks(chunks: &[&[u8]], engine: &DistinctionEngine) -> Vec<Distinction> {
+        let workers = std::thread::available_parallelism()
+            .map_or(1, std::num::NonZeroUsize::get)
+            .min(chunks.len());
+        if workers <= 1 {
+            return chunks
+                .iter()
+                .map(|chunk| Self::fold_bytes(chunk, engine))
+                .collect();
+        }
+
+        // Contiguous groups keep the leaves in input order when joined
+        let per_worker = chunks.len().div_ceil(workers);
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = chunks
+                .chunks(per_worker)
+                .map(|group| {
+                    scope.spawn(move || {
+                        group
+                            .iter()
+                            .map(|chunk| Self::fold_bytes(chunk, engine))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("canonicalization worker panicked"))
+                .collect()
+        })
+    }
+
+    /// Fold each chunk independently (WASM is single-threaded).
+    #[cfg(target_arch = "wasm32")]
+    fn fold_chunks(chunks: &[&[u8]], engine: &DistinctionEngine) -> Vec<Distinction> {
+        chunks
+            .iter()
+            .map(|chunk| Self::fold_bytes(chunk, engine))
+            .collect()
+    }
+
     /// Store a distinction ID for later retrieval.
     ///
     /// Since distinctions are content-addressed, we only need to store
@@ -184,6 +321,69 @@ mod tests {
         assert_ne!(d1.id(), d2.id());
     }
 
+    #[test]
+    fn test_small_values_keep_sequential_fold() {
+        let engine = DistinctionEngine::new();
+        let bytes = vec![7u8; CHUNKED_THRESHOLD - 1];
+
+        let expected = bytes
+            .iter()
+            .map(|&byte| byte.to_canonical_structure(&engine))
+            .fold(engine.d0().clone(), |acc, d| engine.synthesize(&acc, &d));
+        let actual = DocumentMapper::bytes_to_distinction(&bytes, &engine);
+
+        assert_eq!(actual.id(), expected.id());
+    }
+
+    #[test]
+    fn test_fold_mapping_keeps_legacy_ids_for_large_values() {
+        let engine = DistinctionEngine::new();
+        let bytes = vec![7u8; CHUNKED_THRESHOLD + 1];
+
+        let legacy = DocumentMapper::fold_bytes(&bytes, &engine);
+        let folded =
+            DocumentMapper::bytes_to_distinction_with(&bytes, &engine, MappingVersion::Fold);
+        assert_eq!(folded.id(), legacy.id());
+
+        let chunked = DocumentMapper::bytes_to_distinction(&bytes, &engine);
+        assert_ne!(chunked.id(), legacy.id());
+    }
+
+    #[test]
+    fn test_chunked_canonicalization_is_deterministic() {
+        let engine = DistinctionEngine::new();
+        let bytes: Vec<u8> = (0..CHUNKED_THRESHOLD + 12_345)
+            .map(|i| (i % 251) as u8)
+            .collect();
+
+        let d1 = DocumentMapper::bytes_to_distinction(&bytes, &engine);
+        let d2 = DocumentMapper::chunked_bytes_to_distinction(&bytes, &engine);
+        assert_eq!(d1.id(), d2.id());
+
+        // Same tree built on one thread
+        let mut level: Vec<Distinction> = bytes
+            .chunks(CHUNK_SIZE)
+            .map(|chunk| DocumentMapper::fold_bytes(chunk, &engine))
+            .collect();
+        while level.len() > 1 {
+            level = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => engine.synthesize(left, right),
+                    [single] => single.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+        assert_eq!(d1.id(), level[0].id());
+
+        // Content still matters
+        let mut changed = bytes.clone();
+        changed[CHUNKED_THRESHOLD / 2] ^= 1;
+        let d3 = DocumentMapper::bytes_to_distinction(&changed, &engine);
+        assert_ne!(d1.id(), d3.id());
+    }
+
     #[test]
     fn test_store_distinction_id() {
         let engine = DistinctionEngine::new();
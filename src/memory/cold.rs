@@ -43,6 +43,7 @@ use crate::types::{FullKey, VersionedValue};
 use chrono::{DateTime, Duration, Utc};
 use dashmap::DashMap;
 use koru_lambda_core::{Canonicalizable, Distinction, DistinctionEngine, LocalCausalAgent};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -104,12 +105,12 @@ pub struct ArchiveAgent {
 /// A single epoch of consolidated data.
 #[derive(Debug)]
 struct Epoch {
-    /// Epoch number (kept for debugging)
-    _number: usize,
+    /// Epoch number
+    number: usize,
 
-    /// Time range (kept for debugging)
-    _start_time: DateTime<Utc>,
-    _end_time: DateTime<Utc>,
+    /// Time range; the end is moved to the sealing time on rotation
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
 
     /// Index: distinction_id → metadata
     index: HashMap<DistinctionId, EpochEntry>,
@@ -266,6 +267,35 @@ impl ArchiveAgent {
         None
     }
 
+    /// Store a single distinction in the current epoch.
+    ///
+    /// Unlike [`consolidate`](Self::consolidate), no fitness filtering is
+    /// applied; the lifecycle has already decided this distinction belongs here.
+    pub fn store(&self, id: DistinctionId, key: FullKey, timestamp: DateTime<Utc>) {
+        let epoch_num = self.current_epoch.load(Ordering::Relaxed) as usize;
+
+        let action = ArchiveAction::Archive {
+            distinction_ids: vec![id.clone()],
+        };
+        let _ = self.synthesize_action_internal(action);
+
+        self.add_to_epoch(epoch_num, id, key, timestamp, 0);
+        self.maybe_compress_epoch(epoch_num);
+    }
+
+    /// Remove a distinction from whichever epoch holds it.
+    ///
+    /// Returns true if the distinction was found.
+    pub fn remove(&self, id: &DistinctionId) -> bool {
+        for mut epoch in self.epochs.iter_mut() {
+            if epoch.index.remove(id).is_some() {
+                epoch.distinction_count = epoch.distinction_count.saturating_sub(1);
+                return true;
+            }
+        }
+        false
+    }
+
     /// Check if a distinction is in archive.
     pub fn contains(&self, id: &DistinctionId) -> bool {
         self.get(id).is_some()
@@ -286,6 +316,11 @@ impl ArchiveAgent {
         };
         let _ = self.synthesize_action_internal(action);
 
+        // Seal the current epoch
+        if let Some(mut epoch) = self.epochs.get_mut(&(current as usize)) {
+            epoch.end_time = Utc::now();
+        }
+
         // Remove oldest epoch if we have too many
         let to_remove = new_epoch as i64 - self.config.epoch_count as i64;
         if to_remove >= 0 {
@@ -307,6 +342,38 @@ impl ArchiveAgent {
         self.epochs.len()
     }
 
+    /// List sealed epochs (every epoch but the current one), oldest first.
+    pub fn sealed_epochs(&self) -> Vec<ArchiveEpoch> {
+        let current = self.current_epoch();
+        let mut epochs: Vec<ArchiveEpoch> = self
+            .epochs
+            .iter()
+            .filter(|epoch| epoch.number < current)
+            .map(|epoch| ArchiveEpoch {
+                number: epoch.number,
+                start_time: epoch.start_time,
+                end_time: epoch.end_time,
+                distinctions: epoch.distinction_count,
+            })
+            .collect();
+        epochs.sort_by_key(|epoch| epoch.number);
+        epochs
+    }
+
+    /// Distinctions held by an epoch, with their keys.
+    ///
+    /// `None` if the epoch does not exist (or was rotated out).
+    pub fn epoch_entries(&self, epoch_num: usize) -> Option<Vec<(DistinctionId, FullKey)>> {
+        let epoch = self.epochs.get(&epoch_num)?;
+        let mut entries: Vec<_> = epoch
+            .index
+            .iter()
+            .map(|(id, entry)| (id.clone(), entry.key.clone()))
+            .collect();
+        entries.sort_by_key(|(id, key)| (key.to_canonical_string(), id.clone()));
+        Some(entries)
+    }
+
     /// Get total distinctions across all epochs.
     pub fn total_distinctions(&self) -> usize {
         self.epochs.iter().map(|e| e.distinction_count).sum()
@@ -339,9 +406,9 @@ impl ArchiveAgent {
     fn create_epoch(&self, number: usize) {
         let now = Utc::now();
         let epoch = Epoch {
-            _number: number,
-            _start_time: now,
-            _end_time: now + self.config.epoch_duration,
+            number,
+            start_time: now,
+            end_time: now + self.config.epoch_duration,
             index: HashMap::new(),
             distinction_count: 0,
         };
@@ -473,6 +540,19 @@ pub struct Pattern {
     pub template: String,
 }
 
+/// A sealed archive epoch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveEpoch {
+    /// Epoch number (higher is newer)
+    pub number: usize,
+    /// When the epoch was opened
+    pub start_time: DateTime<Utc>,
+    /// When the epoch was sealed
+    pub end_time: DateTime<Utc>,
+    /// Number of distinctions held
+    pub distinctions: usize,
+}
+
 /// Archive agent statistics.
 #[derive(Debug, Clone)]
 pub struct ArchiveStats {
@@ -514,6 +594,21 @@ mod tests {
         assert_eq!(archive.epoch_count(), 1);
     }
 
+    #[test]
+    fn test_store_and_remove() {
+        let engine = create_test_engine();
+        let archive = ArchiveAgent::new(&engine);
+        let key = FullKey::new("ns", "k1");
+
+        archive.store("v1".to_string(), key.clone(), Utc::now());
+        assert!(archive.contains(&"v1".to_string()));
+        assert_eq!(archive.get_by_key(&key), Some("v1".to_string()));
+
+        assert!(archive.remove(&"v1".to_string()));
+        assert!(!archive.contains(&"v1".to_string()));
+        assert!(!archive.remove(&"v1".to_string()));
+    }
+
     #[test]
     fn test_consolidate() {
         let engine = create_test_engine();
@@ -121,7 +121,21 @@ pub struct Genome {
     pub epoch_summary: EpochSummary,
 }
 
+impl Genome {
+    /// ID the genome is stored under, derived from its extraction time.
+    pub fn id(&self) -> String {
+        format!(
+            "genome_{}",
+            self.extracted_at.timestamp_nanos_opt().unwrap_or(0)
+        )
+    }
+}
+
 /// Causal topology - the shape of the causal graph.
+///
+/// `paths` cover every edge of the graph exactly once: each path starts at a
+/// node that is not a simple link in a chain and follows single-parent,
+/// single-child links until the chain ends.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CausalTopology {
     /// Key paths through the graph
@@ -233,9 +247,8 @@ impl EssenceAgent {
             },
         };
 
-        // Store it with nanosecond precision for uniqueness
-        let id = format!("genome_{}", now.timestamp_nanos_opt().unwrap_or(0));
-        self.genome.insert(id, genome.clone());
+        // Stored with nanosecond precision for uniqueness
+        self.genome.insert(genome.id(), genome.clone());
 
         self.genomes_created.fetch_add(1, Ordering::Relaxed);
 
@@ -271,6 +284,53 @@ impl EssenceAgent {
         }
     }
 
+    /// Express a genome into a causal graph, regrowing its structure.
+    ///
+    /// Adds the genome's roots and every edge of its topology that the
+    /// graph does not already have, so expressing the same genome twice is
+    /// harmless. Content is not restored: the regrown graph names versions
+    /// that must be synced from peers or restored from backups.
+    ///
+    /// # LCA Pattern
+    ///
+    /// Expression synthesizes: `ΔNew = ΔLocal_Root ⊕ ΔRegenerate_Action`
+    pub fn express_genome_into(
+        &self,
+        genome: &Genome,
+        causal_graph: &LineageAgent,
+    ) -> ExpressionResult {
+        let action = EssenceAction::Regenerate {
+            from_dna_id: genome.id(),
+        };
+        let _ = self.synthesize_action_internal(action);
+
+        let nodes_before = causal_graph.node_count();
+        for root in &genome.roots {
+            if !causal_graph.contains(root) {
+                causal_graph.add_node(root.clone());
+            }
+        }
+        for path in &genome.topology.paths {
+            for link in path.windows(2) {
+                let (parent, child) = (&link[0], &link[1]);
+                let known = causal_graph
+                    .get_parents(child)
+                    .is_some_and(|parents| parents.contains(parent));
+                if !known {
+                    causal_graph.add_edge(parent.clone(), child.clone());
+                }
+            }
+        }
+
+        self.restorations.fetch_add(1, Ordering::Relaxed);
+
+        ExpressionResult {
+            distinctions_restored: causal_graph.node_count() - nodes_before,
+            roots_restored: genome.roots.len(),
+            patterns_restored: genome.patterns.len(),
+        }
+    }
+
     /// Archive an epoch (move from Cold to Deep).
     pub fn archive_epoch(
         &self,
@@ -330,6 +390,21 @@ impl EssenceAgent {
         &self.genome
     }
 
+    /// Get every distinction referenced by a stored genome.
+    ///
+    /// Garbage collection treats these as roots, so a genome can always be
+    /// expressed against the versions it names.
+    pub fn pinned_distinctions(&self) -> Vec<DistinctionId> {
+        let mut pinned = Vec::new();
+        for genome in self.genome.iter() {
+            pinned.extend(genome.roots.iter().cloned());
+            pinned.extend(genome.topology.paths.iter().flatten().cloned());
+            pinned.extend(genome.topology.branches.iter().cloned());
+            pinned.extend(genome.topology.convergences.iter().cloned());
+        }
+        pinned
+    }
+
     /// Get archive count.
     pub fn archive_count(&self) -> usize {
         self.archive.len()
@@ -372,14 +447,46 @@ impl EssenceAgent {
     }
 
     /// Capture causal topology.
-    fn capture_topology(&self, _causal_graph: &LineageAgent) -> CausalTopology {
-        // TODO: Implement proper topology capture
-        // For now, return empty
-        CausalTopology {
+    fn capture_topology(&self, causal_graph: &LineageAgent) -> CausalTopology {
+        let children_of = |id: &DistinctionId| {
+            let mut children = causal_graph.get_children(id).unwrap_or_default();
+            children.sort();
+            children
+        };
+        let parent_count = |id: &DistinctionId| causal_graph.get_parents(id).map_or(0, |p| p.len());
+        // A link in a chain continues the path that reached it
+        let is_link = |id: &DistinctionId| parent_count(id) == 1 && children_of(id).len() == 1;
+
+        let mut nodes = causal_graph.all_nodes();
+        nodes.sort();
+
+        let mut topology = CausalTopology {
             paths: vec![],
             branches: vec![],
             convergences: vec![],
+        };
+        for node in &nodes {
+            let children = children_of(node);
+            if children.len() > 1 {
+                topology.branches.push(node.clone());
+            }
+            if parent_count(node) > 1 {
+                topology.convergences.push(node.clone());
+            }
+            if is_link(node) {
+                continue;
+            }
+            for child in children {
+                let mut path = vec![node.clone(), child.clone()];
+                let mut current = child;
+                while is_link(&current) {
+                    current = children_of(&current).remove(0);
+                    path.push(current.clone());
+                }
+                topology.paths.push(path);
+            }
         }
+        topology
     }
 
     /// Capture reference patterns.
@@ -512,6 +619,46 @@ mod tests {
         assert_eq!(stats.restorations, 1);
     }
 
+    #[test]
+    fn test_genome_regrows_causal_structure() {
+        let engine = create_test_engine();
+        let essence = EssenceAgent::new(&engine);
+        let source = LineageAgent::new(&engine);
+
+        // a -> b -> c -> d, a -> e, and d also caused by e
+        source.add_node("a".to_string());
+        for (parent, child) in [("a", "b"), ("b", "c"), ("c", "d"), ("a", "e"), ("e", "d")] {
+            source.add_edge(parent.to_string(), child.to_string());
+        }
+        source.add_node("lonely".to_string());
+
+        let genome = essence.extract_genome(&source, 0, source.node_count());
+        assert_eq!(genome.topology.branches, vec!["a".to_string()]);
+        assert_eq!(genome.topology.convergences, vec!["d".to_string()]);
+        let edges: usize = genome.topology.paths.iter().map(|p| p.len() - 1).sum();
+        assert_eq!(edges, source.edge_count());
+        assert!(essence.get_genome(&genome.id()).is_some());
+
+        let bytes = EssenceAgent::serialize_genome(&genome).unwrap();
+        let restored = EssenceAgent::deserialize_genome(&bytes).unwrap();
+
+        let fresh = LineageAgent::new(&create_test_engine());
+        let result = essence.express_genome_into(&restored, &fresh);
+        assert_eq!(result.distinctions_restored, 6);
+        assert_eq!(fresh.edge_count(), source.edge_count());
+        let mut roots = fresh.roots();
+        roots.sort();
+        assert_eq!(roots, vec!["a".to_string(), "lonely".to_string()]);
+        let mut ancestors = fresh.ancestors("d");
+        ancestors.sort();
+        assert_eq!(ancestors, vec!["a", "b", "c", "e"]);
+
+        // Expressing again adds nothing
+        let again = essence.express_genome_into(&restored, &fresh);
+        assert_eq!(again.distinctions_restored, 0);
+        assert_eq!(fresh.edge_count(), source.edge_count());
+    }
+
     #[test]
     fn test_archive_epoch() {
         let engine = create_test_engine();
@@ -22,6 +22,12 @@
 ///
 /// LRU (Least Recently Used): When cache is full, evict the item
 /// that hasn't been accessed longest.
+///
+/// ## Heat
+///
+/// Separately from residency, every recorded access adds one unit of heat
+/// to its key, and heat halves every hour. A key's [`TemperatureLevel`]
+/// is read off its current heat, so it follows the read workload.
 use crate::actions::{TemperatureAction, TemperatureLevel};
 use crate::causal_graph::DistinctionId;
 use crate::engine::{FieldHandle, SharedEngine};
@@ -29,6 +35,7 @@ use crate::roots::RootType;
 #[cfg(test)]
 use crate::types::VectorClock;
 use crate::types::{FullKey, VersionedValue};
+use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use koru_lambda_core::{Canonicalizable, Distinction, DistinctionEngine, LocalCausalAgent};
 use std::collections::VecDeque;
@@ -41,10 +48,31 @@ pub struct TemperatureConfig {
     /// Maximum number of distinctions in hot memory
     pub capacity: usize,
 
-    /// Promote threshold: references >= this → hot candidate
+    /// Promote threshold: heat >= this → Hot
     pub promote_threshold: usize,
 }
 
+/// Time for a key's heat to halve, in seconds.
+const HEAT_HALF_LIFE_SECS: f64 = 3600.0;
+
+/// Heat below which a key is Cold.
+const COOL_HEAT: f64 = 0.25;
+
+/// Accumulated access heat of a key.
+#[derive(Debug, Clone, Copy)]
+struct Heat {
+    score: f64,
+    last_access: DateTime<Utc>,
+}
+
+impl Heat {
+    /// Heat left at `now` after decaying since the last access.
+    fn at(&self, now: DateTime<Utc>) -> f64 {
+        let elapsed = (now - self.last_access).num_milliseconds().max(0) as f64 / 1000.0;
+        self.score * 0.5f64.powf(elapsed / HEAT_HALF_LIFE_SECS)
+    }
+}
+
 impl Default for TemperatureConfig {
     fn default() -> Self {
         Self {
@@ -78,6 +106,9 @@ pub struct TemperatureAgent {
     /// Current → distinction mapping for quick lookup
     current_state: DashMap<FullKey, DistinctionId>,
 
+    /// Access heat per key, whether or not the key is cached
+    heat: DashMap<FullKey, Heat>,
+
     /// Statistics
     hits: AtomicUsize,
     misses: AtomicUsize,
@@ -114,6 +145,7 @@ impl TemperatureAgent {
             cache: DashMap::with_capacity(capacity),
             access_order: std::sync::Mutex::new(VecDeque::with_capacity(capacity)),
             current_state: DashMap::new(),
+            heat: DashMap::new(),
             hits: AtomicUsize::new(0),
             misses: AtomicUsize::new(0),
             evictions: AtomicUsize::new(0),
@@ -225,6 +257,85 @@ impl TemperatureAgent {
         evicted
     }
 
+    /// Remove a key from hot memory, returning its cached value.
+    ///
+    /// Used by the lifecycle to demote a specific distinction.
+    ///
+    /// # LCA Pattern
+    ///
+    /// Removal synthesizes: `ΔNew = ΔLocal_Root ⊕ ΔEvict_Action`
+    pub fn remove(&self, key: &FullKey) -> Option<VersionedValue> {
+        let (_, id) = self.current_state.remove(key)?;
+        let versioned = self.cache.remove(&id).map(|(_, v)| v)?;
+
+        let action = TemperatureAction::Evict {
+            distinction_id: id.clone(),
+        };
+        let _ = self.synthesize_action_internal(action);
+
+        if let Ok(mut order) = self.access_order.lock() {
+            order.retain(|x| x != &id);
+        }
+
+        self.evictions.fetch_add(1, Ordering::Relaxed);
+        Some(versioned)
+    }
+
+    /// Record a read of `key`, heating it up.
+    ///
+    /// # LCA Pattern
+    ///
+    /// Access is synthesized: `ΔNew = ΔLocal_Root ⊕ ΔAccess_Action`
+    pub fn record_access(&self, key: &FullKey, distinction_id: &str) {
+        self.record_access_at(key, distinction_id, Utc::now());
+    }
+
+    /// Record a read of `key` at a given time.
+    pub fn record_access_at(&self, key: &FullKey, distinction_id: &str, at: DateTime<Utc>) {
+        let action = TemperatureAction::Access {
+            distinction_id: distinction_id.to_string(),
+        };
+        let _ = self.synthesize_action_internal(action);
+
+        self.heat
+            .entry(key.clone())
+            .and_modify(|heat| {
+                heat.score = heat.at(at) + 1.0;
+                heat.last_access = heat.last_access.max(at);
+            })
+            .or_insert(Heat {
+                score: 1.0,
+                last_access: at,
+            });
+
+        // Forget keys that have gone cold once the map outgrows the cache
+        if self.heat.len() > self.config.capacity.saturating_mul(4).max(64) {
+            self.heat.retain(|_, heat| heat.at(at) >= COOL_HEAT);
+        }
+    }
+
+    /// Current temperature of `key`, from its access heat.
+    pub fn temperature(&self, key: &FullKey) -> TemperatureLevel {
+        self.temperature_at(key, Utc::now())
+    }
+
+    /// Temperature of `key` at a given time.
+    ///
+    /// Hot at [`promote_threshold`](TemperatureConfig::promote_threshold)
+    /// units of heat, Warm at one, Cool at a quarter, Cold below that.
+    pub fn temperature_at(&self, key: &FullKey, now: DateTime<Utc>) -> TemperatureLevel {
+        let heat = self.heat.get(key).map(|heat| heat.at(now)).unwrap_or(0.0);
+        if heat >= self.config.promote_threshold.max(1) as f64 {
+            TemperatureLevel::Hot
+        } else if heat >= 1.0 {
+            TemperatureLevel::Warm
+        } else if heat >= COOL_HEAT {
+            TemperatureLevel::Cool
+        } else {
+            TemperatureLevel::Cold
+        }
+    }
+
     /// Check if a key is in hot memory.
     pub fn contains_key(&self, key: &FullKey) -> bool {
         self.current_state.contains_key(key)
@@ -337,8 +448,8 @@ impl TemperatureAgent {
             }
         }
 
-        if let Some(key) = key_to_remove {
-            self.current_state.remove(&key);
+        if let Some(ref key) = key_to_remove {
+            self.current_state.remove(key);
         }
 
         // Remove from LRU order
@@ -351,6 +462,7 @@ impl TemperatureAgent {
         Some(Evicted {
             distinction_id: victim_id,
             versioned,
+            key: key_to_remove,
         })
     }
 
@@ -405,6 +517,8 @@ impl LocalCausalAgent for TemperatureAgent {
 pub struct Evicted {
     pub distinction_id: DistinctionId,
     pub versioned: VersionedValue,
+    /// The key the evicted value was stored under
+    pub key: Option<FullKey>,
 }
 
 /// Temperature agent statistics.
@@ -473,6 +587,54 @@ mod tests {
         assert_eq!(retrieved.write_id(), "v1");
     }
 
+    #[test]
+    fn test_access_heat_sets_temperature() {
+        let agent = TemperatureAgent::with_config(
+            TemperatureConfig {
+                capacity: 10,
+                promote_threshold: 3,
+            },
+            &create_test_engine(),
+        );
+        let key = FullKey::new("users", "alice");
+        let now = Utc::now();
+        assert_eq!(agent.temperature_at(&key, now), TemperatureLevel::Cold);
+
+        agent.record_access_at(&key, "v1", now);
+        assert_eq!(agent.temperature_at(&key, now), TemperatureLevel::Warm);
+        agent.record_access_at(&key, "v1", now);
+        agent.record_access_at(&key, "v1", now);
+        assert_eq!(agent.temperature_at(&key, now), TemperatureLevel::Hot);
+
+        // Heat halves every hour without reads
+        let later = |hours| now + chrono::Duration::hours(hours);
+        assert_eq!(agent.temperature_at(&key, later(1)), TemperatureLevel::Warm);
+        assert_eq!(agent.temperature_at(&key, later(3)), TemperatureLevel::Cool);
+        assert_eq!(agent.temperature_at(&key, later(5)), TemperatureLevel::Cold);
+
+        // Residency does not count as access
+        let bob = FullKey::new("users", "bob");
+        agent.put(bob.clone(), create_versioned(json!({"name": "Bob"}), "v2"));
+        assert_eq!(agent.temperature_at(&bob, now), TemperatureLevel::Cold);
+    }
+
+    #[test]
+    fn test_remove() {
+        let engine = create_test_engine();
+        let agent = TemperatureAgent::new(&engine);
+        let key = FullKey::new("users", "alice");
+        agent.put(
+            key.clone(),
+            create_versioned(json!({"name": "Alice"}), "v1"),
+        );
+
+        let removed = agent.remove(&key).unwrap();
+        assert_eq!(removed.write_id(), "v1");
+        assert!(!agent.contains_key(&key));
+        assert!(agent.is_empty());
+        assert!(agent.remove(&key).is_none());
+    }
+
     #[test]
     fn test_lru_eviction() {
         let config = TemperatureConfig {
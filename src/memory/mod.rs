@@ -37,17 +37,21 @@ pub mod deep;
 ///     Epoch ends → Deep (genomic)
 /// ```
 pub mod hot;
+pub mod summarizer;
 pub mod warm;
 pub mod workspace;
 
-pub use cold::{ArchiveAgent, ArchiveConfig, ArchiveStats, ConsolidationResult, Pattern};
+pub use cold::{
+    ArchiveAgent, ArchiveConfig, ArchiveEpoch, ArchiveStats, ConsolidationResult, Pattern,
+};
 pub use deep::{
     CausalTopology, EpochSummary, EssenceAgent, EssenceConfig, EssenceStats, ExpressionResult,
     Genome, ReferencePattern,
 };
 pub use hot::{Evicted, TemperatureAgent, TemperatureConfig, TemperatureStats};
+pub use summarizer::{ExtractiveSummarizer, Summarizer, SummarizerFn};
 pub use warm::{ChronicleAgent, ChronicleConfig, ChronicleStats};
 pub use workspace::{
-    AgentContext, ConsolidationSummary, MemoryPattern, SearchOptions, Workspace, WorkspaceItem,
-    WorkspaceSearchResult, WorkspaceStats,
+    AgentContext, ConsolidationSummary, MemoryPattern, SearchOptions, SharedWorkspace, Workspace,
+    WorkspaceActivity, WorkspaceItem, WorkspaceSearchResult, WorkspaceStats,
 };
@@ -0,0 +1,204 @@
+//! Summaries for workspace consolidation.
+//!
+//! When a workspace consolidates, its cold items are replaced by one summary
+//! item. A [`Summarizer`] writes that summary's text:
+//!
+//! - [`ExtractiveSummarizer`] (the default) keeps the most representative
+//!   sentences of the items, needing no model or network access.
+//! - [`SummarizerFn`] wraps any async callback, e.g. a call to an LLM.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use koru_delta::memory::SummarizerFn;
+//!
+//! let llm = SummarizerFn::new(|items: Vec<WorkspaceItem>| async move {
+//!     let prompt = items.iter().map(|i| i.content.as_str()).collect::<Vec<_>>().join("\n");
+//!     call_my_llm(&format!("Summarize:\n{}", prompt)).await
+//! });
+//! let summary = db.workspace("agent-42").consolidate_with(&llm, 30).await;
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+
+use super::workspace::WorkspaceItem;
+use crate::error::DeltaResult;
+
+/// Produces the text stored in place of consolidated items.
+#[async_trait::async_trait]
+pub trait Summarizer: Send + Sync {
+    /// Identifier recorded with every summary produced.
+    fn name(&self) -> &str;
+
+    /// Summarize the items, oldest first.
+    async fn summarize(&self, items: &[WorkspaceItem]) -> DeltaResult<String>;
+}
+
+/// Dependency-free summarizer that picks sentences out of the items.
+///
+/// Sentences are scored by how common their words are across all items,
+/// weighted by the importance of the item they come from. The best
+/// `max_sentences` are kept in their original order.
+#[derive(Debug, Clone)]
+pub struct ExtractiveSummarizer {
+    /// Maximum number of sentences in a summary.
+    pub max_sentences: usize,
+}
+
+impl ExtractiveSummarizer {
+    /// Create a summarizer keeping at most `max_sentences` sentences.
+    pub fn new(max_sentences: usize) -> Self {
+        Self { max_sentences }
+    }
+
+    /// Summarize without going through the async trait.
+    pub fn extract(&self, items: &[WorkspaceItem]) -> String {
+        let mut seen = HashSet::new();
+        let sentences: Vec<(&str, f32)> = items
+            .iter()
+            .flat_map(|item| {
+                item.content
+                    .split(['.', '!', '?', '\n'])
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(move |s| (s, item.importance))
+            })
+            .filter(|(s, _)| seen.insert(s.to_lowercase()))
+            .collect();
+
+        let mut frequency: HashMap<String, usize> = HashMap::new();
+        for (sentence, _) in &sentences {
+            for word in words(sentence) {
+                *frequency.entry(word).or_default() += 1;
+            }
+        }
+
+        let mut scored: Vec<(usize, f32)> = sentences
+            .iter()
+            .enumerate()
+            .map(|(i, (sentence, importance))| {
+                let words = words(sentence);
+                let total: usize = words.iter().map(|w| frequency[w]).sum();
+                let score = total as f32 / words.len().max(1) as f32;
+                (i, score * (0.5 + importance))
+            })
+            .collect();
+        scored.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.0.cmp(&b.0))
+        });
+        scored.truncate(self.max_sentences);
+        scored.sort_by_key(|(i, _)| *i);
+
+        scored
+            .into_iter()
+            .map(|(i, _)| format!("{}.", sentences[i].0))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl Default for ExtractiveSummarizer {
+    fn default() -> Self {
+        Self::new(5)
+    }
+}
+
+#[async_trait::async_trait]
+impl Summarizer for ExtractiveSummarizer {
+    fn name(&self) -> &str {
+        "extractive"
+    }
+
+    async fn summarize(&self, items: &[WorkspaceItem]) -> DeltaResult<String> {
+        Ok(self.extract(items))
+    }
+}
+
+/// Summarizer backed by an async callback.
+///
+/// The callback receives the items to summarize and returns the summary
+/// text. Errors abort the consolidation, leaving the items in place.
+pub struct SummarizerFn<F> {
+    name: String,
+    callback: F,
+}
+
+impl<F> std::fmt::Debug for SummarizerFn<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SummarizerFn")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F, Fut> SummarizerFn<F>
+where
+    F: Fn(Vec<WorkspaceItem>) -> Fut + Send + Sync,
+    Fut: Future<Output = DeltaResult<String>> + Send,
+{
+    /// Wrap an async callback.
+    pub fn new(callback: F) -> Self {
+        Self {
+            name: "callback".to_string(),
+            callback,
+        }
+    }
+
+    /// Set the name recorded with produced summaries (e.g. the model used).
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl<F, Fut> Summarizer for SummarizerFn<F>
+where
+    F: Fn(Vec<WorkspaceItem>) -> Fut + Send + Sync,
+    Fut: Future<Output = DeltaResult<String>> + Send,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn summarize(&self, items: &[WorkspaceItem]) -> DeltaResult<String> {
+        (self.callback)(items.to_vec()).await
+    }
+}
+
+/// Lowercase words of a sentence, ignoring very short ones.
+fn words(sentence: &str) -> Vec<String> {
+    sentence
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() > 3)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryPattern;
+
+    #[test]
+    fn test_extractive_keeps_representative_sentences() {
+        let items = vec![
+            WorkspaceItem::new(
+                "The deploy failed on Monday. Lunch was pizza.",
+                MemoryPattern::Event,
+            ),
+            WorkspaceItem::new(
+                "The deploy failed again after the rollback. Deploy retried!",
+                MemoryPattern::Event,
+            ),
+            WorkspaceItem::new("The deploy failed on Monday.", MemoryPattern::Event),
+        ];
+
+        let summary = ExtractiveSummarizer::new(2).extract(&items);
+        assert_eq!(summary, "The deploy failed on Monday. Deploy retried.");
+        assert_eq!(ExtractiveSummarizer::default().extract(&[]), "");
+    }
+}
@@ -45,15 +45,24 @@
 //! - Stale configs archive
 //! - Sensor data consolidates
 //! - Agent memories summarize
+//!
+//! ## Shared Workspaces
+//!
+//! Several identities can attach to the same workspace. Each one works
+//! through a [`SharedWorkspace`] that enforces its capabilities on the
+//! workspace namespace (see [`crate::auth`]) and attributes its writes, so
+//! [`activity`](Workspace::activity) shows which agent stored what.
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use tracing::{debug, info, trace};
+use tracing::{debug, info, trace, warn};
 
+use super::summarizer::{ExtractiveSummarizer, Summarizer};
+use crate::auth::{AuthenticatedDelta, Capability, Identity, Permission, ResourcePattern};
 use crate::core::KoruDeltaGeneric;
 use crate::error::{DeltaError, DeltaResult};
 use crate::runtime::Runtime;
-use crate::types::VersionedValue;
+use crate::types::{ProvenanceEntry, VersionedValue};
 use crate::vector::Vector;
 
 /// Memory patterns for organizing workspace data.
@@ -531,26 +540,102 @@ impl<R: Runtime> Workspace<R> {
 
     /// Consolidate old items.
     ///
-    /// Compresses old, low-importance items to save space.
+    /// Compresses old, low-importance items to save space, summarizing them
+    /// with an [`ExtractiveSummarizer`].
     /// Should be called periodically (e.g., nightly).
     pub async fn consolidate(&self) -> ConsolidationSummary {
-        info!(workspace = %self.name, "Starting consolidation");
+        self.consolidate_with(&ExtractiveSummarizer::default(), CONSOLIDATION_AGE_DAYS)
+            .await
+    }
+
+    /// Consolidate old items with a custom summarizer (e.g. an LLM).
+    ///
+    /// Items not written for `older_than_days` that are neither important nor
+    /// frequently used (see [`WorkspaceItem::should_consolidate`]) are
+    /// summarized into one new `summary:` item, then deleted. Their history
+    /// is preserved, so originals stay recoverable. If the summarizer fails,
+    /// nothing is deleted.
+    pub async fn consolidate_with(
+        &self,
+        summarizer: &dyn Summarizer,
+        older_than_days: i64,
+    ) -> ConsolidationSummary {
+        info!(workspace = %self.name, summarizer = summarizer.name(), "Starting consolidation");
 
         let keys = self.db.list_keys(&self.name).await;
-        let total = keys.len();
-
-        // Placeholder: In full implementation, would:
-        // 1. Find old items
-        // 2. Group related items
-        // 3. Create summaries
-        // 4. Archive originals
-
-        ConsolidationSummary {
-            total_items: total,
-            consolidated_count: 0,
-            summaries_created: 0,
-            errors: 0,
+        let mut summary = ConsolidationSummary {
+            total_items: keys.len(),
+            ..Default::default()
+        };
+
+        let mut cold = Vec::new();
+        for key in keys {
+            if key.starts_with(SUMMARY_KEY_PREFIX) {
+                continue;
+            }
+            match self.db.get(&self.name, &key).await {
+                // Deleted items are already gone
+                Ok(versioned) if versioned.value().is_null() => {}
+                Ok(versioned) => {
+                    let item = stored_item(key, &versioned);
+                    if item.should_consolidate(older_than_days) {
+                        cold.push(item);
+                    }
+                }
+                Err(_) => summary.errors += 1,
+            }
+        }
+        if cold.is_empty() {
+            return summary;
+        }
+        cold.sort_by_key(|item| item.created_at);
+
+        let text = match summarizer.summarize(&cold).await {
+            Ok(text) => text,
+            Err(e) => {
+                warn!(workspace = %self.name, error = %e, "Summarizer failed");
+                summary.errors += 1;
+                return summary;
+            }
+        };
+
+        let sources: Vec<&str> = cold.iter().map(|item| item.id.as_str()).collect();
+        let summary_key = format!("{}{}", SUMMARY_KEY_PREFIX, generate_id(&sources.join("\n")));
+        let stored = self
+            .store(
+                &summary_key,
+                serde_json::json!({
+                    "type": "summary",
+                    "content": text,
+                    "sources": sources,
+                    "summarizer": summarizer.name(),
+                    "from": cold.first().map(|item| item.created_at),
+                    "to": cold.last().map(|item| item.created_at),
+                }),
+                MemoryPattern::Reference,
+            )
+            .await;
+        if let Err(e) = stored {
+            warn!(workspace = %self.name, error = %e, "Failed to store summary");
+            summary.errors += 1;
+            return summary;
         }
+        summary.summaries_created = 1;
+        summary.summary_key = Some(summary_key);
+
+        for item in &cold {
+            match self.delete(&item.id).await {
+                Ok(_) => summary.consolidated_count += 1,
+                Err(_) => summary.errors += 1,
+            }
+        }
+
+        info!(
+            workspace = %self.name,
+            consolidated = summary.consolidated_count,
+            "Consolidation complete"
+        );
+        summary
     }
 
     /// Get workspace statistics.
@@ -562,6 +647,207 @@ impl<R: Runtime> Workspace<R> {
             workspace_name: self.name.clone(),
         }
     }
+
+    /// Give an identity access to the whole workspace.
+    ///
+    /// Grants `permission` on the workspace namespace from `owner`, who must
+    /// be able to grant it. Members then [`attach`](Self::attach) with a
+    /// session of their own.
+    pub fn grant(
+        &self,
+        owner: &Identity,
+        owner_secret_key: &[u8],
+        member: &str,
+        permission: Permission,
+    ) -> DeltaResult<Capability> {
+        self.db
+            .auth()
+            .grant_capability(
+                owner,
+                owner_secret_key,
+                member,
+                ResourcePattern::Namespace(self.name.clone()),
+                permission,
+                None,
+            )
+            .map_err(|e| DeltaError::Unauthorized {
+                reason: e.to_string(),
+            })
+    }
+
+    /// Attach to the workspace as the identity behind `session_id`.
+    ///
+    /// Fails with `Unauthorized` if the session is not valid. Capabilities
+    /// are checked on every operation of the returned handle, so grants and
+    /// revocations take effect immediately.
+    pub fn attach(&self, session_id: &str) -> DeltaResult<SharedWorkspace<R>> {
+        Ok(SharedWorkspace {
+            workspace: self.clone(),
+            handle: self.db.as_identity(session_id)?,
+        })
+    }
+
+    /// Who stored what, newest first.
+    ///
+    /// Lists every write to the workspace's live items, with the identity
+    /// that made it when the write was authenticated.
+    pub async fn activity(&self, limit: usize) -> Vec<WorkspaceActivity> {
+        let keys = self.db.list_keys(&self.name).await;
+        self.collect_activity(keys, limit).await
+    }
+
+    async fn collect_activity(&self, keys: Vec<String>, limit: usize) -> Vec<WorkspaceActivity> {
+        let mut feed = Vec::new();
+        for key in keys {
+            if let Ok(provenance) = self.db.provenance(&self.name, &key).await {
+                feed.extend(
+                    provenance
+                        .writes
+                        .into_iter()
+                        .map(|write| WorkspaceActivity::new(key.clone(), write)),
+                );
+            }
+        }
+
+        feed.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        feed.truncate(limit);
+        feed
+    }
+}
+
+/// One write in a workspace's activity feed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceActivity {
+    /// Key of the item written
+    pub key: String,
+    /// Identity that made the write, if it was authenticated
+    pub author: Option<String>,
+    /// When the write happened
+    pub timestamp: DateTime<Utc>,
+    /// Unique ID of the write
+    pub write_id: String,
+    /// Content-addressed version ID of the value written
+    pub version_id: String,
+}
+
+impl WorkspaceActivity {
+    fn new(key: String, write: ProvenanceEntry) -> Self {
+        Self {
+            key,
+            author: write.author,
+            timestamp: write.timestamp,
+            write_id: write.write_id,
+            version_id: write.version_id,
+        }
+    }
+}
+
+/// A workspace as seen by one attached identity.
+///
+/// Created with [`Workspace::attach`]. Reads need Read and writes need
+/// Write on the workspace namespace (or the item's key); otherwise they
+/// fail with [`DeltaError::Unauthorized`]. Writes are attributed to the
+/// identity.
+///
+/// # Example
+///
+/// ```ignore
+/// let shared = db.workspace("research");
+/// shared.grant(&owner, &owner_key, &planner.public_key, Permission::Write)?;
+///
+/// let planner_view = shared.attach(&planner_session)?;
+/// planner_view.store("plan", "Survey sources", MemoryPattern::Procedure).await?;
+///
+/// for entry in shared.activity(10).await {
+///     println!("{:?} stored {}", entry.author, entry.key);
+/// }
+/// ```
+#[derive(Clone)]
+pub struct SharedWorkspace<R: Runtime> {
+    workspace: Workspace<R>,
+    handle: AuthenticatedDelta<R>,
+}
+
+impl<R: Runtime> std::fmt::Debug for SharedWorkspace<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedWorkspace")
+            .field("workspace", &self.workspace.name)
+            .field("identity_key", &self.handle.identity_key())
+            .finish()
+    }
+}
+
+impl<R: Runtime> SharedWorkspace<R> {
+    /// Get the workspace name.
+    pub fn name(&self) -> &str {
+        &self.workspace.name
+    }
+
+    /// Public key of the attached identity.
+    pub fn identity_key(&self) -> &str {
+        self.handle.identity_key()
+    }
+
+    /// Whether the identity may write the given item.
+    pub fn can_write(&self, key: &str) -> bool {
+        self.handle
+            .authorize(&self.workspace.name, key, Permission::Write)
+            .is_ok()
+    }
+
+    /// Store an item as the attached identity (requires Write).
+    pub async fn store(
+        &self,
+        key: impl Into<String>,
+        content: impl Serialize,
+        pattern: MemoryPattern,
+    ) -> DeltaResult<VersionedValue> {
+        let key = key.into();
+        let value = serde_json::to_value(content).map_err(DeltaError::SerializationError)?;
+        let versioned = self.handle.put(&self.workspace.name, &key, value).await?;
+
+        debug!(
+            workspace = %self.workspace.name,
+            key = %key,
+            pattern = %pattern,
+            author = %self.handle.identity_key(),
+            "Item stored"
+        );
+        Ok(versioned)
+    }
+
+    /// Retrieve an item (requires Read).
+    pub async fn get(&self, key: impl Into<String>) -> DeltaResult<serde_json::Value> {
+        let key = key.into();
+        let versioned = self.handle.get(&self.workspace.name, &key).await?;
+        Ok(versioned.value().clone())
+    }
+
+    /// Get complete history for an item (requires Read).
+    pub async fn history(
+        &self,
+        key: impl Into<String>,
+    ) -> DeltaResult<Vec<crate::types::HistoryEntry>> {
+        let key = key.into();
+        self.handle.history(&self.workspace.name, &key).await
+    }
+
+    /// List the items the identity may read.
+    pub async fn list_keys(&self) -> DeltaResult<Vec<String>> {
+        self.handle.list_keys(&self.workspace.name).await
+    }
+
+    /// Delete an item (requires Write).
+    pub async fn delete(&self, key: impl Into<String>) -> DeltaResult<()> {
+        let key = key.into();
+        self.handle.delete(&self.workspace.name, &key).await
+    }
+
+    /// Who stored what, newest first, limited to the items the identity may read.
+    pub async fn activity(&self, limit: usize) -> DeltaResult<Vec<WorkspaceActivity>> {
+        let keys = self.list_keys().await?;
+        Ok(self.workspace.collect_activity(keys, limit).await)
+    }
 }
 
 impl<R: Runtime> Clone for Workspace<R> {
@@ -584,6 +870,39 @@ pub struct ConsolidationSummary {
     pub summaries_created: usize,
     /// Errors encountered
     pub errors: usize,
+    /// Key of the summary item, if one was created
+    pub summary_key: Option<String>,
+}
+
+/// Key prefix of the items created by consolidation.
+pub const SUMMARY_KEY_PREFIX: &str = "summary:";
+
+/// Default age, in days, after which items are consolidated.
+pub const CONSOLIDATION_AGE_DAYS: i64 = 30;
+
+/// Rebuild a workspace item from a stored value.
+///
+/// Strings are taken as-is; objects written by [`AgentContext`] contribute
+/// their `content` and `importance`.
+fn stored_item(key: String, versioned: &VersionedValue) -> WorkspaceItem {
+    let value = versioned.value();
+    let content = match value {
+        serde_json::Value::String(s) => s.clone(),
+        _ => match value.get("content").and_then(|c| c.as_str()) {
+            Some(s) => s.to_string(),
+            None => value.to_string(),
+        },
+    };
+    let importance = value
+        .get("importance")
+        .and_then(|i| i.as_f64())
+        .unwrap_or(0.5) as f32;
+
+    let mut item = WorkspaceItem::new(content, MemoryPattern::Event).with_importance(importance);
+    item.id = key;
+    item.created_at = versioned.timestamp();
+    item.last_accessed = versioned.timestamp();
+    item
 }
 
 /// Workspace statistics.
@@ -756,6 +1075,129 @@ mod tests {
         assert!((opts.min_relevance - 0.7).abs() < 1e-6);
     }
 
+    #[tokio::test]
+    async fn test_shared_workspace_access_and_activity() {
+        use crate::KoruDelta;
+        use crate::auth::{IdentityUserData, create_challenge_response};
+
+        let db = KoruDelta::start().await.unwrap();
+        let login = || {
+            let auth = db.auth();
+            let (identity, secret_key) = auth.create_identity(IdentityUserData::default()).unwrap();
+            let challenge = auth.create_challenge(&identity.public_key).unwrap();
+            let response = create_challenge_response(&secret_key, &challenge).unwrap();
+            let session = auth
+                .verify_and_create_session(&identity.public_key, &challenge, &response)
+                .unwrap();
+            (identity, secret_key, session.session_id)
+        };
+        let (owner, owner_key, _) = login();
+        let (writer, _, writer_session) = login();
+        let (reader, _, reader_session) = login();
+
+        let workspace = db.workspace("team");
+        workspace
+            .grant(&owner, &owner_key, &writer.public_key, Permission::Write)
+            .unwrap();
+        workspace
+            .grant(&owner, &owner_key, &reader.public_key, Permission::Read)
+            .unwrap();
+
+        let writer_view = workspace.attach(&writer_session).unwrap();
+        let reader_view = workspace.attach(&reader_session).unwrap();
+        assert!(workspace.attach("no-such-session").is_err());
+
+        writer_view
+            .store("plan", "draft", MemoryPattern::Procedure)
+            .await
+            .unwrap();
+        writer_view
+            .store("plan", "final", MemoryPattern::Procedure)
+            .await
+            .unwrap();
+        workspace
+            .store("notes", "unattributed", MemoryPattern::Event)
+            .await
+            .unwrap();
+
+        assert_eq!(reader_view.get("plan").await.unwrap(), "final");
+        assert!(!reader_view.can_write("plan"));
+        assert!(matches!(
+            reader_view
+                .store("plan", "hijack", MemoryPattern::Procedure)
+                .await,
+            Err(DeltaError::Unauthorized { .. })
+        ));
+
+        let feed = reader_view.activity(10).await.unwrap();
+        assert_eq!(feed.len(), 3);
+        let plan_writes: Vec<_> = feed.iter().filter(|a| a.key == "plan").collect();
+        assert_eq!(plan_writes.len(), 2);
+        assert!(
+            plan_writes
+                .iter()
+                .all(|a| a.author.as_deref() == Some(writer.public_key.as_str()))
+        );
+        assert!(feed.windows(2).all(|w| w[0].timestamp >= w[1].timestamp));
+        assert_eq!(workspace.activity(1).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_consolidate_with_summarizer() {
+        use crate::KoruDelta;
+        use crate::memory::SummarizerFn;
+
+        let db = KoruDelta::start().await.unwrap();
+        let workspace = db.workspace("agent");
+        let agent = AgentContext::new(workspace.clone());
+        agent
+            .remember_episode("User asked about Python", 0.2)
+            .await
+            .unwrap();
+        agent
+            .remember_episode("User asked about Rust", 0.3)
+            .await
+            .unwrap();
+        agent
+            .remember_episode("User's name is Ada", 0.9)
+            .await
+            .unwrap();
+
+        // Nothing is old enough by default
+        let summary = workspace.consolidate().await;
+        assert_eq!(summary.total_items, 3);
+        assert_eq!(summary.consolidated_count, 0);
+
+        // A failing summarizer leaves everything in place
+        let failing = SummarizerFn::new(|_items: Vec<WorkspaceItem>| async {
+            Err(DeltaError::StorageError("model offline".to_string()))
+        });
+        let summary = workspace.consolidate_with(&failing, 0).await;
+        assert_eq!(summary.errors, 1);
+        assert_eq!(workspace.list_keys().await.len(), 3);
+
+        let llm = SummarizerFn::new(|items: Vec<WorkspaceItem>| async move {
+            Ok(format!("{} questions", items.len()))
+        })
+        .with_name("test-llm");
+        let summary = workspace.consolidate_with(&llm, 0).await;
+        assert_eq!(summary.consolidated_count, 2);
+        assert_eq!(summary.summaries_created, 1);
+
+        let summary_key = summary.summary_key.unwrap();
+        let stored = workspace.get(&summary_key).await.unwrap();
+        assert_eq!(stored["content"], "2 questions");
+        assert_eq!(stored["summarizer"], "test-llm");
+        assert_eq!(stored["sources"].as_array().unwrap().len(), 2);
+
+        // Originals are deleted; the important episode and the summary remain
+        let source = stored["sources"][0].as_str().unwrap();
+        assert!(workspace.get(source).await.unwrap().is_null());
+        let summary = workspace.consolidate_with(&llm, 0).await;
+        assert_eq!(summary.consolidated_count, 0);
+        assert_eq!(summary.errors, 0);
+    }
+
     #[tokio::test]
     async fn test_memory_pattern_display() {
         assert_eq!(format!("{}", MemoryPattern::Event), "event");
@@ -17,6 +17,7 @@
 /// All network operations are designed to be async and can be used with
 /// Tokio's multi-threaded runtime.
 use crate::error::{DeltaError, DeltaResult};
+use crate::mapper::MappingVersion;
 use crate::types::{FullKey, Tombstone, VectorClock, VersionedValue};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -29,6 +30,9 @@ use uuid::Uuid;
 /// Default port for KoruDelta cluster communication.
 pub const DEFAULT_PORT: u16 = 7878;
 
+/// Weight of the latest heartbeat in a peer's health score.
+const HEALTH_SCORE_WEIGHT: f64 = 0.25;
+
 /// Maximum message size (16 MB).
 const MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
 
@@ -73,6 +77,11 @@ pub struct PeerInfo {
     pub last_seen: DateTime<Utc>,
     /// Current status of the peer.
     pub status: PeerStatus,
+    /// Share of recent heartbeats the peer answered, weighted toward the
+    /// latest: 1.0 for a steady link, dropping toward 0.0 as replies go
+    /// missing. Flapping links hover in between.
+    #[serde(default = "PeerInfo::full_health")]
+    pub health_score: f64,
 }
 
 impl PeerInfo {
@@ -85,6 +94,7 @@ impl PeerInfo {
             first_seen: now,
             last_seen: now,
             status: PeerStatus::Unknown,
+            health_score: Self::full_health(),
         }
     }
 
@@ -92,6 +102,16 @@ impl PeerInfo {
     pub fn touch(&mut self) {
         self.last_seen = Utc::now();
     }
+
+    /// Fold the outcome of one heartbeat into the health score.
+    pub fn record_heartbeat(&mut self, answered: bool) {
+        let outcome = if answered { 1.0 } else { 0.0 };
+        self.health_score += (outcome - self.health_score) * HEALTH_SCORE_WEIGHT;
+    }
+
+    fn full_health() -> f64 {
+        1.0
+    }
 }
 
 /// Status of a peer node.
@@ -115,16 +135,21 @@ pub enum Message {
     // ─────────────────────────────────────────────────────────────────────
     // Handshake & Discovery
     // ─────────────────────────────────────────────────────────────────────
-    /// Initial handshake when joining a cluster.
+    /// Initial handshake when joining a cluster, with how the joining node
+    /// canonicalizes values. Absent from older peers, which all fold.
     Join {
         node_id: NodeId,
         address: SocketAddr,
+        #[serde(default)]
+        mapping: Option<MappingVersion>,
     },
 
-    /// Acknowledgment of a join request.
+    /// Acknowledgment of a join request, with the accepting node's mapping.
     JoinAck {
         node_id: NodeId,
         peers: Vec<PeerInfo>,
+        #[serde(default)]
+        mapping: Option<MappingVersion>,
     },
 
     /// Announce presence to peers (gossip).
@@ -140,8 +165,13 @@ pub enum Message {
     /// Heartbeat ping.
     Ping { node_id: NodeId },
 
-    /// Heartbeat response.
-    Pong { node_id: NodeId },
+    /// Heartbeat response, stamped with the responder's wall clock so the
+    /// pinging node can estimate clock skew. Absent from older peers.
+    Pong {
+        node_id: NodeId,
+        #[serde(default)]
+        timestamp: Option<DateTime<Utc>>,
+    },
 
     // ─────────────────────────────────────────────────────────────────────
     // Data Synchronization
@@ -174,8 +204,10 @@ pub enum Message {
     SyncRequest {
         node_id: NodeId,
         /// Keys and their latest known version IDs.
+        #[serde(with = "map_as_pairs")]
         keys: HashMap<FullKey, Option<String>>,
         /// Known tombstones with their vector clocks (for tombstone propagation).
+        #[serde(with = "map_as_pairs")]
         tombstones: HashMap<FullKey, VectorClock>,
     },
 
@@ -207,6 +239,34 @@ impl Message {
     }
 }
 
+/// Serialize maps with struct keys as lists of pairs, since JSON object
+/// keys must be strings.
+mod map_as_pairs {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    pub fn serialize<K, V, S>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        K: Serialize,
+        V: Serialize,
+        S: Serializer,
+    {
+        serializer.collect_seq(map.iter())
+    }
+
+    pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<HashMap<K, V>, D::Error>
+    where
+        K: Deserialize<'de> + Eq + Hash,
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Ok(Vec::<(K, V)>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
+
 /// Network connection to a peer.
 pub struct Connection {
     stream: TcpStream,
@@ -377,6 +437,7 @@ mod tests {
         let message = Message::Join {
             node_id: node_id.clone(),
             address: addr,
+            mapping: Some(MappingVersion::Chunked),
         };
 
         let bytes = message.to_bytes().unwrap();
@@ -386,12 +447,21 @@ mod tests {
             Message::Join {
                 node_id: decoded_id,
                 address: decoded_addr,
+                mapping,
             } => {
                 assert_eq!(decoded_id, node_id);
                 assert_eq!(decoded_addr, addr);
+                assert_eq!(mapping, Some(MappingVersion::Chunked));
             }
             _ => panic!("Expected Join message"),
         }
+
+        // Joins from peers that predate mapping versions still decode
+        let legacy = serde_json::json!({"Join": {"node_id": node_id, "address": addr}});
+        match Message::from_bytes(&serde_json::to_vec(&legacy).unwrap()).unwrap() {
+            Message::Join { mapping, .. } => assert_eq!(mapping, None),
+            _ => panic!("Expected Join message"),
+        }
     }
 
     #[test]
@@ -403,6 +473,7 @@ mod tests {
         };
         let pong = Message::Pong {
             node_id: node_id.clone(),
+            timestamp: Some(Utc::now()),
         };
 
         // Verify serialization round-trip.
@@ -418,7 +489,20 @@ mod tests {
         }
 
         match decoded_pong {
-            Message::Pong { node_id: id } => assert_eq!(id, node_id),
+            Message::Pong {
+                node_id: id,
+                timestamp,
+            } => {
+                assert_eq!(id, node_id);
+                assert!(timestamp.is_some());
+            }
+            _ => panic!("Expected Pong message"),
+        }
+
+        // Pongs from peers that predate timestamps still decode.
+        let legacy = format!(r#"{{"Pong":{{"node_id":"{}"}}}}"#, node_id.0);
+        match Message::from_bytes(legacy.as_bytes()).unwrap() {
+            Message::Pong { timestamp, .. } => assert!(timestamp.is_none()),
             _ => panic!("Expected Pong message"),
         }
     }
@@ -436,6 +520,7 @@ mod tests {
             let msg = conn.receive().await.unwrap();
             conn.send(&Message::Pong {
                 node_id: NodeId::new(),
+                timestamp: None,
             })
             .await
             .unwrap();
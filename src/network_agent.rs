@@ -441,6 +441,7 @@ mod tests {
             first_seen: chrono::Utc::now(),
             last_seen: chrono::Utc::now(),
             status: PeerStatus::Healthy,
+            health_score: 1.0,
         };
 
         tx.send(NetworkEvent::PeerJoined { peer: peer.clone() })
@@ -471,6 +472,7 @@ mod tests {
             first_seen: chrono::Utc::now(),
             last_seen: chrono::Utc::now(),
             status: PeerStatus::Healthy,
+            health_score: 1.0,
         };
 
         tx.send(NetworkEvent::PeerJoined { peer: peer.clone() })
@@ -568,6 +570,7 @@ mod tests {
                 first_seen: chrono::Utc::now(),
                 last_seen: chrono::Utc::now(),
                 status: PeerStatus::Healthy,
+                health_score: 1.0,
             };
             tx.send(NetworkEvent::PeerJoined { peer }).unwrap();
         }
@@ -18,21 +18,50 @@
 //! - Participate in coordination cycles
 //! - Announce their capabilities
 //!
+//! Downstream crates can add their own agents with
+//! [`KoruOrchestrator::register_custom_agent`]: any [`LocalCausalAgent`] plus
+//! a pulse handler. The orchestrator owns the agent, calls the handler on
+//! every pulse, and synthesizes the action it returns into the agent.
+//!
+//! # Action Journal
+//!
+//! With [`KoruOrchestrator::with_journal`], every action the orchestrator
+//! synthesizes is appended to an [`ActionJournal`], and
+//! [`KoruOrchestrator::replay_journal`] rebuilds the local root from it.
+//!
 //! # Pulse Coordination
 //!
 //! The orchestrator can coordinate rhythmic cycles for external systems.
 //! These cycles (called "pulses") allow external agents to synchronize
 //! their operations with KoruDelta's internal state.
+//!
+//! Applications schedule their own work in the rhythm with hooks
+//! ([`KoruOrchestrator::before_pulse`], [`KoruOrchestrator::after_phase`])
+//! and can add their own phases to the cycle with
+//! [`KoruOrchestrator::define_phase`], placed by ordering constraints.
+//!
+//! # Process Scheduling
+//!
+//! [`ProcessScheduler`] tracks the database's background processes
+//! (consolidation, distillation, genome update, lifecycle checks, ...) and
+//! lets callers list, pause, resume, and reschedule them while running.
 
+use std::any::Any;
 use std::collections::HashMap;
-use std::sync::RwLock;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 
-use koru_lambda_core::{Canonicalizable, Distinction};
+use chrono::{DateTime, Utc};
+use koru_lambda_core::{Canonicalizable, Distinction, DistinctionEngine, LocalCausalAgent};
+use serde::{Deserialize, Serialize};
 
 use crate::actions::{KoruAction, PulseAction};
 use crate::engine::{FieldHandle, SharedEngine};
+use crate::error::{DeltaError, DeltaResult};
+use crate::journal::{ActionJournal, JournalEntry};
 use crate::roots::RootType;
+use crate::runtime::{Runtime, WatchReceiver, WatchSender};
 
 /// The central orchestrator for all LCA agents.
 ///
@@ -66,9 +95,18 @@ pub struct KoruOrchestrator {
     /// Registry of all agents
     agents: RwLock<AgentRegistry>,
 
+    /// Agents owned by the orchestrator, driven by pulses
+    custom_agents: Mutex<HashMap<String, Box<dyn PulseParticipant>>>,
+
     /// Pulse coordinator for external coordination
     pulse: PulseCoordinator,
 
+    /// Journal of synthesized actions, if attached
+    journal: Mutex<Option<ActionJournal>>,
+
+    /// Application hooks run around pulses
+    hooks: RwLock<PulseHooks>,
+
     /// Statistics
     agents_registered: AtomicU64,
     pulses_triggered: AtomicU64,
@@ -118,6 +156,53 @@ pub enum AgentCapability {
     Custom(String),
 }
 
+/// Agent name of the orchestrator's own journal entries.
+const JOURNAL_AGENT: &str = "orchestrator";
+
+/// A custom agent with its pulse handler, type-erased.
+trait PulseParticipant: Send {
+    /// The agent's current local root.
+    fn root(&self) -> Distinction;
+
+    /// Run the pulse handler; returns the new root if an action was synthesized.
+    fn on_pulse(
+        &mut self,
+        phase: CoordinationPhase,
+        engine: &Arc<DistinctionEngine>,
+    ) -> Option<Distinction>;
+
+    /// The agent itself, for downcasting.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+struct CustomAgent<A, F> {
+    agent: A,
+    handler: F,
+}
+
+impl<A, F> PulseParticipant for CustomAgent<A, F>
+where
+    A: LocalCausalAgent + Send + 'static,
+    F: FnMut(&mut A, CoordinationPhase) -> Option<A::ActionData> + Send,
+{
+    fn root(&self) -> Distinction {
+        self.agent.get_current_root().clone()
+    }
+
+    fn on_pulse(
+        &mut self,
+        phase: CoordinationPhase,
+        engine: &Arc<DistinctionEngine>,
+    ) -> Option<Distinction> {
+        let action = (self.handler)(&mut self.agent, phase)?;
+        Some(self.agent.synthesize_action(action, engine))
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        &mut self.agent
+    }
+}
+
 /// Registry of all agents in the system.
 #[derive(Debug, Default)]
 pub struct AgentRegistry {
@@ -139,7 +224,7 @@ pub struct PulseCoordinator {
     current_phase: RwLock<CoordinationPhase>,
 
     /// Phase sequence
-    phase_sequence: Vec<CoordinationPhase>,
+    phase_sequence: RwLock<Vec<CoordinationPhase>>,
 
     /// Current position in sequence
     sequence_position: RwLock<usize>,
@@ -164,6 +249,38 @@ pub enum CoordinationPhase {
     /// Idle phase - waiting for next cycle
     #[default]
     Idle,
+    /// Application-defined phase (see [`KoruOrchestrator::define_phase`])
+    Custom(&'static str),
+}
+
+/// Ordering constraint for a custom phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhaseOrder {
+    /// The phase runs after this one
+    After(CoordinationPhase),
+    /// The phase runs before this one
+    Before(CoordinationPhase),
+}
+
+/// Identifies a registered pulse hook, for removal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HookId(u64);
+
+type PulseHook = Arc<dyn Fn(CoordinationPhase) + Send + Sync>;
+
+/// Hooks registered with the orchestrator.
+#[derive(Default)]
+struct PulseHooks {
+    next: u64,
+    before: Vec<(HookId, PulseHook)>,
+    after: Vec<(HookId, CoordinationPhase, PulseHook)>,
+}
+
+impl PulseHooks {
+    fn next_id(&mut self) -> HookId {
+        self.next += 1;
+        HookId(self.next)
+    }
 }
 
 impl KoruOrchestrator {
@@ -191,12 +308,54 @@ impl KoruOrchestrator {
             field,
             local_root: RwLock::new(local_root),
             agents: RwLock::new(AgentRegistry::default()),
+            custom_agents: Mutex::new(HashMap::new()),
             pulse,
+            journal: Mutex::new(None),
+            hooks: RwLock::new(PulseHooks::default()),
             agents_registered: AtomicU64::new(0),
             pulses_triggered: AtomicU64::new(0),
         }
     }
 
+    /// Record every synthesized action in `journal`.
+    ///
+    /// Entries already in the journal are not applied; call
+    /// [`replay_journal`](Self::replay_journal) to restore the root they
+    /// lead to.
+    pub fn with_journal(self, journal: ActionJournal) -> Self {
+        *self.journal.lock().unwrap() = Some(journal);
+        self
+    }
+
+    /// Rebuild the local root by replaying the journal from the canonical
+    /// orchestrator root.
+    ///
+    /// Returns the restored root (the current one if no journal is
+    /// attached). Fails with `InvalidData` if the journal does not replay
+    /// to the roots it recorded; the local root is then left unchanged.
+    pub fn replay_journal(&self) -> DeltaResult<Distinction> {
+        let mut local_root = self.local_root.write().unwrap();
+        let journal = self.journal.lock().unwrap();
+        let Some(journal) = journal.as_ref() else {
+            return Ok(local_root.clone());
+        };
+
+        let initial = self.engine.root(RootType::Orchestrator).clone();
+        let root = journal.replay(self.field.engine_arc(), JOURNAL_AGENT, initial)?;
+        *local_root = root.clone();
+        Ok(root)
+    }
+
+    /// Entries of the attached journal, oldest first.
+    pub fn journal_entries(&self) -> Vec<JournalEntry> {
+        self.journal
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|journal| journal.entries().to_vec())
+            .unwrap_or_default()
+    }
+
     /// Get the shared engine.
     pub fn engine(&self) -> &SharedEngine {
         &self.engine
@@ -259,6 +418,8 @@ impl KoruOrchestrator {
         };
         let _ = self.synthesize_action_internal(action);
 
+        self.custom_agents.lock().unwrap().remove(agent_id);
+
         // Remove from registry
         let mut agents = self.agents.write().unwrap();
 
@@ -272,6 +433,74 @@ impl KoruOrchestrator {
         }
     }
 
+    /// Register a custom agent owned and driven by the orchestrator.
+    ///
+    /// `on_pulse` is called with the agent on every [`pulse`](Self::pulse).
+    /// When it returns an action, the action is synthesized into the agent
+    /// (`ΔNew = ΔAgent_Root ⊕ ΔAction`) and the agent's registered root
+    /// follows. The agent type is recorded as the Rust type name.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// orchestrator.register_custom_agent(
+    ///     "sensor",
+    ///     "Sensor Agent",
+    ///     vec![AgentCapability::Custom("sensing".into())],
+    ///     SensorAgent::new(root),
+    ///     |agent, phase| (phase == CoordinationPhase::Input).then(|| agent.read()),
+    /// );
+    /// ```
+    pub fn register_custom_agent<A, F>(
+        &self,
+        id: impl Into<String>,
+        name: impl Into<String>,
+        capabilities: Vec<AgentCapability>,
+        agent: A,
+        on_pulse: F,
+    ) where
+        A: LocalCausalAgent + Send + 'static,
+        F: FnMut(&mut A, CoordinationPhase) -> Option<A::ActionData> + Send + 'static,
+    {
+        let id = id.into();
+        self.register_agent(AgentInfo {
+            id: id.clone(),
+            name: name.into(),
+            root: agent.get_current_root().clone(),
+            agent_type: std::any::type_name::<A>().to_string(),
+            capabilities,
+        });
+        self.custom_agents.lock().unwrap().insert(
+            id,
+            Box::new(CustomAgent {
+                agent,
+                handler: on_pulse,
+            }),
+        );
+    }
+
+    /// Access a custom agent registered with
+    /// [`register_custom_agent`](Self::register_custom_agent).
+    ///
+    /// Returns `None` if there is no custom agent with that ID or it is not
+    /// an `A`. Changes to the agent's root are reflected in the registry.
+    pub fn with_custom_agent<A: 'static, T>(
+        &self,
+        agent_id: &str,
+        f: impl FnOnce(&mut A) -> T,
+    ) -> Option<T> {
+        let mut custom = self.custom_agents.lock().unwrap();
+        let entry = custom.get_mut(agent_id)?;
+        let result = entry.as_any_mut().downcast_mut::<A>().map(f)?;
+        let root = entry.root();
+        drop(custom);
+
+        if let Some(info) = self.agents.write().unwrap().agents.get_mut(agent_id) {
+            info.root = root;
+        }
+        Some(result)
+    }
+
     /// Get information about a registered agent.
     pub fn get_agent(&self, agent_id: &str) -> Option<AgentInfo> {
         let agents = self.agents.read().unwrap();
@@ -308,6 +537,19 @@ impl KoruOrchestrator {
     ///
     /// Pulse triggers synthesize: `ΔNew = ΔLocal_Root ⊕ ΔPulse_Action`
     pub fn pulse(&self, phase: CoordinationPhase) {
+        // Hooks run without locks held, so they may use the orchestrator
+        let before: Vec<PulseHook> = self
+            .hooks
+            .read()
+            .unwrap()
+            .before
+            .iter()
+            .map(|(_, hook)| Arc::clone(hook))
+            .collect();
+        for hook in before {
+            hook(phase);
+        }
+
         // Update pulse coordinator
         *self.pulse.current_phase.write().unwrap() = phase;
 
@@ -316,7 +558,117 @@ impl KoruOrchestrator {
         let action = PulseAction::TriggerPulse { phase: phase_str };
         let _ = self.synthesize_action_internal(action);
 
+        self.pulse_custom_agents(phase);
+
         self.pulses_triggered.fetch_add(1, Ordering::SeqCst);
+
+        let after: Vec<PulseHook> = self
+            .hooks
+            .read()
+            .unwrap()
+            .after
+            .iter()
+            .filter(|(_, hook_phase, _)| *hook_phase == phase)
+            .map(|(_, _, hook)| Arc::clone(hook))
+            .collect();
+        for hook in after {
+            hook(phase);
+        }
+    }
+
+    /// Run `hook` at the start of every pulse, before the phase is entered.
+    ///
+    /// The hook receives the phase about to run.
+    pub fn before_pulse(&self, hook: impl Fn(CoordinationPhase) + Send + Sync + 'static) -> HookId {
+        let mut hooks = self.hooks.write().unwrap();
+        let id = hooks.next_id();
+        hooks.before.push((id, Arc::new(hook)));
+        id
+    }
+
+    /// Run `hook` each time a pulse of `phase` has completed.
+    ///
+    /// By then the pulse has been synthesized and custom agents have
+    /// handled it.
+    pub fn after_phase(
+        &self,
+        phase: CoordinationPhase,
+        hook: impl Fn(CoordinationPhase) + Send + Sync + 'static,
+    ) -> HookId {
+        let mut hooks = self.hooks.write().unwrap();
+        let id = hooks.next_id();
+        hooks.after.push((id, phase, Arc::new(hook)));
+        id
+    }
+
+    /// Remove a hook. Returns `false` if it was not registered.
+    pub fn remove_hook(&self, id: HookId) -> bool {
+        let mut hooks = self.hooks.write().unwrap();
+        let count = hooks.before.len() + hooks.after.len();
+        hooks.before.retain(|(hook_id, _)| *hook_id != id);
+        hooks.after.retain(|(hook_id, _, _)| *hook_id != id);
+        hooks.before.len() + hooks.after.len() < count
+    }
+
+    /// Add an application-defined phase to the pulse cycle.
+    ///
+    /// The phase is inserted into the phase sequence so that it comes after
+    /// every `PhaseOrder::After` phase and before every `PhaseOrder::Before`
+    /// phase: right after the latest `After` phase, or right before the
+    /// earliest `Before` phase if there is none, or at the end. Fails with
+    /// `InvalidData` if the name is taken, a referenced phase is not in the
+    /// sequence, or the constraints contradict each other.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let reindex = orchestrator.define_phase(
+    ///     "reindex",
+    ///     &[PhaseOrder::After(CoordinationPhase::Processing),
+    ///       PhaseOrder::Before(CoordinationPhase::Output)],
+    /// )?;
+    /// orchestrator.after_phase(reindex, |_| rebuild_indexes());
+    /// ```
+    pub fn define_phase(
+        &self,
+        name: &'static str,
+        constraints: &[PhaseOrder],
+    ) -> DeltaResult<CoordinationPhase> {
+        let phase = CoordinationPhase::Custom(name);
+        self.pulse.insert_phase(phase, constraints)?;
+        Ok(phase)
+    }
+
+    /// Look up a phase defined with [`define_phase`](Self::define_phase) by name.
+    pub fn custom_phase(&self, name: &str) -> Option<CoordinationPhase> {
+        self.pulse
+            .sequence()
+            .into_iter()
+            .find(|phase| matches!(phase, CoordinationPhase::Custom(n) if *n == name))
+    }
+
+    /// Run the pulse handlers of custom agents, in agent ID order.
+    fn pulse_custom_agents(&self, phase: CoordinationPhase) {
+        let engine = self.field.engine_arc();
+        let mut custom = self.custom_agents.lock().unwrap();
+        let mut participants: Vec<_> = custom.iter_mut().collect();
+        participants.sort_by(|a, b| a.0.cmp(b.0));
+
+        let new_roots: Vec<(String, Distinction)> = participants
+            .into_iter()
+            .filter_map(|(id, agent)| Some((id.clone(), agent.on_pulse(phase, engine)?)))
+            .collect();
+        drop(custom);
+
+        if new_roots.is_empty() {
+            return;
+        }
+        let mut agents = self.agents.write().unwrap();
+        for (id, root) in new_roots {
+            if let Some(info) = agents.agents.get_mut(&id) {
+                info.root = root;
+            }
+        }
     }
 
     /// Advance to the next phase in the sequence.
@@ -347,20 +699,23 @@ impl KoruOrchestrator {
     pub fn synthesize_action(&self, action: KoruAction) -> Distinction {
         let engine = self.field.engine_arc();
         let action_distinction = action.to_canonical_structure(engine);
-        let local_root = self.local_root.read().unwrap().clone();
+
+        // Hold the root while journaling so entries follow synthesis order
+        let mut local_root = self.local_root.write().unwrap();
         let new_root = engine.synthesize(&local_root, &action_distinction);
-        *self.local_root.write().unwrap() = new_root.clone();
+        *local_root = new_root.clone();
+
+        if let Some(journal) = self.journal.lock().unwrap().as_mut() {
+            if let Err(e) = journal.record(JOURNAL_AGENT, &action, &new_root) {
+                tracing::warn!(error = %e, category = action.category(), "Failed to journal action");
+            }
+        }
         new_root
     }
 
     /// Internal synthesis helper for orchestrator-specific actions.
     fn synthesize_action_internal(&self, action: PulseAction) -> Distinction {
-        let engine = self.field.engine_arc();
-        let action_distinction = action.to_canonical_structure(engine);
-        let local_root = self.local_root.read().unwrap().clone();
-        let new_root = engine.synthesize(&local_root, &action_distinction);
-        *self.local_root.write().unwrap() = new_root.clone();
-        new_root
+        self.synthesize_action(KoruAction::Pulse(action))
     }
 
     // ========================================================================
@@ -519,7 +874,7 @@ impl PulseCoordinator {
     pub fn with_sequence(phase_sequence: Vec<CoordinationPhase>) -> Self {
         Self {
             current_phase: RwLock::new(CoordinationPhase::Idle),
-            phase_sequence,
+            phase_sequence: RwLock::new(phase_sequence),
             sequence_position: RwLock::new(0),
         }
     }
@@ -531,21 +886,83 @@ impl PulseCoordinator {
 
     /// Get the next phase in the sequence.
     pub fn next_phase(&self) -> CoordinationPhase {
+        // Lock order: sequence, then position
+        let sequence = self.phase_sequence.read().unwrap();
         let mut position = self.sequence_position.write().unwrap();
-        *position = (*position + 1) % self.phase_sequence.len();
-        self.phase_sequence[*position]
+        *position = (*position + 1) % sequence.len();
+        sequence[*position]
     }
 
     /// Get the phase sequence.
-    pub fn sequence(&self) -> &[CoordinationPhase] {
-        &self.phase_sequence
+    pub fn sequence(&self) -> Vec<CoordinationPhase> {
+        self.phase_sequence.read().unwrap().clone()
     }
 
     /// Set the phase sequence.
-    pub fn set_sequence(&mut self, sequence: Vec<CoordinationPhase>) {
-        self.phase_sequence = sequence;
+    pub fn set_sequence(&self, sequence: Vec<CoordinationPhase>) {
+        let mut phase_sequence = self.phase_sequence.write().unwrap();
+        *phase_sequence = sequence;
         *self.sequence_position.write().unwrap() = 0;
     }
+
+    /// Insert a phase into the sequence, honoring ordering constraints.
+    ///
+    /// Returns the index it was inserted at. See
+    /// [`KoruOrchestrator::define_phase`] for the placement rules.
+    pub fn insert_phase(
+        &self,
+        phase: CoordinationPhase,
+        constraints: &[PhaseOrder],
+    ) -> DeltaResult<usize> {
+        let mut sequence = self.phase_sequence.write().unwrap();
+        if sequence.contains(&phase) {
+            return Err(DeltaError::InvalidData {
+                reason: format!("Phase {:?} is already in the sequence", phase),
+            });
+        }
+
+        let index_of = |target: &CoordinationPhase| {
+            sequence
+                .iter()
+                .position(|p| p == target)
+                .ok_or_else(|| DeltaError::InvalidData {
+                    reason: format!("Phase {:?} is not in the sequence", target),
+                })
+        };
+        let mut earliest = None;
+        let mut latest = None;
+        for constraint in constraints {
+            match constraint {
+                PhaseOrder::After(target) => {
+                    let index = index_of(target)? + 1;
+                    earliest = Some(earliest.map_or(index, |e: usize| e.max(index)));
+                }
+                PhaseOrder::Before(target) => {
+                    let index = index_of(target)?;
+                    latest = Some(latest.map_or(index, |l: usize| l.min(index)));
+                }
+            }
+        }
+
+        let index = match (earliest, latest) {
+            (Some(e), Some(l)) if e > l => {
+                return Err(DeltaError::InvalidData {
+                    reason: format!("Ordering constraints for {:?} contradict", phase),
+                });
+            }
+            (Some(e), _) => e,
+            (None, Some(l)) => l,
+            (None, None) => sequence.len(),
+        };
+        sequence.insert(index, phase);
+
+        // Keep pointing at the same phase
+        let mut position = self.sequence_position.write().unwrap();
+        if index <= *position && sequence.len() > 1 {
+            *position += 1;
+        }
+        Ok(index)
+    }
 }
 
 impl Default for PulseCoordinator {
@@ -563,6 +980,371 @@ pub struct OrchestratorStats {
     pub current_phase: CoordinationPhase,
 }
 
+// ============================================================================
+// Process Scheduling
+// ============================================================================
+
+/// A background process run on a schedule by the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackgroundProcess {
+    /// Moves data between memory tiers
+    Consolidation,
+    /// Removes noise, keeps essence
+    Distillation,
+    /// Extracts the causal topology into the deep tier
+    GenomeUpdate,
+    /// Scores distinctions and executes tier transitions
+    LifecycleCheck,
+    /// Reclaims tombstoned vectors from ANN graphs
+    IndexCompaction,
+    /// Warns before capability grants lapse and archives expired ones
+    CapabilityExpiry,
+}
+
+impl BackgroundProcess {
+    /// All processes, in listing order.
+    pub const ALL: [BackgroundProcess; 6] = [
+        BackgroundProcess::Consolidation,
+        BackgroundProcess::Distillation,
+        BackgroundProcess::GenomeUpdate,
+        BackgroundProcess::LifecycleCheck,
+        BackgroundProcess::IndexCompaction,
+        BackgroundProcess::CapabilityExpiry,
+    ];
+
+    /// Stable snake_case name, as used in the HTTP API.
+    pub fn name(&self) -> &'static str {
+        match self {
+            BackgroundProcess::Consolidation => "consolidation",
+            BackgroundProcess::Distillation => "distillation",
+            BackgroundProcess::GenomeUpdate => "genome_update",
+            BackgroundProcess::LifecycleCheck => "lifecycle_check",
+            BackgroundProcess::IndexCompaction => "index_compaction",
+            BackgroundProcess::CapabilityExpiry => "capability_expiry",
+        }
+    }
+}
+
+impl std::str::FromStr for BackgroundProcess {
+    type Err = DeltaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BackgroundProcess::ALL
+            .into_iter()
+            .find(|process| process.name() == s)
+            .ok_or_else(|| DeltaError::InvalidData {
+                reason: format!("Unknown background process: {}", s),
+            })
+    }
+}
+
+/// Schedule and run state of one background process.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessSchedule {
+    /// The process
+    pub process: BackgroundProcess,
+    /// Time between runs
+    pub interval: Duration,
+    /// Whether this process is paused on its own
+    pub paused: bool,
+    /// Completed runs since the database started
+    pub runs: u64,
+    /// When the last run completed
+    pub last_run: Option<DateTime<Utc>>,
+}
+
+/// Namespace where background process reports are stored.
+pub const SYSTEM_NAMESPACE: &str = "_system";
+
+/// Progress of a background process run, streamed while it runs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProcessProgress {
+    /// Whether a run is in progress
+    pub running: bool,
+    /// Items examined so far
+    pub scanned: u64,
+    /// Items moved to a faster tier so far
+    pub promoted: u64,
+    /// Items moved to a slower tier or reclaimed so far
+    pub archived: u64,
+    /// Time since the run started (of the whole run once finished)
+    pub elapsed: Duration,
+}
+
+/// Outcome of a completed background process run.
+///
+/// Runs that touch any items are recorded in [`SYSTEM_NAMESPACE`] under
+/// [`ProcessReport::storage_key`]; idle runs are not, so an unused database
+/// stays empty.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessReport {
+    /// The process
+    pub process: BackgroundProcess,
+    /// When the run started
+    pub started_at: DateTime<Utc>,
+    /// When the run finished
+    pub finished_at: DateTime<Utc>,
+    /// Items examined
+    pub scanned: u64,
+    /// Items moved to a faster tier
+    pub promoted: u64,
+    /// Items moved to a slower tier or reclaimed
+    pub archived: u64,
+    /// Run duration in milliseconds
+    pub elapsed_ms: u64,
+}
+
+impl ProcessReport {
+    /// Key under which a process's last report is stored in [`SYSTEM_NAMESPACE`].
+    pub fn storage_key(process: BackgroundProcess) -> String {
+        format!("report:{}", process.name())
+    }
+
+    /// Whether the run touched any items.
+    pub fn has_activity(&self) -> bool {
+        self.scanned + self.promoted + self.archived > 0
+    }
+}
+
+/// Counters a running background process updates as it goes.
+///
+/// Every update is published to watchers of
+/// [`ProcessScheduler::watch_progress`].
+#[derive(Clone)]
+pub struct RunProgress {
+    started_at: DateTime<Utc>,
+    progress: Arc<Mutex<ProcessProgress>>,
+    progress_tx: WatchSender<ProcessProgress>,
+}
+
+impl RunProgress {
+    /// Count items examined.
+    pub fn scanned(&self, count: u64) {
+        self.update(|progress| progress.scanned += count);
+    }
+
+    /// Count items moved to a faster tier.
+    pub fn promoted(&self, count: u64) {
+        self.update(|progress| progress.promoted += count);
+    }
+
+    /// Count items moved to a slower tier or reclaimed.
+    pub fn archived(&self, count: u64) {
+        self.update(|progress| progress.archived += count);
+    }
+
+    /// Current counters.
+    pub fn snapshot(&self) -> ProcessProgress {
+        self.progress.lock().unwrap().clone()
+    }
+
+    fn update(&self, f: impl FnOnce(&mut ProcessProgress)) {
+        let mut progress = self.progress.lock().unwrap();
+        f(&mut progress);
+        progress.elapsed = (Utc::now() - self.started_at).to_std().unwrap_or_default();
+        let _ = self.progress_tx.send(progress.clone());
+    }
+}
+
+struct ScheduleEntry {
+    paused: bool,
+    runs: u64,
+    last_run: Option<DateTime<Utc>>,
+    /// Current interval; running loops rebuild their timer when it changes
+    interval_tx: WatchSender<Duration>,
+    interval_rx: WatchReceiver<Duration>,
+    interval: Duration,
+    /// Progress of the current (or last) run
+    progress_tx: WatchSender<ProcessProgress>,
+    progress_rx: WatchReceiver<ProcessProgress>,
+}
+
+/// Scheduler for the database's background processes.
+///
+/// Intervals start from the database's process configuration and can be
+/// changed at runtime. A process skips its ticks while it, or the whole
+/// scheduler, is paused; a tick already running finishes.
+pub struct ProcessScheduler {
+    entries: Mutex<HashMap<BackgroundProcess, ScheduleEntry>>,
+    all_paused: AtomicBool,
+}
+
+impl ProcessScheduler {
+    /// Create a scheduler for the given processes and initial intervals.
+    pub fn new<R: Runtime>(runtime: &R, intervals: &[(BackgroundProcess, Duration)]) -> Self {
+        let entries = intervals
+            .iter()
+            .map(|&(process, interval)| {
+                let (interval_tx, interval_rx) = runtime.watch_channel(interval);
+                let (progress_tx, progress_rx) = runtime.watch_channel(ProcessProgress::default());
+                let entry = ScheduleEntry {
+                    paused: false,
+                    runs: 0,
+                    last_run: None,
+                    interval_tx,
+                    interval_rx,
+                    interval,
+                    progress_tx,
+                    progress_rx,
+                };
+                (process, entry)
+            })
+            .collect();
+
+        Self {
+            entries: Mutex::new(entries),
+            all_paused: AtomicBool::new(false),
+        }
+    }
+
+    /// Schedules of all registered processes, in [`BackgroundProcess::ALL`] order.
+    pub fn list(&self) -> Vec<ProcessSchedule> {
+        BackgroundProcess::ALL
+            .into_iter()
+            .filter_map(|process| self.get(process))
+            .collect()
+    }
+
+    /// Schedule of one process, if it is registered.
+    pub fn get(&self, process: BackgroundProcess) -> Option<ProcessSchedule> {
+        let entries = self.entries.lock().unwrap();
+        entries.get(&process).map(|entry| ProcessSchedule {
+            process,
+            interval: entry.interval,
+            paused: entry.paused,
+            runs: entry.runs,
+            last_run: entry.last_run,
+        })
+    }
+
+    /// Pause one process.
+    pub fn pause(&self, process: BackgroundProcess) -> DeltaResult<()> {
+        self.update(process, |entry| entry.paused = true)
+    }
+
+    /// Resume one process.
+    pub fn resume(&self, process: BackgroundProcess) -> DeltaResult<()> {
+        self.update(process, |entry| entry.paused = false)
+    }
+
+    /// Change how often a process runs.
+    ///
+    /// Takes effect immediately: the next run is one new interval from now.
+    pub fn reschedule(&self, process: BackgroundProcess, interval: Duration) -> DeltaResult<()> {
+        if interval.is_zero() {
+            return Err(DeltaError::InvalidData {
+                reason: "Process interval must be greater than zero".to_string(),
+            });
+        }
+        self.update(process, |entry| {
+            entry.interval = interval;
+            let _ = entry.interval_tx.send(interval);
+        })
+    }
+
+    /// Pause every process.
+    pub fn pause_all(&self) {
+        self.all_paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Resume every process not paused on its own.
+    pub fn resume_all(&self) {
+        self.all_paused.store(false, Ordering::SeqCst);
+    }
+
+    /// Whether the whole scheduler is paused.
+    pub fn all_paused(&self) -> bool {
+        self.all_paused.load(Ordering::SeqCst)
+    }
+
+    /// Whether `process` should skip its ticks.
+    pub fn is_paused(&self, process: BackgroundProcess) -> bool {
+        self.all_paused() || self.get(process).is_some_and(|schedule| schedule.paused)
+    }
+
+    /// Watch a process's interval, for the loop driving it.
+    pub(crate) fn watch_interval(
+        &self,
+        process: BackgroundProcess,
+    ) -> Option<WatchReceiver<Duration>> {
+        let entries = self.entries.lock().unwrap();
+        entries.get(&process).map(|entry| entry.interval_rx.clone())
+    }
+
+    /// Watch a process's progress as it runs.
+    ///
+    /// The receiver sees counters update during a run and the final
+    /// counters, with `running` cleared, once it completes.
+    pub fn watch_progress(
+        &self,
+        process: BackgroundProcess,
+    ) -> Option<WatchReceiver<ProcessProgress>> {
+        let entries = self.entries.lock().unwrap();
+        entries.get(&process).map(|entry| entry.progress_rx.clone())
+    }
+
+    /// Start a run, resetting its progress counters.
+    pub(crate) fn begin_run(&self, process: BackgroundProcess) -> Option<RunProgress> {
+        let entries = self.entries.lock().unwrap();
+        let entry = entries.get(&process)?;
+        let progress = ProcessProgress {
+            running: true,
+            ..Default::default()
+        };
+        let _ = entry.progress_tx.send(progress.clone());
+        Some(RunProgress {
+            started_at: Utc::now(),
+            progress: Arc::new(Mutex::new(progress)),
+            progress_tx: entry.progress_tx.clone(),
+        })
+    }
+
+    /// Complete a run started with [`begin_run`](Self::begin_run).
+    pub(crate) fn finish_run(
+        &self,
+        process: BackgroundProcess,
+        run: &RunProgress,
+    ) -> ProcessReport {
+        run.update(|progress| progress.running = false);
+        self.record_run(process);
+
+        let progress = run.snapshot();
+        ProcessReport {
+            process,
+            started_at: run.started_at,
+            finished_at: Utc::now(),
+            scanned: progress.scanned,
+            promoted: progress.promoted,
+            archived: progress.archived,
+            elapsed_ms: progress.elapsed.as_millis() as u64,
+        }
+    }
+
+    /// Record a completed run.
+    pub(crate) fn record_run(&self, process: BackgroundProcess) {
+        let _ = self.update(process, |entry| {
+            entry.runs += 1;
+            entry.last_run = Some(Utc::now());
+        });
+    }
+
+    fn update(
+        &self,
+        process: BackgroundProcess,
+        f: impl FnOnce(&mut ScheduleEntry),
+    ) -> DeltaResult<()> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries
+            .get_mut(&process)
+            .ok_or_else(|| DeltaError::InvalidData {
+                reason: format!("Background process not scheduled: {}", process.name()),
+            })?;
+        f(entry);
+        Ok(())
+    }
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -596,6 +1378,203 @@ mod tests {
         assert!(orch.get_agent("test_agent").is_some());
     }
 
+    /// Minimal downstream agent: synthesizes a byte per action.
+    struct TickAgent {
+        root: Distinction,
+        ticks: usize,
+    }
+
+    impl LocalCausalAgent for TickAgent {
+        type ActionData = u8;
+
+        fn get_current_root(&self) -> &Distinction {
+            &self.root
+        }
+
+        fn update_local_root(&mut self, new_root: Distinction) {
+            self.root = new_root;
+        }
+
+        fn synthesize_action(
+            &mut self,
+            action: u8,
+            engine: &Arc<DistinctionEngine>,
+        ) -> Distinction {
+            let action_distinction = action.to_canonical_structure(engine);
+            self.root = engine.synthesize(&self.root, &action_distinction);
+            self.ticks += 1;
+            self.root.clone()
+        }
+    }
+
+    #[test]
+    fn test_custom_agent_participates_in_pulses() {
+        let orch = KoruOrchestrator::new();
+        let root = orch.engine().inner().d1().clone();
+
+        orch.register_custom_agent(
+            "ticker",
+            "Tick Agent",
+            vec![AgentCapability::Custom("ticking".to_string())],
+            TickAgent {
+                root: root.clone(),
+                ticks: 0,
+            },
+            |agent, phase| (phase == CoordinationPhase::Consolidation).then_some(agent.ticks as u8),
+        );
+
+        let info = orch.get_agent("ticker").unwrap();
+        assert!(info.agent_type.ends_with("TickAgent"));
+        assert_eq!(
+            orch.find_agents_by_capability(AgentCapability::Custom("ticking".to_string()))
+                .len(),
+            1
+        );
+
+        orch.pulse(CoordinationPhase::Input);
+        assert_eq!(orch.get_agent_root("ticker").unwrap().id(), root.id());
+
+        orch.pulse(CoordinationPhase::Consolidation);
+        let pulsed = orch.get_agent_root("ticker").unwrap();
+        assert_ne!(pulsed.id(), root.id());
+        assert_eq!(
+            orch.with_custom_agent("ticker", |a: &mut TickAgent| a.ticks),
+            Some(1)
+        );
+        assert_eq!(
+            orch.with_custom_agent("ticker", |a: &mut TickAgent| a.root.id().to_string()),
+            Some(pulsed.id().to_string())
+        );
+        assert!(
+            orch.with_custom_agent("ticker", |_: &mut String| ())
+                .is_none()
+        );
+
+        orch.unregister_agent("ticker");
+        assert!(
+            orch.with_custom_agent("ticker", |a: &mut TickAgent| a.ticks)
+                .is_none()
+        );
+        orch.pulse(CoordinationPhase::Consolidation);
+    }
+
+    #[test]
+    fn test_journal_replay_restores_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.jsonl");
+
+        let orch = KoruOrchestrator::new().with_journal(ActionJournal::open(&path).unwrap());
+        orch.register_agent(AgentInfo {
+            id: "a".to_string(),
+            name: "A".to_string(),
+            root: orch.engine().inner().d0().clone(),
+            agent_type: "test".to_string(),
+            capabilities: vec![],
+        });
+        orch.pulse(CoordinationPhase::Input);
+        orch.synthesize_action(KoruAction::from(crate::actions::CustomAction::new(
+            "TEST",
+            serde_json::json!({"n": 1}),
+        )));
+        let root = orch.local_root();
+
+        let entries = orch.journal_entries();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].category, "PULSE");
+        assert_eq!(entries[2].category, "CUSTOM");
+        assert_eq!(entries[2].root, root.id().to_string());
+
+        // A fresh orchestrator on the same field restores the root
+        let restarted = KoruOrchestrator::with_engine(orch.engine().clone())
+            .with_journal(ActionJournal::open(&path).unwrap());
+        assert_ne!(restarted.local_root().id(), root.id());
+        assert_eq!(restarted.replay_journal().unwrap().id(), root.id());
+        assert_eq!(restarted.local_root().id(), root.id());
+    }
+
+    #[test]
+    fn test_custom_phases_and_hooks() {
+        use std::sync::Mutex as StdMutex;
+
+        let orch = KoruOrchestrator::new();
+        let reindex = orch
+            .define_phase(
+                "reindex",
+                &[
+                    PhaseOrder::After(CoordinationPhase::Processing),
+                    PhaseOrder::Before(CoordinationPhase::Output),
+                ],
+            )
+            .unwrap();
+        let warmup = orch
+            .define_phase("warmup", &[PhaseOrder::Before(CoordinationPhase::Input)])
+            .unwrap();
+        assert_eq!(
+            orch.pulse_coordinator().sequence(),
+            vec![
+                warmup,
+                CoordinationPhase::Input,
+                CoordinationPhase::Processing,
+                reindex,
+                CoordinationPhase::Output,
+                CoordinationPhase::Consolidation,
+            ]
+        );
+        assert_eq!(orch.custom_phase("reindex"), Some(reindex));
+
+        // Invalid definitions
+        assert!(orch.define_phase("reindex", &[]).is_err());
+        assert!(
+            orch.define_phase(
+                "impossible",
+                &[
+                    PhaseOrder::After(CoordinationPhase::Output),
+                    PhaseOrder::Before(CoordinationPhase::Input),
+                ],
+            )
+            .is_err()
+        );
+        assert!(
+            orch.define_phase(
+                "dangling",
+                &[PhaseOrder::After(CoordinationPhase::Exploration)]
+            )
+            .is_err()
+        );
+
+        let log = Arc::new(StdMutex::new(Vec::new()));
+        let before = {
+            let log = Arc::clone(&log);
+            orch.before_pulse(move |phase| log.lock().unwrap().push(format!("before {:?}", phase)))
+        };
+        {
+            let log = Arc::clone(&log);
+            orch.after_phase(reindex, move |_| {
+                log.lock().unwrap().push("reindexed".into())
+            });
+        }
+
+        // Idle -> position 0 is warmup; walk to the custom phase
+        for _ in 0..3 {
+            orch.advance_phase();
+        }
+        assert_eq!(orch.current_phase(), reindex);
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                "before Input",
+                "before Processing",
+                "before Custom(\"reindex\")",
+                "reindexed",
+            ]
+        );
+
+        assert!(orch.remove_hook(before));
+        assert!(!orch.remove_hook(before));
+        orch.pulse(CoordinationPhase::Output);
+        assert_eq!(log.lock().unwrap().len(), 4);
+    }
+
     #[test]
     fn test_agent_unregistration() {
         let orch = KoruOrchestrator::new();
@@ -722,4 +1701,113 @@ mod tests {
             orch.find_agents_by_capability(AgentCapability::Custom("my_feature".to_string()));
         assert_eq!(found.len(), 1);
     }
+
+    #[test]
+    fn test_process_scheduler_pause_resume_reschedule() {
+        let scheduler = ProcessScheduler::new(
+            &crate::runtime::DefaultRuntime::new(),
+            &[
+                (BackgroundProcess::Distillation, Duration::from_secs(60)),
+                (BackgroundProcess::Consolidation, Duration::from_secs(10)),
+            ],
+        );
+
+        let listed: Vec<_> = scheduler.list().iter().map(|s| s.process).collect();
+        assert_eq!(
+            listed,
+            vec![
+                BackgroundProcess::Consolidation,
+                BackgroundProcess::Distillation
+            ]
+        );
+
+        scheduler.pause(BackgroundProcess::Consolidation).unwrap();
+        assert!(scheduler.is_paused(BackgroundProcess::Consolidation));
+        assert!(!scheduler.is_paused(BackgroundProcess::Distillation));
+
+        scheduler.pause_all();
+        scheduler.resume(BackgroundProcess::Consolidation).unwrap();
+        assert!(scheduler.is_paused(BackgroundProcess::Consolidation));
+        scheduler.resume_all();
+        assert!(!scheduler.is_paused(BackgroundProcess::Consolidation));
+
+        let mut watch = scheduler
+            .watch_interval(BackgroundProcess::Consolidation)
+            .unwrap();
+        scheduler
+            .reschedule(BackgroundProcess::Consolidation, Duration::from_secs(30))
+            .unwrap();
+        assert!(watch.has_changed().unwrap());
+        assert_eq!(watch.borrow_and_update(), Duration::from_secs(30));
+        assert_eq!(
+            scheduler
+                .get(BackgroundProcess::Consolidation)
+                .unwrap()
+                .interval,
+            Duration::from_secs(30)
+        );
+
+        assert!(
+            scheduler
+                .reschedule(BackgroundProcess::Consolidation, Duration::ZERO)
+                .is_err()
+        );
+        assert!(scheduler.pause(BackgroundProcess::GenomeUpdate).is_err());
+    }
+
+    #[test]
+    fn test_process_progress_streams_and_reports() {
+        let scheduler = ProcessScheduler::new(
+            &crate::runtime::DefaultRuntime::new(),
+            &[(BackgroundProcess::Consolidation, Duration::from_secs(10))],
+        );
+        let mut watch = scheduler
+            .watch_progress(BackgroundProcess::Consolidation)
+            .unwrap();
+        assert!(!watch.borrow_and_update().running);
+
+        let run = scheduler
+            .begin_run(BackgroundProcess::Consolidation)
+            .unwrap();
+        run.scanned(5);
+        run.archived(2);
+        let progress = watch.borrow_and_update();
+        assert!(progress.running);
+        assert_eq!((progress.scanned, progress.archived), (5, 2));
+
+        let report = scheduler.finish_run(BackgroundProcess::Consolidation, &run);
+        assert!(!watch.borrow_and_update().running);
+        assert_eq!(
+            (report.scanned, report.promoted, report.archived),
+            (5, 0, 2)
+        );
+        assert!(report.has_activity());
+        assert_eq!(
+            ProcessReport::storage_key(report.process),
+            "report:consolidation"
+        );
+        assert_eq!(
+            scheduler
+                .get(BackgroundProcess::Consolidation)
+                .unwrap()
+                .runs,
+            1
+        );
+        assert!(
+            scheduler
+                .begin_run(BackgroundProcess::GenomeUpdate)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_background_process_names_round_trip() {
+        for process in BackgroundProcess::ALL {
+            assert_eq!(
+                process.name().parse::<BackgroundProcess>().unwrap(),
+                process
+            );
+        }
+        assert!("nope".parse::<BackgroundProcess>().is_err());
+    }
 }
@@ -32,6 +32,12 @@
 /// {"type":"put","ns":"users","key":"alice","value_hash":"abc123...","prev_hash":"def456...","timestamp":"2026-02-05T12:00:00Z","seq":42}
 /// ```
 ///
+/// A batch of writes is a single line holding its entries, so it is
+/// replayed whole or, if the append was torn, not at all:
+/// ```json
+/// {"batch":[{"type":"put",...},{"type":"put",...}],"checksum":"crc32:..."}
+/// ```
+///
 /// On startup, we replay the log to rebuild the in-memory state.
 ///
 /// # Usage
@@ -44,17 +50,21 @@
 /// let storage = persistence::load_from_wal(&path, engine).await?;
 /// ```
 use crate::error::{DeltaError, DeltaResult};
+use crate::mapper::MappingVersion;
 use crate::storage::CausalStorage;
-use crate::types::{FullKey, VectorClock, VersionedValue};
+use crate::types::{FullKey, GcReport, VectorClock, VersionSummary, VersionedValue};
 use chrono::{DateTime, Utc};
 use koru_lambda_core::DistinctionEngine;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
-use std::path::Path;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::fs;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::{Mutex, oneshot};
 
 /// Current WAL format version.
 const WAL_VERSION: u32 = 1;
@@ -84,21 +94,68 @@ struct LogEntry {
     /// The actual value (only in "inline" mode for small values).
     #[serde(skip_serializing_if = "Option::is_none")]
     value: Option<JsonValue>,
+    /// Identity that made the write, if authenticated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+    /// Cluster node the write originated on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    origin_node: Option<String>,
+    /// Write IDs of the versions this write was derived from.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    causes: Vec<String>,
+    /// Idempotency token the write was made with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    idempotency_token: Option<String>,
+    /// Versions this one summarizes after history compaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    summary: Option<VersionSummary>,
     /// Checksum of the entry (for corruption detection).
     /// Format: "crc32:XXXXXXXX" where X is hex.
     checksum: String,
 }
 
+/// A batch of entries written as one line, so replay applies all of them
+/// or, if the append was torn, none.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BatchEntry {
+    /// The batch's entries, in order.
+    batch: Vec<LogEntry>,
+    /// Checksum over the entries' checksums.
+    checksum: String,
+}
+
+impl BatchEntry {
+    fn new(batch: Vec<LogEntry>) -> Self {
+        let checksum = batch_checksum(&batch);
+        Self { batch, checksum }
+    }
+
+    /// Whether the batch and every entry in it are intact.
+    fn verify(&self) -> bool {
+        self.checksum == batch_checksum(&self.batch) && self.batch.iter().all(verify_checksum)
+    }
+}
+
+/// A line of the log: a single entry or a batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum LogRecord {
+    Batch(BatchEntry),
+    Entry(Box<LogEntry>),
+}
+
 /// Calculate CRC32 checksum for data integrity.
 fn calculate_checksum(data: &str) -> String {
     let crc = crc32fast::hash(data.as_bytes());
     format!("crc32:{:08x}", crc)
 }
 
-/// Verify entry checksum.
-fn verify_checksum(entry: &LogEntry) -> bool {
-    // Create a copy without the checksum for verification
-    let json = serde_json::json!({
+/// The fields of an entry covered by its checksum (everything but the checksum).
+///
+/// Provenance and cause fields are only included when set, so entries
+/// written before they existed still verify.
+fn checksum_payload(entry: &LogEntry) -> JsonValue {
+    let mut json = serde_json::json!({
         "version": entry.version,
         "op": &entry.op,
         "ns": &entry.ns,
@@ -109,8 +166,68 @@ fn verify_checksum(entry: &LogEntry) -> bool {
         "seq": entry.seq,
         "value": &entry.value,
     });
-    let data = json.to_string();
-    let expected = calculate_checksum(&data);
+    if let Some(author) = &entry.author {
+        json["author"] = JsonValue::from(author.as_str());
+    }
+    if let Some(origin_node) = &entry.origin_node {
+        json["origin_node"] = JsonValue::from(origin_node.as_str());
+    }
+    if !entry.causes.is_empty() {
+        json["causes"] = JsonValue::from(entry.causes.clone());
+    }
+    if let Some(token) = &entry.idempotency_token {
+        json["idempotency_token"] = JsonValue::from(token.as_str());
+    }
+    if let Some(summary) = &entry.summary {
+        json["summary"] = serde_json::to_value(summary).unwrap_or_default();
+    }
+    json
+}
+
+/// Build a checksummed "put" entry for a version.
+fn put_entry(namespace: &str, key: &str, versioned: &VersionedValue, seq: u64) -> LogEntry {
+    let mut entry = LogEntry {
+        version: WAL_VERSION,
+        op: "put".to_string(),
+        ns: namespace.to_string(),
+        key: key.to_string(),
+        value_hash: versioned.version_id().to_string(),
+        prev_hash: versioned.previous_version().map(|s| s.to_string()),
+        timestamp: versioned.timestamp(),
+        seq,
+        value: None,
+        author: versioned.author.clone(),
+        origin_node: versioned.origin_node.clone(),
+        causes: versioned.causes.clone(),
+        idempotency_token: versioned.idempotency_token.clone(),
+        summary: versioned.summary.clone(),
+        checksum: String::new(),
+    };
+    entry.checksum = calculate_checksum(&checksum_payload(&entry).to_string());
+    entry
+}
+
+/// The write ID of the version a "put" entry recorded.
+///
+/// Write IDs are `value_hash + "_" + timestamp_nanos`, so they can be
+/// recovered from the entry without storing them.
+fn entry_write_id(entry: &LogEntry) -> String {
+    format!(
+        "{}_{}",
+        entry.value_hash,
+        entry.timestamp.timestamp_nanos_opt().unwrap_or(0)
+    )
+}
+
+/// Checksum of a batch: covers every entry, in order.
+fn batch_checksum(batch: &[LogEntry]) -> String {
+    let checksums: Vec<&str> = batch.iter().map(|entry| entry.checksum.as_str()).collect();
+    calculate_checksum(&checksums.join(","))
+}
+
+/// Verify entry checksum.
+fn verify_checksum(entry: &LogEntry) -> bool {
+    let expected = calculate_checksum(&checksum_payload(entry).to_string());
     entry.checksum == expected
 }
 
@@ -121,6 +238,10 @@ struct WalMetadata {
     last_seq: u64,
     /// Current segment number.
     current_segment: u32,
+    /// Mapping the database's distinction IDs are made with; absent in
+    /// databases created before it was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mapping: Option<MappingVersion>,
 }
 
 impl Default for WalMetadata {
@@ -128,6 +249,7 @@ impl Default for WalMetadata {
         Self {
             last_seq: 0,
             current_segment: 1,
+            mapping: Some(MappingVersion::default()),
         }
     }
 }
@@ -172,38 +294,10 @@ pub async fn append_write(
     let seq = metadata.last_seq;
 
     // Store the value (content-addressed)
-    let value_hash = versioned.version_id().to_string();
-    store_value(&values_dir, &value_hash, versioned.value()).await?;
-
-    // Create log entry (without checksum first)
-    let entry_without_checksum = serde_json::json!({
-        "version": WAL_VERSION,
-        "op": "put",
-        "ns": namespace,
-        "key": key,
-        "value_hash": &value_hash,
-        "prev_hash": versioned.previous_version(),
-        "timestamp": versioned.timestamp(),
-        "seq": seq,
-        "value": Option::<JsonValue>::None,
-    });
-
-    // Calculate checksum
-    let checksum = calculate_checksum(&entry_without_checksum.to_string());
+    store_value(&values_dir, versioned.version_id(), versioned.value()).await?;
 
-    // Create final entry with checksum
-    let entry = LogEntry {
-        version: WAL_VERSION,
-        op: "put".to_string(),
-        ns: namespace.to_string(),
-        key: key.to_string(),
-        value_hash,
-        prev_hash: versioned.previous_version().map(|s| s.to_string()),
-        timestamp: versioned.timestamp(),
-        seq,
-        value: None,
-        checksum,
-    };
+    // Create log entry with checksum
+    let entry = put_entry(namespace, key, versioned, seq);
 
     // Serialize to JSON line
     let line = serde_json::to_string(&entry)?;
@@ -256,7 +350,9 @@ pub async fn append_write(
 /// Append multiple writes to the WAL in a single batch operation.
 ///
 /// This is significantly more efficient than calling `append_write` multiple times
-/// because it performs only one fsync for all entries.
+/// because it performs only one fsync for all entries. The writes are
+/// appended as one record, so replay applies either all of them or, if the
+/// append was cut short, none.
 ///
 /// # Arguments
 ///
@@ -279,7 +375,22 @@ pub async fn append_write_batch(
     db_path: &Path,
     writes: Vec<(&str, &str, &VersionedValue)>,
 ) -> DeltaResult<()> {
-    if writes.is_empty() {
+    append_records(db_path, vec![writes]).await
+}
+
+/// Append records to the WAL with a single fsync.
+///
+/// Each record is a group of writes replayed all or nothing: a single
+/// write is appended as a plain entry, several as one batch line.
+async fn append_records(
+    db_path: &Path,
+    records: Vec<Vec<(&str, &str, &VersionedValue)>>,
+) -> DeltaResult<()> {
+    let records: Vec<_> = records
+        .into_iter()
+        .filter(|record| !record.is_empty())
+        .collect();
+    if records.is_empty() {
         return Ok(());
     }
 
@@ -297,45 +408,26 @@ pub async fn append_write_batch(
     let mut metadata = load_metadata(&wal_dir).await.unwrap_or_default();
 
     // Collect all lines to write
-    let mut lines = Vec::with_capacity(writes.len());
-
-    for (namespace, key, versioned) in writes {
-        metadata.last_seq += 1;
-        let seq = metadata.last_seq;
-
-        // Store the value (content-addressed)
-        let value_hash = versioned.version_id().to_string();
-        store_value(&values_dir, &value_hash, versioned.value()).await?;
-
-        // Create log entry
-        let entry_without_checksum = serde_json::json!({
-            "version": WAL_VERSION,
-            "op": "put",
-            "ns": namespace,
-            "key": key,
-            "value_hash": &value_hash,
-            "prev_hash": versioned.previous_version(),
-            "timestamp": versioned.timestamp(),
-            "seq": seq,
-            "value": Option::<JsonValue>::None,
-        });
+    let mut lines = Vec::with_capacity(records.len());
 
-        let checksum = calculate_checksum(&entry_without_checksum.to_string());
-
-        let entry = LogEntry {
-            version: WAL_VERSION,
-            op: "put".to_string(),
-            ns: namespace.to_string(),
-            key: key.to_string(),
-            value_hash,
-            prev_hash: versioned.previous_version().map(|s| s.to_string()),
-            timestamp: versioned.timestamp(),
-            seq,
-            value: None,
-            checksum,
-        };
+    for record in records {
+        let mut entries = Vec::with_capacity(record.len());
+        for (namespace, key, versioned) in record {
+            metadata.last_seq += 1;
+            let seq = metadata.last_seq;
+
+            // Store the value (content-addressed)
+            store_value(&values_dir, versioned.version_id(), versioned.value()).await?;
+
+            // Create log entry
+            entries.push(put_entry(namespace, key, versioned, seq));
+        }
 
-        let line = serde_json::to_string(&entry)?;
+        let line = if entries.len() == 1 {
+            serde_json::to_string(&entries[0])?
+        } else {
+            serde_json::to_string(&BatchEntry::new(entries))?
+        };
         lines.push(line);
     }
 
@@ -388,6 +480,156 @@ pub async fn append_write_batch(
     Ok(())
 }
 
+/// Group commit for WAL appends.
+///
+/// Writers that arrive while a flush is in progress queue up and are
+/// appended together by the next flush, so a burst of concurrent writes
+/// shares a single fsync instead of queueing for one each. Every writer
+/// still waits until its own entries are synced: a write is durable when
+/// [`commit`](Self::commit) returns.
+///
+/// There is no background flusher. Whichever waiting writer gets the flush
+/// lock next flushes the whole queue on behalf of the others, which also
+/// keeps appends from interleaving in the log.
+///
+/// # Example
+///
+/// ```ignore
+/// let wal = GroupCommit::new("~/.korudelta/db");
+/// wal.commit(vec![(FullKey::new("users", "alice"), versioned)]).await?;
+/// ```
+#[derive(Debug)]
+pub struct GroupCommit {
+    db_path: PathBuf,
+    /// Writes waiting for the next flush
+    pending: std::sync::Mutex<Vec<PendingCommit>>,
+    /// Held by the writer flushing on behalf of the group
+    flushing: Mutex<()>,
+    flushes: AtomicU64,
+    writes: AtomicU64,
+}
+
+/// Writes of one `commit` call and where to report their outcome.
+#[derive(Debug)]
+struct PendingCommit {
+    writes: Vec<(FullKey, VersionedValue)>,
+    done: oneshot::Sender<Result<(), String>>,
+}
+
+/// Writes queued with [`GroupCommit::enqueue`], awaiting their flush.
+#[derive(Debug)]
+pub struct QueuedCommit {
+    outcome: oneshot::Receiver<Result<(), String>>,
+}
+
+/// Counters of a [`GroupCommit`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GroupCommitStats {
+    /// WAL flushes (fsyncs) performed.
+    pub flushes: u64,
+    /// Writes appended by those flushes.
+    pub writes: u64,
+}
+
+impl GroupCommit {
+    /// Create a group commit appending to the WAL of `db_path`.
+    pub fn new(db_path: impl Into<PathBuf>) -> Self {
+        Self {
+            db_path: db_path.into(),
+            pending: std::sync::Mutex::new(Vec::new()),
+            flushing: Mutex::new(()),
+            flushes: AtomicU64::new(0),
+            writes: AtomicU64::new(0),
+        }
+    }
+
+    /// Append writes to the WAL, returning once they are synced to disk.
+    ///
+    /// The writes are appended in order as one record, which replay applies
+    /// all or nothing, in the same flush as any other writes queued
+    /// meanwhile.
+    pub async fn commit(&self, writes: Vec<(FullKey, VersionedValue)>) -> DeltaResult<()> {
+        if writes.is_empty() {
+            return Ok(());
+        }
+        self.flush(self.enqueue(writes)).await
+    }
+
+    /// Queue writes for the next flush without waiting for it.
+    ///
+    /// Writes are appended in the order they were queued. Pass the result
+    /// to [`flush`](Self::flush) to wait until they are synced.
+    pub fn enqueue(&self, writes: Vec<(FullKey, VersionedValue)>) -> QueuedCommit {
+        let (done, outcome) = oneshot::channel();
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(PendingCommit { writes, done });
+        QueuedCommit { outcome }
+    }
+
+    /// Wait until queued writes are synced, flushing the queue ourselves
+    /// unless another writer's flush takes them first.
+    pub async fn flush(&self, queued: QueuedCommit) -> DeltaResult<()> {
+        let mut outcome = queued.outcome;
+        let _flushing = self.flushing.lock().await;
+
+        // The previous flush may have taken our writes with it
+        match outcome.try_recv() {
+            Ok(result) => return result.map_err(DeltaError::StorageError),
+            Err(oneshot::error::TryRecvError::Closed) => {
+                return Err(DeltaError::StorageError(
+                    "WAL flush was cancelled".to_string(),
+                ));
+            }
+            Err(oneshot::error::TryRecvError::Empty) => {}
+        }
+
+        let group = std::mem::take(&mut *self.pending.lock().unwrap_or_else(|e| e.into_inner()));
+        // Each commit is its own record, replayed all or nothing
+        let records: Vec<Vec<(&str, &str, &VersionedValue)>> = group
+            .iter()
+            .map(|commit| {
+                commit
+                    .writes
+                    .iter()
+                    .map(|(key, versioned)| (key.namespace.as_str(), key.key.as_str(), versioned))
+                    .collect()
+            })
+            .collect();
+        let count = group
+            .iter()
+            .map(|commit| commit.writes.len())
+            .sum::<usize>() as u64;
+
+        let result = append_records(&self.db_path, records).await;
+        self.flushes.fetch_add(1, Ordering::Relaxed);
+        self.writes.fetch_add(count, Ordering::Relaxed);
+
+        let shared = result.as_ref().map(|_| ()).map_err(|e| e.to_string());
+        for commit in group {
+            let _ = commit.done.send(shared.clone());
+        }
+        result
+    }
+
+    /// Flush everything queued so far.
+    ///
+    /// Once this returns no flush is in progress, so the log can be
+    /// rewritten safely as long as nothing new is queued meanwhile.
+    pub async fn sync(&self) -> DeltaResult<()> {
+        self.flush(self.enqueue(Vec::new())).await
+    }
+
+    /// Flushes performed and writes appended so far.
+    pub fn stats(&self) -> GroupCommitStats {
+        GroupCommitStats {
+            flushes: self.flushes.load(Ordering::Relaxed),
+            writes: self.writes.load(Ordering::Relaxed),
+        }
+    }
+}
+
 /// Store a value in the content-addressed store.
 ///
 /// Values are stored in a directory structure based on their hash:
@@ -487,8 +729,27 @@ pub async fn load_from_wal(
         return Ok(storage);
     }
 
-    // Get all WAL segments in order
-    let mut read_dir = fs::read_dir(&wal_dir)
+    // Keep making IDs the way the stored ones were made; databases from
+    // before the mapping was recorded folded every value
+    let mut metadata = load_metadata(&wal_dir).await.unwrap_or_default();
+    if metadata.mapping.is_none() {
+        metadata.mapping = Some(MappingVersion::Fold);
+        save_metadata(&wal_dir, &metadata).await?;
+    }
+    storage.set_mapping_version(metadata.mapping.unwrap_or_default());
+
+    // Replay each segment
+    for segment in list_segments(&wal_dir).await? {
+        let segment_path = wal_dir.join(&segment);
+        replay_segment(&segment_path, &values_dir, &storage).await?;
+    }
+
+    Ok(storage)
+}
+
+/// Get the names of all WAL segments, in replay order.
+async fn list_segments(wal_dir: &Path) -> DeltaResult<Vec<String>> {
+    let mut read_dir = fs::read_dir(wal_dir)
         .await
         .map_err(|e| DeltaError::StorageError(format!("Failed to read WAL dir: {}", e)))?;
 
@@ -506,14 +767,7 @@ pub async fn load_from_wal(
     }
 
     segments.sort();
-
-    // Replay each segment
-    for segment in segments {
-        let segment_path = wal_dir.join(&segment);
-        replay_segment(&segment_path, &values_dir, &storage).await?;
-    }
-
-    Ok(storage)
+    Ok(segments)
 }
 
 /// Replay a single WAL segment.
@@ -537,47 +791,227 @@ async fn replay_segment(
             continue;
         }
 
-        let entry: LogEntry = match serde_json::from_str(&line) {
-            Ok(e) => e,
+        let entries = match serde_json::from_str::<LogRecord>(&line) {
+            Ok(LogRecord::Entry(entry)) => vec![*entry],
+            // A batch is applied whole or not at all
+            Ok(LogRecord::Batch(batch)) => {
+                if !batch.verify() {
+                    eprintln!(
+                        "Warning: Checksum mismatch for batch of {} entries, possible corruption",
+                        batch.batch.len()
+                    );
+                    continue;
+                }
+                batch.batch
+            }
             Err(e) => {
                 eprintln!("Warning: Failed to parse WAL entry: {}", e);
                 continue;
             }
         };
 
-        // Verify checksum
-        if !verify_checksum(&entry) {
-            eprintln!(
-                "Warning: Checksum mismatch for entry seq={}, possible corruption",
-                entry.seq
+        for entry in entries {
+            replay_entry(entry, values_dir, storage).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Apply a single log entry to `storage`.
+async fn replay_entry(
+    entry: LogEntry,
+    values_dir: &Path,
+    storage: &CausalStorage,
+) -> DeltaResult<()> {
+    // Verify checksum
+    if !verify_checksum(&entry) {
+        eprintln!(
+            "Warning: Checksum mismatch for entry seq={}, possible corruption",
+            entry.seq
+        );
+        return Ok(());
+    }
+
+    if entry.op == "put" {
+        // Load value from content store
+        if let Some(value) = load_value(values_dir, &entry.value_hash).await? {
+            // Reconstruct versioned value
+            // For replay: write_id = value_hash + timestamp_nanos to match original
+            let write_id = entry_write_id(&entry);
+            let mut versioned = VersionedValue::new(
+                Arc::new(value),
+                entry.timestamp,
+                write_id,                 // unique write_id for replay
+                entry.value_hash.clone(), // distinction_id = content hash
+                entry.prev_hash.clone(),  // previous version
+                VectorClock::new(),       // Initialize empty vector clock
             );
-            continue;
+            versioned.author = entry.author;
+            versioned.origin_node = entry.origin_node;
+            versioned.causes = entry.causes;
+            versioned.idempotency_token = entry.idempotency_token;
+            versioned.summary = entry.summary;
+
+            // Store in storage using direct insert to preserve original IDs
+            let _ = storage.insert_direct(&entry.ns, &entry.key, versioned);
+        } else {
+            eprintln!("Warning: Value not found for hash {}", entry.value_hash);
         }
+    }
 
-        if entry.op == "put" {
-            // Load value from content store
-            if let Some(value) = load_value(values_dir, &entry.value_hash).await? {
-                // Reconstruct versioned value
-                // For replay: write_id = value_hash + timestamp_nanos to match original
-                let write_id = format!(
-                    "{}_{}",
-                    entry.value_hash,
-                    entry.timestamp.timestamp_nanos_opt().unwrap_or(0)
-                );
-                let versioned = VersionedValue::new(
-                    Arc::new(value),
-                    entry.timestamp,
-                    write_id,                 // unique write_id for replay
-                    entry.value_hash.clone(), // distinction_id = content hash
-                    entry.prev_hash.clone(),  // previous version
-                    VectorClock::new(),       // Initialize empty vector clock
-                );
+    Ok(())
+}
 
-                // Store in storage using direct insert to preserve original IDs
-                let _ = storage.insert_direct(&entry.ns, &entry.key, versioned);
-            } else {
-                eprintln!("Warning: Value not found for hash {}", entry.value_hash);
+/// Compact the WAL down to the versions `storage` still holds.
+///
+/// Run after garbage collection. "put" entries for versions no longer in
+/// `storage` (along with corrupt and duplicate entries, which replay skips
+/// anyway) are dropped, and value files no remaining entry refers to are
+/// deleted. Fills in the persistence counters of `report`.
+///
+/// The compacted log is written to a new segment after the existing ones
+/// before they are deleted, so a crash at any point still replays to the
+/// same state. Writes must be paused while this runs.
+pub async fn compact(
+    db_path: &Path,
+    storage: &CausalStorage,
+    report: &mut GcReport,
+) -> DeltaResult<()> {
+    let wal_dir = db_path.join("wal");
+    let values_dir = db_path.join("values");
+    if !wal_dir.exists() {
+        return Ok(());
+    }
+
+    let segments = list_segments(&wal_dir).await?;
+    let mut old_bytes = 0u64;
+    let mut compacted = String::new();
+    let mut kept_writes = HashSet::new();
+    let mut kept_values = HashSet::new();
+    for segment in &segments {
+        let segment_path = wal_dir.join(segment);
+        let contents = fs::read_to_string(&segment_path)
+            .await
+            .map_err(|e| DeltaError::StorageError(format!("Failed to read segment: {}", e)))?;
+        old_bytes += contents.len() as u64;
+
+        for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+            // Batches were applied whole, so their live writes are kept as
+            // plain entries
+            let (entries, whole_line) = match serde_json::from_str::<LogRecord>(line) {
+                Ok(LogRecord::Entry(entry)) => (vec![*entry], true),
+                Ok(LogRecord::Batch(batch)) if batch.verify() => (batch.batch, false),
+                _ => {
+                    report.wal_entries_removed += 1;
+                    continue;
+                }
+            };
+            for entry in entries {
+                let mut rewritten = None;
+                let keep = if !verify_checksum(&entry) {
+                    false
+                } else if entry.op == "put" {
+                    let write_id = entry_write_id(&entry);
+                    let current = storage.version(&write_id);
+                    let live = current.is_some() && kept_writes.insert(write_id);
+                    if live {
+                        // History compaction relinks and summarizes versions in place
+                        if let Some(current) = current.filter(|current| {
+                            current.previous_version != entry.prev_hash
+                                || current.causes != entry.causes
+                                || current.summary != entry.summary
+                        }) {
+                            rewritten = Some(put_entry(&entry.ns, &entry.key, &current, entry.seq));
+                        }
+                        kept_values.insert(entry.value_hash.clone());
+                    }
+                    live
+                } else {
+                    true
+                };
+                if let Some(entry) = rewritten {
+                    compacted.push_str(&serde_json::to_string(&entry)?);
+                    compacted.push('\n');
+                } else if keep && whole_line {
+                    compacted.push_str(line);
+                    compacted.push('\n');
+                } else if keep {
+                    compacted.push_str(&serde_json::to_string(&entry)?);
+                    compacted.push('\n');
+                } else {
+                    report.wal_entries_removed += 1;
+                }
+            }
+        }
+    }
+
+    // Point new appends past the compacted segment before it appears
+    let mut metadata = load_metadata(&wal_dir).await.unwrap_or_default();
+    let last_segment = segments
+        .last()
+        .and_then(|name| name.trim_end_matches(".wal").parse::<u32>().ok())
+        .unwrap_or(0);
+    let compacted_segment = metadata.current_segment.max(last_segment) + 1;
+    metadata.current_segment = compacted_segment;
+    save_metadata(&wal_dir, &metadata).await?;
+
+    let temp_path = wal_dir.join("compact.tmp");
+    let mut file = fs::File::create(&temp_path)
+        .await
+        .map_err(|e| DeltaError::StorageError(format!("Failed to create segment: {}", e)))?;
+    file.write_all(compacted.as_bytes())
+        .await
+        .map_err(|e| DeltaError::StorageError(format!("Failed to write segment: {}", e)))?;
+    file.sync_all()
+        .await
+        .map_err(|e| DeltaError::StorageError(format!("Failed to sync segment: {}", e)))?;
+    fs::rename(
+        &temp_path,
+        wal_dir.join(format!("{:06}.wal", compacted_segment)),
+    )
+    .await
+    .map_err(|e| DeltaError::StorageError(format!("Failed to rename segment: {}", e)))?;
+
+    for segment in &segments {
+        fs::remove_file(wal_dir.join(segment))
+            .await
+            .map_err(|e| DeltaError::StorageError(format!("Failed to remove segment: {}", e)))?;
+    }
+    report.bytes_reclaimed += old_bytes.saturating_sub(compacted.len() as u64);
+
+    // Values live at values/AB/CD..., keyed by hash
+    if values_dir.exists() {
+        let mut prefixes = fs::read_dir(&values_dir)
+            .await
+            .map_err(|e| DeltaError::StorageError(format!("Failed to read values dir: {}", e)))?;
+        while let Some(prefix) = prefixes
+            .next_entry()
+            .await
+            .map_err(|e| DeltaError::StorageError(format!("Failed to read values dir: {}", e)))?
+        {
+            let prefix_name = prefix.file_name().to_string_lossy().into_owned();
+            let Ok(mut files) = fs::read_dir(prefix.path()).await else {
+                continue;
+            };
+            while let Some(file) = files.next_entry().await.map_err(|e| {
+                DeltaError::StorageError(format!("Failed to read values dir: {}", e))
+            })? {
+                let suffix = file.file_name().to_string_lossy().into_owned();
+                if suffix.ends_with(".tmp")
+                    || kept_values.contains(&format!("{}{}", prefix_name, suffix))
+                {
+                    continue;
+                }
+                let size = file.metadata().await.map(|m| m.len()).unwrap_or(0);
+                fs::remove_file(file.path()).await.map_err(|e| {
+                    DeltaError::StorageError(format!("Failed to remove value: {}", e))
+                })?;
+                report.value_files_removed += 1;
+                report.bytes_reclaimed += size;
             }
+            // Only succeeds once the prefix directory is empty
+            let _ = fs::remove_dir(prefix.path()).await;
         }
     }
 
@@ -646,6 +1080,43 @@ pub async fn acquire_lock(db_path: &Path) -> DeltaResult<LockState> {
     Ok(LockState::Clean)
 }
 
+/// Flush the WAL to durable storage.
+///
+/// Appends already sync their segment; this additionally syncs the active
+/// segment's metadata and the WAL directory, so segment creation and
+/// rotation survive a power loss. Called on shutdown before the lock is
+/// released.
+pub async fn flush(db_path: &Path) -> DeltaResult<()> {
+    let wal_dir = db_path.join("wal");
+    if !wal_dir.exists() {
+        return Ok(());
+    }
+
+    let metadata = load_metadata(&wal_dir).await.unwrap_or_default();
+    let segment_path = wal_dir.join(format!("{:06}.wal", metadata.current_segment));
+    if segment_path.exists() {
+        let file = fs::OpenOptions::new()
+            .append(true)
+            .open(&segment_path)
+            .await
+            .map_err(|e| DeltaError::StorageError(format!("Failed to open WAL: {}", e)))?;
+        file.sync_all()
+            .await
+            .map_err(|e| DeltaError::StorageError(format!("Failed to sync WAL: {}", e)))?;
+    }
+
+    // Directories can only be opened for syncing on Unix
+    #[cfg(unix)]
+    fs::File::open(&wal_dir)
+        .await
+        .map_err(|e| DeltaError::StorageError(format!("Failed to open WAL dir: {}", e)))?
+        .sync_all()
+        .await
+        .map_err(|e| DeltaError::StorageError(format!("Failed to sync WAL dir: {}", e)))?;
+
+    Ok(())
+}
+
 /// Mark the database as cleanly shut down.
 pub async fn release_lock(db_path: &Path) -> DeltaResult<()> {
     let lock_path = db_path.join(LOCK_FILE);
@@ -655,8 +1126,10 @@ pub async fn release_lock(db_path: &Path) -> DeltaResult<()> {
     Ok(())
 }
 
-/// Mark the database as having shut down uncleanly (for testing).
-#[allow(dead_code)]
+/// Mark the database as having shut down uncleanly.
+///
+/// Used when shutdown gives up before writes drain, so the next start runs
+/// recovery instead of refusing to open a database that looks in use.
 pub async fn mark_unclean_shutdown(db_path: &Path) -> DeltaResult<()> {
     let lock_path = db_path.join(LOCK_FILE);
     fs::write(&lock_path, "UNCLEAN")
@@ -745,6 +1218,19 @@ pub async fn save(storage: &CausalStorage, path: &Path) -> DeltaResult<()> {
         .await
         .map_err(|e| DeltaError::StorageError(format!("Failed to create db dir: {}", e)))?;
 
+    // A new database keeps the mapping the saved versions were made with
+    let wal_dir = path.join("wal");
+    if load_metadata(&wal_dir).await.is_err() {
+        fs::create_dir_all(&wal_dir)
+            .await
+            .map_err(|e| DeltaError::StorageError(format!("Failed to create WAL dir: {}", e)))?;
+        let metadata = WalMetadata {
+            mapping: Some(storage.mapping_version()),
+            ..WalMetadata::default()
+        };
+        save_metadata(&wal_dir, &metadata).await?;
+    }
+
     // Get all history and write to WAL (preserves full history)
     let (_current_state, history_log) = storage.create_snapshot();
 
@@ -931,4 +1417,112 @@ mod tests {
         let keys = storage.list_keys("test");
         assert_eq!(keys.len(), 1);
     }
+
+    #[tokio::test]
+    async fn test_load_keeps_mapping_of_existing_database() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("db");
+        let versioned = VersionedValue::new(
+            Arc::new(json!({"test": "value"})),
+            Utc::now(),
+            "hash123".to_string(),
+            "hash123".to_string(),
+            None,
+            VectorClock::new(),
+        );
+        append_write(&db_path, "test", "key", &versioned)
+            .await
+            .unwrap();
+
+        // New databases record the current mapping
+        let engine = Arc::new(DistinctionEngine::new());
+        let storage = load_from_wal(&db_path, Arc::clone(&engine)).await.unwrap();
+        assert_eq!(storage.mapping_version(), MappingVersion::Chunked);
+
+        // One written before the mapping was recorded keeps folding
+        let wal_dir = db_path.join("wal");
+        let mut metadata = load_metadata(&wal_dir).await.unwrap();
+        metadata.mapping = None;
+        save_metadata(&wal_dir, &metadata).await.unwrap();
+        let storage = load_from_wal(&db_path, Arc::clone(&engine)).await.unwrap();
+        assert_eq!(storage.mapping_version(), MappingVersion::Fold);
+
+        // ...and remembers that from then on
+        append_write(&db_path, "test", "other", &versioned)
+            .await
+            .unwrap();
+        let storage = load_from_wal(&db_path, engine).await.unwrap();
+        assert_eq!(storage.mapping_version(), MappingVersion::Fold);
+    }
+
+    #[tokio::test]
+    async fn test_batch_replays_all_or_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("db");
+        let versions: Vec<VersionedValue> = (0..6)
+            .map(|i| {
+                let id = format!("hash{:04}", i);
+                VersionedValue::new(
+                    Arc::new(json!({"n": i})),
+                    Utc::now(),
+                    id.clone(),
+                    id,
+                    None,
+                    VectorClock::new(),
+                )
+            })
+            .collect();
+        let keys: Vec<String> = (0..6).map(|i| format!("key{}", i)).collect();
+        let batch = |range: std::ops::Range<usize>| -> Vec<(&str, &str, &VersionedValue)> {
+            range
+                .map(|i| ("test", keys[i].as_str(), &versions[i]))
+                .collect()
+        };
+
+        append_write_batch(&db_path, batch(0..3)).await.unwrap();
+        let segment = db_path.join("wal").join("000001.wal");
+        let contents = fs::read_to_string(&segment).await.unwrap();
+        assert_eq!(contents.lines().count(), 1);
+
+        // A second batch whose append was cut short
+        append_write_batch(&db_path, batch(3..6)).await.unwrap();
+        let contents = fs::read_to_string(&segment).await.unwrap();
+        let torn = contents.len() - contents.lines().last().unwrap().len() / 2;
+        fs::write(&segment, &contents[..torn]).await.unwrap();
+
+        let engine = Arc::new(DistinctionEngine::new());
+        let storage = load_from_wal(&db_path, engine).await.unwrap();
+        assert_eq!(storage.list_keys("test"), vec!["key0", "key1", "key2"]);
+    }
+
+    #[tokio::test]
+    async fn test_group_commit_coalesces_concurrent_writes() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("db");
+        let wal = GroupCommit::new(&db_path);
+
+        let writes = (0..50).map(|i| {
+            let id = format!("hash{:04}", i);
+            let versioned = VersionedValue::new(
+                Arc::new(json!({"n": i})),
+                Utc::now(),
+                id.clone(),
+                id,
+                None,
+                VectorClock::new(),
+            );
+            wal.commit(vec![(FullKey::new("test", format!("key{}", i)), versioned)])
+        });
+        for result in futures::future::join_all(writes).await {
+            result.unwrap();
+        }
+
+        let stats = wal.stats();
+        assert_eq!(stats.writes, 50);
+        assert!(stats.flushes < 50, "writes were not grouped: {:?}", stats);
+
+        let engine = Arc::new(DistinctionEngine::new());
+        let storage = load_from_wal(&db_path, engine).await.unwrap();
+        assert_eq!(storage.list_keys("test").len(), 50);
+    }
 }
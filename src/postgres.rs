@@ -0,0 +1,353 @@
+/// Postgres logical replication source.
+///
+/// [`PostgresSource`] mirrors tables of an existing Postgres database into
+/// namespaces. It reads a logical replication slot decoded by the
+/// [wal2json](https://github.com/eulerto/wal2json) output plugin and turns
+/// every row change into a causal version: inserts and updates become
+/// writes, deletes and truncates become tombstones. Each version is
+/// attributed to `postgres:<slot>`, so [`provenance`](crate::KoruDelta::provenance)
+/// shows where it came from, and history keeps every state a row went
+/// through.
+///
+/// Changes are peeked from the slot and the slot is only advanced once they
+/// are applied, so a crash replays changes rather than losing them.
+///
+/// Rows are keyed by their primary key (composite keys are joined with
+/// `/`). Tables need a primary key, or `REPLICA IDENTITY FULL` for deletes
+/// to carry the old row.
+///
+/// # Example
+///
+/// ```ignore
+/// use koru_delta::postgres::PostgresSource;
+///
+/// let source = PostgresSource::new("host=localhost user=app dbname=shop", "koru")
+///     .table("public.orders", "orders")
+///     .table("public.customers", "customers");
+///
+/// // Runs until the connection fails
+/// tokio::spawn(async move { source.run(&db).await });
+/// ```
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::{Map, Value as JsonValue};
+use tokio_postgres::{Client, NoTls};
+use tracing::{debug, info, warn};
+
+use crate::KoruDelta;
+use crate::error::{DeltaError, DeltaResult};
+
+/// Source connector mirroring Postgres tables into namespaces.
+#[derive(Debug, Clone)]
+pub struct PostgresSource {
+    connection: String,
+    slot: String,
+    tables: Vec<(String, String)>,
+    poll_interval: Duration,
+    batch_size: usize,
+}
+
+impl PostgresSource {
+    /// Create a source reading `slot` over `connection` (a libpq-style
+    /// connection string). The slot is created on first use if missing.
+    pub fn new(connection: impl Into<String>, slot: impl Into<String>) -> Self {
+        Self {
+            connection: connection.into(),
+            slot: slot.into(),
+            tables: Vec::new(),
+            poll_interval: Duration::from_secs(1),
+            batch_size: 1000,
+        }
+    }
+
+    /// Mirror `table` (`schema.table`) into `namespace`.
+    pub fn table(mut self, table: impl Into<String>, namespace: impl Into<String>) -> Self {
+        self.tables.push((table.into(), namespace.into()));
+        self
+    }
+
+    /// How long to wait when the slot has no changes (default 1s).
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Maximum changes applied per poll (default 1000).
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Connect and mirror changes until an error occurs.
+    pub async fn run(&self, db: &KoruDelta) -> DeltaResult<()> {
+        let client = self.connect().await?;
+        info!(slot = %self.slot, tables = self.tables.len(), "Postgres source started");
+        loop {
+            let applied = self.poll(&client, db).await?;
+            if applied == 0 {
+                tokio::time::sleep(self.poll_interval).await;
+            }
+        }
+    }
+
+    /// Connect, apply one batch of pending changes, and return how many
+    /// row changes were applied.
+    pub async fn poll_once(&self, db: &KoruDelta) -> DeltaResult<usize> {
+        let client = self.connect().await?;
+        self.poll(&client, db).await
+    }
+
+    /// Connect and make sure the slot exists.
+    async fn connect(&self) -> DeltaResult<Client> {
+        if self.tables.is_empty() {
+            return Err(DeltaError::InvalidData {
+                reason: "Postgres source has no tables".to_string(),
+            });
+        }
+
+        let (client, connection) = tokio_postgres::connect(&self.connection, NoTls)
+            .await
+            .map_err(postgres_error)?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                warn!(error = %e, "Postgres connection closed");
+            }
+        });
+
+        let exists = client
+            .query_opt(
+                "SELECT 1 FROM pg_replication_slots WHERE slot_name = $1",
+                &[&self.slot],
+            )
+            .await
+            .map_err(postgres_error)?
+            .is_some();
+        if !exists {
+            client
+                .execute(
+                    "SELECT pg_create_logical_replication_slot($1, 'wal2json')",
+                    &[&self.slot],
+                )
+                .await
+                .map_err(postgres_error)?;
+            info!(slot = %self.slot, "Created logical replication slot");
+        }
+        Ok(client)
+    }
+
+    /// Apply the next batch of changes, then advance the slot past them.
+    async fn poll(&self, client: &Client, db: &KoruDelta) -> DeltaResult<usize> {
+        let add_tables = self
+            .tables
+            .iter()
+            .map(|(table, _)| table.as_str())
+            .collect::<Vec<_>>()
+            .join(",");
+        let rows = client
+            .query(
+                "SELECT lsn::text, data FROM pg_logical_slot_peek_changes($1, NULL, $2, \
+                 'format-version', '2', 'include-pk', '1', 'add-tables', $3)",
+                &[&self.slot, &(self.batch_size as i32), &add_tables],
+            )
+            .await
+            .map_err(postgres_error)?;
+
+        let mut applied = 0;
+        let mut last_lsn = None;
+        for row in rows {
+            let lsn: String = row.get(0);
+            let data: String = row.get(1);
+            let change: Change = serde_json::from_str(&data)?;
+            applied += self.apply(db, change).await?;
+            last_lsn = Some(lsn);
+        }
+
+        if let Some(lsn) = last_lsn {
+            client
+                .execute(
+                    "SELECT pg_replication_slot_advance($1, $2::text::pg_lsn)",
+                    &[&self.slot, &lsn],
+                )
+                .await
+                .map_err(postgres_error)?;
+            debug!(slot = %self.slot, lsn = %lsn, applied, "Advanced replication slot");
+        }
+        Ok(applied)
+    }
+
+    /// Apply one decoded change; returns the number of rows it touched.
+    async fn apply(&self, db: &KoruDelta, change: Change) -> DeltaResult<usize> {
+        let table = format!("{}.{}", change.schema, change.table);
+        let Some(namespace) = self.namespace_for(&table) else {
+            // Transaction markers and unmapped tables
+            return Ok(0);
+        };
+        let author = Some(format!("postgres:{}", self.slot));
+
+        match change.action.as_str() {
+            "I" | "U" => {
+                let key = change.key(&change.columns)?;
+                let row: Map<String, JsonValue> = change
+                    .columns
+                    .into_iter()
+                    .map(|column| (column.name, column.value))
+                    .collect();
+                db.put_attributed(namespace, key, JsonValue::Object(row), author, Vec::new())
+                    .await?;
+                Ok(1)
+            }
+            "D" => {
+                let key = change.key(&change.identity)?;
+                db.put_attributed(namespace, key, JsonValue::Null, author, Vec::new())
+                    .await?;
+                Ok(1)
+            }
+            "T" => {
+                let keys = db.list_keys(namespace).await;
+                let count = keys.len();
+                for key in keys {
+                    db.put_attributed(namespace, key, JsonValue::Null, author.clone(), Vec::new())
+                        .await?;
+                }
+                Ok(count)
+            }
+            _ => Ok(0),
+        }
+    }
+
+    /// Namespace mirroring `schema.table`, if it is mirrored.
+    fn namespace_for(&self, table: &str) -> Option<&str> {
+        self.tables
+            .iter()
+            .find(|(name, _)| name == table)
+            .map(|(_, namespace)| namespace.as_str())
+    }
+}
+
+/// One wal2json (format version 2) change.
+#[derive(Debug, Deserialize)]
+struct Change {
+    action: String,
+    #[serde(default)]
+    schema: String,
+    #[serde(default)]
+    table: String,
+    #[serde(default)]
+    columns: Vec<Column>,
+    #[serde(default)]
+    identity: Vec<Column>,
+    #[serde(default)]
+    pk: Vec<PkColumn>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Column {
+    name: String,
+    #[serde(default)]
+    value: JsonValue,
+}
+
+#[derive(Debug, Deserialize)]
+struct PkColumn {
+    name: String,
+}
+
+impl Change {
+    /// Key of the row, from its primary key columns in `columns`.
+    fn key(&self, columns: &[Column]) -> DeltaResult<String> {
+        let parts = self
+            .pk
+            .iter()
+            .map(|pk| {
+                columns
+                    .iter()
+                    .find(|column| column.name == pk.name)
+                    .map(|column| match &column.value {
+                        JsonValue::String(s) => s.clone(),
+                        other => other.to_string(),
+                    })
+            })
+            .collect::<Option<Vec<_>>>()
+            .filter(|parts| !parts.is_empty());
+        parts
+            .map(|parts| parts.join("/"))
+            .ok_or_else(|| DeltaError::InvalidData {
+                reason: format!(
+                    "Change to {}.{} has no primary key values",
+                    self.schema, self.table
+                ),
+            })
+    }
+}
+
+fn postgres_error(e: tokio_postgres::Error) -> DeltaError {
+    DeltaError::StorageError(format!("Postgres error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CoreConfig;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_changes_become_versions() {
+        let db = KoruDelta::new(CoreConfig::default()).await.unwrap();
+        let source = PostgresSource::new("host=localhost", "koru").table("public.orders", "orders");
+        let change = |data: JsonValue| serde_json::from_value::<Change>(data).unwrap();
+
+        let insert = change(json!({
+            "action": "I", "schema": "public", "table": "orders",
+            "columns": [{"name": "id", "type": "integer", "value": 7},
+                        {"name": "status", "type": "text", "value": "new"}],
+            "pk": [{"name": "id", "type": "integer"}]
+        }));
+        let update = change(json!({
+            "action": "U", "schema": "public", "table": "orders",
+            "columns": [{"name": "id", "type": "integer", "value": 7},
+                        {"name": "status", "type": "text", "value": "shipped"}],
+            "identity": [{"name": "id", "type": "integer", "value": 7}],
+            "pk": [{"name": "id", "type": "integer"}]
+        }));
+        let other = change(json!({
+            "action": "I", "schema": "public", "table": "audit",
+            "columns": [{"name": "id", "type": "integer", "value": 1}],
+            "pk": [{"name": "id", "type": "integer"}]
+        }));
+        let commit = change(json!({"action": "C"}));
+
+        for c in [insert, update, other, commit] {
+            source.apply(&db, c).await.unwrap();
+        }
+        assert_eq!(
+            db.get("orders", "7").await.unwrap().value(),
+            &json!({"id": 7, "status": "shipped"})
+        );
+        assert_eq!(db.history("orders", "7").await.unwrap().len(), 2);
+        assert!(db.list_keys("audit").await.is_empty());
+        let provenance = db.provenance("orders", "7").await.unwrap();
+        assert_eq!(
+            provenance.writes[0].author.as_deref(),
+            Some("postgres:koru")
+        );
+
+        let delete = change(json!({
+            "action": "D", "schema": "public", "table": "orders",
+            "identity": [{"name": "id", "type": "integer", "value": 7}],
+            "pk": [{"name": "id", "type": "integer"}]
+        }));
+        assert_eq!(source.apply(&db, delete).await.unwrap(), 1);
+        assert!(!db.contains("orders", "7").await);
+
+        // Deletes without the key columns cannot be mirrored
+        let keyless = change(json!({
+            "action": "D", "schema": "public", "table": "orders",
+            "pk": [{"name": "id", "type": "integer"}]
+        }));
+        assert!(matches!(
+            source.apply(&db, keyless).await,
+            Err(DeltaError::InvalidData { .. })
+        ));
+    }
+}
@@ -0,0 +1,363 @@
+/// Capability Expiry Process: grant renewal reminders and cleanup.
+///
+/// Capabilities may carry an expiry. This process periodically looks for
+/// grants that are about to lapse and announces them so granters can renew
+/// (see [`IdentityAgent::renew_capability`]), then archives grants that have
+/// been expired for longer than a grace period.
+///
+/// ## Events
+///
+/// With [`with_audit`](CapabilityExpiryProcess::with_audit), every event is
+/// written to [`CAPABILITY_EXPIRY_NAMESPACE`] and delivered to subscribers of
+/// that collection. Each capability is warned about once per expiry time, so
+/// renewing a grant re-arms its warning.
+use crate::auth::{AuthError, Capability, IdentityAgent, ResourcePattern};
+use crate::storage::CausalStorage;
+use crate::subscriptions::{ChangeEvent, SubscriptionAgent};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Namespace where capability expiry events are recorded.
+pub const CAPABILITY_EXPIRY_NAMESPACE: &str = "_auth_expiry";
+
+/// Capability expiry configuration.
+#[derive(Debug, Clone)]
+pub struct CapabilityExpiryConfig {
+    /// How often to check capabilities (seconds)
+    pub interval_secs: u64,
+
+    /// Warn about capabilities expiring within this many seconds
+    pub warning_window_secs: i64,
+
+    /// Archive capabilities this many seconds after they expire
+    pub archive_grace_secs: i64,
+}
+
+impl Default for CapabilityExpiryConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 300,          // Every 5 minutes
+            warning_window_secs: 86_400, // Warn a day ahead
+            archive_grace_secs: 86_400,  // Renewable for a day after expiry
+        }
+    }
+}
+
+/// What happened to a capability.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CapabilityExpiryKind {
+    /// The capability expires within the warning window
+    ExpiringSoon,
+    /// The capability expired and was archived
+    Archived,
+}
+
+impl CapabilityExpiryKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            CapabilityExpiryKind::ExpiringSoon => "expiring_soon",
+            CapabilityExpiryKind::Archived => "archived",
+        }
+    }
+}
+
+/// A capability expiry event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapabilityExpiryEvent {
+    /// What happened
+    pub kind: CapabilityExpiryKind,
+    /// The capability concerned
+    pub capability_id: String,
+    /// Who granted the capability (and can renew it)
+    pub granter: String,
+    /// Who holds the capability
+    pub grantee: String,
+    /// What the capability applies to
+    pub resource_pattern: ResourcePattern,
+    /// When the capability expires (or expired)
+    pub expires_at: DateTime<Utc>,
+    /// When the event was recorded
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl CapabilityExpiryEvent {
+    fn new(kind: CapabilityExpiryKind, capability: &Capability, expires_at: DateTime<Utc>) -> Self {
+        Self {
+            kind,
+            capability_id: capability.id.clone(),
+            granter: capability.granter.clone(),
+            grantee: capability.grantee.clone(),
+            resource_pattern: capability.resource_pattern.clone(),
+            expires_at,
+            recorded_at: Utc::now(),
+        }
+    }
+
+    /// Key the event is recorded under in [`CAPABILITY_EXPIRY_NAMESPACE`].
+    pub fn storage_key(&self) -> String {
+        format!("{}:{}", self.kind.as_str(), self.capability_id)
+    }
+}
+
+/// Capability Expiry Process - warns before grants lapse and archives them after.
+#[derive(Debug)]
+pub struct CapabilityExpiryProcess {
+    config: CapabilityExpiryConfig,
+    /// Expiry time each capability was last warned about
+    warned: DashMap<String, DateTime<Utc>>,
+    audit_storage: Option<Arc<CausalStorage>>,
+    audit_subscriptions: Option<Arc<SubscriptionAgent>>,
+    checks_performed: AtomicU64,
+    warnings_issued: AtomicU64,
+    capabilities_archived: AtomicU64,
+}
+
+impl CapabilityExpiryProcess {
+    /// Create new capability expiry process.
+    pub fn new() -> Self {
+        Self::with_config(CapabilityExpiryConfig::default())
+    }
+
+    /// Create with custom config.
+    pub fn with_config(config: CapabilityExpiryConfig) -> Self {
+        Self {
+            config,
+            warned: DashMap::new(),
+            audit_storage: None,
+            audit_subscriptions: None,
+            checks_performed: AtomicU64::new(0),
+            warnings_issued: AtomicU64::new(0),
+            capabilities_archived: AtomicU64::new(0),
+        }
+    }
+
+    /// Record events in storage and announce them to subscribers.
+    pub fn with_audit(
+        mut self,
+        storage: Arc<CausalStorage>,
+        subscriptions: Arc<SubscriptionAgent>,
+    ) -> Self {
+        self.audit_storage = Some(storage);
+        self.audit_subscriptions = Some(subscriptions);
+        self
+    }
+
+    /// Warn about expiring capabilities and archive expired ones.
+    ///
+    /// Returns the events produced by this run.
+    pub fn run(&self, auth: &IdentityAgent) -> Result<Vec<CapabilityExpiryEvent>, AuthError> {
+        self.checks_performed.fetch_add(1, Ordering::Relaxed);
+        let mut events = Vec::new();
+
+        let window = chrono::Duration::seconds(self.config.warning_window_secs);
+        for capability in auth.expiring_capabilities(window)? {
+            let Some(expires_at) = capability.expires_at else {
+                continue;
+            };
+            if self.warned.get(&capability.id).as_deref() == Some(&expires_at) {
+                continue;
+            }
+            self.warned.insert(capability.id.clone(), expires_at);
+            events.push(CapabilityExpiryEvent::new(
+                CapabilityExpiryKind::ExpiringSoon,
+                &capability,
+                expires_at,
+            ));
+            self.warnings_issued.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let grace = chrono::Duration::seconds(self.config.archive_grace_secs);
+        for capability in auth.archive_expired_capabilities(grace)? {
+            self.warned.remove(&capability.id);
+            let expires_at = capability.expires_at.unwrap_or_else(Utc::now);
+            events.push(CapabilityExpiryEvent::new(
+                CapabilityExpiryKind::Archived,
+                &capability,
+                expires_at,
+            ));
+            self.capabilities_archived.fetch_add(1, Ordering::Relaxed);
+        }
+
+        for event in &events {
+            self.publish(event);
+        }
+
+        Ok(events)
+    }
+
+    /// Write an event to [`CAPABILITY_EXPIRY_NAMESPACE`] and announce the change.
+    fn publish(&self, event: &CapabilityExpiryEvent) {
+        let Some(storage) = self.audit_storage.as_ref() else {
+            return;
+        };
+
+        let key = event.storage_key();
+        let record = match serde_json::to_value(event) {
+            Ok(record) => record,
+            Err(e) => {
+                tracing::warn!(error = %e, key = %key, "Failed to serialize capability event");
+                return;
+            }
+        };
+
+        let previous = storage.get(CAPABILITY_EXPIRY_NAMESPACE, &key).ok();
+        let versioned = match storage.put(CAPABILITY_EXPIRY_NAMESPACE, &key, record) {
+            Ok(versioned) => versioned,
+            Err(e) => {
+                tracing::warn!(error = %e, key = %key, "Failed to record capability event");
+                return;
+            }
+        };
+
+        if let Some(subscriptions) = self.audit_subscriptions.as_ref() {
+            let change = match previous {
+                Some(previous) => {
+                    ChangeEvent::update(CAPABILITY_EXPIRY_NAMESPACE, &key, &versioned, &previous)
+                }
+                None => ChangeEvent::insert(CAPABILITY_EXPIRY_NAMESPACE, &key, &versioned),
+            };
+            subscriptions.notify(change);
+        }
+    }
+
+    /// Get interval.
+    pub fn interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.config.interval_secs)
+    }
+
+    /// Get statistics.
+    pub fn stats(&self) -> CapabilityExpiryStats {
+        CapabilityExpiryStats {
+            checks_performed: self.checks_performed.load(Ordering::Relaxed),
+            warnings_issued: self.warnings_issued.load(Ordering::Relaxed),
+            capabilities_archived: self.capabilities_archived.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Default for CapabilityExpiryProcess {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Capability expiry statistics.
+#[derive(Debug, Clone, Default)]
+pub struct CapabilityExpiryStats {
+    /// Number of times capabilities were checked
+    pub checks_performed: u64,
+    /// Number of expiry warnings issued
+    pub warnings_issued: u64,
+    /// Number of expired capabilities archived
+    pub capabilities_archived: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{IdentityUserData, Permission};
+    use crate::engine::SharedEngine;
+    use crate::subscriptions::Subscription;
+    use chrono::Duration;
+
+    struct Fixture {
+        auth: IdentityAgent,
+        storage: Arc<CausalStorage>,
+        subscriptions: Arc<SubscriptionAgent>,
+    }
+
+    fn fixture() -> Fixture {
+        let engine = SharedEngine::new();
+        let storage = Arc::new(CausalStorage::new(Arc::clone(engine.inner())));
+        Fixture {
+            auth: IdentityAgent::new(Arc::clone(&storage), &engine),
+            subscriptions: Arc::new(SubscriptionAgent::new(&engine)),
+            storage,
+        }
+    }
+
+    fn grant(
+        auth: &IdentityAgent,
+        expires_in: Duration,
+    ) -> (Capability, crate::auth::Identity, Vec<u8>) {
+        let (granter, granter_key) = auth.create_identity(IdentityUserData::default()).unwrap();
+        let (grantee, _) = auth.create_identity(IdentityUserData::default()).unwrap();
+        let cap = auth
+            .grant_capability(
+                &granter,
+                &granter_key,
+                &grantee.public_key,
+                ResourcePattern::Namespace("docs".to_string()),
+                Permission::Read,
+                Some(Utc::now() + expires_in),
+            )
+            .unwrap();
+        (cap, granter, granter_key)
+    }
+
+    #[test]
+    fn test_warns_once_per_expiry() {
+        let f = fixture();
+        let process = CapabilityExpiryProcess::new()
+            .with_audit(Arc::clone(&f.storage), Arc::clone(&f.subscriptions));
+        let (_id, mut rx) = f
+            .subscriptions
+            .subscribe(Subscription::collection(CAPABILITY_EXPIRY_NAMESPACE));
+
+        let (cap, granter, granter_key) = grant(&f.auth, Duration::hours(1));
+        grant(&f.auth, Duration::days(30));
+
+        let events = process.run(&f.auth).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, CapabilityExpiryKind::ExpiringSoon);
+        assert_eq!(events[0].capability_id, cap.id);
+
+        let change = rx.try_recv().unwrap();
+        assert_eq!(change.key, format!("expiring_soon:{}", cap.id));
+
+        // Already warned
+        assert!(process.run(&f.auth).unwrap().is_empty());
+
+        // Renewing into the window again re-arms the warning
+        f.auth
+            .renew_capability(
+                &cap.id,
+                &granter,
+                &granter_key,
+                Some(Utc::now() + Duration::hours(2)),
+            )
+            .unwrap();
+        assert_eq!(process.run(&f.auth).unwrap().len(), 1);
+        assert_eq!(process.stats().warnings_issued, 2);
+    }
+
+    #[test]
+    fn test_archives_after_grace() {
+        let f = fixture();
+        let process = CapabilityExpiryProcess::with_config(CapabilityExpiryConfig {
+            archive_grace_secs: 60,
+            ..Default::default()
+        });
+
+        let (stale, _, _) = grant(&f.auth, Duration::minutes(-5));
+        let (recent, _, _) = grant(&f.auth, Duration::seconds(-1));
+
+        let events = process.run(&f.auth).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, CapabilityExpiryKind::Archived);
+        assert_eq!(events[0].capability_id, stale.id);
+
+        let storage = f.auth.storage();
+        assert!(storage.get_capability(&stale.id).unwrap().is_none());
+        assert!(storage.get_capability(&recent.id).unwrap().is_some());
+        assert_eq!(
+            storage.list_archived_capabilities().unwrap()[0].id,
+            stale.id
+        );
+        assert_eq!(process.stats().capabilities_archived, 1);
+    }
+}
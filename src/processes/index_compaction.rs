@@ -0,0 +1,181 @@
+/// Index Compaction Process: vector graph maintenance.
+///
+/// Deleting an embedding only tombstones it in graph-based ANN indexes; the
+/// node stays in the graph so searches can still route through it. Left
+/// alone, tombstones pile up under churn and waste search effort. This process
+/// periodically compacts the index once enough of it is dead.
+///
+/// ## Trigger
+///
+/// Compaction runs when the index holds at least `min_tombstones` deleted
+/// entries and they make up at least `min_tombstone_ratio` of all entries.
+use crate::vector::VectorIndex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Index compaction configuration.
+#[derive(Debug, Clone)]
+pub struct IndexCompactionConfig {
+    /// How often to check the index (seconds)
+    pub interval_secs: u64,
+
+    /// Minimum fraction of tombstoned entries before compacting
+    pub min_tombstone_ratio: f32,
+
+    /// Minimum number of tombstoned entries before compacting
+    pub min_tombstones: usize,
+}
+
+impl Default for IndexCompactionConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 600,       // Every 10 minutes
+            min_tombstone_ratio: 0.1, // 10% dead
+            min_tombstones: 1,
+        }
+    }
+}
+
+/// Index Compaction Process - keeps ANN graphs free of deleted nodes.
+#[derive(Debug)]
+pub struct IndexCompactionProcess {
+    config: IndexCompactionConfig,
+    checks_performed: AtomicU64,
+    compactions_performed: AtomicU64,
+    entries_reclaimed: AtomicU64,
+}
+
+impl IndexCompactionProcess {
+    /// Create new index compaction process.
+    pub fn new() -> Self {
+        Self::with_config(IndexCompactionConfig::default())
+    }
+
+    /// Create with custom config.
+    pub fn with_config(config: IndexCompactionConfig) -> Self {
+        Self {
+            config,
+            checks_performed: AtomicU64::new(0),
+            compactions_performed: AtomicU64::new(0),
+            entries_reclaimed: AtomicU64::new(0),
+        }
+    }
+
+    /// Check whether the index has accumulated enough tombstones to compact.
+    pub fn needs_compaction(&self, index: &VectorIndex) -> bool {
+        let tombstones = index.tombstone_count();
+        if tombstones == 0 || tombstones < self.config.min_tombstones {
+            return false;
+        }
+        let total = index.len() + tombstones;
+        tombstones as f32 / total as f32 >= self.config.min_tombstone_ratio
+    }
+
+    /// Compact the index if needed.
+    ///
+    /// Returns the number of entries reclaimed (zero if no compaction ran).
+    pub fn run(&self, index: &VectorIndex) -> usize {
+        self.checks_performed.fetch_add(1, Ordering::Relaxed);
+        if !self.needs_compaction(index) {
+            return 0;
+        }
+
+        let reclaimed = index.compact();
+        self.compactions_performed.fetch_add(1, Ordering::Relaxed);
+        self.entries_reclaimed
+            .fetch_add(reclaimed as u64, Ordering::Relaxed);
+        reclaimed
+    }
+
+    /// Get interval.
+    pub fn interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.config.interval_secs)
+    }
+
+    /// Get statistics.
+    pub fn stats(&self) -> IndexCompactionStats {
+        IndexCompactionStats {
+            checks_performed: self.checks_performed.load(Ordering::Relaxed),
+            compactions_performed: self.compactions_performed.load(Ordering::Relaxed),
+            entries_reclaimed: self.entries_reclaimed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Default for IndexCompactionProcess {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Index compaction statistics.
+#[derive(Debug, Clone, Default)]
+pub struct IndexCompactionStats {
+    /// Number of times the index was checked
+    pub checks_performed: u64,
+    /// Number of compactions run
+    pub compactions_performed: u64,
+    /// Total tombstoned entries removed
+    pub entries_reclaimed: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FullKey;
+    use crate::vector::{HnswConfig, Vector};
+
+    fn hnsw_with(n: usize) -> VectorIndex {
+        let index = VectorIndex::new_hnsw(HnswConfig::default());
+        for i in 0..n {
+            let angle = i as f32 * 0.3;
+            index.add(
+                FullKey::new("docs", format!("doc{}", i)),
+                Vector::new(vec![angle.cos(), angle.sin()], "test-model"),
+            );
+        }
+        index
+    }
+
+    #[test]
+    fn test_compaction_waits_for_threshold() {
+        let process = IndexCompactionProcess::with_config(IndexCompactionConfig {
+            min_tombstone_ratio: 0.5,
+            ..Default::default()
+        });
+        let index = hnsw_with(10);
+        index.remove("docs", "doc0");
+
+        assert!(!process.needs_compaction(&index));
+        assert_eq!(process.run(&index), 0);
+        assert_eq!(index.tombstone_count(), 1);
+    }
+
+    #[test]
+    fn test_compaction_reclaims_tombstones() {
+        let process = IndexCompactionProcess::new();
+        let index = hnsw_with(10);
+        for i in 0..3 {
+            index.remove("docs", &format!("doc{}", i));
+        }
+
+        assert_eq!(process.run(&index), 3);
+        assert_eq!(index.tombstone_count(), 0);
+        assert_eq!(index.len(), 7);
+
+        let stats = process.stats();
+        assert_eq!(stats.checks_performed, 1);
+        assert_eq!(stats.compactions_performed, 1);
+        assert_eq!(stats.entries_reclaimed, 3);
+    }
+
+    #[test]
+    fn test_flat_index_never_needs_compaction() {
+        let index = VectorIndex::new_flat();
+        index.add(
+            FullKey::new("docs", "a"),
+            Vector::new(vec![1.0, 0.0], "test-model"),
+        );
+        index.remove("docs", "a");
+        assert!(!IndexCompactionProcess::new().needs_compaction(&index));
+    }
+}
@@ -4,31 +4,53 @@
 /// - Consolidation: rhythmic movement between layers
 /// - Distillation: fitness-based natural selection
 /// - GenomeUpdate: DNA maintenance and disaster recovery
+/// - IndexCompaction: reclaiming deleted vectors from ANN graphs
+/// - CapabilityExpiry: renewal warnings and archival of expired grants
 ///
 /// ## LCA Architecture
 ///
 /// ProcessAgent implements `LocalCausalAgent`, making all process operations
 /// causal distinctions. The formula: `ΔNew = ΔLocal_Root ⊕ ΔAction_Data`
+///
+/// Process actions are also carried out: a [`ProcessSupervisor`] runs each
+/// spawned process as a task and tracks its status.
+#[cfg(not(target_arch = "wasm32"))]
+pub mod capability_expiry;
 pub mod consolidation;
 pub mod distillation;
 pub mod genome_update;
+pub mod index_compaction;
+pub mod supervisor;
 
 use crate::actions::{ProcessAction, ProcessConfig, ProcessType};
 use crate::engine::SharedEngine;
+use crate::error::DeltaResult;
 use crate::roots::KoruRoots;
+use crate::runtime::{DefaultRuntime, Runtime};
 use koru_lambda_core::{Canonicalizable, Distinction, DistinctionEngine, LocalCausalAgent};
 use std::sync::Arc;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub use capability_expiry::{
+    CAPABILITY_EXPIRY_NAMESPACE, CapabilityExpiryConfig, CapabilityExpiryEvent,
+    CapabilityExpiryKind, CapabilityExpiryProcess, CapabilityExpiryStats,
+};
 pub use consolidation::{ConsolidationResult, SleepAgent, SleepConfig};
 pub use distillation::{EvolutionAgent, EvolutionConfig, EvolutionResult, EvolutionStats, Fitness};
 pub use genome_update::{GenomeUpdateConfig, GenomeUpdateProcess};
+pub use index_compaction::{IndexCompactionConfig, IndexCompactionProcess, IndexCompactionStats};
+pub use supervisor::{ProcessInfo, ProcessJob, ProcessStatus, ProcessSupervisor};
 
 /// Process agent implementing LocalCausalAgent trait.
 ///
 /// Follows the LCA formula: `ΔNew = ΔLocal_Root ⊕ ΔAction_Data`
 /// All process operations are causal distinctions synthesized from the process root.
+///
+/// Applying an action also carries it out through the agent's
+/// [`ProcessSupervisor`]. A spawned process's ID is the ID of the root its
+/// spawn action produced.
 #[derive(Debug)]
-pub struct ProcessAgent {
+pub struct ProcessAgent<R: Runtime = DefaultRuntime> {
     /// LCA: Local root distinction (Root: PROCESS)
     local_root: Distinction,
 
@@ -46,6 +68,12 @@ pub struct ProcessAgent {
 
     /// Genome update process
     genome_update: GenomeUpdateProcess,
+
+    /// Vector index compaction process
+    index_compaction: IndexCompactionProcess,
+
+    /// Tasks behind spawned processes
+    supervisor: ProcessSupervisor<R>,
 }
 
 impl ProcessAgent {
@@ -54,18 +82,7 @@ impl ProcessAgent {
     /// The agent initializes from the process canonical root,
     /// establishing its causal anchor in the field.
     pub fn new(shared_engine: &SharedEngine) -> Self {
-        let engine = Arc::clone(shared_engine.inner());
-        let roots = KoruRoots::initialize(&engine);
-        let local_root = roots.process.clone();
-
-        Self {
-            local_root,
-            _field: shared_engine.clone(),
-            engine,
-            consolidation: SleepAgent::new(shared_engine),
-            distillation: EvolutionAgent::new(shared_engine),
-            genome_update: GenomeUpdateProcess::new(),
-        }
+        Self::with_runtime(shared_engine, DefaultRuntime::new())
     }
 
     /// Create with custom configurations.
@@ -86,6 +103,28 @@ impl ProcessAgent {
             consolidation: SleepAgent::with_config(consolidation, shared_engine),
             distillation: EvolutionAgent::with_config(distillation, shared_engine),
             genome_update: GenomeUpdateProcess::with_config(genome),
+            index_compaction: IndexCompactionProcess::new(),
+            supervisor: ProcessSupervisor::new(DefaultRuntime::new()),
+        }
+    }
+}
+
+impl<R: Runtime> ProcessAgent<R> {
+    /// Create a process agent whose processes run on `runtime`.
+    pub fn with_runtime(shared_engine: &SharedEngine, runtime: R) -> Self {
+        let engine = Arc::clone(shared_engine.inner());
+        let roots = KoruRoots::initialize(&engine);
+        let local_root = roots.process.clone();
+
+        Self {
+            local_root,
+            _field: shared_engine.clone(),
+            engine,
+            consolidation: SleepAgent::new(shared_engine),
+            distillation: EvolutionAgent::new(shared_engine),
+            genome_update: GenomeUpdateProcess::new(),
+            index_compaction: IndexCompactionProcess::new(),
+            supervisor: ProcessSupervisor::new(runtime),
         }
     }
 
@@ -98,13 +137,53 @@ impl ProcessAgent {
     ///
     /// This is the primary interface for process operations following
     /// the LCA formula: `ΔNew = ΔLocal_Root ⊕ ΔAction_Data`
+    ///
+    /// The action is then carried out. Actions that cannot be (e.g. pausing
+    /// an unknown process) are still recorded; use
+    /// [`try_apply_action`](Self::try_apply_action) to reject them instead.
     pub fn apply_action(&mut self, action: ProcessAction) -> Distinction {
         let engine = Arc::clone(&self.engine);
-        let new_root = self.synthesize_action(action, &engine);
+        let new_root = self.synthesize_action(action.clone(), &engine);
         self.local_root = new_root.clone();
+        if let Err(e) = self.supervisor.apply(&action, new_root.id()) {
+            tracing::debug!(error = %e, "Process action recorded without effect");
+        }
         new_root
     }
 
+    /// Apply a process action only if it can be carried out.
+    ///
+    /// Fails with `InvalidData`, leaving the root unchanged, if the action
+    /// targets an unknown or stopped process or has an invalid config.
+    pub fn try_apply_action(&mut self, action: ProcessAction) -> DeltaResult<Distinction> {
+        self.supervisor.check(&action)?;
+        let engine = Arc::clone(&self.engine);
+        let new_root = self.synthesize_action(action.clone(), &engine);
+        self.local_root = new_root.clone();
+        self.supervisor.apply(&action, new_root.id())?;
+        Ok(new_root)
+    }
+
+    /// Live state of every spawned process, oldest first.
+    pub fn list_processes(&self) -> Vec<ProcessInfo> {
+        self.supervisor.list()
+    }
+
+    /// Live state of one process.
+    pub fn process_status(&self, process_id: &str) -> Option<ProcessInfo> {
+        self.supervisor.status(process_id)
+    }
+
+    /// Get the process supervisor.
+    pub fn supervisor(&self) -> &ProcessSupervisor<R> {
+        &self.supervisor
+    }
+
+    /// Get the process supervisor mutably, to set jobs and timeouts.
+    pub fn supervisor_mut(&mut self) -> &mut ProcessSupervisor<R> {
+        &mut self.supervisor
+    }
+
     /// Get the consolidation process.
     pub fn consolidation(&self) -> &SleepAgent {
         &self.consolidation
@@ -120,6 +199,11 @@ impl ProcessAgent {
         &self.genome_update
     }
 
+    /// Get the index compaction process.
+    pub fn index_compaction(&self) -> &IndexCompactionProcess {
+        &self.index_compaction
+    }
+
     /// Spawn a process with synthesis.
     pub fn spawn_process_synthesized(
         &mut self,
@@ -171,7 +255,7 @@ impl ProcessAgent {
 }
 
 // LCA Trait Implementation
-impl LocalCausalAgent for ProcessAgent {
+impl<R: Runtime> LocalCausalAgent for ProcessAgent<R> {
     type ActionData = ProcessAction;
 
     fn get_current_root(&self) -> &Distinction {
@@ -282,8 +366,8 @@ mod tests {
             );
         }
 
-        #[test]
-        fn test_spawn_process_synthesizes() {
+        #[tokio::test]
+        async fn test_spawn_process_synthesizes() {
             let mut agent = setup_agent();
             let root_before = agent.local_root().id().to_string();
 
@@ -373,6 +457,45 @@ mod tests {
             assert_eq!(new_root.id(), root_after);
         }
 
+        #[test]
+        fn test_actions_manage_processes() {
+            use crate::runtime::TestRuntime;
+            use std::time::Duration;
+
+            let runtime = TestRuntime::new();
+            let mut agent = ProcessAgent::with_runtime(&SharedEngine::new(), runtime.clone());
+
+            let spawned = agent.spawn_process_synthesized(
+                ProcessType::Distillation,
+                ProcessConfig {
+                    interval_secs: 60,
+                    ..Default::default()
+                },
+            );
+            let id = spawned.id().to_string();
+            runtime.advance(Duration::from_secs(90));
+
+            let listed = agent.list_processes();
+            assert_eq!(listed.len(), 1);
+            assert_eq!(listed[0].id, id);
+            assert_eq!(listed[0].status, ProcessStatus::Running);
+            assert_eq!(listed[0].runs, 2);
+
+            agent.terminate_process_synthesized(id.clone());
+            runtime.advance(Duration::from_secs(60));
+            assert_eq!(
+                agent.process_status(&id).unwrap().status,
+                ProcessStatus::Terminated
+            );
+            assert_eq!(runtime.pending_tasks(), 0);
+
+            // Rejected actions leave the root alone
+            let root = agent.local_root().id().to_string();
+            let result = agent.try_apply_action(ProcessAction::ResumeProcess { process_id: id });
+            assert!(result.is_err());
+            assert_eq!(agent.local_root().id(), root);
+        }
+
         #[test]
         fn test_apply_action_changes_root() {
             let mut agent = setup_agent();
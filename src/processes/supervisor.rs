@@ -0,0 +1,494 @@
+//! Task management behind process actions.
+//!
+//! [`ProcessAgent`](super::ProcessAgent) records every process action as a
+//! causal distinction; the [`ProcessSupervisor`] carries it out. Each spawned
+//! process is a task on the runtime that runs the job registered for its
+//! [`ProcessType`] once per interval:
+//!
+//! - Pausing skips ticks until the process is resumed.
+//! - Terminating stops the task after any run in progress.
+//! - With a heartbeat timeout set, a running process that goes longer than
+//!   the timeout without a heartbeat is stopped and marked timed out.
+//!
+//! Dropping the supervisor stops all of its tasks.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures::FutureExt;
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+use crate::actions::{ProcessAction, ProcessConfig, ProcessType};
+use crate::error::{DeltaError, DeltaResult};
+use crate::runtime::{Instant, JoinHandle, Runtime, WatchReceiver, WatchSender};
+
+/// Work run on every tick of a process, given the process's configuration.
+pub type ProcessJob = Arc<dyn Fn(ProcessConfig) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// Lifecycle state of a supervised process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessStatus {
+    /// Running its job every interval
+    Running,
+    /// Skipping ticks until resumed
+    Paused,
+    /// Stopped by a terminate action
+    Terminated,
+    /// Stopped after missing its heartbeat
+    TimedOut,
+}
+
+impl ProcessStatus {
+    /// Whether the process's task is still alive.
+    pub fn is_live(&self) -> bool {
+        matches!(self, ProcessStatus::Running | ProcessStatus::Paused)
+    }
+}
+
+/// Snapshot of one supervised process.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProcessInfo {
+    /// Process ID (the root its spawn action produced)
+    pub id: String,
+    /// What kind of process it is
+    pub process_type: ProcessType,
+    /// Configuration it was spawned with
+    pub config: ProcessConfig,
+    /// Current state
+    pub status: ProcessStatus,
+    /// When it was spawned
+    pub spawned_at: DateTime<Utc>,
+    /// Last heartbeat (spawn and resume count as heartbeats)
+    pub last_heartbeat: DateTime<Utc>,
+    /// Completed job runs
+    pub runs: u64,
+    /// When the last run completed
+    pub last_run: Option<DateTime<Utc>>,
+}
+
+struct ProcessState {
+    info: ProcessInfo,
+    /// Runtime time of the last heartbeat, for the timeout
+    heartbeat_at: Instant,
+}
+
+struct ManagedProcess {
+    state: Arc<Mutex<ProcessState>>,
+    /// Wakes the task to re-read its state; dropping it stops the task
+    wake: WatchSender<u64>,
+    handle: Option<JoinHandle<()>>,
+}
+
+/// Runs and tracks the tasks behind process actions.
+pub struct ProcessSupervisor<R: Runtime> {
+    runtime: R,
+    processes: HashMap<String, ManagedProcess>,
+    jobs: HashMap<ProcessType, ProcessJob>,
+    heartbeat_timeout: Option<Duration>,
+    wakes: u64,
+}
+
+impl<R: Runtime> std::fmt::Debug for ProcessSupervisor<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProcessSupervisor")
+            .field("processes", &self.processes.len())
+            .field("jobs", &self.jobs.keys().collect::<Vec<_>>())
+            .field("heartbeat_timeout", &self.heartbeat_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R: Runtime> ProcessSupervisor<R> {
+    /// Create a supervisor spawning tasks on `runtime`.
+    pub fn new(runtime: R) -> Self {
+        Self {
+            runtime,
+            processes: HashMap::new(),
+            jobs: HashMap::new(),
+            heartbeat_timeout: None,
+            wakes: 0,
+        }
+    }
+
+    /// Set the job run by processes of `process_type`.
+    ///
+    /// Applies to processes spawned afterwards. A process whose type has no
+    /// job still keeps its schedule, heartbeat and run count.
+    pub fn set_job(&mut self, process_type: ProcessType, job: ProcessJob) {
+        self.jobs.insert(process_type, job);
+    }
+
+    /// Stop running processes that miss heartbeats for longer than `timeout`.
+    ///
+    /// Applies to processes spawned afterwards; `None` disables the check.
+    pub fn set_heartbeat_timeout(&mut self, timeout: Option<Duration>) {
+        self.heartbeat_timeout = timeout;
+    }
+
+    /// The heartbeat timeout for new processes.
+    pub fn heartbeat_timeout(&self) -> Option<Duration> {
+        self.heartbeat_timeout
+    }
+
+    /// Check that `action` can be carried out, without doing it.
+    pub fn check(&self, action: &ProcessAction) -> DeltaResult<()> {
+        match action {
+            ProcessAction::SpawnProcess { config, .. } => {
+                if config.interval_secs == 0 {
+                    return Err(DeltaError::InvalidData {
+                        reason: "Process interval must be greater than zero".to_string(),
+                    });
+                }
+                Ok(())
+            }
+            ProcessAction::PauseProcess { process_id }
+            | ProcessAction::ResumeProcess { process_id }
+            | ProcessAction::TerminateProcess { process_id }
+            | ProcessAction::Heartbeat { process_id } => self.live_process(process_id).map(|_| ()),
+            ProcessAction::GetStatus { process_id } => self.process(process_id).map(|_| ()),
+            ProcessAction::ListProcesses => Ok(()),
+        }
+    }
+
+    /// Carry out `action`. A spawned process gets the ID `spawn_id`.
+    ///
+    /// Queries (`GetStatus`, `ListProcesses`) change nothing; read the
+    /// result with [`status`](Self::status) or [`list`](Self::list).
+    pub fn apply(&mut self, action: &ProcessAction, spawn_id: &str) -> DeltaResult<()> {
+        self.check(action)?;
+        match action {
+            ProcessAction::SpawnProcess {
+                process_type,
+                config,
+            } => self.spawn(spawn_id, process_type.clone(), config.clone()),
+            ProcessAction::PauseProcess { process_id } => {
+                self.transition(process_id, |state, _| {
+                    state.info.status = ProcessStatus::Paused;
+                })
+            }
+            ProcessAction::ResumeProcess { process_id } => {
+                self.transition(process_id, |state, now| {
+                    state.info.status = ProcessStatus::Running;
+                    state.info.last_heartbeat = Utc::now();
+                    state.heartbeat_at = now;
+                })
+            }
+            ProcessAction::TerminateProcess { process_id } => {
+                self.transition(process_id, |state, _| {
+                    state.info.status = ProcessStatus::Terminated;
+                })
+            }
+            ProcessAction::Heartbeat { process_id } => self.transition(process_id, |state, now| {
+                state.info.last_heartbeat = Utc::now();
+                state.heartbeat_at = now;
+            }),
+            ProcessAction::GetStatus { .. } | ProcessAction::ListProcesses => Ok(()),
+        }
+    }
+
+    /// Current state of one process.
+    pub fn status(&self, process_id: &str) -> Option<ProcessInfo> {
+        self.processes
+            .get(process_id)
+            .map(|process| process.state.lock().unwrap().info.clone())
+    }
+
+    /// Current state of every process, oldest first.
+    pub fn list(&self) -> Vec<ProcessInfo> {
+        let mut infos: Vec<ProcessInfo> = self
+            .processes
+            .values()
+            .map(|process| process.state.lock().unwrap().info.clone())
+            .collect();
+        infos.sort_by(|a, b| a.spawned_at.cmp(&b.spawned_at).then(a.id.cmp(&b.id)));
+        infos
+    }
+
+    /// Terminate every live process and wait for its task to finish.
+    pub async fn shutdown(&mut self) {
+        let ids: Vec<String> = self.processes.keys().cloned().collect();
+        for id in ids {
+            let _ = self.transition(&id, |state, _| {
+                if state.info.status.is_live() {
+                    state.info.status = ProcessStatus::Terminated;
+                }
+            });
+        }
+        for process in self.processes.values_mut() {
+            if let Some(handle) = process.handle.take() {
+                handle.await;
+            }
+        }
+    }
+
+    fn spawn(
+        &mut self,
+        id: &str,
+        process_type: ProcessType,
+        config: ProcessConfig,
+    ) -> DeltaResult<()> {
+        if self.processes.contains_key(id) {
+            return Err(DeltaError::InvalidData {
+                reason: format!("Process already exists: {}", id),
+            });
+        }
+
+        let now = Utc::now();
+        let status = if config.auto_start {
+            ProcessStatus::Running
+        } else {
+            ProcessStatus::Paused
+        };
+        let job = self.jobs.get(&process_type).cloned();
+        let state = Arc::new(Mutex::new(ProcessState {
+            info: ProcessInfo {
+                id: id.to_string(),
+                process_type,
+                config,
+                status,
+                spawned_at: now,
+                last_heartbeat: now,
+                runs: 0,
+                last_run: None,
+            },
+            heartbeat_at: self.runtime.now(),
+        }));
+        let (wake, wake_rx) = self.runtime.watch_channel(self.wakes);
+
+        let handle = self.runtime.spawn(run_process(
+            self.runtime.clone(),
+            Arc::clone(&state),
+            wake_rx,
+            job,
+            self.heartbeat_timeout,
+        ));
+        self.processes.insert(
+            id.to_string(),
+            ManagedProcess {
+                state,
+                wake,
+                handle: Some(handle),
+            },
+        );
+        Ok(())
+    }
+
+    /// Update a process's state and wake its task to notice.
+    fn transition(
+        &mut self,
+        process_id: &str,
+        f: impl FnOnce(&mut ProcessState, Instant),
+    ) -> DeltaResult<()> {
+        self.wakes += 1;
+        let wakes = self.wakes;
+        let now = self.runtime.now();
+        let process = self.process(process_id)?;
+        f(&mut process.state.lock().unwrap(), now);
+        let _ = process.wake.send(wakes);
+        Ok(())
+    }
+
+    fn process(&self, process_id: &str) -> DeltaResult<&ManagedProcess> {
+        self.processes
+            .get(process_id)
+            .ok_or_else(|| DeltaError::InvalidData {
+                reason: format!("Unknown process: {}", process_id),
+            })
+    }
+
+    fn live_process(&self, process_id: &str) -> DeltaResult<&ManagedProcess> {
+        let process = self.process(process_id)?;
+        let status = process.state.lock().unwrap().info.status;
+        if !status.is_live() {
+            return Err(DeltaError::InvalidData {
+                reason: format!("Process {} is no longer running ({:?})", process_id, status),
+            });
+        }
+        Ok(process)
+    }
+}
+
+/// The loop driving one process.
+async fn run_process<R: Runtime>(
+    runtime: R,
+    state: Arc<Mutex<ProcessState>>,
+    mut wake: WatchReceiver<u64>,
+    job: Option<ProcessJob>,
+    heartbeat_timeout: Option<Duration>,
+) {
+    let config = state.lock().unwrap().info.config.clone();
+    let mut interval = runtime.interval(Duration::from_secs(config.interval_secs));
+
+    loop {
+        let (status, heartbeat_left) = {
+            let state = state.lock().unwrap();
+            let since_heartbeat = runtime.now().duration_since(state.heartbeat_at.clone());
+            let left = heartbeat_timeout.map(|timeout| timeout.saturating_sub(since_heartbeat));
+            (state.info.status, left)
+        };
+        if !status.is_live() {
+            break;
+        }
+        // Paused processes are not expected to heartbeat
+        let deadline = heartbeat_left.filter(|_| status == ProcessStatus::Running);
+        let expiry = async {
+            match deadline {
+                Some(left) => runtime.sleep(left).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        futures::select! {
+            _ = interval.tick().fuse() => {
+                if state.lock().unwrap().info.status != ProcessStatus::Running {
+                    continue;
+                }
+                if let Some(job) = &job {
+                    job(config.clone()).await;
+                }
+                let mut state = state.lock().unwrap();
+                state.info.runs += 1;
+                state.info.last_run = Some(Utc::now());
+            }
+            changed = wake.changed().fuse() => {
+                if changed.is_err() {
+                    break;
+                }
+                wake.borrow_and_update();
+            }
+            _ = expiry.fuse() => {
+                let mut state = state.lock().unwrap();
+                // Only time out if no heartbeat or pause landed meanwhile
+                let since_heartbeat = runtime.now().duration_since(state.heartbeat_at.clone());
+                if state.info.status == ProcessStatus::Running
+                    && heartbeat_timeout.is_some_and(|timeout| since_heartbeat >= timeout)
+                {
+                    tracing::warn!(process = %state.info.id, "Process missed its heartbeat");
+                    state.info.status = ProcessStatus::TimedOut;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::TestRuntime;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn spawn_action(interval_secs: u64) -> ProcessAction {
+        ProcessAction::SpawnProcess {
+            process_type: ProcessType::Consolidation,
+            config: ProcessConfig {
+                interval_secs,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_processes_run_pause_and_terminate() {
+        let runtime = TestRuntime::new();
+        let mut supervisor = ProcessSupervisor::new(runtime.clone());
+        let calls = Arc::new(AtomicU64::new(0));
+        let counter = Arc::clone(&calls);
+        supervisor.set_job(
+            ProcessType::Consolidation,
+            Arc::new(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async {}.boxed()
+            }),
+        );
+
+        supervisor.apply(&spawn_action(10), "p1").unwrap();
+        runtime.advance(Duration::from_secs(25));
+        // Ticks at 0s, 10s and 20s
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(supervisor.status("p1").unwrap().runs, 3);
+
+        supervisor
+            .apply(
+                &ProcessAction::PauseProcess {
+                    process_id: "p1".to_string(),
+                },
+                "",
+            )
+            .unwrap();
+        runtime.advance(Duration::from_secs(30));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            supervisor.status("p1").unwrap().status,
+            ProcessStatus::Paused
+        );
+
+        supervisor
+            .apply(
+                &ProcessAction::ResumeProcess {
+                    process_id: "p1".to_string(),
+                },
+                "",
+            )
+            .unwrap();
+        runtime.advance(Duration::from_secs(10));
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+        supervisor
+            .apply(
+                &ProcessAction::TerminateProcess {
+                    process_id: "p1".to_string(),
+                },
+                "",
+            )
+            .unwrap();
+        runtime.advance(Duration::from_secs(30));
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+        assert_eq!(runtime.pending_tasks(), 0);
+
+        let listed = supervisor.list();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].status, ProcessStatus::Terminated);
+
+        // Dead and unknown processes reject control actions
+        let pause = ProcessAction::PauseProcess {
+            process_id: "p1".to_string(),
+        };
+        assert!(supervisor.check(&pause).is_err());
+        let unknown = ProcessAction::Heartbeat {
+            process_id: "nope".to_string(),
+        };
+        assert!(supervisor.apply(&unknown, "").is_err());
+        assert!(supervisor.check(&spawn_action(0)).is_err());
+    }
+
+    #[test]
+    fn test_missed_heartbeat_stops_process() {
+        let runtime = TestRuntime::new();
+        let mut supervisor = ProcessSupervisor::new(runtime.clone());
+        supervisor.set_heartbeat_timeout(Some(Duration::from_secs(60)));
+        supervisor.apply(&spawn_action(3600), "p1").unwrap();
+        let heartbeat = ProcessAction::Heartbeat {
+            process_id: "p1".to_string(),
+        };
+
+        runtime.advance(Duration::from_secs(50));
+        supervisor.apply(&heartbeat, "").unwrap();
+        runtime.advance(Duration::from_secs(50));
+        assert_eq!(
+            supervisor.status("p1").unwrap().status,
+            ProcessStatus::Running
+        );
+
+        runtime.advance(Duration::from_secs(20));
+        assert_eq!(
+            supervisor.status("p1").unwrap().status,
+            ProcessStatus::TimedOut
+        );
+        assert_eq!(runtime.pending_tasks(), 0);
+        assert!(supervisor.check(&heartbeat).is_err());
+    }
+}
@@ -5,10 +5,16 @@
 ///
 /// - **Filtering**: Select records matching specific criteria
 /// - **Projection**: Select specific fields from documents
-/// - **Aggregation**: Compute statistics (count, sum, avg, min, max)
+/// - **Aggregation**: Compute statistics (count, sum, avg, min, max), or
+///   approximate ones (distinct count, percentiles, top values) from sketches
 /// - **Sorting**: Order results by field values
 /// - **Limiting**: Restrict the number of results
+/// - **Computed fields**: Derive fields with [`Expr`] expressions
 /// - **History queries**: Query across all versions of a key
+/// - **Timeouts and cancellation**: Stop long-running queries with a
+///   deadline or a [`CancellationToken`], optionally keeping partial results
+/// - **Resource limits**: Bound the records a query scans and holds with
+///   [`QueryLimits`]
 ///
 /// # Example
 ///
@@ -24,12 +30,18 @@
 ///
 /// let results = db.query("users", query).await?;
 /// ```
-use crate::error::DeltaResult;
+use crate::error::{DeltaError, DeltaResult};
+use crate::expr::Expr;
+use crate::sketch::ApproxAggregate;
 use crate::types::HistoryEntry;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, SecondsFormat, Timelike, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value as JsonValue};
 use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::time::Duration;
 
 /// A filter condition for querying data.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -61,6 +73,25 @@ pub enum Filter {
 }
 
 impl Filter {
+    /// Fields the filter reads, in dot notation.
+    pub fn fields(&self) -> Vec<&str> {
+        match self {
+            Filter::Eq { field, .. }
+            | Filter::Ne { field, .. }
+            | Filter::Gt { field, .. }
+            | Filter::Gte { field, .. }
+            | Filter::Lt { field, .. }
+            | Filter::Lte { field, .. }
+            | Filter::Contains { field, .. }
+            | Filter::Exists { field }
+            | Filter::Matches { field, .. } => vec![field.as_str()],
+            Filter::And(filters) | Filter::Or(filters) => {
+                filters.iter().flat_map(Filter::fields).collect()
+            }
+            Filter::Not(filter) => filter.fields(),
+        }
+    }
+
     /// Create an equality filter.
     pub fn eq(field: impl Into<String>, value: impl Into<JsonValue>) -> Self {
         Self::Eq {
@@ -226,9 +257,83 @@ pub enum Aggregation {
         field: String,
         aggregations: Vec<(String, Aggregation)>,
     },
+    /// Approximate count of distinct values of a field (HyperLogLog).
+    ApproxDistinct { field: String },
+    /// Approximate percentiles (0 to 100) of a numeric field (t-digest).
+    Percentiles {
+        field: String,
+        percentiles: Vec<f64>,
+    },
+    /// Approximate `k` most frequent values of a field (count-min sketch).
+    ApproxTopK { field: String, k: usize },
+    /// Roll up a numeric field per time bucket of the version timestamps.
+    ///
+    /// Produces `[{"bucket": <RFC 3339 start>, "count", "sum", "avg",
+    /// "min", "max"}, ...]` in time order, with empty buckets omitted.
+    /// Over current values each record falls in the bucket of its last
+    /// write; over history entries, in the bucket of that version.
+    TimeBucket {
+        field: String,
+        interval: BucketInterval,
+    },
+}
+
+/// Width of a [`Aggregation::TimeBucket`] bucket (UTC).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BucketInterval {
+    /// One minute.
+    Minute,
+    /// One hour.
+    Hour,
+    /// One day.
+    Day,
+    /// One ISO week, starting on Monday.
+    Week,
+    /// One calendar month.
+    Month,
+}
+
+impl BucketInterval {
+    /// Start of the bucket containing `time`.
+    pub fn truncate(self, time: DateTime<Utc>) -> DateTime<Utc> {
+        let date = time.date_naive();
+        let start = match self {
+            Self::Minute => date.and_hms_opt(time.hour(), time.minute(), 0),
+            Self::Hour => date.and_hms_opt(time.hour(), 0, 0),
+            Self::Day => date.and_hms_opt(0, 0, 0),
+            Self::Week => (date
+                - chrono::Duration::days(time.weekday().num_days_from_monday() as i64))
+            .and_hms_opt(0, 0, 0),
+            Self::Month => date.with_day(1).and_then(|d| d.and_hms_opt(0, 0, 0)),
+        };
+        start.map(|t| t.and_utc()).unwrap_or(time)
+    }
 }
 
 impl Aggregation {
+    /// Fields the aggregation reads, in dot notation.
+    pub fn fields(&self) -> Vec<&str> {
+        match self {
+            Aggregation::Count => Vec::new(),
+            Aggregation::Sum { field }
+            | Aggregation::Avg { field }
+            | Aggregation::Min { field }
+            | Aggregation::Max { field }
+            | Aggregation::Distinct { field }
+            | Aggregation::ApproxDistinct { field }
+            | Aggregation::Percentiles { field, .. }
+            | Aggregation::ApproxTopK { field, .. }
+            | Aggregation::TimeBucket { field, .. } => vec![field.as_str()],
+            Aggregation::GroupBy {
+                field,
+                aggregations,
+            } => std::iter::once(field.as_str())
+                .chain(aggregations.iter().flat_map(|(_, agg)| agg.fields()))
+                .collect(),
+        }
+    }
+
     /// Create a count aggregation.
     pub fn count() -> Self {
         Self::Count
@@ -268,6 +373,37 @@ impl Aggregation {
             field: field.into(),
         }
     }
+
+    /// Create an approximate distinct count aggregation.
+    pub fn approx_distinct(field: impl Into<String>) -> Self {
+        Self::ApproxDistinct {
+            field: field.into(),
+        }
+    }
+
+    /// Create an approximate percentiles aggregation, e.g. `&[50.0, 99.0]`.
+    pub fn percentiles(field: impl Into<String>, percentiles: &[f64]) -> Self {
+        Self::Percentiles {
+            field: field.into(),
+            percentiles: percentiles.to_vec(),
+        }
+    }
+
+    /// Create a time-bucketed rollup of a numeric field.
+    pub fn time_bucket(field: impl Into<String>, interval: BucketInterval) -> Self {
+        Self::TimeBucket {
+            field: field.into(),
+            interval,
+        }
+    }
+
+    /// Create an approximate most-frequent values aggregation.
+    pub fn approx_top_k(field: impl Into<String>, k: usize) -> Self {
+        Self::ApproxTopK {
+            field: field.into(),
+            k,
+        }
+    }
 }
 
 /// Sort direction.
@@ -310,12 +446,16 @@ impl SortBy {
 
 /// A query against KoruDelta data.
 ///
-/// Queries can filter, project, sort, and limit results.
+/// Queries can filter, project, sort, and limit results. Missing fields
+/// deserialize to their defaults, so `{}` is the empty query.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Query {
     /// Filter conditions.
     pub filters: Vec<Filter>,
     /// Fields to project (empty = all fields).
+    ///
+    /// Scans then copy only the fields the query uses; see [`FieldPushdown`].
     pub projection: Vec<String>,
     /// Sort specifications.
     pub sort: Vec<SortBy>,
@@ -325,6 +465,51 @@ pub struct Query {
     pub offset: Option<usize>,
     /// Aggregation to perform.
     pub aggregation: Option<Aggregation>,
+    /// Fields computed from each value before filtering.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub computed: Vec<ComputedField>,
+    /// Give up after this many milliseconds (enforced by
+    /// [`KoruDelta::query`](crate::KoruDelta::query); no limit if unset).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// On timeout or cancellation, return the records matched so far
+    /// (marked [`partial`](QueryResult::partial)) instead of failing.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub allow_partial: bool,
+    /// Bounds on the work the query may do.
+    #[serde(skip_serializing_if = "QueryLimits::is_unlimited")]
+    pub limits: QueryLimits,
+}
+
+/// A field whose value is computed from an [`Expr`].
+///
+/// Computed fields are added to object values before filters run, so
+/// filters, sorting, aggregation and projection can refer to them by name.
+/// Later fields can use earlier ones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComputedField {
+    /// Name of the field to set.
+    pub name: String,
+    /// Expression computing its value.
+    pub expr: Expr,
+}
+
+impl ComputedField {
+    /// Create a computed field.
+    pub fn new(name: impl Into<String>, expr: Expr) -> Self {
+        Self {
+            name: name.into(),
+            expr,
+        }
+    }
+
+    /// Set this field on `value` (objects only).
+    pub fn apply(&self, value: &mut JsonValue) {
+        let computed = self.expr.eval(value);
+        if let JsonValue::Object(map) = value {
+            map.insert(self.name.clone(), computed);
+        }
+    }
 }
 
 impl Query {
@@ -382,6 +567,50 @@ impl Query {
         self
     }
 
+    /// Add a computed field.
+    pub fn compute(mut self, name: impl Into<String>, expr: Expr) -> Self {
+        self.computed.push(ComputedField::new(name, expr));
+        self
+    }
+
+    /// Give up once the query has run for `timeout`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout_ms = Some(timeout.as_millis().try_into().unwrap_or(u64::MAX));
+        self
+    }
+
+    /// Return partial results instead of failing on timeout,
+    /// cancellation or an exceeded limit.
+    pub fn allow_partial(mut self, allowed: bool) -> Self {
+        self.allow_partial = allowed;
+        self
+    }
+
+    /// Stop after scanning `records` records.
+    pub fn max_scanned(mut self, records: usize) -> Self {
+        self.limits.max_scanned = records;
+        self
+    }
+
+    /// Stop once the matched records take more than `bytes` as JSON.
+    pub fn max_memory(mut self, bytes: usize) -> Self {
+        self.limits.max_memory = bytes;
+        self
+    }
+
+    /// Stop after matching `records` records, before sorting and paging.
+    pub fn max_rows(mut self, records: usize) -> Self {
+        self.limits.max_rows = records;
+        self
+    }
+
+    /// Add the computed fields to a value.
+    pub fn apply_computed(&self, value: &mut JsonValue) {
+        for field in &self.computed {
+            field.apply(value);
+        }
+    }
+
     /// Check if a value matches all filters.
     pub fn matches(&self, value: &JsonValue) -> bool {
         self.filters.iter().all(|f| f.matches_value(value))
@@ -412,6 +641,227 @@ pub struct QueryResult {
     pub total_count: usize,
     /// Aggregation result (if aggregation was requested).
     pub aggregation: Option<JsonValue>,
+    /// Whether the query stopped early, so the result only covers the
+    /// records visited before it did.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub partial: bool,
+}
+
+/// Cancels running queries from another task or thread.
+///
+/// Clones share their state: cancelling one cancels every query running
+/// with any of them.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Create a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop every query running with this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, AtomicOrdering::SeqCst);
+    }
+
+    /// Whether [`cancel`](Self::cancel) was called.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(AtomicOrdering::SeqCst)
+    }
+}
+
+/// Bounds on the work one query may do (0 = unlimited).
+///
+/// Sorting, paging and aggregation happen after every match is collected, so
+/// a `limit` alone doesn't bound a query: these do. They are enforced by
+/// [`QueryExecutor`] as records are visited, and a query that exceeds one
+/// fails with [`DeltaError::QueryLimitExceeded`] unless it
+/// [allows partial results](Query::allow_partial).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueryLimits {
+    /// Maximum records scanned
+    pub max_scanned: usize,
+    /// Maximum total size of the matched records, as JSON, in bytes
+    pub max_memory: usize,
+    /// Maximum records matched
+    pub max_rows: usize,
+}
+
+impl QueryLimits {
+    /// Whether no limit is set.
+    pub fn is_unlimited(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The tighter of each limit in `self` and `other`.
+    pub fn within(self, other: QueryLimits) -> Self {
+        fn tighter(a: usize, b: usize) -> usize {
+            match (a, b) {
+                (0, limit) | (limit, 0) => limit,
+                (a, b) => a.min(b),
+            }
+        }
+        Self {
+            max_scanned: tighter(self.max_scanned, other.max_scanned),
+            max_memory: tighter(self.max_memory, other.max_memory),
+            max_rows: tighter(self.max_rows, other.max_rows),
+        }
+    }
+
+    /// Fail if a query that has scanned `scanned` records and matched
+    /// `rows` totalling `memory` bytes is over a limit.
+    fn check(&self, scanned: usize, rows: usize, memory: usize) -> DeltaResult<()> {
+        let exceeded = |limit: &str, max: usize| {
+            Err(DeltaError::QueryLimitExceeded {
+                limit: limit.to_string(),
+                max,
+            })
+        };
+        if self.max_scanned > 0 && scanned > self.max_scanned {
+            return exceeded("records scanned", self.max_scanned);
+        }
+        if self.max_rows > 0 && rows > self.max_rows {
+            return exceeded("records matched", self.max_rows);
+        }
+        if self.max_memory > 0 && memory > self.max_memory {
+            return exceeded("bytes of matched records", self.max_memory);
+        }
+        Ok(())
+    }
+}
+
+/// Number of records a query visits between checks of its [`QueryControl`].
+const CHECK_INTERVAL: usize = 256;
+
+/// When a running query must stop: a cancelled token or a passed deadline.
+///
+/// [`QueryExecutor::execute_with`] checks it every few hundred records. A
+/// stopped query fails with [`DeltaError::QueryCancelled`] or
+/// [`DeltaError::QueryTimeout`], unless it
+/// [allows partial results](Query::allow_partial).
+#[derive(Clone, Default)]
+pub struct QueryControl {
+    token: Option<CancellationToken>,
+    deadline: Option<(Duration, Arc<dyn Fn() -> bool + Send + Sync>)>,
+}
+
+impl std::fmt::Debug for QueryControl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueryControl")
+            .field("token", &self.token)
+            .field(
+                "timeout",
+                &self.deadline.as_ref().map(|(timeout, _)| timeout),
+            )
+            .finish()
+    }
+}
+
+impl QueryControl {
+    /// A control that never stops the query.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop when `token` is cancelled.
+    pub fn with_token(mut self, token: CancellationToken) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Stop once `expired` returns true. `timeout` is only reported in the
+    /// error; `expired` decides, so callers can use whatever clock they
+    /// run on.
+    pub fn with_deadline(
+        mut self,
+        timeout: Duration,
+        expired: impl Fn() -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.deadline = Some((timeout, Arc::new(expired)));
+        self
+    }
+
+    /// Stop `timeout` from now, by the system clock.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        let start = std::time::Instant::now();
+        self.with_deadline(timeout, move || start.elapsed() >= timeout)
+    }
+
+    /// Fail if the query must stop.
+    pub fn check(&self) -> DeltaResult<()> {
+        if self
+            .token
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            return Err(DeltaError::QueryCancelled);
+        }
+        if let Some((timeout, expired)) = &self.deadline
+            && expired()
+        {
+            return Err(DeltaError::QueryTimeout {
+                timeout_ms: timeout.as_millis().try_into().unwrap_or(u64::MAX),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Projection pushdown: reads only the top-level fields a query needs.
+///
+/// A query with a projection never looks at the rest of a value, so
+/// scans copy just the fields its filters, computed fields, sort keys,
+/// aggregation and projection refer to instead of whole documents. Without
+/// a projection, values are read whole.
+#[derive(Debug, Clone, Default)]
+pub struct FieldPushdown {
+    fields: Option<BTreeSet<String>>,
+}
+
+impl FieldPushdown {
+    /// Pushdown for `query`.
+    pub fn new(query: &Query) -> Self {
+        if query.projection.is_empty() {
+            return Self::default();
+        }
+        let paths = query
+            .projection
+            .iter()
+            .map(String::as_str)
+            .chain(query.filters.iter().flat_map(Filter::fields))
+            .chain(query.computed.iter().flat_map(|c| c.expr.fields()))
+            .chain(query.sort.iter().map(|s| s.field.as_str()))
+            .chain(query.aggregation.iter().flat_map(Aggregation::fields));
+        let fields = paths
+            .map(|path| path.split('.').next().unwrap_or(path).to_string())
+            .collect();
+        Self {
+            fields: Some(fields),
+        }
+    }
+
+    /// Top-level fields read, or `None` if values are read whole.
+    pub fn fields(&self) -> Option<&BTreeSet<String>> {
+        self.fields.as_ref()
+    }
+
+    /// Copy what the query needs of a stored value.
+    pub fn read(&self, value: &JsonValue) -> JsonValue {
+        match (&self.fields, value) {
+            (Some(fields), JsonValue::Object(map)) => JsonValue::Object(
+                fields
+                    .iter()
+                    .filter_map(|field| Some((field.clone(), map.get(field)?.clone())))
+                    .collect(),
+            ),
+            _ => value.clone(),
+        }
+    }
 }
 
 /// A single record in query results.
@@ -427,6 +877,28 @@ pub struct QueryRecord {
     pub version_id: String,
 }
 
+/// A change of a key's value, from one version to the next.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValueChange {
+    /// When the new value was written.
+    pub timestamp: DateTime<Utc>,
+    /// Version ID of the new value.
+    pub version_id: String,
+    /// Value before the change (`null` when the key was created).
+    pub old_value: JsonValue,
+    /// Value after the change (`null` when the key was deleted).
+    pub new_value: JsonValue,
+}
+
+/// The changes of one key, as returned by change queries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyChanges {
+    /// The key.
+    pub key: String,
+    /// Its changes, oldest first.
+    pub changes: Vec<ValueChange>,
+}
+
 /// A history query for querying across versions.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct HistoryQuery {
@@ -497,15 +969,57 @@ impl QueryExecutor {
     where
         I: Iterator<Item = (String, JsonValue, DateTime<Utc>, String)>,
     {
-        let mut records: Vec<QueryRecord> = items
-            .filter(|(_, value, _, _)| query.matches(value))
-            .map(|(key, value, timestamp, version_id)| QueryRecord {
-                key,
-                value: query.apply_projection(&value),
-                timestamp,
-                version_id,
-            })
-            .collect();
+        Self::execute_with(query, items, &QueryControl::default())
+    }
+
+    /// Execute a query, stopping early when `control` says so or the
+    /// query's [`limits`](Query::limits) are exceeded.
+    ///
+    /// A stopped query fails, or, if it allows partial results, sorts,
+    /// pages and aggregates the records matched so far.
+    pub fn execute_with<I>(
+        query: &Query,
+        items: I,
+        control: &QueryControl,
+    ) -> DeltaResult<QueryResult>
+    where
+        I: Iterator<Item = (String, JsonValue, DateTime<Utc>, String)>,
+    {
+        let limits = query.limits;
+        let mut records = Vec::new();
+        let mut memory = 0;
+        let mut partial = false;
+        for (visited, (key, mut value, timestamp, version_id)) in items.enumerate() {
+            let stopped = if visited % CHECK_INTERVAL == 0 {
+                control.check()
+            } else {
+                Ok(())
+            };
+            query.apply_computed(&mut value);
+            let matched = query.matches(&value);
+            let size = if matched && limits.max_memory > 0 {
+                key.len() + serde_json::to_vec(&value).map_or(0, |bytes| bytes.len())
+            } else {
+                0
+            };
+            let rows = records.len() + usize::from(matched);
+            if let Err(e) = stopped.and_then(|()| limits.check(visited + 1, rows, memory + size)) {
+                if !query.allow_partial {
+                    return Err(e);
+                }
+                partial = true;
+                break;
+            }
+            if matched {
+                memory += size;
+                records.push(QueryRecord {
+                    key,
+                    value,
+                    timestamp,
+                    version_id,
+                });
+            }
+        }
 
         let total_count = records.len();
 
@@ -552,13 +1066,72 @@ impl QueryExecutor {
             .as_ref()
             .map(|agg| compute_aggregation(agg, &records));
 
+        // Project last so sorting and aggregation see the full values.
+        if !query.projection.is_empty() {
+            for record in &mut records {
+                record.value = query.apply_projection(&record.value);
+            }
+        }
+
         Ok(QueryResult {
             records,
             total_count,
             aggregation,
+            partial,
         })
     }
 
+    /// Compute `aggregation` over history entries.
+    pub fn aggregate_history(aggregation: &Aggregation, history: &[HistoryEntry]) -> JsonValue {
+        let records: Vec<QueryRecord> = history
+            .iter()
+            .map(|entry| QueryRecord {
+                key: String::new(),
+                value: entry.value.clone(),
+                timestamp: entry.timestamp,
+                version_id: entry.version_id.clone(),
+            })
+            .collect();
+        compute_aggregation(aggregation, &records)
+    }
+
+    /// Turn a key's history into the changes selected by a history query.
+    ///
+    /// Each version is paired with the one before it, even when that one
+    /// falls outside the time window; writes that leave the value unchanged
+    /// are skipped. A change is kept when its old or new value matches the
+    /// base query's filters, so filtering on `status = "active"` finds keys
+    /// entering or leaving that state. `latest` keeps the last N changes.
+    pub fn execute_changes(query: &HistoryQuery, history: &[HistoryEntry]) -> Vec<ValueChange> {
+        let mut previous = &JsonValue::Null;
+        let mut changes = Vec::new();
+        for entry in history {
+            let old_value = std::mem::replace(&mut previous, &entry.value);
+            if *old_value == entry.value {
+                continue;
+            }
+            let in_window = query.from_time.is_none_or(|from| entry.timestamp >= from)
+                && query.to_time.is_none_or(|to| entry.timestamp <= to);
+            let matches = query.query.filters.is_empty()
+                || query.query.matches(old_value)
+                || query.query.matches(&entry.value);
+            if in_window && matches {
+                changes.push(ValueChange {
+                    timestamp: entry.timestamp,
+                    version_id: entry.version_id.clone(),
+                    old_value: old_value.clone(),
+                    new_value: entry.value.clone(),
+                });
+            }
+        }
+
+        if let Some(latest) = query.latest {
+            let start = changes.len().saturating_sub(latest);
+            changes.drain(..start);
+        }
+        changes
+    }
+
     /// Execute a history query.
     pub fn execute_history(
         query: &HistoryQuery,
@@ -614,7 +1187,12 @@ impl QueryExecutor {
 }
 
 /// Get a field from a JSON value using dot notation.
-fn get_field(value: &JsonValue, field: &str) -> Option<JsonValue> {
+pub(crate) fn get_field(value: &JsonValue, field: &str) -> Option<JsonValue> {
+    field_ref(value, field).cloned()
+}
+
+/// Borrow a field value from a JSON object using dot notation.
+pub(crate) fn field_ref<'a>(value: &'a JsonValue, field: &str) -> Option<&'a JsonValue> {
     let mut current = value;
     for part in field.split('.') {
         match current {
@@ -628,7 +1206,7 @@ fn get_field(value: &JsonValue, field: &str) -> Option<JsonValue> {
             _ => return None,
         }
     }
-    Some(current.clone())
+    Some(current)
 }
 
 /// Compare two JSON values.
@@ -749,6 +1327,40 @@ fn compute_aggregation(agg: &Aggregation, records: &[QueryRecord]) -> JsonValue
 
             JsonValue::Object(result)
         }
+        Aggregation::ApproxDistinct { .. }
+        | Aggregation::Percentiles { .. }
+        | Aggregation::ApproxTopK { .. } => {
+            let mut sketch = ApproxAggregate::new(agg).expect("approximate aggregation");
+            for record in records {
+                sketch.add(&record.value);
+            }
+            sketch.result()
+        }
+        Aggregation::TimeBucket { field, interval } => {
+            let mut buckets: BTreeMap<DateTime<Utc>, Vec<QueryRecord>> = BTreeMap::new();
+            for record in records {
+                buckets
+                    .entry(interval.truncate(record.timestamp))
+                    .or_default()
+                    .push(record.clone());
+            }
+
+            let rollups = buckets
+                .into_iter()
+                .map(|(start, bucket)| {
+                    let field = field.clone();
+                    serde_json::json!({
+                        "bucket": start.to_rfc3339_opts(SecondsFormat::Secs, true),
+                        "count": bucket.len(),
+                        "sum": compute_aggregation(&Aggregation::Sum { field: field.clone() }, &bucket),
+                        "avg": compute_aggregation(&Aggregation::Avg { field: field.clone() }, &bucket),
+                        "min": compute_aggregation(&Aggregation::Min { field: field.clone() }, &bucket),
+                        "max": compute_aggregation(&Aggregation::Max { field }, &bucket),
+                    })
+                })
+                .collect();
+            JsonValue::Array(rollups)
+        }
     }
 }
 
@@ -826,6 +1438,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_query_computed_fields() {
+        let query = Query::new()
+            .compute("total", Expr::parse("price * qty").unwrap())
+            .filter(Filter::gte("total", json!(20)))
+            .sort_by("total", false)
+            .project(&["total"]);
+
+        let items = [("a", 5, 2), ("b", 10, 3), ("c", 7, 4)].map(|(key, price, qty)| {
+            (
+                key.to_string(),
+                json!({"price": price, "qty": qty}),
+                Utc::now(),
+                format!("v-{}", key),
+            )
+        });
+
+        let result = QueryExecutor::execute(&query, items.into_iter()).unwrap();
+        let totals: Vec<_> = result.records.iter().map(|r| r.value.clone()).collect();
+        assert_eq!(totals, vec![json!({"total": 30}), json!({"total": 28})]);
+
+        // Expressions serialize as their source text
+        let json = serde_json::to_value(&query).unwrap();
+        assert_eq!(json["computed"][0]["expr"], json!("price * qty"));
+        let back: Query = serde_json::from_value(json).unwrap();
+        assert_eq!(back.computed, query.computed);
+    }
+
     #[test]
     fn test_query_execution() {
         let query = Query::new()
@@ -868,6 +1508,225 @@ mod tests {
         assert_eq!(result.records[1].key, "alice"); // Age 30
     }
 
+    #[test]
+    fn test_field_pushdown() {
+        let document = json!({
+            "name": "Ada",
+            "age": 36,
+            "address": {"city": "London", "zip": "N1"},
+            "body": "a large field nobody asked for",
+        });
+
+        let whole = FieldPushdown::new(&Query::new().filter(Filter::gt("age", 30)));
+        assert_eq!(whole.fields(), None);
+        assert_eq!(whole.read(&document), document);
+
+        let query = Query::new()
+            .filter(Filter::gt("age", 30))
+            .sort_by("address.city", true)
+            .project(&["name"]);
+        let pushdown = FieldPushdown::new(&query);
+        assert_eq!(
+            pushdown.read(&document),
+            json!({"name": "Ada", "age": 36, "address": {"city": "London", "zip": "N1"}})
+        );
+
+        // Results match reading whole values
+        let item = |value: JsonValue| ("k".to_string(), value, Utc::now(), "v".to_string());
+        let pruned =
+            QueryExecutor::execute(&query, std::iter::once(item(pushdown.read(&document))))
+                .unwrap();
+        let full = QueryExecutor::execute(&query, std::iter::once(item(document))).unwrap();
+        assert_eq!(pruned.records[0].value, full.records[0].value);
+        assert_eq!(pruned.records[0].value, json!({"name": "Ada"}));
+    }
+
+    #[test]
+    fn test_query_stops_on_cancel_and_timeout() {
+        let items = || {
+            (0..1000).map(|i| {
+                (
+                    format!("k{:04}", i),
+                    json!({"n": i}),
+                    Utc::now(),
+                    format!("v{}", i),
+                )
+            })
+        };
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let control = QueryControl::new().with_token(token);
+        let err = QueryExecutor::execute_with(&Query::new(), items(), &control).unwrap_err();
+        assert!(matches!(err, DeltaError::QueryCancelled));
+
+        // The deadline passes after the first check, so one batch is seen
+        let checks = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = Arc::clone(&checks);
+        let control = QueryControl::new().with_deadline(Duration::from_millis(5), move || {
+            counter.fetch_add(1, AtomicOrdering::SeqCst) > 0
+        });
+        let err = QueryExecutor::execute_with(&Query::new(), items(), &control).unwrap_err();
+        assert!(matches!(err, DeltaError::QueryTimeout { timeout_ms: 5 }));
+
+        checks.store(0, AtomicOrdering::SeqCst);
+        let query = Query::new()
+            .allow_partial(true)
+            .sort_by("n", false)
+            .limit(1);
+        let result = QueryExecutor::execute_with(&query, items(), &control).unwrap();
+        assert!(result.partial);
+        assert_eq!(result.total_count, CHECK_INTERVAL);
+        assert_eq!(result.records[0].value, json!({"n": CHECK_INTERVAL - 1}));
+
+        let result = QueryExecutor::execute(&query, items()).unwrap();
+        assert!(!result.partial);
+        assert_eq!(result.total_count, 1000);
+    }
+
+    #[test]
+    fn test_query_limits() {
+        let items = || {
+            (0..100).map(|i| {
+                (
+                    format!("k{:02}", i),
+                    json!({"n": i}),
+                    Utc::now(),
+                    format!("v{}", i),
+                )
+            })
+        };
+
+        let query = Query::new()
+            .filter(Filter::lt("n", json!(10)))
+            .max_scanned(50);
+        let err = QueryExecutor::execute(&query, items()).unwrap_err();
+        assert!(matches!(
+            err,
+            DeltaError::QueryLimitExceeded { ref limit, max: 50 } if limit == "records scanned"
+        ));
+
+        // Limits are checked against matches, not the page
+        let query = Query::new().max_rows(20).limit(5);
+        let err = QueryExecutor::execute(&query, items()).unwrap_err();
+        assert!(matches!(
+            err,
+            DeltaError::QueryLimitExceeded { max: 20, .. }
+        ));
+
+        // Each record is `k00` plus `{"n":0}`, 10 bytes
+        let query = Query::new().max_memory(105).allow_partial(true);
+        let result = QueryExecutor::execute(&query, items()).unwrap();
+        assert!(result.partial);
+        assert_eq!(result.total_count, 10);
+
+        let query = Query::new().filter(Filter::lt("n", json!(10))).max_rows(10);
+        let result = QueryExecutor::execute(&query, items()).unwrap();
+        assert!(!result.partial);
+        assert_eq!(result.total_count, 10);
+
+        // The tighter limit wins, and zero means unlimited
+        let limits = QueryLimits {
+            max_scanned: 10,
+            max_rows: 0,
+            max_memory: 500,
+        }
+        .within(QueryLimits {
+            max_scanned: 20,
+            max_rows: 5,
+            max_memory: 0,
+        });
+        assert_eq!(
+            limits,
+            QueryLimits {
+                max_scanned: 10,
+                max_rows: 5,
+                max_memory: 500,
+            }
+        );
+    }
+
+    #[test]
+    fn test_execute_changes() {
+        let at = |hour: u32| {
+            DateTime::parse_from_rfc3339(&format!("2024-03-14T{:02}:00:00Z", hour))
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        let history: Vec<HistoryEntry> = [
+            (json!({"status": "trial"}), 1),
+            (json!({"status": "active"}), 2),
+            (json!({"status": "active"}), 3),
+            (json!({"status": "cancelled"}), 4),
+            (JsonValue::Null, 5),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, (value, hour))| HistoryEntry::new(value, at(hour), format!("v{}", i)))
+        .collect();
+
+        // Unchanged rewrites are skipped; the first change starts from null
+        let all = QueryExecutor::execute_changes(&HistoryQuery::new(), &history);
+        assert_eq!(all.len(), 4);
+        assert_eq!(all[0].old_value, JsonValue::Null);
+        assert_eq!(all[3].new_value, JsonValue::Null);
+
+        // Entering or leaving "active", with the old value from before the window
+        let query = HistoryQuery::new()
+            .with_query(Query::new().filter(Filter::eq("status", json!("active"))))
+            .from(at(3));
+        let churn = QueryExecutor::execute_changes(&query, &history);
+        assert_eq!(churn.len(), 1);
+        assert_eq!(churn[0].old_value, json!({"status": "active"}));
+        assert_eq!(churn[0].new_value, json!({"status": "cancelled"}));
+        assert_eq!(churn[0].version_id, "v3");
+
+        let last = QueryExecutor::execute_changes(&HistoryQuery::new().latest(1), &history);
+        assert_eq!(last[0].version_id, "v4");
+    }
+
+    #[test]
+    fn test_aggregation_time_bucket() {
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        let time = at("2024-03-14T13:45:12Z");
+        assert_eq!(
+            BucketInterval::Hour.truncate(time),
+            at("2024-03-14T13:00:00Z")
+        );
+        assert_eq!(
+            BucketInterval::Week.truncate(time),
+            at("2024-03-11T00:00:00Z")
+        );
+        assert_eq!(
+            BucketInterval::Month.truncate(time),
+            at("2024-03-01T00:00:00Z")
+        );
+
+        let query = Query::new().aggregate(Aggregation::time_bucket("ms", BucketInterval::Day));
+        let items = [
+            ("a", 10, "2024-03-15T09:00:00Z"),
+            ("b", 30, "2024-03-14T23:59:59Z"),
+            ("c", 20, "2024-03-15T18:30:00Z"),
+        ]
+        .map(|(key, ms, time)| {
+            (
+                key.to_string(),
+                json!({"ms": ms}),
+                at(time),
+                format!("v-{}", key),
+            )
+        });
+
+        let result = QueryExecutor::execute(&query, items.into_iter()).unwrap();
+        assert_eq!(
+            result.aggregation.unwrap(),
+            json!([
+                {"bucket": "2024-03-14T00:00:00Z", "count": 1, "sum": 30.0, "avg": 30.0, "min": 30, "max": 30},
+                {"bucket": "2024-03-15T00:00:00Z", "count": 2, "sum": 30.0, "avg": 15.0, "min": 10, "max": 20},
+            ])
+        );
+    }
+
     #[test]
     fn test_aggregation_count() {
         let query = Query::new().aggregate(Aggregation::count());
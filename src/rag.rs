@@ -0,0 +1,234 @@
+//! Retrieval-augmented generation helpers.
+//!
+//! [`KoruDelta::store_document`](crate::KoruDelta::store_document) splits a
+//! text into overlapping chunks, embeds every chunk with the configured
+//! [`EmbeddingProvider`](crate::vector::EmbeddingProvider), and stores each
+//! chunk with a provenance link back to the exact version of the source
+//! document it came from. [`KoruDelta::retrieve`](crate::KoruDelta::retrieve)
+//! finds the chunks most similar to a query and reports where they came from
+//! and whether the source has changed since.
+//!
+//! Source documents live in the namespace they are stored under; their chunks
+//! live in a companion internal namespace (see [`chunk_namespace`]).
+//!
+//! # Example
+//!
+//! ```ignore
+//! use koru_delta::rag::ChunkConfig;
+//!
+//! db.store_document("manuals", "pump", manual_text, ChunkConfig::default()).await?;
+//!
+//! for chunk in db.retrieve("how do I prime the pump?", 3).await? {
+//!     println!("{}#{} ({:.2}): {}", chunk.source_key, chunk.chunk_index, chunk.score, chunk.text);
+//! }
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Prefix of the internal namespaces holding document chunks.
+pub const CHUNK_NAMESPACE_PREFIX: &str = "__rag_chunks__";
+
+/// Namespace holding the chunks of documents stored in `namespace`.
+pub fn chunk_namespace(namespace: &str) -> String {
+    format!("{}{}", CHUNK_NAMESPACE_PREFIX, namespace)
+}
+
+/// Source namespace for a chunk namespace, if it is one.
+pub fn source_namespace(chunk_namespace: &str) -> Option<&str> {
+    chunk_namespace.strip_prefix(CHUNK_NAMESPACE_PREFIX)
+}
+
+/// Key of the `index`-th chunk of document `key`.
+pub fn chunk_key(key: &str, index: usize) -> String {
+    format!("{}#{}", key, index)
+}
+
+/// How documents are split into chunks.
+///
+/// Sizes are measured in characters. Chunks break on whitespace, so a chunk
+/// only exceeds `chunk_size` when a single word is longer than that.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkConfig {
+    /// Target maximum characters per chunk
+    pub chunk_size: usize,
+    /// Characters repeated from the end of one chunk at the start of the next
+    pub overlap: usize,
+}
+
+impl Default for ChunkConfig {
+    fn default() -> Self {
+        Self {
+            chunk_size: 1000,
+            overlap: 200,
+        }
+    }
+}
+
+impl ChunkConfig {
+    /// Create a configuration with the given chunk size and no overlap.
+    pub fn new(chunk_size: usize) -> Self {
+        Self {
+            chunk_size: chunk_size.max(1),
+            overlap: 0,
+        }
+    }
+
+    /// Set the overlap between consecutive chunks.
+    pub fn with_overlap(mut self, overlap: usize) -> Self {
+        self.overlap = overlap;
+        self
+    }
+}
+
+/// A span of a document produced by [`chunk_text`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextChunk {
+    /// Position of the chunk in the document
+    pub index: usize,
+    /// Byte offset where the chunk starts in the document
+    pub start: usize,
+    /// Byte offset where the chunk ends in the document
+    pub end: usize,
+    /// The chunk text
+    pub text: String,
+}
+
+/// Split text into overlapping, whitespace-aligned chunks.
+pub fn chunk_text(text: &str, config: &ChunkConfig) -> Vec<TextChunk> {
+    // (byte start, byte end, char offset of start, char offset of end)
+    let mut words = Vec::new();
+    let mut word_start = None;
+    for (chars, (byte, c)) in text.char_indices().enumerate() {
+        match (c.is_whitespace(), word_start) {
+            (false, None) => word_start = Some((byte, chars)),
+            (true, Some((start, start_chars))) => {
+                words.push((start, byte, start_chars, chars));
+                word_start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some((start, start_chars)) = word_start {
+        words.push((start, text.len(), start_chars, text.chars().count()));
+    }
+
+    let chunk_size = config.chunk_size.max(1);
+    let overlap = config.overlap.min(chunk_size - 1);
+    let mut chunks = Vec::new();
+    let mut first = 0;
+
+    while first < words.len() {
+        // Extend the chunk while it fits (always take at least one word)
+        let mut last = first;
+        while last + 1 < words.len() && words[last + 1].3 - words[first].2 <= chunk_size {
+            last += 1;
+        }
+
+        let (start, end) = (words[first].0, words[last].1);
+        chunks.push(TextChunk {
+            index: chunks.len(),
+            start,
+            end,
+            text: text[start..end].to_string(),
+        });
+
+        if last + 1 == words.len() {
+            break;
+        }
+
+        // Step back over words that fall within the overlap, always advancing
+        let overlap_from = words[last].3.saturating_sub(overlap);
+        let mut next = last + 1;
+        while next > first + 1 && words[next - 1].2 >= overlap_from {
+            next -= 1;
+        }
+        first = next;
+    }
+
+    chunks
+}
+
+/// Summary of a document stored with [`store_document`](crate::KoruDelta::store_document).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredDocument {
+    /// Namespace of the source document
+    pub namespace: String,
+    /// Key of the source document
+    pub key: String,
+    /// Version of the source document the chunks were cut from
+    pub version_id: String,
+    /// Number of chunks stored
+    pub chunks: usize,
+}
+
+/// A chunk returned by [`retrieve`](crate::KoruDelta::retrieve).
+#[derive(Debug, Clone)]
+pub struct RetrievedChunk {
+    /// Namespace of the source document
+    pub namespace: String,
+    /// Key of the source document
+    pub source_key: String,
+    /// Position of the chunk in the source document
+    pub chunk_index: usize,
+    /// The chunk text
+    pub text: String,
+    /// Similarity to the query (higher = more similar)
+    pub score: f32,
+    /// Version of the source document the chunk was cut from
+    pub source_version: String,
+    /// When the chunk was stored
+    pub stored_at: DateTime<Utc>,
+    /// Number of versions in the source document's history
+    pub revisions: usize,
+    /// Whether `source_version` is still the document's current version
+    pub is_current: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_text_respects_size() {
+        let text = "one two three four five six seven eight nine ten";
+        let chunks = chunk_text(text, &ChunkConfig::new(14));
+
+        assert!(chunks.iter().all(|c| c.text.chars().count() <= 14));
+        assert_eq!(chunks[0].text, "one two three");
+        assert_eq!(chunks.last().unwrap().text, "nine ten");
+        for chunk in &chunks {
+            assert_eq!(&text[chunk.start..chunk.end], chunk.text);
+        }
+        assert!(chunks.iter().enumerate().all(|(i, c)| c.index == i));
+    }
+
+    #[test]
+    fn test_chunk_text_overlap() {
+        let text = "alpha beta gamma delta epsilon zeta eta theta";
+        let chunks = chunk_text(text, &ChunkConfig::new(16).with_overlap(6));
+
+        assert_eq!(chunks[0].text, "alpha beta gamma");
+        assert_eq!(chunks[1].text, "gamma delta");
+        assert!(chunks.last().unwrap().text.ends_with("theta"));
+    }
+
+    #[test]
+    fn test_chunk_text_long_word_and_empty() {
+        let chunks = chunk_text("tiny supercalifragilistic end", &ChunkConfig::new(5));
+        assert_eq!(
+            chunks.iter().map(|c| c.text.as_str()).collect::<Vec<_>>(),
+            vec!["tiny", "supercalifragilistic", "end"]
+        );
+
+        assert!(chunk_text("   \n ", &ChunkConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn test_chunk_namespace_roundtrip() {
+        let ns = chunk_namespace("manuals");
+        assert_eq!(source_namespace(&ns), Some("manuals"));
+        assert_eq!(source_namespace("manuals"), None);
+        assert_eq!(chunk_key("pump", 3), "pump#3");
+    }
+}
@@ -0,0 +1,557 @@
+/// Ordered indexes over numeric fields.
+///
+/// A [`RangeIndex`] keeps every key of a namespace whose value has a numeric
+/// `field`, ordered by that number. Storage updates it while a write holds
+/// the key's entry, so it is never behind the current values. It answers
+/// range scans, top-N and percentile questions without decoding a single
+/// value, and [`query`](crate::KoruDelta::query) uses it to serve sorted,
+/// limited queries without scanning the namespace.
+///
+/// Order statistics walk the index from whichever end is nearer, skipping
+/// whole runs of keys that share a value.
+///
+/// # Example
+///
+/// ```ignore
+/// let index = db.create_range_index("orders", "total").await?;
+/// let big = db.range_index("orders", "total").unwrap().range(100.0..);
+/// let p99 = db.range_index("orders", "total").unwrap().percentile(99.0);
+/// ```
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::ops::{Bound, RangeBounds};
+use std::sync::{Arc, RwLock};
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::query::{Filter, Query, SortOrder, field_ref};
+
+/// Namespace for persisting range index definitions.
+pub const RANGE_INDEX_NAMESPACE: &str = "__range_indexes";
+
+/// An indexed number, totally ordered.
+#[derive(Debug, Clone, Copy)]
+struct Number(f64);
+
+impl Number {
+    fn new(value: f64) -> Self {
+        // -0.0 and 0.0 compare equal in queries, so they share an entry
+        Self(if value == 0.0 { 0.0 } else { value })
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Number {}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Number {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Whether `(lower, upper)` selects nothing. `BTreeMap::range` panics on
+/// such ranges instead.
+fn is_empty_range(lower: &Bound<Number>, upper: &Bound<Number>) -> bool {
+    match (lower, upper) {
+        (Bound::Included(l), Bound::Included(u)) => l > u,
+        (Bound::Included(l) | Bound::Excluded(l), Bound::Excluded(u))
+        | (Bound::Excluded(l), Bound::Included(u)) => l >= u,
+        _ => false,
+    }
+}
+
+/// The stricter of two bounds on the same side of a range.
+fn stricter(a: Bound<Number>, b: Bound<Number>, lower: bool) -> Bound<Number> {
+    let value = |bound: &Bound<Number>| match bound {
+        Bound::Included(v) | Bound::Excluded(v) => Some(*v),
+        Bound::Unbounded => None,
+    };
+    match (value(&a), value(&b)) {
+        (None, _) => b,
+        (_, None) => a,
+        (Some(x), Some(y)) if x == y => match a {
+            Bound::Excluded(_) => a,
+            _ => b,
+        },
+        (Some(x), Some(y)) => {
+            if (x > y) == lower {
+                a
+            } else {
+                b
+            }
+        }
+    }
+}
+
+/// Keys ordered by the value of their field.
+#[derive(Debug, Default)]
+struct Entries {
+    /// Keys holding each value
+    values: BTreeMap<Number, BTreeSet<String>>,
+    /// Value each indexed key holds
+    keys: HashMap<String, Number>,
+    /// Keys whose field is present but not a number
+    others: HashSet<String>,
+}
+
+impl Entries {
+    fn remove(&mut self, key: &str) {
+        self.others.remove(key);
+        if let Some(number) = self.keys.remove(key)
+            && let Some(keys) = self.values.get_mut(&number)
+        {
+            keys.remove(key);
+            if keys.is_empty() {
+                self.values.remove(&number);
+            }
+        }
+    }
+
+    fn insert(&mut self, key: &str, field: Option<&JsonValue>) {
+        let Some(value) = field else {
+            return;
+        };
+        match value.as_f64() {
+            Some(number) => {
+                let number = Number::new(number);
+                self.values
+                    .entry(number)
+                    .or_default()
+                    .insert(key.to_string());
+                self.keys.insert(key.to_string(), number);
+            }
+            None => {
+                self.others.insert(key.to_string());
+            }
+        }
+    }
+
+    fn contains(&self, key: &str) -> bool {
+        self.keys.contains_key(key) || self.others.contains(key)
+    }
+
+    /// Keys in `(lower, upper)` with their values, in `order`.
+    fn scan(
+        &self,
+        lower: Bound<Number>,
+        upper: Bound<Number>,
+        order: SortOrder,
+    ) -> Box<dyn Iterator<Item = (&String, f64)> + '_> {
+        if is_empty_range(&lower, &upper) {
+            return Box::new(std::iter::empty());
+        }
+        let groups = self.values.range((lower, upper));
+        match order {
+            SortOrder::Asc => Box::new(
+                groups.flat_map(|(number, keys)| keys.iter().map(move |key| (key, number.0))),
+            ),
+            SortOrder::Desc => Box::new(
+                groups
+                    .rev()
+                    .flat_map(|(number, keys)| keys.iter().rev().map(move |key| (key, number.0))),
+            ),
+        }
+    }
+
+    /// Number of keys in `(lower, upper)`.
+    fn count(&self, lower: Bound<Number>, upper: Bound<Number>) -> usize {
+        if is_empty_range(&lower, &upper) {
+            return 0;
+        }
+        self.values
+            .range((lower, upper))
+            .map(|(_, keys)| keys.len())
+            .sum()
+    }
+}
+
+/// Summary of a range index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RangeIndexInfo {
+    /// Namespace the index covers
+    pub namespace: String,
+    /// Field indexed, in dot notation
+    pub field: String,
+    /// Keys with a numeric value for the field
+    pub entries: usize,
+    /// Keys whose field holds something other than a number
+    pub non_numeric: usize,
+    /// Smallest indexed value
+    pub min: Option<f64>,
+    /// Largest indexed value
+    pub max: Option<f64>,
+}
+
+/// A page of keys picked by a range index for a query.
+#[derive(Debug, Clone)]
+pub(crate) struct IndexScan {
+    /// Keys of the page, in the query's order
+    pub keys: Vec<String>,
+    /// Number of records the query matches before offset and limit
+    pub total_count: usize,
+}
+
+/// An ordered index over a numeric field of one namespace.
+#[derive(Debug)]
+pub struct RangeIndex {
+    namespace: String,
+    field: String,
+    entries: RwLock<Entries>,
+}
+
+impl RangeIndex {
+    /// Create an empty index.
+    pub(crate) fn new(namespace: impl Into<String>, field: impl Into<String>) -> Self {
+        Self {
+            namespace: namespace.into(),
+            field: field.into(),
+            entries: RwLock::new(Entries::default()),
+        }
+    }
+
+    /// Namespace the index covers.
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// Field indexed, in dot notation.
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// Record `key`'s current value (`None` once the key is gone).
+    pub(crate) fn update(&self, key: &str, value: Option<&JsonValue>) {
+        let field = value.and_then(|value| field_ref(value, &self.field));
+        let mut entries = self.entries.write().unwrap();
+        entries.remove(key);
+        entries.insert(key, field);
+    }
+
+    /// Record `key`'s value unless a write has already recorded one.
+    ///
+    /// Used to fill a new index from a scan that may race with writes.
+    pub(crate) fn seed(&self, key: &str, value: &JsonValue) {
+        let mut entries = self.entries.write().unwrap();
+        if !entries.contains(key) {
+            entries.insert(key, field_ref(value, &self.field));
+        }
+    }
+
+    /// Number of keys with a numeric value for the field.
+    pub fn len(&self) -> usize {
+        self.entries.read().unwrap().keys.len()
+    }
+
+    /// Whether no key has a numeric value for the field.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Keys whose value lies in `range`, smallest first.
+    pub fn range(&self, range: impl RangeBounds<f64>) -> Vec<(String, f64)> {
+        let lower = range.start_bound().map(|v| Number::new(*v));
+        let upper = range.end_bound().map(|v| Number::new(*v));
+        self.entries
+            .read()
+            .unwrap()
+            .scan(lower, upper, SortOrder::Asc)
+            .map(|(key, value)| (key.clone(), value))
+            .collect()
+    }
+
+    /// Number of keys whose value lies in `range`.
+    pub fn count(&self, range: impl RangeBounds<f64>) -> usize {
+        let lower = range.start_bound().map(|v| Number::new(*v));
+        let upper = range.end_bound().map(|v| Number::new(*v));
+        self.entries.read().unwrap().count(lower, upper)
+    }
+
+    /// The `n` keys with the smallest (`Asc`) or largest (`Desc`) values.
+    pub fn top(&self, n: usize, order: SortOrder) -> Vec<(String, f64)> {
+        self.entries
+            .read()
+            .unwrap()
+            .scan(Bound::Unbounded, Bound::Unbounded, order)
+            .take(n)
+            .map(|(key, value)| (key.clone(), value))
+            .collect()
+    }
+
+    /// Smallest indexed value.
+    pub fn min(&self) -> Option<f64> {
+        let entries = self.entries.read().unwrap();
+        entries.values.keys().next().map(|number| number.0)
+    }
+
+    /// Largest indexed value.
+    pub fn max(&self) -> Option<f64> {
+        let entries = self.entries.read().unwrap();
+        entries.values.keys().next_back().map(|number| number.0)
+    }
+
+    /// The `p`th percentile (0 to 100) of the indexed values, by nearest
+    /// rank.
+    pub fn percentile(&self, p: f64) -> Option<f64> {
+        let entries = self.entries.read().unwrap();
+        let n = entries.keys.len();
+        if n == 0 || p.is_nan() {
+            return None;
+        }
+        let rank = ((p.clamp(0.0, 100.0) / 100.0 * n as f64).ceil() as usize).clamp(1, n);
+
+        // Walk from the nearer end
+        let (mut remaining, groups): (
+            usize,
+            Box<dyn Iterator<Item = (&Number, &BTreeSet<String>)>>,
+        ) = if rank <= n / 2 {
+            (rank, Box::new(entries.values.iter()))
+        } else {
+            (n - rank + 1, Box::new(entries.values.iter().rev()))
+        };
+        for (number, keys) in groups {
+            if remaining <= keys.len() {
+                return Some(number.0);
+            }
+            remaining -= keys.len();
+        }
+        None
+    }
+
+    /// Summary of the index.
+    pub fn info(&self) -> RangeIndexInfo {
+        let entries = self.entries.read().unwrap();
+        RangeIndexInfo {
+            namespace: self.namespace.clone(),
+            field: self.field.clone(),
+            entries: entries.keys.len(),
+            non_numeric: entries.others.len(),
+            min: entries.values.keys().next().map(|number| number.0),
+            max: entries.values.keys().next_back().map(|number| number.0),
+        }
+    }
+
+    /// Pick the page of keys `query` returns, if the index can answer it
+    /// exactly.
+    ///
+    /// That takes a single sort on the indexed field, a limit, no
+    /// aggregation or computed fields, and only numeric range filters on the
+    /// indexed field. Every present value must be a number, and keys lacking
+    /// the field (which sort after numbers ascending, before them
+    /// descending) must not reach the page. `namespace_keys` is the number
+    /// of keys in the namespace.
+    pub(crate) fn plan(&self, query: &Query, namespace_keys: usize) -> Option<IndexScan> {
+        let [sort] = query.sort.as_slice() else {
+            return None;
+        };
+        let limit = query.limit?;
+        if sort.field != self.field || query.aggregation.is_some() || !query.computed.is_empty() {
+            return None;
+        }
+
+        let mut lower = Bound::Unbounded;
+        let mut upper = Bound::Unbounded;
+        for filter in &query.filters {
+            let (field, value) = match filter {
+                Filter::Gt { field, value }
+                | Filter::Gte { field, value }
+                | Filter::Lt { field, value }
+                | Filter::Lte { field, value } => (field, Number::new(value.as_f64()?)),
+                _ => return None,
+            };
+            if *field != self.field {
+                return None;
+            }
+            match filter {
+                Filter::Gt { .. } => lower = stricter(lower, Bound::Excluded(value), true),
+                Filter::Gte { .. } => lower = stricter(lower, Bound::Included(value), true),
+                Filter::Lt { .. } => upper = stricter(upper, Bound::Excluded(value), false),
+                _ => upper = stricter(upper, Bound::Included(value), false),
+            }
+        }
+
+        let entries = self.entries.read().unwrap();
+        if !entries.others.is_empty() {
+            return None;
+        }
+        let offset = query.offset.unwrap_or(0);
+        let filtered = !query.filters.is_empty();
+        let missing = namespace_keys.saturating_sub(entries.keys.len());
+        if !filtered
+            && missing > 0
+            && (sort.order == SortOrder::Desc || offset + limit > entries.keys.len())
+        {
+            return None;
+        }
+
+        let keys = entries
+            .scan(lower, upper, sort.order)
+            .skip(offset)
+            .take(limit)
+            .map(|(key, _)| key.clone())
+            .collect();
+        let total_count = if filtered {
+            entries.count(lower, upper)
+        } else {
+            namespace_keys
+        };
+        Some(IndexScan { keys, total_count })
+    }
+}
+
+/// The range indexes of a storage, by namespace.
+#[derive(Debug, Default)]
+pub(crate) struct RangeIndexes {
+    indexes: DashMap<String, Vec<Arc<RangeIndex>>>,
+}
+
+impl RangeIndexes {
+    /// Indexes covering `namespace`.
+    pub fn of(&self, namespace: &str) -> Vec<Arc<RangeIndex>> {
+        self.indexes
+            .get(namespace)
+            .map(|indexes| indexes.clone())
+            .unwrap_or_default()
+    }
+
+    /// The index on `field` of `namespace`.
+    pub fn get(&self, namespace: &str, field: &str) -> Option<Arc<RangeIndex>> {
+        self.indexes
+            .get(namespace)?
+            .iter()
+            .find(|index| index.field == field)
+            .cloned()
+    }
+
+    /// Register an index on `field` of `namespace`.
+    ///
+    /// Returns the index and whether it is new; an existing index on the
+    /// same field is returned as is.
+    pub fn insert(&self, namespace: &str, field: &str) -> (Arc<RangeIndex>, bool) {
+        let mut indexes = self.indexes.entry(namespace.to_string()).or_default();
+        if let Some(index) = indexes.iter().find(|index| index.field == field) {
+            return (Arc::clone(index), false);
+        }
+        let index = Arc::new(RangeIndex::new(namespace, field));
+        indexes.push(Arc::clone(&index));
+        (index, true)
+    }
+
+    /// Unregister the index on `field` of `namespace`.
+    pub fn remove(&self, namespace: &str, field: &str) -> bool {
+        let Some(mut indexes) = self.indexes.get_mut(namespace) else {
+            return false;
+        };
+        let before = indexes.len();
+        indexes.retain(|index| index.field != field);
+        before != indexes.len()
+    }
+
+    /// Every index.
+    pub fn all(&self) -> Vec<Arc<RangeIndex>> {
+        self.indexes
+            .iter()
+            .flat_map(|indexes| indexes.value().clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn index_of(values: &[(&str, JsonValue)]) -> RangeIndex {
+        let index = RangeIndex::new("orders", "total");
+        for (key, value) in values {
+            index.update(key, Some(value));
+        }
+        index
+    }
+
+    #[test]
+    fn test_range_top_and_percentile() {
+        let index = index_of(&[
+            ("a", json!({"total": 10})),
+            ("b", json!({"total": 30.5})),
+            ("c", json!({"total": 20})),
+            ("d", json!({"total": 20})),
+            ("e", json!({"name": "no total"})),
+        ]);
+        assert_eq!(index.len(), 4);
+        assert_eq!(
+            index.range(15.0..=20.0),
+            vec![("c".to_string(), 20.0), ("d".to_string(), 20.0)]
+        );
+        assert_eq!(index.count(20.0..), 3);
+        assert!(index.range(30.0..10.0).is_empty());
+        assert_eq!(
+            index.top(2, SortOrder::Desc),
+            vec![("b".to_string(), 30.5), ("d".to_string(), 20.0)]
+        );
+        assert_eq!(index.percentile(25.0), Some(10.0));
+        assert_eq!(index.percentile(50.0), Some(20.0));
+        assert_eq!(index.percentile(100.0), Some(30.5));
+        assert_eq!((index.min(), index.max()), (Some(10.0), Some(30.5)));
+
+        // Updates move keys, deletes drop them
+        index.update("a", Some(&json!({"total": 40})));
+        index.update("b", None);
+        assert_eq!(index.top(1, SortOrder::Desc)[0].0, "a");
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.percentile(0.0), Some(20.0));
+    }
+
+    #[test]
+    fn test_plan_sorted_limited_queries() {
+        let index = index_of(&[
+            ("a", json!({"total": 10})),
+            ("b", json!({"total": 30})),
+            ("c", json!({"total": 20})),
+        ]);
+
+        let query = Query::new().sort_by("total", false).limit(2);
+        let scan = index.plan(&query, 3).unwrap();
+        assert_eq!(scan.keys, vec!["b", "c"]);
+        assert_eq!(scan.total_count, 3);
+
+        let query = Query::new()
+            .filter(Filter::gt("total", 10))
+            .sort_by("total", true)
+            .offset(1)
+            .limit(5);
+        let scan = index.plan(&query, 4).unwrap();
+        assert_eq!(scan.keys, vec!["b"]);
+        assert_eq!(scan.total_count, 2);
+
+        // Keys without the field sort first descending
+        let query = Query::new().sort_by("total", false).limit(1);
+        assert!(index.plan(&query, 4).is_none());
+        // Other fields, no limit and mixed types need a scan
+        assert!(
+            index
+                .plan(&Query::new().sort_by("total", true), 3)
+                .is_none()
+        );
+        let query = Query::new()
+            .filter(Filter::eq("status", "paid"))
+            .sort_by("total", true)
+            .limit(1);
+        assert!(index.plan(&query, 3).is_none());
+        index.update("d", Some(&json!({"total": "n/a"})));
+        let query = Query::new().sort_by("total", true).limit(1);
+        assert!(index.plan(&query, 4).is_none());
+    }
+}
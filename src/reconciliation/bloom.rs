@@ -20,13 +20,20 @@
 /// assert!(filter.might_contain("distinction_123")); // Probably true (was inserted)
 /// assert!(filter.definitely_not_contain("distinction_456")); // Definitely not in set
 /// ```
+use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
 /// Bloom filter for distinction set membership.
-#[derive(Debug, Clone)]
+///
+/// Filters serialize compactly (bits packed into a base64 string) and hash
+/// identically on 32- and 64-bit targets, so a browser replica and a native
+/// node can exchange them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "RawBloomFilter")]
 pub struct BloomFilter {
     /// Bit array.
+    #[serde(with = "packed_bits")]
     bits: Vec<bool>,
     /// Number of hash functions.
     k: usize,
@@ -143,8 +150,69 @@ impl BloomFilter {
     fn hash(&self, item: &str, seed: usize) -> usize {
         let mut hasher = DefaultHasher::new();
         item.hash(&mut hasher);
-        seed.hash(&mut hasher);
-        (hasher.finish() as usize) % self.m
+        // Fixed-width seed and modulus keep indexes the same on wasm32
+        (seed as u64).hash(&mut hasher);
+        (hasher.finish() % self.m as u64) as usize
+    }
+}
+
+/// Unchecked wire form of [`BloomFilter`].
+#[derive(Deserialize)]
+struct RawBloomFilter {
+    #[serde(with = "packed_bits")]
+    bits: Vec<bool>,
+    k: usize,
+    m: usize,
+    n: usize,
+}
+
+impl TryFrom<RawBloomFilter> for BloomFilter {
+    type Error = String;
+
+    /// Reject filters a peer could use to index out of bounds or spin.
+    fn try_from(raw: RawBloomFilter) -> Result<Self, Self::Error> {
+        if raw.m == 0 || raw.bits.len() < raw.m {
+            return Err(format!(
+                "bloom filter has {} bits, expected {}",
+                raw.bits.len(),
+                raw.m
+            ));
+        }
+        if raw.k == 0 || raw.k > 64 {
+            return Err(format!("bloom filter has {} hash functions", raw.k));
+        }
+        let mut bits = raw.bits;
+        bits.truncate(raw.m);
+        Ok(Self {
+            bits,
+            k: raw.k,
+            m: raw.m,
+            n: raw.n,
+        })
+    }
+}
+
+/// Serialize the bit array as base64, eight bits per byte.
+mod packed_bits {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bits: &[bool], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bytes = vec![0u8; bits.len().div_ceil(8)];
+        for (i, _) in bits.iter().enumerate().filter(|(_, bit)| **bit) {
+            bytes[i / 8] |= 1 << (i % 8);
+        }
+        serializer.serialize_str(&STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<bool>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        let bytes = STANDARD.decode(encoded).map_err(serde::de::Error::custom)?;
+        Ok(bytes
+            .iter()
+            .flat_map(|byte| (0..8).map(move |i| byte & (1 << i) != 0))
+            .collect())
     }
 }
 
@@ -303,4 +371,23 @@ mod tests {
         let estimated = filter.current_false_positive_rate();
         assert!(estimated > 0.01, "FP rate should increase with more items");
     }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let mut filter = BloomFilter::new(100, 0.01);
+        filter.insert("present");
+
+        let json = serde_json::to_string(&filter).unwrap();
+        let decoded: BloomFilter = serde_json::from_str(&json).unwrap();
+
+        assert!(decoded.might_contain("present"));
+        assert!(decoded.definitely_not_contain("absent"));
+        assert_eq!(decoded.len(), 1);
+    }
+
+    #[test]
+    fn test_deserialize_rejects_short_bits() {
+        let json = r#"{"bits":"AA==","k":3,"m":1000,"n":0}"#;
+        assert!(serde_json::from_str::<BloomFilter>(json).is_err());
+    }
 }
@@ -31,10 +31,14 @@
 /// ```
 pub mod bloom;
 pub mod merkle;
+pub mod protocol;
+pub mod simulation;
 pub mod world;
 
 pub use bloom::{BloomExchange, BloomFilter};
 pub use merkle::{MerkleNode, MerkleTree};
+pub use protocol::{SyncInitiator, SyncMessage, SyncReport, SyncResponder, SyncStep};
+pub use simulation::{NodeState, SimOp, Simulation};
 pub use world::{SyncResult, WorldReconciliation};
 
 use crate::actions::{ConflictResolution, ReconciliationAction};
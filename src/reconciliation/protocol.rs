@@ -0,0 +1,282 @@
+/// Replica Sync Protocol.
+///
+/// Message exchange for reconciling a replica (such as a browser running the
+/// WASM build) with a remote KoruDelta node. The set being reconciled is the
+/// write IDs of every version, so both sides end up with each other's full
+/// history, not just latest values.
+///
+/// ## Exchange
+///
+/// ```text
+/// initiator                          responder
+///     Root { merkle root }      ──▶
+///                               ◀──  Done            (roots match)
+///                               ◀──  Root            (roots differ)
+///     Filter { bloom filter }   ──▶
+///                               ◀──  Versions { missing, responder filter }
+///     Versions { missing }      ──▶
+///                               ◀──  Done
+/// ```
+///
+/// Bloom filters can report false positives, so a version may occasionally
+/// be skipped; the roots then still differ and the next sync picks it up.
+///
+/// Messages serialize as tagged JSON, one message per WebSocket text frame.
+use super::bloom::BloomFilter;
+use super::merkle::MerkleTree;
+use crate::core::KoruDelta;
+use crate::types::{FullKey, VersionedValue};
+use serde::{Deserialize, Serialize};
+
+/// Target false positive rate for exchanged Bloom filters.
+const FILTER_FP_RATE: f64 = 0.01;
+
+/// Filters are sized for at least this many versions, so small sets don't
+/// get tiny filters that saturate.
+const MIN_FILTER_ITEMS: usize = 64;
+
+/// A message in the replica sync protocol.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SyncMessage {
+    /// Merkle root (hex) over the sender's version IDs.
+    Root { root: String, count: usize },
+    /// Bloom filter over the sender's version IDs.
+    Filter { filter: BloomFilter },
+    /// Versions the receiver is missing, optionally with the sender's filter
+    /// so the receiver can answer with what the sender is missing.
+    Versions {
+        versions: Vec<(FullKey, VersionedValue)>,
+        filter: Option<BloomFilter>,
+    },
+    /// Sync finished. Carries the sender's root and how many versions it merged.
+    Done { root: String, merged: usize },
+    /// The sender could not continue.
+    Error { message: String },
+}
+
+/// Outcome of a completed sync, from the initiator's side.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncReport {
+    /// Versions sent to the remote.
+    pub sent: usize,
+    /// Versions received from the remote and merged locally.
+    pub received: usize,
+    /// Whether both sides had the same version set at the start.
+    pub already_in_sync: bool,
+}
+
+/// What the initiator should do after handling a message.
+#[derive(Debug, Clone)]
+pub enum SyncStep {
+    /// Send this message and wait for the reply.
+    Send(SyncMessage),
+    /// Sync is complete.
+    Finished(SyncReport),
+}
+
+/// Merkle root (hex) and size of a database's version set.
+pub fn version_root(db: &KoruDelta) -> (String, usize) {
+    let ids = version_ids(db);
+    let tree = MerkleTree::from_distinctions(&ids);
+    (hex::encode(tree.root_hash()), ids.len())
+}
+
+fn version_ids(db: &KoruDelta) -> Vec<String> {
+    db.storage()
+        .all_versions()
+        .into_iter()
+        .map(|(_, versioned)| versioned.write_id)
+        .collect()
+}
+
+fn version_filter(db: &KoruDelta) -> BloomFilter {
+    let ids = version_ids(db);
+    let mut filter = BloomFilter::new(ids.len().max(MIN_FILTER_ITEMS), FILTER_FP_RATE);
+    for id in &ids {
+        filter.insert(id);
+    }
+    filter
+}
+
+/// Versions the holder of `remote` is definitely missing, oldest first.
+fn missing_from(db: &KoruDelta, remote: &BloomFilter) -> Vec<(FullKey, VersionedValue)> {
+    let mut missing: Vec<_> = db
+        .storage()
+        .all_versions()
+        .into_iter()
+        .filter(|(_, versioned)| remote.definitely_not_contain(&versioned.write_id))
+        .collect();
+    missing.sort_by_key(|(_, versioned)| versioned.timestamp);
+    missing
+}
+
+/// Server side of the protocol.
+///
+/// Stateless: each message carries everything needed to answer it, so one
+/// responder can serve a connection for its whole lifetime.
+pub struct SyncResponder<'a> {
+    db: &'a KoruDelta,
+}
+
+impl<'a> SyncResponder<'a> {
+    /// Create a responder answering from `db`.
+    pub fn new(db: &'a KoruDelta) -> Self {
+        Self { db }
+    }
+
+    /// Answer a message from the initiator.
+    ///
+    /// Returns `None` for messages that need no reply.
+    pub async fn respond(&self, message: SyncMessage) -> Option<SyncMessage> {
+        match message {
+            SyncMessage::Root { root: remote, .. } => {
+                let (root, count) = version_root(self.db);
+                if root == remote {
+                    Some(SyncMessage::Done { root, merged: 0 })
+                } else {
+                    Some(SyncMessage::Root { root, count })
+                }
+            }
+            SyncMessage::Filter { filter } => Some(SyncMessage::Versions {
+                versions: missing_from(self.db, &filter),
+                filter: Some(version_filter(self.db)),
+            }),
+            SyncMessage::Versions { versions, .. } => {
+                let merged = self.db.merge_versions(versions).await.len();
+                let (root, _) = version_root(self.db);
+                Some(SyncMessage::Done { root, merged })
+            }
+            SyncMessage::Done { .. } | SyncMessage::Error { .. } => None,
+        }
+    }
+}
+
+/// Client side of the protocol.
+///
+/// Drive it by sending [`start`](Self::start), then passing each reply to
+/// [`handle`](Self::handle) until it returns [`SyncStep::Finished`].
+pub struct SyncInitiator<'a> {
+    db: &'a KoruDelta,
+    report: SyncReport,
+    /// Set once the responder reports a different root.
+    diverged: bool,
+    /// Versions received and merged locally.
+    merged: Vec<(FullKey, VersionedValue)>,
+}
+
+impl<'a> SyncInitiator<'a> {
+    /// Create an initiator reconciling `db`.
+    pub fn new(db: &'a KoruDelta) -> Self {
+        Self {
+            db,
+            report: SyncReport::default(),
+            diverged: false,
+            merged: Vec::new(),
+        }
+    }
+
+    /// The opening message.
+    pub fn start(&self) -> SyncMessage {
+        let (root, count) = version_root(self.db);
+        SyncMessage::Root { root, count }
+    }
+
+    /// Versions received from the responder that were new locally.
+    ///
+    /// Lets callers persist them, e.g. to IndexedDB in the browser.
+    pub fn merged(&self) -> &[(FullKey, VersionedValue)] {
+        &self.merged
+    }
+
+    /// Handle a reply from the responder.
+    pub async fn handle(&mut self, message: SyncMessage) -> Result<SyncStep, String> {
+        match message {
+            SyncMessage::Root { .. } => {
+                self.diverged = true;
+                Ok(SyncStep::Send(SyncMessage::Filter {
+                    filter: version_filter(self.db),
+                }))
+            }
+            SyncMessage::Versions { versions, filter } => {
+                let filter = filter.ok_or("responder sent versions without its filter")?;
+                let merged = self.db.merge_versions(versions).await;
+                self.report.received += merged.len();
+                self.merged.extend(merged);
+                let missing = missing_from(self.db, &filter);
+                self.report.sent = missing.len();
+                Ok(SyncStep::Send(SyncMessage::Versions {
+                    versions: missing,
+                    filter: None,
+                }))
+            }
+            SyncMessage::Done { .. } => {
+                self.report.already_in_sync = !self.diverged;
+                Ok(SyncStep::Finished(self.report.clone()))
+            }
+            SyncMessage::Error { message } => Err(message),
+            SyncMessage::Filter { .. } => Err("unexpected filter from responder".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Run a full exchange between two databases in memory.
+    async fn sync(local: &KoruDelta, remote: &KoruDelta) -> SyncReport {
+        let responder = SyncResponder::new(remote);
+        let mut initiator = SyncInitiator::new(local);
+        let mut message = initiator.start();
+        loop {
+            let reply = responder.respond(message).await.unwrap();
+            match initiator.handle(reply).await.unwrap() {
+                SyncStep::Send(next) => message = next,
+                SyncStep::Finished(report) => return report,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sync_exchanges_history_both_ways() {
+        let local = KoruDelta::start().await.unwrap();
+        let remote = KoruDelta::start().await.unwrap();
+
+        local.put("notes", "a", json!(1)).await.unwrap();
+        local.put("notes", "a", json!(2)).await.unwrap();
+        remote.put("notes", "b", json!("x")).await.unwrap();
+
+        let report = sync(&local, &remote).await;
+        assert_eq!(report.sent, 2);
+        assert_eq!(report.received, 1);
+
+        assert_eq!(*remote.get("notes", "a").await.unwrap().value, json!(2));
+        assert_eq!(remote.history("notes", "a").await.unwrap().len(), 2);
+        assert_eq!(*local.get("notes", "b").await.unwrap().value, json!("x"));
+        assert_eq!(version_root(&local), version_root(&remote));
+    }
+
+    #[tokio::test]
+    async fn test_sync_when_already_in_sync() {
+        let local = KoruDelta::start().await.unwrap();
+        let remote = KoruDelta::start().await.unwrap();
+
+        let report = sync(&local, &remote).await;
+        assert!(report.already_in_sync);
+    }
+
+    #[tokio::test]
+    async fn test_sync_keeps_newest_value() {
+        let local = KoruDelta::start().await.unwrap();
+        let remote = KoruDelta::start().await.unwrap();
+
+        remote.put("notes", "a", json!("old")).await.unwrap();
+        local.put("notes", "a", json!("new")).await.unwrap();
+
+        sync(&local, &remote).await;
+        assert_eq!(*local.get("notes", "a").await.unwrap().value, json!("new"));
+        assert_eq!(*remote.get("notes", "a").await.unwrap().value, json!("new"));
+    }
+}
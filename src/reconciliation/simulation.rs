@@ -0,0 +1,322 @@
+/// In-process multi-node sync simulation.
+///
+/// Runs N in-process databases and applies a schedule of writes, syncs and
+/// network partitions to them, so reconciliation can be checked against
+/// arbitrary interleavings without sockets or timers. Syncs use the real
+/// replica [protocol](super::protocol), with every message delivered in
+/// memory; a partition drops syncs between nodes on different sides, and a
+/// sync can be cut off part way through to model a connection that fails
+/// mid-exchange.
+///
+/// The property the protocol must uphold: whatever the schedule, once the
+/// network heals and nodes keep syncing, every node ends up with the same
+/// Merkle root, the same history for every key and the same current value.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut sim = Simulation::new(3).await?;
+/// sim.run(&[
+///     SimOp::Partition(vec![0, 0, 1]),
+///     SimOp::write(0, "k", 1),
+///     SimOp::write(2, "k", 2),
+///     SimOp::Sync { from: 0, to: 2 }, // dropped: different sides
+///     SimOp::Heal,
+/// ])
+/// .await?;
+/// sim.converge(10).await?;
+/// ```
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::Value as JsonValue;
+
+use super::protocol::{SyncInitiator, SyncReport, SyncResponder, SyncStep, version_root};
+use crate::core::KoruDelta;
+use crate::error::{DeltaError, DeltaResult};
+
+/// Namespace simulated writes go to.
+pub const SIMULATION_NAMESPACE: &str = "sim";
+
+/// One step of a simulation schedule.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimOp {
+    /// Write `value` to `key` on `node`.
+    Write {
+        node: usize,
+        key: String,
+        value: JsonValue,
+    },
+    /// Run a full sync initiated by `from` against `to`.
+    Sync { from: usize, to: usize },
+    /// Start a sync from `from` to `to` and drop the connection after
+    /// `messages` messages have been delivered.
+    InterruptedSync {
+        from: usize,
+        to: usize,
+        messages: usize,
+    },
+    /// Split the network: node `i` can only reach nodes with the same
+    /// group number as `groups[i]`. Nodes past the end share group 0.
+    Partition(Vec<usize>),
+    /// Reconnect every node.
+    Heal,
+}
+
+impl SimOp {
+    /// Write `value` to `key` on `node`.
+    pub fn write(node: usize, key: impl Into<String>, value: impl Into<JsonValue>) -> Self {
+        Self::Write {
+            node,
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+}
+
+/// What convergence is judged on for one node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeState {
+    /// Merkle root (hex) over the node's version IDs.
+    pub root: String,
+    /// Number of versions held.
+    pub versions: usize,
+    /// Current write ID of each `(namespace, key)`.
+    pub current: BTreeMap<(String, String), String>,
+    /// Write IDs of every version of each `(namespace, key)`.
+    pub histories: BTreeMap<(String, String), BTreeSet<String>>,
+}
+
+/// A set of in-process nodes and the network between them.
+pub struct Simulation {
+    nodes: Vec<KoruDelta>,
+    /// Partition group of each node
+    groups: Vec<usize>,
+}
+
+impl Simulation {
+    /// Start `nodes` empty in-memory nodes, all connected.
+    pub async fn new(nodes: usize) -> DeltaResult<Self> {
+        let mut started = Vec::with_capacity(nodes);
+        for _ in 0..nodes {
+            started.push(KoruDelta::start().await?);
+        }
+        Ok(Self {
+            nodes: started,
+            groups: vec![0; nodes],
+        })
+    }
+
+    /// Number of nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the simulation has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The database of node `index`.
+    pub fn node(&self, index: usize) -> &KoruDelta {
+        &self.nodes[index]
+    }
+
+    /// Whether `a` and `b` are on the same side of the current partition.
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        self.groups[a] == self.groups[b]
+    }
+
+    /// Apply one step of a schedule.
+    ///
+    /// Returns the sync report for syncs that ran to completion, and `None`
+    /// for everything else, including syncs dropped by a partition. Node
+    /// indexes out of range are an error.
+    pub async fn apply(&mut self, op: &SimOp) -> DeltaResult<Option<SyncReport>> {
+        match op {
+            SimOp::Write { node, key, value } => {
+                self.check_node(*node)?;
+                self.nodes[*node]
+                    .put(SIMULATION_NAMESPACE, key.as_str(), value.clone())
+                    .await?;
+                Ok(None)
+            }
+            SimOp::Sync { from, to } => {
+                self.check_node(*from)?;
+                self.check_node(*to)?;
+                if from == to || !self.connected(*from, *to) {
+                    return Ok(None);
+                }
+                self.sync(*from, *to, usize::MAX).await
+            }
+            SimOp::InterruptedSync { from, to, messages } => {
+                self.check_node(*from)?;
+                self.check_node(*to)?;
+                if from == to || !self.connected(*from, *to) {
+                    return Ok(None);
+                }
+                self.sync(*from, *to, *messages).await?;
+                Ok(None)
+            }
+            SimOp::Partition(groups) => {
+                for (node, group) in self.groups.iter_mut().enumerate() {
+                    *group = groups.get(node).copied().unwrap_or(0);
+                }
+                Ok(None)
+            }
+            SimOp::Heal => {
+                self.groups.fill(0);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Apply a whole schedule in order.
+    pub async fn run(&mut self, schedule: &[SimOp]) -> DeltaResult<()> {
+        for op in schedule {
+            self.apply(op).await?;
+        }
+        Ok(())
+    }
+
+    /// Heal the network and sync every pair of nodes until all nodes hold
+    /// the same state, for at most `max_rounds` rounds.
+    ///
+    /// Returns the number of rounds it took. More than one round can be
+    /// needed when Bloom filter false positives hold back a version.
+    pub async fn converge(&mut self, max_rounds: usize) -> DeltaResult<usize> {
+        self.groups.fill(0);
+        for round in 0..=max_rounds {
+            if self.is_converged() {
+                return Ok(round);
+            }
+            for from in 0..self.nodes.len() {
+                for to in from + 1..self.nodes.len() {
+                    self.sync(from, to, usize::MAX).await?;
+                }
+            }
+        }
+        Err(DeltaError::InvalidData {
+            reason: format!("nodes did not converge within {} rounds", max_rounds),
+        })
+    }
+
+    /// The convergence state of every node.
+    pub fn states(&self) -> Vec<NodeState> {
+        self.nodes.iter().map(node_state).collect()
+    }
+
+    /// Whether every node holds the same state.
+    pub fn is_converged(&self) -> bool {
+        let states = self.states();
+        states.windows(2).all(|pair| pair[0] == pair[1])
+    }
+
+    /// Run the sync protocol from `from` to `to`, delivering at most
+    /// `messages` messages.
+    async fn sync(
+        &self,
+        from: usize,
+        to: usize,
+        mut messages: usize,
+    ) -> DeltaResult<Option<SyncReport>> {
+        let responder = SyncResponder::new(&self.nodes[to]);
+        let mut initiator = SyncInitiator::new(&self.nodes[from]);
+        let mut message = initiator.start();
+        loop {
+            if messages == 0 {
+                return Ok(None);
+            }
+            messages -= 1;
+            let Some(reply) = responder.respond(message).await else {
+                return Ok(None);
+            };
+            if messages == 0 {
+                return Ok(None);
+            }
+            messages -= 1;
+            match initiator
+                .handle(reply)
+                .await
+                .map_err(|reason| DeltaError::InvalidData { reason })?
+            {
+                SyncStep::Send(next) => message = next,
+                SyncStep::Finished(report) => return Ok(Some(report)),
+            }
+        }
+    }
+
+    fn check_node(&self, node: usize) -> DeltaResult<()> {
+        if node >= self.nodes.len() {
+            return Err(DeltaError::InvalidData {
+                reason: format!("node {} does not exist ({} nodes)", node, self.nodes.len()),
+            });
+        }
+        Ok(())
+    }
+}
+
+fn node_state(db: &KoruDelta) -> NodeState {
+    let (root, versions) = version_root(db);
+    let current = db
+        .storage()
+        .scan_all()
+        .into_iter()
+        .map(|(key, versioned)| ((key.namespace, key.key), versioned.write_id))
+        .collect();
+    let mut histories: BTreeMap<(String, String), BTreeSet<String>> = BTreeMap::new();
+    for (key, versioned) in db.storage().all_versions() {
+        histories
+            .entry((key.namespace, key.key))
+            .or_default()
+            .insert(versioned.write_id);
+    }
+    NodeState {
+        root,
+        versions,
+        current,
+        histories,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_partitioned_writes_converge_after_heal() {
+        let mut sim = Simulation::new(3).await.unwrap();
+        sim.run(&[
+            SimOp::Partition(vec![0, 0, 1]),
+            SimOp::write(0, "k", 1),
+            SimOp::write(2, "k", 2),
+            SimOp::write(2, "other", "x"),
+            SimOp::Sync { from: 0, to: 2 },
+            SimOp::Sync { from: 0, to: 1 },
+            SimOp::InterruptedSync {
+                from: 1,
+                to: 0,
+                messages: 1,
+            },
+        ])
+        .await
+        .unwrap();
+
+        // The partition kept node 2's writes away from the others
+        assert!(
+            sim.node(0)
+                .get(SIMULATION_NAMESPACE, "other")
+                .await
+                .is_err()
+        );
+        assert!(sim.node(1).get(SIMULATION_NAMESPACE, "k").await.is_ok());
+        assert!(!sim.is_converged());
+
+        sim.converge(5).await.unwrap();
+        let key = (SIMULATION_NAMESPACE.to_string(), "k".to_string());
+        for state in sim.states() {
+            assert_eq!(state.histories[&key].len(), 2);
+        }
+        assert!(sim.node(0).get(SIMULATION_NAMESPACE, "other").await.is_ok());
+        assert!(sim.apply(&SimOp::write(7, "k", 0)).await.is_err());
+    }
+}
@@ -0,0 +1,236 @@
+//! Typed records.
+//!
+//! A [`KoruRecord`] is a Rust type that knows its namespace and key, so it
+//! can be stored and read with [`put_typed`](crate::KoruDelta::put_typed),
+//! [`get_typed`](crate::KoruDelta::get_typed) and
+//! [`query_typed`](crate::KoruDelta::query_typed) instead of handling JSON by
+//! hand. With the `derive` feature, `#[derive(KoruRecord)]` implements it
+//! and adds a `FIELDS` constant whose [`Field`]s build [`Filter`]s, plus a
+//! `<Struct>Query` entry point for [`TypedQuery`]:
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize, KoruRecord)]
+//! #[koru(namespace = "users")]
+//! struct User {
+//!     #[koru(key)]
+//!     handle: String,
+//!     age: u32,
+//! }
+//!
+//! db.put_typed(&User { handle: "alice".into(), age: 30 }).await?;
+//! let alice: User = db.get_typed("alice").await?;
+//! let adults = db
+//!     .query_typed::<User>(Query::new().filter(User::FIELDS.age.gte(18)))
+//!     .await?;
+//!
+//! // Field names checked at compile time
+//! let oldest = db
+//!     .query_typed::<User>(UserQuery::filter(|u| u.age.gt(30)).sort_by_desc(|u| u.age))
+//!     .await?;
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value as JsonValue;
+
+use crate::query::{Filter, Query};
+
+/// A type stored as one value per key in a fixed namespace.
+pub trait KoruRecord: Serialize + DeserializeOwned {
+    /// Namespace records of this type live in.
+    const NAMESPACE: &'static str;
+
+    /// Key this record is stored under.
+    fn key(&self) -> String;
+}
+
+/// A record type with a struct of its [`Field`]s, used by [`TypedQuery`].
+///
+/// `#[derive(KoruRecord)]` implements this with `Fields = <Struct>Fields`.
+pub trait RecordFields: KoruRecord {
+    /// Struct with one [`Field`] per serialized field.
+    type Fields: 'static;
+
+    /// The record's fields.
+    const FIELDS: Self::Fields;
+}
+
+/// Name of a field of record type `R`, for building filters.
+pub struct Field<R> {
+    name: &'static str,
+    record: PhantomData<fn() -> R>,
+}
+
+impl<R> Field<R> {
+    /// Field with the given serialized name.
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            record: PhantomData,
+        }
+    }
+
+    /// Serialized name of the field.
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Field equals `value`.
+    pub fn eq(&self, value: impl Into<JsonValue>) -> Filter {
+        Filter::eq(self.name, value)
+    }
+
+    /// Field does not equal `value`.
+    pub fn ne(&self, value: impl Into<JsonValue>) -> Filter {
+        Filter::ne(self.name, value)
+    }
+
+    /// Field is greater than `value`.
+    pub fn gt(&self, value: impl Into<JsonValue>) -> Filter {
+        Filter::gt(self.name, value)
+    }
+
+    /// Field is greater than or equal to `value`.
+    pub fn gte(&self, value: impl Into<JsonValue>) -> Filter {
+        Filter::gte(self.name, value)
+    }
+
+    /// Field is less than `value`.
+    pub fn lt(&self, value: impl Into<JsonValue>) -> Filter {
+        Filter::lt(self.name, value)
+    }
+
+    /// Field is less than or equal to `value`.
+    pub fn lte(&self, value: impl Into<JsonValue>) -> Filter {
+        Filter::lte(self.name, value)
+    }
+
+    /// Field (string or array) contains `value`.
+    pub fn contains(&self, value: impl Into<JsonValue>) -> Filter {
+        Filter::contains(self.name, value)
+    }
+
+    /// Field is present.
+    pub fn exists(&self) -> Filter {
+        Filter::exists(self.name)
+    }
+
+    /// Field matches the regular expression `pattern`.
+    pub fn matches(&self, pattern: impl Into<String>) -> Filter {
+        Filter::matches(self.name, pattern)
+    }
+}
+
+// Manual impls: derives would needlessly require `R: Clone` etc.
+impl<R> Clone for Field<R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R> Copy for Field<R> {}
+
+impl<R> fmt::Debug for Field<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Field").field(&self.name).finish()
+    }
+}
+
+impl<R> From<Field<R>> for String {
+    fn from(field: Field<R>) -> Self {
+        field.name.to_string()
+    }
+}
+
+impl<R> AsRef<str> for Field<R> {
+    fn as_ref(&self) -> &str {
+        self.name
+    }
+}
+
+/// Query over records of type `R` whose field names are checked at compile
+/// time.
+///
+/// Closures receive `R`'s [`Fields`](RecordFields::Fields), so a misspelled
+/// field is a compile error rather than an empty result. Lowers to a plain
+/// [`Query`] and can be passed anywhere one is accepted by
+/// [`query_typed`](crate::KoruDelta::query_typed).
+pub struct TypedQuery<R> {
+    query: Query,
+    record: PhantomData<fn() -> R>,
+}
+
+impl<R: RecordFields> TypedQuery<R> {
+    /// Query matching every record.
+    pub fn new() -> Self {
+        Self {
+            query: Query::new(),
+            record: PhantomData,
+        }
+    }
+
+    /// Add a filter condition (filters are ANDed).
+    pub fn filter(mut self, filter: impl FnOnce(&R::Fields) -> Filter) -> Self {
+        self.query = self.query.filter(filter(&R::FIELDS));
+        self
+    }
+
+    /// Sort ascending by a field.
+    pub fn sort_by(mut self, field: impl FnOnce(&R::Fields) -> Field<R>) -> Self {
+        self.query = self.query.sort_by(field(&R::FIELDS), true);
+        self
+    }
+
+    /// Sort descending by a field.
+    pub fn sort_by_desc(mut self, field: impl FnOnce(&R::Fields) -> Field<R>) -> Self {
+        self.query = self.query.sort_by(field(&R::FIELDS), false);
+        self
+    }
+
+    /// Set the maximum number of results.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.query = self.query.limit(limit);
+        self
+    }
+
+    /// Set the number of results to skip.
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.query = self.query.offset(offset);
+        self
+    }
+
+    /// The untyped query this lowers to.
+    pub fn into_query(self) -> Query {
+        self.query
+    }
+}
+
+impl<R: RecordFields> Default for TypedQuery<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R> Clone for TypedQuery<R> {
+    fn clone(&self) -> Self {
+        Self {
+            query: self.query.clone(),
+            record: PhantomData,
+        }
+    }
+}
+
+impl<R> fmt::Debug for TypedQuery<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypedQuery").field(&self.query).finish()
+    }
+}
+
+impl<R> From<TypedQuery<R>> for Query {
+    fn from(query: TypedQuery<R>) -> Self {
+        query.query
+    }
+}
@@ -11,6 +11,9 @@
 // =============================================================================
 
 pub mod sync;
+pub mod testing;
+
+pub use testing::TestRuntime;
 
 use std::future::Future;
 use std::pin::Pin;
@@ -23,6 +26,7 @@ use std::time::Duration;
 /// Implementations:
 /// - `TokioRuntime`: Native platforms (Linux, macOS, Windows)
 /// - `WasmRuntime`: WebAssembly (browsers, edge compute)
+/// - `TestRuntime`: Virtual time and deterministic scheduling for tests
 ///
 /// # Send/Sync Requirements
 ///
@@ -50,6 +54,14 @@ pub trait Runtime: Send + Sync + Clone + 'static {
     /// Get current time.
     fn now(&self) -> Instant;
 
+    /// Get the current wall-clock time.
+    ///
+    /// Runtimes with virtual time override this, so timestamps taken from
+    /// the runtime follow their clock.
+    fn utc_now(&self) -> chrono::DateTime<chrono::Utc> {
+        chrono::Utc::now()
+    }
+
     /// Wrap a future with a timeout.
     fn timeout<F>(&self, duration: Duration, future: F) -> Timeout<F::Output>
     where
@@ -488,6 +500,9 @@ enum JoinHandleInner<T> {
     Wasm {
         receiver: futures::channel::oneshot::Receiver<T>,
     },
+    Test {
+        receiver: futures::channel::oneshot::Receiver<T>,
+    },
 }
 
 impl<T> Future for JoinHandle<T> {
@@ -501,6 +516,9 @@ impl<T> Future for JoinHandle<T> {
             JoinHandleInner::Dummy => Poll::Pending,
             #[cfg(target_arch = "wasm32")]
             JoinHandleInner::Wasm { receiver } => Pin::new(receiver).poll(cx).map(|r| r.unwrap()),
+            JoinHandleInner::Test { receiver } => Pin::new(receiver)
+                .poll(cx)
+                .map(|r| r.expect("test runtime dropped the task")),
         }
     }
 }
@@ -515,6 +533,7 @@ enum IntervalInner {
     Tokio(tokio::time::Interval),
     #[cfg(target_arch = "wasm32")]
     Wasm(wasm_impl::WasmInterval),
+    Test(testing::TestInterval),
 }
 
 impl Interval {
@@ -529,6 +548,9 @@ impl Interval {
             IntervalInner::Wasm(interval) => {
                 std::future::poll_fn(|cx| Pin::new(&mut *interval).poll(cx)).await;
             }
+            IntervalInner::Test(interval) => {
+                std::future::poll_fn(|cx| Pin::new(&mut *interval).poll(cx)).await;
+            }
         }
     }
 }
@@ -542,6 +564,7 @@ impl Future for Interval {
             IntervalInner::Tokio(interval) => Pin::new(interval).poll_tick(cx).map(|_| ()),
             #[cfg(target_arch = "wasm32")]
             IntervalInner::Wasm(interval) => Pin::new(interval).poll(cx),
+            IntervalInner::Test(interval) => Pin::new(interval).poll(cx),
         }
     }
 }
@@ -644,6 +667,7 @@ enum InstantInner {
     Tokio(tokio::time::Instant),
     #[cfg(target_arch = "wasm32")]
     Wasm(f64), // Performance.now() in milliseconds
+    Test(Duration), // Virtual time since the TestRuntime was created
 }
 
 impl Instant {
@@ -656,10 +680,8 @@ impl Instant {
             (InstantInner::Wasm(now), InstantInner::Wasm(then)) => {
                 Duration::from_millis((now - then) as u64)
             }
-            #[cfg(all(not(target_arch = "wasm32"), target_arch = "wasm32"))]
-            _ => unreachable!(),
-            #[cfg(all(target_arch = "wasm32", not(target_arch = "wasm32")))]
-            _ => unreachable!(),
+            (InstantInner::Test(now), InstantInner::Test(then)) => now.saturating_sub(then),
+            _ => panic!("cannot compare instants from different runtimes"),
         }
     }
 
@@ -686,6 +708,10 @@ enum TimeoutInner<T> {
         deadline: f64,
         future: Pin<Box<dyn Future<Output = T>>>,
     },
+    Test {
+        sleep: testing::TestSleep,
+        future: Pin<Box<dyn Future<Output = T> + Send>>,
+    },
 }
 
 impl<T> Future for Timeout<T> {
@@ -714,6 +740,12 @@ impl<T> Future for Timeout<T> {
                     Poll::Pending => Poll::Pending,
                 }
             }
+            TimeoutInner::Test { sleep, future } => {
+                if let Poll::Ready(v) = future.as_mut().poll(cx) {
+                    return Poll::Ready(Ok(v));
+                }
+                Pin::new(sleep).poll(cx).map(|()| Err(TimeoutError))
+            }
         }
     }
 }
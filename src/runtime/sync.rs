@@ -177,6 +177,12 @@ impl<T> Mutex<T> {
     }
 }
 
+impl<T> std::fmt::Debug for Mutex<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Mutex").finish_non_exhaustive()
+    }
+}
+
 /// Guard for Mutex.
 pub struct MutexGuard<'a, T> {
     #[cfg(not(target_arch = "wasm32"))]
@@ -198,3 +204,208 @@ impl<T> std::ops::DerefMut for MutexGuard<'_, T> {
         &mut self.guard
     }
 }
+
+// =============================================================================
+// broadcast - Platform-agnostic broadcast channel
+// =============================================================================
+
+/// Platform-agnostic multi-consumer broadcast channel.
+///
+/// On native: Re-exports `tokio::sync::broadcast`
+/// On WASM: A minimal single-threaded implementation with the same API
+/// surface (`channel`, `Sender::send`, `Sender::subscribe`, `Receiver::recv`)
+pub mod broadcast {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub use tokio::sync::broadcast::{Receiver, Sender, channel, error};
+
+    #[cfg(target_arch = "wasm32")]
+    pub use wasm::{Receiver, Sender, channel, error};
+
+    #[cfg(target_arch = "wasm32")]
+    mod wasm {
+        use std::collections::VecDeque;
+        use std::future::poll_fn;
+        use std::sync::{Arc, Mutex};
+        use std::task::{Poll, Waker};
+
+        /// Broadcast channel errors, mirroring `tokio::sync::broadcast::error`.
+        pub mod error {
+            /// The value could not be sent because there are no receivers.
+            #[derive(Debug, PartialEq, Eq)]
+            pub struct SendError<T>(pub T);
+
+            impl<T> std::fmt::Display for SendError<T> {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    write!(f, "channel closed")
+                }
+            }
+
+            /// Why a receive failed.
+            #[derive(Debug, PartialEq, Eq, Clone)]
+            pub enum RecvError {
+                /// All senders were dropped.
+                Closed,
+                /// The receiver fell behind and this many values were dropped.
+                Lagged(u64),
+            }
+
+            impl std::fmt::Display for RecvError {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    match self {
+                        RecvError::Closed => write!(f, "channel closed"),
+                        RecvError::Lagged(n) => write!(f, "channel lagged by {}", n),
+                    }
+                }
+            }
+        }
+
+        use error::{RecvError, SendError};
+
+        struct Shared<T> {
+            /// Buffered values with their sequence numbers, oldest first.
+            buffer: VecDeque<(u64, T)>,
+            capacity: usize,
+            next_seq: u64,
+            senders: usize,
+            receivers: usize,
+            wakers: Vec<Waker>,
+        }
+
+        impl<T> Shared<T> {
+            fn wake_all(&mut self) {
+                for waker in self.wakers.drain(..) {
+                    waker.wake();
+                }
+            }
+        }
+
+        /// Create a bounded broadcast channel.
+        pub fn channel<T: Clone>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+            let shared = Arc::new(Mutex::new(Shared {
+                buffer: VecDeque::with_capacity(capacity),
+                capacity: capacity.max(1),
+                next_seq: 0,
+                senders: 1,
+                receivers: 1,
+                wakers: Vec::new(),
+            }));
+            (
+                Sender {
+                    shared: Arc::clone(&shared),
+                },
+                Receiver { shared, next: 0 },
+            )
+        }
+
+        /// Sending half of a broadcast channel.
+        pub struct Sender<T> {
+            shared: Arc<Mutex<Shared<T>>>,
+        }
+
+        impl<T: Clone> Sender<T> {
+            /// Send a value to every receiver.
+            ///
+            /// Returns the number of receivers, or the value back if none.
+            pub fn send(&self, value: T) -> Result<usize, SendError<T>> {
+                let mut shared = self.shared.lock().unwrap();
+                if shared.receivers == 0 {
+                    return Err(SendError(value));
+                }
+                let seq = shared.next_seq;
+                shared.next_seq += 1;
+                if shared.buffer.len() == shared.capacity {
+                    shared.buffer.pop_front();
+                }
+                shared.buffer.push_back((seq, value));
+                shared.wake_all();
+                Ok(shared.receivers)
+            }
+
+            /// Create a receiver that sees values sent from now on.
+            pub fn subscribe(&self) -> Receiver<T> {
+                let mut shared = self.shared.lock().unwrap();
+                shared.receivers += 1;
+                Receiver {
+                    shared: Arc::clone(&self.shared),
+                    next: shared.next_seq,
+                }
+            }
+
+            /// Number of live receivers.
+            pub fn receiver_count(&self) -> usize {
+                self.shared.lock().unwrap().receivers
+            }
+        }
+
+        impl<T> Clone for Sender<T> {
+            fn clone(&self) -> Self {
+                self.shared.lock().unwrap().senders += 1;
+                Self {
+                    shared: Arc::clone(&self.shared),
+                }
+            }
+        }
+
+        impl<T> Drop for Sender<T> {
+            fn drop(&mut self) {
+                let mut shared = self.shared.lock().unwrap();
+                shared.senders -= 1;
+                if shared.senders == 0 {
+                    shared.wake_all();
+                }
+            }
+        }
+
+        impl<T> std::fmt::Debug for Sender<T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct("Sender").finish_non_exhaustive()
+            }
+        }
+
+        /// Receiving half of a broadcast channel.
+        pub struct Receiver<T> {
+            shared: Arc<Mutex<Shared<T>>>,
+            /// Sequence number of the next value to receive.
+            next: u64,
+        }
+
+        impl<T: Clone> Receiver<T> {
+            /// Receive the next value, waiting until one is sent.
+            pub async fn recv(&mut self) -> Result<T, RecvError> {
+                poll_fn(|cx| {
+                    let mut shared = self.shared.lock().unwrap();
+                    if let Some(&(oldest, _)) = shared.buffer.front() {
+                        if self.next < oldest {
+                            let skipped = oldest - self.next;
+                            self.next = oldest;
+                            return Poll::Ready(Err(RecvError::Lagged(skipped)));
+                        }
+                        let index = (self.next - oldest) as usize;
+                        if let Some((_, value)) = shared.buffer.get(index) {
+                            self.next += 1;
+                            return Poll::Ready(Ok(value.clone()));
+                        }
+                    }
+                    if shared.senders == 0 {
+                        return Poll::Ready(Err(RecvError::Closed));
+                    }
+                    shared.wakers.push(cx.waker().clone());
+                    Poll::Pending
+                })
+                .await
+            }
+        }
+
+        impl<T> Drop for Receiver<T> {
+            fn drop(&mut self) {
+                self.shared.lock().unwrap().receivers -= 1;
+            }
+        }
+
+        impl<T> std::fmt::Debug for Receiver<T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct("Receiver").finish_non_exhaustive()
+            }
+        }
+    }
+}
@@ -0,0 +1,497 @@
+//! Deterministic Test Runtime
+//!
+//! A [`Runtime`](super::Runtime) with virtual time and a single-threaded,
+//! FIFO task queue. Nothing runs until the test drives it, so schedules
+//! built on `sleep`, `interval` and `timeout` can be exercised without real
+//! waiting:
+//!
+//! ```ignore
+//! let runtime = TestRuntime::new();
+//! let db = KoruDeltaGeneric::new_with_runtime(config, runtime.clone()).await?;
+//!
+//! // Fire every background tick due in the next five minutes
+//! runtime.advance(Duration::from_secs(300));
+//! ```
+//!
+//! Ordering is fully deterministic: spawned tasks are polled in the order
+//! they became ready, and timers fire in deadline order (ties in the order
+//! they were registered). Channels are the platform's regular channels;
+//! they need no timer or executor support.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::Duration;
+
+use futures::channel::oneshot;
+
+use super::{DefaultRuntime, Runtime};
+
+type Task = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Runtime with controllable virtual time for tests.
+///
+/// Clones share the same clock and task queue.
+#[derive(Clone, Default)]
+pub struct TestRuntime {
+    shared: Arc<Shared>,
+}
+
+#[derive(Default)]
+struct Shared {
+    clock: Mutex<Clock>,
+    /// Spawned tasks. A slot is `None` while its task is being polled.
+    tasks: Mutex<HashMap<u64, Option<Task>>>,
+    /// Tasks woken since they were last polled, in wake order.
+    ready: Mutex<VecDeque<u64>>,
+    next_task: AtomicU64,
+}
+
+#[derive(Default)]
+struct Clock {
+    /// Virtual time since the runtime was created.
+    now: Duration,
+    /// Pending timers keyed by (deadline, registration id).
+    timers: BTreeMap<(Duration, u64), Waker>,
+    next_timer: u64,
+}
+
+impl Shared {
+    fn now(&self) -> Duration {
+        self.clock.lock().unwrap().now
+    }
+
+    /// Register (or refresh) the timer `id` to wake `waker` at `deadline`.
+    fn register_timer(&self, deadline: Duration, id: &mut Option<u64>, waker: &Waker) {
+        let mut clock = self.clock.lock().unwrap();
+        let timer = *id.get_or_insert_with(|| {
+            clock.next_timer += 1;
+            clock.next_timer
+        });
+        clock.timers.insert((deadline, timer), waker.clone());
+    }
+
+    fn cancel_timer(&self, deadline: Duration, id: u64) {
+        self.clock.lock().unwrap().timers.remove(&(deadline, id));
+    }
+
+    fn schedule(&self, id: u64) {
+        let mut ready = self.ready.lock().unwrap();
+        if !ready.contains(&id) {
+            ready.push_back(id);
+        }
+    }
+}
+
+/// Wakes a spawned task by putting it back on the ready queue.
+struct TaskWaker {
+    id: u64,
+    shared: Arc<Shared>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.shared.schedule(self.id);
+    }
+}
+
+impl TestRuntime {
+    /// Create a runtime whose clock starts at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Virtual time elapsed since the runtime was created.
+    pub fn elapsed(&self) -> Duration {
+        self.shared.now()
+    }
+
+    /// Number of spawned tasks that have not completed.
+    pub fn pending_tasks(&self) -> usize {
+        self.shared.tasks.lock().unwrap().len()
+    }
+
+    /// Poll ready tasks, in FIFO order, until none are ready.
+    ///
+    /// Time does not move; tasks waiting on timers stay parked.
+    pub fn run_until_idle(&self) {
+        loop {
+            let Some(id) = self.shared.ready.lock().unwrap().pop_front() else {
+                break;
+            };
+            let Some(mut task) = self
+                .shared
+                .tasks
+                .lock()
+                .unwrap()
+                .get_mut(&id)
+                .and_then(Option::take)
+            else {
+                continue;
+            };
+
+            let waker = Waker::from(Arc::new(TaskWaker {
+                id,
+                shared: Arc::clone(&self.shared),
+            }));
+            let poll = task.as_mut().poll(&mut Context::from_waker(&waker));
+
+            let mut tasks = self.shared.tasks.lock().unwrap();
+            match poll {
+                Poll::Ready(()) => {
+                    tasks.remove(&id);
+                }
+                Poll::Pending => {
+                    if let Some(slot) = tasks.get_mut(&id) {
+                        *slot = Some(task);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Move virtual time forward by `duration`.
+    ///
+    /// Timers due within the window fire one deadline at a time, and ready
+    /// tasks run to idle after each, so a task observes every tick of an
+    /// interval rather than one collapsed wake-up.
+    pub fn advance(&self, duration: Duration) {
+        let target = self.elapsed() + duration;
+        self.run_until_idle();
+
+        loop {
+            let due = {
+                let mut clock = self.shared.clock.lock().unwrap();
+                let Some(&(deadline, _)) = clock.timers.keys().next() else {
+                    break;
+                };
+                if deadline > target {
+                    break;
+                }
+                clock.now = clock.now.max(deadline);
+                let keys: Vec<_> = clock
+                    .timers
+                    .range((deadline, 0)..=(deadline, u64::MAX))
+                    .map(|(key, _)| *key)
+                    .collect();
+                keys.into_iter()
+                    .filter_map(|key| clock.timers.remove(&key))
+                    .collect::<Vec<_>>()
+            };
+            // Wake outside the lock: wakers may belong to other executors
+            for waker in due {
+                waker.wake();
+            }
+            self.run_until_idle();
+        }
+
+        let mut clock = self.shared.clock.lock().unwrap();
+        clock.now = clock.now.max(target);
+        drop(clock);
+        self.run_until_idle();
+    }
+}
+
+impl Runtime for TestRuntime {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn spawn<F>(&self, future: F) -> super::JoinHandle<F::Output>
+    where
+        F: Future<Output: Send> + Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        let id = self.shared.next_task.fetch_add(1, Ordering::SeqCst);
+        let task: Task = Box::pin(async move {
+            let _ = tx.send(future.await);
+        });
+        self.shared.tasks.lock().unwrap().insert(id, Some(task));
+        self.shared.schedule(id);
+        super::JoinHandle {
+            inner: super::JoinHandleInner::Test { receiver: rx },
+        }
+    }
+
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + Send {
+        TestSleep::new(Arc::clone(&self.shared), duration)
+    }
+
+    fn interval(&self, period: Duration) -> super::Interval {
+        super::Interval {
+            inner: super::IntervalInner::Test(TestInterval {
+                next_tick: self.shared.now(),
+                sleep: TestSleep::new(Arc::clone(&self.shared), Duration::ZERO),
+                period,
+            }),
+        }
+    }
+
+    fn channel<T>(&self, capacity: usize) -> (super::Sender<T>, super::Receiver<T>)
+    where
+        T: Send + 'static,
+    {
+        DefaultRuntime::new().channel(capacity)
+    }
+
+    fn now(&self) -> super::Instant {
+        super::Instant {
+            inner: super::InstantInner::Test(self.shared.now()),
+        }
+    }
+
+    /// Virtual time counted from the Unix epoch.
+    fn utc_now(&self) -> chrono::DateTime<chrono::Utc> {
+        (std::time::UNIX_EPOCH + self.shared.now()).into()
+    }
+
+    fn timeout<F>(&self, duration: Duration, future: F) -> super::Timeout<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send,
+    {
+        super::Timeout {
+            inner: super::TimeoutInner::Test {
+                sleep: TestSleep::new(Arc::clone(&self.shared), duration),
+                future: Box::pin(future),
+            },
+        }
+    }
+
+    fn yield_now(&self) -> impl Future<Output = ()> + Send {
+        YieldNow { yielded: false }
+    }
+
+    fn watch_channel<T>(&self, initial: T) -> (super::WatchSender<T>, super::WatchReceiver<T>)
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        DefaultRuntime::new().watch_channel(initial)
+    }
+}
+
+/// Completes once virtual time reaches its deadline.
+pub(super) struct TestSleep {
+    shared: Arc<Shared>,
+    deadline: Duration,
+    timer: Option<u64>,
+}
+
+impl TestSleep {
+    fn new(shared: Arc<Shared>, duration: Duration) -> Self {
+        let deadline = shared.now() + duration;
+        Self {
+            shared,
+            deadline,
+            timer: None,
+        }
+    }
+
+    fn reset(&mut self, deadline: Duration) {
+        if let Some(id) = self.timer.take() {
+            self.shared.cancel_timer(self.deadline, id);
+        }
+        self.deadline = deadline;
+    }
+}
+
+impl Future for TestSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        if this.shared.now() >= this.deadline {
+            if let Some(id) = this.timer.take() {
+                this.shared.cancel_timer(this.deadline, id);
+            }
+            return Poll::Ready(());
+        }
+        this.shared
+            .register_timer(this.deadline, &mut this.timer, cx.waker());
+        Poll::Pending
+    }
+}
+
+impl Drop for TestSleep {
+    fn drop(&mut self) {
+        if let Some(id) = self.timer.take() {
+            self.shared.cancel_timer(self.deadline, id);
+        }
+    }
+}
+
+/// Interval on virtual time. The first tick completes immediately, like
+/// Tokio's; later ticks land exactly on multiples of the period.
+pub(super) struct TestInterval {
+    sleep: TestSleep,
+    next_tick: Duration,
+    period: Duration,
+}
+
+impl Future for TestInterval {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        if this.sleep.deadline != this.next_tick {
+            this.sleep.reset(this.next_tick);
+        }
+        match Pin::new(&mut this.sleep).poll(cx) {
+            Poll::Ready(()) => {
+                this.next_tick += this.period;
+                Poll::Ready(())
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Returns `Pending` once, sending the task to the back of the queue.
+struct YieldNow {
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+        self.yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log() -> Arc<Mutex<Vec<String>>> {
+        Arc::new(Mutex::new(Vec::new()))
+    }
+
+    #[test]
+    fn test_spawned_tasks_wait_to_be_driven() {
+        let runtime = TestRuntime::new();
+        let ran = log();
+        let r = Arc::clone(&ran);
+        runtime.spawn(async move { r.lock().unwrap().push("ran".into()) });
+
+        assert!(ran.lock().unwrap().is_empty());
+        assert_eq!(runtime.pending_tasks(), 1);
+
+        runtime.run_until_idle();
+        assert_eq!(*ran.lock().unwrap(), vec!["ran"]);
+        assert_eq!(runtime.pending_tasks(), 0);
+    }
+
+    #[test]
+    fn test_sleep_completes_on_advance() {
+        let runtime = TestRuntime::new();
+        let ran = log();
+        let (r, rt) = (Arc::clone(&ran), runtime.clone());
+        runtime.spawn(async move {
+            rt.sleep(Duration::from_secs(60)).await;
+            r.lock().unwrap().push("woke".into());
+        });
+
+        runtime.advance(Duration::from_secs(59));
+        assert!(ran.lock().unwrap().is_empty());
+
+        runtime.advance(Duration::from_secs(1));
+        assert_eq!(*ran.lock().unwrap(), vec!["woke"]);
+        assert_eq!(runtime.elapsed(), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_timers_fire_in_deadline_order() {
+        let runtime = TestRuntime::new();
+        let order = log();
+        for (name, secs) in [("c", 30), ("a", 10), ("b", 20), ("a2", 10)] {
+            let (o, rt) = (Arc::clone(&order), runtime.clone());
+            runtime.spawn(async move {
+                rt.sleep(Duration::from_secs(secs)).await;
+                o.lock()
+                    .unwrap()
+                    .push(format!("{}@{}", name, rt.elapsed().as_secs()));
+            });
+        }
+
+        runtime.advance(Duration::from_secs(60));
+        assert_eq!(
+            *order.lock().unwrap(),
+            vec!["a@10", "a2@10", "b@20", "c@30"]
+        );
+    }
+
+    #[test]
+    fn test_interval_ticks_every_period() {
+        let runtime = TestRuntime::new();
+        let ticks = log();
+        let (t, rt) = (Arc::clone(&ticks), runtime.clone());
+        runtime.spawn(async move {
+            let mut interval = rt.interval(Duration::from_secs(5));
+            loop {
+                interval.tick().await;
+                t.lock().unwrap().push(rt.elapsed().as_secs().to_string());
+            }
+        });
+
+        runtime.advance(Duration::from_secs(12));
+        assert_eq!(*ticks.lock().unwrap(), vec!["0", "5", "10"]);
+    }
+
+    #[test]
+    fn test_timeout_elapses_on_virtual_time() {
+        let runtime = TestRuntime::new();
+        let result = log();
+        let (r, rt) = (Arc::clone(&result), runtime.clone());
+        runtime.spawn(async move {
+            let slow = rt.sleep(Duration::from_secs(10));
+            let outcome = rt.timeout(Duration::from_secs(3), slow).await;
+            r.lock().unwrap().push(outcome.is_err().to_string());
+        });
+
+        runtime.advance(Duration::from_secs(2));
+        assert!(result.lock().unwrap().is_empty());
+        runtime.advance(Duration::from_secs(1));
+        assert_eq!(*result.lock().unwrap(), vec!["true"]);
+    }
+
+    #[test]
+    fn test_yield_interleaves_tasks_deterministically() {
+        let runtime = TestRuntime::new();
+        let order = log();
+        for name in ["a", "b"] {
+            let (o, rt) = (Arc::clone(&order), runtime.clone());
+            runtime.spawn(async move {
+                for step in 0..2 {
+                    o.lock().unwrap().push(format!("{}{}", name, step));
+                    rt.yield_now().await;
+                }
+            });
+        }
+
+        runtime.run_until_idle();
+        assert_eq!(*order.lock().unwrap(), vec!["a0", "b0", "a1", "b1"]);
+    }
+
+    #[test]
+    fn test_join_handle_resolves_inside_runtime() {
+        let runtime = TestRuntime::new();
+        let result = log();
+        let (r, rt) = (Arc::clone(&result), runtime.clone());
+        runtime.spawn(async move {
+            let handle = rt.spawn(async { 42 });
+            r.lock().unwrap().push(handle.await.to_string());
+        });
+
+        runtime.run_until_idle();
+        assert_eq!(*result.lock().unwrap(), vec!["42"]);
+    }
+}
@@ -40,7 +40,7 @@
 
 use std::sync::Arc;
 
-use crate::actions::{KoruAction, PulseAction};
+use crate::actions::{CustomAction, KoruAction, PulseAction};
 use crate::orchestrator::{CoordinationPhase, KoruOrchestrator};
 
 /// Sensory Interface - the boundary where external signals become distinctions.
@@ -158,15 +158,9 @@ impl SensoryInterface {
             SensoryEvent::AgentUnregistered { agent_id } => {
                 KoruAction::from(PulseAction::UnregisterAgent { agent_id })
             }
-            SensoryEvent::Custom {
-                event_type,
-                data: _,
-            } => {
-                // Custom events become pulse actions with serialized data
+            SensoryEvent::Custom { event_type, data } => {
                 // The data is part of the causal chain via the synthesized distinction
-                KoruAction::from(PulseAction::TriggerPulse {
-                    phase: format!("CUSTOM:{}", event_type),
-                })
+                KoruAction::from(CustomAction::new(event_type, data))
             }
         }
     }
@@ -179,7 +173,10 @@ impl SensoryInterface {
             "OUTPUT" => CoordinationPhase::Output,
             "CONSOLIDATION" => CoordinationPhase::Consolidation,
             "EXPLORATION" => CoordinationPhase::Exploration,
-            _ => CoordinationPhase::Idle,
+            _ => self
+                .orchestrator
+                .custom_phase(phase)
+                .unwrap_or(CoordinationPhase::Idle),
         }
     }
 
@@ -0,0 +1,576 @@
+/// Probabilistic sketches for approximate aggregations.
+///
+/// Exact distinct counts, percentiles and frequencies need every value in
+/// memory. These sketches answer the same questions in bounded space, can be
+/// updated one value at a time and merged, so an aggregate over millions of
+/// versions can be maintained as writes happen instead of rescanning:
+///
+/// - [`HyperLogLog`]: distinct count (about 2% standard error)
+/// - [`TDigest`]: percentiles, most accurate near the tails
+/// - [`CountMinSketch`]: value frequencies (never underestimates)
+///
+/// [`ApproxAggregate`] runs the approximate [`Aggregation`]s over them. It
+/// backs both one-off queries and
+/// [`track_aggregate`](crate::KoruDelta::track_aggregate), which keeps one
+/// up to date from the write stream.
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value as JsonValue, json};
+
+use crate::hooks::HookId;
+use crate::query::{Aggregation, get_field};
+
+/// Hash of a JSON value, stable across processes and versions.
+fn hash_value(value: &JsonValue) -> [u8; 32] {
+    *blake3::hash(value.to_string().as_bytes()).as_bytes()
+}
+
+fn word(hash: &[u8; 32], index: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hash[index * 8..index * 8 + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+/// HyperLogLog distinct counter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// Bits of the hash used to pick a register (2^12 registers, ~1.6%
+    /// standard error, 4 KiB).
+    const PRECISION: u32 = 12;
+
+    /// Create an empty counter.
+    pub fn new() -> Self {
+        Self {
+            registers: vec![0; 1 << Self::PRECISION],
+        }
+    }
+
+    /// Add a value.
+    pub fn insert(&mut self, value: &JsonValue) {
+        let hash = word(&hash_value(value), 0);
+        let index = (hash >> (64 - Self::PRECISION)) as usize;
+        let rank = ((hash << Self::PRECISION) | (1 << (Self::PRECISION - 1))).leading_zeros() + 1;
+        self.registers[index] = self.registers[index].max(rank as u8);
+    }
+
+    /// Estimated number of distinct values added.
+    pub fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|&r| 2f64.powi(-(r as i32))).sum();
+        let raw = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|&&r| r == 0).count();
+        let estimate = if raw <= 2.5 * m && zeros > 0 {
+            // Linear counting is more accurate for small cardinalities
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        };
+        estimate.round() as u64
+    }
+
+    /// Merge another counter into this one.
+    pub fn merge(&mut self, other: &Self) {
+        for (mine, theirs) in self.registers.iter_mut().zip(&other.registers) {
+            *mine = (*mine).max(*theirs);
+        }
+    }
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A t-digest cluster: the mean of `weight` values.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+/// Merging t-digest for percentile estimates.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TDigest {
+    compression: f64,
+    centroids: Vec<Centroid>,
+    buffer: Vec<Centroid>,
+    count: f64,
+    min: f64,
+    max: f64,
+}
+
+impl TDigest {
+    /// Default compression; keeps a few hundred centroids at most.
+    pub const DEFAULT_COMPRESSION: f64 = 100.0;
+
+    /// Create an empty digest.
+    pub fn new(compression: f64) -> Self {
+        Self {
+            compression,
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            count: 0.0,
+            min: 0.0,
+            max: 0.0,
+        }
+    }
+
+    /// Add a value (non-finite values are ignored).
+    pub fn insert(&mut self, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        self.push(Centroid {
+            mean: value,
+            weight: 1.0,
+        });
+    }
+
+    /// Number of values added.
+    pub fn count(&self) -> u64 {
+        self.count as u64
+    }
+
+    /// Estimated value at quantile `q` (0.0 to 1.0), `None` if empty.
+    pub fn quantile(&mut self, q: f64) -> Option<f64> {
+        self.compress();
+        let centroids = &self.centroids;
+        let (first, last) = (centroids.first()?, centroids.last()?);
+        if centroids.len() == 1 {
+            return Some(first.mean);
+        }
+        let target = q.clamp(0.0, 1.0) * self.count;
+        if target < first.weight / 2.0 {
+            return Some(self.min + (first.mean - self.min) * target / (first.weight / 2.0));
+        }
+        if target > self.count - last.weight / 2.0 {
+            let tail = self.count - target;
+            return Some(self.max - (self.max - last.mean) * tail / (last.weight / 2.0));
+        }
+
+        // Interpolate between the centers of the two surrounding centroids
+        let mut center = first.weight / 2.0;
+        for pair in centroids.windows(2) {
+            let next = center + (pair[0].weight + pair[1].weight) / 2.0;
+            if target <= next {
+                let t = (target - center) / (next - center);
+                return Some(pair[0].mean + (pair[1].mean - pair[0].mean) * t);
+            }
+            center = next;
+        }
+        Some(last.mean)
+    }
+
+    /// Merge another digest into this one.
+    pub fn merge(&mut self, other: &Self) {
+        if other.count == 0.0 {
+            return;
+        }
+        for centroid in other.centroids.iter().chain(&other.buffer) {
+            self.push(*centroid);
+        }
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    fn push(&mut self, centroid: Centroid) {
+        if self.count == 0.0 {
+            self.min = centroid.mean;
+            self.max = centroid.mean;
+        }
+        self.count += centroid.weight;
+        self.min = self.min.min(centroid.mean);
+        self.max = self.max.max(centroid.mean);
+        self.buffer.push(centroid);
+        if self.buffer.len() as f64 > self.compression * 5.0 {
+            self.compress();
+        }
+    }
+
+    /// Fold buffered values into centroids whose size shrinks towards the
+    /// tails, where percentiles need the most resolution.
+    fn compress(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        let mut all = std::mem::take(&mut self.centroids);
+        all.append(&mut self.buffer);
+        all.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+
+        // Each centroid spans at most one unit of the k1 scale function
+        let scale = |q: f64| {
+            self.compression / (2.0 * std::f64::consts::PI)
+                * (2.0 * q - 1.0).clamp(-1.0, 1.0).asin()
+        };
+        let mut merged = Vec::new();
+        let mut current = all[0];
+        let mut before = 0.0;
+        let mut k_start = scale(0.0);
+        for next in all.into_iter().skip(1) {
+            let proposed = current.weight + next.weight;
+            if scale((before + proposed) / self.count) - k_start <= 1.0 {
+                current.mean += (next.mean - current.mean) * next.weight / proposed;
+                current.weight = proposed;
+            } else {
+                before += current.weight;
+                k_start = scale(before / self.count);
+                merged.push(current);
+                current = next;
+            }
+        }
+        merged.push(current);
+        self.centroids = merged;
+    }
+}
+
+impl Default for TDigest {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_COMPRESSION)
+    }
+}
+
+/// Count-min sketch of value frequencies.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CountMinSketch {
+    width: usize,
+    counters: Vec<u64>,
+}
+
+impl CountMinSketch {
+    /// Rows, one per 64-bit word of the value's hash.
+    const DEPTH: usize = 4;
+
+    /// Create a sketch with `width` counters per row; counts overestimate
+    /// by at most `e / width` of the total with high probability.
+    pub fn new(width: usize) -> Self {
+        let width = width.max(1);
+        Self {
+            width,
+            counters: vec![0; width * Self::DEPTH],
+        }
+    }
+
+    /// Count one occurrence of a value.
+    pub fn insert(&mut self, value: &JsonValue) {
+        let hash = hash_value(value);
+        for row in 0..Self::DEPTH {
+            let cell = self.cell(&hash, row);
+            self.counters[cell] += 1;
+        }
+    }
+
+    /// Estimated number of occurrences of a value.
+    pub fn estimate(&self, value: &JsonValue) -> u64 {
+        let hash = hash_value(value);
+        (0..Self::DEPTH)
+            .map(|row| self.counters[self.cell(&hash, row)])
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Merge another sketch of the same width into this one.
+    pub fn merge(&mut self, other: &Self) {
+        if other.width != self.width {
+            return;
+        }
+        for (mine, theirs) in self.counters.iter_mut().zip(&other.counters) {
+            *mine += theirs;
+        }
+    }
+
+    fn cell(&self, hash: &[u8; 32], row: usize) -> usize {
+        row * self.width + (word(hash, row) % self.width as u64) as usize
+    }
+}
+
+impl Default for CountMinSketch {
+    fn default() -> Self {
+        Self::new(2048)
+    }
+}
+
+/// Incremental state of an approximate [`Aggregation`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ApproxAggregate {
+    /// [`Aggregation::ApproxDistinct`]
+    Distinct {
+        /// Field counted.
+        field: String,
+        /// Distinct counter.
+        sketch: HyperLogLog,
+    },
+    /// [`Aggregation::Percentiles`]
+    Percentiles {
+        /// Numeric field.
+        field: String,
+        /// Percentiles reported (0 to 100).
+        percentiles: Vec<f64>,
+        /// Digest of the field's values.
+        digest: TDigest,
+    },
+    /// [`Aggregation::ApproxTopK`]
+    TopK {
+        /// Field counted.
+        field: String,
+        /// Number of values reported.
+        k: usize,
+        /// Frequency sketch.
+        sketch: CountMinSketch,
+        /// Likely heavy hitters (JSON text to estimated count).
+        candidates: HashMap<String, u64>,
+    },
+}
+
+impl ApproxAggregate {
+    /// State for `aggregation`, or `None` if it is not approximate.
+    pub fn new(aggregation: &Aggregation) -> Option<Self> {
+        Some(match aggregation {
+            Aggregation::ApproxDistinct { field } => Self::Distinct {
+                field: field.clone(),
+                sketch: HyperLogLog::new(),
+            },
+            Aggregation::Percentiles { field, percentiles } => Self::Percentiles {
+                field: field.clone(),
+                percentiles: percentiles.clone(),
+                digest: TDigest::default(),
+            },
+            Aggregation::ApproxTopK { field, k } => Self::TopK {
+                field: field.clone(),
+                k: *k,
+                sketch: CountMinSketch::default(),
+                candidates: HashMap::new(),
+            },
+            _ => return None,
+        })
+    }
+
+    /// Fold in a document; documents without the field are skipped.
+    pub fn add(&mut self, document: &JsonValue) {
+        match self {
+            Self::Distinct { field, sketch } => {
+                if let Some(value) = get_field(document, field).filter(|v| !v.is_null()) {
+                    sketch.insert(&value);
+                }
+            }
+            Self::Percentiles { field, digest, .. } => {
+                if let Some(x) = get_field(document, field).and_then(|v| v.as_f64()) {
+                    digest.insert(x);
+                }
+            }
+            Self::TopK {
+                field,
+                k,
+                sketch,
+                candidates,
+            } => {
+                let Some(value) = get_field(document, field).filter(|v| !v.is_null()) else {
+                    return;
+                };
+                sketch.insert(&value);
+                let count = sketch.estimate(&value);
+                candidates.insert(value.to_string(), count);
+                if candidates.len() > Self::candidate_capacity(*k) {
+                    Self::evict_smallest(candidates);
+                }
+            }
+        }
+    }
+
+    /// Merge state for the same aggregation, e.g. from another node.
+    pub fn merge(&mut self, other: &Self) {
+        match (self, other) {
+            (Self::Distinct { sketch, .. }, Self::Distinct { sketch: theirs, .. }) => {
+                sketch.merge(theirs)
+            }
+            (Self::Percentiles { digest, .. }, Self::Percentiles { digest: theirs, .. }) => {
+                digest.merge(theirs)
+            }
+            (
+                Self::TopK {
+                    k,
+                    sketch,
+                    candidates,
+                    ..
+                },
+                Self::TopK {
+                    sketch: their_sketch,
+                    candidates: their_candidates,
+                    ..
+                },
+            ) => {
+                sketch.merge(their_sketch);
+                for text in their_candidates.keys() {
+                    candidates.entry(text.clone()).or_default();
+                }
+                for (text, count) in candidates.iter_mut() {
+                    if let Ok(value) = serde_json::from_str(text) {
+                        *count = sketch.estimate(&value);
+                    }
+                }
+                while candidates.len() > Self::candidate_capacity(*k) {
+                    Self::evict_smallest(candidates);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The aggregation's current result.
+    ///
+    /// - distinct: a number
+    /// - percentiles: an object like `{"p50": 12.0, "p99": 80.5}`
+    ///   (values are `null` when nothing was added)
+    /// - top-k: `[{"value": ..., "count": n}, ...]`, most frequent first
+    pub fn result(&mut self) -> JsonValue {
+        match self {
+            Self::Distinct { sketch, .. } => json!(sketch.estimate()),
+            Self::Percentiles {
+                percentiles,
+                digest,
+                ..
+            } => JsonValue::Object(
+                percentiles
+                    .iter()
+                    .map(|p| (format!("p{}", p), json!(digest.quantile(p / 100.0))))
+                    .collect(),
+            ),
+            Self::TopK { k, candidates, .. } => {
+                let mut top: Vec<(&String, &u64)> = candidates.iter().collect();
+                top.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+                JsonValue::Array(
+                    top.into_iter()
+                        .take(*k)
+                        .map(|(text, count)| {
+                            let value: JsonValue = serde_json::from_str(text).unwrap_or_default();
+                            json!({"value": value, "count": count})
+                        })
+                        .collect(),
+                )
+            }
+        }
+    }
+
+    /// Heavy-hitter candidates kept beyond `k`, so values climbing into the
+    /// top `k` are not evicted too early.
+    fn candidate_capacity(k: usize) -> usize {
+        k.saturating_mul(4).max(64)
+    }
+
+    fn evict_smallest(candidates: &mut HashMap<String, u64>) {
+        if let Some(smallest) = candidates
+            .iter()
+            .min_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(text, _)| text.clone())
+        {
+            candidates.remove(&smallest);
+        }
+    }
+}
+
+/// An approximate aggregate kept up to date as a namespace is written.
+///
+/// Returned by [`track_aggregate`](crate::KoruDelta::track_aggregate).
+/// Updates are applied by a post-write hook, so a read may briefly lag the
+/// latest writes. Stop tracking with
+/// [`remove_hook`](crate::KoruDelta::remove_hook) and [`hook_id`](Self::hook_id).
+#[derive(Debug, Clone)]
+pub struct TrackedAggregate {
+    pub(crate) hook: HookId,
+    pub(crate) state: Arc<Mutex<ApproxAggregate>>,
+}
+
+impl TrackedAggregate {
+    /// The aggregate's current result, as [`ApproxAggregate::result`].
+    pub fn value(&self) -> JsonValue {
+        self.state.lock().unwrap().result()
+    }
+
+    /// A copy of the sketch state, e.g. to merge with another node's.
+    pub fn snapshot(&self) -> ApproxAggregate {
+        self.state.lock().unwrap().clone()
+    }
+
+    /// Post-write hook maintaining the aggregate.
+    pub fn hook_id(&self) -> HookId {
+        self.hook
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hyperloglog_estimate() {
+        let mut hll = HyperLogLog::new();
+        for i in 0..10_000 {
+            hll.insert(&json!(i % 5_000));
+        }
+        let estimate = hll.estimate() as f64;
+        assert!((estimate - 5_000.0).abs() < 250.0, "estimate {}", estimate);
+
+        let mut other = HyperLogLog::new();
+        for i in 5_000..10_000 {
+            other.insert(&json!(i));
+        }
+        hll.merge(&other);
+        let estimate = hll.estimate() as f64;
+        assert!((estimate - 10_000.0).abs() < 500.0, "estimate {}", estimate);
+
+        assert_eq!(HyperLogLog::new().estimate(), 0);
+    }
+
+    #[test]
+    fn test_tdigest_percentiles() {
+        let mut digest = TDigest::default();
+        assert_eq!(digest.quantile(0.5), None);
+        for i in 1..=100_000 {
+            digest.insert(i as f64);
+        }
+        assert_eq!(digest.count(), 100_000);
+        for (q, expected) in [(0.5, 50_000.0), (0.9, 90_000.0), (0.99, 99_000.0)] {
+            let estimate = digest.quantile(q).unwrap();
+            assert!(
+                (estimate - expected).abs() < expected * 0.01,
+                "q{} = {}",
+                q,
+                estimate
+            );
+        }
+        assert_eq!(digest.quantile(0.0), Some(1.0));
+        assert_eq!(digest.quantile(1.0), Some(100_000.0));
+        assert!(digest.centroids.len() < 500);
+    }
+
+    #[test]
+    fn test_top_k_finds_heavy_hitters() {
+        let mut top = ApproxAggregate::new(&Aggregation::approx_top_k("page", 2)).unwrap();
+        for i in 0..5_000 {
+            let page = match i % 10 {
+                0..=4 => "home".to_string(),
+                5..=7 => "search".to_string(),
+                _ => format!("item-{}", i),
+            };
+            top.add(&json!({"page": page}));
+        }
+        let result = top.result();
+        assert_eq!(result[0]["value"], json!("home"));
+        assert!(result[0]["count"].as_u64().unwrap() >= 2_500);
+        assert_eq!(result[1]["value"], json!("search"));
+        assert_eq!(result.as_array().unwrap().len(), 2);
+
+        let mut sketch = CountMinSketch::default();
+        sketch.insert(&json!("a"));
+        sketch.insert(&json!("a"));
+        assert_eq!(sketch.estimate(&json!("a")), 2);
+        assert_eq!(sketch.estimate(&json!("b")), 0);
+    }
+}
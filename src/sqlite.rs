@@ -0,0 +1,199 @@
+/// SQLite import and export.
+///
+/// [`KoruDelta::import_sqlite`](crate::KoruDelta::import_sqlite) reads whole
+/// tables into namespaces, one JSON object per row, keyed by the table's
+/// primary key (the `rowid` if it has none; composite keys are joined with
+/// `/`). Blobs are imported as base64 strings.
+///
+/// [`KoruDelta::export_sqlite`](crate::KoruDelta::export_sqlite) writes
+/// namespaces back out, either the current value of each key
+/// ([`ExportMode::Heads`]) or every version ([`ExportMode::History`]), with
+/// values stored as JSON text so SQLite's `json_extract` can reach into them.
+///
+/// # Example
+///
+/// ```ignore
+/// use koru_delta::sqlite::ExportMode;
+///
+/// db.import_sqlite("app.db", &[("users", "users"), ("orders", "orders")]).await?;
+/// db.export_sqlite("backup.db", &[("users", "users")], ExportMode::History).await?;
+/// ```
+use std::path::Path;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chrono::{DateTime, Utc};
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, params};
+use serde_json::{Map, Value as JsonValue};
+
+use crate::error::{DeltaError, DeltaResult};
+
+/// What [`export_sqlite`](crate::KoruDelta::export_sqlite) writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportMode {
+    /// Current value of each live key:
+    /// `(key, value, version_id, timestamp)` with `key` as primary key
+    #[default]
+    Heads,
+    /// Every version of every key, oldest first, deletions as NULL values:
+    /// `(key, seq, value, version_id, timestamp)` keyed by `(key, seq)`
+    History,
+}
+
+/// Rows moved between one table and one namespace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableTransfer {
+    /// SQLite table
+    pub table: String,
+    /// KoruDelta namespace
+    pub namespace: String,
+    /// Rows read or written
+    pub rows: usize,
+}
+
+/// One row to export.
+#[derive(Debug, Clone)]
+pub(crate) struct ExportRow {
+    pub key: String,
+    pub seq: usize,
+    pub value: Option<JsonValue>,
+    pub version_id: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Open (or create) a database file.
+pub(crate) fn open(path: &Path) -> DeltaResult<Connection> {
+    Connection::open(path).map_err(sqlite_error)
+}
+
+/// Read every row of `table` as `(key, object)` pairs.
+pub(crate) fn read_table(conn: &Connection, table: &str) -> DeltaResult<Vec<(String, JsonValue)>> {
+    // (pk position, name) of each column; no columns means no table
+    let columns: Vec<(i64, String)> = conn
+        .prepare(&format!("PRAGMA table_info({})", quote(table)))
+        .and_then(|mut info| {
+            info.query_map([], |row| Ok((row.get(5)?, row.get(1)?)))?
+                .collect()
+        })
+        .map_err(sqlite_error)?;
+    if columns.is_empty() {
+        return Err(DeltaError::InvalidData {
+            reason: format!("No such SQLite table: {}", table),
+        });
+    }
+    let mut pk: Vec<(i64, String)> = columns
+        .into_iter()
+        .filter(|(position, _)| *position > 0)
+        .collect();
+    pk.sort();
+
+    let sql = if pk.is_empty() {
+        format!("SELECT rowid, * FROM {}", quote(table))
+    } else {
+        format!("SELECT * FROM {}", quote(table))
+    };
+    let mut stmt = conn.prepare(&sql).map_err(sqlite_error)?;
+    let names: Vec<String> = stmt.column_names().iter().map(|n| n.to_string()).collect();
+    let mut rows = stmt.query([]).map_err(sqlite_error)?;
+
+    let mut records = Vec::new();
+    while let Some(row) = rows.next().map_err(sqlite_error)? {
+        let mut object = Map::new();
+        let mut rowid = None;
+        for (i, name) in names.iter().enumerate() {
+            let value = to_json(row.get_ref(i).map_err(sqlite_error)?);
+            if pk.is_empty() && i == 0 {
+                rowid = Some(key_part(&value));
+            } else {
+                object.insert(name.clone(), value);
+            }
+        }
+        let key = match rowid {
+            Some(rowid) => rowid,
+            None => pk
+                .iter()
+                .map(|(_, column)| object.get(column).map(key_part).unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join("/"),
+        };
+        records.push((key, JsonValue::Object(object)));
+    }
+    Ok(records)
+}
+
+/// Replace `table`'s rows for the exported keys with `rows`.
+pub(crate) fn write_table(
+    conn: &mut Connection,
+    table: &str,
+    rows: &[ExportRow],
+    mode: ExportMode,
+) -> DeltaResult<()> {
+    let tx = conn.transaction().map_err(sqlite_error)?;
+    let (schema, insert) = match mode {
+        ExportMode::Heads => (
+            "key TEXT PRIMARY KEY, value TEXT NOT NULL, version_id TEXT NOT NULL, \
+             timestamp TEXT NOT NULL",
+            "INSERT OR REPLACE INTO {} (key, value, version_id, timestamp) \
+             VALUES (?1, ?3, ?4, ?5)",
+        ),
+        ExportMode::History => (
+            "key TEXT NOT NULL, seq INTEGER NOT NULL, value TEXT, version_id TEXT NOT NULL, \
+             timestamp TEXT NOT NULL, PRIMARY KEY (key, seq)",
+            "INSERT OR REPLACE INTO {} (key, seq, value, version_id, timestamp) \
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        ),
+    };
+    tx.execute(
+        &format!("CREATE TABLE IF NOT EXISTS {} ({})", quote(table), schema),
+        [],
+    )
+    .map_err(sqlite_error)?;
+    {
+        let mut stmt = tx
+            .prepare(&insert.replace("{}", &quote(table)))
+            .map_err(sqlite_error)?;
+        for row in rows {
+            let value = row.value.as_ref().map(JsonValue::to_string);
+            stmt.execute(params![
+                row.key,
+                row.seq as i64,
+                value,
+                row.version_id,
+                row.timestamp.to_rfc3339(),
+            ])
+            .map_err(sqlite_error)?;
+        }
+    }
+    tx.commit().map_err(sqlite_error)
+}
+
+/// Convert a SQLite value to JSON.
+fn to_json(value: ValueRef<'_>) -> JsonValue {
+    match value {
+        ValueRef::Null => JsonValue::Null,
+        ValueRef::Integer(i) => JsonValue::from(i),
+        ValueRef::Real(f) => serde_json::Number::from_f64(f)
+            .map(JsonValue::Number)
+            .unwrap_or(JsonValue::Null),
+        ValueRef::Text(text) => JsonValue::String(String::from_utf8_lossy(text).into_owned()),
+        ValueRef::Blob(blob) => JsonValue::String(STANDARD.encode(blob)),
+    }
+}
+
+/// Render a primary key value as (part of) a key.
+fn key_part(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Quote an identifier.
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn sqlite_error(e: rusqlite::Error) -> DeltaError {
+    DeltaError::StorageError(format!("SQLite error: {}", e))
+}
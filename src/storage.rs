@@ -10,18 +10,147 @@
 /// - Time-travel queries traverse the causal graph
 ///
 /// The storage layer is thread-safe and uses DashMap for lock-free concurrent access.
-use crate::causal_graph::LineageAgent;
+/// Current values are sharded by namespace, so writes and scans in one
+/// namespace never contend with another's.
+use crate::causal_graph::{GraphFormat, LineageAgent};
 use crate::error::{DeltaError, DeltaResult};
-use crate::mapper::DocumentMapper;
+use crate::mapper::{DocumentMapper, MappingVersion};
+use crate::query::Query;
+use crate::range_index::{IndexScan, RANGE_INDEX_NAMESPACE, RangeIndex, RangeIndexes};
 use crate::reference_graph::ReferenceGraph;
 use crate::types::{
-    CausalWriteResult, FullKey, HistoryEntry, Tombstone, VectorClock, VersionedValue,
+    CausalWriteResult, CompactionPolicy, ConflictStatus, FullKey, GcReport,
+    HistoryCompactionReport, HistoryEntry, Provenance, ProvenanceEntry, Tombstone, VectorClock,
+    VersionSummary, VersionedValue, WriteConflict,
 };
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use koru_lambda_core::DistinctionEngine;
 use serde_json::Value as JsonValue;
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+
+/// Distinction ID of the tombstone versions left by replicated deletes.
+const TOMBSTONE_DISTINCTION: &str = "tombstone";
+
+/// Namespace the conflicts replication finds are recorded in, keyed by
+/// conflict id.
+pub const CONFLICT_NAMESPACE: &str = "__conflicts";
+
+/// Whether `versioned` deletes its key: a local delete's null value or a
+/// replicated delete's tombstone.
+fn is_deletion(versioned: &VersionedValue) -> bool {
+    versioned.distinction_id == TOMBSTONE_DISTINCTION || versioned.value.is_null()
+}
+
+/// Current values, sharded by namespace.
+///
+/// Every namespace has its own map, so writes to one namespace never lock
+/// another's entries, and scanning a namespace only visits its own keys
+/// instead of filtering every key in the database. The directory of
+/// namespaces is only write-locked when a namespace is first written to;
+/// everything else takes shared locks on it.
+#[derive(Debug, Default)]
+struct NamespaceShards {
+    shards: DashMap<String, DashMap<String, VersionedValue>>,
+}
+
+impl NamespaceShards {
+    /// Run `f` on a namespace's shard, creating the shard if needed.
+    fn with_shard<R>(
+        &self,
+        namespace: &str,
+        f: impl FnOnce(&DashMap<String, VersionedValue>) -> R,
+    ) -> R {
+        if let Some(shard) = self.shards.get(namespace) {
+            return f(&shard);
+        }
+        let shard = self.shards.entry(namespace.to_string()).or_default();
+        f(&shard.downgrade())
+    }
+
+    fn get(&self, key: &FullKey) -> Option<VersionedValue> {
+        self.shards
+            .get(&key.namespace)?
+            .get(&key.key)
+            .map(|v| v.clone())
+    }
+
+    fn contains_key(&self, key: &FullKey) -> bool {
+        self.shards
+            .get(&key.namespace)
+            .is_some_and(|shard| shard.contains_key(&key.key))
+    }
+
+    fn insert(&self, key: FullKey, versioned: VersionedValue) {
+        self.with_shard(&key.namespace, |shard| shard.insert(key.key, versioned));
+    }
+
+    fn remove(&self, key: &FullKey) -> Option<VersionedValue> {
+        self.shards
+            .get(&key.namespace)?
+            .remove(&key.key)
+            .map(|(_, v)| v)
+    }
+
+    /// Make `versioned` the current value of `key`, calling `locked` while
+    /// the key's entry is locked.
+    fn replace(&self, key: FullKey, versioned: VersionedValue, locked: impl FnOnce()) {
+        self.with_shard(&key.namespace, |shard| {
+            let mut entry = shard.entry(key.key).or_insert_with(|| versioned.clone());
+            locked();
+            *entry = versioned;
+        });
+    }
+
+    fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.len()).sum()
+    }
+
+    fn namespace_len(&self, namespace: &str) -> usize {
+        self.shards.get(namespace).map_or(0, |shard| shard.len())
+    }
+
+    /// Namespaces holding at least one key.
+    fn namespaces(&self) -> Vec<String> {
+        self.shards
+            .iter()
+            .filter(|shard| !shard.is_empty())
+            .map(|shard| shard.key().clone())
+            .collect()
+    }
+
+    fn scan(&self, namespace: &str) -> Vec<(String, VersionedValue)> {
+        self.shards
+            .get(namespace)
+            .map(|shard| {
+                shard
+                    .iter()
+                    .map(|entry| (entry.key().clone(), entry.value().clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn entries(&self) -> Vec<(FullKey, VersionedValue)> {
+        self.shards
+            .iter()
+            .flat_map(|shard| {
+                let namespace = shard.key().clone();
+                shard
+                    .iter()
+                    .map(|entry| {
+                        (
+                            FullKey::new(namespace.clone(), entry.key().clone()),
+                            entry.value().clone(),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
 
 /// Storage engine capturing emergent distinction behavior.
 ///
@@ -48,6 +177,12 @@ pub struct CausalStorage {
     /// Respected, unchanged - computes distinctions via 5 axioms
     engine: Arc<DistinctionEngine>,
 
+    /// Engine stored values are canonicalized in, kept apart from the field
+    /// engine so garbage collection can drop the distinctions of swept values
+    /// by replacing it. Distinction IDs are content hashes, so a fresh engine
+    /// maps the same value to the same ID.
+    values_engine: RwLock<Arc<DistinctionEngine>>,
+
     /// Causal graph: tracks how distinctions emerge from one another
     /// Captured from emergent behavior of put() operations
     causal_graph: LineageAgent,
@@ -56,9 +191,9 @@ pub struct CausalStorage {
     /// Captured from emergent relationships in values
     reference_graph: ReferenceGraph,
 
-    /// Current (latest) value for each key
-    /// Maps FullKey → VersionedValue
-    current_state: DashMap<FullKey, VersionedValue>,
+    /// Current (latest) value for each key, sharded by namespace
+    /// Maps namespace → key → VersionedValue
+    current_state: NamespaceShards,
 
     /// All versions (for history and time travel)
     /// Maps version_id → VersionedValue
@@ -74,6 +209,35 @@ pub struct CausalStorage {
     /// Maps FullKey → Tombstone
     /// Prevents deleted keys from reappearing during sync
     tombstones: DashMap<FullKey, Tombstone>,
+
+    /// Last version of each key removed by a replicated delete: its
+    /// tombstone version, linked to the value it deleted
+    /// Maps FullKey → VersionedValue, until the key is written again
+    deleted_heads: DashMap<FullKey, VersionedValue>,
+
+    /// Serializes updates to the records in [`CONFLICT_NAMESPACE`]
+    conflict_writes: Mutex<()>,
+
+    /// ID of the cluster node this storage belongs to, stamped on local writes
+    origin_node: OnceLock<String>,
+
+    /// How values are canonicalized, fixed for the life of a database so its
+    /// distinction IDs stay stable
+    mapping: OnceLock<MappingVersion>,
+
+    /// Sequence number of the last applied version
+    sequence: AtomicU64,
+
+    /// Sequence number each version was applied at
+    /// Maps version_id → sequence (versions restored from a snapshot have none)
+    applied: DashMap<String, u64>,
+
+    /// Sequence numbers live read snapshots are pinned to
+    /// Maps sequence → number of snapshots pinned there
+    snapshots: Mutex<BTreeMap<u64, usize>>,
+
+    /// Ordered indexes over numeric fields, updated as current values change
+    range_indexes: RangeIndexes,
 }
 
 impl CausalStorage {
@@ -88,12 +252,21 @@ impl CausalStorage {
 
         Self {
             engine,
+            values_engine: RwLock::new(Arc::new(DistinctionEngine::new())),
             causal_graph: LineageAgent::new(&shared_engine),
             reference_graph: ReferenceGraph::new(),
-            current_state: DashMap::new(),
+            current_state: NamespaceShards::default(),
             version_store: DashMap::new(),
             value_store: DashMap::new(),
             tombstones: DashMap::new(),
+            deleted_heads: DashMap::new(),
+            origin_node: OnceLock::new(),
+            mapping: OnceLock::new(),
+            sequence: AtomicU64::new(0),
+            applied: DashMap::new(),
+            snapshots: Mutex::new(BTreeMap::new()),
+            range_indexes: RangeIndexes::default(),
+            conflict_writes: Mutex::new(()),
         }
     }
 
@@ -102,6 +275,38 @@ impl CausalStorage {
         Arc::clone(&self.engine)
     }
 
+    /// The engine stored values are currently canonicalized in.
+    fn values_engine(&self) -> Arc<DistinctionEngine> {
+        Arc::clone(&self.values_engine.read().unwrap())
+    }
+
+    /// Record the cluster node this storage belongs to.
+    ///
+    /// Writes made afterwards carry the node ID as their `origin_node`. Only
+    /// the first call has an effect.
+    pub fn set_origin_node(&self, node_id: impl Into<String>) {
+        let _ = self.origin_node.set(node_id.into());
+    }
+
+    /// Canonicalize values with `version`.
+    ///
+    /// Set when a database is opened, before any writes, to the version its
+    /// stored distinction IDs were made with. Only the first call has an
+    /// effect.
+    pub fn set_mapping_version(&self, version: MappingVersion) {
+        let _ = self.mapping.set(version);
+    }
+
+    /// How values are canonicalized (the current default unless set).
+    pub fn mapping_version(&self) -> MappingVersion {
+        self.mapping.get().copied().unwrap_or_default()
+    }
+
+    /// ID of the cluster node this storage belongs to, if set.
+    pub fn origin_node(&self) -> Option<&str> {
+        self.origin_node.get().map(String::as_str)
+    }
+
     /// Store a value, capturing the emergent distinction and its relationships.
     ///
     /// This operation:
@@ -119,17 +324,100 @@ impl CausalStorage {
         key: impl Into<String>,
         value: JsonValue,
     ) -> DeltaResult<VersionedValue> {
+        self.put_attributed(namespace, key, value, None, Vec::new())
+    }
+
+    /// Store a value with its author and declared causes.
+    ///
+    /// Same as [`put`](Self::put), but the version records `author` and
+    /// references each version in `causes` (write IDs, typically of other
+    /// keys) in the reference graph. Every cause must already be stored.
+    pub fn put_attributed(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        value: JsonValue,
+        author: Option<String>,
+        causes: Vec<String>,
+    ) -> DeltaResult<VersionedValue> {
+        self.put_version(namespace, key, value, author, causes, None)
+    }
+
+    /// Store a value made by a request carrying `idempotency_token`.
+    ///
+    /// Same as [`put_attributed`](Self::put_attributed), but the version
+    /// records the token so retries can be recognized with
+    /// [`find_idempotent`](Self::find_idempotent).
+    pub fn put_idempotent(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        value: JsonValue,
+        author: Option<String>,
+        idempotency_token: impl Into<String>,
+    ) -> DeltaResult<VersionedValue> {
+        self.put_version(
+            namespace,
+            key,
+            value,
+            author,
+            Vec::new(),
+            Some(idempotency_token.into()),
+        )
+    }
+
+    /// Store a new version of a key with its metadata.
+    fn put_version(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        value: JsonValue,
+        author: Option<String>,
+        causes: Vec<String>,
+        idempotency_token: Option<String>,
+    ) -> DeltaResult<VersionedValue> {
+        if let Some(unknown) = causes
+            .iter()
+            .find(|cause| !self.version_store.contains_key(*cause))
+        {
+            return Err(DeltaError::InvalidData {
+                reason: format!("Unknown cause version '{}'", unknown),
+            });
+        }
+
         let full_key = FullKey::new(namespace, key);
-        let timestamp = Utc::now();
 
-        // Get previous version if it exists (causal parent)
-        let previous_version = self
-            .current_state
-            .get(&full_key)
-            .map(|v| v.write_id.clone());
+        // Get previous version if it exists (causal parent), which may be
+        // the tombstone of a replicated delete
+        let previous_version = self.head(&full_key).map(|v| v.write_id.clone());
+
+        let versioned =
+            self.new_version(value, previous_version, author, causes, idempotency_token)?;
+
+        // Update current state
+        self.publish(full_key, versioned.clone());
+
+        Ok(versioned)
+    }
+
+    /// Store a new version following `previous_version`, without making it
+    /// current.
+    fn new_version(
+        &self,
+        value: JsonValue,
+        previous_version: Option<String>,
+        author: Option<String>,
+        causes: Vec<String>,
+        idempotency_token: Option<String>,
+    ) -> DeltaResult<VersionedValue> {
+        let timestamp = Utc::now();
 
         // Compute distinction via koru-lambda-core (unchanged, respected)
-        let distinction = DocumentMapper::json_to_distinction(&value, &self.engine)?;
+        let distinction = DocumentMapper::json_to_distinction_with(
+            &value,
+            &self.values_engine(),
+            self.mapping_version(),
+        )?;
         let distinction_id = DocumentMapper::store_distinction_id(&distinction);
 
         // Generate unique write ID for this specific write event
@@ -149,6 +437,7 @@ impl CausalStorage {
 
         // Capture in reference graph (NEW: emergent tracking)
         self.reference_graph.add_node(write_id.clone());
+        self.link_causes(&write_id, &causes);
         // TODO: Extract and track references from value
 
         // Get or create shared value from the value store (deduplication)
@@ -160,7 +449,7 @@ impl CausalStorage {
             .clone();
 
         // Create new versioned value with unique write_id
-        let versioned = VersionedValue::new(
+        let mut versioned = VersionedValue::new(
             shared_value,
             timestamp,
             write_id.clone(), // unique per write
@@ -168,16 +457,18 @@ impl CausalStorage {
             previous_version,
             VectorClock::new(), // Initialize empty vector clock for new writes
         );
+        versioned.author = author;
+        versioned.origin_node = self.origin_node.get().cloned();
+        versioned.causes = causes;
+        versioned.idempotency_token = idempotency_token;
+        // Serialize once now, so raw reads of this version only clone an Arc
+        versioned.to_raw();
 
         // Store in version store (for history and time travel)
         // Uses unique write_id as key to preserve all writes
         self.version_store
             .insert(write_id.clone(), versioned.clone());
 
-        // Update current state
-        self.current_state
-            .insert(full_key.clone(), versioned.clone());
-
         Ok(versioned)
     }
 
@@ -209,20 +500,70 @@ impl CausalStorage {
     /// - N separate WAL fsyncs (when combined with persistence layer)
     ///
     /// Typical improvement: 2-5x faster for in-memory operations.
+    ///
+    /// The batch is all or nothing: every item is
+    /// [staged](Self::stage_batch) before any is made current.
     pub fn put_batch(
         &self,
         items: Vec<(String, String, JsonValue)>,
     ) -> DeltaResult<Vec<VersionedValue>> {
-        let mut results = Vec::with_capacity(items.len());
+        let staged = self.stage_batch(items)?;
+        self.publish_batch(&staged);
+        Ok(staged.into_iter().map(|(_, versioned)| versioned).collect())
+    }
+
+    /// Store the versions of a batch without making any of them current.
+    ///
+    /// Readers keep seeing the previous values until the batch is
+    /// [published](Self::publish_batch), so it can be made durable first;
+    /// a batch that is never published must be
+    /// [discarded](Self::discard_batch). A key written more than once in the
+    /// batch chains its versions in order. If an item fails, the items
+    /// already staged are discarded and the error is returned.
+    pub fn stage_batch(
+        &self,
+        items: Vec<(String, String, JsonValue)>,
+    ) -> DeltaResult<Vec<(FullKey, VersionedValue)>> {
+        let mut staged: Vec<(FullKey, VersionedValue)> = Vec::with_capacity(items.len());
+        let mut heads: HashMap<FullKey, String> = HashMap::new();
 
         for (namespace, key, value) in items {
-            // Use the single put logic for each item
-            // The optimization comes at the persistence layer (single fsync)
-            let versioned = self.put(namespace, key, value)?;
-            results.push(versioned);
+            let full_key = FullKey::new(namespace, key);
+            let previous_version = heads
+                .get(&full_key)
+                .cloned()
+                .or_else(|| self.head(&full_key).map(|v| v.write_id.clone()));
+            match self.new_version(value, previous_version, None, Vec::new(), None) {
+                Ok(versioned) => {
+                    heads.insert(full_key.clone(), versioned.write_id.clone());
+                    staged.push((full_key, versioned));
+                }
+                Err(e) => {
+                    self.discard_batch(&staged);
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(staged)
+    }
+
+    /// Make the versions of a [staged](Self::stage_batch) batch current, in
+    /// order.
+    pub fn publish_batch(&self, staged: &[(FullKey, VersionedValue)]) {
+        for (key, versioned) in staged {
+            self.publish(key.clone(), versioned.clone());
         }
+    }
 
-        Ok(results)
+    /// Drop the versions of a [staged](Self::stage_batch) batch that will
+    /// not be published, from the version store and both graphs.
+    pub fn discard_batch(&self, staged: &[(FullKey, VersionedValue)]) {
+        for (_, versioned) in staged.iter().rev() {
+            self.version_store.remove(&versioned.write_id);
+            self.causal_graph.remove(&versioned.write_id);
+            self.reference_graph.remove(&versioned.write_id);
+        }
     }
 
     /// Insert a versioned value directly (for persistence replay).
@@ -248,6 +589,7 @@ impl CausalStorage {
 
         // Add to reference graph
         self.reference_graph.add_node(write_id.clone());
+        self.link_causes(&write_id, &versioned.causes);
 
         // Store value in value store (content-addressed)
         self.value_store
@@ -259,11 +601,72 @@ impl CausalStorage {
             .insert(write_id.clone(), versioned.clone());
 
         // Update current state (this overwrites any existing entry for the key)
-        self.current_state.insert(full_key.clone(), versioned);
+        self.publish(full_key, versioned);
 
         Ok(())
     }
 
+    /// Merge a version received from a remote replica.
+    ///
+    /// Unlike [`insert_direct`](Self::insert_direct), the version only becomes
+    /// the key's current value if it is newer than the current one, so merging
+    /// in any order converges on the latest write. Versions already present
+    /// are ignored.
+    ///
+    /// Returns `true` if the version was new to this replica.
+    pub fn merge_version(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        versioned: VersionedValue,
+    ) -> bool {
+        if self.version_store.contains_key(&versioned.write_id) {
+            return false;
+        }
+
+        let full_key = FullKey::new(namespace, key);
+        let head = self.head(&full_key);
+        let is_newer = head.as_ref().is_none_or(|head| {
+            (head.timestamp, &head.write_id) < (versioned.timestamp, &versioned.write_id)
+        });
+
+        // Garbage collection dropped this deleted key's history; taking
+        // older versions back from a peer that still has them would only
+        // hold them until the next collection
+        if !is_newer
+            && let Some(head) = head.filter(is_deletion)
+            && head
+                .previous_version
+                .as_ref()
+                .is_some_and(|previous| !self.version_store.contains_key(previous))
+        {
+            return false;
+        }
+
+        let write_id = versioned.write_id.clone();
+        self.causal_graph.add_node(write_id.clone());
+        if let Some(ref parent_id) = versioned.previous_version {
+            self.causal_graph
+                .add_edge(parent_id.clone(), write_id.clone());
+        }
+        self.reference_graph.add_node(write_id.clone());
+        self.link_causes(&write_id, &versioned.causes);
+
+        self.value_store
+            .entry(versioned.distinction_id.clone())
+            .or_insert_with(|| versioned.value.clone());
+        self.version_store
+            .insert(write_id.clone(), versioned.clone());
+
+        if is_newer {
+            self.publish(full_key, versioned);
+        } else {
+            self.stamp(&write_id);
+        }
+
+        true
+    }
+
     /// Store a value with vector clock-based causal merge.
     ///
     /// This method is used for writes received from other nodes in a cluster.
@@ -327,13 +730,14 @@ impl CausalStorage {
         }
 
         // Apply the write (either no existing value or causally later)
-        let previous_version = self
-            .current_state
-            .get(&full_key)
-            .map(|v| v.write_id.clone());
+        let previous_version = self.head(&full_key).map(|v| v.write_id.clone());
 
         // Compute distinction
-        let distinction = DocumentMapper::json_to_distinction(&incoming_value, &self.engine)?;
+        let distinction = DocumentMapper::json_to_distinction_with(
+            &incoming_value,
+            &self.values_engine(),
+            self.mapping_version(),
+        )?;
         let distinction_id = DocumentMapper::store_distinction_id(&distinction);
         let write_id = format!(
             "{}_{}",
@@ -371,8 +775,7 @@ impl CausalStorage {
         // Store in version store and current state
         self.version_store
             .insert(write_id.clone(), versioned.clone());
-        self.current_state
-            .insert(full_key.clone(), versioned.clone());
+        self.publish(full_key.clone(), versioned.clone());
 
         Ok(CausalWriteResult::Applied(versioned))
     }
@@ -413,7 +816,11 @@ impl CausalStorage {
 
         // Generate write ID
         let previous_version = Some(existing.write_id.clone());
-        let distinction = DocumentMapper::json_to_distinction(&final_value, &self.engine)?;
+        let distinction = DocumentMapper::json_to_distinction_with(
+            &final_value,
+            &self.values_engine(),
+            self.mapping_version(),
+        )?;
         let distinction_id = DocumentMapper::store_distinction_id(&distinction);
         let write_id = format!(
             "merge_{}_{}",
@@ -449,8 +856,7 @@ impl CausalStorage {
         // Store in version store and current state
         self.version_store
             .insert(write_id.clone(), versioned.clone());
-        self.current_state
-            .insert(full_key.clone(), versioned.clone());
+        self.publish(full_key.clone(), versioned.clone());
 
         tracing::info!(
             "Merged concurrent write for {:?}: kept {} value",
@@ -502,9 +908,6 @@ impl CausalStorage {
             v.write_id.clone()
         });
 
-        // Remove from current state (tombstone)
-        self.current_state.remove(&full_key);
-
         // Increment our clock to mark this deletion event
         deletion_clock.increment("local");
 
@@ -521,13 +924,29 @@ impl CausalStorage {
             tombstone_value,
             timestamp,
             write_id.clone(),
-            "tombstone".to_string(),
+            TOMBSTONE_DISTINCTION.to_string(),
             previous_version,
             deletion_clock.clone(),
         );
 
-        // Store in version store for history/audit
-        self.version_store.insert(write_id, versioned_tombstone);
+        // Store in version store for history/audit, linked to the deleted
+        // value so snapshots taken before the delete can still find it
+        self.causal_graph.add_node(write_id.clone());
+        if let Some(ref parent_id) = versioned_tombstone.previous_version {
+            self.causal_graph
+                .add_edge(parent_id.clone(), write_id.clone());
+        }
+        self.stamp(&write_id);
+        self.version_store
+            .insert(write_id, versioned_tombstone.clone());
+        self.deleted_heads
+            .insert(full_key.clone(), versioned_tombstone);
+
+        // Remove from current state (tombstone)
+        self.current_state.remove(&full_key);
+        for index in self.range_indexes.of(&full_key.namespace) {
+            index.update(&full_key.key, None);
+        }
 
         // Create and store tombstone record
         let deleted_by = deleted_by.into();
@@ -580,6 +999,148 @@ impl CausalStorage {
         self.tombstones.insert(tombstone.key.clone(), tombstone);
     }
 
+    /// Record concurrent versions of `key`: the local `existing` one, the
+    /// `incoming` one from a peer, and the `winner` that became current
+    /// (`None` if merging failed), detected at `detected_at`.
+    ///
+    /// If `existing` is the winner or a sibling of a conflict on the key not
+    /// yet resolved by an application, the write joins that conflict as
+    /// another sibling, so three or more concurrent writers end up in one
+    /// record. Recording the same incoming write again, as happens when a
+    /// peer retries a broadcast, only updates the winner.
+    ///
+    /// Conflicts are stored in [`CONFLICT_NAMESPACE`], so they are saved and
+    /// restored with the rest of the data.
+    pub fn record_conflict(
+        &self,
+        key: FullKey,
+        existing: VersionedValue,
+        incoming: VersionedValue,
+        winner: Option<VersionedValue>,
+        detected_at: DateTime<Utc>,
+    ) -> DeltaResult<WriteConflict> {
+        let _guard = self.conflict_writes.lock().unwrap();
+        let open = self.stored_conflicts().into_iter().find(|conflict| {
+            conflict.key == key
+                && conflict.status != ConflictStatus::Resolved
+                && (conflict
+                    .winner
+                    .as_ref()
+                    .is_some_and(|winner| winner.write_id == existing.write_id)
+                    || conflict
+                        .siblings
+                        .iter()
+                        .any(|sibling| sibling.write_id == existing.write_id))
+        });
+
+        let mut conflict = match open {
+            Some(mut conflict) => {
+                let is_winner = |versioned: &VersionedValue| {
+                    conflict
+                        .winner
+                        .as_ref()
+                        .is_some_and(|winner| winner.write_id == versioned.write_id)
+                };
+                let mut added = Vec::new();
+                for versioned in [existing, incoming] {
+                    let known = conflict
+                        .siblings
+                        .iter()
+                        .any(|sibling| sibling.write_id == versioned.write_id);
+                    if !known && !is_winner(&versioned) {
+                        added.push(versioned);
+                    }
+                }
+                conflict.siblings.extend(added);
+                conflict
+            }
+            None => WriteConflict {
+                id: format!("conflict_{}", incoming.write_id),
+                key,
+                siblings: vec![existing, incoming],
+                winner: None,
+                status: ConflictStatus::Unresolved,
+                detected_at,
+            },
+        };
+        conflict.status = if winner.is_some() {
+            ConflictStatus::AutoResolved
+        } else {
+            ConflictStatus::Unresolved
+        };
+        conflict.winner = winner;
+        self.store_conflict(&conflict)?;
+        Ok(conflict)
+    }
+
+    /// Conflicts recorded in a namespace, oldest first.
+    pub fn conflicts(&self, namespace: &str) -> Vec<WriteConflict> {
+        let mut conflicts: Vec<_> = self
+            .stored_conflicts()
+            .into_iter()
+            .filter(|conflict| conflict.key.namespace == namespace)
+            .collect();
+        conflicts.sort_by(|a, b| a.detected_at.cmp(&b.detected_at).then(a.id.cmp(&b.id)));
+        conflicts
+    }
+
+    /// Get a recorded conflict by id.
+    pub fn conflict(&self, id: &str) -> Option<WriteConflict> {
+        self.current_state
+            .get(&FullKey::new(CONFLICT_NAMESPACE, id))
+            .and_then(|versioned| serde_json::from_value(versioned.value().clone()).ok())
+    }
+
+    /// Mark a conflict as resolved by an application in favour of `winner`.
+    pub fn settle_conflict(
+        &self,
+        id: &str,
+        winner: VersionedValue,
+    ) -> DeltaResult<Option<WriteConflict>> {
+        let _guard = self.conflict_writes.lock().unwrap();
+        let Some(mut conflict) = self.conflict(id) else {
+            return Ok(None);
+        };
+        conflict.winner = Some(winner);
+        conflict.status = ConflictStatus::Resolved;
+        self.store_conflict(&conflict)?;
+        Ok(Some(conflict))
+    }
+
+    /// Drop settled conflicts detected before `before`.
+    ///
+    /// Unresolved conflicts are kept until an application resolves them.
+    /// Returns how many conflicts were dropped.
+    pub fn prune_conflicts(&self, before: DateTime<Utc>) -> DeltaResult<usize> {
+        let _guard = self.conflict_writes.lock().unwrap();
+        let mut pruned = 0;
+        for conflict in self.stored_conflicts() {
+            if !conflict.is_unresolved() && conflict.detected_at < before {
+                self.put(CONFLICT_NAMESPACE, &conflict.id, JsonValue::Null)?;
+                pruned += 1;
+            }
+        }
+        Ok(pruned)
+    }
+
+    /// Every conflict record in [`CONFLICT_NAMESPACE`].
+    fn stored_conflicts(&self) -> Vec<WriteConflict> {
+        self.current_state
+            .scan(CONFLICT_NAMESPACE)
+            .into_iter()
+            .filter_map(|(_, versioned)| serde_json::from_value(versioned.value().clone()).ok())
+            .collect()
+    }
+
+    fn store_conflict(&self, conflict: &WriteConflict) -> DeltaResult<()> {
+        self.put(
+            CONFLICT_NAMESPACE,
+            &conflict.id,
+            serde_json::to_value(conflict)?,
+        )?;
+        Ok(())
+    }
+
     /// Get the current (latest) value for a key.
     pub fn get(
         &self,
@@ -590,7 +1151,6 @@ impl CausalStorage {
 
         self.current_state
             .get(&full_key)
-            .map(|v| v.clone())
             .ok_or_else(|| DeltaError::KeyNotFound {
                 namespace: full_key.namespace.clone(),
                 key: full_key.key.clone(),
@@ -663,65 +1223,763 @@ impl CausalStorage {
         })
     }
 
-    /// Get the complete history for a key via causal graph traversal.
+    /// Sequence number of the last applied version.
     ///
-    /// Returns all versions in causal order (oldest to newest).
-    pub fn history(
-        &self,
-        namespace: impl Into<String>,
-        key: impl Into<String>,
-    ) -> DeltaResult<Vec<HistoryEntry>> {
-        let full_key = FullKey::new(namespace, key);
-
-        // Get current version
-        let current = self
-            .current_state
-            .get(&full_key)
-            .ok_or_else(|| DeltaError::KeyNotFound {
-                namespace: full_key.namespace.clone(),
-                key: full_key.key.clone(),
-            })?;
-
-        // Collect all versions via causal graph traversal
-        let mut versions: Vec<VersionedValue> = Vec::new();
-        let mut visited = std::collections::HashSet::new();
-        let mut to_visit = vec![current.write_id.clone()];
-
-        while let Some(version_id) = to_visit.pop() {
-            if !visited.insert(version_id.clone()) {
-                continue;
-            }
+    /// Every version applied to this storage gets the next number, so a
+    /// sequence number pins a consistent state: see
+    /// [`get_as_of`](Self::get_as_of).
+    pub fn sequence(&self) -> u64 {
+        self.sequence.load(Ordering::SeqCst)
+    }
 
-            // Get version from version store
-            if let Some(versioned) = self.version_store.get(&version_id) {
-                versions.push(versioned.clone());
-            }
+    /// Pin a read snapshot to the current sequence number.
+    ///
+    /// Until it is [unpinned](Self::unpin_snapshot), garbage collection and
+    /// history compaction keep every version the snapshot can still see.
+    pub(crate) fn pin_snapshot(&self) -> u64 {
+        let mut snapshots = self.snapshots.lock().unwrap();
+        let sequence = self.sequence();
+        *snapshots.entry(sequence).or_insert(0) += 1;
+        sequence
+    }
 
-            // Add parents
-            let parents = self.causal_graph.ancestors(&version_id);
-            for parent in parents {
-                if !visited.contains(&parent) {
-                    to_visit.push(parent);
-                }
+    /// Release a snapshot pinned by [`pin_snapshot`](Self::pin_snapshot).
+    pub(crate) fn unpin_snapshot(&self, sequence: u64) {
+        let mut snapshots = self.snapshots.lock().unwrap();
+        if let Some(count) = snapshots.get_mut(&sequence) {
+            *count -= 1;
+            if *count == 0 {
+                snapshots.remove(&sequence);
             }
         }
+    }
 
-        // Sort by timestamp (oldest first)
-        versions.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
-
-        // Convert to HistoryEntry
-        Ok(versions.iter().map(HistoryEntry::from).collect())
+    /// Lowest sequence number a live read snapshot is pinned to.
+    pub fn oldest_snapshot(&self) -> Option<u64> {
+        self.snapshots.lock().unwrap().keys().next().copied()
     }
 
-    /// Check if a key exists in the storage.
-    pub fn contains_key(&self, namespace: impl Into<String>, key: impl Into<String>) -> bool {
-        let full_key = FullKey::new(namespace, key);
-        self.current_state.contains_key(&full_key)
+    /// Whether a live snapshot is pinned in `from..until`, i.e. would see a
+    /// version applied at `from` and replaced at `until`.
+    fn snapshot_sees(&self, from: u64, until: u64) -> bool {
+        from < until
+            && self
+                .snapshots
+                .lock()
+                .unwrap()
+                .range(from..until)
+                .next()
+                .is_some()
     }
 
-    /// Get the number of unique keys currently stored.
-    pub fn key_count(&self) -> usize {
-        self.current_state.len()
+    /// Vector clock of the state at `sequence`: this node's entry is the
+    /// sequence number.
+    pub fn clock_at(&self, sequence: u64) -> VectorClock {
+        let node = self.origin_node().unwrap_or("local");
+        let mut clock = VectorClock::new();
+        clock.clocks.insert(node.to_string(), sequence);
+        clock
+    }
+
+    /// Get a key's value as it was when [`sequence`](Self::sequence) returned
+    /// `sequence`.
+    ///
+    /// Cheap when the key has not changed since: the current version is
+    /// returned as is. Otherwise walks back through the key's versions,
+    /// starting from the tombstone if a replicated delete has removed the
+    /// key since, to the latest one applied at or before `sequence`.
+    pub fn get_as_of(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        sequence: u64,
+    ) -> DeltaResult<VersionedValue> {
+        let full_key = FullKey::new(namespace, key);
+        self.head(&full_key)
+            .and_then(|head| self.version_as_of(head, sequence))
+            .ok_or_else(|| DeltaError::KeyNotFound {
+                namespace: full_key.namespace,
+                key: full_key.key,
+            })
+    }
+
+    /// Scan a namespace as it was at `sequence` (see [`get_as_of`](Self::get_as_of)).
+    pub fn scan_collection_as_of(
+        &self,
+        namespace: &str,
+        sequence: u64,
+    ) -> Vec<(String, VersionedValue)> {
+        let mut heads: HashMap<String, VersionedValue> = self
+            .deleted_heads
+            .iter()
+            .filter(|entry| entry.key().namespace == namespace)
+            .map(|entry| (entry.key().key.clone(), entry.value().clone()))
+            .collect();
+        for (key, current) in self.scan_collection(namespace) {
+            self.keep_later(&mut heads, key, current);
+        }
+        heads
+            .into_iter()
+            .filter_map(|(key, head)| {
+                self.version_as_of(head, sequence)
+                    .map(|versioned| (key, versioned))
+            })
+            .collect()
+    }
+
+    /// Latest version of a key: its current value, or the tombstone of a
+    /// replicated delete, whichever was applied last.
+    fn head(&self, key: &FullKey) -> Option<VersionedValue> {
+        let mut heads = HashMap::new();
+        if let Some(deleted) = self.deleted_heads.get(key) {
+            heads.insert(key.key.clone(), deleted.clone());
+        }
+        if let Some(current) = self.current_state.get(key) {
+            self.keep_later(&mut heads, key.key.clone(), current);
+        }
+        heads.remove(&key.key)
+    }
+
+    /// Put `versioned` in `heads` unless the version there was applied later.
+    fn keep_later(
+        &self,
+        heads: &mut HashMap<String, VersionedValue>,
+        key: String,
+        versioned: VersionedValue,
+    ) {
+        match heads.get(&key) {
+            Some(head)
+                if self.applied_at(&head.write_id) > self.applied_at(&versioned.write_id) => {}
+            _ => {
+                heads.insert(key, versioned);
+            }
+        }
+    }
+
+    /// Latest version in `head`'s chain applied at or before `sequence`,
+    /// unless that is a replicated delete's tombstone.
+    fn version_as_of(&self, head: VersionedValue, sequence: u64) -> Option<VersionedValue> {
+        let versioned = if self.applied_at(&head.write_id) <= sequence {
+            head
+        } else {
+            self.causal_graph
+                .ancestors(&head.write_id)
+                .into_iter()
+                .map(|id| (self.applied_at(&id), id))
+                .filter(|(applied, _)| *applied <= sequence)
+                .max_by_key(|(applied, _)| *applied)
+                .and_then(|(_, id)| self.version_store.get(&id).map(|v| v.clone()))?
+        };
+        (versioned.distinction_id != TOMBSTONE_DISTINCTION).then_some(versioned)
+    }
+
+    /// Sequence number a version was applied at (0 if unknown).
+    fn applied_at(&self, write_id: &str) -> u64 {
+        self.applied.get(write_id).map(|seq| *seq).unwrap_or(0)
+    }
+
+    /// Give a version the next sequence number.
+    fn stamp(&self, write_id: &str) {
+        let sequence = self.sequence.fetch_add(1, Ordering::SeqCst) + 1;
+        self.applied.insert(write_id.to_string(), sequence);
+    }
+
+    /// Make `versioned` the current value of `key`.
+    ///
+    /// The version is stamped while the key's entry is locked, so a reader
+    /// pinned to a sequence number never sees the key change under it.
+    fn publish(&self, key: FullKey, versioned: VersionedValue) {
+        let write_id = versioned.write_id.clone();
+        let indexes = self.range_indexes.of(&key.namespace);
+        let indexed = (!indexes.is_empty()).then(|| (key.key.clone(), versioned.shared_value()));
+        self.current_state.replace(key.clone(), versioned, || {
+            self.stamp(&write_id);
+            if let Some((key, value)) = &indexed {
+                for index in &indexes {
+                    index.update(key, Some(value));
+                }
+            }
+        });
+        // The key is live again; its history now runs through the new version
+        self.deleted_heads.remove(&key);
+    }
+
+    /// Find the version of a key written with `idempotency_token`.
+    ///
+    /// Only versions written at or after `since` are considered, newest
+    /// first, so the search stops as soon as the key's chain gets older.
+    pub fn find_idempotent(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        idempotency_token: &str,
+        since: DateTime<Utc>,
+    ) -> Option<VersionedValue> {
+        let full_key = FullKey::new(namespace, key);
+        let mut next = self.current_state.get(&full_key);
+        while let Some(versioned) = next {
+            if versioned.timestamp < since {
+                break;
+            }
+            if versioned.idempotency_token.as_deref() == Some(idempotency_token) {
+                return Some(versioned);
+            }
+            next = versioned
+                .previous_version
+                .as_deref()
+                .and_then(|id| self.version(id));
+        }
+        None
+    }
+
+    /// Get the complete history for a key via causal graph traversal.
+    ///
+    /// Returns all versions in causal order (oldest to newest).
+    pub fn history(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+    ) -> DeltaResult<Vec<HistoryEntry>> {
+        let full_key = FullKey::new(namespace, key);
+
+        // Get current version
+        let current = self
+            .current_state
+            .get(&full_key)
+            .ok_or_else(|| DeltaError::KeyNotFound {
+                namespace: full_key.namespace.clone(),
+                key: full_key.key.clone(),
+            })?;
+
+        // Collect all versions via causal graph traversal
+        let versions = self.causal_chain(&current.write_id);
+
+        // Convert to HistoryEntry
+        Ok(versions.iter().map(HistoryEntry::from).collect())
+    }
+
+    /// Get the provenance of a key's current value.
+    ///
+    /// Lists every write in the value's causal history and, for object
+    /// values, which write last changed each top-level field that is still
+    /// present.
+    pub fn provenance(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+    ) -> DeltaResult<Provenance> {
+        let full_key = FullKey::new(namespace, key);
+        let head = self
+            .current_state
+            .get(&full_key)
+            .map(|current| current.write_id.clone())
+            .ok_or_else(|| DeltaError::KeyNotFound {
+                namespace: full_key.namespace.clone(),
+                key: full_key.key.clone(),
+            })?;
+        let chain = self.causal_chain(&head);
+
+        // Walk forward, attributing each field to the write that changed it
+        let mut fields = BTreeMap::new();
+        let mut previous: Option<&JsonValue> = None;
+        for versioned in &chain {
+            if let JsonValue::Object(object) = versioned.value.as_ref() {
+                for (field, value) in object {
+                    if previous.and_then(|p| p.get(field)) != Some(value) {
+                        fields.insert(field.clone(), versioned.write_id.clone());
+                    }
+                }
+            }
+            previous = Some(versioned.value.as_ref());
+        }
+        match chain.last().map(|current| current.value.as_ref()) {
+            Some(JsonValue::Object(current)) => {
+                fields.retain(|field, _| current.contains_key(field))
+            }
+            _ => fields.clear(),
+        }
+
+        Ok(Provenance {
+            namespace: full_key.namespace,
+            key: full_key.key,
+            writes: chain.iter().map(ProvenanceEntry::from).collect(),
+            fields,
+        })
+    }
+
+    /// Render the causal graph around a key's current version.
+    ///
+    /// Includes ancestors and descendants within `depth` hops of the head,
+    /// labelling each version with the `namespace/key` it belongs to.
+    pub fn export_lineage(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        depth: usize,
+        format: GraphFormat,
+    ) -> DeltaResult<String> {
+        let full_key = FullKey::new(namespace, key);
+        let head = self
+            .current_state
+            .get(&full_key)
+            .map(|current| current.write_id.clone())
+            .ok_or_else(|| DeltaError::KeyNotFound {
+                namespace: full_key.namespace.clone(),
+                key: full_key.key.clone(),
+            })?;
+
+        // Versions only record their key through the head they hang off
+        let labels: HashMap<String, String> = self
+            .all_versions()
+            .into_iter()
+            .map(|(key, versioned)| (versioned.write_id, format!("{}/{}", key.namespace, key.key)))
+            .collect();
+
+        Ok(self
+            .causal_graph
+            .export(&head, depth, format, |id| labels.get(id).cloned()))
+    }
+
+    /// Get the versions a key's current version declared as its causes.
+    ///
+    /// Each cause is returned with the key it belongs to. Resolving keys
+    /// walks every key's history.
+    pub fn causes(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+    ) -> DeltaResult<Vec<(FullKey, VersionedValue)>> {
+        let full_key = FullKey::new(namespace, key);
+        let causes = self
+            .current_state
+            .get(&full_key)
+            .map(|current| current.causes.clone())
+            .ok_or_else(|| DeltaError::KeyNotFound {
+                namespace: full_key.namespace.clone(),
+                key: full_key.key.clone(),
+            })?;
+        Ok(self.locate_versions(&causes))
+    }
+
+    /// Get the versions that declared `write_id` as one of their causes.
+    ///
+    /// Each version is returned with the key it belongs to. Resolving keys
+    /// walks every key's history.
+    pub fn dependents(&self, write_id: &str) -> Vec<(FullKey, VersionedValue)> {
+        self.locate_versions(&self.reference_graph.referrers(&write_id.to_string()))
+    }
+
+    /// Record that `write_id` was derived from each of `causes`.
+    ///
+    /// Causes may not have been replayed or merged yet, so their nodes are
+    /// created on demand.
+    fn link_causes(&self, write_id: &str, causes: &[String]) {
+        for cause in causes {
+            self.reference_graph.add_node(cause.clone());
+            self.reference_graph
+                .add_reference(write_id.to_string(), cause.clone());
+        }
+    }
+
+    /// Find the given versions along with their keys, in the given order.
+    fn locate_versions(&self, write_ids: &[String]) -> Vec<(FullKey, VersionedValue)> {
+        if write_ids.is_empty() {
+            return Vec::new();
+        }
+        let mut found: HashMap<String, (FullKey, VersionedValue)> = self
+            .all_versions()
+            .into_iter()
+            .filter(|(_, versioned)| write_ids.contains(&versioned.write_id))
+            .map(|(key, versioned)| (versioned.write_id.clone(), (key, versioned)))
+            .collect();
+        write_ids
+            .iter()
+            .filter_map(|write_id| found.remove(write_id))
+            .collect()
+    }
+
+    /// Collect a version and all of its causal ancestors, oldest first.
+    fn causal_chain(&self, head: &str) -> Vec<VersionedValue> {
+        let mut versions: Vec<VersionedValue> = Vec::new();
+        let mut visited = std::collections::HashSet::new();
+        let mut to_visit = vec![head.to_string()];
+
+        while let Some(version_id) = to_visit.pop() {
+            if !visited.insert(version_id.clone()) {
+                continue;
+            }
+
+            // Get version from version store
+            if let Some(versioned) = self.version_store.get(&version_id) {
+                versions.push(versioned.clone());
+            }
+
+            // Add parents
+            let parents = self.causal_graph.ancestors(&version_id);
+            for parent in parents {
+                if !visited.contains(&parent) {
+                    to_visit.push(parent);
+                }
+            }
+        }
+
+        // Sort by timestamp (oldest first)
+        versions.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        versions
+    }
+
+    /// Get every version of every key, each key's versions oldest first.
+    ///
+    /// Used to reconcile with remote replicas, which exchange version sets.
+    pub fn all_versions(&self) -> Vec<(FullKey, VersionedValue)> {
+        let heads: Vec<(FullKey, String)> = self
+            .current_state
+            .entries()
+            .into_iter()
+            .map(|(key, versioned)| (key, versioned.write_id))
+            .collect();
+
+        heads
+            .into_iter()
+            .flat_map(|(key, head)| {
+                self.causal_chain(&head)
+                    .into_iter()
+                    .map(move |versioned| (key.clone(), versioned))
+            })
+            .collect()
+    }
+
+    /// Check if a version (by write ID) is held in the version store.
+    pub fn contains_version(&self, write_id: &str) -> bool {
+        self.version_store.contains_key(write_id)
+    }
+
+    /// Get a version by write ID.
+    pub fn version(&self, write_id: &str) -> Option<VersionedValue> {
+        self.version_store.get(write_id).map(|v| v.clone())
+    }
+
+    /// Reclaim versions that nothing live can reach (mark and sweep).
+    ///
+    /// Marks every live key's head and full history, the `namespace:key`
+    /// nodes of live keys, the declared causes of anything marked, and
+    /// `pinned` (e.g. distinctions named by stored genomes). A deleted key
+    /// keeps only its deletion, so its history goes, unless a live
+    /// [`ReadTransaction`](crate::transaction::ReadTransaction) began before
+    /// the delete and can still read it; a [`Tombstone`] is kept for it so
+    /// anti-entropy can't bring the key back from a peer.
+    /// Everything else is swept from the version store, both graphs and the
+    /// value store, and the engine values are canonicalized in is replaced,
+    /// dropping the distinctions of everything swept.
+    ///
+    /// Callers must keep writes out while this runs, or a write linking to a
+    /// version being swept could be left dangling.
+    pub fn collect_garbage(&self, pinned: &[String]) -> GcReport {
+        // Mark
+        let mut marked = HashSet::new();
+        let mut to_visit: Vec<String> = pinned.to_vec();
+        let mut deleted = Vec::new();
+        let heads = self.current_state.entries().into_iter().chain(
+            self.deleted_heads
+                .iter()
+                .map(|entry| (entry.key().clone(), entry.value().clone())),
+        );
+        for (key, versioned) in heads {
+            to_visit.push(key.to_canonical_string());
+            if is_deletion(&versioned) {
+                // A snapshot from before the delete still reads the old value
+                if self.snapshot_sees(0, self.applied_at(&versioned.write_id)) {
+                    to_visit.push(versioned.write_id.clone());
+                } else {
+                    marked.insert(versioned.write_id.clone());
+                }
+                deleted.push((key, versioned));
+            } else {
+                to_visit.push(versioned.write_id);
+            }
+        }
+        while let Some(id) = to_visit.pop() {
+            if !marked.insert(id.clone()) {
+                continue;
+            }
+            if let Some(parents) = self.causal_graph.get_parents(&id) {
+                to_visit.extend(parents);
+            }
+            if let Some(previous) = self
+                .version_store
+                .get(&id)
+                .and_then(|versioned| versioned.previous_version.clone())
+            {
+                to_visit.push(previous);
+            }
+            to_visit.extend(self.reference_graph.references(&id));
+        }
+
+        // Sweep
+        let mut report = GcReport::default();
+        let unreachable: HashSet<String> = self
+            .version_store
+            .iter()
+            .map(|entry| entry.key().clone())
+            .chain(self.causal_graph.all_nodes())
+            .filter(|id| !marked.contains(id))
+            .collect();
+        for id in &unreachable {
+            if self.version_store.remove(id).is_some() {
+                report.versions_collected += 1;
+            }
+            self.applied.remove(id);
+            if self.causal_graph.contains(id) {
+                self.causal_graph.remove(id);
+                report.graph_nodes_collected += 1;
+            }
+            self.reference_graph.remove(id);
+        }
+
+        for (key, deletion) in deleted {
+            self.tombstones.entry(key.clone()).or_insert_with(|| {
+                let deleted_by = deletion.origin_node.as_deref().unwrap_or("local");
+                Tombstone::new(key, deleted_by, deletion.vector_clock.clone())
+            });
+        }
+
+        let shared: HashSet<String> = self
+            .version_store
+            .iter()
+            .map(|entry| entry.value().distinction_id.clone())
+            .collect();
+        let values_before = self.value_store.len();
+        self.value_store.retain(|id, _| shared.contains(id));
+        report.values_collected = values_before - self.value_store.len();
+
+        // Live values are canonicalized again as they are next written
+        let fresh = Arc::new(DistinctionEngine::new());
+        let swept = std::mem::replace(
+            &mut *self.values_engine.write().unwrap(),
+            Arc::clone(&fresh),
+        );
+        report.distinctions_collected = swept
+            .distinction_count()
+            .saturating_sub(fresh.distinction_count());
+
+        report
+    }
+
+    /// Fold runs of a key's old versions into single summary versions.
+    ///
+    /// Walks the key's chain oldest first. A version can be folded if it is
+    /// neither the key's first version nor among the `keep_recent` newest,
+    /// is old enough for the policy, is not `pinned`, no other write
+    /// declared it as a cause, and it has no branches in the causal graph.
+    /// Each run of at least `min_run` such versions is replaced by its
+    /// newest version, which keeps its write ID and value, links straight
+    /// to the version before the run, inherits the run's causes, and
+    /// records the rest of the run in its [`VersionSummary`].
+    ///
+    /// A version a live read transaction still sees is never folded.
+    /// Time travel into a folded run resolves to the version before it.
+    /// Like [`collect_garbage`](Self::collect_garbage), callers must keep
+    /// writes out while this runs.
+    pub fn compact_history(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        policy: &CompactionPolicy,
+        pinned: &[String],
+    ) -> DeltaResult<HistoryCompactionReport> {
+        let full_key = FullKey::new(namespace, key);
+        let head = self
+            .current_state
+            .get(&full_key)
+            .ok_or_else(|| DeltaError::KeyNotFound {
+                namespace: full_key.namespace.clone(),
+                key: full_key.key.clone(),
+            })?;
+
+        // The key's own chain, oldest first
+        let mut chain = vec![head];
+        while let Some(previous) = chain.last().and_then(|v| v.previous_version.clone()) {
+            match self.version_store.get(&previous) {
+                Some(versioned) => chain.push(versioned.clone()),
+                None => break,
+            }
+        }
+        chain.reverse();
+
+        let cutoff = policy.older_than.map(|age| Utc::now() - age);
+        let recent = chain.len().saturating_sub(policy.keep_recent.max(1));
+        let min_run = policy.min_run.max(2);
+        let branches = |edges: Option<Vec<String>>| edges.map_or(0, |edges| edges.len());
+        let applied: Vec<u64> = chain.iter().map(|v| self.applied_at(&v.write_id)).collect();
+        let foldable = |index: usize, versioned: &VersionedValue| {
+            index > 0
+                && index < recent
+                && !self.snapshot_sees(applied[index], applied[index + 1])
+                && cutoff.is_none_or(|cutoff| versioned.timestamp <= cutoff)
+                && !pinned.contains(&versioned.write_id)
+                && !pinned.contains(&versioned.distinction_id)
+                && self
+                    .reference_graph
+                    .referrers(&versioned.write_id)
+                    .is_empty()
+                && branches(self.causal_graph.get_parents(&versioned.write_id)) <= 1
+                && branches(self.causal_graph.get_children(&versioned.write_id)) <= 1
+        };
+
+        // The head is never foldable, so every run ends inside the loop
+        let mut runs = Vec::new();
+        let mut run = Vec::new();
+        for (index, versioned) in chain.into_iter().enumerate() {
+            if foldable(index, &versioned) {
+                run.push(versioned);
+            } else if run.len() >= min_run {
+                runs.push(std::mem::take(&mut run));
+            } else {
+                run.clear();
+            }
+        }
+
+        let mut report = HistoryCompactionReport::default();
+        for run in runs {
+            let (last, folded) = run.split_last().expect("runs are never empty");
+            let first = &run[0];
+
+            let mut versions = 0;
+            let mut causes: Vec<String> = Vec::new();
+            let mut authors: Vec<String> = Vec::new();
+            for versioned in &run {
+                versions += versioned.summary.as_ref().map_or(1, |s| s.versions);
+                for cause in &versioned.causes {
+                    if !causes.contains(cause) {
+                        causes.push(cause.clone());
+                    }
+                }
+                let run_authors = versioned
+                    .summary
+                    .iter()
+                    .flat_map(|s| s.authors.iter())
+                    .chain(versioned.author.iter());
+                for author in run_authors {
+                    if !authors.contains(author) {
+                        authors.push(author.clone());
+                    }
+                }
+            }
+
+            let mut summary = last.clone();
+            summary.previous_version = first.previous_version.clone();
+            summary.summary = Some(VersionSummary {
+                versions,
+                first_write_id: first
+                    .summary
+                    .as_ref()
+                    .map_or_else(|| first.write_id.clone(), |s| s.first_write_id.clone()),
+                first_timestamp: first
+                    .summary
+                    .as_ref()
+                    .map_or(first.timestamp, |s| s.first_timestamp),
+                authors,
+            });
+
+            for versioned in folded {
+                self.version_store.remove(&versioned.write_id);
+                self.applied.remove(&versioned.write_id);
+                self.causal_graph.remove(&versioned.write_id);
+                self.reference_graph.remove(&versioned.write_id);
+            }
+            if let Some(previous) = &summary.previous_version {
+                self.causal_graph
+                    .add_edge(previous.clone(), last.write_id.clone());
+            }
+            let inherited: Vec<String> = causes
+                .iter()
+                .filter(|cause| !last.causes.contains(cause))
+                .cloned()
+                .collect();
+            self.link_causes(&last.write_id, &inherited);
+            summary.causes = causes;
+            self.version_store.insert(last.write_id.clone(), summary);
+
+            report.runs_compacted += 1;
+            report.versions_removed += folded.len();
+        }
+
+        if report.versions_removed > 0 {
+            let shared: HashSet<String> = self
+                .version_store
+                .iter()
+                .map(|entry| entry.value().distinction_id.clone())
+                .collect();
+            let values_before = self.value_store.len();
+            self.value_store.retain(|id, _| shared.contains(id));
+            report.values_collected = values_before - self.value_store.len();
+        }
+
+        Ok(report)
+    }
+
+    /// Index `field` of `namespace`, filling the index from current values.
+    ///
+    /// Returns the existing index if the field is already indexed. Writes
+    /// made while the index fills are not lost, but callers should keep
+    /// local writes out so a filling scan never records a stale value.
+    pub fn create_range_index(&self, namespace: &str, field: &str) -> Arc<RangeIndex> {
+        let (index, created) = self.range_indexes.insert(namespace, field);
+        if created {
+            for (key, versioned) in self.current_state.scan(namespace) {
+                index.seed(&key, versioned.value());
+            }
+        }
+        index
+    }
+
+    /// Get the index on `field` of `namespace`.
+    pub fn range_index(&self, namespace: &str, field: &str) -> Option<Arc<RangeIndex>> {
+        self.range_indexes.get(namespace, field)
+    }
+
+    /// Drop the index on `field` of `namespace`.
+    ///
+    /// Returns `false` if there was none.
+    pub fn drop_range_index(&self, namespace: &str, field: &str) -> bool {
+        self.range_indexes.remove(namespace, field)
+    }
+
+    /// Every range index.
+    pub fn range_indexes(&self) -> Vec<Arc<RangeIndex>> {
+        self.range_indexes.all()
+    }
+
+    /// Recreate the range indexes whose definitions are stored in
+    /// [`RANGE_INDEX_NAMESPACE`].
+    pub fn restore_range_indexes(&self) {
+        for (_, definition) in self.current_state.scan(RANGE_INDEX_NAMESPACE) {
+            if let (Some(namespace), Some(field)) = (
+                definition.str_field("namespace"),
+                definition.str_field("field"),
+            ) {
+                self.create_range_index(namespace, field);
+            }
+        }
+    }
+
+    /// Pick the keys `query` returns from a range index on `namespace`, if
+    /// one can answer it without a scan.
+    pub(crate) fn indexed_scan(&self, namespace: &str, query: &Query) -> Option<IndexScan> {
+        let sort = query.sort.first()?;
+        let index = self.range_indexes.get(namespace, &sort.field)?;
+        index.plan(query, self.current_state.namespace_len(namespace))
+    }
+
+    /// Check if a key exists in the storage.
+    pub fn contains_key(&self, namespace: impl Into<String>, key: impl Into<String>) -> bool {
+        let full_key = FullKey::new(namespace, key);
+        self.current_state.contains_key(&full_key)
+    }
+
+    /// Get the number of unique keys currently stored.
+    pub fn key_count(&self) -> usize {
+        self.current_state.len()
     }
 
     /// Get the total number of versions across all keys.
@@ -733,14 +1991,8 @@ impl CausalStorage {
 
     /// Get all namespaces currently in use.
     pub fn list_namespaces(&self) -> Vec<String> {
-        let mut namespaces: Vec<String> = self
-            .current_state
-            .iter()
-            .map(|entry| entry.key().namespace.clone())
-            .collect();
-
+        let mut namespaces = self.current_state.namespaces();
         namespaces.sort();
-        namespaces.dedup();
         namespaces
     }
 
@@ -748,9 +2000,9 @@ impl CausalStorage {
     pub fn list_keys(&self, namespace: &str) -> Vec<String> {
         let mut keys: Vec<String> = self
             .current_state
-            .iter()
-            .filter(|entry| entry.key().namespace == namespace)
-            .map(|entry| entry.key().key.clone())
+            .scan(namespace)
+            .into_iter()
+            .map(|(key, _)| key)
             .collect();
 
         keys.sort();
@@ -759,19 +2011,12 @@ impl CausalStorage {
 
     /// Scan all key-value pairs in a namespace.
     pub fn scan_collection(&self, namespace: &str) -> Vec<(String, VersionedValue)> {
-        self.current_state
-            .iter()
-            .filter(|entry| entry.key().namespace == namespace)
-            .map(|entry| (entry.key().key.clone(), entry.value().clone()))
-            .collect()
+        self.current_state.scan(namespace)
     }
 
     /// Scan all key-value pairs across all namespaces.
     pub fn scan_all(&self) -> Vec<(FullKey, VersionedValue)> {
-        self.current_state
-            .iter()
-            .map(|entry| (entry.key().clone(), entry.value().clone()))
-            .collect()
+        self.current_state.entries()
     }
 
     /// Access the causal graph (for advanced operations).
@@ -795,19 +2040,12 @@ impl CausalStorage {
         use std::collections::HashMap;
 
         // Snapshot current state
-        let current_state: HashMap<_, _> = self
-            .current_state
-            .iter()
-            .map(|entry| (entry.key().clone(), entry.value().clone()))
-            .collect();
+        let current_state: HashMap<_, _> = self.current_state.entries().into_iter().collect();
 
         // Build history log from causal graph - traverse and collect all versions
         let mut history_log: HashMap<FullKey, Vec<VersionedValue>> = HashMap::new();
 
-        for entry in self.current_state.iter() {
-            let key = entry.key().clone();
-            let current = entry.value().clone();
-
+        for (key, current) in self.current_state.entries() {
             // Traverse causal graph to collect all versions
             let mut history = Vec::new();
             let mut visited = std::collections::HashSet::new();
@@ -891,6 +2129,10 @@ impl CausalStorage {
                     storage.causal_graph.add_edge(parent.clone(), id.clone());
                 }
 
+                // Restore declared cross-key causes
+                storage.reference_graph.add_node(id.clone());
+                storage.link_causes(&id, &versioned.causes);
+
                 prev_id = Some(id);
             }
         }
@@ -916,6 +2158,242 @@ mod tests {
         CausalStorage::new(engine)
     }
 
+    #[test]
+    fn test_provenance_blames_fields() {
+        let storage = create_storage();
+        storage.set_origin_node("node-a");
+
+        let first = storage
+            .put("users", "alice", json!({"name": "Alice", "email": "a@old"}))
+            .unwrap();
+        thread::sleep(Duration::from_millis(2));
+        let second = storage
+            .put_attributed(
+                "users",
+                "alice",
+                json!({"name": "Alice", "email": "a@new", "age": 30}),
+                Some("editor".to_string()),
+                Vec::new(),
+            )
+            .unwrap();
+        thread::sleep(Duration::from_millis(2));
+        storage
+            .put("users", "alice", json!({"name": "Alice", "email": "a@new"}))
+            .unwrap();
+
+        let provenance = storage.provenance("users", "alice").unwrap();
+        assert_eq!(provenance.writes.len(), 3);
+        assert_eq!(provenance.blame("name").unwrap().write_id, first.write_id);
+        let email = provenance.blame("email").unwrap();
+        assert_eq!(email.write_id, second.write_id);
+        assert_eq!(email.author.as_deref(), Some("editor"));
+        assert_eq!(email.origin_node.as_deref(), Some("node-a"));
+        // Removed fields are no longer blamed
+        assert!(provenance.blame("age").is_none());
+
+        assert!(storage.provenance("users", "bob").is_err());
+    }
+
+    #[test]
+    fn test_collect_garbage_keeps_live_history() {
+        let storage = create_storage();
+        let v1 = storage.put("users", "alice", json!({"v": 1})).unwrap();
+        thread::sleep(Duration::from_millis(2));
+        let v2 = storage.put("users", "alice", json!({"v": 2})).unwrap();
+        let gone = storage.put("users", "bob", json!({"v": 3})).unwrap();
+        let cause = storage.put("orders", "1", json!({"v": 4})).unwrap();
+        storage
+            .put_attributed(
+                "invoices",
+                "1",
+                json!({}),
+                None,
+                vec![cause.write_id.clone()],
+            )
+            .unwrap();
+
+        storage
+            .delete_causal("users", "bob", VectorClock::new(), "node")
+            .unwrap();
+        storage
+            .delete_causal("orders", "1", VectorClock::new(), "node")
+            .unwrap();
+
+        let report = storage.collect_garbage(&[]);
+        // bob's version; the deletions themselves stay
+        assert_eq!(report.versions_collected, 1);
+        assert!(report.distinctions_collected > 0);
+        assert!(!storage.contains_version(&gone.write_id));
+        assert!(!storage.causal_graph().contains(&gone.write_id));
+        assert!(storage.has_tombstone("users", "bob"));
+
+        // Live history and declared causes survive
+        assert!(storage.contains_version(&v1.write_id));
+        assert!(storage.contains_version(&v2.write_id));
+        assert!(storage.contains_version(&cause.write_id));
+        assert_eq!(storage.history("users", "alice").unwrap().len(), 2);
+
+        // A second pass finds nothing
+        assert_eq!(storage.collect_garbage(&[]), GcReport::default());
+    }
+
+    #[test]
+    fn test_compact_history_folds_runs() {
+        let storage = create_storage();
+        let mut versions = Vec::new();
+        for n in 0..8 {
+            let author = (n % 2 == 0).then(|| format!("writer-{}", n));
+            let versioned = storage
+                .put_attributed("metrics", "cpu", json!({"load": n}), author, Vec::new())
+                .unwrap();
+            versions.push(versioned);
+            thread::sleep(Duration::from_millis(2));
+        }
+        // A version another write depends on is an endpoint worth keeping
+        storage
+            .put_attributed(
+                "alerts",
+                "1",
+                json!({}),
+                None,
+                vec![versions[3].write_id.clone()],
+            )
+            .unwrap();
+
+        let policy = CompactionPolicy::new().keep_recent(2);
+        let report = storage
+            .compact_history("metrics", "cpu", &policy, &[])
+            .unwrap();
+        assert_eq!(report.runs_compacted, 2);
+        assert_eq!(report.versions_removed, 2);
+        assert_eq!(report.values_collected, 2);
+
+        let history = storage.history("metrics", "cpu").unwrap();
+        assert_eq!(history.len(), 6);
+        let summary = history[1].summary.as_ref().unwrap();
+        assert_eq!(summary.versions, 2);
+        assert_eq!(summary.first_write_id, versions[1].write_id);
+        assert_eq!(summary.authors, vec!["writer-2".to_string()]);
+        assert_eq!(history[1].value, json!({"load": 2}));
+        assert!(history[2].summary.is_none());
+        assert!(!storage.contains_version(&versions[1].write_id));
+        assert!(storage.contains_version(&versions[3].write_id));
+
+        // The summary links straight back to the run's parent
+        let folded = storage.version(&versions[2].write_id).unwrap();
+        assert_eq!(
+            folded.previous_version(),
+            Some(versions[0].write_id.as_str())
+        );
+        let at = storage
+            .get_at("metrics", "cpu", versions[2].timestamp)
+            .unwrap();
+        assert_eq!(at.write_id, versions[2].write_id);
+        assert_eq!(
+            storage.get("metrics", "cpu").unwrap().write_id,
+            versions[7].write_id
+        );
+
+        // Folding a summary again merges its metadata
+        let policy = CompactionPolicy::new().keep_recent(1);
+        let report = storage
+            .compact_history("metrics", "cpu", &policy, &[])
+            .unwrap();
+        assert_eq!(report.runs_compacted, 1);
+        let summary = storage
+            .version(&versions[6].write_id)
+            .unwrap()
+            .summary
+            .unwrap();
+        assert_eq!(summary.versions, 3);
+        assert_eq!(summary.first_write_id, versions[4].write_id);
+        assert_eq!(
+            summary.authors,
+            vec!["writer-4".to_string(), "writer-6".to_string()]
+        );
+        assert!(
+            storage
+                .compact_history("metrics", "missing", &policy, &[])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_put_with_causes_links_versions() {
+        let storage = create_storage();
+        let order = storage.put("orders", "1", json!({"total": 10})).unwrap();
+        let customer = storage
+            .put("customers", "bob", json!({"name": "Bob"}))
+            .unwrap();
+
+        let invoice = storage
+            .put_attributed(
+                "invoices",
+                "1",
+                json!({"amount": 10}),
+                None,
+                vec![order.write_id.clone(), customer.write_id.clone()],
+            )
+            .unwrap();
+        assert_eq!(invoice.causes().len(), 2);
+
+        let causes = storage.causes("invoices", "1").unwrap();
+        assert_eq!(causes.len(), 2);
+        assert_eq!(causes[0].0, FullKey::new("orders", "1"));
+        assert_eq!(causes[1].0, FullKey::new("customers", "bob"));
+
+        let dependents = storage.dependents(&order.write_id);
+        assert_eq!(dependents.len(), 1);
+        assert_eq!(dependents[0].0, FullKey::new("invoices", "1"));
+        assert_eq!(dependents[0].1.write_id, invoice.write_id);
+
+        let unknown = storage.put_attributed(
+            "invoices",
+            "2",
+            json!({}),
+            None,
+            vec!["missing".to_string()],
+        );
+        assert!(matches!(unknown, Err(DeltaError::InvalidData { .. })));
+        assert!(!storage.contains_key("invoices", "2"));
+    }
+
+    #[test]
+    fn test_staged_batch_is_published_or_discarded() {
+        let storage = create_storage();
+        storage.put("users", "alice", json!(1)).unwrap();
+
+        let staged = storage
+            .stage_batch(vec![
+                ("users".to_string(), "alice".to_string(), json!(2)),
+                ("users".to_string(), "alice".to_string(), json!(3)),
+                ("users".to_string(), "bob".to_string(), json!(4)),
+            ])
+            .unwrap();
+
+        // Nothing is visible until the batch is published
+        assert_eq!(storage.get("users", "alice").unwrap().value(), &json!(1));
+        assert!(!storage.contains_key("users", "bob"));
+        assert_eq!(
+            staged[1].1.previous_version.as_deref(),
+            Some(staged[0].1.write_id())
+        );
+
+        storage.discard_batch(&staged);
+        assert!(
+            staged
+                .iter()
+                .all(|(_, v)| !storage.contains_version(v.write_id()))
+        );
+        assert_eq!(storage.history("users", "alice").unwrap().len(), 1);
+
+        let staged = storage
+            .stage_batch(vec![("users".to_string(), "bob".to_string(), json!(5))])
+            .unwrap();
+        storage.publish_batch(&staged);
+        assert_eq!(storage.get("users", "bob").unwrap().value(), &json!(5));
+    }
+
     #[test]
     fn test_put_and_get() {
         let storage = create_storage();
@@ -1086,4 +2564,38 @@ mod tests {
         // Causal graph should have 10 nodes
         assert_eq!(storage.total_version_count(), 10);
     }
+
+    #[test]
+    fn test_concurrent_writes_across_namespaces() {
+        let storage = Arc::new(create_storage());
+        let handles: Vec<_> = (0..8)
+            .map(|n| {
+                let storage = Arc::clone(&storage);
+                thread::spawn(move || {
+                    for i in 0..50 {
+                        storage
+                            .put(format!("ns{}", n), format!("key{}", i), json!(i))
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(storage.key_count(), 400);
+        assert_eq!(storage.list_namespaces().len(), 8);
+        assert_eq!(storage.scan_collection("ns3").len(), 50);
+        assert_eq!(storage.get("ns7", "key49").unwrap().value(), &json!(49));
+        assert_eq!(storage.sequence(), 400);
+
+        // A namespace whose keys are all deleted is no longer listed
+        storage.put("temp", "only", json!(1)).unwrap();
+        storage
+            .delete_causal("temp", "only", VectorClock::new(), "node")
+            .unwrap();
+        assert!(!storage.list_namespaces().contains(&"temp".to_string()));
+        assert!(storage.list_keys("temp").is_empty());
+    }
 }
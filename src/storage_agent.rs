@@ -460,6 +460,7 @@ impl StorageAgent {
                     timestamp: versioned.timestamp,
                     value: (*versioned.value).clone(),
                     version_id: write_id.clone(),
+                    summary: versioned.summary.clone(),
                 });
                 current_write_id = versioned.previous_version.clone();
             } else {
@@ -7,6 +7,16 @@
 /// - **Key-level**: Get notified when a specific key changes
 /// - **Filter-based**: Get notified when changes match a filter
 ///
+/// ## Ordering
+///
+/// Every change published for a key gets the next number in that key's
+/// [`sequence`](ChangeEvent::sequence), and each subscription receives a
+/// key's changes in sequence order. Changes to different keys may
+/// interleave. A subscriber that falls behind its channel loses events;
+/// [`SequenceTracker`] spots the resulting gaps per key so the consumer can
+/// re-read those keys. Sequences are kept in memory and restart at 1 when
+/// the database restarts, which a tracker also reports as a gap.
+///
 /// ## LCA Architecture
 ///
 /// SubscriptionAgent implements `LocalCausalAgent`, making all subscription operations
@@ -36,6 +46,9 @@ use crate::engine::SharedEngine;
 use crate::error::{DeltaError, DeltaResult};
 use crate::query::Filter;
 use crate::roots::KoruRoots;
+use crate::runtime::sync::broadcast;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::runtime::sync::{Mutex, MutexGuard};
 #[cfg(test)]
 use crate::types::VectorClock;
 use crate::types::VersionedValue;
@@ -44,13 +57,20 @@ use dashmap::DashMap;
 use koru_lambda_core::{Canonicalizable, Distinction, DistinctionEngine, LocalCausalAgent};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::hash::{BuildHasher, RandomState};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
-use tokio::sync::broadcast;
 
 /// Default channel capacity for subscription broadcasts.
 const DEFAULT_CHANNEL_CAPACITY: usize = 256;
 
+/// Number of locks that writers of different keys hash onto to publish
+/// their changes in the order they were stored.
+#[cfg(not(target_arch = "wasm32"))]
+const ORDERING_STRIPES: usize = 64;
+
 /// Unique identifier for a subscription.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct SubscriptionId(pub u64);
@@ -91,6 +111,11 @@ pub struct ChangeEvent {
     pub version_id: Option<String>,
     /// Previous version ID.
     pub previous_version_id: Option<String>,
+    /// Position among the changes published for this key, from 1; 0 for
+    /// events not published through a [`SubscriptionAgent`], such as
+    /// replayed history.
+    #[serde(default)]
+    pub sequence: u64,
 }
 
 impl ChangeEvent {
@@ -109,6 +134,7 @@ impl ChangeEvent {
             timestamp: value.timestamp(),
             version_id: Some(value.version_id().to_string()),
             previous_version_id: None,
+            sequence: 0,
         }
     }
 
@@ -128,6 +154,7 @@ impl ChangeEvent {
             timestamp: value.timestamp(),
             version_id: Some(value.version_id().to_string()),
             previous_version_id: Some(previous.version_id().to_string()),
+            sequence: 0,
         }
     }
 
@@ -146,6 +173,7 @@ impl ChangeEvent {
             timestamp: Utc::now(),
             version_id: None,
             previous_version_id: Some(previous.version_id().to_string()),
+            sequence: 0,
         }
     }
 }
@@ -285,6 +313,112 @@ pub struct SubscriptionInfo {
     pub events_delivered: u64,
 }
 
+/// Changes a subscriber missed for one key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SequenceGap {
+    /// The collection of the key
+    pub collection: String,
+    /// The key
+    pub key: String,
+    /// The sequence number that should have come next
+    pub expected: u64,
+    /// The sequence number that arrived instead
+    pub received: u64,
+}
+
+/// Spots gaps in the per-key sequences of received changes.
+///
+/// Feed it every event a receiver yields. A gap means changes to that key
+/// were lost, typically because the receiver lagged, so re-read the key;
+/// tracking carries on from the event that revealed the gap. The first
+/// event seen for a key sets its baseline, so subscribing partway through
+/// is not a gap, unless [`resync`](Self::resync) set one when the key was
+/// loaded.
+///
+/// Subscriptions with a value filter or restricted change types skip the
+/// numbers of changes they exclude, so a tracker reports those as gaps too.
+///
+/// ```ignore
+/// let mut tracker = SequenceTracker::new();
+/// while let Ok(event) = rx.recv().await {
+///     match tracker.observe(&event) {
+///         Some(gap) => reload(&gap.collection, &gap.key).await?,
+///         None => apply(event),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SequenceTracker {
+    last_seen: HashMap<(String, String), u64>,
+}
+
+impl SequenceTracker {
+    /// Create a tracker that has seen nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record `event`, returning the gap before it if it isn't the next
+    /// change to its key. Unsequenced events (sequence 0) are ignored.
+    pub fn observe(&mut self, event: &ChangeEvent) -> Option<SequenceGap> {
+        if event.sequence == 0 {
+            return None;
+        }
+        let last = self.last_seen.insert(
+            (event.collection.clone(), event.key.clone()),
+            event.sequence,
+        )?;
+        let expected = last + 1;
+        (event.sequence != expected).then(|| SequenceGap {
+            collection: event.collection.clone(),
+            key: event.key.clone(),
+            expected,
+            received: event.sequence,
+        })
+    }
+
+    /// Treat `sequence` as the last change seen for a key.
+    pub fn resync(&mut self, collection: &str, key: &str, sequence: u64) {
+        self.last_seen
+            .insert((collection.to_string(), key.to_string()), sequence);
+    }
+
+    /// The last sequence number seen for a key, if any.
+    pub fn last_seen(&self, collection: &str, key: &str) -> Option<u64> {
+        self.last_seen
+            .get(&(collection.to_string(), key.to_string()))
+            .copied()
+    }
+}
+
+/// Unsubscribes when dropped.
+///
+/// Ties a subscription to the lifetime of a connection, such as a
+/// streaming response that ends when the client goes away.
+#[derive(Debug)]
+pub struct SubscriptionGuard {
+    agent: Arc<SubscriptionAgent>,
+    id: SubscriptionId,
+}
+
+impl SubscriptionGuard {
+    /// Guard an existing subscription.
+    pub fn new(agent: Arc<SubscriptionAgent>, id: SubscriptionId) -> Self {
+        Self { agent, id }
+    }
+
+    /// The guarded subscription ID.
+    pub fn id(&self) -> SubscriptionId {
+        self.id
+    }
+}
+
+impl Drop for SubscriptionGuard {
+    fn drop(&mut self) {
+        let _ = self.agent.unsubscribe(self.id);
+    }
+}
+
 /// Internal subscription state.
 #[derive(Debug)]
 struct SubscriptionState {
@@ -312,6 +446,14 @@ pub struct SubscriptionAgent {
     subscriptions: DashMap<SubscriptionId, SubscriptionState>,
     next_id: AtomicU64,
     channel_capacity: usize,
+
+    /// Last sequence number published for each key
+    sequences: DashMap<(String, String), u64>,
+    /// Held by writers from storing a change until it is published
+    #[cfg(not(target_arch = "wasm32"))]
+    ordering: Vec<Mutex<()>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    ordering_hasher: RandomState,
 }
 
 impl SubscriptionAgent {
@@ -336,6 +478,11 @@ impl SubscriptionAgent {
             subscriptions: DashMap::new(),
             next_id: AtomicU64::new(1),
             channel_capacity: capacity,
+            sequences: DashMap::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            ordering: (0..ORDERING_STRIPES).map(|_| Mutex::new(())).collect(),
+            #[cfg(not(target_arch = "wasm32"))]
+            ordering_hasher: RandomState::new(),
         }
     }
 
@@ -424,8 +571,18 @@ impl SubscriptionAgent {
 
     /// Notify subscribers of a change.
     ///
-    /// This is called by the storage layer when data changes.
-    pub fn notify(&self, event: ChangeEvent) {
+    /// This is called by the storage layer when data changes. The event is
+    /// given the key's next sequence number, and is sent to every
+    /// subscription before another change to the key can be, so each
+    /// subscription receives the key's changes in sequence order.
+    pub fn notify(&self, mut event: ChangeEvent) {
+        let mut sequence = self
+            .sequences
+            .entry((event.collection.clone(), event.key.clone()))
+            .or_insert(0);
+        *sequence += 1;
+        event.sequence = *sequence;
+
         for entry in self.subscriptions.iter() {
             let state = entry.value();
             if state.subscription.matches(&event) {
@@ -437,6 +594,57 @@ impl SubscriptionAgent {
         }
     }
 
+    /// The sequence number of the last change published for a key (0 if
+    /// none has been).
+    pub fn sequence(&self, collection: &str, key: &str) -> u64 {
+        self.sequences
+            .get(&(collection.to_string(), key.to_string()))
+            .map_or(0, |sequence| *sequence)
+    }
+
+    /// Lock a key's changes into publication order.
+    ///
+    /// Hold the guard from reading the previous value until the change is
+    /// [notified](Self::notify), so concurrent writers to the key publish
+    /// in the order their writes were stored. Keys share a fixed set of
+    /// locks, so unrelated keys may occasionally wait on each other.
+    ///
+    /// Not available on wasm, whose single-threaded mutex can't wait for a
+    /// holder suspended at an `.await`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn order_key(&self, collection: &str, key: &str) -> MutexGuard<'_, ()> {
+        self.ordering[self.ordering_stripe(collection, key)]
+            .lock()
+            .await
+    }
+
+    /// Lock several keys of a collection into publication order at once.
+    ///
+    /// Same as [`order_key`](Self::order_key) for each key, but each shared
+    /// lock is taken once and in a fixed order, so batches over overlapping
+    /// keys can't deadlock each other.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn order_keys<'k>(
+        &self,
+        collection: &str,
+        keys: impl IntoIterator<Item = &'k str>,
+    ) -> Vec<MutexGuard<'_, ()>> {
+        let stripes: std::collections::BTreeSet<usize> = keys
+            .into_iter()
+            .map(|key| self.ordering_stripe(collection, key))
+            .collect();
+        let mut guards = Vec::with_capacity(stripes.len());
+        for stripe in stripes {
+            guards.push(self.ordering[stripe].lock().await);
+        }
+        guards
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn ordering_stripe(&self, collection: &str, key: &str) -> usize {
+        self.ordering_hasher.hash_one((collection, key)) as usize % self.ordering.len()
+    }
+
     /// Notify subscribers of an insert.
     pub fn notify_insert(
         &self,
@@ -797,6 +1005,53 @@ mod tests {
         assert_eq!(e2.key, "key");
     }
 
+    #[tokio::test]
+    async fn test_sequences_and_gaps() {
+        use crate::engine::SharedEngine;
+        use crate::runtime::sync::broadcast::error::RecvError;
+        let field = SharedEngine::new();
+        let manager = SubscriptionAgent::with_capacity(&field, 2);
+        let (_id, mut rx) = manager.subscribe(Subscription::collection("users"));
+        let mut tracker = SequenceTracker::new();
+
+        let value = create_test_value(json!({"name": "Alice"}));
+        manager.notify_insert("users", "alice", &value);
+        let first = rx.recv().await.unwrap();
+        assert_eq!(first.sequence, 1);
+        assert_eq!(tracker.observe(&first), None);
+
+        // Overflow the channel: the two oldest updates are lost
+        for _ in 0..4 {
+            manager.notify_update("users", "alice", &value, &value);
+        }
+        assert!(matches!(rx.recv().await, Err(RecvError::Lagged(2))));
+        let next = rx.recv().await.unwrap();
+        assert_eq!(
+            tracker.observe(&next),
+            Some(SequenceGap {
+                collection: "users".to_string(),
+                key: "alice".to_string(),
+                expected: 2,
+                received: 4,
+            })
+        );
+        assert_eq!(tracker.observe(&rx.recv().await.unwrap()), None);
+        assert_eq!(tracker.last_seen("users", "alice"), Some(5));
+        assert_eq!(manager.sequence("users", "alice"), 5);
+
+        // Keys are numbered independently
+        manager.notify_insert("users", "bob", &value);
+        let bob = rx.recv().await.unwrap();
+        assert_eq!(bob.sequence, 1);
+        assert_eq!(tracker.observe(&bob), None);
+
+        // A baseline set on load makes a later event's gap visible
+        tracker.resync("users", "carol", 3);
+        manager.notify_insert("users", "carol", &value);
+        let carol = rx.recv().await.unwrap();
+        assert_eq!(tracker.observe(&carol).map(|gap| gap.expected), Some(4));
+    }
+
     #[test]
     fn test_subscription_info() {
         use crate::engine::SharedEngine;
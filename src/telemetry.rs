@@ -0,0 +1,80 @@
+/// OpenTelemetry trace export.
+///
+/// Database operations are instrumented with `tracing` spans (`put`, `get`,
+/// `query`, `merge_versions`, `sync_with_peer`, `anti_entropy`,
+/// `replicate_write`, ...) carrying `namespace`, `key`, `version_id` and
+/// `peer` attributes. This module forwards those spans to an OTLP collector
+/// such as Jaeger or Grafana Tempo.
+///
+/// # Example
+///
+/// ```ignore
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     // Keep the guard alive; dropping it flushes pending spans
+///     let _telemetry = koru_delta::telemetry::init_otlp("koru-delta", "http://localhost:4317")?;
+///
+///     let db = koru_delta::KoruDelta::start().await?;
+///     db.put("users", "alice", koru_delta::json!({"name": "Alice"})).await?;
+///     Ok(())
+/// }
+/// ```
+use crate::error::{DeltaError, DeltaResult};
+use opentelemetry::KeyValue;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::trace::TracerProvider;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Flushes and shuts down the exporter when dropped.
+#[must_use = "dropping the guard shuts down trace export"]
+pub struct TelemetryGuard {
+    provider: TracerProvider,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("Failed to shut down trace exporter: {}", e);
+        }
+    }
+}
+
+/// Install a global subscriber that logs like [`init_logging`](crate::init_logging)
+/// and exports spans over OTLP/gRPC to `endpoint` (e.g. `http://localhost:4317`).
+///
+/// Spans are tagged with `service.name = service_name`. The `KORU_LOG`
+/// environment variable filters both logs and exported spans.
+///
+/// Must be called from within a Tokio runtime; spans are exported in
+/// batches on a background task.
+pub fn init_otlp(service_name: &str, endpoint: &str) -> DeltaResult<TelemetryGuard> {
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint)
+        .build()
+        .map_err(|e| DeltaError::StorageError(format!("Failed to build OTLP exporter: {}", e)))?;
+
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+        .with_resource(Resource::new(vec![KeyValue::new(
+            "service.name",
+            service_name.to_string(),
+        )]))
+        .build();
+    let tracer = provider.tracer("koru-delta");
+
+    let filter = EnvFilter::try_from_env("KORU_LOG").unwrap_or_else(|_| EnvFilter::new("info"));
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer().with_target(false))
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .try_init()
+        .map_err(|e| DeltaError::StorageError(format!("Failed to install subscriber: {}", e)))?;
+
+    Ok(TelemetryGuard { provider })
+}
@@ -0,0 +1,765 @@
+/// Multi-tenant isolation.
+///
+/// One node can serve several applications by giving each a [`Tenant`]. A
+/// tenant handle works like the database itself, except that:
+///
+/// - **Namespaces are prefixed** with the tenant name (`users` in tenant
+///   `acme` is stored as `acme/users`). Every namespace a handle touches gets
+///   the prefix, so a tenant can't name, list or query another tenant's data,
+///   nor the database's internal namespaces.
+/// - **Capabilities are scoped**: [`Tenant::grant_capability`] prefixes the
+///   resource pattern, and a handle from [`Tenant::as_identity`] authorizes
+///   every operation against the prefixed namespaces.
+/// - **Quotas** cap the live keys and value bytes a tenant holds; writes over
+///   quota fail with [`DeltaError::WriteRejected`]. They can also bound each
+///   query's work with [`QueryLimits`], tightening the database's own.
+/// - **Metrics** count reads, writes, deletes, queries and rejected writes
+///   per tenant, reported with its usage by [`Tenant::stats`].
+///
+/// Tenants are registered with
+/// [`KoruDelta::create_tenant`](crate::KoruDelta::create_tenant), which
+/// persists the quota, and opened with
+/// [`KoruDelta::tenant`](crate::KoruDelta::tenant). The database itself is
+/// not a tenant: it sees every namespace and bypasses quotas, so keep it to
+/// operators.
+///
+/// ```ignore
+/// let acme = db.create_tenant("acme", TenantQuota { max_keys: 10_000, ..Default::default() }).await?;
+/// acme.put("users", "alice", json!({"plan": "pro"})).await?; // stored in acme/users
+///
+/// let globex = db.create_tenant("globex", TenantQuota::default()).await?;
+/// assert!(globex.get("users", "alice").await.is_err());
+/// ```
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::auth::{AuthenticatedDelta, Capability, Identity, Permission, ResourcePattern};
+use crate::core::KoruDeltaGeneric;
+use crate::error::{DeltaError, DeltaResult};
+use crate::query::{Query, QueryLimits, QueryResult};
+use crate::runtime::Runtime;
+use crate::runtime::sync::{Mutex, MutexGuard};
+use crate::storage::CausalStorage;
+use crate::types::{HistoryEntry, VersionedValue};
+
+/// Namespace where tenant registrations (name → quota) are stored.
+pub const TENANT_NAMESPACE: &str = "_tenants";
+
+/// Separates the tenant name from the namespace in storage: `acme/users`.
+pub const TENANT_SEPARATOR: char = '/';
+
+/// Limits on what a tenant may store.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TenantQuota {
+    /// Maximum number of live keys (0 = unlimited)
+    pub max_keys: usize,
+    /// Maximum total size of live values, as JSON, in bytes (0 = unlimited)
+    pub max_bytes: usize,
+    /// Bounds on each query the tenant runs
+    #[serde(default)]
+    pub query_limits: QueryLimits,
+}
+
+impl TenantQuota {
+    fn is_unlimited(&self) -> bool {
+        self.max_keys == 0 && self.max_bytes == 0
+    }
+}
+
+/// A tenant's usage and activity.
+///
+/// Usage is counted from storage; activity counts operations through tenant
+/// handles since the database started.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TenantStats {
+    /// Namespaces holding the tenant's data
+    pub namespaces: usize,
+    /// Live keys across those namespaces
+    pub keys: usize,
+    /// Total size of live values, as JSON, in bytes
+    pub bytes: usize,
+    /// Reads (`get`, `get_at`, `history`, `contains`, `list_keys`)
+    pub reads: u64,
+    /// Writes applied
+    pub writes: u64,
+    /// Deletes applied
+    pub deletes: u64,
+    /// Queries run
+    pub queries: u64,
+    /// Writes rejected for exceeding the quota
+    pub rejected_writes: u64,
+}
+
+/// Live keys and value bytes held by a tenant.
+#[derive(Debug, Clone, Copy, Default)]
+struct Usage {
+    keys: usize,
+    bytes: usize,
+}
+
+/// Quota, usage and metrics of one tenant, shared by all its handles.
+pub(crate) struct TenantState {
+    quota: RwLock<TenantQuota>,
+    /// Usage as of the last write under a quota, counted from storage when
+    /// `None`. Held across quota-checked writes so they apply one at a time.
+    usage: Mutex<Option<Usage>>,
+    reads: AtomicU64,
+    writes: AtomicU64,
+    deletes: AtomicU64,
+    queries: AtomicU64,
+    rejected_writes: AtomicU64,
+}
+
+impl TenantState {
+    fn new(quota: TenantQuota) -> Self {
+        Self {
+            quota: RwLock::new(quota),
+            usage: Mutex::new(None),
+            reads: AtomicU64::new(0),
+            writes: AtomicU64::new(0),
+            deletes: AtomicU64::new(0),
+            queries: AtomicU64::new(0),
+            rejected_writes: AtomicU64::new(0),
+        }
+    }
+}
+
+/// State of the tenants opened on a database, by name.
+#[derive(Default)]
+pub(crate) struct Tenants {
+    states: RwLock<HashMap<String, Arc<TenantState>>>,
+}
+
+impl Tenants {
+    /// Shared state of `name`, created with `quota` if it has none yet.
+    pub(crate) fn state(&self, name: &str, quota: TenantQuota) -> Arc<TenantState> {
+        if let Some(state) = self.states.read().unwrap().get(name) {
+            return Arc::clone(state);
+        }
+        let mut states = self.states.write().unwrap();
+        Arc::clone(
+            states
+                .entry(name.to_string())
+                .or_insert_with(|| Arc::new(TenantState::new(quota))),
+        )
+    }
+}
+
+/// Check that `name` can be used as a tenant name.
+///
+/// Names are ASCII letters, digits, `-` and `_`, and may not start with `_`,
+/// which marks the database's own namespaces.
+pub fn validate_tenant_name(name: &str) -> DeltaResult<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(DeltaError::InvalidData {
+            reason: format!(
+                "Invalid tenant name '{}': use letters, digits, '-' and '_', not starting with '_'",
+                name
+            ),
+        })
+    }
+}
+
+/// A database handle confined to one tenant.
+///
+/// Created with [`KoruDelta::tenant`](crate::KoruDelta::tenant) or
+/// [`KoruDelta::create_tenant`](crate::KoruDelta::create_tenant).
+/// Namespaces passed to it are the tenant's own; they are prefixed with the
+/// tenant name before reaching storage.
+#[derive(Clone)]
+pub struct Tenant<R: Runtime> {
+    db: KoruDeltaGeneric<R>,
+    name: String,
+    state: Arc<TenantState>,
+    identity: Option<AuthenticatedDelta<R>>,
+}
+
+impl<R: Runtime> std::fmt::Debug for Tenant<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tenant")
+            .field("name", &self.name)
+            .field("identity", &self.identity)
+            .finish()
+    }
+}
+
+impl<R: Runtime> Tenant<R> {
+    pub(crate) fn new(db: KoruDeltaGeneric<R>, name: String, state: Arc<TenantState>) -> Self {
+        Self {
+            db,
+            name,
+            state,
+            identity: None,
+        }
+    }
+
+    /// Name of the tenant.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The tenant's current quota.
+    pub fn quota(&self) -> TenantQuota {
+        *self.state.quota.read().unwrap()
+    }
+
+    /// Change the tenant's quota.
+    ///
+    /// Data already over the new quota stays; further writes that would
+    /// grow it are rejected.
+    pub async fn set_quota(&self, quota: TenantQuota) -> DeltaResult<()> {
+        self.db.put(TENANT_NAMESPACE, &self.name, quota).await?;
+        *self.state.quota.write().unwrap() = quota;
+        *self.state.usage.lock().await = None;
+        Ok(())
+    }
+
+    /// Storage namespace for the tenant's `namespace`: `acme/users`.
+    ///
+    /// Fails with `InvalidData` for empty namespaces and namespaces
+    /// containing `:`, which capability patterns use as a separator.
+    pub fn qualify(&self, namespace: &str) -> DeltaResult<String> {
+        if namespace.is_empty() || namespace.contains(':') {
+            return Err(DeltaError::InvalidData {
+                reason: format!(
+                    "Invalid namespace '{}' for tenant '{}': must be non-empty and free of ':'",
+                    namespace, self.name
+                ),
+            });
+        }
+        Ok(format!("{}{}{}", self.name, TENANT_SEPARATOR, namespace))
+    }
+
+    /// Confine a capability pattern to the tenant's namespaces.
+    ///
+    /// `Namespace("users")` becomes `Namespace("acme/users")`, and exact
+    /// and wildcard patterns get the same prefix, so an empty wildcard
+    /// covers the whole tenant and nothing else.
+    pub fn scope(&self, pattern: ResourcePattern) -> DeltaResult<ResourcePattern> {
+        Ok(match pattern {
+            ResourcePattern::Namespace(namespace) => {
+                ResourcePattern::Namespace(self.qualify(&namespace)?)
+            }
+            ResourcePattern::Exact(resource) => {
+                let Some((namespace, key)) = resource.split_once(':') else {
+                    return Err(DeltaError::InvalidData {
+                        reason: format!("Invalid resource '{}': expected namespace:key", resource),
+                    });
+                };
+                ResourcePattern::Exact(format!("{}:{}", self.qualify(namespace)?, key))
+            }
+            ResourcePattern::Wildcard { prefix } => ResourcePattern::Wildcard {
+                prefix: format!("{}{}{}", self.name, TENANT_SEPARATOR, prefix),
+            },
+        })
+    }
+
+    /// Grant `grantee` a capability on the tenant's resources matching
+    /// `pattern` (see [`scope`](Self::scope)).
+    pub fn grant_capability(
+        &self,
+        granter: &Identity,
+        granter_secret_key: &[u8],
+        grantee: &str,
+        pattern: ResourcePattern,
+        permission: Permission,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> DeltaResult<Capability> {
+        let pattern = self.scope(pattern)?;
+        self.db
+            .auth()
+            .grant_capability(
+                granter,
+                granter_secret_key,
+                grantee,
+                pattern,
+                permission,
+                expires_at,
+            )
+            .map_err(|e| DeltaError::Unauthorized {
+                reason: e.to_string(),
+            })
+    }
+
+    /// Act as the identity behind an authenticated session, within the
+    /// tenant.
+    ///
+    /// Every operation on the returned handle is authorized against the
+    /// identity's capabilities on the prefixed namespaces, as with
+    /// [`KoruDelta::as_identity`](crate::KoruDelta::as_identity).
+    pub fn as_identity(&self, session_id: &str) -> DeltaResult<Self> {
+        Ok(Self {
+            identity: Some(self.db.as_identity(session_id)?),
+            ..self.clone()
+        })
+    }
+
+    /// Store a value, subject to the tenant's quota.
+    pub async fn put<T: Serialize>(
+        &self,
+        namespace: &str,
+        key: &str,
+        value: T,
+    ) -> DeltaResult<VersionedValue> {
+        let qualified = self.qualify(namespace)?;
+        let value = serde_json::to_value(value)?;
+        let reservation = self.reserve(namespace, &qualified, key, &value).await?;
+        let versioned = match &self.identity {
+            Some(identity) => identity.put(qualified, key, value).await?,
+            None => self.db.put(qualified, key, value).await?,
+        };
+        commit(reservation);
+        self.state.writes.fetch_add(1, Ordering::Relaxed);
+        Ok(versioned)
+    }
+
+    /// Retrieve the current value.
+    pub async fn get(&self, namespace: &str, key: &str) -> DeltaResult<VersionedValue> {
+        let namespace = self.qualify(namespace)?;
+        self.state.reads.fetch_add(1, Ordering::Relaxed);
+        match &self.identity {
+            Some(identity) => identity.get(namespace, key).await,
+            None => self.db.get(namespace, key).await,
+        }
+    }
+
+    /// Retrieve the value as of a point in time.
+    pub async fn get_at(
+        &self,
+        namespace: &str,
+        key: &str,
+        timestamp: DateTime<Utc>,
+    ) -> DeltaResult<VersionedValue> {
+        let namespace = self.qualify(namespace)?;
+        self.state.reads.fetch_add(1, Ordering::Relaxed);
+        match &self.identity {
+            Some(identity) => identity.get_at(&namespace, key, timestamp).await,
+            None => self.db.get_at(&namespace, key, timestamp).await,
+        }
+    }
+
+    /// Get the full history of a key.
+    pub async fn history(&self, namespace: &str, key: &str) -> DeltaResult<Vec<HistoryEntry>> {
+        let namespace = self.qualify(namespace)?;
+        self.state.reads.fetch_add(1, Ordering::Relaxed);
+        match &self.identity {
+            Some(identity) => identity.history(&namespace, key).await,
+            None => self.db.history(&namespace, key).await,
+        }
+    }
+
+    /// Check whether a key exists.
+    pub async fn contains(&self, namespace: &str, key: &str) -> DeltaResult<bool> {
+        let namespace = self.qualify(namespace)?;
+        self.state.reads.fetch_add(1, Ordering::Relaxed);
+        match &self.identity {
+            Some(identity) => identity.contains(&namespace, key).await,
+            None => Ok(self.db.contains(namespace, key).await),
+        }
+    }
+
+    /// Delete a key.
+    pub async fn delete(&self, namespace: &str, key: &str) -> DeltaResult<()> {
+        let qualified = self.qualify(namespace)?;
+        let reservation = self
+            .reserve(namespace, &qualified, key, &JsonValue::Null)
+            .await?;
+        match &self.identity {
+            Some(identity) => identity.delete(&qualified, key).await?,
+            None => self.db.delete(&qualified, key).await?,
+        }
+        commit(reservation);
+        self.state.deletes.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// List the keys in one of the tenant's namespaces.
+    pub async fn list_keys(&self, namespace: &str) -> DeltaResult<Vec<String>> {
+        let namespace = self.qualify(namespace)?;
+        self.state.reads.fetch_add(1, Ordering::Relaxed);
+        match &self.identity {
+            Some(identity) => identity.list_keys(&namespace).await,
+            None => Ok(self.db.list_keys(&namespace).await),
+        }
+    }
+
+    /// List the tenant's namespaces, without the tenant prefix.
+    pub async fn list_namespaces(&self) -> Vec<String> {
+        let prefix = self.prefix();
+        self.db
+            .list_namespaces()
+            .await
+            .into_iter()
+            .filter_map(|namespace| namespace.strip_prefix(&prefix).map(str::to_string))
+            .collect()
+    }
+
+    /// Query one of the tenant's namespaces.
+    ///
+    /// The tenant's [`query_limits`](TenantQuota::query_limits) apply on top
+    /// of the query's own.
+    pub async fn query(&self, namespace: &str, mut query: Query) -> DeltaResult<QueryResult> {
+        let namespace = self.qualify(namespace)?;
+        self.state.queries.fetch_add(1, Ordering::Relaxed);
+        query.limits = query.limits.within(self.quota().query_limits);
+        match &self.identity {
+            Some(identity) => identity.query(&namespace, query),
+            None => self.db.query(&namespace, query).await,
+        }
+    }
+
+    /// The tenant's usage and activity.
+    ///
+    /// Recounts usage from storage, which also picks up writes made to the
+    /// tenant's namespaces without going through a tenant handle.
+    pub async fn stats(&self) -> TenantStats {
+        let storage = self.db.storage();
+        let usage = measure(storage, &self.prefix());
+        *self.state.usage.lock().await = Some(usage);
+
+        let state = &self.state;
+        TenantStats {
+            namespaces: self.list_namespaces().await.len(),
+            keys: usage.keys,
+            bytes: usage.bytes,
+            reads: state.reads.load(Ordering::Relaxed),
+            writes: state.writes.load(Ordering::Relaxed),
+            deletes: state.deletes.load(Ordering::Relaxed),
+            queries: state.queries.load(Ordering::Relaxed),
+            rejected_writes: state.rejected_writes.load(Ordering::Relaxed),
+        }
+    }
+
+    fn prefix(&self) -> String {
+        format!("{}{}", self.name, TENANT_SEPARATOR)
+    }
+
+    /// Check that writing `value` (`null` for deletes) to `key` keeps the
+    /// tenant within its quota.
+    ///
+    /// Under a quota, holds the tenant's usage until the write is committed,
+    /// so checked writes apply one at a time. Writes that don't grow the
+    /// tenant are always allowed.
+    async fn reserve(
+        &self,
+        namespace: &str,
+        qualified: &str,
+        key: &str,
+        value: &JsonValue,
+    ) -> DeltaResult<Reservation<'_>> {
+        let quota = self.quota();
+        if quota.is_unlimited() {
+            return Ok(None);
+        }
+
+        let mut usage = self.state.usage.lock().await;
+        let storage = self.db.storage();
+        let current = usage.unwrap_or_else(|| measure(storage, &self.prefix()));
+        let old = storage
+            .get(qualified, key)
+            .ok()
+            .filter(|versioned| !versioned.value().is_null())
+            .map(|versioned| value_size(versioned.value()));
+        let new = (!value.is_null()).then(|| value_size(value));
+        let after = Usage {
+            keys: current.keys.saturating_sub(usize::from(old.is_some()))
+                + usize::from(new.is_some()),
+            bytes: current.bytes.saturating_sub(old.unwrap_or(0)) + new.unwrap_or(0),
+        };
+
+        let reason =
+            if quota.max_keys > 0 && after.keys > quota.max_keys && after.keys > current.keys {
+                Some(format!(
+                    "tenant '{}' would exceed its quota of {} keys",
+                    self.name, quota.max_keys
+                ))
+            } else if quota.max_bytes > 0
+                && after.bytes > quota.max_bytes
+                && after.bytes > current.bytes
+            {
+                Some(format!(
+                    "tenant '{}' would exceed its quota of {} bytes",
+                    self.name, quota.max_bytes
+                ))
+            } else {
+                None
+            };
+        *usage = Some(current);
+        if let Some(reason) = reason {
+            self.state.rejected_writes.fetch_add(1, Ordering::Relaxed);
+            return Err(DeltaError::WriteRejected {
+                namespace: namespace.to_string(),
+                key: key.to_string(),
+                reason,
+            });
+        }
+        Ok(Some((usage, after)))
+    }
+}
+
+/// Usage held during a quota-checked write, and the usage after it.
+type Reservation<'a> = Option<(MutexGuard<'a, Option<Usage>>, Usage)>;
+
+/// Record the usage of a write that was applied.
+fn commit(reservation: Reservation<'_>) {
+    if let Some((mut usage, after)) = reservation {
+        *usage = Some(after);
+    }
+}
+
+/// Count the live keys and value bytes in namespaces starting with `prefix`.
+fn measure(storage: &CausalStorage, prefix: &str) -> Usage {
+    let mut usage = Usage::default();
+    for namespace in storage.list_namespaces() {
+        if !namespace.starts_with(prefix) {
+            continue;
+        }
+        for (_, versioned) in storage.scan_collection(&namespace) {
+            if !versioned.value().is_null() {
+                usage.keys += 1;
+                usage.bytes += value_size(versioned.value());
+            }
+        }
+    }
+    usage
+}
+
+fn value_size(value: &JsonValue) -> usize {
+    serde_json::to_vec(value).map_or(0, |bytes| bytes.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KoruDelta;
+    use crate::auth::{IdentityUserData, create_challenge_response};
+    use serde_json::json;
+
+    /// Create an identity with a live session; returns (identity, secret key, session id).
+    fn login(db: &KoruDelta) -> (Identity, Vec<u8>, String) {
+        let auth = db.auth();
+        let (identity, secret_key) = auth.create_identity(IdentityUserData::default()).unwrap();
+        let challenge = auth.create_challenge(&identity.public_key).unwrap();
+        let response = create_challenge_response(&secret_key, &challenge).unwrap();
+        let session = auth
+            .verify_and_create_session(&identity.public_key, &challenge, &response)
+            .unwrap();
+        (identity, secret_key, session.session_id)
+    }
+
+    #[tokio::test]
+    async fn test_tenants_are_isolated() {
+        let db = KoruDelta::start().await.unwrap();
+        let acme = db
+            .create_tenant("acme", TenantQuota::default())
+            .await
+            .unwrap();
+        let globex = db
+            .create_tenant("globex", TenantQuota::default())
+            .await
+            .unwrap();
+
+        acme.put("users", "alice", json!({"plan": "pro"}))
+            .await
+            .unwrap();
+        assert_eq!(
+            acme.get("users", "alice").await.unwrap().value(),
+            &json!({"plan": "pro"})
+        );
+        assert!(globex.get("users", "alice").await.is_err());
+        assert!(!globex.contains("users", "alice").await.unwrap());
+        assert!(
+            globex
+                .query("users", Query::new())
+                .await
+                .unwrap()
+                .records
+                .is_empty()
+        );
+
+        // Namespaces are prefixed in storage and unprefixed for the tenant
+        assert!(db.contains("acme/users", "alice").await);
+        assert_eq!(acme.list_namespaces().await, vec!["users"]);
+        assert!(globex.list_namespaces().await.is_empty());
+        assert!(acme.get("users:x", "alice").await.is_err());
+
+        assert_eq!(db.list_tenants().await, vec!["acme", "globex"]);
+        assert_eq!(
+            db.tenant("acme")
+                .await
+                .unwrap()
+                .list_keys("users")
+                .await
+                .unwrap(),
+            vec!["alice"]
+        );
+        assert!(matches!(
+            db.tenant("initech").await,
+            Err(DeltaError::KeyNotFound { .. })
+        ));
+        for name in ["", "_system", "a/b", "a:b"] {
+            assert!(
+                db.create_tenant(name, TenantQuota::default())
+                    .await
+                    .is_err()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_quota_limits_writes() {
+        let db = KoruDelta::start().await.unwrap();
+        let acme = db
+            .create_tenant(
+                "acme",
+                TenantQuota {
+                    max_keys: 2,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        acme.put("notes", "a", json!(1)).await.unwrap();
+        acme.put("notes", "b", json!(2)).await.unwrap();
+        assert!(matches!(
+            acme.put("notes", "c", json!(3)).await,
+            Err(DeltaError::WriteRejected { .. })
+        ));
+
+        // Overwrites don't grow the tenant, and deletes make room
+        acme.put("notes", "a", json!(10)).await.unwrap();
+        acme.delete("notes", "b").await.unwrap();
+        acme.put("other", "c", json!(3)).await.unwrap();
+
+        let stats = acme.stats().await;
+        assert_eq!(stats.keys, 2);
+        assert_eq!(stats.namespaces, 2);
+        assert_eq!(stats.writes, 4);
+        assert_eq!(stats.deletes, 1);
+        assert_eq!(stats.rejected_writes, 1);
+
+        // Quotas survive reopening the tenant and can be raised
+        let reopened = db.tenant("acme").await.unwrap();
+        assert_eq!(reopened.quota().max_keys, 2);
+        assert!(reopened.put("notes", "d", json!(4)).await.is_err());
+        reopened
+            .set_quota(TenantQuota {
+                max_keys: 3,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        reopened.put("notes", "d", json!(4)).await.unwrap();
+        assert_eq!(acme.stats().await.rejected_writes, 2);
+    }
+
+    #[tokio::test]
+    async fn test_quota_limits_queries() {
+        let db = KoruDelta::start().await.unwrap();
+        let acme = db
+            .create_tenant(
+                "acme",
+                TenantQuota {
+                    query_limits: QueryLimits {
+                        max_scanned: 3,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        for i in 0..5 {
+            acme.put("events", &format!("e{}", i), json!({"n": i}))
+                .await
+                .unwrap();
+        }
+
+        assert!(matches!(
+            acme.query("events", Query::new()).await,
+            Err(DeltaError::QueryLimitExceeded { max: 3, .. })
+        ));
+        let partial = acme
+            .query("events", Query::new().allow_partial(true))
+            .await
+            .unwrap();
+        assert!(partial.partial);
+        assert_eq!(partial.total_count, 3);
+
+        // The database itself isn't bound by the tenant's limits
+        let all = db.query("acme/events", Query::new()).await.unwrap();
+        assert_eq!(all.total_count, 5);
+    }
+
+    #[tokio::test]
+    async fn test_capabilities_are_scoped_to_tenant() {
+        let db = KoruDelta::start().await.unwrap();
+        let (owner, owner_key, _) = login(&db);
+        let (reader, _, session_id) = login(&db);
+        let acme = db
+            .create_tenant("acme", TenantQuota::default())
+            .await
+            .unwrap();
+        let globex = db
+            .create_tenant("globex", TenantQuota::default())
+            .await
+            .unwrap();
+        acme.put("docs", "a", json!(1)).await.unwrap();
+        globex.put("docs", "a", json!(2)).await.unwrap();
+        db.put("docs", "a", json!(3)).await.unwrap();
+
+        acme.grant_capability(
+            &owner,
+            &owner_key,
+            &reader.public_key,
+            ResourcePattern::Namespace("docs".to_string()),
+            Permission::Read,
+            None,
+        )
+        .unwrap();
+
+        let acme_reader = acme.as_identity(&session_id).unwrap();
+        assert_eq!(
+            acme_reader.get("docs", "a").await.unwrap().value(),
+            &json!(1)
+        );
+        assert!(matches!(
+            acme_reader.put("docs", "b", json!(1)).await,
+            Err(DeltaError::Unauthorized { .. })
+        ));
+
+        // The grant covers neither other tenants nor unprefixed namespaces
+        let globex_reader = globex.as_identity(&session_id).unwrap();
+        assert!(globex_reader.get("docs", "a").await.is_err());
+        assert!(
+            db.as_identity(&session_id)
+                .unwrap()
+                .get("docs", "a")
+                .await
+                .is_err()
+        );
+
+        assert_eq!(
+            acme.scope(ResourcePattern::Wildcard {
+                prefix: String::new()
+            })
+            .unwrap(),
+            ResourcePattern::Wildcard {
+                prefix: "acme/".to_string()
+            }
+        );
+    }
+}
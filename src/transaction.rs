@@ -0,0 +1,226 @@
+/// Read transactions.
+///
+/// A [`ReadTransaction`] is pinned to the state of the database when it was
+/// begun: every get and query through it sees the same consistent snapshot,
+/// however many writes land in the meantime.
+///
+/// Snapshots are optimistic. Beginning one only records the storage's
+/// sequence number; nothing is copied and no lock is held, so a transaction
+/// is cheap to begin, never blocks writers, and can be held across awaits.
+/// While any clone of a transaction is alive, garbage collection and history
+/// compaction keep the versions it can still see.
+/// Reads of keys that have not changed since cost the same as a plain get;
+/// only keys written after the snapshot walk back through their history.
+///
+/// # Example
+///
+/// ```ignore
+/// let txn = db.begin_read();
+/// let order = txn.get("orders", "42").await?;
+/// let lines = txn.query("order_lines", Query::new().filter(Filter::eq("order", "42"))).await?;
+/// // `order` and `lines` come from the same state, even if writes ran in between
+/// ```
+use std::sync::Arc;
+
+use crate::error::DeltaResult;
+use crate::query::{FieldPushdown, Query, QueryExecutor, QueryResult};
+use crate::record::KoruRecord;
+use crate::storage::CausalStorage;
+use crate::types::{VectorClock, VersionedValue};
+
+/// Read-only view of the database pinned to one point in its history.
+#[derive(Debug, Clone)]
+pub struct ReadTransaction {
+    storage: Arc<CausalStorage>,
+    sequence: u64,
+    _pin: Arc<SnapshotPin>,
+}
+
+/// Keeps a snapshot's sequence number pinned in storage until dropped.
+#[derive(Debug)]
+struct SnapshotPin {
+    storage: Arc<CausalStorage>,
+    sequence: u64,
+}
+
+impl Drop for SnapshotPin {
+    fn drop(&mut self) {
+        self.storage.unpin_snapshot(self.sequence);
+    }
+}
+
+impl ReadTransaction {
+    /// Pin a transaction to the storage's current state.
+    pub(crate) fn begin(storage: Arc<CausalStorage>) -> Self {
+        let sequence = storage.pin_snapshot();
+        let pin = Arc::new(SnapshotPin {
+            storage: Arc::clone(&storage),
+            sequence,
+        });
+        Self {
+            storage,
+            sequence,
+            _pin: pin,
+        }
+    }
+
+    /// Storage sequence number the transaction is pinned to.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Vector clock of the snapshot: this node's sequence number when the
+    /// transaction began.
+    pub fn clock(&self) -> VectorClock {
+        self.storage.clock_at(self.sequence)
+    }
+
+    /// Get a key's value as of the snapshot.
+    pub async fn get(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+    ) -> DeltaResult<VersionedValue> {
+        self.storage.get_as_of(namespace, key, self.sequence)
+    }
+
+    /// Get a typed record as of the snapshot.
+    pub async fn get_typed<T: KoruRecord>(&self, key: &str) -> DeltaResult<T> {
+        let versioned = self.get(T::NAMESPACE, key).await?;
+        if versioned.value().is_null() {
+            return Err(crate::error::DeltaError::KeyNotFound {
+                namespace: T::NAMESPACE.to_string(),
+                key: key.to_string(),
+            });
+        }
+        Ok(serde_json::from_value(versioned.value().clone())?)
+    }
+
+    /// Check whether a key existed at the snapshot.
+    pub async fn contains(&self, namespace: impl Into<String>, key: impl Into<String>) -> bool {
+        self.get(namespace, key).await.is_ok()
+    }
+
+    /// Keys of a namespace as of the snapshot, sorted.
+    pub async fn list_keys(&self, namespace: &str) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .storage
+            .scan_collection_as_of(namespace, self.sequence)
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    /// Query a namespace as of the snapshot.
+    pub async fn query(&self, namespace: &str, query: Query) -> DeltaResult<QueryResult> {
+        let pushdown = FieldPushdown::new(&query);
+        let items = self
+            .storage
+            .scan_collection_as_of(namespace, self.sequence)
+            .into_iter()
+            .map(|(key, value)| {
+                (
+                    key,
+                    pushdown.read(value.value()),
+                    value.timestamp(),
+                    value.version_id().to_string(),
+                )
+            });
+        QueryExecutor::execute(&query, items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use koru_lambda_core::DistinctionEngine;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_snapshot_ignores_later_writes() {
+        let storage = Arc::new(CausalStorage::new(Arc::new(DistinctionEngine::new())));
+        storage.put("users", "alice", json!({"age": 30})).unwrap();
+        storage.put("users", "bob", json!({"age": 20})).unwrap();
+
+        let txn = ReadTransaction::begin(Arc::clone(&storage));
+        storage.put("users", "alice", json!({"age": 31})).unwrap();
+        storage.put("users", "alice", json!({"age": 32})).unwrap();
+        storage.put("users", "carol", json!({"age": 40})).unwrap();
+
+        assert_eq!(
+            txn.get("users", "alice").await.unwrap().value(),
+            &json!({"age": 30})
+        );
+        assert!(!txn.contains("users", "carol").await);
+        assert_eq!(txn.list_keys("users").await, vec!["alice", "bob"]);
+        let result = txn
+            .query(
+                "users",
+                Query::new().filter(crate::query::Filter::gt("age", 25)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.total_count, 1);
+
+        // A new transaction sees the writes
+        let later = ReadTransaction::begin(Arc::clone(&storage));
+        assert!(later.sequence() > txn.sequence());
+        assert_eq!(
+            later.get("users", "alice").await.unwrap().value(),
+            &json!({"age": 32})
+        );
+        assert_eq!(later.list_keys("users").await.len(), 3);
+        assert_eq!(txn.clock().clocks.get("local"), Some(&txn.sequence()));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_survives_gc() {
+        let storage = Arc::new(CausalStorage::new(Arc::new(DistinctionEngine::new())));
+        storage.put("users", "alice", json!({"age": 30})).unwrap();
+
+        let txn = ReadTransaction::begin(Arc::clone(&storage));
+        let clone = txn.clone();
+        drop(txn);
+        storage
+            .put("users", "alice", serde_json::Value::Null)
+            .unwrap();
+
+        // The delete's history stays while the snapshot can read it
+        let report = storage.collect_garbage(&[]);
+        assert_eq!(report.versions_collected, 0);
+        assert_eq!(
+            clone.get("users", "alice").await.unwrap().value(),
+            &json!({"age": 30})
+        );
+        assert_eq!(clone.list_keys("users").await, vec!["alice"]);
+
+        drop(clone);
+        assert_eq!(storage.oldest_snapshot(), None);
+        let report = storage.collect_garbage(&[]);
+        assert_eq!(report.versions_collected, 1);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_survives_compaction() {
+        let storage = Arc::new(CausalStorage::new(Arc::new(DistinctionEngine::new())));
+        for age in 0..2 {
+            storage.put("users", "alice", json!({"age": age})).unwrap();
+        }
+        let txn = ReadTransaction::begin(Arc::clone(&storage));
+        for age in 2..6 {
+            storage.put("users", "alice", json!({"age": age})).unwrap();
+        }
+
+        let policy = crate::types::CompactionPolicy::new().keep_recent(1);
+        let report = storage
+            .compact_history("users", "alice", &policy, &[])
+            .unwrap();
+        assert_eq!(report.runs_compacted, 1);
+        assert_eq!(
+            txn.get("users", "alice").await.unwrap().value(),
+            &json!({"age": 1})
+        );
+    }
+}
@@ -6,8 +6,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use std::collections::HashMap;
-use std::sync::Arc;
+use serde_json::value::RawValue;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, OnceLock};
 
 /// A version identifier for causal tracking.
 pub type VersionId = u64;
@@ -31,6 +32,11 @@ impl VectorClock {
         }
     }
 
+    /// A node's counter (0 if the clock has never seen the node).
+    pub fn get(&self, node_id: &str) -> u64 {
+        self.clocks.get(node_id).copied().unwrap_or(0)
+    }
+
     /// Increment the clock for a specific node.
     pub fn increment(&mut self, node_id: &str) {
         let entry = self.clocks.entry(node_id.to_string()).or_insert(0);
@@ -45,6 +51,21 @@ impl VectorClock {
         }
     }
 
+    /// The earliest clock that has seen both this one and `other`.
+    pub fn merged(&self, other: &VectorClock) -> VectorClock {
+        let mut merged = self.clone();
+        merged.merge(other);
+        merged
+    }
+
+    /// The earliest clock that has seen every one of `clocks`, such as the
+    /// clock to write with when resolving a conflict between them.
+    pub fn merge_all<'a>(clocks: impl IntoIterator<Item = &'a VectorClock>) -> VectorClock {
+        clocks
+            .into_iter()
+            .fold(VectorClock::new(), |merged, clock| merged.merged(clock))
+    }
+
     /// Compare two vector clocks.
     ///
     /// Returns:
@@ -92,6 +113,27 @@ impl VectorClock {
     pub fn is_concurrent_with(&self, other: &VectorClock) -> bool {
         self.compare(other).is_none()
     }
+
+    /// Whether this clock happened strictly before `other`: no counter is
+    /// ahead of `other`'s and at least one is behind.
+    pub fn happens_before(&self, other: &VectorClock) -> bool {
+        self.is_dominated_by(other)
+    }
+
+    /// Whether neither clock happened before the other, so the writes they
+    /// stamp conflict.
+    pub fn concurrent_with(&self, other: &VectorClock) -> bool {
+        self.is_concurrent_with(other)
+    }
+
+    /// Whether this clock has seen everything `other` has: it is equal to
+    /// or happened after it.
+    pub fn descends_from(&self, other: &VectorClock) -> bool {
+        matches!(
+            self.compare(other),
+            Some(std::cmp::Ordering::Greater | std::cmp::Ordering::Equal)
+        )
+    }
 }
 
 /// A fully-qualified key combining namespace and key.
@@ -165,6 +207,8 @@ impl Tombstone {
 /// - `distinction_id`: Content hash of the value (same content = same distinction_id)
 /// - `previous_version`: The write_id of the previous version of this key
 /// - `vector_clock`: Causal ordering for distributed conflict resolution
+/// - `author` / `origin_node`: Who made the write and on which node, when known
+/// - `causes`: write_ids of other keys' versions this write was derived from
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VersionedValue {
     /// The actual data stored (Arc-wrapped for deduplication)
@@ -183,6 +227,26 @@ pub struct VersionedValue {
     pub previous_version: Option<String>,
     /// Vector clock for causal ordering in distributed systems
     pub vector_clock: VectorClock,
+    /// Identity (public key) that made the write through an authenticated session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// ID of the cluster node the write originated on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin_node: Option<String>,
+    /// Write IDs of versions (of any key) this write declares it was derived from
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub causes: Vec<String>,
+    /// Idempotency token the write was made with, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_token: Option<String>,
+    /// Versions this one stands in for after history compaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<VersionSummary>,
+    /// The value as raw JSON text, serialized once and shared by every copy
+    /// of this version; replace the value with
+    /// [`with_value`](Self::with_value) so it is not left stale
+    #[serde(skip)]
+    raw: Arc<OnceLock<Arc<RawValue>>>,
 }
 
 /// Serialize Arc<JsonValue> as plain JsonValue
@@ -219,6 +283,12 @@ impl VersionedValue {
             distinction_id,
             previous_version,
             vector_clock,
+            author: None,
+            origin_node: None,
+            causes: Vec::new(),
+            idempotency_token: None,
+            summary: None,
+            raw: Arc::default(),
         }
     }
 
@@ -239,6 +309,12 @@ impl VersionedValue {
             distinction_id,
             previous_version,
             vector_clock,
+            author: None,
+            origin_node: None,
+            causes: Vec::new(),
+            idempotency_token: None,
+            summary: None,
+            raw: Arc::default(),
         }
     }
 
@@ -247,6 +323,52 @@ impl VersionedValue {
         &self.value
     }
 
+    /// Get a shared handle to the value.
+    ///
+    /// Versions share their value with the storage that holds them, so this
+    /// only bumps a reference count where `value().clone()` deep-copies.
+    pub fn shared_value(&self) -> Arc<JsonValue> {
+        Arc::clone(&self.value)
+    }
+
+    /// Take the value, copying it only if it is still shared.
+    pub fn into_value(self) -> JsonValue {
+        Arc::unwrap_or_clone(self.value)
+    }
+
+    /// Borrow a field of the value by dot-notation path (e.g.
+    /// `"address.city"` or `"tags.0"`), without copying it.
+    pub fn field(&self, path: &str) -> Option<&JsonValue> {
+        crate::query::field_ref(&self.value, path)
+    }
+
+    /// Borrow a string field of the value.
+    pub fn str_field(&self, path: &str) -> Option<&str> {
+        self.field(path)?.as_str()
+    }
+
+    /// The value as raw JSON text.
+    ///
+    /// Serialized at most once per version: local writes do it when they
+    /// are stored, other versions on first use. Every copy of the version
+    /// shares the result, so this only clones an `Arc`.
+    pub fn to_raw(&self) -> Arc<RawValue> {
+        Arc::clone(self.raw.get_or_init(|| {
+            let raw = serde_json::value::to_raw_value(&*self.value)
+                .expect("JSON values always serialize");
+            Arc::from(raw)
+        }))
+    }
+
+    /// This version with its value replaced, e.g. by the decrypted value.
+    pub fn with_value(self, value: JsonValue) -> Self {
+        Self {
+            value: Arc::new(value),
+            raw: Arc::default(),
+            ..self
+        }
+    }
+
     /// Get the timestamp when this version was created.
     pub fn timestamp(&self) -> DateTime<Utc> {
         self.timestamp
@@ -279,6 +401,112 @@ impl VersionedValue {
     pub fn vector_clock(&self) -> &VectorClock {
         &self.vector_clock
     }
+
+    /// Get the identity that made this write, if it was authenticated.
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    /// Get the ID of the node this write originated on, if clustered.
+    pub fn origin_node(&self) -> Option<&str> {
+        self.origin_node.as_deref()
+    }
+
+    /// Get the write IDs this version declared as its causes.
+    pub fn causes(&self) -> &[String] {
+        &self.causes
+    }
+
+    /// Get the run of versions this one summarizes, if history compaction
+    /// replaced them.
+    pub fn summary(&self) -> Option<&VersionSummary> {
+        self.summary.as_ref()
+    }
+}
+
+/// Aggregate metadata for a run of versions folded into one by history
+/// compaction.
+///
+/// The summary version keeps the value, timestamp and write ID of the
+/// newest version in the run; the rest of the run is only remembered here.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionSummary {
+    /// Number of versions folded into the summary (including itself)
+    pub versions: usize,
+    /// Write ID of the oldest version in the run
+    pub first_write_id: String,
+    /// When the oldest version in the run was written
+    pub first_timestamp: DateTime<Utc>,
+    /// Distinct authors of the versions in the run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<String>,
+}
+
+/// Which versions of a key `compact_history()` may fold into summaries.
+///
+/// The key's first and newest versions are always kept, as are versions
+/// other writes declared as causes, so the key's causal endpoints survive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactionPolicy {
+    /// Number of most recent versions to leave untouched (at least 1)
+    pub keep_recent: usize,
+    /// Only compact versions older than this
+    pub older_than: Option<chrono::Duration>,
+    /// Shortest run of consecutive versions worth replacing
+    pub min_run: usize,
+}
+
+impl Default for CompactionPolicy {
+    fn default() -> Self {
+        Self {
+            keep_recent: 10,
+            older_than: None,
+            min_run: 2,
+        }
+    }
+}
+
+impl CompactionPolicy {
+    /// Create a policy with the defaults: keep the 10 most recent versions
+    /// and fold any older run of 2 or more.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Leave the `count` most recent versions untouched.
+    pub fn keep_recent(mut self, count: usize) -> Self {
+        self.keep_recent = count.max(1);
+        self
+    }
+
+    /// Only compact versions written more than `age` ago.
+    pub fn older_than(mut self, age: chrono::Duration) -> Self {
+        self.older_than = Some(age);
+        self
+    }
+
+    /// Only replace runs of at least `length` versions.
+    pub fn min_run(mut self, length: usize) -> Self {
+        self.min_run = length.max(2);
+        self
+    }
+}
+
+/// What a history compaction pass replaced.
+///
+/// Returned by `compact_history()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryCompactionReport {
+    /// Runs of versions replaced by a summary version
+    pub runs_compacted: usize,
+    /// Versions removed from the key's history
+    pub versions_removed: usize,
+    /// Deduplicated values no remaining version shares
+    pub values_collected: usize,
+    /// WAL entries dropped by compaction
+    pub wal_entries_removed: usize,
+    /// Disk space freed, in bytes
+    pub bytes_reclaimed: u64,
 }
 
 /// Result of a causal write operation.
@@ -326,6 +554,45 @@ impl CausalWriteResult {
     }
 }
 
+/// How a [`WriteConflict`] was settled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStatus {
+    /// Merging failed; the key still holds the existing sibling.
+    Unresolved,
+    /// Last-write-wins picked the winner during replication.
+    AutoResolved,
+    /// An application chose the winner with `resolve_conflict`.
+    Resolved,
+}
+
+/// Concurrent versions of one key, kept when replication had to merge them.
+///
+/// Last-write-wins keeps only one value as current; the conflict keeps every
+/// sibling so the choice can be audited or re-made later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WriteConflict {
+    /// Conflict identifier, stable across retries of the same incoming write.
+    pub id: String,
+    /// The key written concurrently.
+    pub key: FullKey,
+    /// The concurrent versions: the local one first, then the incoming one.
+    pub siblings: Vec<VersionedValue>,
+    /// The version that became current, if any.
+    pub winner: Option<VersionedValue>,
+    /// How the conflict was settled.
+    pub status: ConflictStatus,
+    /// When the conflict was detected.
+    pub detected_at: DateTime<Utc>,
+}
+
+impl WriteConflict {
+    /// Whether no winner has been chosen yet.
+    pub fn is_unresolved(&self) -> bool {
+        self.status == ConflictStatus::Unresolved
+    }
+}
+
 /// A history entry representing a single change to a key.
 ///
 /// This is returned by the `history()` method and provides a chronological
@@ -338,6 +605,9 @@ pub struct HistoryEntry {
     pub timestamp: DateTime<Utc>,
     /// The version ID for this change
     pub version_id: String,
+    /// The versions this entry stands in for, if history was compacted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<VersionSummary>,
 }
 
 impl HistoryEntry {
@@ -347,6 +617,7 @@ impl HistoryEntry {
             value,
             timestamp,
             version_id,
+            summary: None,
         }
     }
 }
@@ -357,10 +628,88 @@ impl From<&VersionedValue> for HistoryEntry {
             value: (*versioned.value).clone(),
             timestamp: versioned.timestamp,
             version_id: versioned.distinction_id.clone(), // Use distinction_id (content hash)
+            summary: versioned.summary.clone(),
+        }
+    }
+}
+
+/// One write in a record's provenance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProvenanceEntry {
+    /// Unique ID of the write
+    pub write_id: String,
+    /// Content-addressed version ID of the value written
+    pub version_id: String,
+    /// When the write happened
+    pub timestamp: DateTime<Utc>,
+    /// Identity that made the write, if it was authenticated
+    pub author: Option<String>,
+    /// Node the write originated on, if it was made in a cluster
+    pub origin_node: Option<String>,
+}
+
+impl From<&VersionedValue> for ProvenanceEntry {
+    fn from(versioned: &VersionedValue) -> Self {
+        Self {
+            write_id: versioned.write_id.clone(),
+            version_id: versioned.distinction_id.clone(),
+            timestamp: versioned.timestamp,
+            author: versioned.author.clone(),
+            origin_node: versioned.origin_node.clone(),
         }
     }
 }
 
+/// Which writes produced a record's current value, like `git blame`.
+///
+/// Returned by `provenance()`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Provenance {
+    /// The namespace of the record
+    pub namespace: String,
+    /// The key of the record
+    pub key: String,
+    /// Every write in the current value's causal history, oldest first
+    pub writes: Vec<ProvenanceEntry>,
+    /// For object values, the write ID that last set each top-level field
+    pub fields: BTreeMap<String, String>,
+}
+
+impl Provenance {
+    /// The write that produced the current value.
+    pub fn current(&self) -> Option<&ProvenanceEntry> {
+        self.writes.last()
+    }
+
+    /// The write that last set a top-level field of the current value.
+    pub fn blame(&self, field: &str) -> Option<&ProvenanceEntry> {
+        let write_id = self.fields.get(field)?;
+        self.writes.iter().find(|write| &write.write_id == write_id)
+    }
+}
+
+/// What a garbage collection pass reclaimed.
+///
+/// Returned by `collect_garbage()`. The persistence counters stay zero for
+/// in-memory databases.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GcReport {
+    /// Versions removed from the version store
+    pub versions_collected: usize,
+    /// Deduplicated values no remaining version shares
+    pub values_collected: usize,
+    /// Nodes removed from the causal and reference graphs
+    pub graph_nodes_collected: usize,
+    /// Distinctions dropped with the engine values are canonicalized in
+    pub distinctions_collected: usize,
+    /// WAL entries dropped by compaction
+    pub wal_entries_removed: usize,
+    /// Files removed from the on-disk value store
+    pub value_files_removed: usize,
+    /// Disk space freed, in bytes
+    pub bytes_reclaimed: u64,
+}
+
 /// A distinction with connectivity information.
 ///
 /// Returned by `get_highly_connected()` to represent distinctions
@@ -517,10 +866,91 @@ impl RandomCombination {
     }
 }
 
+/// Namespace holding dream-phase syntheses awaiting review.
+pub const DREAMS_NAMESPACE: &str = "_dreams";
+
+/// Review state of a [`Dream`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DreamStatus {
+    /// Not reviewed yet
+    Pending,
+    /// Kept as a meaningful connection
+    Accepted,
+    /// Rejected as noise
+    Discarded,
+}
+
+/// A dream-phase synthesis stored in [`DREAMS_NAMESPACE`].
+///
+/// Each dream records a [`RandomCombination`] together with the versions
+/// it was drawn from. Those versions are also the dream's causes, so
+/// [`KoruDelta::dependents`](crate::KoruDelta::dependents) on a source
+/// finds the dreams it took part in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dream {
+    /// Key of the dream in [`DREAMS_NAMESPACE`]
+    pub id: String,
+    /// The combination the dream proposes
+    pub combination: RandomCombination,
+    /// Write IDs of the start and end versions
+    pub sources: Vec<String>,
+    /// Review state
+    pub status: DreamStatus,
+    /// When the dream was synthesized
+    pub dreamed_at: DateTime<Utc>,
+    /// When the dream was accepted or discarded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reviewed_at: Option<DateTime<Utc>>,
+}
+
+impl Dream {
+    /// Dream ID for a combination of two keys, independent of walk direction.
+    pub fn id_for(start: &FullKey, end: &FullKey) -> String {
+        let mut ends = [start.to_canonical_string(), end.to_canonical_string()];
+        ends.sort();
+        let hash = blake3::hash(ends.join("\n").as_bytes());
+        format!("dream_{}", &hash.to_hex()[..16])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_vector_clock_causality() {
+        let clock = |entries: &[(&str, u64)]| VectorClock {
+            clocks: entries
+                .iter()
+                .map(|(node, count)| (node.to_string(), *count))
+                .collect(),
+        };
+        let base = clock(&[("a", 1)]);
+        let left = clock(&[("a", 2)]);
+        let right = clock(&[("a", 1), ("b", 1)]);
+
+        assert!(base.happens_before(&left));
+        assert!(!left.happens_before(&base));
+        assert!(!base.happens_before(&base));
+        assert!(left.concurrent_with(&right));
+        assert!(!base.concurrent_with(&right));
+        assert!(right.descends_from(&base));
+        assert!(base.descends_from(&base));
+        assert!(!left.descends_from(&right));
+
+        // Missing nodes count as zero
+        assert_eq!(right.get("b"), 1);
+        assert_eq!(base.get("b"), 0);
+        assert!(clock(&[("a", 1), ("b", 0)]).descends_from(&base));
+
+        let merged = VectorClock::merge_all([&base, &left, &right]);
+        assert_eq!(merged, clock(&[("a", 2), ("b", 1)]));
+        assert_eq!(left.merged(&right), merged);
+        assert!(left.happens_before(&merged) && right.happens_before(&merged));
+        assert_eq!(VectorClock::merge_all([]), VectorClock::new());
+    }
+
     #[test]
     fn test_full_key_canonical_string() {
         let key = FullKey::new("users", "alice");
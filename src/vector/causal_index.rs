@@ -184,7 +184,7 @@ impl CausalVectorIndex {
 
         // Add to current index immediately
         let current = self.current.read().await;
-        let full_id = format!("{}:{}", self.namespace, id);
+        let full_id = super::hnsw::node_id(&self.namespace, &id);
         let _ = current.add(full_id.clone(), vector.clone());
         drop(current);
 
@@ -330,12 +330,7 @@ impl CausalVectorIndex {
                 // Compute distances to additional vectors
                 for (id, vec) in additional {
                     if let Some(similarity) = query.cosine_similarity(&vec) {
-                        // Parse namespace:key
-                        let (ns, key) = if let Some(pos) = id.find(':') {
-                            (id[..pos].to_string(), id[pos + 1..].to_string())
-                        } else {
-                            (self.namespace.clone(), id)
-                        };
+                        let (ns, key) = super::hnsw::parse_node_id(&id);
                         results.push(VectorSearchResult::new(ns, key, similarity, vec));
                     }
                 }
@@ -365,7 +360,7 @@ impl CausalVectorIndex {
             .collect();
 
         results.retain(|r| {
-            let full_id = format!("{}:{}", r.namespace, r.key);
+            let full_id = super::hnsw::node_id(&r.namespace, &r.key);
             valid_ids.contains(&full_id)
         });
 
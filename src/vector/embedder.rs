@@ -0,0 +1,379 @@
+//! Local text embedding.
+//!
+//! [`TextEmbedder`] turns text into a [`Vector`] in-process, so
+//! `db.embed_text(...)` works without an external embedding API.
+//!
+//! With the `embedder` feature, [`SentenceEmbedder`] runs a sentence
+//! transformer such as all-MiniLM-L6-v2 locally with candle. A database
+//! uses one only when configured to (see
+//! [`EmbedderConfig`](crate::EmbedderConfig)), so no model is downloaded
+//! unless asked for.
+//!
+//! The default is [`HashingEmbedder`], which needs no model files: it hashes word tokens and character trigrams into a fixed number
+//! of dimensions (the "hashing trick"). It captures lexical overlap, not
+//! meaning, so it only suits keyword-like similarity. Other models plug in
+//! by implementing [`TextEmbedder`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! use koru_delta::vector::{SentenceEmbedder, TextEmbedder};
+//!
+//! let embedder = SentenceEmbedder::from_hub(SentenceEmbedder::DEFAULT_MODEL);
+//! let vector = embedder.embed("the quick brown fox")?;
+//! assert_eq!(vector.dimensions(), 384);
+//! ```
+
+use super::types::Vector;
+use crate::error::{DeltaError, DeltaResult};
+
+/// Produces embeddings from text locally.
+pub trait TextEmbedder: Send + Sync {
+    /// Identifier recorded as the model of every produced vector.
+    fn model(&self) -> &str;
+
+    /// Number of dimensions of produced vectors.
+    fn dimensions(&self) -> usize;
+
+    /// Embed a single text.
+    fn embed(&self, text: &str) -> DeltaResult<Vector>;
+}
+
+/// Dependency-free embedder based on feature hashing.
+///
+/// Each lowercase word and each character trigram of a word is hashed to a
+/// dimension and a sign; the result is L2-normalized. Texts sharing words or
+/// word fragments get high cosine similarity.
+#[derive(Debug, Clone)]
+pub struct HashingEmbedder {
+    dimensions: usize,
+    model: String,
+}
+
+impl HashingEmbedder {
+    /// Create an embedder producing vectors with the given dimensions.
+    pub fn new(dimensions: usize) -> Self {
+        let dimensions = dimensions.max(1);
+        Self {
+            dimensions,
+            model: format!("koru-hashing-v1-{}", dimensions),
+        }
+    }
+
+    fn add_feature(&self, data: &mut [f32], feature: &str, weight: f32) {
+        let hash = blake3::hash(feature.as_bytes());
+        let bytes = hash.as_bytes();
+        let bucket = u64::from_le_bytes(bytes[..8].try_into().unwrap()) as usize % self.dimensions;
+        let sign = if bytes[8] & 1 == 0 { 1.0 } else { -1.0 };
+        data[bucket] += sign * weight;
+    }
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self::new(256)
+    }
+}
+
+impl TextEmbedder for HashingEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn embed(&self, text: &str) -> DeltaResult<Vector> {
+        let mut data = vec![0.0f32; self.dimensions];
+        let words = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase);
+
+        let mut any = false;
+        for word in words {
+            any = true;
+            self.add_feature(&mut data, &word, 1.0);
+
+            let padded: Vec<char> = format!("#{}#", word).chars().collect();
+            for trigram in padded.windows(3) {
+                let trigram: String = trigram.iter().collect();
+                self.add_feature(&mut data, &trigram, 0.5);
+            }
+        }
+
+        if !any {
+            return Err(DeltaError::InvalidData {
+                reason: "Cannot embed text without any words".to_string(),
+            });
+        }
+
+        let magnitude = data.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if magnitude > 0.0 {
+            for value in &mut data {
+                *value /= magnitude;
+            }
+        }
+
+        Ok(Vector::new(data, self.model.clone()))
+    }
+}
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "embedder"))]
+pub use sentence::SentenceEmbedder;
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "embedder"))]
+mod sentence {
+    use super::*;
+    use candle_core::{Device, Tensor};
+    use candle_nn::VarBuilder;
+    use candle_transformers::models::bert::{BertModel, Config, DTYPE};
+    use std::path::{Path, PathBuf};
+    use std::sync::OnceLock;
+    use tokenizers::{Tokenizer, TruncationParams};
+
+    /// Where the model files come from.
+    #[derive(Debug, Clone)]
+    enum Source {
+        /// A Hugging Face Hub repository, downloaded once into the local cache.
+        Hub(String),
+        /// A directory holding the files, shipped with the application.
+        Dir(PathBuf),
+    }
+
+    /// A loaded model and its tokenizer.
+    struct Model {
+        bert: BertModel,
+        tokenizer: Tokenizer,
+        device: Device,
+        dimensions: usize,
+    }
+
+    /// Sentence transformer run in-process with candle.
+    ///
+    /// Works with BERT-family sentence-transformers models that use mean
+    /// pooling, such as all-MiniLM-L6-v2 (384 dimensions).
+    /// Vectors are L2-normalized. Inference runs on the CPU.
+    ///
+    /// The model is loaded on first use. Hub models are downloaded once
+    /// into the Hugging Face cache (`HF_HOME`) and read from there
+    /// afterwards; [`from_dir`](Self::from_dir) loads files shipped with the
+    /// application and never touches the network.
+    pub struct SentenceEmbedder {
+        source: Source,
+        model_id: String,
+        model: OnceLock<Model>,
+    }
+
+    impl std::fmt::Debug for SentenceEmbedder {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("SentenceEmbedder")
+                .field("source", &self.source)
+                .field("loaded", &self.model.get().is_some())
+                .finish()
+        }
+    }
+
+    impl SentenceEmbedder {
+        /// Hub repository of a good general-purpose model.
+        pub const DEFAULT_MODEL: &'static str = "sentence-transformers/all-MiniLM-L6-v2";
+
+        /// Embed with a model from the Hugging Face Hub, e.g.
+        /// [`DEFAULT_MODEL`](Self::DEFAULT_MODEL). The files are downloaded
+        /// on first use.
+        pub fn from_hub(model_id: impl Into<String>) -> Self {
+            let model_id = model_id.into();
+            Self {
+                source: Source::Hub(model_id.clone()),
+                model_id,
+                model: OnceLock::new(),
+            }
+        }
+
+        /// Embed with a model stored in `dir`, which must hold the
+        /// `config.json`, `tokenizer.json` and `model.safetensors` of a
+        /// sentence-transformers model. The directory name is recorded as
+        /// the model of produced vectors.
+        pub fn from_dir(dir: impl Into<PathBuf>) -> Self {
+            let dir = dir.into();
+            let model_id = dir
+                .file_name()
+                .map_or_else(|| "local".to_string(), |name| name.to_string_lossy().into());
+            Self {
+                source: Source::Dir(dir),
+                model_id,
+                model: OnceLock::new(),
+            }
+        }
+
+        /// Load the model now rather than on first use, e.g. to fail at
+        /// startup if it is missing.
+        pub fn load(&self) -> DeltaResult<()> {
+            self.model().map(|_| ())
+        }
+
+        fn model(&self) -> DeltaResult<&Model> {
+            if let Some(model) = self.model.get() {
+                return Ok(model);
+            }
+            let (config, tokenizer, weights) = match &self.source {
+                Source::Hub(model_id) => {
+                    let repo = hf_hub::api::sync::Api::new()
+                        .map_err(embedding_error)?
+                        .model(model_id.clone());
+                    let get = |file: &str| repo.get(file).map_err(embedding_error);
+                    (
+                        get("config.json")?,
+                        get("tokenizer.json")?,
+                        get("model.safetensors")?,
+                    )
+                }
+                Source::Dir(dir) => (
+                    dir.join("config.json"),
+                    dir.join("tokenizer.json"),
+                    dir.join("model.safetensors"),
+                ),
+            };
+            // Two threads racing here both load; only one model is kept
+            let model = Model::load(&config, &tokenizer, &weights)?;
+            Ok(self.model.get_or_init(|| model))
+        }
+    }
+
+    impl Model {
+        fn load(config: &Path, tokenizer: &Path, weights: &Path) -> DeltaResult<Self> {
+            let config: Config =
+                serde_json::from_str(&std::fs::read_to_string(config).map_err(embedding_error)?)?;
+
+            let mut tokenizer = Tokenizer::from_file(tokenizer).map_err(embedding_error)?;
+            tokenizer.with_padding(None);
+            tokenizer
+                .with_truncation(Some(TruncationParams {
+                    max_length: config.max_position_embeddings,
+                    ..Default::default()
+                }))
+                .map_err(embedding_error)?;
+
+            let device = Device::Cpu;
+            // SAFETY: the weights file is only read, and nothing rewrites it
+            // while it is mapped
+            let weights =
+                unsafe { VarBuilder::from_mmaped_safetensors(&[weights], DTYPE, &device) }
+                    .map_err(embedding_error)?;
+            let bert = BertModel::load(weights, &config).map_err(embedding_error)?;
+
+            Ok(Self {
+                bert,
+                tokenizer,
+                device,
+                dimensions: config.hidden_size,
+            })
+        }
+
+        fn embed(&self, text: &str) -> DeltaResult<Vec<f32>> {
+            let encoding = self.tokenizer.encode(text, true).map_err(embedding_error)?;
+            let input =
+                |ids: &[u32]| Tensor::new(ids, &self.device).and_then(|tensor| tensor.unsqueeze(0));
+            let ids = input(encoding.get_ids()).map_err(embedding_error)?;
+            let type_ids = input(encoding.get_type_ids()).map_err(embedding_error)?;
+            let mask = input(encoding.get_attention_mask()).map_err(embedding_error)?;
+
+            // Mean pooling over the tokens, as the models were trained
+            let hidden = self
+                .bert
+                .forward(&ids, &type_ids, Some(&mask))
+                .map_err(embedding_error)?;
+            let pooled = hidden
+                .dim(1)
+                .and_then(|tokens| hidden.sum(1)? / tokens as f64)
+                .and_then(|pooled| pooled.squeeze(0)?.to_vec1::<f32>())
+                .map_err(embedding_error)?;
+            Ok(pooled)
+        }
+    }
+
+    impl TextEmbedder for SentenceEmbedder {
+        fn model(&self) -> &str {
+            &self.model_id
+        }
+
+        /// Loads the model if needed; 0 if it cannot be loaded.
+        fn dimensions(&self) -> usize {
+            self.model().map_or(0, |model| model.dimensions)
+        }
+
+        fn embed(&self, text: &str) -> DeltaResult<Vector> {
+            if text.trim().is_empty() {
+                return Err(DeltaError::InvalidData {
+                    reason: "Cannot embed empty text".to_string(),
+                });
+            }
+
+            let mut data = self.model()?.embed(text)?;
+            let magnitude = data.iter().map(|x| x * x).sum::<f32>().sqrt();
+            if magnitude > 0.0 {
+                for value in &mut data {
+                    *value /= magnitude;
+                }
+            }
+
+            Ok(Vector::new(data, self.model_id.clone()))
+        }
+    }
+
+    fn embedding_error(error: impl std::fmt::Display) -> DeltaError {
+        DeltaError::EmbeddingError(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hashing_embedder_is_deterministic() {
+        let embedder = HashingEmbedder::new(64);
+        let a = embedder.embed("Hello, world").unwrap();
+        let b = embedder.embed("hello world").unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.dimensions(), 64);
+        assert_eq!(a.model(), "koru-hashing-v1-64");
+    }
+
+    #[test]
+    fn test_hashing_embedder_similarity() {
+        let embedder = HashingEmbedder::default();
+        let query = embedder.embed("fire in the warehouse").unwrap();
+        let related = embedder.embed("warehouse fire reported").unwrap();
+        let unrelated = embedder.embed("quarterly revenue grew").unwrap();
+
+        let related_score = query.cosine_similarity(&related).unwrap();
+        let unrelated_score = query.cosine_similarity(&unrelated).unwrap();
+        assert!(related_score > unrelated_score);
+    }
+
+    #[test]
+    fn test_hashing_embedder_rejects_empty_text() {
+        assert!(HashingEmbedder::default().embed("  ,. ").is_err());
+    }
+
+    #[cfg(all(not(target_arch = "wasm32"), feature = "embedder"))]
+    #[test]
+    #[ignore] // Downloads the default model from the Hugging Face Hub
+    fn test_sentence_embedder_similarity() {
+        let embedder = SentenceEmbedder::from_hub(SentenceEmbedder::DEFAULT_MODEL);
+        let query = embedder
+            .embed("a blaze broke out in the storehouse")
+            .unwrap();
+        let related = embedder.embed("warehouse fire reported").unwrap();
+        let unrelated = embedder.embed("quarterly revenue grew").unwrap();
+        assert_eq!(query.dimensions(), 384);
+        assert_eq!(query.model(), SentenceEmbedder::DEFAULT_MODEL);
+
+        // Related in meaning, with no words in common
+        let related_score = query.cosine_similarity(&related).unwrap();
+        let unrelated_score = query.cosine_similarity(&unrelated).unwrap();
+        assert!(related_score > unrelated_score);
+    }
+}
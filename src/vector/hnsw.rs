@@ -10,6 +10,8 @@
 //! - Configurable M (max connections) and ef (search scope) parameters
 //! - Causal-consistent snapshots for time-travel queries
 //! - Thread-safe concurrent access
+//! - On-disk persistence of the graph, with lazy loading on first use
+//! - Tombstoned deletes with periodic graph compaction
 //!
 //! # Example
 //!
@@ -27,16 +29,24 @@
 //! let results = index.search(&query_vector, 10, 50);
 //! ```
 
-use super::types::{Vector, VectorSearchResult};
+use super::index::VectorIndexStats;
+use super::types::{DistanceMetric, Vector, VectorSearchResult};
+use crate::error::{DeltaError, DeltaResult};
 use crate::types::FullKey;
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 use rand::SeedableRng;
 use rand::distributions::{Distribution, Uniform};
 use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
 use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Magic bytes at the start of a persisted HNSW index file.
+const SNAPSHOT_MAGIC: &[u8; 8] = b"KDHNSW01";
 
 /// Configuration for HNSW index.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct HnswConfig {
     /// Maximum number of connections per node (default: 16)
     pub m: usize,
@@ -46,6 +56,8 @@ pub struct HnswConfig {
     pub ef_search: usize,
     /// Probability decay factor for layer assignment (default: 1.0 / ln(M))
     pub m_l: f64,
+    /// Metric the graph is built and searched with (default: cosine)
+    pub metric: DistanceMetric,
 }
 
 impl Default for HnswConfig {
@@ -56,6 +68,7 @@ impl Default for HnswConfig {
             ef_construction: 200,
             ef_search: 50,
             m_l: 1.0 / (m as f64).ln(),
+            metric: DistanceMetric::Cosine,
         }
     }
 }
@@ -68,9 +81,16 @@ impl HnswConfig {
             ef_construction: 200,
             ef_search: 50,
             m_l: 1.0 / (m as f64).ln(),
+            metric: DistanceMetric::Cosine,
         }
     }
 
+    /// Set the distance metric.
+    pub fn metric(mut self, metric: DistanceMetric) -> Self {
+        self.metric = metric;
+        self
+    }
+
     /// Set ef_construction.
     pub fn ef_construction(mut self, ef: usize) -> Self {
         self.ef_construction = ef;
@@ -144,6 +164,8 @@ pub struct HnswIndex {
     rng: std::sync::Mutex<StdRng>,
     /// Model filter (only index vectors from this model)
     model_filter: Option<String>,
+    /// Deleted node ids still kept in the graph for navigation
+    tombstones: DashSet<String>,
 }
 
 impl std::fmt::Debug for HnswIndex {
@@ -151,6 +173,7 @@ impl std::fmt::Debug for HnswIndex {
         f.debug_struct("HnswIndex")
             .field("config", &self.config)
             .field("num_nodes", &self.nodes.len())
+            .field("num_tombstones", &self.tombstones.len())
             .field("num_layers", &self.layers.len())
             .field(
                 "max_layer",
@@ -202,6 +225,7 @@ impl HnswIndex {
             max_layer: std::sync::atomic::AtomicUsize::new(0),
             rng: std::sync::Mutex::new(StdRng::seed_from_u64(42)),
             model_filter: None,
+            tombstones: DashSet::new(),
         }
     }
 
@@ -212,30 +236,73 @@ impl HnswIndex {
         index
     }
 
-    /// Get the number of vectors in the index.
+    /// Get the number of live (non-deleted) vectors in the index.
     pub fn len(&self) -> usize {
-        self.nodes.len()
+        self.nodes.len().saturating_sub(self.tombstones.len())
     }
 
     /// Check if the index is empty.
     pub fn is_empty(&self) -> bool {
-        self.nodes.is_empty()
+        self.len() == 0
+    }
+
+    /// Describe the graph: live nodes, per-layer node counts, and memory use.
+    ///
+    /// `layer_distribution[l]` counts nodes whose top layer is `l`; with a
+    /// healthy `m_l` each layer holds roughly `1/M` of the one below it.
+    pub fn stats(&self) -> VectorIndexStats {
+        let mut layer_distribution =
+            vec![0; self.max_layer.load(std::sync::atomic::Ordering::Relaxed) + 1];
+        let mut memory_bytes = 0;
+        for entry in self.nodes.iter() {
+            let node = entry.value();
+            if node.max_layer >= layer_distribution.len() {
+                layer_distribution.resize(node.max_layer + 1, 0);
+            }
+            layer_distribution[node.max_layer] += 1;
+            memory_bytes += entry.key().len()
+                + node.vector.model().len()
+                + node.vector.dimensions() * std::mem::size_of::<f32>();
+        }
+        for layer in &self.layers {
+            let guard = layer.read().unwrap();
+            memory_bytes += guard
+                .edges
+                .values()
+                .flatten()
+                .map(|id| id.len() + std::mem::size_of::<String>())
+                .sum::<usize>();
+        }
+
+        VectorIndexStats {
+            vectors: self.len(),
+            tombstones: self.tombstone_count(),
+            layer_distribution,
+            memory_bytes,
+        }
+    }
+
+    /// Number of deleted nodes awaiting [`compact`](Self::compact).
+    pub fn tombstone_count(&self) -> usize {
+        self.tombstones.len()
+    }
+
+    /// Fraction of graph nodes that are tombstones.
+    pub fn tombstone_ratio(&self) -> f32 {
+        match self.nodes.len() {
+            0 => 0.0,
+            total => self.tombstones.len() as f32 / total as f32,
+        }
     }
 
     /// Assign a random layer to a new node.
     fn random_layer(&self) -> usize {
         let mut rng = self.rng.lock().unwrap();
-        let uniform = Uniform::from(0.0..1.0);
-        let mut level = 0;
-        loop {
-            let r: f64 = uniform.sample(&mut *rng);
-            if r < (-(level as f64) * self.config.m_l).exp() {
-                level += 1;
-            } else {
-                break;
-            }
-        }
-        level
+        let uniform = Uniform::from(f64::EPSILON..1.0);
+        let r: f64 = uniform.sample(&mut *rng);
+        // Exponentially decaying level distribution: P(level >= l) = exp(-l / m_l)
+        let level = (-r.ln() * self.config.m_l).floor() as usize;
+        level.min(self.layers.len() - 1)
     }
 
     /// Add a vector to the index.
@@ -247,7 +314,90 @@ impl HnswIndex {
     /// # Errors
     /// Returns an error if the vector's model doesn't match the filter.
     pub fn add(&self, id: String, vector: Vector) -> crate::error::DeltaResult<()> {
-        // Check model filter
+        self.check_model(&vector)?;
+
+        // Replace any existing (or tombstoned) entry
+        if self.nodes.contains_key(&id) {
+            self.unlink(&id);
+        }
+
+        let layer = self.random_layer();
+        self.insert_at_layer(id, vector, layer)
+    }
+
+    /// Add many vectors in a single construction pass.
+    ///
+    /// All vectors are validated before any is inserted, so a model mismatch
+    /// leaves the index unchanged. Layers are assigned up front and nodes are
+    /// inserted from the highest layer down, so the upper navigation layers
+    /// exist before the bulk of layer-0 insertions search through them.
+    /// Once the top node is in place, the rest are inserted in parallel on
+    /// all available cores.
+    pub fn add_batch(&self, items: Vec<(String, Vector)>) -> crate::error::DeltaResult<()> {
+        for (_, vector) in &items {
+            self.check_model(vector)?;
+        }
+
+        // Later duplicates win, as with repeated `add` calls
+        let mut unique: HashMap<String, Vector> = HashMap::with_capacity(items.len());
+        for (id, vector) in items {
+            unique.insert(id, vector);
+        }
+
+        let mut planned: Vec<(usize, String, Vector)> = unique
+            .into_iter()
+            .map(|(id, vector)| {
+                if self.nodes.contains_key(&id) {
+                    self.unlink(&id);
+                }
+                (self.random_layer(), id, vector)
+            })
+            .collect();
+        planned.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+        let mut planned = planned.into_iter();
+        // The top node goes in alone: it may become the entry point, which
+        // every other insertion starts from
+        if let Some((layer, id, vector)) = planned.next() {
+            self.insert_at_layer(id, vector, layer)?;
+        }
+
+        let rest: Vec<_> = planned.collect();
+        let threads = std::thread::available_parallelism()
+            .map_or(1, usize::from)
+            .min(rest.len());
+        if threads <= 1 {
+            for (layer, id, vector) in rest {
+                self.insert_at_layer(id, vector, layer)?;
+            }
+            return Ok(());
+        }
+
+        // Deal the nodes out round-robin, so higher layers still go in first
+        let mut shares: Vec<Vec<_>> = (0..threads).map(|_| Vec::new()).collect();
+        for (at, node) in rest.into_iter().enumerate() {
+            shares[at % threads].push(node);
+        }
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = shares
+                .into_iter()
+                .map(|share| {
+                    scope.spawn(move || {
+                        share.into_iter().try_for_each(|(layer, id, vector)| {
+                            self.insert_at_layer(id, vector, layer)
+                        })
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("HNSW insert worker panicked"))
+                .collect()
+        })
+    }
+
+    /// Reject vectors from a model other than the filter.
+    fn check_model(&self, vector: &Vector) -> crate::error::DeltaResult<()> {
         if let Some(ref filter) = self.model_filter {
             if vector.model() != filter {
                 return Err(crate::error::DeltaError::InvalidData {
@@ -259,36 +409,36 @@ impl HnswIndex {
                 });
             }
         }
+        Ok(())
+    }
 
-        // Check if already exists
-        if self.nodes.contains_key(&id) {
-            // Remove old entry first
-            self.remove(&id);
-        }
-
-        let layer = self.random_layer();
+    /// Insert a new node at a given top layer and connect it into the graph.
+    fn insert_at_layer(
+        &self,
+        id: String,
+        vector: Vector,
+        layer: usize,
+    ) -> crate::error::DeltaResult<()> {
         let node = Node::new(vector.clone(), layer, self.config.m);
         let vector_ref = vector.clone();
 
         // Insert node
         self.nodes.insert(id.clone(), node);
 
-        // Update max layer
+        // If this is the first node, it becomes the entry point
         let current_max = self.max_layer.load(std::sync::atomic::Ordering::Relaxed);
-        if layer > current_max {
+        let entry_point = self.entry_point.read().unwrap().clone();
+        let Some(mut curr_ep) = entry_point else {
             self.max_layer
                 .store(layer, std::sync::atomic::Ordering::Relaxed);
-            *self.entry_point.write().unwrap() = Some(id.clone());
-        }
-
-        // If this is the first node, we're done
-        let entry_point = self.entry_point.read().unwrap().clone();
-        if entry_point.is_none() || (entry_point.as_ref() == Some(&id)) {
+            *self.entry_point.write().unwrap() = Some(id);
+            return Ok(());
+        };
+        if curr_ep == id {
             return Ok(());
         }
 
         // Find entry point for search
-        let mut curr_ep = entry_point.unwrap();
         let curr_node = self.nodes.get(&curr_ep).unwrap();
         let mut curr_dist = self.distance(&curr_node.vector, &vector_ref);
         let curr_max_layer = curr_node.max_layer;
@@ -322,6 +472,13 @@ impl HnswIndex {
             }
         }
 
+        // Promote to entry point only once connected, so the graph stays reachable
+        if layer > current_max {
+            self.max_layer
+                .store(layer, std::sync::atomic::Ordering::Relaxed);
+            *self.entry_point.write().unwrap() = Some(id);
+        }
+
         Ok(())
     }
 
@@ -391,8 +548,10 @@ impl HnswIndex {
             }
         }
 
-        // Return the closest
-        best.pop()
+        // Return the closest (the heap pops the farthest first)
+        best.into_sorted_vec()
+            .into_iter()
+            .next()
             .map(|c| (c.id, -c.distance))
             .unwrap_or_else(|| (entry_point.to_string(), entry_dist))
     }
@@ -463,8 +622,11 @@ impl HnswIndex {
             }
         }
 
-        // Convert to vec
-        best.into_iter().map(|c| (c.id, -c.distance)).collect()
+        // Convert to vec, closest first
+        let mut results: Vec<(String, f32)> =
+            best.into_iter().map(|c| (c.id, -c.distance)).collect();
+        results.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        results
     }
 
     /// Select M neighbors from candidates using simple heuristic.
@@ -527,30 +689,85 @@ impl HnswIndex {
         Ok(())
     }
 
-    /// Compute distance between two vectors (using cosine distance).
+    /// Compute distance between two vectors under the configured metric.
     fn distance(&self, a: &Vector, b: &Vector) -> f32 {
         // Convert similarity to distance: distance = 1 - similarity
-        a.cosine_similarity(b).map(|s| 1.0 - s).unwrap_or(f32::MAX)
+        self.config
+            .metric
+            .similarity(a, b)
+            .map(|s| 1.0 - s)
+            .unwrap_or(f32::MAX)
     }
 
     /// Remove a vector from the index.
+    ///
+    /// The node is tombstoned rather than unlinked: it stops appearing in
+    /// results but keeps its edges, so the graph stays navigable. Call
+    /// [`compact`](Self::compact) to reclaim tombstoned nodes.
     pub fn remove(&self, id: &str) {
-        // First, check if we need to update the entry point
+        if self.nodes.contains_key(id) {
+            self.tombstones.insert(id.to_string());
+        }
+    }
+
+    /// Physically remove all tombstoned nodes and repair the graph.
+    ///
+    /// Every node that pointed at a removed node is reconnected to the removed
+    /// node's neighbors, keeping its closest `2 * M` links, so deletions do
+    /// not fragment the graph. Returns the number of nodes removed.
+    pub fn compact(&self) -> usize {
+        let dead: Vec<String> = self.tombstones.iter().map(|id| id.clone()).collect();
+        let mut removed = 0;
+        for id in &dead {
+            if self.nodes.contains_key(id) {
+                self.unlink(id);
+                removed += 1;
+            } else {
+                self.tombstones.remove(id);
+            }
+        }
+        removed
+    }
+
+    /// Remove a node and its edges, repairing the neighborhoods it was part of.
+    fn unlink(&self, id: &str) {
+        self.tombstones.remove(id);
+
         let needs_ep_update = {
             let ep_guard = self.entry_point.read().unwrap();
             ep_guard.as_ref().map(|ep| ep == id).unwrap_or(false)
         };
 
-        if let Some((_, node)) = self.nodes.remove(id) {
-            // Remove edges at all layers
-            for layer in 0..=node.max_layer {
-                if let Ok(mut layer_guard) = self.layers[layer].write() {
-                    layer_guard.edges.remove(id);
-                    // Remove references from other nodes
-                    for (_, neighbors) in layer_guard.edges.iter_mut() {
-                        neighbors.retain(|n| n != id);
+        let Some((_, node)) = self.nodes.remove(id) else {
+            return;
+        };
+
+        for layer in 0..=node.max_layer {
+            let orphaned: Vec<String> = {
+                let Ok(mut layer_guard) = self.layers[layer].write() else {
+                    continue;
+                };
+                let removed_neighbors = layer_guard.edges.remove(id).unwrap_or_default();
+                let mut orphaned = Vec::new();
+                for (node_id, neighbors) in layer_guard.edges.iter_mut() {
+                    let before = neighbors.len();
+                    neighbors.retain(|n| n != id);
+                    if neighbors.len() == before {
+                        continue;
                     }
+                    // Bridge over the removed node
+                    for candidate in &removed_neighbors {
+                        if candidate != node_id && !neighbors.contains(candidate) {
+                            neighbors.push(candidate.clone());
+                        }
+                    }
+                    orphaned.push(node_id.clone());
                 }
+                orphaned
+            };
+
+            for node_id in orphaned {
+                let _ = self.prune_connections(layer, &node_id);
             }
         }
 
@@ -581,7 +798,7 @@ impl HnswIndex {
     /// # Returns
     /// Vector of search results sorted by similarity (highest first).
     pub fn search(&self, query: &Vector, k: usize, ef: usize) -> Vec<VectorSearchResult> {
-        if self.nodes.is_empty() {
+        if self.is_empty() {
             return Vec::new();
         }
 
@@ -597,7 +814,8 @@ impl HnswIndex {
             None => return Vec::new(),
         };
 
-        let ef = ef.max(k);
+        // Widen the beam so tombstoned candidates don't crowd out live ones
+        let ef = ef.max(k) + self.tombstones.len().min(ef.max(k));
         let max_layer = self.max_layer.load(std::sync::atomic::Ordering::Relaxed);
 
         // Get entry point node
@@ -625,16 +843,12 @@ impl HnswIndex {
         // Build results
         let mut results: Vec<VectorSearchResult> = candidates
             .into_iter()
+            .filter(|(id, _)| !self.tombstones.contains(id))
             .take(k)
             .filter_map(|(id, dist)| {
                 self.nodes.get(&id).map(|node| {
                     let similarity = 1.0 - dist;
-                    // Parse namespace from id (format: "namespace:key")
-                    let (namespace, key) = if let Some(pos) = id.find(':') {
-                        (id[..pos].to_string(), id[pos + 1..].to_string())
-                    } else {
-                        ("default".to_string(), id.clone())
-                    };
+                    let (namespace, key) = parse_node_id(&id);
                     VectorSearchResult::new(namespace, key, similarity, node.vector.clone())
                 })
             })
@@ -653,6 +867,7 @@ impl HnswIndex {
     /// Clear all vectors from the index.
     pub fn clear(&self) {
         self.nodes.clear();
+        self.tombstones.clear();
         for layer in &self.layers {
             if let Ok(mut guard) = layer.write() {
                 guard.edges.clear();
@@ -662,17 +877,360 @@ impl HnswIndex {
         self.max_layer
             .store(0, std::sync::atomic::Ordering::Relaxed);
     }
+
+    /// Serialize the index, graph structure included.
+    pub fn to_bytes(&self) -> DeltaResult<Vec<u8>> {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|entry| SnapshotNode {
+                id: entry.key().clone(),
+                data: entry.value().vector.as_slice().to_vec(),
+                model: entry.value().vector.model().to_string(),
+                max_layer: entry.value().max_layer,
+            })
+            .collect();
+
+        let layers = self
+            .layers
+            .iter()
+            .map(|layer| {
+                let guard = layer.read().unwrap();
+                guard
+                    .edges
+                    .iter()
+                    .map(|(id, neighbors)| (id.clone(), neighbors.clone()))
+                    .collect()
+            })
+            .collect();
+
+        let snapshot = HnswSnapshot {
+            config: self.config,
+            model_filter: self.model_filter.clone(),
+            entry_point: self.entry_point.read().unwrap().clone(),
+            max_layer: self.max_layer.load(std::sync::atomic::Ordering::Relaxed),
+            nodes,
+            layers,
+            tombstones: self.tombstones.iter().map(|id| id.clone()).collect(),
+        };
+
+        let mut bytes = SNAPSHOT_MAGIC.to_vec();
+        bincode::serialize_into(&mut bytes, &snapshot)
+            .map_err(|e| DeltaError::StorageError(format!("Failed to encode HNSW index: {}", e)))?;
+        Ok(bytes)
+    }
+
+    /// Restore an index from [`to_bytes`](Self::to_bytes) output without rebuilding the graph.
+    pub fn from_bytes(bytes: &[u8]) -> DeltaResult<Self> {
+        let body = bytes
+            .strip_prefix(SNAPSHOT_MAGIC.as_slice())
+            .ok_or_else(|| DeltaError::InvalidData {
+                reason: "Not a persisted HNSW index".to_string(),
+            })?;
+        let snapshot: HnswSnapshot =
+            bincode::deserialize(body).map_err(|e| DeltaError::InvalidData {
+                reason: format!("Corrupt HNSW index: {}", e),
+            })?;
+
+        let mut index = Self::new(snapshot.config);
+        index.model_filter = snapshot.model_filter;
+        for node in snapshot.nodes {
+            let vector = Vector::new(node.data, node.model);
+            index.nodes.insert(
+                node.id,
+                Node::new(vector, node.max_layer, snapshot.config.m),
+            );
+        }
+        for (layer, edges) in index.layers.iter().zip(snapshot.layers) {
+            layer.write().unwrap().edges = edges.into_iter().collect();
+        }
+        for id in snapshot.tombstones {
+            index.tombstones.insert(id);
+        }
+        *index.entry_point.write().unwrap() = snapshot.entry_point;
+        index
+            .max_layer
+            .store(snapshot.max_layer, std::sync::atomic::Ordering::Relaxed);
+
+        Ok(index)
+    }
+
+    /// Persist the index to a file.
+    ///
+    /// The file is written to a temporary path and renamed into place, so a
+    /// crash mid-write never leaves a truncated index behind.
+    pub fn save(&self, path: impl AsRef<Path>) -> DeltaResult<()> {
+        let path = path.as_ref();
+        let bytes = self.to_bytes()?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                DeltaError::StorageError(format!("Failed to create index directory: {}", e))
+            })?;
+        }
+
+        let temp_path = path.with_extension("tmp");
+        std::fs::write(&temp_path, bytes)
+            .map_err(|e| DeltaError::StorageError(format!("Failed to write HNSW index: {}", e)))?;
+        std::fs::rename(&temp_path, path)
+            .map_err(|e| DeltaError::StorageError(format!("Failed to rename HNSW index: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Load an index previously written with [`save`](Self::save).
+    ///
+    /// The file is memory-mapped and decoded straight from the mapping, so
+    /// it is never copied into an intermediate buffer.
+    pub fn load(path: impl AsRef<Path>) -> DeltaResult<Self> {
+        let read_error = |e: std::io::Error| {
+            DeltaError::StorageError(format!("Failed to read HNSW index: {}", e))
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let file = std::fs::File::open(path.as_ref()).map_err(read_error)?;
+            // SAFETY: `save` replaces the file by renaming a new one over
+            // it and never writes it in place, so the mapped bytes can't
+            // change while they are decoded
+            let map = unsafe { memmap2::Mmap::map(&file) }.map_err(read_error)?;
+            Self::from_bytes(&map)
+        }
+        #[cfg(target_arch = "wasm32")]
+        Self::from_bytes(&std::fs::read(path.as_ref()).map_err(read_error)?)
+    }
+}
+
+/// On-disk representation of an HNSW index.
+#[derive(Serialize, Deserialize)]
+struct HnswSnapshot {
+    config: HnswConfig,
+    model_filter: Option<String>,
+    entry_point: Option<String>,
+    max_layer: usize,
+    nodes: Vec<SnapshotNode>,
+    /// Per layer: node id -> neighbor ids
+    layers: Vec<Vec<(String, Vec<String>)>>,
+    /// Deleted nodes not yet compacted away
+    tombstones: Vec<String>,
+}
+
+/// A persisted node (vector stored raw to keep the encoding compact).
+#[derive(Serialize, Deserialize)]
+struct SnapshotNode {
+    id: String,
+    data: Vec<f32>,
+    model: String,
+    max_layer: usize,
+}
+
+/// An HNSW index backed by a file, loaded on first use.
+///
+/// Opening is free; the persisted graph is mapped in the first time the
+/// index is searched or modified. A missing file yields an empty index,
+/// which is built incrementally as vectors are added.
+///
+/// A file that exists but can't be loaded also yields an empty index, but
+/// the failure is kept (see [`load_error`](Self::load_error)) and
+/// [`save`](Self::save) refuses to run, so the file is never overwritten
+/// with a partial index; move it aside to start afresh.
+pub struct LazyHnswIndex {
+    path: PathBuf,
+    config: HnswConfig,
+    index: OnceLock<HnswIndex>,
+    load_error: std::sync::Mutex<Option<String>>,
+}
+
+impl std::fmt::Debug for LazyHnswIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LazyHnswIndex")
+            .field("path", &self.path)
+            .field("loaded", &self.is_loaded())
+            .finish()
+    }
+}
+
+impl LazyHnswIndex {
+    /// Open an index file without reading it.
+    ///
+    /// `config` is used only if no persisted index exists yet.
+    pub fn open(path: impl Into<PathBuf>, config: HnswConfig) -> Self {
+        Self {
+            path: path.into(),
+            config,
+            index: OnceLock::new(),
+            load_error: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Path of the backing file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Check whether the index has been loaded into memory.
+    pub fn is_loaded(&self) -> bool {
+        self.index.get().is_some()
+    }
+
+    /// Get the in-memory index, loading it if necessary.
+    pub fn index(&self) -> &HnswIndex {
+        self.index.get_or_init(|| {
+            if !self.path.exists() {
+                return HnswIndex::new(self.config);
+            }
+            match HnswIndex::load(&self.path) {
+                Ok(index) => index,
+                Err(e) => {
+                    tracing::error!(path = %self.path.display(), error = %e, "Failed to load HNSW index; starting empty and leaving the file untouched");
+                    *self.load_error.lock().unwrap() = Some(e.to_string());
+                    HnswIndex::new(self.config)
+                }
+            }
+        })
+    }
+
+    /// Why the persisted index could not be loaded, if it has been tried.
+    pub fn load_error(&self) -> Option<String> {
+        self.load_error.lock().unwrap().clone()
+    }
+
+    /// Write the index back to its file (no-op if it was never loaded).
+    ///
+    /// Fails without touching the file if loading it failed, unless the
+    /// index has since been cleared and rebuilt.
+    pub fn save(&self) -> DeltaResult<()> {
+        if let Some(error) = self.load_error() {
+            return Err(DeltaError::StorageError(format!(
+                "Not overwriting HNSW index {} that failed to load: {}",
+                self.path.display(),
+                error
+            )));
+        }
+        match self.index.get() {
+            Some(index) => index.save(&self.path),
+            None => Ok(()),
+        }
+    }
+}
+
+impl super::index::AnnIndex for LazyHnswIndex {
+    fn add(&self, key: FullKey, vector: Vector) {
+        super::index::AnnIndex::add(self.index(), key, vector);
+    }
+
+    fn add_batch(&self, items: Vec<(FullKey, Vector)>) {
+        super::index::AnnIndex::add_batch(self.index(), items);
+    }
+
+    fn remove(&self, namespace: &str, key: &str) {
+        super::index::AnnIndex::remove(self.index(), namespace, key);
+    }
+
+    fn search(
+        &self,
+        query: &Vector,
+        opts: &super::types::VectorSearchOptions,
+    ) -> Vec<VectorSearchResult> {
+        super::index::AnnIndex::search(self.index(), query, opts)
+    }
+
+    fn len(&self) -> usize {
+        self.index().len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.index().is_empty()
+    }
+
+    fn clear(&self) {
+        self.index().clear();
+        // Whatever the file held is being replaced, so it may be overwritten
+        *self.load_error.lock().unwrap() = None;
+    }
+
+    fn stats(&self) -> VectorIndexStats {
+        self.index().stats()
+    }
+
+    fn entries(&self) -> Vec<(FullKey, Vector)> {
+        super::index::AnnIndex::entries(self.index())
+    }
+
+    fn metric(&self) -> Option<DistanceMetric> {
+        Some(self.index().config.metric)
+    }
+
+    fn tombstone_count(&self) -> usize {
+        self.index.get().map_or(0, HnswIndex::tombstone_count)
+    }
+
+    fn compact(&self) -> usize {
+        self.index.get().map_or(0, HnswIndex::compact)
+    }
+
+    fn persist(&self) -> DeltaResult<()> {
+        self.save()
+    }
+
+    fn load_error(&self) -> Option<String> {
+        self.index();
+        self.load_error()
+    }
+}
+
+/// Build a node id from a namespace and key.
+///
+/// Ids take the form `namespace:key`, with `\` and `:` escaped inside the
+/// namespace so that either part may contain colons.
+pub(crate) fn node_id(namespace: &str, key: &str) -> String {
+    let mut id = String::with_capacity(namespace.len() + key.len() + 1);
+    for c in namespace.chars() {
+        if c == '\\' || c == ':' {
+            id.push('\\');
+        }
+        id.push(c);
+    }
+    id.push(':');
+    id.push_str(key);
+    id
+}
+
+/// Split a node id built by [`node_id`] back into its namespace and key.
+///
+/// Ids without a namespace belong to `"default"`.
+pub(crate) fn parse_node_id(id: &str) -> (String, String) {
+    let mut namespace = String::new();
+    let mut chars = id.char_indices();
+    while let Some((pos, c)) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some((_, escaped)) = chars.next() {
+                    namespace.push(escaped);
+                }
+            }
+            ':' => return (namespace, id[pos + 1..].to_string()),
+            _ => namespace.push(c),
+        }
+    }
+    ("default".to_string(), id.to_string())
 }
 
 impl super::index::AnnIndex for HnswIndex {
     fn add(&self, key: FullKey, vector: Vector) {
-        let id = format!("{}:{}", key.namespace, key.key);
+        let id = node_id(&key.namespace, &key.key);
         let _ = self.add(id, vector);
     }
 
+    fn add_batch(&self, items: Vec<(FullKey, Vector)>) {
+        let items = items
+            .into_iter()
+            .map(|(key, vector)| (node_id(&key.namespace, &key.key), vector))
+            .collect();
+        let _ = self.add_batch(items);
+    }
+
     fn remove(&self, namespace: &str, key: &str) {
-        let id = format!("{}:{}", namespace, key);
-        self.remove(&id);
+        self.remove(&node_id(namespace, key));
     }
 
     fn search(
@@ -680,6 +1238,15 @@ impl super::index::AnnIndex for HnswIndex {
         query: &Vector,
         opts: &super::types::VectorSearchOptions,
     ) -> Vec<VectorSearchResult> {
+        // The graph is only navigable under the metric it was built with;
+        // VectorIndex answers other metrics from an exact index of its own
+        if opts
+            .metric
+            .is_some_and(|metric| metric != self.config.metric)
+        {
+            return Vec::new();
+        }
+
         let results = self.search(query, opts.top_k, self.config.ef_search);
         // Filter by threshold
         results
@@ -699,6 +1266,33 @@ impl super::index::AnnIndex for HnswIndex {
     fn clear(&self) {
         self.clear();
     }
+
+    fn stats(&self) -> VectorIndexStats {
+        self.stats()
+    }
+
+    fn entries(&self) -> Vec<(FullKey, Vector)> {
+        self.nodes
+            .iter()
+            .filter(|entry| !self.tombstones.contains(entry.key()))
+            .map(|entry| {
+                let (namespace, key) = parse_node_id(entry.key());
+                (FullKey::new(namespace, key), entry.value().vector.clone())
+            })
+            .collect()
+    }
+
+    fn metric(&self) -> Option<DistanceMetric> {
+        Some(self.config.metric)
+    }
+
+    fn tombstone_count(&self) -> usize {
+        self.tombstone_count()
+    }
+
+    fn compact(&self) -> usize {
+        self.compact()
+    }
 }
 
 #[cfg(test)]
@@ -709,6 +1303,165 @@ mod tests {
         Vector::new(data, "test-model")
     }
 
+    fn populated_index(n: usize) -> HnswIndex {
+        let index = HnswIndex::new(HnswConfig::default());
+        for i in 0..n {
+            let angle = i as f32 * 0.1;
+            let v = create_test_vector(vec![angle.cos(), angle.sin(), (i % 7) as f32 * 0.01]);
+            index.add(format!("docs:doc{}", i), v).unwrap();
+        }
+        index
+    }
+
+    #[test]
+    fn test_hnsw_add_batch() {
+        let index = HnswIndex::new(HnswConfig::default());
+        let items: Vec<_> = (0..100)
+            .map(|i| {
+                let angle = i as f32 * 0.1;
+                (
+                    format!("docs:doc{}", i),
+                    create_test_vector(vec![angle.cos(), angle.sin(), 0.0]),
+                )
+            })
+            .collect();
+        index.add_batch(items).unwrap();
+        assert_eq!(index.len(), 100);
+
+        // Re-adding an existing id replaces it rather than duplicating
+        index
+            .add_batch(vec![(
+                "docs:doc0".to_string(),
+                create_test_vector(vec![0.0, 0.0, 1.0]),
+            )])
+            .unwrap();
+        assert_eq!(index.len(), 100);
+    }
+
+    #[test]
+    fn test_hnsw_add_batch_validates_model_first() {
+        let index = HnswIndex::with_model_filter(HnswConfig::default(), "test-model");
+        let result = index.add_batch(vec![
+            ("a".to_string(), create_test_vector(vec![1.0, 0.0])),
+            ("b".to_string(), Vector::new(vec![0.0, 1.0], "other-model")),
+        ]);
+        assert!(result.is_err());
+        assert!(index.is_empty());
+    }
+
+    #[test]
+    fn test_hnsw_persistence_roundtrip() {
+        let index = populated_index(200);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.hnsw");
+        index.save(&path).unwrap();
+
+        let restored = HnswIndex::load(&path).unwrap();
+        assert_eq!(restored.len(), index.len());
+
+        let query = create_test_vector(vec![1.0, 0.2, 0.0]);
+        let expected: Vec<_> = index
+            .search(&query, 5, 50)
+            .into_iter()
+            .map(|r| r.key)
+            .collect();
+        let actual: Vec<_> = restored
+            .search(&query, 5, 50)
+            .into_iter()
+            .map(|r| r.key)
+            .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_hnsw_from_bytes_rejects_garbage() {
+        assert!(HnswIndex::from_bytes(b"not an index").is_err());
+
+        let mut bytes = populated_index(3).to_bytes().unwrap();
+        bytes.truncate(SNAPSHOT_MAGIC.len() + 4);
+        assert!(HnswIndex::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_lazy_hnsw_loads_on_first_use() {
+        use crate::vector::index::AnnIndex;
+        use crate::vector::types::VectorSearchOptions;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.hnsw");
+        let original = populated_index(50);
+        original.save(&path).unwrap();
+
+        let lazy = LazyHnswIndex::open(&path, HnswConfig::default());
+        assert!(!lazy.is_loaded());
+
+        let query = create_test_vector(vec![1.0, 0.0, 0.0]);
+        let opts = VectorSearchOptions::new().top_k(3);
+        let results = AnnIndex::search(&lazy, &query, &opts);
+        assert!(lazy.is_loaded());
+        assert_eq!(lazy.len(), 50);
+
+        let keys = |results: Vec<VectorSearchResult>| -> Vec<String> {
+            results.into_iter().map(|r| r.key).collect()
+        };
+        assert_eq!(
+            keys(results),
+            keys(AnnIndex::search(&original, &query, &opts))
+        );
+
+        // Changes survive a save and reopen
+        AnnIndex::add(
+            &lazy,
+            FullKey::new("docs", "extra"),
+            create_test_vector(vec![0.0, 0.0, 1.0]),
+        );
+        lazy.persist().unwrap();
+        assert_eq!(LazyHnswIndex::open(&path, HnswConfig::default()).len(), 51);
+    }
+
+    #[test]
+    fn test_lazy_hnsw_never_overwrites_unloadable_file() {
+        use crate::vector::index::AnnIndex;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.hnsw");
+        std::fs::write(&path, b"not an index").unwrap();
+
+        let lazy = LazyHnswIndex::open(&path, HnswConfig::default());
+        assert!(lazy.index().is_empty());
+        assert!(lazy.load_error().is_some());
+
+        AnnIndex::add(
+            &lazy,
+            FullKey::new("docs", "new"),
+            create_test_vector(vec![1.0, 0.0, 0.0]),
+        );
+        assert!(lazy.persist().is_err());
+        assert_eq!(std::fs::read(&path).unwrap(), b"not an index");
+
+        // Once rebuilt from scratch, the file is replaced
+        AnnIndex::clear(&lazy);
+        AnnIndex::add(
+            &lazy,
+            FullKey::new("docs", "new"),
+            create_test_vector(vec![1.0, 0.0, 0.0]),
+        );
+        lazy.persist().unwrap();
+        assert_eq!(
+            LazyHnswIndex::open(&path, HnswConfig::default())
+                .index()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_lazy_hnsw_missing_file_starts_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let lazy = LazyHnswIndex::open(dir.path().join("missing.hnsw"), HnswConfig::default());
+        assert!(lazy.index().is_empty());
+    }
+
     #[test]
     fn test_hnsw_config_default() {
         let config = HnswConfig::default();
@@ -781,6 +1534,142 @@ mod tests {
         assert!(results.is_empty());
     }
 
+    #[test]
+    fn test_hnsw_remove_tombstones_node() {
+        let index = populated_index(50);
+        let query = create_test_vector(vec![1.0, 0.0, 0.0]);
+        let nearest = index.search(&query, 1, 50)[0].key.clone();
+
+        index.remove(&format!("docs:{}", nearest));
+        assert_eq!(index.len(), 49);
+        assert_eq!(index.tombstone_count(), 1);
+
+        let results = index.search(&query, 10, 50);
+        assert_eq!(results.len(), 10);
+        assert!(results.iter().all(|r| r.key != nearest));
+
+        // Re-adding revives the id
+        index
+            .add(format!("docs:{}", nearest), query.clone())
+            .unwrap();
+        assert_eq!(index.tombstone_count(), 0);
+        assert_eq!(index.len(), 50);
+    }
+
+    #[test]
+    fn test_hnsw_compact_repairs_graph() {
+        let index = populated_index(100);
+        for i in (0..100).step_by(2) {
+            index.remove(&format!("docs:doc{}", i));
+        }
+        assert_eq!(index.tombstone_count(), 50);
+
+        assert_eq!(index.compact(), 50);
+        assert_eq!(index.tombstone_count(), 0);
+        assert_eq!(index.len(), 50);
+        assert_eq!(index.nodes.len(), 50);
+
+        // Surviving nodes only reference surviving nodes
+        for layer in &index.layers {
+            let guard = layer.read().unwrap();
+            for (id, neighbors) in &guard.edges {
+                assert!(index.nodes.contains_key(id));
+                assert!(neighbors.iter().all(|n| index.nodes.contains_key(n)));
+            }
+        }
+
+        // Every survivor is still reachable by searching for itself
+        for i in (1..100).step_by(2) {
+            let angle = i as f32 * 0.1;
+            let query = create_test_vector(vec![angle.cos(), angle.sin(), (i % 7) as f32 * 0.01]);
+            let keys: Vec<String> = index
+                .search(&query, 5, 50)
+                .into_iter()
+                .map(|r| r.key)
+                .collect();
+            assert!(keys.contains(&format!("doc{}", i)), "doc{} unreachable", i);
+        }
+    }
+
+    #[test]
+    fn test_hnsw_tombstones_survive_persistence() {
+        let index = populated_index(20);
+        index.remove("docs:doc3");
+
+        let restored = HnswIndex::from_bytes(&index.to_bytes().unwrap()).unwrap();
+        assert_eq!(restored.len(), 19);
+        assert_eq!(restored.tombstone_count(), 1);
+        assert_eq!(restored.compact(), 1);
+    }
+
+    #[test]
+    fn test_hnsw_stats_and_recall() {
+        let index = super::super::index::VectorIndex::new_hnsw(HnswConfig::with_m(8));
+        for i in 0..300 {
+            let angle = i as f32 * 0.37;
+            let v = create_test_vector(vec![angle.cos(), angle.sin(), (i % 11) as f32 * 0.05]);
+            index.add(FullKey::new("docs", format!("doc{}", i)), v);
+        }
+        index.remove("docs", "doc0");
+
+        let stats = index.stats();
+        assert_eq!(stats.vectors, 299);
+        assert_eq!(stats.tombstones, 1);
+        assert_eq!(stats.layer_distribution.iter().sum::<usize>(), 300);
+        assert!(stats.layer_distribution[0] > stats.layer_distribution[1]);
+        assert!(stats.memory_bytes > 300 * 3 * 4);
+
+        let report = index.evaluate_recall(50, 10);
+        assert_eq!(report.queries, 50);
+        assert!(report.mean_recall > 0.9, "recall {}", report.mean_recall);
+    }
+
+    #[test]
+    fn test_hnsw_parallel_add_batch_recall() {
+        let index = super::super::index::VectorIndex::new_hnsw(HnswConfig::with_m(8));
+        index.add_batch(
+            (0..1000)
+                .map(|i| {
+                    let angle = i as f32 * 0.37;
+                    let v =
+                        create_test_vector(vec![angle.cos(), angle.sin(), (i % 11) as f32 * 0.05]);
+                    (FullKey::new("docs", format!("doc{}", i)), v)
+                })
+                .collect(),
+        );
+        assert_eq!(index.len(), 1000);
+
+        let report = index.evaluate_recall(50, 10);
+        assert!(report.mean_recall > 0.9, "recall {}", report.mean_recall);
+    }
+
+    #[test]
+    fn test_ann_entries_keep_colons() {
+        use super::super::index::AnnIndex;
+
+        let index = HnswIndex::new(HnswConfig::default());
+        let keys = [
+            FullKey::new("team:docs", "a:b"),
+            FullKey::new("team", "docs:a:b"),
+            FullKey::new("back\\slash", "key"),
+        ];
+        for (i, key) in keys.iter().enumerate() {
+            AnnIndex::add(&index, key.clone(), create_test_vector(vec![1.0, i as f32]));
+        }
+
+        let mut entries: Vec<FullKey> = AnnIndex::entries(&index)
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        entries.sort_by(|a, b| (&a.namespace, &a.key).cmp(&(&b.namespace, &b.key)));
+        let mut expected = keys.to_vec();
+        expected.sort_by(|a, b| (&a.namespace, &a.key).cmp(&(&b.namespace, &b.key)));
+        assert_eq!(entries, expected);
+
+        AnnIndex::remove(&index, "team:docs", "a:b");
+        assert_eq!(AnnIndex::len(&index), 2);
+    }
+
     #[test]
     fn test_hnsw_clear() {
         let index = HnswIndex::new(HnswConfig::default());
@@ -6,11 +6,41 @@
 //!
 //! Future: HNSW or IVF indexes for larger datasets.
 
-use super::types::{Vector, VectorSearchOptions, VectorSearchResult};
+use super::hnsw::{HnswConfig, HnswIndex, LazyHnswIndex};
+use super::quantization::{QuantizationConfig, QuantizedIndex};
+use super::types::{DistanceMetric, Vector, VectorSearchOptions, VectorSearchResult};
+use crate::error::DeltaResult;
 use crate::types::FullKey;
 use dashmap::DashMap;
+use std::collections::HashSet;
 use std::sync::Arc;
 
+/// Size and shape of a vector index.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VectorIndexStats {
+    /// Number of live vectors
+    pub vectors: usize,
+    /// Deleted vectors not yet compacted away
+    pub tombstones: usize,
+    /// Number of nodes whose top layer is each layer index (graph indexes only)
+    pub layer_distribution: Vec<usize>,
+    /// Approximate bytes used by vectors, keys, and graph links
+    pub memory_bytes: usize,
+}
+
+/// Search quality of an index measured against exact search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecallReport {
+    /// Number of query vectors evaluated
+    pub queries: usize,
+    /// Number of true nearest neighbors compared per query
+    pub k: usize,
+    /// Average fraction of the true top-k returned by the index
+    pub mean_recall: f32,
+    /// Worst recall observed for a single query
+    pub min_recall: f32,
+}
+
 /// An approximate nearest neighbor index for vectors.
 ///
 /// This trait abstracts over different indexing strategies (flat, HNSW, IVF, etc.)
@@ -19,6 +49,16 @@ pub trait AnnIndex: Send + Sync {
     /// Add a vector to the index.
     fn add(&self, key: FullKey, vector: Vector);
 
+    /// Add many vectors at once.
+    ///
+    /// Graph-based indexes override this to build in a single pass; the
+    /// default inserts one at a time.
+    fn add_batch(&self, items: Vec<(FullKey, Vector)>) {
+        for (key, vector) in items {
+            self.add(key, vector);
+        }
+    }
+
     /// Remove a vector from the index.
     fn remove(&self, namespace: &str, key: &str);
 
@@ -33,6 +73,55 @@ pub trait AnnIndex: Send + Sync {
 
     /// Clear all vectors from the index.
     fn clear(&self);
+
+    /// Describe the index's size and structure.
+    fn stats(&self) -> VectorIndexStats {
+        VectorIndexStats {
+            vectors: self.len(),
+            tombstones: self.tombstone_count(),
+            ..Default::default()
+        }
+    }
+
+    /// All live vectors, as the index stores them.
+    ///
+    /// Used as ground truth by [`VectorIndex::evaluate_recall`]; indexes that
+    /// cannot enumerate their contents return nothing.
+    fn entries(&self) -> Vec<(FullKey, Vector)> {
+        Vec::new()
+    }
+
+    /// The metric the index is built for, if fixed at construction.
+    ///
+    /// Flat indexes accept any metric per query and return None.
+    fn metric(&self) -> Option<DistanceMetric> {
+        None
+    }
+
+    /// Number of deleted entries still occupying space in the index.
+    ///
+    /// Indexes that delete eagerly always report zero.
+    fn tombstone_count(&self) -> usize {
+        0
+    }
+
+    /// Reclaim deleted entries, returning how many were removed.
+    fn compact(&self) -> usize {
+        0
+    }
+
+    /// Flush the index to durable storage, if it has any.
+    ///
+    /// In-memory indexes have nothing to persist.
+    fn persist(&self) -> DeltaResult<()> {
+        Ok(())
+    }
+
+    /// Why the index could not be read back from durable storage, if it
+    /// could not. Indexes without durable storage never fail to load.
+    fn load_error(&self) -> Option<String> {
+        None
+    }
 }
 
 /// A flat (brute-force) vector index.
@@ -94,6 +183,7 @@ impl AnnIndex for FlatIndex {
 
     fn search(&self, query: &Vector, opts: &VectorSearchOptions) -> Vec<VectorSearchResult> {
         let mut results: Vec<VectorSearchResult> = Vec::new();
+        let metric = opts.metric.unwrap_or_default();
 
         // Iterate over all namespaces and vectors
         for namespace_entry in self.vectors.iter() {
@@ -115,8 +205,8 @@ impl AnnIndex for FlatIndex {
                     continue;
                 }
 
-                // Compute similarity (cosine)
-                if let Some(similarity) = query.cosine_similarity(vector) {
+                // Compute similarity under the requested metric
+                if let Some(similarity) = metric.similarity(query, vector) {
                     // Apply threshold
                     if similarity >= opts.threshold {
                         results.push(VectorSearchResult::new(
@@ -154,11 +244,47 @@ impl AnnIndex for FlatIndex {
     fn clear(&self) {
         self.vectors.clear();
     }
+
+    fn stats(&self) -> VectorIndexStats {
+        let memory_bytes = self
+            .vectors
+            .iter()
+            .flat_map(|ns| {
+                ns.value()
+                    .iter()
+                    .map(|e| e.key().len() + e.value().dimensions() * std::mem::size_of::<f32>())
+                    .collect::<Vec<_>>()
+            })
+            .sum();
+        VectorIndexStats {
+            vectors: self.len(),
+            memory_bytes,
+            ..Default::default()
+        }
+    }
+
+    fn entries(&self) -> Vec<(FullKey, Vector)> {
+        self.vectors
+            .iter()
+            .flat_map(|ns| {
+                let namespace = ns.key().clone();
+                ns.value()
+                    .iter()
+                    .map(|e| (FullKey::new(&namespace, e.key()), e.value().clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
 }
 
 /// A thread-safe wrapper around an ANN index.
+///
+/// Queries under a metric the index was not built for are answered by an
+/// exact index kept for that metric, created on first use and kept in step
+/// with the main one.
 pub struct VectorIndex {
     inner: Arc<dyn AnnIndex>,
+    by_metric: Arc<DashMap<DistanceMetric, FlatIndex>>,
 }
 
 impl std::fmt::Debug for VectorIndex {
@@ -175,22 +301,97 @@ impl VectorIndex {
     pub fn new_flat() -> Self {
         Self {
             inner: Arc::new(FlatIndex::new()),
+            by_metric: Arc::default(),
         }
     }
 
+    /// Create a new vector index with an in-memory HNSW backend.
+    pub fn new_hnsw(config: HnswConfig) -> Self {
+        Self {
+            inner: Arc::new(HnswIndex::new(config)),
+            by_metric: Arc::default(),
+        }
+    }
+
+    /// Create a vector index backed by a persisted HNSW file.
+    ///
+    /// The graph is loaded lazily on first use; call [`persist`](Self::persist)
+    /// to write changes back.
+    pub fn open_hnsw(path: impl Into<std::path::PathBuf>, config: HnswConfig) -> Self {
+        Self {
+            inner: Arc::new(LazyHnswIndex::open(path, config)),
+            by_metric: Arc::default(),
+        }
+    }
+
+    /// Create a vector index that stores vectors compressed.
+    ///
+    /// Use [`QuantizationConfig::int8`] for ~4x savings with near-exact recall,
+    /// or [`QuantizationConfig::product`] for much smaller vectors at lower recall.
+    pub fn new_quantized(config: QuantizationConfig) -> Self {
+        Self {
+            inner: Arc::new(QuantizedIndex::new(config)),
+            by_metric: Arc::default(),
+        }
+    }
+
+    /// Flush the index to durable storage (no-op for in-memory backends).
+    pub fn persist(&self) -> DeltaResult<()> {
+        self.inner.persist()
+    }
+
+    /// Why the persisted index could not be loaded, if it could not.
+    ///
+    /// Loads a lazily opened index if that has not happened yet.
+    pub fn load_error(&self) -> Option<String> {
+        self.inner.load_error()
+    }
+
     /// Add a vector to the index.
     pub fn add(&self, key: FullKey, vector: Vector) {
-        self.inner.add(key, vector);
+        // The main index first: a metric index being created meanwhile
+        // either copies the vector from it or is added to here
+        self.inner.add(key.clone(), vector.clone());
+        for index in self.by_metric.iter() {
+            index.add(key.clone(), vector.clone());
+        }
+    }
+
+    /// Add many vectors to the index in one pass.
+    pub fn add_batch(&self, items: Vec<(FullKey, Vector)>) {
+        if self.by_metric.is_empty() {
+            self.inner.add_batch(items);
+            return;
+        }
+        self.inner.add_batch(items.clone());
+        for index in self.by_metric.iter() {
+            index.add_batch(items.clone());
+        }
     }
 
     /// Remove a vector from the index.
     pub fn remove(&self, namespace: &str, key: &str) {
         self.inner.remove(namespace, key);
+        for index in self.by_metric.iter() {
+            index.remove(namespace, key);
+        }
     }
 
     /// Search for nearest neighbors.
     pub fn search(&self, query: &Vector, opts: &VectorSearchOptions) -> Vec<VectorSearchResult> {
-        self.inner.search(query, opts)
+        match (opts.metric, self.inner.metric()) {
+            (Some(metric), Some(built)) if metric != built => self
+                .by_metric
+                .entry(metric)
+                .or_insert_with(|| {
+                    let index = FlatIndex::new();
+                    index.add_batch(self.inner.entries());
+                    index
+                })
+                .downgrade()
+                .search(query, opts),
+            _ => self.inner.search(query, opts),
+        }
     }
 
     /// Get the number of vectors in the index.
@@ -206,6 +407,74 @@ impl VectorIndex {
     /// Clear all vectors from the index.
     pub fn clear(&self) {
         self.inner.clear();
+        self.by_metric.clear();
+    }
+
+    /// Describe the index's size and structure.
+    pub fn stats(&self) -> VectorIndexStats {
+        self.inner.stats()
+    }
+
+    /// Measure how well the index recovers exact nearest neighbors.
+    ///
+    /// Up to `sample` stored vectors, spread evenly across the index, are used
+    /// as queries. For each, the index's top `ground_truth_k` results are
+    /// compared with an exhaustive scan under the index's metric. Use this to
+    /// tune `HnswConfig` (`m`, `ef_construction`, `ef_search`) on your own data.
+    pub fn evaluate_recall(&self, sample: usize, ground_truth_k: usize) -> RecallReport {
+        let mut entries = self.inner.entries();
+        entries.sort_by(|a, b| (&a.0.namespace, &a.0.key).cmp(&(&b.0.namespace, &b.0.key)));
+        let metric = self.inner.metric().unwrap_or_default();
+
+        let k = ground_truth_k.max(1);
+        let step = (entries.len() / sample.max(1)).max(1);
+        let mut recalls = Vec::new();
+
+        for (_, query) in entries.iter().step_by(step).take(sample) {
+            let mut truth: Vec<(&FullKey, f32)> = entries
+                .iter()
+                .filter(|(_, v)| v.model() == query.model())
+                .filter_map(|(key, v)| metric.similarity(query, v).map(|s| (key, s)))
+                .collect();
+            truth.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+            let truth: HashSet<&FullKey> = truth.into_iter().take(k).map(|(key, _)| key).collect();
+
+            let opts = VectorSearchOptions::new()
+                .top_k(k)
+                .threshold(f32::NEG_INFINITY)
+                .model_filter(query.model());
+            let found = self
+                .search(query, &opts)
+                .into_iter()
+                .filter(|r| truth.contains(&FullKey::new(&r.namespace, &r.key)))
+                .count();
+            recalls.push(found as f32 / truth.len() as f32);
+        }
+
+        RecallReport {
+            queries: recalls.len(),
+            k,
+            mean_recall: if recalls.is_empty() {
+                0.0
+            } else {
+                recalls.iter().sum::<f32>() / recalls.len() as f32
+            },
+            min_recall: recalls
+                .iter()
+                .copied()
+                .fold(f32::INFINITY, f32::min)
+                .min(1.0),
+        }
+    }
+
+    /// Number of deleted entries awaiting compaction.
+    pub fn tombstone_count(&self) -> usize {
+        self.inner.tombstone_count()
+    }
+
+    /// Reclaim deleted entries, returning how many were removed.
+    pub fn compact(&self) -> usize {
+        self.inner.compact()
     }
 }
 
@@ -219,6 +488,7 @@ impl Clone for VectorIndex {
     fn clone(&self) -> Self {
         Self {
             inner: Arc::clone(&self.inner),
+            by_metric: Arc::clone(&self.by_metric),
         }
     }
 }
@@ -330,6 +600,62 @@ mod tests {
         assert_eq!(results.len(), 1);
     }
 
+    #[test]
+    fn test_flat_index_stats_and_exact_recall() {
+        let index = VectorIndex::new_flat();
+        for i in 0..20 {
+            let angle = i as f32 * 0.3;
+            index.add(
+                FullKey::new("docs", format!("doc{}", i)),
+                Vector::new(vec![angle.cos(), angle.sin()], "test-model"),
+            );
+        }
+
+        let stats = index.stats();
+        assert_eq!(stats.vectors, 20);
+        assert_eq!(stats.tombstones, 0);
+        assert!(stats.layer_distribution.is_empty());
+        assert!(stats.memory_bytes >= 20 * 2 * 4);
+
+        let report = index.evaluate_recall(5, 3);
+        assert_eq!(report.queries, 5);
+        assert_eq!(report.k, 3);
+        assert_eq!(report.mean_recall, 1.0);
+        assert_eq!(report.min_recall, 1.0);
+    }
+
+    #[test]
+    fn test_hnsw_search_under_other_metric_tracks_updates() {
+        let index = VectorIndex::new_hnsw(HnswConfig::default());
+        index.add(
+            FullKey::new("docs", "near"),
+            Vector::new(vec![1.0, 0.0], "test"),
+        );
+        index.add(
+            FullKey::new("docs", "far"),
+            Vector::new(vec![10.0, 0.0], "test"),
+        );
+
+        let query = Vector::new(vec![1.0, 0.1], "test");
+        let opts = VectorSearchOptions::new()
+            .top_k(1)
+            .metric(DistanceMetric::Euclidean);
+        let results = index.search(&query, &opts);
+        assert_eq!(results[0].key, "near");
+
+        // Later writes reach the euclidean index without a rebuild
+        index.add(
+            FullKey::new("docs", "nearer"),
+            Vector::new(vec![1.0, 0.1], "test"),
+        );
+        let results = index.search(&query, &opts);
+        assert_eq!(results[0].key, "nearer");
+
+        index.remove("docs", "nearer");
+        let results = index.search(&query, &opts);
+        assert_eq!(results[0].key, "near");
+    }
+
     #[test]
     fn test_vector_index_clone() {
         let index = VectorIndex::new_flat();
@@ -37,22 +37,37 @@
 
 mod causal_index;
 mod distinction_integration;
+mod embedder;
 mod hnsw;
 mod index;
+mod provider;
+mod quantization;
 pub mod snsw;
 mod types;
 
 // Public exports
 pub use causal_index::{CausalIndexConfig, CausalVectorIndex, IndexSnapshot, SnapshotStats};
 pub use distinction_integration::{DistinctionBackedSNSW, DistinctionVector};
-pub use hnsw::{HnswConfig, HnswIndex};
-pub use index::{AnnIndex, FlatIndex, VectorIndex};
+#[cfg(all(not(target_arch = "wasm32"), feature = "embedder"))]
+pub use embedder::SentenceEmbedder;
+pub use embedder::{HashingEmbedder, TextEmbedder};
+pub use hnsw::{HnswConfig, HnswIndex, LazyHnswIndex};
+pub use index::{AnnIndex, FlatIndex, RecallReport, VectorIndex, VectorIndexStats};
+#[cfg(all(not(target_arch = "wasm32"), feature = "http"))]
+pub use provider::OpenAiEmbeddingProvider;
+pub(crate) use provider::default_provider as default_embedding_provider;
+pub use provider::{EmbeddingProvider, LocalEmbeddingProvider};
+pub use quantization::{
+    Int8Vector, ProductQuantizer, QuantizationConfig, QuantizationMode, QuantizedIndex,
+};
 pub use snsw::{
-    ContentHash, DistinctionOverlap, ExplainableResult, NavigationOp, ProximityWeights,
-    SearchResult, SearchTier, SynthesisEdge, SynthesisExplanation, SynthesisGraph, SynthesisNode,
-    SynthesisPath, SynthesisProximity, SynthesisType,
+    ContentHash, DistinctionOverlap, ExplainableResult, ExplainedSearchResult, NavigationOp,
+    ProximityWeights, SearchResult, SearchTier, SynthesisEdge, SynthesisExplanation,
+    SynthesisGraph, SynthesisNode, SynthesisPath, SynthesisProximity, SynthesisType,
+};
+pub use types::{
+    DistanceMetric, Vector, VectorNamespaceConfig, VectorSearchOptions, VectorSearchResult,
 };
-pub use types::{Vector, VectorSearchOptions, VectorSearchResult};
 
 // Re-export snsw module for advanced usage
 pub use snsw as synthesis_navigable;
@@ -61,6 +76,9 @@ use crate::error::DeltaResult;
 use crate::types::VersionedValue;
 use serde_json::json;
 
+/// Internal namespace holding per-namespace vector index configuration.
+pub const VECTOR_CONFIG_NAMESPACE: &str = "__vector_config";
+
 /// Extension trait for vector operations on KoruDelta.
 ///
 /// This trait adds embedding storage and search capabilities to the core
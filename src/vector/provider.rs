@@ -0,0 +1,416 @@
+//! Embedding providers.
+//!
+//! An [`EmbeddingProvider`] turns batches of text into vectors, either
+//! in-process ([`LocalEmbeddingProvider`], wrapping any [`TextEmbedder`]) or
+//! by calling a remote service ([`OpenAiEmbeddingProvider`], for any
+//! OpenAI-compatible `/embeddings` endpoint; requires the `http` feature).
+//!
+//! Remote providers handle batching, retries with exponential backoff, and
+//! client-side rate limiting, so callers can hand over any number of texts.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use koru_delta::vector::OpenAiEmbeddingProvider;
+//!
+//! let provider = OpenAiEmbeddingProvider::new("https://api.openai.com/v1", "text-embedding-3-small")
+//!     .with_api_key(std::env::var("OPENAI_API_KEY")?)
+//!     .with_requests_per_second(5.0);
+//! db.set_embedding_provider(Arc::new(provider));
+//! db.embed_text("docs", "k1", "full text").await?;
+//! ```
+
+use super::embedder::TextEmbedder;
+use super::types::Vector;
+use crate::error::{DeltaError, DeltaResult};
+use std::sync::Arc;
+
+/// Produces embeddings for batches of text.
+#[async_trait::async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    /// Identifier recorded as the model of every produced vector.
+    fn model(&self) -> &str;
+
+    /// Embed many texts; the result has one vector per text, in order.
+    async fn embed_batch(&self, texts: &[String]) -> DeltaResult<Vec<Vector>>;
+
+    /// Embed a single text.
+    async fn embed(&self, text: &str) -> DeltaResult<Vector> {
+        self.embed_batch(&[text.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| DeltaError::EmbeddingError("Provider returned no vector".to_string()))
+    }
+}
+
+/// Runs a [`TextEmbedder`] in-process.
+#[derive(Clone)]
+pub struct LocalEmbeddingProvider {
+    embedder: Arc<dyn TextEmbedder>,
+}
+
+impl std::fmt::Debug for LocalEmbeddingProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalEmbeddingProvider")
+            .field("model", &self.embedder.model())
+            .finish()
+    }
+}
+
+impl LocalEmbeddingProvider {
+    /// Wrap a local embedder.
+    pub fn new(embedder: Arc<dyn TextEmbedder>) -> Self {
+        Self { embedder }
+    }
+}
+
+#[async_trait::async_trait]
+impl EmbeddingProvider for LocalEmbeddingProvider {
+    fn model(&self) -> &str {
+        self.embedder.model()
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> DeltaResult<Vec<Vector>> {
+        // Models can take a while, and may fetch their files on first use,
+        // so keep them off the async workers
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let embedder = Arc::clone(&self.embedder);
+            let texts = texts.to_vec();
+            return runtime
+                .spawn_blocking(move || texts.iter().map(|text| embedder.embed(text)).collect())
+                .await
+                .map_err(|e| DeltaError::EmbeddingError(e.to_string()))?;
+        }
+        texts.iter().map(|text| self.embedder.embed(text)).collect()
+    }
+}
+
+/// Provider a new database embeds text with: the sentence transformer
+/// `config` names, otherwise a [`HashingEmbedder`](super::HashingEmbedder).
+///
+/// Without the `embedder` feature a configured model is ignored here;
+/// [`CoreConfig::validate`](crate::CoreConfig::validate) rejects it.
+pub(crate) fn default_provider(config: &crate::core::EmbedderConfig) -> Arc<dyn EmbeddingProvider> {
+    #[cfg(all(not(target_arch = "wasm32"), feature = "embedder"))]
+    let embedder: Arc<dyn TextEmbedder> = match (&config.model_dir, &config.hub_model) {
+        (Some(dir), _) => Arc::new(super::SentenceEmbedder::from_dir(dir)),
+        (None, Some(model_id)) => Arc::new(super::SentenceEmbedder::from_hub(model_id)),
+        (None, None) => Arc::new(super::HashingEmbedder::default()),
+    };
+    #[cfg(not(all(not(target_arch = "wasm32"), feature = "embedder")))]
+    let embedder: Arc<dyn TextEmbedder> = {
+        let _ = config;
+        Arc::new(super::HashingEmbedder::default())
+    };
+    Arc::new(LocalEmbeddingProvider::new(embedder))
+}
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "http"))]
+pub use remote::OpenAiEmbeddingProvider;
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "http"))]
+mod remote {
+    use super::*;
+    use serde::Deserialize;
+    use std::time::{Duration, Instant};
+    use tokio::sync::Mutex;
+
+    /// Calls an OpenAI-compatible `POST {base_url}/embeddings` endpoint.
+    ///
+    /// Texts are sent in chunks of `batch_size`. Requests failing with a
+    /// network error, `429`, or a `5xx` status are retried up to
+    /// `max_retries` times with exponential backoff (honoring `Retry-After`).
+    /// Requests are spaced to stay under the configured rate limit.
+    pub struct OpenAiEmbeddingProvider {
+        client: reqwest::Client,
+        base_url: String,
+        model: String,
+        api_key: Option<String>,
+        batch_size: usize,
+        max_retries: u32,
+        initial_backoff: Duration,
+        min_interval: Option<Duration>,
+        next_request: Mutex<Option<Instant>>,
+    }
+
+    impl std::fmt::Debug for OpenAiEmbeddingProvider {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            // The API key is deliberately left out
+            f.debug_struct("OpenAiEmbeddingProvider")
+                .field("base_url", &self.base_url)
+                .field("model", &self.model)
+                .field("batch_size", &self.batch_size)
+                .field("max_retries", &self.max_retries)
+                .finish_non_exhaustive()
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct EmbeddingResponse {
+        data: Vec<EmbeddingData>,
+    }
+
+    #[derive(Deserialize)]
+    struct EmbeddingData {
+        embedding: Vec<f32>,
+        #[serde(default)]
+        index: Option<usize>,
+    }
+
+    impl OpenAiEmbeddingProvider {
+        /// Create a provider for `model` at `base_url` (e.g. `https://api.openai.com/v1`).
+        pub fn new(base_url: impl Into<String>, model: impl Into<String>) -> Self {
+            Self {
+                client: reqwest::Client::new(),
+                base_url: base_url.into().trim_end_matches('/').to_string(),
+                model: model.into(),
+                api_key: None,
+                batch_size: 100,
+                max_retries: 3,
+                initial_backoff: Duration::from_millis(500),
+                min_interval: None,
+                next_request: Mutex::new(None),
+            }
+        }
+
+        /// Send `Authorization: Bearer <key>` with each request.
+        pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+            self.api_key = Some(api_key.into());
+            self
+        }
+
+        /// Maximum texts per request (default: 100).
+        pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+            self.batch_size = batch_size.max(1);
+            self
+        }
+
+        /// Retries per request after the first attempt (default: 3).
+        pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+            self.max_retries = max_retries;
+            self
+        }
+
+        /// Delay before the first retry; doubles on each further retry (default: 500ms).
+        pub fn with_initial_backoff(mut self, backoff: Duration) -> Self {
+            self.initial_backoff = backoff;
+            self
+        }
+
+        /// Limit outgoing requests per second (default: unlimited).
+        pub fn with_requests_per_second(mut self, requests_per_second: f64) -> Self {
+            self.min_interval = (requests_per_second > 0.0)
+                .then(|| Duration::from_secs_f64(1.0 / requests_per_second));
+            self
+        }
+
+        /// Wait until the rate limit allows another request.
+        async fn throttle(&self) {
+            let Some(interval) = self.min_interval else {
+                return;
+            };
+            let mut next = self.next_request.lock().await;
+            let now = Instant::now();
+            if let Some(at) = *next {
+                if at > now {
+                    tokio::time::sleep(at - now).await;
+                }
+            }
+            *next = Some(Instant::now() + interval);
+        }
+
+        /// Embed one chunk, retrying transient failures.
+        async fn request(&self, texts: &[String]) -> DeltaResult<Vec<Vector>> {
+            let url = format!("{}/embeddings", self.base_url);
+            let body = serde_json::json!({ "model": self.model, "input": texts });
+            let mut backoff = self.initial_backoff;
+            let mut attempt = 0;
+
+            loop {
+                self.throttle().await;
+
+                let mut request = self.client.post(&url).json(&body);
+                if let Some(ref key) = self.api_key {
+                    request = request.bearer_auth(key);
+                }
+
+                let retry_after = match request.send().await {
+                    Ok(response) if response.status().is_success() => {
+                        let parsed: EmbeddingResponse = response.json().await.map_err(|e| {
+                            DeltaError::EmbeddingError(format!("Invalid response: {}", e))
+                        })?;
+                        return self.parse_vectors(parsed, texts.len());
+                    }
+                    Ok(response)
+                        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
+                            || response.status().is_server_error() =>
+                    {
+                        let retry_after = response
+                            .headers()
+                            .get(reqwest::header::RETRY_AFTER)
+                            .and_then(|v| v.to_str().ok())
+                            .and_then(|v| v.parse::<u64>().ok())
+                            .map(Duration::from_secs);
+                        if attempt >= self.max_retries {
+                            return Err(DeltaError::EmbeddingError(format!(
+                                "Provider returned {} after {} attempts",
+                                response.status(),
+                                attempt + 1
+                            )));
+                        }
+                        retry_after
+                    }
+                    Ok(response) => {
+                        let status = response.status();
+                        let text = response.text().await.unwrap_or_default();
+                        return Err(DeltaError::EmbeddingError(format!(
+                            "Provider returned {}: {}",
+                            status, text
+                        )));
+                    }
+                    Err(e) if attempt < self.max_retries => {
+                        tracing::debug!(error = %e, attempt, "Embedding request failed; retrying");
+                        None
+                    }
+                    Err(e) => {
+                        return Err(DeltaError::EmbeddingError(format!(
+                            "Request failed after {} attempts: {}",
+                            attempt + 1,
+                            e
+                        )));
+                    }
+                };
+
+                tokio::time::sleep(retry_after.unwrap_or(backoff)).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+        }
+
+        fn parse_vectors(
+            &self,
+            response: EmbeddingResponse,
+            expected: usize,
+        ) -> DeltaResult<Vec<Vector>> {
+            if response.data.len() != expected {
+                return Err(DeltaError::EmbeddingError(format!(
+                    "Expected {} embeddings, got {}",
+                    expected,
+                    response.data.len()
+                )));
+            }
+
+            let mut data = response.data;
+            data.sort_by_key(|d| d.index.unwrap_or(usize::MAX));
+            data.into_iter()
+                .map(|d| {
+                    if d.embedding.is_empty() {
+                        return Err(DeltaError::EmbeddingError(
+                            "Provider returned an empty embedding".to_string(),
+                        ));
+                    }
+                    Ok(Vector::new(d.embedding, self.model.clone()))
+                })
+                .collect()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl EmbeddingProvider for OpenAiEmbeddingProvider {
+        fn model(&self) -> &str {
+            &self.model
+        }
+
+        async fn embed_batch(&self, texts: &[String]) -> DeltaResult<Vec<Vector>> {
+            let mut vectors = Vec::with_capacity(texts.len());
+            for chunk in texts.chunks(self.batch_size) {
+                vectors.extend(self.request(chunk).await?);
+            }
+            Ok(vectors)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use axum::{Json, Router, http::StatusCode, routing::post};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        /// Serve a fake endpoint that rate-limits the first request.
+        async fn mock_server(calls: Arc<AtomicUsize>) -> String {
+            let app = Router::new().route(
+                "/v1/embeddings",
+                post(move |Json(body): Json<serde_json::Value>| {
+                    let calls = Arc::clone(&calls);
+                    async move {
+                        if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                            return Err(StatusCode::TOO_MANY_REQUESTS);
+                        }
+                        let inputs = body["input"].as_array().cloned().unwrap_or_default();
+                        let data: Vec<_> = inputs
+                            .iter()
+                            .enumerate()
+                            .rev()
+                            .map(|(i, text)| {
+                                let len = text.as_str().unwrap_or("").len() as f32;
+                                serde_json::json!({"index": i, "embedding": [len, 1.0]})
+                            })
+                            .collect();
+                        Ok(Json(serde_json::json!({ "data": data })))
+                    }
+                }),
+            );
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+            format!("http://{}/v1", addr)
+        }
+
+        #[tokio::test]
+        async fn test_openai_provider_batches_and_retries() {
+            let calls = Arc::new(AtomicUsize::new(0));
+            let base_url = mock_server(Arc::clone(&calls)).await;
+            let provider = OpenAiEmbeddingProvider::new(base_url, "mock-model")
+                .with_batch_size(2)
+                .with_initial_backoff(Duration::from_millis(1));
+
+            let texts: Vec<String> = ["a", "bb", "ccc"].iter().map(|s| s.to_string()).collect();
+            let vectors = provider.embed_batch(&texts).await.unwrap();
+
+            // One rate-limited attempt, then two chunks
+            assert_eq!(calls.load(Ordering::SeqCst), 3);
+            let lengths: Vec<f32> = vectors.iter().map(|v| v.as_slice()[0]).collect();
+            assert_eq!(lengths, vec![1.0, 2.0, 3.0]);
+            assert_eq!(vectors[0].model(), "mock-model");
+        }
+
+        #[tokio::test]
+        async fn test_openai_provider_gives_up_after_retries() {
+            let calls = Arc::new(AtomicUsize::new(0));
+            let base_url = mock_server(Arc::clone(&calls)).await;
+            let provider = OpenAiEmbeddingProvider::new(base_url, "mock-model").with_max_retries(0);
+
+            assert!(provider.embed("text").await.is_err());
+            assert_eq!(calls.load(Ordering::SeqCst), 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector::HashingEmbedder;
+
+    #[tokio::test]
+    async fn test_local_provider_embeds_in_order() {
+        let embedder = Arc::new(HashingEmbedder::new(32));
+        let provider = LocalEmbeddingProvider::new(embedder.clone());
+
+        let texts = vec!["first text".to_string(), "second text".to_string()];
+        let vectors = provider.embed_batch(&texts).await.unwrap();
+        assert_eq!(vectors.len(), 2);
+        assert_eq!(vectors[1], embedder.embed("second text").unwrap());
+        assert_eq!(provider.model(), embedder.model());
+    }
+}
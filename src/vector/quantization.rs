@@ -0,0 +1,604 @@
+//! Vector quantization for memory-constrained deployments.
+//!
+//! Full-precision embeddings cost 4 bytes per dimension. Quantization trades
+//! a little recall for a lot of memory:
+//!
+//! - **Int8 (scalar)**: each component is stored as a signed byte with one
+//!   scale factor per vector. ~4x smaller, recall is nearly unchanged.
+//! - **Product quantization (PQ)**: the vector is split into `subspaces`
+//!   chunks and each chunk is replaced by the id of its nearest centroid in a
+//!   learned codebook. One byte per subspace, so a 768-dim embedding with 96
+//!   subspaces takes 96 bytes (~32x smaller) at some cost in recall.
+//!
+//! PQ codebooks are trained per embedding model once `training_size`
+//! vectors have been seen. Until then vectors are held as int8, and they are
+//! re-encoded when the codebook becomes available.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use koru_delta::vector::{QuantizationConfig, VectorIndex};
+//!
+//! // ~4x compression, near-exact recall
+//! let index = VectorIndex::new_quantized(QuantizationConfig::int8());
+//!
+//! // ~32x compression for 768-dim embeddings
+//! let index = VectorIndex::new_quantized(QuantizationConfig::product(96));
+//! ```
+
+use super::index::{AnnIndex, VectorIndexStats};
+use super::types::{Vector, VectorSearchOptions, VectorSearchResult};
+use crate::types::FullKey;
+use dashmap::DashMap;
+use std::sync::{Arc, Mutex};
+
+/// How vectors are compressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuantizationMode {
+    /// Scalar int8 quantization (one byte per dimension)
+    Int8,
+    /// Product quantization (one byte per subspace)
+    Product {
+        /// Number of chunks each vector is split into
+        subspaces: usize,
+        /// Centroids per subspace codebook (at most 256)
+        centroids: usize,
+    },
+}
+
+/// Configuration for a quantized vector index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuantizationConfig {
+    /// Compression mode
+    pub mode: QuantizationMode,
+    /// Vectors to collect per model before training PQ codebooks
+    pub training_size: usize,
+    /// K-means iterations when training PQ codebooks
+    pub kmeans_iterations: usize,
+}
+
+impl QuantizationConfig {
+    /// Scalar int8 quantization.
+    pub fn int8() -> Self {
+        Self {
+            mode: QuantizationMode::Int8,
+            training_size: 0,
+            kmeans_iterations: 0,
+        }
+    }
+
+    /// Product quantization with the given number of subspaces.
+    ///
+    /// Fewer subspaces means less memory and lower recall.
+    pub fn product(subspaces: usize) -> Self {
+        Self {
+            mode: QuantizationMode::Product {
+                subspaces: subspaces.max(1),
+                centroids: 256,
+            },
+            training_size: 1024,
+            kmeans_iterations: 10,
+        }
+    }
+
+    /// Set the number of centroids per PQ codebook (clamped to 1..=256).
+    pub fn with_centroids(mut self, centroids: usize) -> Self {
+        if let QuantizationMode::Product { subspaces, .. } = self.mode {
+            self.mode = QuantizationMode::Product {
+                subspaces,
+                centroids: centroids.clamp(1, 256),
+            };
+        }
+        self
+    }
+
+    /// Set how many vectors are collected before PQ codebooks are trained.
+    pub fn with_training_size(mut self, training_size: usize) -> Self {
+        self.training_size = training_size.max(1);
+        self
+    }
+
+    /// Set the number of k-means iterations used for PQ training.
+    pub fn with_kmeans_iterations(mut self, iterations: usize) -> Self {
+        self.kmeans_iterations = iterations.max(1);
+        self
+    }
+}
+
+impl Default for QuantizationConfig {
+    fn default() -> Self {
+        Self::int8()
+    }
+}
+
+/// A vector compressed to int8 with a per-vector scale.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Int8Vector {
+    codes: Vec<i8>,
+    scale: f32,
+}
+
+impl Int8Vector {
+    /// Quantize a vector (symmetric, scaled by its largest component).
+    pub fn encode(data: &[f32]) -> Self {
+        let max_abs = data.iter().fold(0.0f32, |acc, x| acc.max(x.abs()));
+        let scale = if max_abs > 0.0 { max_abs / 127.0 } else { 1.0 };
+        let codes = data
+            .iter()
+            .map(|x| (x / scale).round().clamp(-127.0, 127.0) as i8)
+            .collect();
+        Self { codes, scale }
+    }
+
+    /// Reconstruct an approximation of the original components.
+    pub fn decode(&self) -> Vec<f32> {
+        self.codes.iter().map(|&c| c as f32 * self.scale).collect()
+    }
+
+    /// Bytes used by the compressed representation.
+    pub fn memory_bytes(&self) -> usize {
+        self.codes.len() + std::mem::size_of::<f32>()
+    }
+}
+
+/// A trained product quantizer for vectors of one dimensionality.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProductQuantizer {
+    dimensions: usize,
+    /// Component range covered by each subspace
+    ranges: Vec<(usize, usize)>,
+    /// Per subspace: centroids, each `end - start` components long
+    codebooks: Vec<Vec<Vec<f32>>>,
+}
+
+impl ProductQuantizer {
+    /// Train codebooks on sample vectors with k-means.
+    ///
+    /// Returns None if there are no samples or they have mixed dimensions.
+    pub fn train(
+        samples: &[&[f32]],
+        subspaces: usize,
+        centroids: usize,
+        iterations: usize,
+    ) -> Option<Self> {
+        let dimensions = samples.first()?.len();
+        if dimensions == 0 || samples.iter().any(|s| s.len() != dimensions) {
+            return None;
+        }
+
+        let subspaces = subspaces.clamp(1, dimensions);
+        let centroids = centroids.clamp(1, 256).min(samples.len());
+        let ranges: Vec<(usize, usize)> = (0..subspaces)
+            .map(|i| (i * dimensions / subspaces, (i + 1) * dimensions / subspaces))
+            .collect();
+
+        let codebooks = ranges
+            .iter()
+            .map(|&(start, end)| {
+                let chunks: Vec<&[f32]> = samples.iter().map(|s| &s[start..end]).collect();
+                kmeans(&chunks, centroids, iterations.max(1))
+            })
+            .collect();
+
+        Some(Self {
+            dimensions,
+            ranges,
+            codebooks,
+        })
+    }
+
+    /// Dimensionality this quantizer was trained for.
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// Encode a vector as one centroid id per subspace.
+    pub fn encode(&self, data: &[f32]) -> Vec<u8> {
+        self.ranges
+            .iter()
+            .zip(&self.codebooks)
+            .map(|(&(start, end), codebook)| nearest(codebook, &data[start..end]) as u8)
+            .collect()
+    }
+
+    /// Reconstruct an approximation of an encoded vector.
+    pub fn decode(&self, codes: &[u8]) -> Vec<f32> {
+        codes
+            .iter()
+            .zip(&self.codebooks)
+            .flat_map(|(&code, codebook)| codebook[code as usize].iter().copied())
+            .collect()
+    }
+}
+
+/// Index of the centroid nearest to `point` (squared Euclidean).
+fn nearest(centroids: &[Vec<f32>], point: &[f32]) -> usize {
+    centroids
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (i, squared_distance(c, point)))
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+fn squared_distance(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+/// Lloyd's k-means with deterministic, evenly spaced initialization.
+fn kmeans(points: &[&[f32]], k: usize, iterations: usize) -> Vec<Vec<f32>> {
+    let dims = points[0].len();
+    let mut centroids: Vec<Vec<f32>> = (0..k)
+        .map(|i| points[i * points.len() / k].to_vec())
+        .collect();
+
+    for _ in 0..iterations {
+        let mut sums = vec![vec![0.0f32; dims]; k];
+        let mut counts = vec![0usize; k];
+        for point in points {
+            let c = nearest(&centroids, point);
+            counts[c] += 1;
+            for (sum, x) in sums[c].iter_mut().zip(point.iter()) {
+                *sum += x;
+            }
+        }
+
+        let mut moved = false;
+        for ((centroid, sum), count) in centroids.iter_mut().zip(sums).zip(counts) {
+            if count == 0 {
+                continue; // keep empty clusters where they are
+            }
+            let updated: Vec<f32> = sum.into_iter().map(|s| s / count as f32).collect();
+            moved |= updated != *centroid;
+            *centroid = updated;
+        }
+        if !moved {
+            break;
+        }
+    }
+
+    centroids
+}
+
+/// Stored representation of one vector.
+#[derive(Debug, Clone)]
+enum Codes {
+    Int8(Int8Vector),
+    Product {
+        codes: Vec<u8>,
+        quantizer: Arc<ProductQuantizer>,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct QuantizedEntry {
+    model: String,
+    codes: Codes,
+}
+
+impl QuantizedEntry {
+    fn decode(&self) -> Vec<f32> {
+        match &self.codes {
+            Codes::Int8(v) => v.decode(),
+            Codes::Product { codes, quantizer } => quantizer.decode(codes),
+        }
+    }
+
+    fn memory_bytes(&self) -> usize {
+        match &self.codes {
+            Codes::Int8(v) => v.memory_bytes(),
+            Codes::Product { codes, .. } => codes.len(),
+        }
+    }
+}
+
+/// Codebook (or training buffer) for one embedding model and dimensionality.
+#[derive(Debug, Default)]
+struct ModelCodebook {
+    quantizer: Option<Arc<ProductQuantizer>>,
+    /// Vectors seen before training, kept at full precision
+    pending: Vec<(FullKey, Vector)>,
+}
+
+/// A flat vector index that stores vectors in compressed form.
+///
+/// Search decodes each candidate and scores it against the full-precision
+/// query, so results carry approximate scores and vectors.
+#[derive(Debug)]
+pub struct QuantizedIndex {
+    config: QuantizationConfig,
+    /// namespace -> (key -> compressed vector)
+    vectors: DashMap<String, DashMap<String, QuantizedEntry>>,
+    /// (model, dimensions) -> PQ codebook
+    codebooks: DashMap<(String, usize), Arc<Mutex<ModelCodebook>>>,
+}
+
+impl QuantizedIndex {
+    /// Create an empty quantized index.
+    pub fn new(config: QuantizationConfig) -> Self {
+        Self {
+            config,
+            vectors: DashMap::new(),
+            codebooks: DashMap::new(),
+        }
+    }
+
+    /// Get the quantization configuration.
+    pub fn config(&self) -> &QuantizationConfig {
+        &self.config
+    }
+
+    /// Approximate bytes used by stored vector codes.
+    pub fn memory_bytes(&self) -> usize {
+        self.vectors
+            .iter()
+            .map(|ns| {
+                ns.value()
+                    .iter()
+                    .map(|e| e.value().memory_bytes())
+                    .sum::<usize>()
+            })
+            .sum()
+    }
+
+    /// Check whether a PQ codebook has been trained for a model.
+    pub fn is_trained(&self, model: &str, dimensions: usize) -> bool {
+        self.codebooks
+            .get(&(model.to_string(), dimensions))
+            .map(|cb| cb.lock().unwrap().quantizer.is_some())
+            .unwrap_or(false)
+    }
+
+    fn insert(&self, key: &FullKey, model: String, codes: Codes) {
+        self.vectors
+            .entry(key.namespace.clone())
+            .or_default()
+            .insert(key.key.clone(), QuantizedEntry { model, codes });
+    }
+
+    /// Encode with PQ if a codebook is ready; otherwise buffer for training.
+    fn add_product(&self, key: FullKey, vector: Vector, subspaces: usize, centroids: usize) {
+        let codebook = self
+            .codebooks
+            .entry((vector.model().to_string(), vector.dimensions()))
+            .or_default()
+            .clone();
+        let mut codebook = codebook.lock().unwrap();
+
+        if let Some(quantizer) = codebook.quantizer.as_ref() {
+            let codes = quantizer.encode(vector.as_slice());
+            let quantizer = Arc::clone(quantizer);
+            self.insert(
+                &key,
+                vector.model().to_string(),
+                Codes::Product { codes, quantizer },
+            );
+            return;
+        }
+
+        self.insert(
+            &key,
+            vector.model().to_string(),
+            Codes::Int8(Int8Vector::encode(vector.as_slice())),
+        );
+        codebook.pending.push((key, vector));
+        if codebook.pending.len() < self.config.training_size {
+            return;
+        }
+
+        // Enough samples: train, then re-encode everything buffered so far
+        let pending = std::mem::take(&mut codebook.pending);
+        let samples: Vec<&[f32]> = pending.iter().map(|(_, v)| v.as_slice()).collect();
+        let Some(quantizer) = ProductQuantizer::train(
+            &samples,
+            subspaces,
+            centroids,
+            self.config.kmeans_iterations,
+        ) else {
+            return;
+        };
+        let quantizer = Arc::new(quantizer);
+
+        for (key, vector) in &pending {
+            let Some(namespace) = self.vectors.get(&key.namespace) else {
+                continue;
+            };
+            if let Some(mut entry) = namespace.get_mut(&key.key) {
+                // Only re-encode entries that still hold this vector
+                if Int8Vector::encode(vector.as_slice())
+                    == *match &entry.codes {
+                        Codes::Int8(v) => v,
+                        Codes::Product { .. } => continue,
+                    }
+                {
+                    entry.codes = Codes::Product {
+                        codes: quantizer.encode(vector.as_slice()),
+                        quantizer: Arc::clone(&quantizer),
+                    };
+                }
+            }
+        }
+        codebook.quantizer = Some(quantizer);
+    }
+}
+
+impl AnnIndex for QuantizedIndex {
+    fn add(&self, key: FullKey, vector: Vector) {
+        match self.config.mode {
+            QuantizationMode::Int8 => self.insert(
+                &key,
+                vector.model().to_string(),
+                Codes::Int8(Int8Vector::encode(vector.as_slice())),
+            ),
+            QuantizationMode::Product {
+                subspaces,
+                centroids,
+            } => self.add_product(key, vector, subspaces, centroids),
+        }
+    }
+
+    fn remove(&self, namespace: &str, key: &str) {
+        if let Some(namespace_entry) = self.vectors.get(namespace) {
+            namespace_entry.remove(key);
+            if namespace_entry.is_empty() {
+                drop(namespace_entry);
+                self.vectors.remove(namespace);
+            }
+        }
+    }
+
+    fn search(&self, query: &Vector, opts: &VectorSearchOptions) -> Vec<VectorSearchResult> {
+        let mut results = Vec::new();
+        let metric = opts.metric.unwrap_or_default();
+
+        for namespace_entry in self.vectors.iter() {
+            let namespace = namespace_entry.key();
+
+            for entry in namespace_entry.value().iter() {
+                let stored = entry.value();
+                if let Some(ref model_filter) = opts.model_filter {
+                    if &stored.model != model_filter {
+                        continue;
+                    }
+                }
+
+                let vector = Vector::new(stored.decode(), stored.model.clone());
+                if !query.is_compatible_with(&vector) {
+                    continue;
+                }
+
+                if let Some(similarity) = metric.similarity(query, &vector) {
+                    if similarity >= opts.threshold {
+                        results.push(VectorSearchResult::new(
+                            namespace.clone(),
+                            entry.key().clone(),
+                            similarity,
+                            vector,
+                        ));
+                    }
+                }
+            }
+        }
+
+        results.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results.truncate(opts.top_k);
+        results
+    }
+
+    fn len(&self) -> usize {
+        self.vectors.iter().map(|entry| entry.value().len()).sum()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.vectors.is_empty()
+    }
+
+    fn clear(&self) {
+        self.vectors.clear();
+        self.codebooks.clear();
+    }
+
+    fn stats(&self) -> VectorIndexStats {
+        VectorIndexStats {
+            vectors: self.len(),
+            memory_bytes: self.memory_bytes(),
+            ..Default::default()
+        }
+    }
+
+    fn entries(&self) -> Vec<(FullKey, Vector)> {
+        self.vectors
+            .iter()
+            .flat_map(|ns| {
+                let namespace = ns.key().clone();
+                ns.value()
+                    .iter()
+                    .map(|e| {
+                        let vector = Vector::new(e.value().decode(), e.value().model.clone());
+                        (FullKey::new(&namespace, e.key()), vector)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(i: usize, dims: usize) -> Vec<f32> {
+        (0..dims)
+            .map(|d| ((i * 31 + d * 17) % 97) as f32 / 97.0 - 0.5)
+            .collect()
+    }
+
+    #[test]
+    fn test_int8_roundtrip_is_close() {
+        let data = sample(3, 64);
+        let decoded = Int8Vector::encode(&data).decode();
+        let original = Vector::new(data, "m");
+        let approx = Vector::new(decoded, "m");
+        assert!(original.cosine_similarity(&approx).unwrap() > 0.999);
+    }
+
+    #[test]
+    fn test_int8_index_compresses_and_finds_neighbors() {
+        let index = QuantizedIndex::new(QuantizationConfig::int8());
+        for i in 0..50 {
+            index.add(
+                FullKey::new("docs", format!("doc{}", i)),
+                Vector::new(sample(i, 64), "m"),
+            );
+        }
+
+        // 64 bytes + scale per vector instead of 256 bytes
+        assert_eq!(index.memory_bytes(), 50 * (64 + 4));
+
+        let query = Vector::new(sample(7, 64), "m");
+        let results = index.search(&query, &VectorSearchOptions::new().top_k(1));
+        assert_eq!(results[0].key, "doc7");
+        assert!(results[0].score > 0.99);
+    }
+
+    #[test]
+    fn test_product_quantization_trains_and_reencodes() {
+        let config = QuantizationConfig::product(8)
+            .with_centroids(16)
+            .with_training_size(40);
+        let index = QuantizedIndex::new(config);
+
+        for i in 0..39 {
+            index.add(
+                FullKey::new("docs", format!("doc{}", i)),
+                Vector::new(sample(i, 32), "m"),
+            );
+        }
+        assert!(!index.is_trained("m", 32));
+
+        index.add(
+            FullKey::new("docs", "doc39"),
+            Vector::new(sample(39, 32), "m"),
+        );
+        assert!(index.is_trained("m", 32));
+
+        // One byte per subspace once trained
+        assert_eq!(index.memory_bytes(), 40 * 8);
+
+        let query = Vector::new(sample(12, 32), "m");
+        let results = index.search(&query, &VectorSearchOptions::new().top_k(5));
+        assert!(results.iter().any(|r| r.key == "doc12"));
+    }
+
+    #[test]
+    fn test_product_quantizer_rejects_mixed_dimensions() {
+        let a = sample(0, 8);
+        let b = sample(1, 4);
+        assert!(ProductQuantizer::train(&[&a, &b], 2, 2, 5).is_none());
+    }
+}
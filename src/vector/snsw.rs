@@ -44,7 +44,7 @@ use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 
 use crate::error::DeltaResult;
-use crate::vector::types::Vector;
+use crate::vector::types::{Vector, VectorSearchResult};
 
 /// Content hash for vector identity (Blake3).
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
@@ -185,6 +185,20 @@ impl SynthesisEdge {
     pub fn proximity(target: ContentHash, similarity: f32) -> Self {
         Self::new(target, SynthesisType::Proximity, similarity, 0.0)
     }
+
+    /// Create an edge typed by geometric similarity.
+    ///
+    /// Very high similarity reads as abstraction, high similarity as
+    /// composition, and anything else as plain proximity.
+    pub fn classify(target: ContentHash, similarity: f32) -> Self {
+        if similarity > 0.95 {
+            Self::new(target, SynthesisType::Abstraction, similarity, 0.9)
+        } else if similarity > 0.85 {
+            Self::new(target, SynthesisType::Composition, similarity, 0.7)
+        } else {
+            Self::proximity(target, similarity)
+        }
+    }
 }
 
 /// Distinction overlap metrics for synthesis proximity calculation.
@@ -360,6 +374,143 @@ pub struct SynthesisExplanation {
     pub description: String,
 }
 
+/// A database search hit with an explanation of why it was retrieved.
+#[derive(Clone, Debug)]
+pub struct ExplainedSearchResult {
+    /// The search hit
+    pub result: VectorSearchResult,
+    /// Synthesis path and relationships from the query to the hit
+    pub explanation: SynthesisExplanation,
+    /// Breakdown of the hit's proximity to the query
+    pub proximity: SynthesisProximity,
+}
+
+/// Explain a set of hits against the query that produced them.
+///
+/// The query and hits form a small synthesis graph. For each hit this finds
+/// the strongest synthesis path from the query, either direct or through
+/// another hit, and breaks its proximity into a geometric part (cosine
+/// similarity), a semantic part (overlap of strongly related hits), and the
+/// caller-supplied causal part (e.g. temporal closeness), given per hit.
+pub fn explain_hits(
+    query: &Vector,
+    hits: &[(Vector, f32)],
+) -> Vec<(SynthesisExplanation, SynthesisProximity)> {
+    let query_id = ContentHash::from_vector(query);
+    let ids: Vec<ContentHash> = hits
+        .iter()
+        .map(|(v, _)| ContentHash::from_vector(v))
+        .collect();
+    let similarity = |a: &Vector, b: &Vector| a.cosine_similarity(b).unwrap_or(0.0);
+
+    // Edges from the query, and between every pair of hits
+    let from_query: Vec<SynthesisEdge> = hits
+        .iter()
+        .zip(&ids)
+        .map(|((v, _), id)| SynthesisEdge::classify(id.clone(), similarity(query, v)))
+        .collect();
+    let between: Vec<Vec<SynthesisEdge>> = hits
+        .iter()
+        .map(|(a, _)| {
+            hits.iter()
+                .zip(&ids)
+                .map(|((b, _), id)| SynthesisEdge::classify(id.clone(), similarity(a, b)))
+                .collect()
+        })
+        .collect();
+
+    // Strongly related neighbors, as graph insertion records shared distinctions
+    let strong = |edges: &[SynthesisEdge], skip: Option<usize>| -> HashSet<ContentHash> {
+        edges
+            .iter()
+            .enumerate()
+            .filter(|(j, e)| Some(*j) != skip && e.semantic_score > 0.5)
+            .map(|(_, e)| e.target.clone())
+            .collect()
+    };
+    let query_neighbors = strong(&from_query, None);
+
+    (0..hits.len())
+        .map(|i| {
+            let (vector, causal) = &hits[i];
+            let geometric = from_query[i].geometric_score;
+
+            let hit_neighbors = strong(&between[i], Some(i));
+            let shared = query_neighbors.intersection(&hit_neighbors).count();
+            let union = query_neighbors.union(&hit_neighbors).count();
+            let semantic = if union > 0 {
+                shared as f32 / union as f32
+            } else {
+                0.0
+            };
+
+            // Direct edge, or a two-hop route through another hit if stronger
+            let mut segments = vec![PathSegment {
+                from: query_id.clone(),
+                to: ids[i].clone(),
+                relationship: from_query[i].relationship.clone(),
+                strength: from_query[i].strength,
+            }];
+            let mut strength = from_query[i].strength;
+            for j in (0..hits.len()).filter(|&j| ids[j] != ids[i] && ids[j] != query_id) {
+                let via = from_query[j].strength * between[j][i].strength;
+                if via > strength {
+                    strength = via;
+                    segments = vec![
+                        PathSegment {
+                            from: query_id.clone(),
+                            to: ids[j].clone(),
+                            relationship: from_query[j].relationship.clone(),
+                            strength: from_query[j].strength,
+                        },
+                        PathSegment {
+                            from: ids[j].clone(),
+                            to: ids[i].clone(),
+                            relationship: between[j][i].relationship.clone(),
+                            strength: between[j][i].strength,
+                        },
+                    ];
+                }
+            }
+
+            let relationships: Vec<SynthesisType> =
+                segments.iter().map(|s| s.relationship.clone()).collect();
+            let route = relationships
+                .iter()
+                .map(|r| r.to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            let description = if segments.len() == 1 {
+                format!(
+                    "Direct {} match (similarity {:.2}), sharing {} strong relations with the query",
+                    route, geometric, shared
+                )
+            } else {
+                format!(
+                    "Reached via another hit ({}, strength {:.2}); direct similarity {:.2}",
+                    route, strength, geometric
+                )
+            };
+
+            let explanation = SynthesisExplanation {
+                geometric_similarity: geometric,
+                shared_distinctions: shared,
+                synthesis_path: Some(SynthesisPath {
+                    from: query_id.clone(),
+                    to: ContentHash::from_vector(vector),
+                    segments,
+                    strength,
+                }),
+                relationships,
+                description,
+            };
+            let proximity =
+                SynthesisProximity::new(geometric.clamp(0.0, 1.0), semantic, causal.clamp(0.0, 1.0));
+            (explanation, proximity)
+        })
+        .collect()
+}
+
 /// Cached query result for hot tier.
 #[derive(Clone, Debug)]
 struct CachedResult {
@@ -679,17 +830,7 @@ impl SynthesisGraph {
                 neighbors.push((neighbor_id.clone(), similarity));
 
                 // Create synthesis edge with semantic typing
-                let edge = if similarity > 0.95 {
-                    // Very high similarity = likely abstraction relationship
-                    SynthesisEdge::new(neighbor_id, SynthesisType::Abstraction, similarity, 0.9)
-                } else if similarity > 0.85 {
-                    // High similarity = composition relationship
-                    SynthesisEdge::new(neighbor_id, SynthesisType::Composition, similarity, 0.7)
-                } else {
-                    // Standard proximity
-                    SynthesisEdge::proximity(neighbor_id, similarity)
-                };
-                synthesis_edges.push(edge);
+                synthesis_edges.push(SynthesisEdge::classify(neighbor_id, similarity));
             }
         }
 
@@ -1607,6 +1748,34 @@ mod tests {
         assert_eq!(total, graph.len(), "Distribution should cover all nodes");
     }
 
+    #[test]
+    fn test_explain_hits_paths() {
+        let query = Vector::new(vec![1.0, 0.0], "test-model");
+        let near = Vector::new(vec![0.97, 0.24], "test-model");
+        let far = Vector::new(vec![0.6, 0.8], "test-model");
+
+        let explained = explain_hits(&query, &[(near.clone(), 1.0), (far.clone(), 0.25)]);
+        assert_eq!(explained.len(), 2);
+
+        // The near hit is a direct abstraction match
+        let (explanation, proximity) = &explained[0];
+        let path = explanation.synthesis_path.as_ref().unwrap();
+        assert_eq!(path.segments.len(), 1);
+        assert_eq!(explanation.relationships, vec![SynthesisType::Abstraction]);
+        assert_eq!(path.to, ContentHash::from_vector(&near));
+        assert!(proximity.geometric > 0.95);
+        assert_eq!(proximity.causal, 1.0);
+
+        // The far hit is reached more strongly through the near one
+        let (explanation, proximity) = &explained[1];
+        let path = explanation.synthesis_path.as_ref().unwrap();
+        assert_eq!(path.segments.len(), 2);
+        assert_eq!(path.segments[0].to, ContentHash::from_vector(&near));
+        assert_eq!(path.to, ContentHash::from_vector(&far));
+        assert!(path.strength > SynthesisEdge::classify(path.to.clone(), 0.6).strength);
+        assert_eq!(proximity.causal, 0.25);
+    }
+
     #[test]
     fn test_synthesis_proximity_calculation() {
         // Test synthesis proximity with different weights
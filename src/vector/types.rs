@@ -291,6 +291,23 @@ impl Vector {
         )
     }
 
+    /// Compute Manhattan (L1) distance to another vector.
+    ///
+    /// Returns None if dimensions don't match.
+    pub fn manhattan_distance(&self, other: &Vector) -> Option<f32> {
+        if self.dimensions() != other.dimensions() {
+            return None;
+        }
+
+        Some(
+            self.data
+                .iter()
+                .zip(other.data.iter())
+                .map(|(a, b)| (a - b).abs())
+                .sum(),
+        )
+    }
+
     /// Check if this vector can be compared with another.
     ///
     /// Vectors must have the same dimensions and ideally the same model.
@@ -337,6 +354,75 @@ impl fmt::Display for Vector {
     }
 }
 
+/// How vectors are compared during search.
+///
+/// Every metric is reported as a similarity where higher means closer:
+/// cosine and dot product are used as-is, while distances `d` are mapped to
+/// `1 / (1 + d)` so they fall in `(0, 1]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DistanceMetric {
+    /// Cosine similarity (angle only; the default)
+    #[default]
+    Cosine,
+    /// Raw dot product (for embeddings trained with inner-product loss)
+    DotProduct,
+    /// Euclidean (L2) distance
+    Euclidean,
+    /// Manhattan (L1) distance
+    Manhattan,
+}
+
+impl DistanceMetric {
+    /// Similarity between two vectors under this metric.
+    ///
+    /// Returns None if dimensions don't match.
+    pub fn similarity(&self, a: &Vector, b: &Vector) -> Option<f32> {
+        match self {
+            DistanceMetric::Cosine => a.cosine_similarity(b),
+            DistanceMetric::DotProduct => a.dot_product(b),
+            DistanceMetric::Euclidean => a.euclidean_distance(b).map(|d| 1.0 / (1.0 + d)),
+            DistanceMetric::Manhattan => a.manhattan_distance(b).map(|d| 1.0 / (1.0 + d)),
+        }
+    }
+}
+
+impl fmt::Display for DistanceMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DistanceMetric::Cosine => write!(f, "cosine"),
+            DistanceMetric::DotProduct => write!(f, "dot_product"),
+            DistanceMetric::Euclidean => write!(f, "euclidean"),
+            DistanceMetric::Manhattan => write!(f, "manhattan"),
+        }
+    }
+}
+
+/// Per-namespace vector index configuration.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct VectorNamespaceConfig {
+    /// Metric used to compare vectors in this namespace
+    pub metric: DistanceMetric,
+    /// Embedding model every vector in this namespace must come from (optional)
+    pub model: Option<String>,
+}
+
+impl VectorNamespaceConfig {
+    /// Create a configuration using the given metric.
+    pub fn new(metric: DistanceMetric) -> Self {
+        Self {
+            metric,
+            model: None,
+        }
+    }
+
+    /// Require vectors and queries to come from a specific model.
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+}
+
 /// A search result containing a vector and its similarity score.
 #[derive(Debug, Clone)]
 pub struct VectorSearchResult {
@@ -376,6 +462,8 @@ pub struct VectorSearchOptions {
     pub threshold: f32,
     /// Filter by model (optional)
     pub model_filter: Option<String>,
+    /// Metric to score with (None = the namespace's configured metric)
+    pub metric: Option<DistanceMetric>,
 }
 
 impl VectorSearchOptions {
@@ -385,11 +473,13 @@ impl VectorSearchOptions {
     /// - top_k: 10
     /// - threshold: 0.0 (no filtering)
     /// - model_filter: None
+    /// - metric: None (namespace default, cosine if unconfigured)
     pub fn new() -> Self {
         Self {
             top_k: 10,
             threshold: 0.0,
             model_filter: None,
+            metric: None,
         }
     }
 
@@ -410,6 +500,12 @@ impl VectorSearchOptions {
         self.model_filter = Some(model.into());
         self
     }
+
+    /// Score with a specific distance metric.
+    pub fn metric(mut self, metric: DistanceMetric) -> Self {
+        self.metric = Some(metric);
+        self
+    }
 }
 
 impl Default for VectorSearchOptions {
@@ -499,6 +595,29 @@ mod tests {
         assert!((dot - 32.0).abs() < 1e-6, "Dot product should be 32.0");
     }
 
+    #[test]
+    fn test_manhattan_distance() {
+        let v1 = Vector::new(vec![0.0, 0.0], "test");
+        let v2 = Vector::new(vec![3.0, -4.0], "test");
+        assert_eq!(v1.manhattan_distance(&v2), Some(7.0));
+    }
+
+    #[test]
+    fn test_distance_metric_similarity() {
+        let a = Vector::new(vec![1.0, 0.0], "test");
+        let b = Vector::new(vec![3.0, 0.0], "test");
+
+        // Same direction: identical under cosine, apart under distances
+        assert!((DistanceMetric::Cosine.similarity(&a, &b).unwrap() - 1.0).abs() < 1e-6);
+        assert_eq!(DistanceMetric::DotProduct.similarity(&a, &b), Some(3.0));
+        assert_eq!(
+            DistanceMetric::Euclidean.similarity(&a, &b),
+            Some(1.0 / 3.0)
+        );
+        assert_eq!(DistanceMetric::Manhattan.similarity(&a, &a), Some(1.0));
+        assert_eq!(DistanceMetric::default(), DistanceMetric::Cosine);
+    }
+
     #[test]
     fn test_vector_equality() {
         let v1 = Vector::new(vec![1.0, 2.0, 3.0], "test");
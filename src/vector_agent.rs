@@ -32,13 +32,13 @@ use koru_lambda_core::{Canonicalizable, Distinction, DistinctionEngine, LocalCau
 use serde::{Deserialize, Serialize};
 
 use crate::actions::VectorAction;
+use crate::mapper::DocumentMapper;
 
 /// Convert bytes to distinction via byte-wise synthesis.
+///
+/// Delegates to [`DocumentMapper`], which canonicalizes large inputs in parallel.
 fn bytes_to_distinction(bytes: &[u8], engine: &DistinctionEngine) -> Distinction {
-    bytes
-        .iter()
-        .map(|&byte| byte.to_canonical_structure(engine))
-        .fold(engine.d0().clone(), |acc, d| engine.synthesize(&acc, &d))
+    DocumentMapper::bytes_to_distinction(bytes, engine)
 }
 
 /// Convert a vector to distinction via float-wise synthesis.
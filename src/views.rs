@@ -7,6 +7,10 @@
 /// - **Refreshed** on demand or automatically
 /// - **Queried** directly for fast access to computed results
 ///
+/// Each refresh reads the source collection as of a single storage sequence
+/// number, so writes landing mid-refresh are either wholly in the view or
+/// not at all. [`ViewInfo`] reports that snapshot.
+///
 /// ## LCA Architecture
 ///
 /// As a Local Causal Agent, all operations follow the synthesis pattern:
@@ -35,13 +39,20 @@
 ///
 /// // Query the view
 /// let results = manager.query_view("active_adults")?;
+///
+/// // Computed fields are evaluated during materialization
+/// let totals = ViewDefinition::new("order_totals", "orders")
+///     .compute("total", Expr::parse("price * qty")?)
+///     .with_query(Query::new().filter(Filter::gt("total", 100)));
 /// ```
 use crate::actions::PerspectiveAction;
 use crate::engine::{FieldHandle, SharedEngine};
 use crate::error::{DeltaError, DeltaResult};
-use crate::query::{Query, QueryExecutor, QueryRecord, QueryResult};
+use crate::expr::Expr;
+use crate::query::{ComputedField, FieldPushdown, Query, QueryExecutor, QueryRecord, QueryResult};
 use crate::roots::RootType;
 use crate::storage::CausalStorage;
+use crate::types::VectorClock;
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use koru_lambda_core::{Canonicalizable, Distinction, DistinctionEngine, LocalCausalAgent};
@@ -63,6 +74,9 @@ pub struct ViewDefinition {
     pub description: Option<String>,
     /// Whether this view auto-refreshes on writes.
     pub auto_refresh: bool,
+    /// Fields computed for each record before the query runs.
+    #[serde(default)]
+    pub computed: Vec<ComputedField>,
 }
 
 impl ViewDefinition {
@@ -75,6 +89,7 @@ impl ViewDefinition {
             created_at: Utc::now(),
             description: None,
             auto_refresh: false,
+            computed: Vec::new(),
         }
     }
 
@@ -95,6 +110,14 @@ impl ViewDefinition {
         self.auto_refresh = enabled;
         self
     }
+
+    /// Add a computed field, stored in the view's records.
+    ///
+    /// The view's query can filter, sort and aggregate on it.
+    pub fn compute(mut self, name: impl Into<String>, expr: Expr) -> Self {
+        self.computed.push(ComputedField::new(name, expr));
+        self
+    }
 }
 
 /// Cached view data.
@@ -110,6 +133,12 @@ pub struct ViewData {
     pub total_count: usize,
     /// View distinction ID (synthesized representation)
     pub view_distinction_id: Option<String>,
+    /// Storage sequence number the records were read at.
+    #[serde(default)]
+    pub snapshot_sequence: u64,
+    /// Vector clock of the state the records reflect.
+    #[serde(default)]
+    pub snapshot: VectorClock,
 }
 
 impl ViewData {
@@ -121,6 +150,8 @@ impl ViewData {
             last_refreshed: Utc::now(),
             total_count: result.total_count,
             view_distinction_id: None,
+            snapshot_sequence: 0,
+            snapshot: VectorClock::new(),
         }
     }
 
@@ -147,6 +178,11 @@ pub struct ViewInfo {
     pub record_count: usize,
     /// Whether auto-refresh is enabled.
     pub auto_refresh: bool,
+    /// Storage sequence number the view was last refreshed at: it reflects
+    /// every write up to and including this one, and none after.
+    pub snapshot_sequence: u64,
+    /// Vector clock of the state the view reflects.
+    pub snapshot: VectorClock,
 }
 
 impl From<&ViewData> for ViewInfo {
@@ -159,6 +195,8 @@ impl From<&ViewData> for ViewInfo {
             last_refreshed: data.last_refreshed,
             record_count: data.records.len(),
             auto_refresh: data.definition.auto_refresh,
+            snapshot_sequence: data.snapshot_sequence,
+            snapshot: data.snapshot.clone(),
         }
     }
 }
@@ -218,8 +256,7 @@ impl PerspectiveAgent {
                     serde_json::from_value::<ViewDefinition>((*versioned.value()).clone())
                 {
                     // Execute the query to populate the view
-                    if let Ok(result) = self.execute_view_query(&definition) {
-                        let view_data = ViewData::from_result(definition, result);
+                    if let Ok(view_data) = self.materialize(definition) {
                         self.views.insert(key, view_data);
                     }
                 }
@@ -274,13 +311,12 @@ impl PerspectiveAgent {
         let _ = self.synthesize_action_internal(action);
 
         // Execute the query to populate the view.
-        let result = self.execute_view_query(&definition)?;
+        let view_data = self.materialize(definition)?;
 
         // Persist the view definition.
-        self.persist_view(&definition)?;
+        self.persist_view(&view_data.definition)?;
 
         // Store the view in memory.
-        let view_data = ViewData::from_result(definition, result);
         let info = ViewInfo::from(&view_data);
         self.views.insert(name, view_data);
 
@@ -317,13 +353,17 @@ impl PerspectiveAgent {
         };
         let _ = self.synthesize_action_internal(action);
 
+        // The entry stays locked until the refresh is stored, so refreshes
+        // of a view apply in snapshot order.
         let definition = entry.definition.clone();
-        let result = self.execute_view_query(&definition)?;
+        let refreshed = self.materialize(definition)?;
 
         // Update the cached data.
-        entry.records = result.records;
-        entry.total_count = result.total_count;
-        entry.last_refreshed = Utc::now();
+        entry.records = refreshed.records;
+        entry.total_count = refreshed.total_count;
+        entry.last_refreshed = refreshed.last_refreshed;
+        entry.snapshot_sequence = refreshed.snapshot_sequence;
+        entry.snapshot = refreshed.snapshot;
 
         Ok(ViewInfo::from(entry.value()))
     }
@@ -388,6 +428,7 @@ impl PerspectiveAgent {
             records: view.records.clone(),
             total_count: view.total_count,
             aggregation: None,
+            partial: false,
         })
     }
 
@@ -459,23 +500,44 @@ impl PerspectiveAgent {
         Ok(())
     }
 
-    /// Execute the query for a view definition.
-    fn execute_view_query(&self, definition: &ViewDefinition) -> DeltaResult<QueryResult> {
+    /// Materialize a view from a snapshot of storage taken now.
+    fn materialize(&self, definition: ViewDefinition) -> DeltaResult<ViewData> {
+        let sequence = self.storage.sequence();
+        let result = self.execute_view_query(&definition, sequence)?;
+        let mut data = ViewData::from_result(definition, result);
+        data.snapshot_sequence = sequence;
+        data.snapshot = self.storage.clock_at(sequence);
+        Ok(data)
+    }
+
+    /// Execute the query for a view definition against the source
+    /// collection as of `sequence`.
+    fn execute_view_query(
+        &self,
+        definition: &ViewDefinition,
+        sequence: u64,
+    ) -> DeltaResult<QueryResult> {
+        let mut query = definition.query.clone();
+        query
+            .computed
+            .splice(0..0, definition.computed.iter().cloned());
+        let pushdown = FieldPushdown::new(&query);
+
         // Get all items from the source collection.
         let items = self
             .storage
-            .scan_collection(&definition.source_collection)
+            .scan_collection_as_of(&definition.source_collection, sequence)
             .into_iter()
             .map(|(key, value)| {
                 (
                     key,
-                    value.value().clone(),
+                    pushdown.read(value.value()),
                     value.timestamp(),
                     value.version_id().to_string(),
                 )
             });
 
-        QueryExecutor::execute(&definition.query, items)
+        QueryExecutor::execute(&query, items)
     }
 
     /// Internal synthesis helper.
@@ -644,6 +706,40 @@ mod tests {
         assert_eq!(result.records.len(), 3);
     }
 
+    #[test]
+    fn test_view_refresh_records_snapshot() {
+        let storage = create_test_storage();
+        let engine = create_test_engine();
+
+        storage.put("items", "a", json!({"value": 1})).unwrap();
+        let manager = PerspectiveAgent::new(storage.clone(), &engine);
+        let created = manager
+            .create_view(ViewDefinition::new("all_items", "items"))
+            .unwrap();
+        assert!(created.snapshot_sequence > 0);
+        assert_eq!(
+            created.snapshot,
+            storage.clock_at(created.snapshot_sequence)
+        );
+
+        // Writes after the snapshot are left out of a query pinned to it
+        let pinned = storage.sequence();
+        storage.put("items", "a", json!({"value": 10})).unwrap();
+        storage.put("items", "b", json!({"value": 2})).unwrap();
+        let definition = manager.get_view("all_items").unwrap().definition;
+        let result = manager.execute_view_query(&definition, pinned).unwrap();
+        assert_eq!(result.records.len(), 1);
+        assert_eq!(result.records[0].value, json!({"value": 1}));
+
+        let refreshed = manager.refresh_view("all_items").unwrap();
+        assert_eq!(refreshed.snapshot_sequence, storage.sequence());
+        assert_eq!(refreshed.record_count, 2);
+        assert_eq!(
+            manager.list_views()[0].snapshot_sequence,
+            refreshed.snapshot_sequence
+        );
+    }
+
     #[test]
     fn test_view_list_and_delete() {
         let storage = create_test_storage();
@@ -811,4 +907,49 @@ mod tests {
         agent.update_local_root(new_root.clone());
         assert_eq!(agent.get_current_root().id(), new_root.id());
     }
+
+    #[test]
+    fn test_view_computed_fields() {
+        let storage = create_test_storage();
+        let engine = create_test_engine();
+
+        storage
+            .put(
+                "orders",
+                "o1",
+                json!({"price": 12.5, "qty": 4, "first": "Ada", "last": "Lovelace",
+                       "placed_at": "2024-03-15T13:45:12Z"}),
+            )
+            .unwrap();
+        storage
+            .put(
+                "orders",
+                "o2",
+                json!({"price": 3, "qty": 2, "first": "Alan", "last": "Turing",
+                       "placed_at": "2024-03-16T09:00:00Z"}),
+            )
+            .unwrap();
+
+        let manager = PerspectiveAgent::new(storage, &engine);
+        let definition = ViewDefinition::new("big_orders", "orders")
+            .compute("total", Expr::parse("price * qty").unwrap())
+            .compute("customer", Expr::parse("first + ' ' + last").unwrap())
+            .compute("day", Expr::parse("date_trunc('day', placed_at)").unwrap())
+            .with_query(Query::new().filter(Filter::gt("total", 10)));
+        manager.create_view(definition).unwrap();
+
+        // Computed before filtering, and stored in the materialized records
+        let result = manager.query_view("big_orders").unwrap();
+        assert_eq!(result.records.len(), 1);
+        let order = &result.records[0].value;
+        assert_eq!(order["total"], json!(50.0));
+        assert_eq!(order["customer"], json!("Ada Lovelace"));
+        assert_eq!(order["day"], json!("2024-03-15T00:00:00Z"));
+
+        // Definitions round-trip with their expressions
+        let stored = manager.get_view("big_orders").unwrap().definition;
+        let text = serde_json::to_string(&stored).unwrap();
+        let back: ViewDefinition = serde_json::from_str(&text).unwrap();
+        assert_eq!(back.computed, stored.computed);
+    }
 }
@@ -13,7 +13,9 @@
 //! - Materialized views
 //! - Identity management (create, verify)
 //! - Workspace abstraction
-//! - **IndexedDB persistence** for data survival across page reloads
+//! - **IndexedDB persistence** for data and version history across page reloads
+//! - **Server sync** over WebSocket for offline-first apps
+//! - **Change subscriptions** delivered to JavaScript callbacks
 //!
 //! # Usage
 //! ```javascript
@@ -38,11 +40,20 @@
 //! // Identity management
 //! const identity = await db.createIdentity('User Name', 'Bio');
 //! const valid = await db.verifyIdentity(identity.id);
+//!
+//! // Change notifications
+//! const subId = await db.subscribe('users', (event) => console.log(event));
+//!
+//! // Reconcile with a server (offline-first)
+//! const report = await db.syncWith('wss://example.com/api/v1/sync');
 //! ```
 
 mod storage;
+mod sync;
 
 use crate::auth::IdentityUserData;
+use crate::runtime::sync::broadcast::error::RecvError;
+use crate::subscriptions::{ChangeEvent, ChangeType, Subscription, SubscriptionId};
 use crate::vector::{Vector, VectorSearchOptions};
 use crate::{DeltaError, HistoryEntry, KoruDelta, VersionedValue, ViewDefinition};
 use serde::Deserialize;
@@ -161,57 +172,60 @@ impl KoruDeltaWasm {
     }
 
     /// Load data from IndexedDB storage
+    ///
+    /// Versions are replayed with their original IDs and timestamps, so
+    /// history and time travel survive reloads.
     async fn load_from_storage(&mut self) -> Result<(), JsValue> {
         let storage = match &self.storage {
             Some(s) => s,
             None => return Ok(()),
         };
 
-        let records = storage.load_all_records().await?;
-        let record_count = records.len();
+        let versions = storage.load_all_versions().await?;
+        let version_count = versions.len();
 
-        for (namespace, key, value, _timestamp, _version_id, _previous_version) in records {
-            // Restore the data to the database
-            // We use put without triggering persistence to avoid recursion
-            let _ = self.db.put(&namespace, &key, value).await;
+        for (namespace, key, versioned) in versions {
+            // Insert directly so the put isn't persisted again
+            let _ = self.db.storage().insert_direct(namespace, key, versioned);
+        }
+
+        // Schema version 1 kept only each key's latest value; carry those
+        // over as first versions
+        let legacy = storage.load_all_records().await?;
+        if !legacy.is_empty() {
+            for (namespace, key, value, _timestamp, _version_id, _previous_version) in legacy {
+                if self.db.contains(&namespace, &key).await {
+                    continue;
+                }
+                if let Ok(versioned) = self.db.put(&namespace, &key, value).await {
+                    storage.save_version(&namespace, &key, &versioned).await?;
+                }
+            }
+            storage.clear_legacy_records().await?;
         }
 
         if storage.is_persistent() {
             web_sys::console::log_1(
-                &format!("Loaded {} records from IndexedDB", record_count).into(),
+                &format!("Loaded {} versions from IndexedDB", version_count).into(),
             );
         }
 
         Ok(())
     }
 
-    /// Save a record to IndexedDB (called after successful put)
-    async fn save_to_storage(
-        &self,
-        namespace: &str,
-        key: &str,
-        value: &serde_json::Value,
-    ) -> Result<(), JsValue> {
+    /// Persist a new version of a key (called after a successful write)
+    ///
+    /// Failures are logged rather than returned - the write already
+    /// succeeded in memory.
+    async fn save_to_storage(&self, namespace: &str, key: &str, versioned: &VersionedValue) {
         let storage = match &self.storage {
             Some(s) => s,
-            None => return Ok(()),
+            None => return,
         };
 
-        // Get the latest version info from the database
-        if let Ok(versioned) = self.db.get(namespace, key).await {
-            storage
-                .save_record(
-                    namespace,
-                    key,
-                    value,
-                    &versioned.timestamp(),
-                    versioned.version_id(),
-                    versioned.previous_version(),
-                )
-                .await?;
+        if let Err(e) = storage.save_version(namespace, key, versioned).await {
+            web_sys::console::warn_1(&format!("Failed to save to IndexedDB: {:?}", e).into());
         }
-
-        Ok(())
     }
 
     /// Store a value in the database
@@ -235,15 +249,12 @@ impl KoruDeltaWasm {
 
         let versioned = self
             .db
-            .put(namespace, key, json_value.clone())
+            .put_notify(namespace, key, json_value.clone())
             .await
             .map_err(|e| JsValue::from_str(&format!("Failed to store value: {}", e)))?;
 
         // Auto-save to IndexedDB if persistence is enabled
-        if let Err(e) = self.save_to_storage(namespace, key, &json_value).await {
-            web_sys::console::warn_1(&format!("Failed to save to IndexedDB: {:?}", e).into());
-            // Don't fail the put if IndexedDB save fails - data is still in memory
-        }
+        self.save_to_storage(namespace, key, &versioned).await;
 
         versioned_to_js(&versioned)
     }
@@ -366,6 +377,10 @@ impl KoruDeltaWasm {
             .into_iter()
             .map(|item| (item.namespace, item.key, item.value))
             .collect();
+        let keys: Vec<(String, String)> = tuples
+            .iter()
+            .map(|(namespace, key, _)| (namespace.clone(), key.clone()))
+            .collect();
 
         // Perform the batch write
         let versioned = self
@@ -374,6 +389,10 @@ impl KoruDeltaWasm {
             .await
             .map_err(|e| JsValue::from_str(&format!("Batch write failed: {}", e)))?;
 
+        for ((namespace, key), version) in keys.iter().zip(&versioned) {
+            self.save_to_storage(namespace, key, version).await;
+        }
+
         // Convert results back to JavaScript array
         let js_results: Vec<JsValue> = versioned
             .iter()
@@ -427,13 +446,19 @@ impl KoruDeltaWasm {
             .collect();
 
         let count = tuples.len();
+        let keys: Vec<String> = tuples.iter().map(|(key, _)| key.clone()).collect();
 
         // Perform the batch write
-        self.db
+        let versioned = self
+            .db
             .put_batch_in_ns(namespace, tuples)
             .await
             .map_err(|e| JsValue::from_str(&format!("Batch write failed: {}", e)))?;
 
+        for (key, version) in keys.iter().zip(&versioned) {
+            self.save_to_storage(namespace, key, version).await;
+        }
+
         Ok(count)
     }
 
@@ -545,26 +570,80 @@ impl KoruDeltaWasm {
 
     /// Delete a key
     ///
-    /// Also removes the key from IndexedDB if persistence is enabled.
+    /// Writes a tombstone version; with persistence enabled it is saved to
+    /// IndexedDB like any other version, so the key's history is kept.
     #[wasm_bindgen(js_name = delete)]
     pub async fn delete_js(&self, namespace: &str, key: &str) -> Result<(), JsValue> {
-        self.db
-            .delete(namespace, key)
+        let tombstone = self
+            .db
+            .delete_notify(namespace, key)
             .await
             .map_err(|e| JsValue::from_str(&format!("Failed to delete: {}", e)))?;
 
-        // Also delete from IndexedDB if persistence is enabled
-        if let Some(storage) = &self.storage {
-            if let Err(e) = storage.delete_record(namespace, key).await {
-                web_sys::console::warn_1(
-                    &format!("Failed to delete from IndexedDB: {:?}", e).into(),
-                );
-            }
-        }
+        self.save_to_storage(namespace, key, &tombstone).await;
 
         Ok(())
     }
 
+    /// Subscribe to changes in a namespace
+    ///
+    /// `callback` is called with a change event for every `put` or `delete`
+    /// in the namespace, including writes made through workspace handles:
+    /// `{ changeType, namespace, key, value, previousValue, timestamp, versionId }`.
+    /// `changeType` is `"insert"`, `"update"` or `"delete"`.
+    ///
+    /// # Returns
+    /// A subscription ID to pass to `unsubscribe`
+    ///
+    /// # Example (JavaScript)
+    /// ```javascript
+    /// const id = await db.subscribe('users', (event) => {
+    ///   console.log(event.changeType, event.key, event.value);
+    /// });
+    /// await db.unsubscribe(id);
+    /// ```
+    #[wasm_bindgen(js_name = subscribe)]
+    pub async fn subscribe_js(
+        &self,
+        namespace: &str,
+        callback: js_sys::Function,
+    ) -> Result<f64, JsValue> {
+        let (id, mut receiver) = self.db.subscribe(Subscription::collection(namespace)).await;
+
+        // Runs until unsubscribe drops the sender
+        wasm_bindgen_futures::spawn_local(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => match change_event_to_js(&event) {
+                        Ok(js_event) => {
+                            if let Err(e) = callback.call1(&JsValue::NULL, &js_event) {
+                                web_sys::console::warn_1(&e);
+                            }
+                        }
+                        Err(e) => web_sys::console::warn_1(&e),
+                    },
+                    Err(RecvError::Lagged(skipped)) => {
+                        web_sys::console::warn_1(
+                            &format!("Subscription skipped {} events", skipped).into(),
+                        );
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+
+        Ok(id.0 as f64)
+    }
+
+    /// Stop a subscription created with `subscribe`
+    #[wasm_bindgen(js_name = unsubscribe)]
+    pub async fn unsubscribe_js(&self, id: f64) -> Result<(), JsValue> {
+        self.db
+            .unsubscribe(SubscriptionId(id as u64))
+            .await
+            .map_err(|e| JsValue::from_str(&format!("Failed to unsubscribe: {}", e)))
+    }
+
     /// Check if a key exists
     #[wasm_bindgen(js_name = contains)]
     pub async fn contains_js(&self, namespace: &str, key: &str) -> bool {
@@ -588,6 +667,39 @@ impl KoruDeltaWasm {
         Ok(obj.into())
     }
 
+    /// Reconcile with a KoruDelta server over WebSocket
+    ///
+    /// Exchanges missing versions in both directions, so local writes made
+    /// while offline reach the server and the server's history reaches the
+    /// browser. Received versions are saved to IndexedDB when persistence
+    /// is enabled.
+    ///
+    /// # Arguments
+    /// * `url` - The server's sync endpoint, e.g. `wss://host/api/v1/sync`
+    ///
+    /// # Returns
+    /// `{ sent, received, alreadyInSync }`
+    #[wasm_bindgen(js_name = syncWith)]
+    pub async fn sync_with_js(&self, url: &str) -> Result<JsValue, JsValue> {
+        let (report, received) = sync::sync_with(&self.db, url).await?;
+
+        for (full_key, versioned) in &received {
+            self.save_to_storage(&full_key.namespace, &full_key.key, versioned)
+                .await;
+        }
+
+        let obj = js_sys::Object::new();
+        js_sys::Reflect::set(&obj, &"sent".into(), &report.sent.into())?;
+        js_sys::Reflect::set(&obj, &"received".into(), &report.received.into())?;
+        js_sys::Reflect::set(
+            &obj,
+            &"alreadyInSync".into(),
+            &report.already_in_sync.into(),
+        )?;
+
+        Ok(obj.into())
+    }
+
     /// Store a vector embedding associated with a document
     ///
     /// # Arguments
@@ -1018,9 +1130,7 @@ impl KoruDeltaWasm {
             .map_err(|e| JsValue::from_str(&format!("Failed to store value with TTL: {}", e)))?;
 
         // Auto-save to IndexedDB if persistence is enabled
-        if let Err(e) = self.save_to_storage(namespace, key, &json_value).await {
-            web_sys::console::warn_1(&format!("Failed to save to IndexedDB: {:?}", e).into());
-        }
+        self.save_to_storage(namespace, key, &versioned).await;
 
         versioned_to_js(&versioned)
     }
@@ -1329,7 +1439,7 @@ impl WorkspaceHandle {
 
         let versioned = self
             .db
-            .put(&self.namespace, key, json_value)
+            .put_notify(&self.namespace, key, json_value)
             .await
             .map_err(|e| JsValue::from_str(&format!("Failed to store value: {}", e)))?;
 
@@ -1352,7 +1462,7 @@ impl WorkspaceHandle {
     #[wasm_bindgen(js_name = delete)]
     pub async fn delete_js(&self, key: &str) -> Result<(), JsValue> {
         self.db
-            .delete(&self.namespace, key)
+            .delete_notify(&self.namespace, key)
             .await
             .map_err(|e| JsValue::from_str(&format!("Failed to delete: {}", e)))?;
         Ok(())
@@ -1397,6 +1507,48 @@ fn versioned_to_js(versioned: &VersionedValue) -> Result<JsValue, JsValue> {
     Ok(obj.into())
 }
 
+/// Convert ChangeEvent to JavaScript object
+fn change_event_to_js(event: &ChangeEvent) -> Result<JsValue, JsValue> {
+    let obj = js_sys::Object::new();
+
+    let change_type = match event.change_type {
+        ChangeType::Insert => "insert",
+        ChangeType::Update => "update",
+        ChangeType::Delete => "delete",
+    };
+    js_sys::Reflect::set(&obj, &"changeType".into(), &JsValue::from_str(change_type))?;
+    js_sys::Reflect::set(
+        &obj,
+        &"namespace".into(),
+        &JsValue::from_str(&event.collection),
+    )?;
+    js_sys::Reflect::set(&obj, &"key".into(), &JsValue::from_str(&event.key))?;
+
+    let value_js = serde_wasm_bindgen::to_value(&event.value)
+        .map_err(|e| JsValue::from_str(&format!("Failed to convert value: {}", e)))?;
+    js_sys::Reflect::set(&obj, &"value".into(), &value_js)?;
+
+    let previous_js = serde_wasm_bindgen::to_value(&event.previous_value)
+        .map_err(|e| JsValue::from_str(&format!("Failed to convert value: {}", e)))?;
+    js_sys::Reflect::set(&obj, &"previousValue".into(), &previous_js)?;
+
+    js_sys::Reflect::set(
+        &obj,
+        &"timestamp".into(),
+        &JsValue::from_str(&event.timestamp.to_rfc3339()),
+    )?;
+    if let Some(version_id) = &event.version_id {
+        js_sys::Reflect::set(&obj, &"versionId".into(), &JsValue::from_str(version_id))?;
+    }
+    js_sys::Reflect::set(
+        &obj,
+        &"sequence".into(),
+        &JsValue::from_f64(event.sequence as f64),
+    )?;
+
+    Ok(obj.into())
+}
+
 /// Convert HistoryEntry to JavaScript object
 fn history_entry_to_js(entry: &HistoryEntry) -> Result<JsValue, JsValue> {
     let obj = js_sys::Object::new();
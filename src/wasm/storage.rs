@@ -5,10 +5,16 @@
 //!
 //! # Features
 //! - Auto-save on data changes
-//! - Auto-load on startup
+//! - Auto-load on startup, including each key's full version history
 //! - Graceful fallback to memory-only if IndexedDB unavailable
 //! - Efficient batch operations
 //!
+//! # Layout
+//! Every write is appended to the `versions` store as the full
+//! `VersionedValue`, so reloading restores history, timestamps and causal
+//! links exactly. The `data` store holds latest-value records written by
+//! version 1 of the schema; they are migrated into `versions` on load.
+//!
 //! # Usage
 //! The storage is automatically initialized when calling `KoruDeltaWasm::new_persistent()`.
 
@@ -19,6 +25,8 @@ use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::JsFuture;
 use web_sys::{IdbDatabase, IdbOpenDbRequest, IdbTransactionMode};
 
+use crate::types::VersionedValue;
+
 /// Convert an IdbRequest to a JsFuture by creating a Promise wrapper
 fn idb_request_to_future(request: &web_sys::IdbRequest) -> Result<JsFuture, JsValue> {
     // Create a Promise that resolves/rejects based on the request
@@ -43,8 +51,9 @@ fn idb_request_to_future(request: &web_sys::IdbRequest) -> Result<JsFuture, JsVa
 }
 
 const DB_NAME: &str = "koru-delta";
-const DB_VERSION: u32 = 1;
+const DB_VERSION: u32 = 2;
 const STORE_DATA: &str = "data";
+const STORE_VERSIONS: &str = "versions";
 const STORE_METADATA: &str = "metadata";
 
 /// Persistent storage backend using IndexedDB
@@ -65,6 +74,14 @@ struct StoredRecord {
     previous_version: Option<String>,
 }
 
+/// One persisted version of a key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredVersion {
+    namespace: String,
+    key: String,
+    version: VersionedValue,
+}
+
 /// Database metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct DatabaseMetadata {
@@ -110,15 +127,12 @@ impl IndexedDbStorage {
         self.memory_fallback
     }
 
-    /// Save a record to IndexedDB
-    pub async fn save_record(
+    /// Append a version of a key to IndexedDB
+    pub async fn save_version(
         &self,
         namespace: &str,
         key: &str,
-        value: &serde_json::Value,
-        timestamp: &chrono::DateTime<chrono::Utc>,
-        version_id: &str,
-        previous_version: Option<&str>,
+        version: &VersionedValue,
     ) -> Result<(), JsValue> {
         if self.memory_fallback {
             return Ok(());
@@ -126,31 +140,28 @@ impl IndexedDbStorage {
 
         let db = self.db.as_ref().ok_or("Database not available")?;
 
-        let record = StoredRecord {
+        let record = StoredVersion {
             namespace: namespace.to_string(),
             key: key.to_string(),
-            value: value.clone(),
-            timestamp: timestamp.to_rfc3339(),
-            version_id: version_id.to_string(),
-            previous_version: previous_version.map(|s| s.to_string()),
+            version: version.clone(),
         };
 
         let json = serde_json::to_string(&record)
             .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))?;
 
         let transaction = db
-            .transaction_with_str_and_mode(STORE_DATA, IdbTransactionMode::Readwrite)
+            .transaction_with_str_and_mode(STORE_VERSIONS, IdbTransactionMode::Readwrite)
             .map_err(|e| JsValue::from_str(&format!("Transaction error: {:?}", e)))?;
 
         let store = transaction
-            .object_store(STORE_DATA)
+            .object_store(STORE_VERSIONS)
             .map_err(|e| JsValue::from_str(&format!("Object store error: {:?}", e)))?;
 
-        let full_key = format!("{}:{}", namespace, key);
+        // write_id is unique per write, so versions never overwrite each other
+        let version_key = format!("{}:{}:{}", namespace, key, version.write_id);
 
-        // Use put (upsert) to handle updates
         let request = store
-            .put_with_key(&JsValue::from_str(&json), &JsValue::from_str(&full_key))
+            .put_with_key(&JsValue::from_str(&json), &JsValue::from_str(&version_key))
             .map_err(|e| JsValue::from_str(&format!("Put error: {:?}", e)))?;
 
         // Wait for the request to complete
@@ -162,7 +173,49 @@ impl IndexedDbStorage {
         Ok(())
     }
 
-    /// Load all records from IndexedDB
+    /// Load every persisted version, oldest first
+    pub async fn load_all_versions(
+        &self,
+    ) -> Result<Vec<(String, String, VersionedValue)>, JsValue> {
+        if self.memory_fallback {
+            return Ok(Vec::new());
+        }
+
+        let db = self.db.as_ref().ok_or("Database not available")?;
+
+        let transaction = db
+            .transaction_with_str(STORE_VERSIONS)
+            .map_err(|e| JsValue::from_str(&format!("Transaction error: {:?}", e)))?;
+
+        let store = transaction
+            .object_store(STORE_VERSIONS)
+            .map_err(|e| JsValue::from_str(&format!("Object store error: {:?}", e)))?;
+
+        let request = store
+            .get_all()
+            .map_err(|e| JsValue::from_str(&format!("Get all error: {:?}", e)))?;
+
+        let result = idb_request_to_future(&request)?.await?;
+        let array: Array = result
+            .dyn_into()
+            .map_err(|_| JsValue::from_str("Expected array"))?;
+
+        let mut versions: Vec<(String, String, VersionedValue)> = array
+            .iter()
+            .filter_map(|item| item.as_string())
+            .filter_map(|json| serde_json::from_str::<StoredVersion>(&json).ok())
+            .map(|record| (record.namespace, record.key, record.version))
+            .collect();
+
+        // Replay order: each key's latest version must be inserted last
+        versions.sort_by_key(|(_, _, version)| version.timestamp);
+
+        web_sys::console::log_1(&format!("IndexedDB: Loaded {} versions", versions.len()).into());
+
+        Ok(versions)
+    }
+
+    /// Load latest-value records written by schema version 1
     pub async fn load_all_records(
         &self,
     ) -> Result<
@@ -224,36 +277,19 @@ impl IndexedDbStorage {
         Ok(records)
     }
 
-    /// Delete a record from IndexedDB
-    pub async fn delete_record(&self, namespace: &str, key: &str) -> Result<(), JsValue> {
-        if self.memory_fallback {
-            return Ok(());
-        }
-
-        let db = self.db.as_ref().ok_or("Database not available")?;
-
-        let transaction = db
-            .transaction_with_str_and_mode(STORE_DATA, IdbTransactionMode::Readwrite)
-            .map_err(|e| JsValue::from_str(&format!("Transaction error: {:?}", e)))?;
-
-        let store = transaction
-            .object_store(STORE_DATA)
-            .map_err(|e| JsValue::from_str(&format!("Object store error: {:?}", e)))?;
-
-        let full_key = format!("{}:{}", namespace, key);
-        let request = store
-            .delete(&JsValue::from_str(&full_key))
-            .map_err(|e| JsValue::from_str(&format!("Delete error: {:?}", e)))?;
-
-        let _: JsValue = idb_request_to_future(&request)?.await?;
-
-        self.update_metadata(db).await?;
-
-        Ok(())
+    /// Remove latest-value records left by schema version 1
+    pub async fn clear_legacy_records(&self) -> Result<(), JsValue> {
+        self.clear_store(STORE_DATA).await
     }
 
     /// Clear all data from IndexedDB
     pub async fn clear_all(&self) -> Result<(), JsValue> {
+        self.clear_store(STORE_DATA).await?;
+        self.clear_store(STORE_VERSIONS).await
+    }
+
+    /// Clear one object store
+    async fn clear_store(&self, store_name: &str) -> Result<(), JsValue> {
         if self.memory_fallback {
             return Ok(());
         }
@@ -261,11 +297,11 @@ impl IndexedDbStorage {
         let db = self.db.as_ref().ok_or("Database not available")?;
 
         let transaction = db
-            .transaction_with_str_and_mode(STORE_DATA, IdbTransactionMode::Readwrite)
+            .transaction_with_str_and_mode(store_name, IdbTransactionMode::Readwrite)
             .map_err(|e| JsValue::from_str(&format!("Transaction error: {:?}", e)))?;
 
         let store = transaction
-            .object_store(STORE_DATA)
+            .object_store(store_name)
             .map_err(|e| JsValue::from_str(&format!("Object store error: {:?}", e)))?;
 
         let request = store
@@ -277,7 +313,7 @@ impl IndexedDbStorage {
         Ok(())
     }
 
-    /// Get database statistics
+    /// Get database statistics: (persisted versions, namespaces)
     #[allow(dead_code)]
     pub async fn get_stats(&self) -> Result<(usize, usize), JsValue> {
         if self.memory_fallback {
@@ -287,11 +323,11 @@ impl IndexedDbStorage {
         let db = self.db.as_ref().ok_or("Database not available")?;
 
         let transaction = db
-            .transaction_with_str(STORE_DATA)
+            .transaction_with_str(STORE_VERSIONS)
             .map_err(|e| JsValue::from_str(&format!("Transaction error: {:?}", e)))?;
 
         let store = transaction
-            .object_store(STORE_DATA)
+            .object_store(STORE_VERSIONS)
             .map_err(|e| JsValue::from_str(&format!("Object store error: {:?}", e)))?;
 
         let request = store
@@ -302,11 +338,9 @@ impl IndexedDbStorage {
         let count = result.as_f64().unwrap_or(0.0) as usize;
 
         // Count unique namespaces
-        let all_records = self.load_all_records().await?;
-        let namespaces: std::collections::HashSet<_> = all_records
-            .iter()
-            .map(|(ns, _, _, _, _, _)| ns.clone())
-            .collect();
+        let all_versions = self.load_all_versions().await?;
+        let namespaces: std::collections::HashSet<_> =
+            all_versions.iter().map(|(ns, _, _)| ns.clone()).collect();
 
         Ok((count, namespaces.len()))
     }
@@ -333,6 +367,11 @@ impl IndexedDbStorage {
             let store_names = db.object_store_names();
             let has_data_store = (0..store_names.length())
                 .any(|i| store_names.get(i).is_some_and(|name| name == STORE_DATA));
+            let has_versions_store = (0..store_names.length()).any(|i| {
+                store_names
+                    .get(i)
+                    .is_some_and(|name| name == STORE_VERSIONS)
+            });
             let has_meta_store = (0..store_names.length()).any(|i| {
                 store_names
                     .get(i)
@@ -344,6 +383,11 @@ impl IndexedDbStorage {
                     .expect("Failed to create data store");
             }
 
+            if !has_versions_store {
+                db.create_object_store(STORE_VERSIONS)
+                    .expect("Failed to create versions store");
+            }
+
             if !has_meta_store {
                 db.create_object_store(STORE_METADATA)
                     .expect("Failed to create metadata store");
@@ -0,0 +1,124 @@
+//! Browser-to-server sync over WebSocket
+//!
+//! Reconciles the browser-local database with a KoruDelta node serving
+//! `GET /api/v1/sync`, using the Merkle/Bloom exchange in
+//! [`crate::reconciliation::protocol`]. Apps write locally while offline and
+//! call `syncWith(url)` once a connection is available; both sides end up
+//! with each other's full version history.
+
+use futures::StreamExt;
+use futures::channel::mpsc;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+use web_sys::{CloseEvent, MessageEvent, WebSocket};
+
+use crate::KoruDelta;
+use crate::reconciliation::{SyncInitiator, SyncMessage, SyncReport, SyncStep};
+use crate::types::{FullKey, VersionedValue};
+
+/// Socket activity forwarded from the WebSocket callbacks
+enum SocketEvent {
+    Open,
+    Text(String),
+    Closed(String),
+}
+
+/// Run one sync session against the node at `url` (`ws://` or `wss://`)
+///
+/// Returns the report and the versions received from the server, so the
+/// caller can persist them.
+#[tracing::instrument(name = "sync_with", skip_all, fields(peer = %url))]
+pub(crate) async fn sync_with(
+    db: &KoruDelta,
+    url: &str,
+) -> Result<(SyncReport, Vec<(FullKey, VersionedValue)>), JsValue> {
+    let socket = WebSocket::new(url)?;
+    let (tx, mut rx) = mpsc::unbounded::<SocketEvent>();
+
+    // The closures must outlive the session; they are dropped (and the
+    // handlers cleared) when this function returns
+    let on_open = {
+        let tx = tx.clone();
+        Closure::<dyn FnMut()>::new(move || {
+            let _ = tx.unbounded_send(SocketEvent::Open);
+        })
+    };
+    let on_message = {
+        let tx = tx.clone();
+        Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            if let Some(text) = event.data().as_string() {
+                let _ = tx.unbounded_send(SocketEvent::Text(text));
+            }
+        })
+    };
+    let on_error = {
+        let tx = tx.clone();
+        Closure::<dyn FnMut()>::new(move || {
+            let _ = tx.unbounded_send(SocketEvent::Closed("WebSocket error".to_string()));
+        })
+    };
+    let on_close = Closure::<dyn FnMut(CloseEvent)>::new(move |event: CloseEvent| {
+        let _ = tx.unbounded_send(SocketEvent::Closed(format!(
+            "Connection closed ({})",
+            event.code()
+        )));
+    });
+
+    socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+    socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    socket.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+    socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+    let result = run_session(db, &socket, &mut rx).await;
+
+    socket.set_onopen(None);
+    socket.set_onmessage(None);
+    socket.set_onerror(None);
+    socket.set_onclose(None);
+    let _ = socket.close();
+
+    result
+}
+
+/// Drive the initiator side of the protocol over an opening socket
+async fn run_session(
+    db: &KoruDelta,
+    socket: &WebSocket,
+    events: &mut mpsc::UnboundedReceiver<SocketEvent>,
+) -> Result<(SyncReport, Vec<(FullKey, VersionedValue)>), JsValue> {
+    match events.next().await {
+        Some(SocketEvent::Open) => {}
+        Some(SocketEvent::Closed(reason)) => return Err(JsValue::from_str(&reason)),
+        _ => return Err(JsValue::from_str("WebSocket closed before opening")),
+    }
+
+    let mut initiator = SyncInitiator::new(db);
+    send(socket, &initiator.start())?;
+
+    loop {
+        let text = match events.next().await {
+            Some(SocketEvent::Text(text)) => text,
+            Some(SocketEvent::Open) => continue,
+            Some(SocketEvent::Closed(reason)) => return Err(JsValue::from_str(&reason)),
+            None => return Err(JsValue::from_str("WebSocket closed during sync")),
+        };
+
+        let message: SyncMessage = serde_json::from_str(&text)
+            .map_err(|e| JsValue::from_str(&format!("Invalid sync message: {}", e)))?;
+
+        match initiator
+            .handle(message)
+            .await
+            .map_err(|e| JsValue::from_str(&format!("Sync failed: {}", e)))?
+        {
+            SyncStep::Send(next) => send(socket, &next)?,
+            SyncStep::Finished(report) => return Ok((report, initiator.merged().to_vec())),
+        }
+    }
+}
+
+fn send(socket: &WebSocket, message: &SyncMessage) -> Result<(), JsValue> {
+    let json = serde_json::to_string(message)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))?;
+    socket.send_with_str(&json)
+}
@@ -32,13 +32,13 @@ use koru_lambda_core::{Canonicalizable, Distinction, DistinctionEngine, LocalCau
 use serde::{Deserialize, Serialize};
 
 use crate::actions::WorkspaceAction;
+use crate::mapper::DocumentMapper;
 
 /// Convert bytes to distinction via byte-wise synthesis.
+///
+/// Delegates to [`DocumentMapper`], which canonicalizes large inputs in parallel.
 fn bytes_to_distinction(bytes: &[u8], engine: &DistinctionEngine) -> Distinction {
-    bytes
-        .iter()
-        .map(|&byte| byte.to_canonical_structure(engine))
-        .fold(engine.d0().clone(), |acc, d| engine.synthesize(&acc, &d))
+    DocumentMapper::bytes_to_distinction(bytes, engine)
 }
 
 /// A synthesized workspace distinction.
@@ -83,6 +83,57 @@ pub struct WorkspaceMetadata {
 
     /// Number of items in this workspace.
     pub item_count: usize,
+
+    /// Quotas and retention enforced on every `remember`.
+    #[serde(default)]
+    pub limits: WorkspaceLimits,
+}
+
+/// Per-workspace quotas and retention.
+///
+/// Memories that exceed a limit are not rejected: the oldest ones are
+/// folded into the workspace's rolling summary memory instead. The summary
+/// itself does not count towards the limits.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceLimits {
+    /// Maximum number of memories kept individually.
+    pub max_items: Option<usize>,
+
+    /// Maximum total content bytes kept individually.
+    pub max_bytes: Option<usize>,
+
+    /// Maximum age of an individual memory.
+    pub max_age: Option<std::time::Duration>,
+}
+
+impl WorkspaceLimits {
+    /// No limits (the default).
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// Limit the number of memories kept individually.
+    pub fn with_max_items(mut self, max_items: usize) -> Self {
+        self.max_items = Some(max_items);
+        self
+    }
+
+    /// Limit the total content bytes kept individually.
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Limit how long a memory is kept individually.
+    pub fn with_max_age(mut self, max_age: std::time::Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Whether any limit is set.
+    pub fn is_unlimited(&self) -> bool {
+        self.max_items.is_none() && self.max_bytes.is_none() && self.max_age.is_none()
+    }
 }
 
 impl WorkspaceMetadata {
@@ -97,8 +148,15 @@ impl WorkspaceMetadata {
             created_at: now,
             last_accessed_at: now,
             item_count: 0,
+            limits: WorkspaceLimits::default(),
         }
     }
+
+    /// Set the workspace limits.
+    pub fn with_limits(mut self, limits: WorkspaceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
 }
 
 impl Canonicalizable for WorkspaceMetadata {
@@ -124,8 +182,24 @@ pub struct SynthesizedMemory {
 
     /// When this memory was synthesized.
     pub synthesized_at: DateTime<Utc>,
+
+    /// Size of the remembered content in bytes (zero for summaries).
+    pub size: usize,
+
+    /// Number of memories folded into this one (zero unless a summary).
+    pub summarized: usize,
+}
+
+impl SynthesizedMemory {
+    /// Whether this is the workspace's rolling summary.
+    pub fn is_summary(&self) -> bool {
+        self.summarized > 0
+    }
 }
 
+/// Item ID of the rolling summary memory.
+pub const SUMMARY_ITEM_ID: &str = "summary";
+
 /// Workspace agent - manages isolated memory spaces.
 ///
 /// The workspace agent follows the LCA pattern:
@@ -185,6 +259,9 @@ pub struct WorkspaceMetrics {
 
     /// Number of searches performed.
     pub searches_performed: u64,
+
+    /// Number of memories folded into summaries by workspace limits.
+    pub memories_summarized: u64,
 }
 
 impl WorkspaceAgent {
@@ -277,6 +354,30 @@ impl WorkspaceAgent {
         self.synthesize_workspace(metadata)
     }
 
+    /// Create a new workspace with quotas and retention.
+    pub fn create_workspace_with_limits(
+        &mut self,
+        id: impl Into<String>,
+        name: impl Into<String>,
+        limits: WorkspaceLimits,
+    ) -> SynthesizedWorkspace {
+        let metadata = WorkspaceMetadata::new(id, name).with_limits(limits);
+        self.synthesize_workspace(metadata)
+    }
+
+    /// Change the limits of an existing workspace.
+    ///
+    /// The new limits are applied immediately. Returns `false` if the
+    /// workspace does not exist.
+    pub fn set_limits(&self, workspace_id: &str, limits: WorkspaceLimits) -> bool {
+        match self.workspaces.write().unwrap().get_mut(workspace_id) {
+            Some(ws) => ws.metadata.limits = limits,
+            None => return false,
+        }
+        self.enforce_limits(workspace_id);
+        true
+    }
+
     /// Get a workspace by ID.
     pub fn get_workspace(&self, id: &str) -> Option<SynthesizedWorkspace> {
         self.workspaces.read().unwrap().get(id).cloned()
@@ -318,6 +419,8 @@ impl WorkspaceAgent {
             item_id: item_id.clone(),
             workspace_id: workspace_id.to_string(),
             synthesized_at: Utc::now(),
+            size: content.len(),
+            summarized: 0,
         };
 
         // Store the memory
@@ -333,9 +436,94 @@ impl WorkspaceAgent {
 
         self.metrics.write().unwrap().memories_synthesized += 1;
 
+        if !workspace.metadata.limits.is_unlimited() {
+            self.enforce_limits(workspace_id);
+        }
+
         Some(memory)
     }
 
+    /// Fold memories that exceed the workspace limits into its summary.
+    ///
+    /// Expired memories go first, then the oldest ones until the item and
+    /// byte quotas are met. They are synthesized, oldest first, onto the
+    /// existing summary (or the workspace local root), which is kept at
+    /// the front of the workspace's memories.
+    ///
+    /// Returns the number of memories folded.
+    fn enforce_limits(&self, workspace_id: &str) -> usize {
+        let Some(workspace) = self.get_workspace(workspace_id) else {
+            return 0;
+        };
+        let limits = &workspace.metadata.limits;
+
+        let mut memories = self.memories.write().unwrap();
+        let Some(workspace_memories) = memories.get_mut(workspace_id) else {
+            return 0;
+        };
+
+        let (mut summary, mut items): (Vec<_>, Vec<_>) = std::mem::take(workspace_memories)
+            .into_iter()
+            .partition(SynthesizedMemory::is_summary);
+        let mut summary = summary.pop();
+
+        // Memories are kept in insertion order, so the oldest come first
+        let mut evict = 0;
+        if let Some(max_age) = limits
+            .max_age
+            .and_then(|age| chrono::Duration::from_std(age).ok())
+        {
+            let cutoff = Utc::now() - max_age;
+            evict = items
+                .iter()
+                .take_while(|m| m.synthesized_at < cutoff)
+                .count();
+        }
+        if let Some(max_items) = limits.max_items {
+            evict = evict.max(items.len().saturating_sub(max_items));
+        }
+        if let Some(max_bytes) = limits.max_bytes {
+            let mut bytes: usize = items[evict..].iter().map(|m| m.size).sum();
+            while bytes > max_bytes && evict < items.len() {
+                bytes -= items[evict].size;
+                evict += 1;
+            }
+        }
+
+        if evict > 0 {
+            let (mut distinction, mut summarized) = match summary.take() {
+                Some(s) => (s.distinction, s.summarized),
+                None => (workspace.local_root.clone(), 0),
+            };
+            for memory in items.drain(..evict) {
+                distinction = self.engine.synthesize(&distinction, &memory.distinction);
+                summarized += 1;
+            }
+            summary = Some(SynthesizedMemory {
+                distinction,
+                item_id: SUMMARY_ITEM_ID.to_string(),
+                workspace_id: workspace_id.to_string(),
+                synthesized_at: Utc::now(),
+                size: 0,
+                summarized,
+            });
+        }
+
+        workspace_memories.extend(summary);
+        workspace_memories.extend(items);
+        let item_count = workspace_memories.len();
+        drop(memories);
+
+        if evict > 0 {
+            if let Some(ws) = self.workspaces.write().unwrap().get_mut(workspace_id) {
+                ws.metadata.item_count = item_count;
+            }
+            self.metrics.write().unwrap().memories_summarized += evict as u64;
+        }
+
+        evict
+    }
+
     /// Recall memories from a workspace.
     ///
     /// Returns all memories for the given workspace.
@@ -667,6 +855,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_limits_fold_overflow_into_summary() {
+        let (mut agent, _) = setup_agent();
+
+        agent.create_workspace_with_limits(
+            "ws-1",
+            "Limited",
+            WorkspaceLimits::unlimited().with_max_items(2),
+        );
+        for i in 0..5 {
+            agent.remember("ws-1", format!("item-{}", i), b"content");
+        }
+
+        let memories = agent.recall("ws-1").unwrap();
+        assert_eq!(memories.len(), 3);
+        assert!(memories[0].is_summary());
+        assert_eq!(memories[0].summarized, 3);
+        assert_eq!(memories[1].item_id, "item-3");
+        assert_eq!(memories[2].item_id, "item-4");
+        assert_eq!(agent.get_workspace("ws-1").unwrap().metadata.item_count, 3);
+        assert_eq!(agent.metrics().memories_summarized, 3);
+
+        // Byte quota folds the oldest until the rest fit
+        assert!(agent.set_limits("ws-1", WorkspaceLimits::unlimited().with_max_bytes(10)));
+        agent.remember("ws-1", "big", b"0123456789");
+        let memories = agent.recall("ws-1").unwrap();
+        assert_eq!(memories.len(), 2);
+        assert_eq!(memories[0].summarized, 5);
+        assert_eq!(memories[1].item_id, "big");
+
+        // Expired memories are folded as well
+        agent.set_limits(
+            "ws-1",
+            WorkspaceLimits::unlimited().with_max_age(std::time::Duration::ZERO),
+        );
+        let memories = agent.recall("ws-1").unwrap();
+        assert_eq!(memories.len(), 1);
+        assert_eq!(memories[0].summarized, 6);
+
+        assert!(!agent.set_limits("missing", WorkspaceLimits::unlimited()));
+    }
+
     #[test]
     fn test_workspace_has_unique_local_root() {
         let (mut agent, _engine) = setup_agent();
@@ -410,6 +410,7 @@ mod network_tests {
         };
         let pong = Message::Pong {
             node_id: node_id.clone(),
+            timestamp: Some(chrono::Utc::now()),
         };
 
         let _ = ping.to_bytes().unwrap();
@@ -453,6 +454,7 @@ mod network_tests {
             // Echo back a Pong.
             conn.send(&Message::Pong {
                 node_id: NodeId::new(),
+                timestamp: None,
             })
             .await
             .unwrap();
@@ -1373,6 +1373,16 @@ async fn falsify_subscription_change_types() {
     assert_eq!(event.change_type, ChangeType::Update);
     assert!(event.previous_value.is_some());
     assert_eq!(event.previous_value.unwrap()["v"], 1);
+
+    // Delete
+    db.delete_notify("sub", "key1").await.unwrap();
+    let event = tokio::time::timeout(StdDuration::from_millis(100), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.change_type, ChangeType::Delete);
+    assert!(event.value.is_none());
+    assert_eq!(event.previous_value.unwrap()["v"], 2);
 }
 
 /// Falsification: Multiple subscribers receive same event
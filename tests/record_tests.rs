@@ -0,0 +1,106 @@
+#![cfg(feature = "derive")]
+/// Tests for `#[derive(KoruRecord)]`.
+///
+/// Run with `cargo test --features derive`.
+use koru_delta::{KoruDelta, KoruRecord, Query};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, KoruRecord)]
+#[koru(namespace = "people")]
+struct Person {
+    #[koru(key)]
+    handle: String,
+    #[serde(rename = "years")]
+    age: u32,
+    #[serde(skip)]
+    cached: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, KoruRecord)]
+struct LineItem {
+    id: u64,
+    sku: String,
+}
+
+#[test]
+fn test_derive_namespace_key_and_fields() {
+    assert_eq!(Person::NAMESPACE, "people");
+    assert_eq!(LineItem::NAMESPACE, "line_item");
+
+    let item = LineItem {
+        id: 42,
+        sku: "A-1".to_string(),
+    };
+    assert_eq!(item.key(), "42");
+
+    // Field names follow serde
+    assert_eq!(Person::FIELDS.age.name(), "years");
+    assert_eq!(Person::FIELDS.handle.name(), "handle");
+    assert_eq!(LineItem::FIELDS.sku.name(), "sku");
+}
+
+#[tokio::test]
+async fn test_derived_records_round_trip() {
+    let db = KoruDelta::start().await.unwrap();
+    let people = [("ada", 36), ("tim", 9)].map(|(handle, age)| Person {
+        handle: handle.to_string(),
+        age,
+        cached: None,
+    });
+    for person in &people {
+        db.put_typed(person).await.unwrap();
+    }
+
+    let ada: Person = db.get_typed("ada").await.unwrap();
+    assert_eq!(ada, people[0]);
+
+    let adults = db
+        .query_typed::<Person>(Query::new().filter(Person::FIELDS.age.gte(18)))
+        .await
+        .unwrap();
+    assert_eq!(adults, vec![people[0].clone()]);
+}
+
+#[tokio::test]
+async fn test_typed_query_builder() {
+    let db = KoruDelta::start().await.unwrap();
+    for (handle, age) in [("ada", 36), ("tim", 9), ("lin", 52), ("joe", 30)] {
+        db.put_typed(&Person {
+            handle: handle.to_string(),
+            age,
+            cached: None,
+        })
+        .await
+        .unwrap();
+    }
+
+    // Lowers to a plain query using serde's field names
+    let query: Query = PersonQuery::filter(|p| p.age.gt(30))
+        .sort_by(|p| p.handle)
+        .into();
+    assert_eq!(query.filters.len(), 1);
+    assert_eq!(query.sort[0].field, "handle");
+
+    let over_thirty: Vec<String> = db
+        .query_typed::<Person>(query)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|p| p.handle)
+        .collect();
+    assert_eq!(over_thirty, vec!["ada", "lin"]);
+
+    let youngest = db
+        .query_typed::<Person>(PersonQuery::sort_by(|p| p.age).limit(1))
+        .await
+        .unwrap();
+    assert_eq!(youngest[0].handle, "tim");
+
+    let all = db.query_typed::<Person>(PersonQuery::all()).await.unwrap();
+    assert_eq!(all.len(), 4);
+    assert_eq!(
+        serde_json::to_value(&all[0]).unwrap()["years"],
+        json!(all[0].age)
+    );
+}
@@ -3,7 +3,7 @@
 //! These tests verify the end-to-end vector storage and search API.
 
 use koru_delta::prelude::*;
-use koru_delta::vector::{Vector, VectorSearchOptions};
+use koru_delta::vector::{DistanceMetric, Vector, VectorNamespaceConfig, VectorSearchOptions};
 
 /// Test basic vector storage and retrieval
 #[tokio::test]
@@ -174,6 +174,39 @@ async fn test_vector_deletion() {
     assert_eq!(results.len(), 0);
 }
 
+/// Test that plain deletes and non-vector overwrites drop vectors from search
+#[tokio::test]
+async fn test_vector_removed_on_delete_and_overwrite() {
+    let db = KoruDelta::start().await.unwrap();
+
+    let query = Vector::new(vec![1.0, 0.0, 0.0], "test-model");
+    for key in ["vec1", "vec2", "vec3"] {
+        db.embed("vectors", key, query.clone(), None).await.unwrap();
+    }
+
+    db.delete("vectors", "vec1").await.unwrap();
+    db.put("vectors", "vec2", json!({"text": "no longer a vector"}))
+        .await
+        .unwrap();
+
+    let results = db
+        .embed_search(Some("vectors"), &query, VectorSearchOptions::new())
+        .await
+        .unwrap();
+    let keys: Vec<_> = results.iter().map(|r| r.key.as_str()).collect();
+    assert_eq!(keys, vec!["vec3"]);
+
+    // A batch overwrite drops it too
+    db.put_batch(vec![("vectors", "vec3", json!(null))])
+        .await
+        .unwrap();
+    let results = db
+        .embed_search(Some("vectors"), &query, VectorSearchOptions::new())
+        .await
+        .unwrap();
+    assert!(results.is_empty());
+}
+
 /// Test vector search with model filtering
 #[tokio::test]
 async fn test_vector_search_model_filter() {
@@ -265,3 +298,267 @@ async fn test_vector_dimension_mismatch() {
     // Should be empty because dimensions don't match
     assert!(results.is_empty());
 }
+
+/// Test batch embedding ingestion
+#[tokio::test]
+async fn test_embed_batch() {
+    let db = KoruDelta::start().await.unwrap();
+
+    let items = vec![
+        (
+            "vec1",
+            Vector::new(vec![1.0, 0.0, 0.0], "test-model"),
+            Some(json!({"title": "first"})),
+        ),
+        ("vec2", Vector::new(vec![0.0, 1.0, 0.0], "test-model"), None),
+        ("vec3", Vector::new(vec![0.9, 0.1, 0.0], "test-model"), None),
+    ];
+    let stored = db.embed_batch("vectors", items).await.unwrap();
+    assert_eq!(stored.len(), 3);
+
+    let first = db.get("vectors", "vec1").await.unwrap();
+    assert_eq!(first.value()["metadata"]["title"], "first");
+
+    let query = Vector::new(vec![1.0, 0.0, 0.0], "test-model");
+    let results = db
+        .embed_search(Some("vectors"), &query, VectorSearchOptions::new().top_k(2))
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].key, "vec1");
+    assert_eq!(results[1].key, "vec3");
+}
+
+/// Test per-namespace distance metrics
+#[tokio::test]
+async fn test_namespace_distance_metric() {
+    let db = KoruDelta::start().await.unwrap();
+    db.configure_vector_namespace(
+        "points",
+        VectorNamespaceConfig::new(DistanceMetric::Euclidean).with_model("test-model"),
+    )
+    .await
+    .unwrap();
+
+    // Same direction as the query but far away, vs. nearby at an angle
+    let far = Vector::new(vec![10.0, 0.0], "test-model");
+    let near = Vector::new(vec![1.0, 0.5], "test-model");
+    db.embed("points", "far", far, None).await.unwrap();
+    db.embed("points", "near", near, None).await.unwrap();
+
+    let query = Vector::new(vec![1.0, 0.0], "test-model");
+    let results = db
+        .embed_search(Some("points"), &query, VectorSearchOptions::new())
+        .await
+        .unwrap();
+    assert_eq!(results[0].key, "near");
+
+    // Asking for a different metric than the namespace uses is an error
+    let mismatched = VectorSearchOptions::new().metric(DistanceMetric::Cosine);
+    assert!(
+        db.embed_search(Some("points"), &query, mismatched)
+            .await
+            .is_err()
+    );
+
+    // So are vectors and queries from another model
+    let other = Vector::new(vec![1.0, 0.0], "other-model");
+    assert!(
+        db.embed("points", "other", other.clone(), None)
+            .await
+            .is_err()
+    );
+    assert!(
+        db.embed_search(Some("points"), &other, VectorSearchOptions::new())
+            .await
+            .is_err()
+    );
+}
+
+/// Test storing and searching text with the local embedder
+#[tokio::test]
+#[cfg_attr(feature = "embedder", ignore)] // Downloads the default model
+async fn test_embed_text() {
+    let db = KoruDelta::start().await.unwrap();
+
+    db.embed_text("docs", "fire", "Warehouse fire reported downtown")
+        .await
+        .unwrap();
+    db.embed_text("docs", "finance", "Quarterly revenue grew strongly")
+        .await
+        .unwrap();
+
+    let stored = db.get("docs", "fire").await.unwrap();
+    assert_eq!(stored.value()["text"], "Warehouse fire reported downtown");
+    assert!(db.get_embed("docs", "fire").await.unwrap().is_some());
+
+    let results = db
+        .search_text(Some("docs"), "fire downtown", VectorSearchOptions::new())
+        .await
+        .unwrap();
+    assert_eq!(results[0].key, "fire");
+}
+
+/// Test batch text embedding through the configured provider
+#[tokio::test]
+async fn test_embed_texts() {
+    let db = KoruDelta::start().await.unwrap();
+    db.set_embedder(std::sync::Arc::new(koru_delta::HashingEmbedder::new(64)));
+
+    let stored = db
+        .embed_texts(
+            "docs",
+            vec![("a", "river flood warning"), ("b", "stock market rally")],
+        )
+        .await
+        .unwrap();
+    assert_eq!(stored.len(), 2);
+
+    let doc = db.get("docs", "a").await.unwrap();
+    assert_eq!(doc.value()["text"], "river flood warning");
+    assert_eq!(doc.value()["dimensions"], 64);
+
+    let results = db
+        .search_text(Some("docs"), "flood", VectorSearchOptions::new())
+        .await
+        .unwrap();
+    assert_eq!(results[0].key, "a");
+}
+
+/// Test storing a chunked document and retrieving chunks with provenance
+#[tokio::test]
+async fn test_store_document_and_retrieve() {
+    use koru_delta::ChunkConfig;
+
+    let db = KoruDelta::start().await.unwrap();
+    db.set_embedder(std::sync::Arc::new(koru_delta::HashingEmbedder::new(128)));
+
+    let manual = "Prime the pump by filling the housing with water. \
+                  Replace the impeller every two years. \
+                  Store the unit indoors during winter frost.";
+    let stored = db
+        .store_document("manuals", "pump", manual, ChunkConfig::new(50))
+        .await
+        .unwrap();
+    assert_eq!(stored.chunks, 3);
+
+    // Unrelated embeddings from the same model don't crowd out chunks
+    db.embed_text("notes", "n1", "winter frost warning")
+        .await
+        .unwrap();
+
+    let results = db
+        .retrieve("how do I replace the impeller", 1)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].namespace, "manuals");
+    assert_eq!(results[0].source_key, "pump");
+    assert_eq!(results[0].chunk_index, 1);
+    assert!(results[0].text.contains("impeller"));
+    assert_eq!(results[0].source_version, stored.version_id);
+    assert!(results[0].is_current);
+    assert_eq!(results[0].revisions, 1);
+
+    // A shorter revision retires the extra chunks
+    let revised = db
+        .store_document(
+            "manuals",
+            "pump",
+            "Prime the pump with water.",
+            ChunkConfig::new(50),
+        )
+        .await
+        .unwrap();
+    assert_eq!(revised.chunks, 1);
+
+    let results = db.retrieve("winter frost", 5).await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].revisions, 2);
+    assert!(results[0].is_current);
+    assert!(results.iter().all(|r| !r.text.contains("winter")));
+}
+
+/// Test that explained search returns a path and proximity breakdown per hit
+#[tokio::test]
+async fn test_embed_search_explain() {
+    let db = KoruDelta::start().await.unwrap();
+
+    db.embed("docs", "near", Vector::new(vec![0.97, 0.24], "m"), None)
+        .await
+        .unwrap();
+    db.embed("docs", "far", Vector::new(vec![0.6, 0.8], "m"), None)
+        .await
+        .unwrap();
+
+    let query = Vector::new(vec![1.0, 0.0], "m");
+    let plain = db
+        .embed_search(Some("docs"), &query, VectorSearchOptions::new())
+        .await
+        .unwrap();
+    let explained = db
+        .embed_search_explain(Some("docs"), &query, VectorSearchOptions::new())
+        .await
+        .unwrap();
+
+    assert_eq!(explained.len(), plain.len());
+    assert_eq!(explained[0].result.key, "near");
+    assert!(explained[0].explanation.synthesis_path.is_some());
+    assert!(!explained[0].explanation.description.is_empty());
+    assert!((explained[0].proximity.geometric - plain[0].score).abs() < 1e-5);
+    assert!(
+        explained
+            .iter()
+            .all(|hit| hit.proximity.causal > 0.0 && hit.proximity.causal <= 1.0)
+    );
+}
+
+/// Test that a persistent database keeps its vector index across restarts
+#[tokio::test]
+async fn test_vector_index_survives_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let index_file = dir.path().join("vectors.hnsw");
+    let query = Vector::new(vec![1.0, 0.1], "m");
+    let options = VectorSearchOptions::new().top_k(1);
+
+    let db = KoruDelta::start_with_path(dir.path()).await.unwrap();
+    db.embed("docs", "a", Vector::new(vec![1.0, 0.0], "m"), None)
+        .await
+        .unwrap();
+    db.embed("docs", "b", Vector::new(vec![0.0, 1.0], "m"), None)
+        .await
+        .unwrap();
+    db.shutdown().await.unwrap();
+    assert!(index_file.exists());
+
+    let db = KoruDelta::start_with_path(dir.path()).await.unwrap();
+    let results = db
+        .embed_search(Some("docs"), &query, options.clone())
+        .await
+        .unwrap();
+    assert_eq!(results[0].key, "a");
+    db.shutdown().await.unwrap();
+
+    // Without the saved index, it is rebuilt from the stored vectors
+    std::fs::remove_file(&index_file).unwrap();
+    let db = KoruDelta::start_with_path(dir.path()).await.unwrap();
+    let results = db
+        .embed_search(Some("docs"), &query, options.clone())
+        .await
+        .unwrap();
+    assert_eq!(results[0].key, "a");
+    db.shutdown().await.unwrap();
+
+    // A corrupt index is rebuilt too, and replaced on the next shutdown
+    std::fs::write(&index_file, b"not an index").unwrap();
+    for _ in 0..2 {
+        let db = KoruDelta::start_with_path(dir.path()).await.unwrap();
+        let results = db
+            .embed_search(Some("docs"), &query, options.clone())
+            .await
+            .unwrap();
+        assert_eq!(results[0].key, "a");
+        db.shutdown().await.unwrap();
+    }
+    assert_ne!(std::fs::read(&index_file).unwrap(), b"not an index");
+}